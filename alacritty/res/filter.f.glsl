@@ -0,0 +1,35 @@
+#if defined(GLES2_RENDERER)
+precision mediump float;
+#define FRAG_COLOR gl_FragColor
+#define TEXTURE texture2D
+
+varying vec2 texCoords;
+
+#else
+out vec4 FragColor;
+#define FRAG_COLOR FragColor
+#define TEXTURE texture
+
+in vec2 texCoords;
+
+#endif
+
+// Frame rendered without the filter.
+uniform sampler2D frame;
+
+// Transformation of the colors in linear RGB.
+uniform mat3 colorMatrix;
+
+void main() {
+    vec4 color = TEXTURE(frame, texCoords);
+    if (color.a == 0.0) {
+        FRAG_COLOR = color;
+        return;
+    }
+
+    // Colors are premultiplied with their alpha.
+    vec3 linear = pow(color.rgb / color.a, vec3(2.2));
+    linear = clamp(colorMatrix * linear, 0.0, 1.0);
+
+    FRAG_COLOR = vec4(pow(linear, vec3(1.0 / 2.2)) * color.a, color.a);
+}
@@ -0,0 +1,14 @@
+#if defined(GLES2_RENDERER)
+attribute vec2 aPos;
+
+varying mediump vec2 texCoords;
+#else
+layout (location = 0) in vec2 aPos;
+
+out vec2 texCoords;
+#endif
+
+void main() {
+    texCoords = aPos * 0.5 + 0.5;
+    gl_Position = vec4(aPos.x, aPos.y, 0.0, 1.0);
+}
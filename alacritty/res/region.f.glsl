@@ -0,0 +1,75 @@
+#if defined(GLES2_RENDERER)
+#if defined(GL_FRAGMENT_PRECISION_HIGH)
+precision highp float;
+#else
+precision mediump float;
+#endif
+#define FRAG_COLOR gl_FragColor
+
+varying vec2 position;
+
+#else
+out vec4 FragColor;
+#define FRAG_COLOR FragColor
+
+in vec2 position;
+
+#endif
+
+// XXX: This must be in-sync with the region renderer.
+#define MAX_RECTS 5
+#define MAX_FILLETS 4
+
+// Rectangles making up the region, as left, top, right and bottom edge.
+uniform vec4 rects[MAX_RECTS];
+
+// Concave corners of the region, with the direction towards the empty quadrant.
+uniform vec4 fillets[MAX_FILLETS];
+
+uniform float radius;
+uniform float borderWidth;
+
+uniform vec4 fillColor;
+uniform vec4 borderColor;
+
+// Signed distance to a box.
+float box(vec2 p, vec2 center, vec2 halfSize) {
+    vec2 d = abs(p - center) - halfSize;
+    return length(max(d, 0.0)) + min(max(d.x, d.y), 0.0);
+}
+
+// Signed distance to a rectangle with its corners rounded by `radius`.
+float roundedRect(vec2 p, vec4 rect) {
+    vec2 center = (rect.xy + rect.zw) * 0.5;
+    vec2 halfSize = max((rect.zw - rect.xy) * 0.5 - radius, 0.0);
+    return box(p, center, halfSize) - radius;
+}
+
+// Signed distance to the material filling a concave corner.
+//
+// The fillet square reaches into the region by the border's width, so the region's edges next
+// to the corner don't get outlined.
+float fillet(vec2 p, vec4 corner) {
+    vec2 center = corner.xy + corner.zw * radius;
+    vec2 inner = corner.xy - corner.zw * (borderWidth + 1.0);
+    float square = box(p, (center + inner) * 0.5, abs(center - inner) * 0.5);
+    return max(square, radius - length(p - center));
+}
+
+void main() {
+    float d = roundedRect(position, rects[0]);
+    for (int i = 1; i < MAX_RECTS; i++) {
+        d = min(d, roundedRect(position, rects[i]));
+    }
+    for (int i = 0; i < MAX_FILLETS; i++) {
+        d = min(d, fillet(position, fillets[i]));
+    }
+
+    vec4 color = fillColor;
+    if (borderWidth > 0.0) {
+        float interior = clamp(0.5 - (d + borderWidth), 0.0, 1.0);
+        color = mix(borderColor, fillColor, interior);
+    }
+
+    FRAG_COLOR = vec4(color.rgb, color.a * clamp(0.5 - d, 0.0, 1.0));
+}
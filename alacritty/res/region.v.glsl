@@ -0,0 +1,20 @@
+#if defined(GLES2_RENDERER)
+attribute vec2 aPos;
+
+varying vec2 position;
+#else
+layout (location = 0) in vec2 aPos;
+
+out vec2 position;
+#endif
+
+// Size of the viewport in pixels.
+uniform vec2 viewportSize;
+
+void main() {
+    // Positions are in pixels, starting at the top-left corner of the viewport.
+    position = aPos;
+
+    vec2 ndc = aPos / viewportSize * 2.0 - 1.0;
+    gl_Position = vec4(ndc.x, -ndc.y, 0.0, 1.0);
+}
@@ -0,0 +1,368 @@
+//! Rendering benchmarks using bundled stress workloads.
+//!
+//! Workloads are generated from a fixed seed, so every run feeds the terminal the same output.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+
+use alacritty_terminal::ansi::{self, Handler};
+use alacritty_terminal::config::Config;
+use alacritty_terminal::event::{EventListener, VoidListener};
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::term::test::TermSize;
+use alacritty_terminal::term::Term;
+
+use crate::cli::BenchOptions;
+
+/// Terminal columns used for headless benchmarks.
+const HEADLESS_COLUMNS: usize = 120;
+
+/// Terminal lines used for headless benchmarks.
+const HEADLESS_LINES: usize = 40;
+
+/// Cursor movements in a single frame of the cursor workload.
+const CURSOR_MOVES: usize = 64;
+
+/// Characters occupying two cells.
+const WIDE_CHARS: [char; 8] = ['漢', '字', '한', '글', 'カ', 'ナ', '🦀', '🚀'];
+
+/// Underline styles cycled through by the underline workload.
+const UNDERLINES: [&str; 6] = ["4", "4:2", "4:3", "4:4", "4:5", "9"];
+
+/// Bundled stress workloads.
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Workload {
+    /// Plain text scrolling the screen.
+    Scroll,
+
+    /// Full screen redraws with random foreground and background colors.
+    Colors,
+
+    /// Full screen redraws with wide characters.
+    WideChars,
+
+    /// Full screen redraws with every underline style.
+    Underlines,
+
+    /// Cursor jumping across the screen while changing its shape.
+    Cursor,
+}
+
+impl Workload {
+    /// All bundled workloads, in the order they are run by default.
+    pub const ALL: [Workload; 5] =
+        [Self::Scroll, Self::Colors, Self::WideChars, Self::Underlines, Self::Cursor];
+
+    /// Name used on the command line and in reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Scroll => "scroll",
+            Self::Colors => "colors",
+            Self::WideChars => "wide-chars",
+            Self::Underlines => "underlines",
+            Self::Cursor => "cursor",
+        }
+    }
+
+    /// Terminal output for one frame of the workload.
+    pub fn frame(self, index: usize, columns: usize, lines: usize) -> String {
+        let mut rng = Rng::new(index as u64);
+        let mut output = String::new();
+
+        match self {
+            Self::Scroll => {
+                for _ in 0..(lines / 2).max(1) {
+                    output.extend((0..columns.saturating_sub(1)).map(|_| rng.printable()));
+                    output.push_str("\r\n");
+                }
+            },
+            Self::Colors => redraw(&mut output, lines, |output, _| {
+                for _ in 0..columns {
+                    let (fg, bg) = (rng.below(256), rng.below(256));
+                    let _ = write!(output, "\x1b[38;5;{fg};48;5;{bg}m{}", rng.printable());
+                }
+            }),
+            Self::WideChars => redraw(&mut output, lines, |output, _| {
+                output.extend((0..columns / 2).map(|_| WIDE_CHARS[rng.below(WIDE_CHARS.len())]));
+            }),
+            Self::Underlines => redraw(&mut output, lines, |output, line| {
+                let style = UNDERLINES[(index + line) % UNDERLINES.len()];
+                let _ = write!(output, "\x1b[{style};58;5;{}m", rng.below(256));
+                output.extend((0..columns).map(|_| rng.printable()));
+                output.push_str("\x1b[m");
+            }),
+            Self::Cursor => {
+                for _ in 0..CURSOR_MOVES {
+                    let (line, column) = (rng.below(lines) + 1, rng.below(columns) + 1);
+                    let shape = rng.below(7);
+                    let _ =
+                        write!(output, "\x1b[{line};{column}H\x1b[{shape} q{}", rng.printable());
+                }
+            },
+        }
+
+        output
+    }
+}
+
+/// Redraw the entire screen line by line, without scrolling after the last line.
+fn redraw<F: FnMut(&mut String, usize)>(output: &mut String, lines: usize, mut draw_line: F) {
+    output.push_str("\x1b[H");
+    for line in 0..lines {
+        draw_line(output, line);
+        if line + 1 < lines {
+            output.push_str("\r\n");
+        }
+    }
+    output.push_str("\x1b[m");
+}
+
+/// Benchmark driving the terminal of a window.
+///
+/// Every drawn frame is preceded by feeding the next frame of the active workload into the
+/// terminal.
+pub struct Bench {
+    workloads: Vec<Workload>,
+    frames: usize,
+    parser: ansi::Processor,
+    extended_parser: ansi::ExtendedProcessor,
+
+    /// Index of the next frame, counted across all workloads.
+    next: usize,
+
+    results: Vec<BenchResult>,
+}
+
+impl Bench {
+    pub fn new(options: &BenchOptions) -> Self {
+        Self {
+            workloads: options.workloads(),
+            frames: options.frames.max(1),
+            parser: Default::default(),
+            extended_parser: Default::default(),
+            next: 0,
+            results: Vec::new(),
+        }
+    }
+
+    /// Feed the next frame into the terminal.
+    ///
+    /// Returns `false` once all workloads are finished.
+    pub fn advance<T: EventListener>(&mut self, terminal: &mut Term<T>) -> bool {
+        let index = self.next % self.frames;
+        let workload = match self.workloads.get(self.next / self.frames) {
+            Some(workload) => *workload,
+            None => return false,
+        };
+
+        // Start every workload from a clean terminal.
+        if index == 0 {
+            terminal.reset_state();
+            self.parser = Default::default();
+            self.extended_parser = Default::default();
+            self.results.push(BenchResult::new(workload));
+        }
+
+        let frame = workload.frame(index, terminal.columns(), terminal.screen_lines());
+        for byte in frame.bytes() {
+            self.parser.advance(terminal, byte);
+            self.extended_parser.advance(&self.parser, terminal, byte);
+        }
+
+        self.next += 1;
+
+        true
+    }
+
+    /// Record the time spent drawing the last frame.
+    pub fn record_frame(&mut self, cpu_time: Duration, gpu_time: Duration) {
+        if let Some(result) = self.results.last_mut() {
+            result.record_frame(cpu_time, gpu_time);
+        }
+    }
+
+    /// Print the results of all workloads.
+    pub fn report(&self) {
+        print_results(&self.results, false);
+    }
+}
+
+/// Run the benchmark without a window, measuring only the terminal's processing of the output.
+pub fn run_headless(options: &BenchOptions) {
+    let size = TermSize::new(HEADLESS_COLUMNS, HEADLESS_LINES);
+    let config = Config::default();
+
+    let mut results = Vec::new();
+    for workload in options.workloads() {
+        let mut terminal = Term::new(&config, &size, VoidListener);
+        let mut parser = ansi::Processor::<ansi::StdSyncHandler>::new();
+        let mut extended_parser = ansi::ExtendedProcessor::new();
+        let mut result = BenchResult::new(workload);
+
+        for index in 0..options.frames.max(1) {
+            let frame = workload.frame(index, size.columns, size.screen_lines);
+
+            let start = Instant::now();
+            for byte in frame.bytes() {
+                parser.advance(&mut terminal, byte);
+                extended_parser.advance(&parser, &mut terminal, byte);
+            }
+
+            // Collect the visible cells, like the renderer would.
+            let _ = terminal.renderable_content().display_iter.count();
+
+            result.record_frame(start.elapsed(), Duration::ZERO);
+        }
+
+        results.push(result);
+    }
+
+    print_results(&results, true);
+}
+
+/// Measurements of a single workload.
+struct BenchResult {
+    workload: Workload,
+    frames: usize,
+    start: Instant,
+    duration: Duration,
+    cpu_time: Duration,
+    gpu_time: Duration,
+}
+
+impl BenchResult {
+    fn new(workload: Workload) -> Self {
+        Self {
+            workload,
+            start: Instant::now(),
+            frames: Default::default(),
+            duration: Default::default(),
+            cpu_time: Default::default(),
+            gpu_time: Default::default(),
+        }
+    }
+
+    fn record_frame(&mut self, cpu_time: Duration, gpu_time: Duration) {
+        self.frames += 1;
+        self.duration = self.start.elapsed();
+        self.cpu_time += cpu_time;
+        self.gpu_time += gpu_time;
+    }
+
+    /// Frames per second.
+    ///
+    /// Without a window, only the time spent processing frames is taken into account.
+    fn fps(&self, headless: bool) -> f64 {
+        let duration = if headless { self.cpu_time } else { self.duration };
+        self.frames as f64 / duration.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Average time in milliseconds per frame.
+    fn average_ms(&self, total: Duration) -> f64 {
+        total.as_secs_f64() * 1000. / self.frames.max(1) as f64
+    }
+}
+
+/// Print benchmark results as a table.
+fn print_results(results: &[BenchResult], headless: bool) {
+    let mut stdout = io::stdout().lock();
+    let _ = writeln!(
+        stdout,
+        "{:<12} {:>8} {:>10} {:>10} {:>10}",
+        "workload", "frames", "fps", "cpu (ms)", "gpu (ms)"
+    );
+
+    for result in results {
+        let gpu_time = if headless {
+            String::from("-")
+        } else {
+            format!("{:.3}", result.average_ms(result.gpu_time))
+        };
+
+        let _ = writeln!(
+            stdout,
+            "{:<12} {:>8} {:>10.1} {:>10.3} {:>10}",
+            result.workload.name(),
+            result.frames,
+            result.fps(headless),
+            result.average_ms(result.cpu_time),
+            gpu_time,
+        );
+    }
+}
+
+/// Deterministic pseudorandom numbers, using xorshift.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Xorshift requires a non-zero state.
+        Self(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Random number below `max`.
+    fn below(&mut self, max: usize) -> usize {
+        (self.next() % max.max(1) as u64) as usize
+    }
+
+    /// Random printable ASCII character.
+    fn printable(&mut self) -> char {
+        char::from(b'!' + self.below(94) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_are_deterministic() {
+        for workload in Workload::ALL {
+            assert_eq!(workload.frame(3, 80, 24), workload.frame(3, 80, 24));
+            assert_ne!(workload.frame(3, 80, 24), workload.frame(4, 80, 24));
+        }
+    }
+
+    #[test]
+    fn redraws_do_not_scroll() {
+        let size = TermSize::new(80, 24);
+        let mut terminal = Term::new(&Config::default(), &size, VoidListener);
+        let mut parser = ansi::Processor::<ansi::StdSyncHandler>::new();
+
+        for workload in [Workload::Colors, Workload::WideChars, Workload::Underlines] {
+            for byte in workload.frame(0, size.columns, size.screen_lines).bytes() {
+                parser.advance(&mut terminal, byte);
+            }
+            assert_eq!(terminal.grid().history_size(), 0, "{} scrolled", workload.name());
+        }
+    }
+
+    #[test]
+    fn bench_runs_every_workload() {
+        let workloads = vec![Workload::Scroll, Workload::Cursor];
+        let options = BenchOptions { workloads, frames: 2, ..Default::default() };
+        let mut bench = Bench::new(&options);
+        let size = TermSize::new(80, 24);
+        let mut terminal = Term::new(&Config::default(), &size, VoidListener);
+
+        let mut frames = 0;
+        while bench.advance(&mut terminal) {
+            bench.record_frame(Duration::ZERO, Duration::ZERO);
+            frames += 1;
+        }
+
+        assert_eq!(frames, 4);
+        assert_eq!(bench.results.len(), 2);
+        assert!(bench.results.iter().all(|result| result.frames == 2));
+    }
+}
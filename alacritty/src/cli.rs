@@ -1,4 +1,5 @@
 use std::cmp::max;
+use std::env;
 use std::os::raw::c_ulong;
 use std::path::PathBuf;
 
@@ -9,8 +10,12 @@ use toml::{Table, Value};
 
 use alacritty_terminal::config::{Program, PtyConfig};
 
+use crate::bench::Workload;
 use crate::config::window::{Class, Identity};
 use crate::config::{serde_utils, UiConfig};
+use crate::layout::LayoutSplit;
+#[cfg(unix)]
+use crate::pane::PaneDirection;
 
 /// CLI options for the main Alacritty executable.
 #[derive(Parser, Default, Debug)]
@@ -49,6 +54,15 @@ pub struct Options {
     #[clap(long, value_hint = ValueHint::FilePath)]
     pub socket: Option<PathBuf>,
 
+    /// Run without an initial window, hosting windows created through IPC until terminated.
+    #[cfg(unix)]
+    #[clap(long)]
+    pub daemon: bool,
+
+    /// Open the windows, tabs and panes described by a layout file.
+    #[clap(long, value_hint = ValueHint::FilePath)]
+    pub layout: Option<PathBuf>,
+
     /// Reduces the level of verbosity (the min level is -qq).
     #[clap(short, conflicts_with("verbose"), action = ArgAction::Count)]
     quiet: u8,
@@ -88,7 +102,7 @@ impl Options {
     pub fn override_config(&self, config: &mut UiConfig) {
         #[cfg(unix)]
         {
-            config.ipc_socket |= self.socket.is_some();
+            config.ipc_socket |= self.socket.is_some() || self.daemon;
         }
 
         config.window.dynamic_title &= self.window_options.window_identity.title.is_none();
@@ -102,6 +116,18 @@ impl Options {
         }
     }
 
+    /// Whether Alacritty should run without requiring any windows.
+    #[cfg(unix)]
+    pub fn daemon(&self) -> bool {
+        self.daemon
+    }
+
+    /// Whether Alacritty should run without requiring any windows.
+    #[cfg(not(unix))]
+    pub fn daemon(&self) -> bool {
+        false
+    }
+
     /// Logging filter level.
     pub fn log_level(&self) -> LevelFilter {
         match (self.quiet, self.verbose) {
@@ -125,7 +151,8 @@ impl Options {
 
 /// Combine multiple options into a [`toml::Value`].
 pub fn options_as_value(options: &[String]) -> Value {
-    options.iter().fold(Value::Table(Table::new()), |value, option| match toml::from_str(option) {
+    let value = Value::Table(Table::new());
+    options.iter().fold(value, |value, option| match serde_utils::parse_option(option) {
         Ok(new_value) => serde_utils::merge(value, new_value),
         Err(_) => {
             eprintln!("Ignoring invalid option: {:?}", option);
@@ -148,6 +175,24 @@ fn parse_class(input: &str) -> Result<Class, String> {
     Ok(Class::new(general, instance))
 }
 
+/// Split an environment variable into its key and value.
+fn parse_env(input: &str) -> Result<(String, String), String> {
+    match input.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.into(), value.into())),
+        _ => Err(String::from("Expected KEY=VALUE")),
+    }
+}
+
+/// Resolve a path relative to the current working directory.
+fn parse_absolute_path(input: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(input);
+    if path.is_absolute() {
+        return Ok(path);
+    }
+
+    env::current_dir().map(|cwd| cwd.join(path)).map_err(|err| err.to_string())
+}
+
 /// Convert to hex if possible, else decimal
 fn parse_hex_or_decimal(input: &str) -> Option<c_ulong> {
     input
@@ -163,13 +208,25 @@ pub struct TerminalOptions {
     #[clap(long, value_hint = ValueHint::FilePath)]
     pub working_directory: Option<PathBuf>,
 
+    /// Environment variable for the shell, overriding the env config section.
+    #[clap(long, value_name = "KEY=VALUE", value_parser = parse_env)]
+    pub env: Vec<(String, String)>,
+
     /// Remain open after child process exit.
     #[clap(long)]
     pub hold: bool,
 
+    /// Remain open after child process exit with a non-zero exit code.
+    #[clap(long)]
+    pub hold_on_error: bool,
+
+    /// Record the terminal output to an asciicast file.
+    #[clap(long, value_hint = ValueHint::FilePath, value_parser = parse_absolute_path)]
+    pub record: Option<PathBuf>,
+
     /// Command and args to execute (must be last argument).
     #[clap(short = 'e', long, allow_hyphen_values = true, num_args = 1..)]
-    command: Vec<String>,
+    pub command: Vec<String>,
 }
 
 impl TerminalOptions {
@@ -193,7 +250,10 @@ impl TerminalOptions {
             pty_config.shell = Some(command);
         }
 
+        pty_config.env.extend(self.env.iter().cloned());
+
         pty_config.hold |= self.hold;
+        pty_config.hold_on_error |= self.hold_on_error;
     }
 }
 
@@ -202,7 +262,9 @@ impl From<TerminalOptions> for PtyConfig {
         PtyConfig {
             working_directory: options.working_directory.take(),
             shell: options.command(),
+            env: options.env.drain(..).collect(),
             hold: options.hold,
+            hold_on_error: options.hold_on_error,
         }
     }
 }
@@ -217,6 +279,14 @@ pub struct WindowIdentity {
     /// Defines window class/app_id on X11/Wayland [default: Alacritty].
     #[clap(long, value_name = "general> | <general>,<instance", value_parser = parse_class)]
     pub class: Option<Class>,
+
+    /// Name used to target the window through IPC.
+    #[clap(long)]
+    pub name: Option<String>,
+
+    /// Defines the window icon on X11 and Windows.
+    #[clap(long, value_hint = ValueHint::FilePath, value_parser = parse_absolute_path)]
+    pub icon: Option<PathBuf>,
 }
 
 impl WindowIdentity {
@@ -228,6 +298,9 @@ impl WindowIdentity {
         if let Some(class) = &self.class {
             identity.class = class.clone();
         }
+        if let Some(icon) = &self.icon {
+            identity.icon = Some(icon.clone());
+        }
     }
 }
 
@@ -237,6 +310,8 @@ pub enum Subcommands {
     #[cfg(unix)]
     Msg(MessageOptions),
     Migrate(MigrateOptions),
+    Replay(ReplayOptions),
+    Bench(BenchOptions),
 }
 
 /// Send a message to the Alacritty socket.
@@ -257,10 +332,46 @@ pub struct MessageOptions {
 #[derive(Subcommand, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum SocketMessage {
     /// Create a new window in the same Alacritty process.
-    CreateWindow(WindowOptions),
+    CreateWindow(Box<WindowOptions>),
 
     /// Update the Alacritty configuration.
     Config(IpcConfig),
+
+    /// Search for a regex in the scrollback.
+    Search(IpcSearch),
+
+    /// Write the scrollback history and screen content to a file.
+    DumpHistory(IpcDumpHistory),
+
+    /// Write the next rendered frame to a PNG file.
+    Screenshot(IpcScreenshot),
+
+    /// Print the terminal content.
+    GetText(IpcGetText),
+
+    /// Print the terminal state as JSON.
+    GetState(IpcGetState),
+
+    /// Print all windows as JSON.
+    ListWindows,
+
+    /// Write text to the terminal as if it was typed.
+    SendText(IpcSendText),
+
+    /// Mirror input to all panes of a window or a group of windows.
+    Broadcast(IpcBroadcast),
+
+    /// Open the windows, tabs and panes described by a layout file.
+    Layout(IpcLayout),
+
+    /// Move the focused pane of a window into a new or existing window.
+    MovePane(IpcMovePane),
+
+    /// Print performance metrics as JSON.
+    Stats(IpcStats),
+
+    /// Show or hide dropdown windows, opening one if none exists.
+    Toggle(IpcToggle),
 }
 
 /// Migrate the configuration file.
@@ -287,6 +398,41 @@ pub struct MigrateOptions {
     pub silent: bool,
 }
 
+/// Replay a session recorded in the asciicast format.
+#[derive(Args, Clone, Debug)]
+pub struct ReplayOptions {
+    /// Path to the recording.
+    #[clap(value_hint = ValueHint::FilePath, value_parser = parse_absolute_path)]
+    pub path: PathBuf,
+}
+
+/// Measure rendering performance using bundled stress workloads.
+#[derive(Args, Default, Clone, Debug, PartialEq, Eq)]
+pub struct BenchOptions {
+    /// Only measure the terminal's processing of the output, without opening a window.
+    #[clap(long)]
+    pub headless: bool,
+
+    /// Workloads which should be run, defaults to all of them.
+    #[clap(short, long = "workload", value_enum)]
+    pub workloads: Vec<Workload>,
+
+    /// Number of frames drawn for every workload.
+    #[clap(long, default_value = "600")]
+    pub frames: usize,
+}
+
+impl BenchOptions {
+    /// Workloads which should be run.
+    pub fn workloads(&self) -> Vec<Workload> {
+        if self.workloads.is_empty() {
+            Workload::ALL.to_vec()
+        } else {
+            self.workloads.clone()
+        }
+    }
+}
+
 /// Subset of options that we pass to 'create-window' IPC subcommand.
 #[derive(Serialize, Deserialize, Args, Default, Clone, Debug, PartialEq, Eq)]
 pub struct WindowOptions {
@@ -298,10 +444,40 @@ pub struct WindowOptions {
     /// Window options which could be passed via IPC.
     pub window_identity: WindowIdentity,
 
+    /// Dock the window to the top of the screen, showing and hiding it with `alacritty msg
+    /// toggle`.
+    #[clap(long)]
+    pub dropdown: bool,
+
+    #[clap(skip)]
+    #[serde(skip)]
+    /// File removed once the window is closed.
+    pub temporary_file: Option<PathBuf>,
+
+    #[clap(skip)]
+    #[serde(skip)]
+    /// Recording replayed instead of spawning a shell.
+    pub replay: Option<PathBuf>,
+
+    #[clap(skip)]
+    #[serde(skip)]
+    /// Benchmark run instead of spawning a shell.
+    pub bench: Option<BenchOptions>,
+
+    #[clap(skip)]
+    #[serde(skip)]
+    /// Panes split off the window's first pane.
+    pub splits: Vec<LayoutSplit>,
+
     #[clap(skip)]
     #[cfg(target_os = "macos")]
     /// The window tabbing identifier to use when building a window.
     pub window_tabbing_id: Option<String>,
+
+    #[clap(skip)]
+    #[cfg(not(any(target_os = "macos", windows)))]
+    /// Token of the application requesting the window, allowing it to take focus.
+    pub activation_token: Option<String>,
 }
 
 /// Parameters to the `config` IPC subcommand.
@@ -318,9 +494,226 @@ pub struct IpcConfig {
     #[clap(short, long, allow_hyphen_values = true, env = "ALACRITTY_WINDOW_ID")]
     pub window_id: Option<i128>,
 
+    /// Window name or ID for the new config, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
+
     /// Clear all runtime configuration changes.
     #[clap(short, long, conflicts_with = "options")]
     pub reset: bool,
+
+    /// Only change the configuration of the window's focused pane.
+    #[clap(short, long, conflicts_with = "get")]
+    pub pane: bool,
+
+    /// Print the value of an option set through configuration files, the CLI or IPC.
+    #[clap(long, value_name = "OPTION", conflicts_with_all = ["options", "reset"])]
+    pub get: Option<String>,
+}
+
+/// Parameters to the `search` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcSearch {
+    /// Regex to search for.
+    pub regex: String,
+
+    /// Search upwards from the bottom of the screen.
+    #[clap(short, long)]
+    pub backwards: bool,
+
+    /// Select the first match.
+    #[clap(short, long)]
+    pub select: bool,
+
+    /// Window ID for the search, defaults to the focused window.
+    #[clap(short, long)]
+    pub window_id: Option<i128>,
+
+    /// Window name or ID for the search, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
+}
+
+/// Parameters to the `dump-history` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcDumpHistory {
+    /// Path of the output file.
+    #[clap(value_hint = ValueHint::FilePath)]
+    pub path: PathBuf,
+
+    /// Preserve text attributes using SGR escape sequences.
+    #[clap(short, long)]
+    pub ansi: bool,
+
+    /// Prefix every line with the time it was written at.
+    #[clap(short, long)]
+    pub timestamps: bool,
+
+    /// Window ID for the history, defaults to the focused window.
+    #[clap(short, long)]
+    pub window_id: Option<i128>,
+
+    /// Window name or ID for the history, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
+}
+
+/// Parameters to the `screenshot` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcScreenshot {
+    /// Path of the PNG file.
+    #[clap(value_hint = ValueHint::FilePath)]
+    pub path: PathBuf,
+
+    /// Window ID for the screenshot, defaults to the focused window.
+    #[clap(short, long)]
+    pub window_id: Option<i128>,
+
+    /// Window name or ID for the screenshot, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
+}
+
+/// Parameters to the `get-text` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcGetText {
+    /// Print the active selection.
+    #[clap(long, conflicts_with_all = ["screen", "history"])]
+    pub selection: bool,
+
+    /// Print the visible screen content [default].
+    #[clap(long, conflicts_with = "history")]
+    pub screen: bool,
+
+    /// Print the screen content along with up to LINES lines of scrollback history above it.
+    #[clap(long, value_name = "LINES")]
+    pub history: Option<usize>,
+
+    /// Window ID for the text, defaults to the focused window.
+    #[clap(short, long)]
+    pub window_id: Option<i128>,
+
+    /// Window name or ID for the text, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
+}
+
+/// Parameters to the `get-state` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcGetState {
+    /// Window ID for the state, defaults to the focused window.
+    #[clap(short, long)]
+    pub window_id: Option<i128>,
+
+    /// Window name or ID for the state, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
+}
+
+/// Parameters to the `send-text` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcSendText {
+    /// Text to send, read from STDIN when omitted.
+    pub text: Option<String>,
+
+    /// Send the text as a bracketed paste, if enabled by the application.
+    #[clap(short, long)]
+    pub bracketed: bool,
+
+    /// Window ID for the text, defaults to the focused window.
+    #[clap(short, long)]
+    pub window_id: Option<i128>,
+
+    /// Window name or ID for the text, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
+}
+
+/// Parameters to the `broadcast` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcBroadcast {
+    /// Write input to all panes of the window.
+    #[clap(short, long)]
+    pub panes: bool,
+
+    /// Share input with all windows in the same group.
+    #[clap(short, long, value_name = "NAME")]
+    pub group: Option<String>,
+
+    /// Stop mirroring input from and to the window.
+    #[clap(long, conflicts_with_all = ["panes", "group"])]
+    pub off: bool,
+
+    /// Window ID for the broadcast, defaults to the focused window.
+    #[clap(short, long)]
+    pub window_id: Option<i128>,
+
+    /// Window name or ID for the broadcast, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
+}
+
+/// Parameters to the `layout` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcLayout {
+    /// Path of the TOML or YAML layout file.
+    #[clap(value_hint = ValueHint::FilePath)]
+    pub path: PathBuf,
+}
+
+/// Parameters to the `move-pane` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcMovePane {
+    /// Window name or ID receiving the pane, defaults to a new window.
+    #[clap(short, long)]
+    pub target: Option<String>,
+
+    /// Side of the target window's focused pane the pane is opened on.
+    #[clap(short, long, value_enum, default_value = "right")]
+    pub direction: PaneDirection,
+
+    /// Window ID of the moved pane, defaults to the focused window.
+    #[clap(short, long)]
+    pub window_id: Option<i128>,
+
+    /// Window name or ID of the moved pane, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
+}
+
+/// Parameters to the `stats` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcStats {
+    /// Window ID for the metrics, defaults to the focused window.
+    #[clap(short, long)]
+    pub window_id: Option<i128>,
+
+    /// Window name or ID for the metrics, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
+}
+
+/// Parameters to the `toggle` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcToggle {
+    /// Window ID of the dropdown, defaults to all dropdown windows.
+    #[clap(short, long)]
+    pub window_id: Option<i128>,
+
+    /// Window name or ID of the dropdown, overriding --window-id.
+    #[clap(long)]
+    pub window: Option<String>,
 }
 
 /// Toml value with default implementation.
@@ -438,6 +831,16 @@ mod tests {
         assert!(class.is_err());
     }
 
+    #[test]
+    fn parse_env_variable() {
+        let env = parse_env("TERM=xterm=256color").unwrap();
+        assert_eq!(env, (String::from("TERM"), String::from("xterm=256color")));
+
+        assert_eq!(parse_env("EMPTY="), Ok((String::from("EMPTY"), String::new())));
+        assert!(parse_env("=value").is_err());
+        assert!(parse_env("TERM").is_err());
+    }
+
     #[test]
     fn valid_decimal() {
         let value = parse_hex_or_decimal("10485773");
@@ -456,6 +859,25 @@ mod tests {
         assert_eq!(value, None);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn parse_move_pane() {
+        let args = ["alacritty", "msg", "move-pane", "--target", "editor", "-d", "down"];
+        let message = match Options::parse_from(args).subcommands {
+            Some(Subcommands::Msg(options)) => options.message,
+            subcommands => panic!("unexpected subcommands: {subcommands:?}"),
+        };
+
+        assert_eq!(
+            message,
+            SocketMessage::MovePane(IpcMovePane {
+                target: Some(String::from("editor")),
+                direction: PaneDirection::Down,
+                ..Default::default()
+            })
+        );
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn completions() {
@@ -1,8 +1,15 @@
+use std::collections::{HashMap, VecDeque};
+#[cfg(windows)]
+use std::{io, ptr};
+
 use log::{debug, warn};
 use winit::window::raw_window_handle::RawDisplayHandle;
 
 use alacritty_terminal::term::ClipboardType;
 
+#[cfg(not(any(target_os = "macos", windows)))]
+use crate::daemon::spawn_with_input;
+
 #[cfg(any(test, not(any(feature = "x11", target_os = "macos", windows))))]
 use copypasta::nop_clipboard::NopClipboardContext;
 #[cfg(all(feature = "wayland", not(any(target_os = "macos", windows))))]
@@ -13,9 +20,25 @@ use copypasta::x11_clipboard::{Primary as X11SelectionClipboard, X11ClipboardCon
 use copypasta::ClipboardContext;
 use copypasta::ClipboardProvider;
 
+/// Maximum number of entries in the clipboard history.
+pub const MAX_CLIPBOARD_HISTORY: usize = 10;
+
 pub struct Clipboard {
     clipboard: Box<dyn ClipboardProvider>,
     selection: Option<Box<dyn ClipboardProvider>>,
+
+    /// Primary selection emulated on platforms without one.
+    primary: String,
+
+    /// Text recently copied to the clipboard, newest first.
+    history: VecDeque<String>,
+
+    /// Named vi mode yank registers.
+    registers: HashMap<char, String>,
+
+    /// Whether the clipboard is owned by a Wayland compositor.
+    #[cfg(not(any(target_os = "macos", windows)))]
+    wayland: bool,
 }
 
 impl Clipboard {
@@ -25,7 +48,9 @@ impl Clipboard {
             RawDisplayHandle::Wayland(display) => {
                 let (selection, clipboard) =
                     wayland_clipboard::create_clipboards_from_external(display.display);
-                Self { clipboard: Box::new(clipboard), selection: Some(Box::new(selection)) }
+                let clipboard =
+                    Self::with_providers(Box::new(clipboard), Some(Box::new(selection)));
+                Self { wayland: true, ..clipboard }
             },
             _ => Self::default(),
         }
@@ -35,20 +60,35 @@ impl Clipboard {
     /// feature.
     #[cfg(any(test, not(any(feature = "x11", target_os = "macos", windows))))]
     pub fn new_nop() -> Self {
-        Self { clipboard: Box::new(NopClipboardContext::new().unwrap()), selection: None }
+        Self::with_providers(Box::new(NopClipboardContext::new().unwrap()), None)
+    }
+
+    fn with_providers(
+        clipboard: Box<dyn ClipboardProvider>,
+        selection: Option<Box<dyn ClipboardProvider>>,
+    ) -> Self {
+        Self {
+            clipboard,
+            selection,
+            primary: Default::default(),
+            history: Default::default(),
+            registers: Default::default(),
+            #[cfg(not(any(target_os = "macos", windows)))]
+            wayland: false,
+        }
     }
 }
 
 impl Default for Clipboard {
     fn default() -> Self {
         #[cfg(any(target_os = "macos", windows))]
-        return Self { clipboard: Box::new(ClipboardContext::new().unwrap()), selection: None };
+        return Self::with_providers(Box::new(ClipboardContext::new().unwrap()), None);
 
         #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
-        return Self {
-            clipboard: Box::new(ClipboardContext::new().unwrap()),
-            selection: Some(Box::new(X11ClipboardContext::<X11SelectionClipboard>::new().unwrap())),
-        };
+        return Self::with_providers(
+            Box::new(ClipboardContext::new().unwrap()),
+            Some(Box::new(X11ClipboardContext::<X11SelectionClipboard>::new().unwrap())),
+        );
 
         #[cfg(not(any(feature = "x11", target_os = "macos", windows)))]
         return Self::new_nop();
@@ -57,20 +97,64 @@ impl Default for Clipboard {
 
 impl Clipboard {
     pub fn store(&mut self, ty: ClipboardType, text: impl Into<String>) {
+        let text = text.into();
+
         let clipboard = match (ty, &mut self.selection) {
             (ClipboardType::Selection, Some(provider)) => provider,
-            (ClipboardType::Selection, None) => return,
-            _ => &mut self.clipboard,
+            (ClipboardType::Selection, None) => {
+                self.primary = text;
+                return;
+            },
+            _ => {
+                Self::push_history(&mut self.history, text.clone());
+                &mut self.clipboard
+            },
         };
 
-        clipboard.set_contents(text.into()).unwrap_or_else(|err| {
+        clipboard.set_contents(text).unwrap_or_else(|err| {
             warn!("Unable to store text in clipboard: {}", err);
         });
     }
 
+    /// Store text in the clipboard together with its HTML and RTF representations.
+    ///
+    /// Without a native clipboard supporting multiple formats, the HTML is passed to `wl-copy` or
+    /// `xclip` instead and the RTF is dropped.
+    pub fn store_formatted(&mut self, text: String, html: String, rtf: String) {
+        Self::push_history(&mut self.history, text.clone());
+
+        #[cfg(target_os = "macos")]
+        crate::macos::pasteboard::set_formatted(&text, &html, &rtf);
+
+        #[cfg(windows)]
+        if let Err(err) = set_formatted(&text, &html, &rtf) {
+            warn!("Unable to store formatted text in clipboard: {}", err);
+            self.store(ClipboardType::Clipboard, text);
+        }
+
+        #[cfg(not(any(target_os = "macos", windows)))]
+        {
+            let _ = rtf;
+            let (program, args): (_, &[_]) = if self.wayland {
+                ("wl-copy", &["--type", "text/html"])
+            } else {
+                ("xclip", &["-selection", "clipboard", "-target", "text/html"])
+            };
+
+            if let Err(err) = spawn_with_input(program, args, &[], html) {
+                warn!("Unable to store formatted text in clipboard using {}: {}", program, err);
+                self.store(ClipboardType::Clipboard, text);
+            }
+        }
+    }
+
     pub fn load(&mut self, ty: ClipboardType) -> String {
         let clipboard = match (ty, &mut self.selection) {
             (ClipboardType::Selection, Some(provider)) => provider,
+            // Fall back to the clipboard until something was selected.
+            (ClipboardType::Selection, None) if !self.primary.is_empty() => {
+                return self.primary.clone();
+            },
             _ => &mut self.clipboard,
         };
 
@@ -82,4 +166,146 @@ impl Clipboard {
             Ok(text) => text,
         }
     }
+
+    /// Store text in a named yank register.
+    pub fn store_register(&mut self, name: char, text: String) {
+        Self::push_history(&mut self.history, text.clone());
+        self.registers.insert(name, text);
+    }
+
+    /// Load the text of a named yank register.
+    pub fn load_register(&self, name: char) -> Option<&str> {
+        self.registers.get(&name).map(String::as_str)
+    }
+
+    /// Text recently copied to the clipboard, newest first.
+    pub fn history(&self) -> &VecDeque<String> {
+        &self.history
+    }
+
+    /// Add an entry to the clipboard history, moving duplicates to the front.
+    fn push_history(history: &mut VecDeque<String>, text: String) {
+        if text.is_empty() {
+            return;
+        }
+
+        history.retain(|entry| entry != &text);
+        history.push_front(text);
+        history.truncate(MAX_CLIPBOARD_HISTORY);
+    }
+}
+
+/// Replace the clipboard content with plain text, HTML and RTF.
+#[cfg(windows)]
+fn set_formatted(text: &str, html: &str, rtf: &str) -> Result<(), io::Error> {
+    use windows_sys::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+    };
+    use windows_sys::Win32::System::Memory::{
+        GlobalAlloc, GlobalFree, GlobalLock, GlobalUnlock, GMEM_MOVEABLE,
+    };
+
+    const CF_UNICODETEXT: u32 = 13;
+
+    /// Copy null-terminated `data` into a new global memory object owned by the clipboard.
+    unsafe fn set_data(format: u32, data: &[u8]) -> Result<(), io::Error> {
+        let memory = GlobalAlloc(GMEM_MOVEABLE, data.len() + 2);
+        if memory == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let target = GlobalLock(memory) as *mut u8;
+        ptr::copy_nonoverlapping(data.as_ptr(), target, data.len());
+        ptr::write_bytes(target.add(data.len()), 0, 2);
+        GlobalUnlock(memory);
+
+        if SetClipboardData(format, memory) == 0 {
+            let err = io::Error::last_os_error();
+            GlobalFree(memory);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    let format = |name: &str| {
+        let name: Vec<u16> = name.encode_utf16().chain([0]).collect();
+        unsafe { RegisterClipboardFormatW(name.as_ptr()) }
+    };
+
+    let text: Vec<u8> = text.encode_utf16().flat_map(u16::to_ne_bytes).collect();
+    let html = cf_html(html);
+
+    unsafe {
+        if OpenClipboard(0) == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        EmptyClipboard();
+        let result = set_data(CF_UNICODETEXT, &text)
+            .and_then(|_| set_data(format("HTML Format"), html.as_bytes()))
+            .and_then(|_| set_data(format("Rich Text Format"), rtf.as_bytes()));
+        CloseClipboard();
+
+        result
+    }
+}
+
+/// Wrap an HTML fragment in the header of the Windows HTML clipboard format.
+#[cfg(windows)]
+fn cf_html(fragment: &str) -> String {
+    const PREFIX: &str = "<html><body><!--StartFragment-->";
+    const SUFFIX: &str = "<!--EndFragment--></body></html>";
+
+    // Offsets have a fixed width, so the header's length doesn't depend on them.
+    let header =
+        |start_html: usize, end_html: usize, start_fragment: usize, end_fragment: usize| {
+            format!(
+                "Version:0.9\r\nStartHTML:{start_html:010}\r\nEndHTML:{end_html:010}\r\\
+                 nStartFragment:{start_fragment:010}\r\nEndFragment:{end_fragment:010}\r\n"
+            )
+        };
+
+    let start_html = header(0, 0, 0, 0).len();
+    let start_fragment = start_html + PREFIX.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + SUFFIX.len();
+
+    header(start_html, end_html, start_fragment, end_fragment) + PREFIX + fragment + SUFFIX
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_deduplicated() {
+        let mut clipboard = Clipboard::new_nop();
+
+        clipboard.store(ClipboardType::Clipboard, "a");
+        clipboard.store(ClipboardType::Selection, "b");
+        clipboard.store_register('r', String::from("c"));
+        clipboard.store(ClipboardType::Clipboard, "a");
+        clipboard.store(ClipboardType::Clipboard, "");
+
+        assert_eq!(clipboard.history(), &["a", "c"]);
+        assert_eq!(clipboard.load_register('r'), Some("c"));
+        assert_eq!(clipboard.load_register('s'), None);
+
+        for i in 0..MAX_CLIPBOARD_HISTORY + 1 {
+            clipboard.store(ClipboardType::Clipboard, i.to_string());
+        }
+        assert_eq!(clipboard.history().len(), MAX_CLIPBOARD_HISTORY);
+        assert_eq!(clipboard.history()[0], MAX_CLIPBOARD_HISTORY.to_string());
+    }
+
+    #[test]
+    fn emulated_primary_selection() {
+        let mut clipboard = Clipboard::new_nop();
+        assert_eq!(clipboard.load(ClipboardType::Selection), "");
+
+        clipboard.store(ClipboardType::Selection, "selected");
+        assert_eq!(clipboard.load(ClipboardType::Selection), "selected");
+        assert_eq!(clipboard.load(ClipboardType::Clipboard), "");
+    }
 }
@@ -1,11 +1,12 @@
+use std::path::PathBuf;
 use std::time::Duration;
 
 use alacritty_config_derive::ConfigDeserialize;
 
-use alacritty_terminal::config::Program;
+use alacritty_terminal::config::{Percentage, Program};
 use alacritty_terminal::term::color::Rgb;
 
-#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq)]
 pub struct BellConfig {
     /// Visual bell animation function.
     pub animation: BellAnimation,
@@ -18,6 +19,15 @@ pub struct BellConfig {
 
     /// Visual bell duration in milliseconds.
     duration: u16,
+
+    /// Sound file played on bell.
+    pub sound: Option<PathBuf>,
+
+    /// Volume of the bell sound.
+    pub volume: Percentage,
+
+    /// Minimum time between bell sounds in milliseconds.
+    sound_interval: u16,
 }
 
 impl Default for BellConfig {
@@ -27,6 +37,9 @@ impl Default for BellConfig {
             animation: Default::default(),
             command: Default::default(),
             duration: Default::default(),
+            sound: Default::default(),
+            volume: Default::default(),
+            sound_interval: 100,
         }
     }
 }
@@ -35,6 +48,10 @@ impl BellConfig {
     pub fn duration(&self) -> Duration {
         Duration::from_millis(self.duration as u64)
     }
+
+    pub fn sound_interval(&self) -> Duration {
+        Duration::from_millis(self.sound_interval as u64)
+    }
 }
 
 /// `VisualBellAnimations` are modeled after a subset of CSS transitions and Robert
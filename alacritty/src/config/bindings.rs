@@ -18,6 +18,7 @@ use alacritty_terminal::term::TermMode;
 use alacritty_terminal::vi_mode::ViMotion;
 
 use crate::config::ui_config::Hint;
+use crate::config::window::OptionAsAlt;
 
 /// Describes a state and action to take in that state.
 ///
@@ -40,6 +41,13 @@ pub struct Binding<T> {
     ///
     /// For example, this might be a key like "G", or a mouse button.
     pub trigger: T,
+
+    /// `Option` keys treated as `Alt` by this key binding, overriding `window.option_as_alt`.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub option_as_alt: Option<OptionAsAlt>,
+
+    /// Trigger the binding when the key or button is released, instead of pressed.
+    pub release: bool,
 }
 
 /// Bindings that are triggered by a keyboard key.
@@ -82,6 +90,14 @@ impl<T: Eq> Binding<T> {
 
         true
     }
+
+    /// Check if both bindings are triggered by the same input in any combination of modes.
+    #[inline]
+    pub fn overlaps(&self, binding: &Binding<T>) -> bool {
+        self.trigger == binding.trigger
+            && self.mods == binding.mods
+            && !(self.mode | binding.mode).intersects(self.notmode | binding.notmode)
+    }
 }
 
 #[derive(ConfigDeserialize, Debug, Clone, PartialEq, Eq)]
@@ -98,6 +114,18 @@ pub enum Action {
     #[config(skip)]
     Hint(Hint),
 
+    /// Toggle the highlighting rule at an index.
+    #[config(skip)]
+    ToggleHighlight(usize),
+
+    /// Run an action registered by a Lua plugin.
+    #[config(skip)]
+    Script(String),
+
+    /// Override config options of the focused pane.
+    #[config(skip)]
+    PaneConfig(Vec<String>),
+
     /// Move vi mode cursor.
     #[config(skip)]
     ViMotion(ViMotion),
@@ -114,12 +142,22 @@ pub enum Action {
     #[config(skip)]
     Mouse(MouseAction),
 
+    /// Control the playback of a replayed session.
+    #[config(skip)]
+    Replay(ReplayAction),
+
     /// Paste contents of system clipboard.
     Paste,
 
     /// Store current selection into clipboard.
     Copy,
 
+    /// Store current selection into clipboard with its colors as HTML and RTF.
+    CopyFormatted,
+
+    /// Store current selection into clipboard with SGR escape sequences.
+    CopyAnsi,
+
     #[cfg(not(any(target_os = "macos", windows)))]
     /// Store current selection into selection buffer.
     CopySelection,
@@ -127,6 +165,9 @@ pub enum Action {
     /// Paste contents of selection buffer.
     PasteSelection,
 
+    /// Pick an entry from the clipboard history to paste.
+    ClipboardHistory,
+
     /// Increase font size.
     IncreaseFontSize,
 
@@ -160,9 +201,96 @@ pub enum Action {
     /// Scroll all the way to the bottom.
     ScrollToBottom,
 
+    /// Scroll to the previous shell prompt.
+    ScrollToPreviousPrompt,
+
+    /// Scroll to the next shell prompt.
+    ScrollToNextPrompt,
+
+    /// Select the output of the last command.
+    SelectLastOutput,
+
     /// Clear the display buffer(s) to remove history.
     ClearHistory,
 
+    /// Clear the history and all lines above the cursor, moving the cursor's line to the top.
+    ClearScreenAndHistory,
+
+    /// Clear all lines above the last shell prompt marked with OSC 133.
+    ClearHistoryAbovePrompt,
+
+    /// Write the scrollback history and screen content to a file as plain text.
+    DumpHistory,
+
+    /// Write the scrollback history and screen content to a file with SGR sequences.
+    DumpHistoryAnsi,
+
+    /// Open the scrollback history and screen content in the configured pager.
+    OpenHistoryInPager,
+
+    /// Write the rendered frame to a PNG file.
+    Screenshot,
+
+    /// Write the selection or the visible screen to an SVG file.
+    ExportSvg,
+
+    /// Show or hide the time each line was written at.
+    ToggleTimestamps,
+
+    /// Switch to the next color vision deficiency filter.
+    CycleColorFilter,
+
+    /// Start or stop recording the terminal output to an asciicast file.
+    ToggleRecording,
+
+    /// Split the focused pane, opening a new pane to its right.
+    SplitRight,
+
+    /// Split the focused pane, opening a new pane below it.
+    SplitDown,
+
+    /// Focus the pane to the left of the focused pane.
+    FocusPaneLeft,
+
+    /// Focus the pane to the right of the focused pane.
+    FocusPaneRight,
+
+    /// Focus the pane above the focused pane.
+    FocusPaneUp,
+
+    /// Focus the pane below the focused pane.
+    FocusPaneDown,
+
+    /// Move the border of the focused pane to the left.
+    ResizePaneLeft,
+
+    /// Move the border of the focused pane to the right.
+    ResizePaneRight,
+
+    /// Move the border of the focused pane up.
+    ResizePaneUp,
+
+    /// Move the border of the focused pane down.
+    ResizePaneDown,
+
+    /// Close the focused pane.
+    ClosePane,
+
+    /// Move the focused pane into a new window.
+    DetachPane,
+
+    /// Move the focused pane into the previously focused window.
+    AttachPane,
+
+    /// Remove all config overrides of the focused pane.
+    ResetPaneConfig,
+
+    /// Start or stop writing input to all panes of the window.
+    ToggleBroadcastInput,
+
+    /// Show or hide dropdown windows, opening one if none exists.
+    ToggleDropdown,
+
     /// Hide the Alacritty window.
     Hide,
 
@@ -179,6 +307,12 @@ pub enum Action {
     /// Clear warning and error notices.
     ClearLogNotice,
 
+    /// Clear all queued warning and error notices.
+    ClearAllLogNotices,
+
+    /// Open the configuration file with the default application.
+    OpenConfig,
+
     /// Spawn a new instance of Alacritty.
     SpawnNewInstance,
 
@@ -243,6 +377,12 @@ pub enum Action {
     /// Toggle maximized.
     ToggleMaximized,
 
+    /// Toggle keeping the window above all other windows.
+    ToggleAlwaysOnTop,
+
+    /// Toggle showing the window on all workspaces.
+    ToggleSticky,
+
     /// Toggle simple fullscreen on macOS.
     #[cfg(target_os = "macos")]
     ToggleSimpleFullscreen,
@@ -296,6 +436,12 @@ impl From<MouseAction> for Action {
     }
 }
 
+impl From<ReplayAction> for Action {
+    fn from(action: ReplayAction) -> Self {
+        Self::Replay(action)
+    }
+}
+
 /// Display trait used for error logging.
 impl Display for Action {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -331,6 +477,30 @@ pub enum ViAction {
     Open,
     /// Centers the screen around the vi mode cursor.
     CenterAroundViCursor,
+    /// Jump to the previous position in the jump list.
+    JumpOlder,
+    /// Jump to the next position in the jump list.
+    JumpNewer,
+    /// Add or remove a bookmark on the vi mode cursor's line.
+    ToggleBookmark,
+    /// Jump to the previous bookmarked line.
+    PreviousBookmark,
+    /// Jump to the next bookmarked line.
+    NextBookmark,
+    /// Copy the text object typed next, without an active selection.
+    Yank,
+    /// Replace the active selection with the inner text object typed next.
+    SelectInner,
+    /// Replace the active selection with the text object typed next, including its delimiters.
+    SelectAround,
+    /// Set the mark named by the letter typed next.
+    SetMark,
+    /// Jump to the mark named by the letter typed next.
+    GotoMark,
+    /// Jump to the first occupied cell in the line of the mark named by the letter typed next.
+    GotoMarkLine,
+    /// Yank to or paste from the register named by the letter typed next.
+    Register,
 }
 
 /// Search mode specific actions.
@@ -353,6 +523,14 @@ pub enum SearchAction {
     SearchHistoryPrevious,
     /// Go to the next regex in the search history.
     SearchHistoryNext,
+    /// Cycle between smart case, case sensitive and case insensitive search.
+    SearchToggleCaseSensitivity,
+    /// Toggle matching only whole words.
+    SearchToggleWholeWord,
+    /// Toggle restricting the search to the selection or the last command's output.
+    SearchToggleScope,
+    /// Toggle fuzzy matching, with matches ordered by how closely they resemble the search.
+    SearchToggleFuzzy,
 }
 
 /// Mouse binding specific actions.
@@ -362,6 +540,22 @@ pub enum MouseAction {
     ExpandSelection,
 }
 
+/// Replay specific actions.
+#[allow(clippy::enum_variant_names)]
+#[derive(ConfigDeserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReplayAction {
+    /// Pause or resume the playback.
+    ReplayTogglePause,
+    /// Move the playback back by a few seconds.
+    ReplaySeekBackward,
+    /// Move the playback forward by a few seconds.
+    ReplaySeekForward,
+    /// Double the playback speed.
+    ReplaySpeedUp,
+    /// Halve the playback speed.
+    ReplaySlowDown,
+}
+
 macro_rules! bindings {
     (
         $ty:ident;
@@ -391,6 +585,8 @@ macro_rules! bindings {
                 mode: _mode,
                 notmode: _notmode,
                 action: $action.into(),
+                option_as_alt: None,
+                release: false,
             });
         )*
 
@@ -429,6 +625,7 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
         Copy,  +BindingMode::VI; Action::ClearSelection;
         Paste, ~BindingMode::VI; Action::Paste;
         "l",       ModifiersState::CONTROL; Action::ClearLogNotice;
+        "l",       ModifiersState::CONTROL | ModifiersState::SHIFT; Action::ClearAllLogNotices;
         "l",       ModifiersState::CONTROL, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x0c".into());
         Tab,       ModifiersState::SHIFT,   ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[Z".into());
         Backspace, ModifiersState::ALT,     ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b\x7f".into());
@@ -494,6 +691,7 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
         "f",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollPageDown;
         "u",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollHalfPageUp;
         "d",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollHalfPageDown;
+        "y",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::Yank;
         "y",                                +BindingMode::VI, ~BindingMode::SEARCH; Action::Copy;
         "y",                                +BindingMode::VI, ~BindingMode::SEARCH; Action::ClearSelection;
         "/",                                +BindingMode::VI, ~BindingMode::SEARCH; Action::SearchForward;
@@ -506,6 +704,16 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
         "n",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViAction::SearchPrevious;
         Enter,                              +BindingMode::VI, ~BindingMode::SEARCH; ViAction::Open;
         "z",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::CenterAroundViCursor;
+        "o",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; ViAction::JumpOlder;
+        "i",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; ViAction::JumpNewer;
+        "m",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; ViAction::ToggleBookmark;
+        "[",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::PreviousBookmark;
+        "]",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::NextBookmark;
+        "a",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::SelectAround;
+        "m",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::SetMark;
+        "`",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::GotoMark;
+        "'",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::GotoMarkLine;
+        "\"",     ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViAction::Register;
         "k",                                +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Up;
         "j",                                +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Down;
         "h",                                +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Left;
@@ -537,8 +745,18 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
         "n",      ModifiersState::CONTROL,  +BindingMode::SEARCH; SearchAction::SearchHistoryNext;
         ArrowUp,                            +BindingMode::SEARCH; SearchAction::SearchHistoryPrevious;
         ArrowDown,                          +BindingMode::SEARCH; SearchAction::SearchHistoryNext;
+        "c",      ModifiersState::ALT,      +BindingMode::SEARCH; SearchAction::SearchToggleCaseSensitivity;
+        "w",      ModifiersState::ALT,      +BindingMode::SEARCH; SearchAction::SearchToggleWholeWord;
+        "s",      ModifiersState::ALT,      +BindingMode::SEARCH; SearchAction::SearchToggleScope;
+        "f",      ModifiersState::ALT,      +BindingMode::SEARCH; SearchAction::SearchToggleFuzzy;
         Enter,                              +BindingMode::SEARCH, ~BindingMode::VI; SearchAction::SearchFocusNext;
         Enter, ModifiersState::SHIFT,       +BindingMode::SEARCH, ~BindingMode::VI; SearchAction::SearchFocusPrevious;
+        // Replay.
+        Space,      +BindingMode::REPLAY, ~BindingMode::VI, ~BindingMode::SEARCH; ReplayAction::ReplayTogglePause;
+        ArrowLeft,  +BindingMode::REPLAY, ~BindingMode::VI, ~BindingMode::SEARCH; ReplayAction::ReplaySeekBackward;
+        ArrowRight, +BindingMode::REPLAY, ~BindingMode::VI, ~BindingMode::SEARCH; ReplayAction::ReplaySeekForward;
+        ArrowUp,    +BindingMode::REPLAY, ~BindingMode::VI, ~BindingMode::SEARCH; ReplayAction::ReplaySpeedUp;
+        ArrowDown,  +BindingMode::REPLAY, ~BindingMode::VI, ~BindingMode::SEARCH; ReplayAction::ReplaySlowDown;
     );
 
     //   Code     Modifiers
@@ -622,6 +840,16 @@ fn common_keybindings() -> Vec<KeyBinding> {
         "b",    ModifiersState::CONTROL | ModifiersState::SHIFT, ~BindingMode::SEARCH;                   Action::SearchBackward;
         Insert, ModifiersState::SHIFT,                           ~BindingMode::VI;                       Action::PasteSelection;
         "c",    ModifiersState::CONTROL | ModifiersState::SHIFT;                                         Action::Copy;
+        "h",    ModifiersState::CONTROL | ModifiersState::SHIFT;                                         Action::ClipboardHistory;
+        "z",    ModifiersState::CONTROL | ModifiersState::SHIFT, ~BindingMode::SEARCH;                   Action::ScrollToPreviousPrompt;
+        "x",    ModifiersState::CONTROL | ModifiersState::SHIFT, ~BindingMode::SEARCH;                   Action::ScrollToNextPrompt;
+        "g",    ModifiersState::CONTROL | ModifiersState::SHIFT, ~BindingMode::SEARCH;                   Action::SelectLastOutput;
+        "e",    ModifiersState::CONTROL | ModifiersState::SHIFT;                                         Action::SplitRight;
+        "o",    ModifiersState::CONTROL | ModifiersState::SHIFT;                                         Action::SplitDown;
+        "w",    ModifiersState::CONTROL | ModifiersState::SHIFT;                                         Action::ClosePane;
+        "d",    ModifiersState::CONTROL | ModifiersState::SHIFT;                                         Action::DetachPane;
+        "a",    ModifiersState::CONTROL | ModifiersState::SHIFT;                                         Action::AttachPane;
+        "i",    ModifiersState::CONTROL | ModifiersState::SHIFT;                                         Action::ToggleBroadcastInput;
         "0",    ModifiersState::CONTROL;                                                                 Action::ResetFontSize;
         "=",    ModifiersState::CONTROL;                                                                 Action::IncreaseFontSize;
         "+",    ModifiersState::CONTROL;                                                                 Action::IncreaseFontSize;
@@ -675,7 +903,24 @@ pub fn platform_key_bindings() -> Vec<KeyBinding> {
         "k",    ModifiersState::SUPER, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x0c".into());
         "k",    ModifiersState::SUPER, ~BindingMode::VI, ~BindingMode::SEARCH; Action::ClearHistory;
         "v",    ModifiersState::SUPER, ~BindingMode::VI;                       Action::Paste;
+        "v",    ModifiersState::SUPER | ModifiersState::SHIFT;                 Action::ClipboardHistory;
+        ArrowUp,   ModifiersState::SUPER | ModifiersState::SHIFT, ~BindingMode::SEARCH; Action::ScrollToPreviousPrompt;
+        ArrowDown, ModifiersState::SUPER | ModifiersState::SHIFT, ~BindingMode::SEARCH; Action::ScrollToNextPrompt;
+        "a",    ModifiersState::SUPER | ModifiersState::SHIFT, ~BindingMode::SEARCH; Action::SelectLastOutput;
         "n",    ModifiersState::SUPER;                                         Action::CreateNewWindow;
+        "d",    ModifiersState::SUPER;                                         Action::SplitRight;
+        "d",    ModifiersState::SUPER | ModifiersState::SHIFT;                 Action::SplitDown;
+        ArrowLeft,  ModifiersState::SUPER | ModifiersState::ALT;               Action::FocusPaneLeft;
+        ArrowRight, ModifiersState::SUPER | ModifiersState::ALT;               Action::FocusPaneRight;
+        ArrowUp,    ModifiersState::SUPER | ModifiersState::ALT;               Action::FocusPaneUp;
+        ArrowDown,  ModifiersState::SUPER | ModifiersState::ALT;               Action::FocusPaneDown;
+        ArrowLeft,  ModifiersState::SUPER | ModifiersState::CONTROL;           Action::ResizePaneLeft;
+        ArrowRight, ModifiersState::SUPER | ModifiersState::CONTROL;           Action::ResizePaneRight;
+        ArrowUp,    ModifiersState::SUPER | ModifiersState::CONTROL;           Action::ResizePaneUp;
+        ArrowDown,  ModifiersState::SUPER | ModifiersState::CONTROL;           Action::ResizePaneDown;
+        "d",    ModifiersState::SUPER | ModifiersState::ALT;                   Action::DetachPane;
+        "a",    ModifiersState::SUPER | ModifiersState::ALT;                   Action::AttachPane;
+        "i",    ModifiersState::SUPER | ModifiersState::ALT;                   Action::ToggleBroadcastInput;
         "f",    ModifiersState::CONTROL | ModifiersState::SUPER;               Action::ToggleFullscreen;
         "c",    ModifiersState::SUPER;                                         Action::Copy;
         "h",    ModifiersState::SUPER;                                         Action::Hide;
@@ -793,17 +1038,19 @@ bitflags! {
         const ALT_SCREEN          = 0b0000_0100;
         const VI                  = 0b0000_1000;
         const SEARCH              = 0b0001_0000;
+        const REPLAY              = 0b0010_0000;
     }
 }
 
 impl BindingMode {
-    pub fn new(mode: &TermMode, search: bool) -> BindingMode {
+    pub fn new(mode: &TermMode, search: bool, replay: bool) -> BindingMode {
         let mut binding_mode = BindingMode::empty();
         binding_mode.set(BindingMode::APP_CURSOR, mode.contains(TermMode::APP_CURSOR));
         binding_mode.set(BindingMode::APP_KEYPAD, mode.contains(TermMode::APP_KEYPAD));
         binding_mode.set(BindingMode::ALT_SCREEN, mode.contains(TermMode::ALT_SCREEN));
         binding_mode.set(BindingMode::VI, mode.contains(TermMode::VI));
         binding_mode.set(BindingMode::SEARCH, search);
+        binding_mode.set(BindingMode::REPLAY, replay);
         binding_mode
     }
 }
@@ -849,6 +1096,8 @@ impl<'a> Deserialize<'a> for ModeWrapper {
                         "~vi" => res.not_mode |= BindingMode::VI,
                         "search" => res.mode |= BindingMode::SEARCH,
                         "~search" => res.not_mode |= BindingMode::SEARCH,
+                        "replay" => res.mode |= BindingMode::REPLAY,
+                        "~replay" => res.not_mode |= BindingMode::REPLAY,
                         _ => return Err(E::invalid_value(Unexpected::Str(modifier), &self)),
                     }
                 }
@@ -919,6 +1168,8 @@ struct RawBinding {
     mode: BindingMode,
     notmode: BindingMode,
     action: Action,
+    option_as_alt: Option<OptionAsAlt>,
+    release: bool,
 }
 
 impl RawBinding {
@@ -930,6 +1181,8 @@ impl RawBinding {
                 action: self.action,
                 mode: self.mode,
                 notmode: self.notmode,
+                option_as_alt: self.option_as_alt,
+                release: self.release,
             })
         } else {
             Err(Box::new(self))
@@ -944,6 +1197,8 @@ impl RawBinding {
                 action: self.action,
                 mode: self.mode,
                 notmode: self.notmode,
+                option_as_alt: self.option_as_alt,
+                release: self.release,
             })
         } else {
             Err(Box::new(self))
@@ -956,7 +1211,19 @@ impl<'a> Deserialize<'a> for RawBinding {
     where
         D: Deserializer<'a>,
     {
-        const FIELDS: &[&str] = &["key", "mods", "mode", "action", "chars", "mouse", "command"];
+        const FIELDS: &[&str] = &[
+            "key",
+            "mods",
+            "mode",
+            "action",
+            "chars",
+            "mouse",
+            "command",
+            "script",
+            "pane_config",
+            "option_as_alt",
+            "release",
+        ];
 
         enum Field {
             Key,
@@ -966,6 +1233,10 @@ impl<'a> Deserialize<'a> for RawBinding {
             Chars,
             Mouse,
             Command,
+            Script,
+            PaneConfig,
+            OptionAsAlt,
+            Release,
         }
 
         impl<'a> Deserialize<'a> for Field {
@@ -994,6 +1265,10 @@ impl<'a> Deserialize<'a> for RawBinding {
                             "chars" => Ok(Field::Chars),
                             "mouse" => Ok(Field::Mouse),
                             "command" => Ok(Field::Command),
+                            "script" => Ok(Field::Script),
+                            "pane_config" => Ok(Field::PaneConfig),
+                            "option_as_alt" => Ok(Field::OptionAsAlt),
+                            "release" => Ok(Field::Release),
                             _ => Err(E::unknown_field(value, FIELDS)),
                         }
                     }
@@ -1023,6 +1298,10 @@ impl<'a> Deserialize<'a> for RawBinding {
                 let mut not_mode: Option<BindingMode> = None;
                 let mut mouse: Option<MouseButton> = None;
                 let mut command: Option<Program> = None;
+                let mut script: Option<String> = None;
+                let mut pane_config: Option<Vec<String>> = None;
+                let mut option_as_alt: Option<OptionAsAlt> = None;
+                let mut release: Option<bool> = None;
 
                 use de::Error;
 
@@ -1089,6 +1368,10 @@ impl<'a> Deserialize<'a> for RawBinding {
                             } else if let Ok(mouse_action) = MouseAction::deserialize(value.clone())
                             {
                                 Some(mouse_action.into())
+                            } else if let Ok(replay_action) =
+                                ReplayAction::deserialize(value.clone())
+                            {
+                                Some(replay_action.into())
                             } else {
                                 match Action::deserialize(value.clone()).map_err(V::Error::custom) {
                                     Ok(action) => Some(action),
@@ -1126,18 +1409,47 @@ impl<'a> Deserialize<'a> for RawBinding {
 
                             command = Some(map.next_value::<Program>()?);
                         },
+                        Field::Script => {
+                            if script.is_some() {
+                                return Err(<V::Error as Error>::duplicate_field("script"));
+                            }
+
+                            script = Some(map.next_value()?);
+                        },
+                        Field::PaneConfig => {
+                            if pane_config.is_some() {
+                                return Err(<V::Error as Error>::duplicate_field("pane_config"));
+                            }
+
+                            pane_config = Some(map.next_value()?);
+                        },
+                        Field::OptionAsAlt => {
+                            if option_as_alt.is_some() {
+                                return Err(<V::Error as Error>::duplicate_field("option_as_alt"));
+                            }
+
+                            option_as_alt = Some(map.next_value()?);
+                        },
+                        Field::Release => {
+                            if release.is_some() {
+                                return Err(<V::Error as Error>::duplicate_field("release"));
+                            }
+
+                            release = Some(map.next_value()?);
+                        },
                     }
                 }
 
                 let mode = mode.unwrap_or_else(BindingMode::empty);
                 let not_mode = not_mode.unwrap_or_else(BindingMode::empty);
                 let mods = mods.unwrap_or_default();
+                let release = release.unwrap_or_default();
 
-                let action = match (action, chars, command) {
-                    (Some(action @ Action::ViMotion(_)), None, None)
-                    | (Some(action @ Action::Vi(_)), None, None) => action,
-                    (Some(action @ Action::Search(_)), None, None) => action,
-                    (Some(action @ Action::Mouse(_)), None, None) => {
+                let action = match (action, chars, command, script, pane_config) {
+                    (Some(action @ Action::ViMotion(_)), None, None, None, None)
+                    | (Some(action @ Action::Vi(_)), None, None, None, None) => action,
+                    (Some(action @ Action::Search(_)), None, None, None, None) => action,
+                    (Some(action @ Action::Mouse(_)), None, None, None, None) => {
                         if mouse.is_none() {
                             return Err(V::Error::custom(format!(
                                 "action `{}` is only available for mouse bindings",
@@ -1146,12 +1458,15 @@ impl<'a> Deserialize<'a> for RawBinding {
                         }
                         action
                     },
-                    (Some(action), None, None) => action,
-                    (None, Some(chars), None) => Action::Esc(chars),
-                    (None, None, Some(cmd)) => Action::Command(cmd),
+                    (Some(action), None, None, None, None) => action,
+                    (None, Some(chars), None, None, None) => Action::Esc(chars),
+                    (None, None, Some(cmd), None, None) => Action::Command(cmd),
+                    (None, None, None, Some(script), None) => Action::Script(script),
+                    (None, None, None, None, Some(options)) => Action::PaneConfig(options),
                     _ => {
                         return Err(V::Error::custom(
-                            "must specify exactly one of chars, action or command",
+                            "must specify exactly one of chars, action, command, script or \
+                             pane_config",
                         ));
                     },
                 };
@@ -1160,7 +1475,16 @@ impl<'a> Deserialize<'a> for RawBinding {
                     return Err(V::Error::custom("bindings require mouse button or key"));
                 }
 
-                Ok(RawBinding { mode, notmode: not_mode, action, key, mouse, mods })
+                Ok(RawBinding {
+                    mode,
+                    notmode: not_mode,
+                    action,
+                    key,
+                    mouse,
+                    mods,
+                    option_as_alt,
+                    release,
+                })
             }
         }
 
@@ -1257,6 +1581,8 @@ mod tests {
                 mode: BindingMode::empty(),
                 notmode: BindingMode::empty(),
                 trigger: Default::default(),
+                option_as_alt: None,
+                release: false,
             }
         }
     }
@@ -1289,6 +1615,27 @@ mod tests {
         assert!(!subset_mods.triggers_match(&superset_mods));
     }
 
+    #[test]
+    fn release_binding_replaces_press_binding() {
+        let press = MockBinding::default();
+        let release = MockBinding { release: true, ..MockBinding::default() };
+
+        assert!(press.triggers_match(&release));
+        assert!(release.triggers_match(&press));
+    }
+
+    #[test]
+    fn bindings_overlap_in_combined_modes() {
+        let vi = MockBinding { mode: BindingMode::VI, ..MockBinding::default() };
+        let search = MockBinding { mode: BindingMode::SEARCH, ..MockBinding::default() };
+        let not_vi = MockBinding { notmode: BindingMode::VI, ..MockBinding::default() };
+
+        assert!(!vi.triggers_match(&search));
+        assert!(vi.overlaps(&search));
+        assert!(!vi.overlaps(&not_vi));
+        assert!(search.overlaps(&not_vi));
+    }
+
     #[test]
     fn binding_matches_identical_mode() {
         let b1 = MockBinding { mode: BindingMode::ALT_SCREEN, ..MockBinding::default() };
@@ -1446,4 +1793,27 @@ mod tests {
         assert!(!binding.is_triggered_by(BindingMode::ALT_SCREEN, mods, &t));
         assert!(!binding.is_triggered_by(BindingMode::ALT_SCREEN | BindingMode::VI, mods, &t));
     }
+
+    #[test]
+    fn deserialize_script_binding() {
+        let binding: KeyBinding = toml::from_str("key = 'L'\nscript = 'ClearOverlay'").unwrap();
+        assert_eq!(binding.action, Action::Script(String::from("ClearOverlay")));
+
+        let binding = toml::from_str::<KeyBinding>("key = 'L'\nchars = 'l'\nscript = 'Clear'");
+        assert!(binding.is_err());
+    }
+
+    #[test]
+    fn deserialize_pane_config_binding() {
+        let binding: KeyBinding =
+            toml::from_str("key = 'F2'\npane_config = ['font.size=8', 'cursor.animation=false']")
+                .unwrap();
+        assert_eq!(
+            binding.action,
+            Action::PaneConfig(vec![
+                String::from("font.size=8"),
+                String::from("cursor.animation=false")
+            ])
+        );
+    }
 }
@@ -1,7 +1,10 @@
+use std::time::Duration;
+
 use serde::de::Error as SerdeError;
 use serde::{Deserialize, Deserializer};
 
 use alacritty_config_derive::ConfigDeserialize;
+use alacritty_terminal::ansi::CursorShape;
 use alacritty_terminal::term::color::{CellRgb, Rgb};
 
 #[derive(ConfigDeserialize, Clone, Debug, Default, PartialEq, Eq)]
@@ -9,6 +12,8 @@ pub struct Colors {
     pub primary: PrimaryColors,
     pub cursor: InvertedCellColors,
     pub vi_mode_cursor: InvertedCellColors,
+    pub cursor_shapes: CursorShapeColors,
+    pub adaptive_cursor: bool,
     pub selection: InvertedCellColors,
     pub normal: NormalColors,
     pub bright: BrightColors,
@@ -18,8 +23,18 @@ pub struct Colors {
     pub line_indicator: LineIndicatorColors,
     pub hints: HintColors,
     pub transparent_background_colors: bool,
+    pub color_key: bool,
+    pub high_contrast: HighContrast,
+    pub color_filter: ColorFilter,
     pub draw_bold_text_with_bright_colors: bool,
     footer_bar: BarColors,
+    command_status: CommandStatusColors,
+    bookmark: Option<Rgb>,
+    broadcast: Option<Rgb>,
+    search_cursor: Option<Rgb>,
+
+    /// Duration of the transition between palettes in milliseconds.
+    transition: u16,
 }
 
 impl Colors {
@@ -30,6 +45,77 @@ impl Colors {
     pub fn footer_bar_background(&self) -> Rgb {
         self.footer_bar.background.unwrap_or(self.primary.foreground)
     }
+
+    pub fn command_success(&self) -> Option<Rgb> {
+        self.command_status.success
+    }
+
+    pub fn command_failure(&self) -> Rgb {
+        self.command_status.failure.unwrap_or(self.normal.red)
+    }
+
+    pub fn bookmark(&self) -> Rgb {
+        self.bookmark.unwrap_or(self.normal.blue)
+    }
+
+    pub fn broadcast(&self) -> Rgb {
+        self.broadcast.unwrap_or(self.normal.red)
+    }
+
+    pub fn search_cursor(&self) -> Rgb {
+        self.search_cursor.unwrap_or_else(|| self.footer_bar_foreground())
+    }
+
+    pub fn transition(&self) -> Duration {
+        Duration::from_millis(self.transition as u64)
+    }
+}
+
+/// When colors are replaced by a high contrast palette.
+#[derive(ConfigDeserialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HighContrast {
+    /// Always use the configured colors.
+    Off,
+    /// Always use the high contrast palette.
+    On,
+    /// Follow the system's high contrast setting.
+    #[default]
+    System,
+}
+
+/// Filter applied to the rendered window for color vision deficiencies.
+#[derive(ConfigDeserialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorFilter {
+    /// Draw colors unchanged.
+    #[default]
+    None,
+    /// Simulate the absence of red cones.
+    Protanopia,
+    /// Simulate the absence of green cones.
+    Deuteranopia,
+    /// Simulate the absence of blue cones.
+    Tritanopia,
+    /// Shift colors indistinguishable without red cones to distinguishable ones.
+    ProtanopiaCorrection,
+    /// Shift colors indistinguishable without green cones to distinguishable ones.
+    DeuteranopiaCorrection,
+    /// Shift colors indistinguishable without blue cones to distinguishable ones.
+    TritanopiaCorrection,
+}
+
+impl ColorFilter {
+    /// Filter following this one, wrapping around to no filter after the last one.
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Protanopia,
+            Self::Protanopia => Self::Deuteranopia,
+            Self::Deuteranopia => Self::Tritanopia,
+            Self::Tritanopia => Self::ProtanopiaCorrection,
+            Self::ProtanopiaCorrection => Self::DeuteranopiaCorrection,
+            Self::DeuteranopiaCorrection => Self::TritanopiaCorrection,
+            Self::TritanopiaCorrection => Self::None,
+        }
+    }
 }
 
 #[derive(ConfigDeserialize, Copy, Clone, Default, Debug, PartialEq, Eq)]
@@ -124,6 +210,38 @@ impl Default for InvertedCellColors {
     }
 }
 
+/// Cursor colors replacing the normal cursor colors for individual cursor shapes.
+#[derive(ConfigDeserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CursorShapeColors {
+    pub block: CursorColorOverride,
+    pub beam: CursorColorOverride,
+    pub underline: CursorColorOverride,
+}
+
+impl CursorShapeColors {
+    /// Apply the overrides of a cursor shape to the normal cursor colors.
+    pub fn apply(&self, shape: CursorShape, colors: InvertedCellColors) -> InvertedCellColors {
+        let colors_override = match shape {
+            CursorShape::Block | CursorShape::HollowBlock => self.block,
+            CursorShape::Beam => self.beam,
+            CursorShape::Underline => self.underline,
+            CursorShape::Hidden => return colors,
+        };
+
+        InvertedCellColors {
+            foreground: colors_override.text.unwrap_or(colors.foreground),
+            background: colors_override.cursor.unwrap_or(colors.background),
+        }
+    }
+}
+
+/// Cursor colors, falling back to the normal cursor colors when missing.
+#[derive(ConfigDeserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CursorColorOverride {
+    pub text: Option<CellRgb>,
+    pub cursor: Option<CellRgb>,
+}
+
 #[derive(ConfigDeserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
 pub struct SearchColors {
     pub focused_match: FocusedMatchColors,
@@ -166,6 +284,12 @@ pub struct BarColors {
     background: Option<Rgb>,
 }
 
+#[derive(ConfigDeserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CommandStatusColors {
+    success: Option<Rgb>,
+    failure: Option<Rgb>,
+}
+
 #[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct PrimaryColors {
     pub foreground: Rgb,
@@ -269,3 +393,22 @@ impl Default for DimColors {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_shape_overrides() {
+        let red = CellRgb::Rgb(Rgb::new(0xff, 0, 0));
+        let mut shapes = CursorShapeColors::default();
+        shapes.beam.cursor = Some(red);
+
+        let colors = InvertedCellColors::default();
+        let beam = shapes.apply(CursorShape::Beam, colors);
+        assert_eq!(beam.background, red);
+        assert_eq!(beam.foreground, colors.foreground);
+
+        assert_eq!(shapes.apply(CursorShape::Block, colors), colors);
+    }
+}
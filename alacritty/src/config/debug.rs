@@ -14,7 +14,7 @@ pub struct Debug {
     /// Keep the log file after quitting.
     pub persistent_logging: bool,
 
-    /// Should show render timer.
+    /// Should show render timer and performance metrics.
     pub render_timer: bool,
 
     /// Highlight damage information produced by alacritty.
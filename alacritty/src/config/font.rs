@@ -42,6 +42,9 @@ pub struct Font {
 
     /// Whether to use the built-in font for box drawing characters.
     pub builtin_box_drawing: bool,
+
+    /// Maximum memory used by the glyph atlas textures in MiB, `0` for no limit.
+    pub max_atlas_memory: usize,
 }
 
 impl Font {
@@ -88,6 +91,7 @@ impl Default for Font {
             normal: Default::default(),
             bold: Default::default(),
             size: Default::default(),
+            max_atlas_memory: 0,
         }
     }
 }
@@ -0,0 +1,78 @@
+use serde::Deserialize;
+
+use alacritty_config_derive::ConfigDeserialize;
+use alacritty_terminal::term::color::CellRgb;
+
+use crate::config::ui_config::{HintBinding, LazyRegex};
+
+/// Styles applied to visible text matching a regex.
+#[derive(ConfigDeserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct Highlights {
+    /// Highlighting rules, with earlier rules taking precedence.
+    pub rules: Vec<HighlightRule>,
+}
+
+/// Style of text matching a regex.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HighlightRule {
+    /// Regex for finding matches.
+    pub regex: LazyRegex,
+
+    /// Text color of matches.
+    #[serde(default)]
+    pub foreground: Option<CellRgb>,
+
+    /// Background color of matches.
+    #[serde(default)]
+    pub background: Option<CellRgb>,
+
+    /// Draw matches with the bold font.
+    #[serde(default)]
+    pub bold: bool,
+
+    /// Underline matches.
+    #[serde(default)]
+    pub underline: bool,
+
+    /// Apply the rule when a window is created.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Binding toggling the rule.
+    #[serde(default)]
+    pub binding: Option<HintBinding>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::term::color::Rgb;
+
+    #[test]
+    fn deserialize_rules() {
+        let highlights: Highlights = toml::from_str(
+            r##"
+            rules = [
+                { regex = "ERROR", foreground = "#ff0000", bold = true },
+                { regex = "WARN", background = "CellForeground", enabled = false },
+            ]
+            "##,
+        )
+        .unwrap();
+
+        let error = &highlights.rules[0];
+        assert_eq!(error.foreground, Some(CellRgb::Rgb(Rgb::new(0xff, 0, 0))));
+        assert_eq!(error.background, None);
+        assert!(error.bold && !error.underline && error.enabled);
+
+        let warn = &highlights.rules[1];
+        assert_eq!(warn.background, Some(CellRgb::CellForeground));
+        assert!(!warn.enabled);
+    }
+}
@@ -0,0 +1,34 @@
+use alacritty_config_derive::ConfigDeserialize;
+
+use alacritty_terminal::config::Program;
+
+/// Commands run on terminal events.
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Hooks {
+    /// Command to run when a shell command reported through OSC 133 finished.
+    pub command_finished: Option<Program>,
+
+    /// Command to run when the window title changed.
+    pub title_changed: Option<Program>,
+
+    /// Command to run when the window gained or lost focus.
+    pub focus_changed: Option<Program>,
+
+    /// Command receiving all text copied to the clipboard or selection on stdin.
+    pub copy: Option<Program>,
+
+    /// Maximum number of bytes passed to the copy hook.
+    pub copy_max_size: usize,
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Self {
+            command_finished: Default::default(),
+            title_changed: Default::default(),
+            focus_changed: Default::default(),
+            copy: Default::default(),
+            copy_max_size: 1024 * 1024,
+        }
+    }
+}
@@ -16,8 +16,16 @@ pub mod bell;
 pub mod color;
 pub mod debug;
 pub mod font;
+pub mod highlights;
+pub mod hooks;
 pub mod monitor;
+pub mod notifications;
+pub mod pager;
+pub mod panes;
+pub mod scripting;
+pub mod search;
 pub mod serde_utils;
+pub mod timestamps;
 pub mod ui_config;
 pub mod window;
 
@@ -26,7 +34,7 @@ mod mouse;
 
 use crate::cli::Options;
 pub use crate::config::bindings::{
-    Action, Binding, BindingKey, BindingMode, MouseAction, SearchAction, ViAction,
+    Action, Binding, BindingKey, BindingMode, MouseAction, ReplayAction, SearchAction, ViAction,
 };
 #[cfg(test)]
 pub use crate::config::mouse::Mouse;
@@ -139,7 +147,8 @@ pub fn load(options: &Options) -> UiConfig {
         .as_ref()
         .and_then(|config_path| load_from(config_path, config_options.clone()).ok())
         .unwrap_or_else(|| {
-            let mut config = UiConfig::deserialize(config_options).unwrap_or_default();
+            let mut config = UiConfig::deserialize(config_options.clone()).unwrap_or_default();
+            config.source = Some(config_options);
             match config_path {
                 Some(config_path) => config.config_paths.push(config_path),
                 None => info!(target: LOG_TARGET_CONFIG, "No config file found; using default"),
@@ -170,7 +179,7 @@ fn after_loading(config: &mut UiConfig, options: &Options) {
     // Override config with CLI options.
     options.override_config(config);
 
-    // Create key bindings for regex hints.
+    // Create key bindings for regex hints and highlights.
     config.generate_hint_bindings();
 }
 
@@ -194,8 +203,9 @@ fn read_config(path: &Path, cli_config: Value) -> Result<UiConfig> {
     config_value = serde_utils::merge(config_value, cli_config);
 
     // Deserialize to concrete type.
-    let mut config = UiConfig::deserialize(config_value)?;
+    let mut config = UiConfig::deserialize(config_value.clone())?;
     config.config_paths = config_paths;
+    config.source = Some(config_value);
 
     Ok(config)
 }
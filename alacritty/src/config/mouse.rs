@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use serde::{Deserialize, Deserializer};
 
 use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
@@ -9,6 +11,84 @@ use crate::config::ui_config;
 pub struct Mouse {
     pub hide_when_typing: bool,
     pub bindings: MouseBindings,
+    pub dropped_files: DroppedFiles,
+}
+
+/// Formatting of paths inserted by dropping files onto the window.
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DroppedFiles {
+    /// Escaping applied to each path.
+    pub quoting: PathQuoting,
+
+    /// Text inserted after each path.
+    pub separator: String,
+}
+
+impl Default for DroppedFiles {
+    fn default() -> Self {
+        Self { quoting: Default::default(), separator: String::from(" ") }
+    }
+}
+
+impl DroppedFiles {
+    /// Format a dropped path for insertion into the terminal.
+    pub fn format(&self, path: &Path) -> String {
+        let path = path.to_string_lossy();
+        match self.quoting {
+            PathQuoting::Plain => format!("{}{}", path, self.separator),
+            PathQuoting::Shell => format!("{}{}", shell_quote(&path), self.separator),
+            PathQuoting::Url => format!("{}{}", file_url(&path), self.separator),
+            PathQuoting::Null => format!("{}\0", path),
+        }
+    }
+}
+
+#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PathQuoting {
+    /// Insert the path without any escaping.
+    #[default]
+    Plain,
+    /// Quote the path for use in a shell.
+    Shell,
+    /// Insert the path as a `file://` URL.
+    Url,
+    /// Terminate each path with a null byte, ignoring the separator.
+    Null,
+}
+
+/// Quote a string to be interpreted literally by the shell.
+fn shell_quote(text: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-+=@%:,./".contains(c);
+    if !text.is_empty() && text.chars().all(is_safe) {
+        return text.into();
+    }
+
+    if cfg!(windows) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+/// Convert an absolute path to a percent-encoded `file://` URL.
+fn file_url(path: &str) -> String {
+    let path = if cfg!(windows) { path.replace('\\', "/") } else { path.into() };
+
+    let mut url = String::from("file://");
+    if !path.starts_with('/') {
+        url.push('/');
+    }
+
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' => {
+                url.push(byte as char)
+            },
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    url
 }
 
 #[derive(SerdeReplace, Clone, Debug, PartialEq, Eq)]
@@ -28,3 +108,32 @@ impl<'de> Deserialize<'de> for MouseBindings {
         Ok(Self(ui_config::deserialize_bindings(deserializer, Self::default().0)?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_dropped_files() {
+        let path = Path::new("/tmp/it's here");
+
+        let mut dropped = DroppedFiles::default();
+        assert_eq!(dropped.format(path), "/tmp/it's here ");
+
+        dropped.quoting = PathQuoting::Url;
+        assert_eq!(dropped.format(path), "file:///tmp/it%27s%20here ");
+
+        dropped.quoting = PathQuoting::Null;
+        assert_eq!(dropped.format(path), "/tmp/it's here\0");
+
+        dropped.quoting = PathQuoting::Shell;
+        dropped.separator = String::from("\n");
+        assert_eq!(dropped.format(Path::new("/tmp/plain")), "/tmp/plain\n");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn shell_quote_path() {
+        assert_eq!(shell_quote("/tmp/it's here"), "'/tmp/it'\\''s here'");
+    }
+}
@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use alacritty_config_derive::ConfigDeserialize;
+
+/// Notifications about events in unfocused windows.
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Notifications {
+    /// Notify when a long running command finished while the window was unfocused.
+    pub command_finished: bool,
+
+    /// Minimum duration of commands to notify about, in seconds.
+    command_threshold: u32,
+
+    /// Show a desktop notification in addition to the urgency hint.
+    pub desktop: bool,
+
+    /// Set the urgency hint when the bell rings while the window is unfocused.
+    pub bell: bool,
+
+    /// Set the urgency hint for new output while the window is unfocused and scrolled back.
+    pub scrolled_output: bool,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            command_finished: false,
+            command_threshold: 10,
+            desktop: true,
+            bell: true,
+            scrolled_output: false,
+        }
+    }
+}
+
+impl Notifications {
+    pub fn command_threshold(&self) -> Duration {
+        Duration::from_secs(self.command_threshold as u64)
+    }
+}
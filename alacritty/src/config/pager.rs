@@ -0,0 +1,41 @@
+use alacritty_config_derive::ConfigDeserialize;
+
+use alacritty_terminal::config::Program;
+
+/// Program used to browse the scrollback history.
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Pager {
+    /// Command opening the history file, the path is passed as the last argument.
+    command: Option<Program>,
+
+    /// Preserve text attributes using SGR escape sequences.
+    pub ansi: bool,
+}
+
+impl Default for Pager {
+    fn default() -> Self {
+        Self { command: Default::default(), ansi: true }
+    }
+}
+
+impl Pager {
+    /// Command and arguments used to open the history.
+    ///
+    /// Falls back to `$PAGER` and then `less -R` when no command is configured.
+    pub fn command(&self) -> Vec<String> {
+        match &self.command {
+            Some(Program::Just(program)) => vec![program.clone()],
+            Some(Program::WithArgs { program, args }) => {
+                let mut command = vec![program.clone()];
+                command.extend(args.iter().cloned());
+                command
+            },
+            None => match std::env::var("PAGER") {
+                Ok(pager) if !pager.trim().is_empty() => {
+                    pager.split_whitespace().map(String::from).collect()
+                },
+                _ => vec![String::from("less"), String::from("-R")],
+            },
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use alacritty_config_derive::ConfigDeserialize;
+
+use alacritty_terminal::term::color::Rgb;
+
+/// Appearance and behavior of split panes.
+#[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq)]
+pub struct Panes {
+    /// Width of the border between panes in logical pixels.
+    pub border_width: u8,
+
+    /// Color of the border around unfocused panes.
+    pub border_color: Rgb,
+
+    /// Color of the border around the focused pane.
+    pub focused_border_color: Rgb,
+
+    /// Share of a split's area a border is moved by when resizing panes.
+    pub resize_step: f32,
+}
+
+impl Default for Panes {
+    fn default() -> Self {
+        Self {
+            border_width: 2,
+            border_color: Rgb::new(0x44, 0x44, 0x44),
+            focused_border_color: Rgb::new(0xf4, 0xbf, 0x75),
+            resize_step: 0.05,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+
+use alacritty_config_derive::ConfigDeserialize;
+
+/// Lua plugins extending the terminal.
+#[derive(ConfigDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scripting {
+    /// Lua scripts loaded for every window, in order.
+    pub plugins: Vec<PathBuf>,
+}
@@ -0,0 +1,17 @@
+use alacritty_config_derive::ConfigDeserialize;
+
+/// Regex search configuration.
+#[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Search {
+    /// Maximum number of previous searches kept in the history.
+    pub history_size: usize,
+
+    /// Store the search history on disk, sharing it across windows and restarts.
+    pub persistent_history: bool,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self { history_size: 255, persistent_history: false }
+    }
+}
@@ -1,5 +1,6 @@
 //! Serde helpers.
 
+use toml::de::Error as TomlError;
 use toml::{Table, Value};
 
 /// Merge two serde structures.
@@ -32,6 +33,25 @@ fn merge_tables(mut base: Table, replacement: Table) -> Table {
     base
 }
 
+/// Parse a single configuration option like `font.size=12`.
+///
+/// Values which aren't valid TOML are parsed as strings, so `cursor.style.shape=Beam` is accepted
+/// without quoting the shape.
+pub fn parse_option(option: &str) -> Result<Value, TomlError> {
+    toml::from_str(option).or_else(|err| match option.split_once('=') {
+        Some((key, value)) => {
+            let value = Value::String(value.trim().to_owned());
+            toml::from_str(&format!("{} = {}", key.trim(), value)).map_err(|_| err)
+        },
+        None => Err(err),
+    })
+}
+
+/// Get the value at a dotted option path like `font.normal.family`.
+pub fn get_option<'a>(value: &'a Value, option: &str) -> Option<&'a Value> {
+    option.split('.').try_fold(value, |value, key| value.get(key.trim()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,4 +109,24 @@ mod tests {
 
         assert_eq!(merged, expected);
     }
+
+    #[test]
+    fn parse_nested_option() {
+        let value = parse_option("colors.primary.background = '#000000'").unwrap();
+        let background = get_option(&value, "colors.primary.background");
+        assert_eq!(background, Some(&Value::String(String::from("#000000"))));
+
+        let value = parse_option("font.size=12.5").unwrap();
+        assert_eq!(get_option(&value, "font.size"), Some(&Value::Float(12.5)));
+        assert_eq!(get_option(&value, "font.normal"), None);
+    }
+
+    #[test]
+    fn parse_unquoted_option() {
+        let value = parse_option("cursor.style.shape=Beam").unwrap();
+        let shape = get_option(&value, "cursor.style.shape");
+        assert_eq!(shape, Some(&Value::String(String::from("Beam"))));
+
+        assert!(parse_option("cursor.style.shape").is_err());
+    }
 }
@@ -0,0 +1,21 @@
+use alacritty_config_derive::ConfigDeserialize;
+
+/// Times at which lines were written.
+#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Timestamps {
+    /// Show timestamps next to lines when a window is created.
+    pub visible: bool,
+
+    /// Format of the displayed timestamps.
+    pub format: TimestampFormat,
+}
+
+#[derive(ConfigDeserialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Time elapsed since the line was written.
+    #[default]
+    Relative,
+
+    /// Local time of day at which the line was written.
+    Absolute,
+}
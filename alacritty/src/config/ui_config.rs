@@ -6,6 +6,7 @@ use std::rc::Rc;
 use log::{error, warn};
 use serde::de::{Error as SerdeError, MapAccess, Visitor};
 use serde::{self, Deserialize, Deserializer};
+use toml::Value;
 use unicode_width::UnicodeWidthChar;
 use winit::keyboard::{Key, KeyLocation, ModifiersState};
 
@@ -17,10 +18,18 @@ use crate::config::bell::BellConfig;
 use crate::config::bindings::{
     self, Action, Binding, BindingKey, KeyBinding, ModeWrapper, ModsWrapper, MouseBinding,
 };
-use crate::config::color::Colors;
+use crate::config::color::{Colors, HighContrast};
 use crate::config::debug::Debug;
 use crate::config::font::Font;
+use crate::config::highlights::Highlights;
+use crate::config::hooks::Hooks;
 use crate::config::mouse::{Mouse, MouseBindings};
+use crate::config::notifications::Notifications;
+use crate::config::pager::Pager;
+use crate::config::panes::Panes;
+use crate::config::scripting::Scripting;
+use crate::config::search::Search;
+use crate::config::timestamps::Timestamps;
 use crate::config::window::WindowConfig;
 
 /// Regex used for the default URL hint.
@@ -60,13 +69,45 @@ pub struct UiConfig {
     #[config(skip)]
     pub config_paths: Vec<PathBuf>,
 
+    /// Options explicitly set by the configuration files and CLI.
+    #[config(skip)]
+    pub source: Option<Value>,
+
     /// Regex hints for interacting with terminal content.
     pub hints: Hints,
 
+    /// Regex highlighting rules for terminal content.
+    pub highlights: Highlights,
+
+    /// Regex search configuration.
+    pub search: Search,
+
+    /// Commands run on terminal events.
+    pub hooks: Hooks,
+
+    /// Notifications about events in unfocused windows.
+    pub notifications: Notifications,
+
+    /// Program used to browse the scrollback history.
+    pub pager: Pager,
+
+    /// Times at which lines were written.
+    pub timestamps: Timestamps,
+
+    /// Split panes within a window.
+    pub panes: Panes,
+
+    /// Lua plugins extending the terminal.
+    pub scripting: Scripting,
+
     /// Offer IPC through a unix socket.
     #[cfg(unix)]
     pub ipc_socket: bool,
 
+    /// Offer IPC through the D-Bus session bus.
+    #[cfg(not(any(target_os = "macos", windows)))]
+    pub ipc_dbus: bool,
+
     /// Config for the alacritty_terminal itself.
     #[config(flatten)]
     pub terminal_config: TerminalConfig,
@@ -99,10 +140,13 @@ impl Default for UiConfig {
             live_config_reload: true,
             #[cfg(unix)]
             ipc_socket: true,
+            #[cfg(not(any(target_os = "macos", windows)))]
+            ipc_dbus: true,
             draw_bold_text_with_bright_colors: Default::default(),
             terminal_config: Default::default(),
             mouse_bindings: Default::default(),
             config_paths: Default::default(),
+            source: Default::default(),
             key_bindings: Default::default(),
             alt_send_esc: Default::default(),
             keyboard: Default::default(),
@@ -112,6 +156,14 @@ impl Default for UiConfig {
             mouse: Default::default(),
             debug: Default::default(),
             hints: Default::default(),
+            highlights: Default::default(),
+            search: Default::default(),
+            hooks: Default::default(),
+            notifications: Default::default(),
+            pager: Default::default(),
+            timestamps: Default::default(),
+            panes: Default::default(),
+            scripting: Default::default(),
             font: Default::default(),
             bell: Default::default(),
         }
@@ -119,7 +171,7 @@ impl Default for UiConfig {
 }
 
 impl UiConfig {
-    /// Generate key bindings for all keyboard hints.
+    /// Generate key bindings for all keyboard hints and highlighting rules.
     pub fn generate_hint_bindings(&mut self) {
         // Check which key bindings is most likely to be the user's configuration.
         //
@@ -142,6 +194,27 @@ impl UiConfig {
                 mode: binding.mode.mode,
                 notmode: binding.mode.not_mode,
                 action: Action::Hint(hint.clone()),
+                option_as_alt: None,
+                release: false,
+            };
+
+            key_bindings.push(binding);
+        }
+
+        for (index, rule) in self.highlights.rules.iter().enumerate() {
+            let binding = match &rule.binding {
+                Some(binding) => binding,
+                None => continue,
+            };
+
+            let binding = KeyBinding {
+                trigger: binding.key.clone(),
+                mods: binding.mods.0,
+                mode: binding.mode.mode,
+                notmode: binding.mode.not_mode,
+                action: Action::ToggleHighlight(index),
+                option_as_alt: None,
+                release: false,
             };
 
             key_bindings.push(binding);
@@ -150,6 +223,11 @@ impl UiConfig {
 
     #[inline]
     pub fn window_opacity(&self) -> f32 {
+        // Translucency is disabled to preserve contrast.
+        if self.colors.high_contrast == HighContrast::On {
+            return 1.;
+        }
+
         self.window.opacity.as_f32()
     }
 
@@ -208,7 +286,7 @@ pub fn deserialize_bindings<'a, D, T>(
 ) -> Result<Vec<Binding<T>>, D::Error>
 where
     D: Deserializer<'a>,
-    T: Clone + Eq,
+    T: Clone + Eq + fmt::Debug,
     Binding<T>: Deserialize<'a>,
 {
     let values = Vec::<toml::Value>::deserialize(deserializer)?;
@@ -229,6 +307,24 @@ where
         default.retain(|b| !b.triggers_match(binding));
     }
 
+    // Point out bindings running different actions for the same input in overlapping modes.
+    for (i, binding) in bindings.iter().enumerate() {
+        let conflicts = bindings[i + 1..]
+            .iter()
+            .chain(&default)
+            .filter(|other| other.action != binding.action && other.overlaps(binding));
+        for other in conflicts {
+            warn!(
+                target: LOG_TARGET_CONFIG,
+                "Config warning: bindings for {:?} with mods {:?} trigger both {} and {}",
+                binding.trigger,
+                binding.mods,
+                binding.action,
+                other.action,
+            );
+        }
+    }
+
     bindings.extend(default);
 
     Ok(bindings)
@@ -251,6 +347,12 @@ pub struct Hints {
 
     /// All configured terminal hints.
     pub enabled: Vec<Hint>,
+
+    /// Order in which matches are assigned the shortest labels.
+    pub label_order: HintLabelOrder,
+
+    /// Use labels of equal length for all matches.
+    pub uniform_labels: bool,
 }
 
 impl Default for Hints {
@@ -260,15 +362,7 @@ impl Default for Hints {
         let regex = LazyRegex(Rc::new(RefCell::new(pattern)));
         let content = HintContent::new(Some(regex), true);
 
-        #[cfg(not(any(target_os = "macos", windows)))]
-        let action = HintAction::Command(Program::Just(String::from("xdg-open")));
-        #[cfg(target_os = "macos")]
-        let action = HintAction::Command(Program::Just(String::from("open")));
-        #[cfg(windows)]
-        let action = HintAction::Command(Program::WithArgs {
-            program: String::from("cmd"),
-            args: vec!["/c".to_string(), "start".to_string(), "".to_string()],
-        });
+        let action = HintAction::Command(default_opener());
 
         Self {
             enabled: vec![Hint {
@@ -285,12 +379,31 @@ impl Default for Hints {
                     mods: ModsWrapper(ModifiersState::SHIFT | ModifiersState::CONTROL),
                     mode: Default::default(),
                 }),
+                alternate_actions: Vec::new(),
+                launchers: Vec::new(),
             }],
             alphabet: Default::default(),
+            label_order: Default::default(),
+            uniform_labels: Default::default(),
         }
     }
 }
 
+/// Program opening files and URLs with the platform's default application.
+pub fn default_opener() -> Program {
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let opener = Program::Just(String::from("xdg-open"));
+    #[cfg(target_os = "macos")]
+    let opener = Program::Just(String::from("open"));
+    #[cfg(windows)]
+    let opener = Program::WithArgs {
+        program: String::from("cmd"),
+        args: vec!["/c".to_string(), "start".to_string(), "".to_string()],
+    };
+
+    opener
+}
+
 impl Hints {
     /// Characters for the hint labels.
     pub fn alphabet(&self) -> &str {
@@ -298,6 +411,18 @@ impl Hints {
     }
 }
 
+/// Priority of hint matches when assigning labels.
+#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HintLabelOrder {
+    /// Matches at the bottom of the screen receive the shortest labels.
+    #[default]
+    BottomFirst,
+    /// Matches at the top of the screen receive the shortest labels.
+    TopFirst,
+    /// Matches closest to the cursor receive the shortest labels.
+    ClosestToCursor,
+}
+
 #[derive(SerdeReplace, Clone, Debug, PartialEq, Eq)]
 struct HintsAlphabet(String);
 
@@ -341,6 +466,10 @@ pub enum HintInternalAction {
     Select,
     /// Move the vi mode cursor to the beginning of the hint.
     MoveViModeCursor,
+    /// Write the text to the PTY without bracketed paste, as if it was typed.
+    Type,
+    /// Start a new search for the text.
+    Search,
 }
 
 /// Actions for hint bindings.
@@ -379,6 +508,55 @@ pub struct Hint {
 
     /// Binding required to search for this hint.
     binding: Option<HintBinding>,
+
+    /// Actions which can be selected with a key before typing the hint label.
+    #[serde(default)]
+    pub alternate_actions: Vec<HintAlternateAction>,
+
+    /// Launchers replacing the hint's action for specific URL schemes.
+    #[serde(default)]
+    pub launchers: Vec<HintLauncher>,
+}
+
+impl Hint {
+    /// Launcher responsible for the scheme of a URL.
+    pub fn launcher(&self, url: &str) -> Option<&HintLauncher> {
+        let (scheme, _) = url.split_once(':')?;
+        self.launchers
+            .iter()
+            .find(|launcher| launcher.scheme.trim_end_matches(':').eq_ignore_ascii_case(scheme))
+    }
+
+    /// Action for a URL, taking its scheme's launcher into account.
+    pub fn action_for(&self, url: &str) -> &HintAction {
+        self.launcher(url).map_or(&self.action, |launcher| &launcher.action)
+    }
+}
+
+/// Hint action triggered by pressing its key while hint labels are visible.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HintAlternateAction {
+    /// Key selecting this action.
+    pub key: char,
+
+    /// Action executed instead of the hint's default action.
+    #[serde(flatten)]
+    pub action: HintAction,
+}
+
+/// Action used for hint matches with a specific URL scheme.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HintLauncher {
+    /// URL scheme handled by this launcher, like `mailto`.
+    pub scheme: String,
+
+    /// Action executed instead of the hint's default action.
+    #[serde(flatten)]
+    pub action: HintAction,
+
+    /// Mouse modifiers required for highlighting, instead of the hint's modifiers.
+    #[serde(default)]
+    pub mods: Option<ModsWrapper>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, Eq)]
@@ -388,11 +566,17 @@ pub struct HintContent {
 
     /// Escape sequence hyperlinks.
     pub hyperlinks: bool,
+
+    /// Paths of existing files.
+    pub paths: bool,
+
+    /// Number of recent command outputs.
+    pub command_outputs: usize,
 }
 
 impl HintContent {
     pub fn new(regex: Option<LazyRegex>, hyperlinks: bool) -> Self {
-        Self { regex, hyperlinks }
+        Self { regex, hyperlinks, paths: false, command_outputs: 0 }
     }
 }
 
@@ -435,16 +619,39 @@ impl<'de> Deserialize<'de> for HintContent {
                                 );
                             },
                         },
+                        "paths" => match bool::deserialize(value) {
+                            Ok(paths) => content.paths = paths,
+                            Err(err) => {
+                                error!(
+                                    target: LOG_TARGET_CONFIG,
+                                    "Config error: hint's paths: {}", err
+                                );
+                            },
+                        },
+                        "command_outputs" => match usize::deserialize(value) {
+                            Ok(command_outputs) => content.command_outputs = command_outputs,
+                            Err(err) => {
+                                error!(
+                                    target: LOG_TARGET_CONFIG,
+                                    "Config error: hint's command_outputs: {}", err
+                                );
+                            },
+                        },
                         "command" | "action" => (),
                         key => warn!(target: LOG_TARGET_CONFIG, "Unrecognized hint field: {key}"),
                     }
                 }
 
-                // Require at least one of hyperlinks or regex trigger hint matches.
-                if content.regex.is_none() && !content.hyperlinks {
+                // Require at least one of hyperlinks, paths, command outputs or regex trigger hint
+                // matches.
+                if content.regex.is_none()
+                    && !content.hyperlinks
+                    && !content.paths
+                    && content.command_outputs == 0
+                {
                     return Err(M::Error::custom(
-                        "Config error: At least on of the hint's regex or hint's hyperlinks must \
-                         be set",
+                        "Config error: At least on of the hint's regex, hyperlinks, paths or \
+                         command_outputs must be set",
                     ));
                 }
 
@@ -485,7 +692,7 @@ impl LazyRegex {
     /// Execute a function with the compiled regex DFAs as parameter.
     pub fn with_compiled<T, F>(&self, f: F) -> Option<T>
     where
-        F: FnMut(&RegexSearch) -> T,
+        F: FnMut(&mut RegexSearch) -> T,
     {
         self.0.borrow_mut().compiled().map(f)
     }
@@ -514,7 +721,7 @@ impl LazyRegexVariant {
     ///
     /// If the regex is not already compiled, this will compile the DFAs and store them for future
     /// access.
-    fn compiled(&mut self) -> Option<&RegexSearch> {
+    fn compiled(&mut self) -> Option<&mut RegexSearch> {
         // Check if the regex has already been compiled.
         let regex = match self {
             Self::Compiled(regex_search) => return Some(regex_search),
@@ -607,4 +814,20 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn launcher_by_scheme() {
+        let mut hint = Hints::default().enabled.remove(0);
+        let mail = HintAction::Command(Program::Just(String::from("thunderbird")));
+        hint.launchers.push(HintLauncher {
+            scheme: String::from("mailto:"),
+            action: mail.clone(),
+            mods: Some(ModsWrapper(ModifiersState::CONTROL)),
+        });
+
+        assert_eq!(hint.action_for("MAILTO:example@example.org"), &mail);
+        assert_eq!(hint.action_for("https://example.org"), &hint.action);
+        assert_eq!(hint.action_for("mailto"), &hint.action);
+        assert!(hint.launcher("magnet:?xt=urn:btih:L0UDHA5H12").is_none());
+    }
 }
@@ -1,5 +1,8 @@
+use std::collections::HashMap;
 use std::fmt::{self, Formatter};
 use std::os::raw::c_ulong;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use log::{error, warn};
 use serde::de::{self, MapAccess, Visitor};
@@ -36,6 +39,9 @@ pub struct WindowConfig {
     /// System decorations theme variant.
     pub decorations_theme_variant: Option<Theme>,
 
+    /// Decorations drawn when the compositor refuses server-side decorations.
+    pub client_decorations: ClientDecorations,
+
     /// Spread out additional padding evenly.
     pub dynamic_padding: bool,
 
@@ -53,9 +59,43 @@ pub struct WindowConfig {
     #[cfg(target_os = "macos")]
     option_as_alt: OptionAsAlt,
 
+    /// System backdrop shown through transparent windows.
+    #[cfg(windows)]
+    pub backdrop: Backdrop,
+
     /// Resize increments.
     pub resize_increments: bool,
 
+    /// Disable animations when jumping between search matches and vi mode marks.
+    pub reduce_motion: bool,
+
+    /// Show the exit status of finished commands next to their output.
+    pub command_status: bool,
+
+    /// Show the progress reported by applications at the top of the window.
+    pub progress_bar: bool,
+
+    /// Overview of the scrollback at the right edge of the window.
+    pub minimap: MinimapConfig,
+
+    /// Maximum number of frames presented per second.
+    frame_rate: Option<u16>,
+
+    /// Throttle animations and disable cursor blinking while on battery power.
+    pub battery_saver: bool,
+
+    /// Appearance of windows opened with `--dropdown`.
+    pub dropdown: Dropdown,
+
+    /// Keep the window above all other windows.
+    pub always_on_top: bool,
+
+    /// Show the window on all workspaces.
+    pub sticky: bool,
+
+    /// Icons applications can switch the window to by name.
+    pub icons: HashMap<String, PathBuf>,
+
     /// Pixel padding.
     padding: Delta<u16>,
 
@@ -72,14 +112,27 @@ impl Default for WindowConfig {
             startup_mode: Default::default(),
             embed: Default::default(),
             decorations_theme_variant: Default::default(),
+            client_decorations: Default::default(),
             dynamic_padding: Default::default(),
             identity: Identity::default(),
             opacity: Default::default(),
             padding: Default::default(),
             dimensions: Default::default(),
             resize_increments: Default::default(),
+            reduce_motion: Default::default(),
+            command_status: true,
+            progress_bar: true,
+            minimap: Default::default(),
+            frame_rate: Default::default(),
+            battery_saver: true,
+            dropdown: Default::default(),
+            always_on_top: Default::default(),
+            sticky: Default::default(),
+            icons: Default::default(),
             #[cfg(target_os = "macos")]
             option_as_alt: Default::default(),
+            #[cfg(windows)]
+            backdrop: Default::default(),
         }
     }
 }
@@ -117,6 +170,13 @@ impl WindowConfig {
         }
     }
 
+    /// Minimum time between two presented frames, if the frame rate is limited.
+    #[inline]
+    pub fn frame_interval(&self) -> Option<Duration> {
+        let frame_rate = self.frame_rate.filter(|frame_rate| *frame_rate > 0)?;
+        Some(Duration::from_secs_f64(1. / f64::from(frame_rate)))
+    }
+
     #[inline]
     pub fn padding(&self, scale_factor: f32) -> (f32, f32) {
         let padding_x = (f32::from(self.padding.x) * scale_factor).floor();
@@ -140,12 +200,7 @@ impl WindowConfig {
 
     #[cfg(target_os = "macos")]
     pub fn option_as_alt(&self) -> WinitOptionAsAlt {
-        match self.option_as_alt {
-            OptionAsAlt::OnlyLeft => WinitOptionAsAlt::OnlyLeft,
-            OptionAsAlt::OnlyRight => WinitOptionAsAlt::OnlyRight,
-            OptionAsAlt::Both => WinitOptionAsAlt::Both,
-            OptionAsAlt::None => WinitOptionAsAlt::None,
-        }
+        self.option_as_alt.into()
     }
 }
 
@@ -156,11 +211,14 @@ pub struct Identity {
 
     /// Window class.
     pub class: Class,
+
+    /// Window icon.
+    pub icon: Option<PathBuf>,
 }
 
 impl Default for Identity {
     fn default() -> Self {
-        Self { title: DEFAULT_NAME.into(), class: Default::default() }
+        Self { title: DEFAULT_NAME.into(), class: Default::default(), icon: Default::default() }
     }
 }
 
@@ -185,6 +243,20 @@ pub enum Decorations {
     None,
 }
 
+/// Decorations drawn on Wayland when the compositor refuses server-side decorations.
+#[derive(ConfigDeserialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClientDecorations {
+    /// Frame drawn by the windowing toolkit.
+    #[default]
+    Toolkit,
+
+    /// Minimal title bar with window buttons, drawn by Alacritty.
+    TitleBar,
+
+    /// No decorations.
+    None,
+}
+
 /// Window Dimensions.
 ///
 /// Newtype to avoid passing values incorrectly.
@@ -197,6 +269,45 @@ pub struct Dimensions {
     pub lines: usize,
 }
 
+/// Dropdown window docked to the top of the screen.
+#[derive(ConfigDeserialize, Debug, Copy, Clone, PartialEq)]
+pub struct Dropdown {
+    /// Height of the window relative to the height of the monitor.
+    pub height: Percentage,
+
+    /// Duration of the slide animation in milliseconds.
+    duration: u16,
+}
+
+impl Default for Dropdown {
+    fn default() -> Self {
+        Self { height: Percentage::new(0.4), duration: 150 }
+    }
+}
+
+impl Dropdown {
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(u64::from(self.duration))
+    }
+}
+
+/// Overview of the scrollback drawn over the right edge of the terminal.
+#[derive(ConfigDeserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MinimapConfig {
+    /// Show the minimap.
+    pub enabled: bool,
+
+    /// Width of the minimap in logical pixels.
+    pub width: u16,
+}
+
+impl Default for MinimapConfig {
+    fn default() -> Self {
+        Self { enabled: false, width: 80 }
+    }
+}
+
 /// Window class hint.
 #[derive(SerdeReplace, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct Class {
@@ -274,7 +385,6 @@ impl<'de> Deserialize<'de> for Class {
     }
 }
 
-#[cfg(target_os = "macos")]
 #[derive(ConfigDeserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OptionAsAlt {
     /// The left `Option` key is treated as `Alt`.
@@ -290,3 +400,33 @@ pub enum OptionAsAlt {
     #[default]
     None,
 }
+
+/// Material of the DWM system backdrop.
+#[cfg(windows)]
+#[derive(ConfigDeserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backdrop {
+    /// Plain transparency without a backdrop.
+    #[default]
+    None,
+
+    /// Wallpaper tinted backdrop of main windows.
+    Mica,
+
+    /// Blurred backdrop of transient windows.
+    Acrylic,
+
+    /// Wallpaper tinted backdrop of tabbed windows.
+    Tabbed,
+}
+
+#[cfg(target_os = "macos")]
+impl From<OptionAsAlt> for WinitOptionAsAlt {
+    fn from(option_as_alt: OptionAsAlt) -> Self {
+        match option_as_alt {
+            OptionAsAlt::OnlyLeft => WinitOptionAsAlt::OnlyLeft,
+            OptionAsAlt::OnlyRight => WinitOptionAsAlt::OnlyRight,
+            OptionAsAlt::Both => WinitOptionAsAlt::Both,
+            OptionAsAlt::None => WinitOptionAsAlt::None,
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Detection of the system's high contrast setting.
+
+use std::time::Duration;
+
+use winit::event_loop::EventLoopProxy;
+
+use alacritty_terminal::thread;
+
+use crate::event::{Event, EventType};
+
+/// Interval between checks of the high contrast setting.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Watch the high contrast setting, notifying the event loop when it's toggled.
+pub fn watch(event_proxy: EventLoopProxy<Event>) {
+    thread::spawn_named("contrast monitor", move || {
+        let mut was_enabled = false;
+
+        loop {
+            let enabled = high_contrast();
+            if enabled != was_enabled {
+                was_enabled = enabled;
+
+                let event = Event::new(EventType::HighContrastChanged(enabled), None);
+                if event_proxy.send_event(event).is_err() {
+                    break;
+                }
+            }
+
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// Check if the system asks for high contrast.
+///
+/// This reads the `contrast` appearance setting of the XDG desktop portal.
+#[cfg(all(feature = "dbus", not(any(target_os = "macos", windows))))]
+fn high_contrast() -> bool {
+    use once_cell::sync::OnceCell;
+    use zbus::blocking::Connection;
+    use zbus::zvariant::{OwnedValue, Value};
+
+    static CONNECTION: OnceCell<Option<Connection>> = OnceCell::new();
+
+    let connection = match CONNECTION.get_or_init(|| Connection::session().ok()) {
+        Some(connection) => connection,
+        None => return false,
+    };
+
+    let reply = connection.call_method(
+        Some("org.freedesktop.portal.Desktop"),
+        "/org/freedesktop/portal/desktop",
+        Some("org.freedesktop.portal.Settings"),
+        "Read",
+        &("org.freedesktop.appearance", "contrast"),
+    );
+    let value = match reply.and_then(|reply| reply.body::<OwnedValue>()) {
+        Ok(value) => value,
+        Err(_) => return false,
+    };
+
+    // The setting is wrapped in an additional variant, with `1` requesting high contrast.
+    match &*value {
+        Value::Value(value) => matches!(**value, Value::U32(1)),
+        value => matches!(value, Value::U32(1)),
+    }
+}
+
+/// Check if the system asks for high contrast.
+#[cfg(target_os = "macos")]
+fn high_contrast() -> bool {
+    use objc::runtime::{Object, BOOL, NO};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let workspace: *mut Object = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let increase_contrast: BOOL =
+            msg_send![workspace, accessibilityDisplayShouldIncreaseContrast];
+        increase_contrast != NO
+    }
+}
+
+/// Check if the system asks for high contrast.
+#[cfg(windows)]
+fn high_contrast() -> bool {
+    use std::mem;
+
+    use windows_sys::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{SystemParametersInfoW, SPI_GETHIGHCONTRAST};
+
+    let mut high_contrast: HIGHCONTRASTW = unsafe { mem::zeroed() };
+    high_contrast.cbSize = mem::size_of::<HIGHCONTRASTW>() as u32;
+
+    let result = unsafe {
+        SystemParametersInfoW(
+            SPI_GETHIGHCONTRAST,
+            high_contrast.cbSize,
+            (&mut high_contrast as *mut HIGHCONTRASTW).cast(),
+            0,
+        )
+    };
+
+    result != 0 && high_contrast.dwFlags & HCF_HIGHCONTRASTON != 0
+}
+
+/// Check if the system asks for high contrast.
+#[cfg(not(any(feature = "dbus", target_os = "macos", windows)))]
+fn high_contrast() -> bool {
+    false
+}
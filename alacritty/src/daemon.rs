@@ -1,7 +1,7 @@
 use std::ffi::OsStr;
 #[cfg(not(any(target_os = "macos", windows)))]
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 use std::process::{Command, Stdio};
@@ -17,6 +17,8 @@ use {
 
 #[cfg(not(windows))]
 use libc::pid_t;
+
+use alacritty_terminal::thread;
 #[cfg(windows)]
 use windows_sys::Win32::System::Threading::{CREATE_NEW_PROCESS_GROUP, CREATE_NO_WINDOW};
 
@@ -25,7 +27,7 @@ use crate::macos;
 
 /// Start a new process in the background.
 #[cfg(windows)]
-pub fn spawn_daemon<I, S>(program: &str, args: I) -> io::Result<()>
+pub fn spawn_daemon<I, S>(program: &str, args: I, envs: &[(&str, String)]) -> io::Result<()>
 where
     I: IntoIterator<Item = S> + Copy,
     S: AsRef<OsStr>,
@@ -36,6 +38,7 @@ where
     // console window.
     Command::new(program)
         .args(args)
+        .envs(envs.iter().cloned())
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::null())
@@ -49,6 +52,7 @@ where
 pub fn spawn_daemon<I, S>(
     program: &str,
     args: I,
+    envs: &[(&str, String)],
     master_fd: RawFd,
     shell_pid: u32,
 ) -> io::Result<()>
@@ -58,6 +62,7 @@ where
 {
     let mut command = Command::new(program);
     command.args(args).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    command.envs(envs.iter().cloned());
     if let Ok(cwd) = foreground_process_path(master_fd, shell_pid) {
         command.current_dir(cwd);
     }
@@ -82,6 +87,37 @@ where
     }
 }
 
+/// Start a new process in the background, writing `input` to its stdin.
+pub fn spawn_with_input<I, S>(
+    program: &str,
+    args: I,
+    envs: &[(&str, String)],
+    input: String,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = S> + Copy,
+    S: AsRef<OsStr>,
+{
+    let mut command = Command::new(program);
+    command.args(args).envs(envs.iter().cloned());
+    command.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null());
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NEW_PROCESS_GROUP | CREATE_NO_WINDOW);
+
+    let mut child = command.spawn()?;
+    let stdin = child.stdin.take();
+
+    // Write the input without blocking, then reap the child once it's done.
+    thread::spawn_named("daemon input", move || {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+        let _ = child.wait();
+    });
+
+    Ok(())
+}
+
 /// Get working directory of controlling process.
 #[cfg(not(windows))]
 pub fn foreground_process_path(
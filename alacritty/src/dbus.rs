@@ -0,0 +1,298 @@
+//! D-Bus interface exposing Alacritty's IPC on the session bus.
+//!
+//! Every Alacritty instance is reachable as `org.alacritty.Alacritty.Pid<PID>`, the first instance
+//! additionally owns `org.alacritty.Alacritty`. Methods are forwarded to the same handler as
+//! messages received over the IPC socket.
+
+use std::path::PathBuf;
+use std::process;
+
+use log::{info, warn};
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use serde::Serialize;
+use winit::event_loop::EventLoopProxy;
+use winit::window::WindowId;
+use zbus::blocking::{Connection, ConnectionBuilder};
+use zbus::names::BusName;
+use zbus::zvariant::DynamicType;
+use zbus::{dbus_interface, fdo};
+
+use alacritty_terminal::thread;
+
+use crate::cli::{
+    IpcBroadcast, IpcConfig, IpcDumpHistory, IpcGetState, IpcGetText, IpcLayout, IpcMovePane,
+    IpcScreenshot, IpcSearch, IpcSendText, IpcStats, IpcToggle, SocketMessage, TerminalOptions,
+    WindowIdentity, WindowOptions,
+};
+use crate::event::Event;
+use crate::ipc::{self, SocketReply};
+use crate::scripting::ScriptEvent;
+
+/// Well-known bus name shared by all instances.
+const BUS_NAME: &str = "org.alacritty.Alacritty";
+
+/// Path of the Alacritty object.
+const OBJECT_PATH: &str = "/org/alacritty/Alacritty";
+
+/// Name of the Alacritty interface.
+const INTERFACE: &str = "org.alacritty.Alacritty1";
+
+/// Session bus connection, used for emitting signals.
+static CONNECTION: OnceCell<Connection> = OnceCell::new();
+
+/// Connect to the session bus and register the Alacritty interface.
+pub fn spawn_dbus_service(event_proxy: EventLoopProxy<Event>) {
+    // Connect in the background, to avoid delaying startup when the bus is unresponsive.
+    thread::spawn_named("dbus service", move || {
+        let interface = Alacritty { event_proxy: Mutex::new(event_proxy) };
+        let connection = match connect(interface) {
+            Ok(connection) => connection,
+            Err(err) => {
+                warn!("Unable to register D-Bus interface: {}", err);
+                return;
+            },
+        };
+
+        // Only one instance can own the shared name, so failing to acquire it is expected.
+        if let Err(err) = connection.request_name(BUS_NAME) {
+            info!("Unable to acquire D-Bus name {}: {}", BUS_NAME, err);
+        }
+
+        let _ = CONNECTION.set(connection);
+    });
+}
+
+/// Emit the D-Bus signal for a terminal event.
+pub fn emit_signal(window_id: WindowId, event: &ScriptEvent) {
+    let connection = match CONNECTION.get() {
+        Some(connection) => connection,
+        None => return,
+    };
+
+    let window_id = u64::from(window_id);
+    let result = match event {
+        ScriptEvent::Bell => emit(connection, "Bell", &(window_id,)),
+        ScriptEvent::Prompt => emit(connection, "Prompt", &(window_id,)),
+        ScriptEvent::CommandFinished(exit_code) => {
+            emit(connection, "CommandFinished", &(window_id, exit_code.unwrap_or(-1)))
+        },
+        ScriptEvent::Title(title) => emit(connection, "TitleChanged", &(window_id, title)),
+        ScriptEvent::Focus(focused) => emit(connection, "FocusChanged", &(window_id, *focused)),
+    };
+
+    if let Err(err) = result {
+        warn!("Unable to emit D-Bus signal: {}", err);
+    }
+}
+
+/// Open the session bus connection serving the Alacritty interface.
+fn connect(interface: Alacritty) -> zbus::Result<Connection> {
+    let name = format!("{}.Pid{}", BUS_NAME, process::id());
+    ConnectionBuilder::session()?.name(name)?.serve_at(OBJECT_PATH, interface)?.build()
+}
+
+/// Emit a signal of the Alacritty interface.
+fn emit<B: Serialize + DynamicType>(
+    connection: &Connection,
+    name: &str,
+    body: &B,
+) -> zbus::Result<()> {
+    connection.emit_signal(None::<BusName<'_>>, OBJECT_PATH, INTERFACE, name, body)
+}
+
+/// Alacritty D-Bus interface.
+struct Alacritty {
+    event_proxy: Mutex<EventLoopProxy<Event>>,
+}
+
+impl Alacritty {
+    /// Handle a message which does not expect a reply.
+    fn send(&self, message: SocketMessage) -> fdo::Result<()> {
+        match self.handle(message) {
+            Some(SocketReply::Error(err)) => Err(fdo::Error::Failed(err)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Handle a message querying Alacritty's state.
+    ///
+    /// Structured replies are serialized as JSON, like the output of `alacritty msg`.
+    fn query(&self, message: SocketMessage) -> fdo::Result<String> {
+        let json = |value: Result<String, serde_json::Error>| {
+            value.map_err(|err| fdo::Error::Failed(err.to_string()))
+        };
+
+        match self.handle(message) {
+            Some(SocketReply::Text(text)) => Ok(text),
+            Some(SocketReply::State(state)) => json(serde_json::to_string(&state)),
+            Some(SocketReply::Windows(windows)) => json(serde_json::to_string(&windows)),
+            Some(SocketReply::Stats(stats)) => json(serde_json::to_string(&stats)),
+            Some(SocketReply::Error(err)) => Err(fdo::Error::Failed(err)),
+            None => Err(fdo::Error::Failed(String::from("no reply received"))),
+        }
+    }
+
+    /// Forward a message to the IPC handler.
+    fn handle(&self, message: SocketMessage) -> Option<SocketReply> {
+        let event_proxy = self.event_proxy.lock().clone();
+        ipc::handle_message(message, &event_proxy)
+    }
+}
+
+#[dbus_interface(name = "org.alacritty.Alacritty1")]
+impl Alacritty {
+    /// Create a new window.
+    fn create_window(
+        &self,
+        working_directory: String,
+        command: Vec<String>,
+        name: String,
+    ) -> fdo::Result<()> {
+        let working_directory = non_empty(working_directory).map(PathBuf::from);
+        let terminal_options = TerminalOptions { working_directory, command, ..Default::default() };
+        let window_identity = WindowIdentity { name: non_empty(name), ..Default::default() };
+        let options = WindowOptions { terminal_options, window_identity, ..Default::default() };
+        self.send(SocketMessage::CreateWindow(Box::new(options)))
+    }
+
+    /// Open the windows, tabs and panes described by a layout file.
+    fn open_layout(&self, path: String) -> fdo::Result<()> {
+        self.send(SocketMessage::Layout(IpcLayout { path: PathBuf::from(path) }))
+    }
+
+    /// Update the runtime config of a window.
+    fn set_config(&self, window: String, options: Vec<String>) -> fdo::Result<()> {
+        let window = non_empty(window);
+        self.send(SocketMessage::Config(IpcConfig { options, window, ..Default::default() }))
+    }
+
+    /// Reset the runtime config of a window.
+    fn reset_config(&self, window: String) -> fdo::Result<()> {
+        let window = non_empty(window);
+        self.send(SocketMessage::Config(IpcConfig { reset: true, window, ..Default::default() }))
+    }
+
+    /// Update the runtime config of a window's focused pane.
+    fn set_pane_config(&self, window: String, options: Vec<String>) -> fdo::Result<()> {
+        let window = non_empty(window);
+        let ipc_config = IpcConfig { options, window, pane: true, ..Default::default() };
+        self.send(SocketMessage::Config(ipc_config))
+    }
+
+    /// Reset the runtime config of a window's focused pane.
+    fn reset_pane_config(&self, window: String) -> fdo::Result<()> {
+        let window = non_empty(window);
+        let ipc_config = IpcConfig { reset: true, window, pane: true, ..Default::default() };
+        self.send(SocketMessage::Config(ipc_config))
+    }
+
+    /// Get the current value of a config option.
+    fn get_config(&self, window: String, option: String) -> fdo::Result<String> {
+        let window = non_empty(window);
+        let get = Some(option);
+        self.query(SocketMessage::Config(IpcConfig { get, window, ..Default::default() }))
+    }
+
+    /// Search the terminal for a regex.
+    fn search(
+        &self,
+        window: String,
+        regex: String,
+        backwards: bool,
+        select: bool,
+    ) -> fdo::Result<()> {
+        let window = non_empty(window);
+        let search = IpcSearch { regex, backwards, select, window, ..Default::default() };
+        self.send(SocketMessage::Search(search))
+    }
+
+    /// Write the scrollback history to a file.
+    fn dump_history(
+        &self,
+        window: String,
+        path: String,
+        ansi: bool,
+        timestamps: bool,
+    ) -> fdo::Result<()> {
+        let window = non_empty(window);
+        let path = PathBuf::from(path);
+        let dump = IpcDumpHistory { path, ansi, timestamps, window, ..Default::default() };
+        self.send(SocketMessage::DumpHistory(dump))
+    }
+
+    /// Write the next rendered frame to a PNG file.
+    fn screenshot(&self, window: String, path: String) -> fdo::Result<()> {
+        let window = non_empty(window);
+        let path = PathBuf::from(path);
+        let screenshot = IpcScreenshot { path, window, ..Default::default() };
+        self.send(SocketMessage::Screenshot(screenshot))
+    }
+
+    /// Send text to the terminal, as if it was typed or pasted.
+    fn send_text(&self, window: String, text: String, bracketed: bool) -> fdo::Result<()> {
+        let window = non_empty(window);
+        let text = Some(text);
+        let send_text = IpcSendText { text, bracketed, window, ..Default::default() };
+        self.send(SocketMessage::SendText(send_text))
+    }
+
+    /// Mirror input to all panes of a window or a group of windows.
+    fn broadcast(&self, window: String, panes: bool, group: String, off: bool) -> fdo::Result<()> {
+        let window = non_empty(window);
+        let group = non_empty(group);
+        let broadcast = IpcBroadcast { panes, group, off, window, ..Default::default() };
+        self.send(SocketMessage::Broadcast(broadcast))
+    }
+
+    /// Move the focused pane of a window into another window, or a new one without target.
+    fn move_pane(&self, window: String, target: String) -> fdo::Result<()> {
+        let window = non_empty(window);
+        let target = non_empty(target);
+        let move_pane = IpcMovePane { target, window, ..Default::default() };
+        self.send(SocketMessage::MovePane(move_pane))
+    }
+
+    /// Show or hide dropdown windows, opening one if none exists.
+    fn toggle(&self, window: String) -> fdo::Result<()> {
+        let window = non_empty(window);
+        self.send(SocketMessage::Toggle(IpcToggle { window, ..Default::default() }))
+    }
+
+    /// Get the text of the active screen, including `history` lines of scrollback.
+    fn get_text(&self, window: String, history: u32) -> fdo::Result<String> {
+        let window = non_empty(window);
+        let history = Some(history as usize);
+        let get_text = IpcGetText { history, window, ..Default::default() };
+        self.query(SocketMessage::GetText(get_text))
+    }
+
+    /// Get the selected text.
+    fn get_selection(&self, window: String) -> fdo::Result<String> {
+        let window = non_empty(window);
+        let get_text = IpcGetText { selection: true, window, ..Default::default() };
+        self.query(SocketMessage::GetText(get_text))
+    }
+
+    /// Get the terminal state as JSON.
+    fn get_state(&self, window: String) -> fdo::Result<String> {
+        let window = non_empty(window);
+        self.query(SocketMessage::GetState(IpcGetState { window, ..Default::default() }))
+    }
+
+    /// Get the performance metrics of a window as JSON.
+    fn get_stats(&self, window: String) -> fdo::Result<String> {
+        let window = non_empty(window);
+        self.query(SocketMessage::Stats(IpcStats { window, ..Default::default() }))
+    }
+
+    /// Get all windows as JSON.
+    fn list_windows(&self) -> fdo::Result<String> {
+        self.query(SocketMessage::ListWindows)
+    }
+}
+
+/// Convert empty D-Bus string arguments to `None`.
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
@@ -0,0 +1,346 @@
+//! Screen reader support through AccessKit.
+//!
+//! The visible lines of the focused terminal are exposed as one text node each, with the cursor
+//! or selection reported as text selection of the terminal node.
+
+use accesskit::{
+    Action, ActionHandler, ActionRequest, NodeBuilder, NodeClassSet, NodeId, Rect, Role,
+    TextPosition, TextSelection, Tree, TreeUpdate,
+};
+use winit::window::raw_window_handle::RawWindowHandle;
+
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::{Dimensions, Row};
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::selection::SelectionRange;
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::{Term, TermMode};
+
+use crate::display::SizeInfo;
+
+/// Node of the window.
+const WINDOW_ID: NodeId = NodeId(0);
+
+/// Node of the terminal, parent of all lines.
+const TERMINAL_ID: NodeId = NodeId(1);
+
+/// Node of the first visible line.
+const FIRST_LINE_ID: u64 = 2;
+
+#[cfg(not(any(target_os = "macos", windows)))]
+type Adapter = accesskit_unix::Adapter;
+#[cfg(windows)]
+type Adapter = accesskit_windows::SubclassingAdapter;
+#[cfg(target_os = "macos")]
+type Adapter = accesskit_macos::SubclassingAdapter;
+
+/// Accessible text surface of a window.
+pub struct Accessibility {
+    adapter: Adapter,
+    classes: NodeClassSet,
+
+    /// Text of the lines last sent to the adapter.
+    lines: Vec<String>,
+
+    /// Cursor or selection last sent to the adapter.
+    text_selection: Option<TextSelection>,
+
+    /// Terminal dimensions the lines were sent with.
+    size_info: Option<SizeInfo>,
+}
+
+impl Accessibility {
+    /// Expose a window to assistive technologies.
+    ///
+    /// This must be called before the window is shown for the first time. Returns `None` if no
+    /// accessibility service is available.
+    pub fn new(raw_window_handle: RawWindowHandle) -> Option<Self> {
+        let adapter = Self::create_adapter(raw_window_handle)?;
+        Some(Self {
+            adapter,
+            classes: NodeClassSet::new(),
+            lines: Vec::new(),
+            text_selection: None,
+            size_info: None,
+        })
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    fn create_adapter(_raw_window_handle: RawWindowHandle) -> Option<Adapter> {
+        Adapter::new(initial_tree, false, Box::new(NoopActionHandler))
+    }
+
+    #[cfg(windows)]
+    fn create_adapter(raw_window_handle: RawWindowHandle) -> Option<Adapter> {
+        let hwnd = match raw_window_handle {
+            RawWindowHandle::Win32(handle) => accesskit_windows::HWND(handle.hwnd as isize),
+            _ => return None,
+        };
+        Some(Adapter::new(hwnd, initial_tree, Box::new(NoopActionHandler)))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn create_adapter(raw_window_handle: RawWindowHandle) -> Option<Adapter> {
+        match raw_window_handle {
+            RawWindowHandle::AppKit(handle) => unsafe {
+                Some(Adapter::new(handle.ns_view, initial_tree, Box::new(NoopActionHandler)))
+            },
+            _ => None,
+        }
+    }
+
+    /// Update the window focus reported to assistive technologies.
+    pub fn set_focused(&self, focused: bool) {
+        #[cfg(not(any(target_os = "macos", windows)))]
+        self.adapter.update_window_focus_state(focused);
+        #[cfg(target_os = "macos")]
+        if let Some(events) = self.adapter.update_view_focus_state(focused) {
+            events.raise();
+        }
+
+        // Windows tracks the focus of subclassed windows on its own.
+        #[cfg(windows)]
+        let _ = focused;
+    }
+
+    /// Send the lines which changed since the last update to the adapter.
+    pub fn update<T: EventListener>(
+        &mut self,
+        terminal: &Term<T>,
+        size_info: &SizeInfo,
+        selection: Option<SelectionRange>,
+    ) {
+        let grid = terminal.grid();
+        let display_offset = grid.display_offset() as i32;
+        let resized = self.size_info != Some(*size_info);
+
+        let mut nodes = Vec::new();
+        let mut lines = Vec::with_capacity(size_info.screen_lines());
+        for viewport_line in 0..size_info.screen_lines() {
+            let line = LineText::new(&grid[Line(viewport_line as i32 - display_offset)]);
+
+            if resized || self.lines.get(viewport_line) != Some(&line.text) {
+                let node = line.node(viewport_line, size_info).build(&mut self.classes);
+                nodes.push((line_id(viewport_line), node));
+            }
+
+            lines.push(line);
+        }
+
+        // Report the selection, falling back to the cursor when nothing is selected.
+        let last_line = lines.len() - 1;
+        let viewport_point = |point: Point| match point.line.0 + display_offset {
+            line if line < 0 => (0, 0),
+            line if line as usize > last_line => (last_line, lines[last_line].columns.len()),
+            line => (line as usize, lines[line as usize].character_index(point.column)),
+        };
+        let text_selection = match selection {
+            Some(range) => {
+                let (start_line, start_index) = viewport_point(range.start);
+                let (end_line, end_index) = viewport_point(range.end);
+
+                // Selections include their last character.
+                let end_index = (end_index + 1).min(lines[end_line].columns.len());
+
+                Some(TextSelection {
+                    anchor: TextPosition {
+                        node: line_id(start_line),
+                        character_index: start_index,
+                    },
+                    focus: TextPosition { node: line_id(end_line), character_index: end_index },
+                })
+            },
+            None if terminal.mode().contains(TermMode::SHOW_CURSOR) => {
+                let cursor = grid.cursor.point;
+                let line = (cursor.line.0 + display_offset) as usize;
+                lines.get(line).map(|text| {
+                    let character_index = text.character_index(cursor.column);
+                    let position = TextPosition { node: line_id(line), character_index };
+                    TextSelection { anchor: position, focus: position }
+                })
+            },
+            None => None,
+        };
+
+        if resized || !nodes.is_empty() || text_selection != self.text_selection {
+            let mut node = NodeBuilder::new(Role::Terminal);
+            node.set_children((0..lines.len()).map(line_id).collect::<Vec<_>>());
+            node.set_bounds(Rect::new(0., 0., size_info.width() as f64, size_info.height() as f64));
+            node.add_action(Action::Focus);
+            node.set_read_only();
+            if let Some(text_selection) = text_selection {
+                node.set_text_selection(text_selection);
+            }
+            nodes.push((TERMINAL_ID, node.build(&mut self.classes)));
+        }
+
+        self.lines = lines.into_iter().map(|line| line.text).collect();
+        self.text_selection = text_selection;
+        self.size_info = Some(*size_info);
+
+        if nodes.is_empty() {
+            return;
+        }
+
+        let update = TreeUpdate { nodes, tree: None, focus: TERMINAL_ID };
+
+        #[cfg(not(any(target_os = "macos", windows)))]
+        self.adapter.update(update);
+        #[cfg(any(target_os = "macos", windows))]
+        self.adapter.update(update).raise();
+    }
+}
+
+/// Text of a terminal line, split into characters.
+#[derive(Default, Debug, PartialEq)]
+struct LineText {
+    text: String,
+
+    /// Number of UTF-8 bytes of each character.
+    character_lengths: Vec<u8>,
+
+    /// First column of each character.
+    columns: Vec<usize>,
+
+    /// Number of characters of each word, including trailing whitespace.
+    word_lengths: Vec<u8>,
+}
+
+impl LineText {
+    fn new(row: &Row<Cell>) -> Self {
+        let mut line = Self::default();
+        let mut word_length = 0u8;
+        let mut in_whitespace = false;
+
+        for column in 0..row.len() {
+            let cell = &row[Column(column)];
+            if cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER) {
+                continue;
+            }
+
+            // Start a new word after whitespace.
+            let whitespace = cell.c.is_whitespace();
+            if (in_whitespace && !whitespace) || word_length == u8::MAX {
+                line.word_lengths.push(word_length);
+                word_length = 0;
+            }
+            in_whitespace = whitespace;
+            word_length += 1;
+
+            // Combine zerowidth characters with their base character.
+            let start = line.text.len();
+            line.text.push(cell.c);
+            line.text.extend(cell.zerowidth().into_iter().flatten());
+
+            line.character_lengths.push((line.text.len() - start).min(u8::MAX as usize) as u8);
+            line.columns.push(column);
+        }
+
+        if word_length > 0 {
+            line.word_lengths.push(word_length);
+        }
+
+        line
+    }
+
+    /// Index of the character covering a column.
+    fn character_index(&self, column: Column) -> usize {
+        self.columns.partition_point(|&start| start <= column.0).saturating_sub(1)
+    }
+
+    /// Text node of the line.
+    fn node(&self, viewport_line: usize, size_info: &SizeInfo) -> NodeBuilder {
+        let cell_width = size_info.cell_width();
+        let y = size_info.padding_y() + viewport_line as f32 * size_info.cell_height();
+        let x = size_info.padding_x();
+
+        // Character positions are relative to the line.
+        let mut positions = Vec::with_capacity(self.columns.len());
+        let mut widths = Vec::with_capacity(self.columns.len());
+        for (index, column) in self.columns.iter().enumerate() {
+            let next = self.columns.get(index + 1).copied().unwrap_or(size_info.columns());
+            positions.push(*column as f32 * cell_width);
+            widths.push((next - column) as f32 * cell_width);
+        }
+
+        let mut node = NodeBuilder::new(Role::InlineTextBox);
+        node.set_bounds(Rect::new(
+            x as f64,
+            y as f64,
+            (x + size_info.columns() as f32 * cell_width) as f64,
+            (y + size_info.cell_height()) as f64,
+        ));
+        node.set_value(self.text.as_str());
+        node.set_character_lengths(self.character_lengths.as_slice());
+        node.set_word_lengths(self.word_lengths.as_slice());
+        node.set_character_positions(positions);
+        node.set_character_widths(widths);
+        node
+    }
+}
+
+/// Node of a visible line.
+fn line_id(viewport_line: usize) -> NodeId {
+    NodeId(FIRST_LINE_ID + viewport_line as u64)
+}
+
+/// Tree of a window before the terminal content is known.
+fn initial_tree() -> TreeUpdate {
+    let mut classes = NodeClassSet::new();
+
+    let mut window = NodeBuilder::new(Role::Window);
+    window.set_name("Alacritty");
+    window.set_children(vec![TERMINAL_ID]);
+
+    let terminal = NodeBuilder::new(Role::Terminal);
+
+    let mut tree = Tree::new(WINDOW_ID);
+    tree.app_name = Some(String::from("Alacritty"));
+    tree.toolkit_name = Some(String::from("Alacritty"));
+    tree.toolkit_version = Some(String::from(env!("CARGO_PKG_VERSION")));
+
+    TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, window.build(&mut classes)),
+            (TERMINAL_ID, terminal.build(&mut classes)),
+        ],
+        tree: Some(tree),
+        focus: TERMINAL_ID,
+    }
+}
+
+/// Assistive technologies can't modify the terminal.
+struct NoopActionHandler;
+
+impl ActionHandler for NoopActionHandler {
+    fn do_action(&mut self, _request: ActionRequest) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_text_characters() {
+        let mut row: Row<Cell> = Row::new(6);
+        row[Column(0)].c = 'a';
+        row[Column(1)].c = '\u{4e2d}';
+        row[Column(1)].flags.insert(Flags::WIDE_CHAR);
+        row[Column(2)].flags.insert(Flags::WIDE_CHAR_SPACER);
+        row[Column(3)].c = 'e';
+        row[Column(3)].push_zerowidth('\u{301}');
+        row[Column(5)].c = 'b';
+
+        let line = LineText::new(&row);
+
+        assert_eq!(line.text, "a\u{4e2d}e\u{301} b");
+        assert_eq!(line.character_lengths, [1, 3, 3, 1, 1]);
+        assert_eq!(line.columns, [0, 1, 3, 4, 5]);
+        assert_eq!(line.word_lengths, [4, 1]);
+
+        assert_eq!(line.character_index(Column(1)), 1);
+        assert_eq!(line.character_index(Column(2)), 1);
+        assert_eq!(line.character_index(Column(3)), 2);
+        assert_eq!(line.character_index(Column(5)), 4);
+    }
+}
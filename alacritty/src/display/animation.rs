@@ -0,0 +1,413 @@
+//! Time based animations of the display.
+
+use std::time::{Duration, Instant};
+
+use crossfont::Metrics;
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term;
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::color::{Rgb, COUNT};
+use alacritty_terminal::term::search::Match;
+
+use crate::display::color::List;
+use crate::display::SizeInfo;
+use crate::renderer::rects::{RenderLine, RenderRect};
+
+/// Duration of the viewport scroll and highlight when jumping.
+const JUMP_ANIMATION_DURATION: Duration = Duration::from_millis(200);
+
+/// Opacity of the jump target's highlight at the start of the animation.
+const JUMP_HIGHLIGHT_ALPHA: f64 = 0.5;
+
+/// Duration of the underline sweeping in below a hovered hint.
+const HOVER_SWEEP_DURATION: Duration = Duration::from_millis(150);
+
+/// Duration of the underline fading out after a hint is no longer hovered.
+const HOVER_FADE_DURATION: Duration = Duration::from_millis(200);
+
+/// Clock for an animation progressing over a fixed duration.
+#[derive(Debug, Copy, Clone)]
+pub struct Animation {
+    start: Instant,
+    duration: Duration,
+}
+
+impl Animation {
+    pub fn new(duration: Duration) -> Self {
+        Self { start: Instant::now(), duration }
+    }
+
+    /// Linear progress of the animation from 0.0 to 1.0.
+    pub fn progress(&self) -> f64 {
+        if self.duration.is_zero() {
+            return 1.;
+        }
+
+        (self.start.elapsed().as_secs_f64() / self.duration.as_secs_f64()).min(1.)
+    }
+}
+
+/// Animated viewport scroll and highlight when jumping between positions.
+#[derive(Default)]
+pub struct JumpAnimation {
+    animation: Option<Animation>,
+
+    /// Display offset before the jump.
+    origin: usize,
+
+    /// Display offset after the jump.
+    target: usize,
+
+    /// Cells highlighted at the jump's target.
+    highlight: Option<Match>,
+
+    /// Progress of the animation at its last step.
+    progress: f64,
+
+    /// Display offset of the viewport at the animation's last step.
+    display_offset: usize,
+}
+
+impl JumpAnimation {
+    /// Start animating a jump between two display offsets.
+    pub fn start(&mut self, origin: usize, target: usize, highlight: Match) {
+        self.animation = Some(Animation::new(JUMP_ANIMATION_DURATION));
+        self.highlight = Some(highlight);
+        self.origin = origin;
+        self.target = target;
+        self.progress = 0.;
+        self.display_offset = origin;
+    }
+
+    /// Check if a jump is currently animated.
+    pub fn active(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Advance the animation, returning the display offset the viewport should scroll to.
+    ///
+    /// The animation is stopped once the target's display offset has been returned.
+    pub fn advance(&mut self) -> Option<usize> {
+        let progress = self.animation?.progress();
+        if progress >= 1. {
+            self.animation = None;
+        }
+
+        let eased = cubic_bezier(0.215, 0.61, 0.355, 1.0, progress);
+        let distance = self.target as f64 - self.origin as f64;
+        self.display_offset = (self.origin as f64 + distance * eased).round() as usize;
+        self.progress = progress;
+
+        Some(self.display_offset)
+    }
+
+    /// State of the animation at its last step.
+    pub fn frame(&self) -> Option<JumpFrame> {
+        self.animation?;
+
+        Some(JumpFrame {
+            display_offset: self.display_offset,
+            highlight: self.highlight.clone()?,
+            alpha: (JUMP_HIGHLIGHT_ALPHA * (1. - self.progress)) as f32,
+        })
+    }
+}
+
+/// Single frame of a jump animation.
+pub struct JumpFrame {
+    /// Display offset of the viewport.
+    display_offset: usize,
+
+    /// Cells highlighted at the jump's target.
+    highlight: Match,
+
+    /// Opacity of the highlight.
+    alpha: f32,
+}
+
+impl JumpFrame {
+    /// Rectangles highlighting the jump's target in the viewport.
+    pub fn highlight_rects(&self, size_info: &SizeInfo, color: Rgb) -> Vec<RenderRect> {
+        let (start, end) = (self.highlight.start(), self.highlight.end());
+
+        (start.line.0..=end.line.0)
+            .filter_map(|line| {
+                let first = if line == start.line.0 { start.column } else { Column(0) };
+                let last = if line == end.line.0 { end.column } else { size_info.last_column() };
+
+                let point = Point::new(Line(line), first);
+                let viewport_point = term::point_to_viewport(self.display_offset, point)
+                    .filter(|point| point.line < size_info.screen_lines())?;
+
+                let viewport_line = viewport_point.line as f32;
+                let x = size_info.padding_x() + first.0 as f32 * size_info.cell_width();
+                let y = size_info.padding_y() + viewport_line * size_info.cell_height();
+                let width = (last.0 + 1 - first.0) as f32 * size_info.cell_width();
+
+                Some(RenderRect::new(x, y, width, size_info.cell_height(), color, self.alpha))
+            })
+            .collect()
+    }
+}
+
+/// Animated underline of the hint hovered by the mouse.
+#[derive(Default)]
+pub struct HoverAnimation {
+    /// Hovered hint whose underline is sweeping in from the left.
+    sweep: Option<(Match, Animation)>,
+
+    /// Previously hovered hint and the width of its underline when it started fading out.
+    fade: Option<(Match, Animation, f64)>,
+
+    /// Underline color of the hovered hint.
+    color: Rgb,
+}
+
+impl HoverAnimation {
+    /// Sweep in the underline of the `hovered` hint, fading out the `previous` one.
+    pub fn hover(&mut self, previous: Option<&Match>, hovered: Option<&Match>) {
+        let sweep = self.sweep.take();
+        self.fade = previous.map(|previous| {
+            let width = sweep.map_or(1., |(_, animation)| sweep_width(animation.progress()));
+            (previous.clone(), Animation::new(HOVER_FADE_DURATION), width)
+        });
+
+        self.sweep = hovered.map(|hovered| (hovered.clone(), Animation::new(HOVER_SWEEP_DURATION)));
+    }
+
+    /// Check if any underline is currently animated.
+    pub fn active(&self) -> bool {
+        self.sweep.is_some() || self.fade.is_some()
+    }
+
+    /// Check if the underline of the hovered hint is still sweeping in.
+    pub fn sweeping(&self) -> bool {
+        self.sweep.is_some()
+    }
+
+    /// Set the underline color of the hovered hint.
+    pub fn set_color(&mut self, color: Rgb) {
+        self.color = color;
+    }
+
+    /// Underline rects for the next frame.
+    ///
+    /// Underlines are dropped once their final frame has been returned, or when the `hovered`
+    /// hint no longer matches the animated one.
+    pub fn rects(
+        &mut self,
+        hovered: Option<&Match>,
+        display_offset: usize,
+        metrics: &Metrics,
+        size_info: &SizeInfo,
+    ) -> Vec<RenderRect> {
+        let mut rects = Vec::new();
+
+        if self.sweep.as_ref().map_or(false, |(bounds, _)| Some(bounds) != hovered) {
+            self.sweep = None;
+        }
+
+        if let Some((bounds, animation)) = &self.sweep {
+            let progress = animation.progress();
+            let width = sweep_width(progress) as f32;
+            let line_rects =
+                underline_rects(bounds, display_offset, metrics, size_info, self.color);
+            rects.extend(line_rects.map(|rect| RenderRect { width: rect.width * width, ..rect }));
+
+            if progress >= 1. {
+                self.sweep = None;
+            }
+        }
+
+        if let Some((bounds, animation, width)) = &self.fade {
+            let progress = animation.progress();
+            let alpha = (1. - progress) as f32;
+            let width = *width as f32;
+            let line_rects =
+                underline_rects(bounds, display_offset, metrics, size_info, self.color);
+            rects.extend(line_rects.map(|rect| RenderRect {
+                width: rect.width * width,
+                alpha,
+                ..rect
+            }));
+
+            if progress >= 1. {
+                self.fade = None;
+            }
+        }
+
+        rects
+    }
+}
+
+/// Eased fraction of the underline's width at a point of the sweep.
+fn sweep_width(progress: f64) -> f64 {
+    cubic_bezier(0., 0.42, 0.58, 1., progress)
+}
+
+/// Full width underline rects of all visible lines of a match.
+fn underline_rects<'a>(
+    bounds: &'a Match,
+    display_offset: usize,
+    metrics: &'a Metrics,
+    size_info: &'a SizeInfo,
+    color: Rgb,
+) -> impl Iterator<Item = RenderRect> + 'a {
+    let (start, end) = (bounds.start(), bounds.end());
+
+    (start.line.0..=end.line.0).flat_map(move |line| {
+        let first = if line == start.line.0 { start.column } else { Column(0) };
+        let last = if line == end.line.0 { end.column } else { size_info.last_column() };
+
+        let mut rects = Vec::new();
+        let start = term::point_to_viewport(display_offset, Point::new(Line(line), first));
+        if let Some(start) = start.filter(|point| point.line < size_info.screen_lines()) {
+            let line = RenderLine { start, end: Point::new(start.line, last), color };
+            line.rects(&mut rects, Flags::UNDERLINE, metrics, size_info);
+        }
+        rects
+    })
+}
+
+/// Transition between color palettes, when they're changed by escape sequences or the config.
+#[derive(Default)]
+pub struct PaletteAnimation {
+    animation: Option<Animation>,
+
+    /// Palette at the start of the transition.
+    origin: Option<List>,
+
+    /// Palette at the end of the transition.
+    target: Option<List>,
+
+    /// Palette of the current frame.
+    current: Option<List>,
+}
+
+impl PaletteAnimation {
+    /// Palette of the next frame, starting a transition to `target` whenever it changes.
+    pub fn frame(&mut self, target: List, duration: Duration) -> &List {
+        if self.target != Some(target) {
+            self.target = Some(target);
+            self.origin = self.current;
+            self.animation =
+                (!duration.is_zero() && self.origin.is_some()).then(|| Animation::new(duration));
+        }
+
+        let progress = self.animation.map_or(1., |animation| animation.progress());
+        if progress >= 1. {
+            self.animation = None;
+        }
+
+        let current = match self.origin.filter(|_| progress < 1.) {
+            Some(origin) => {
+                let eased = cubic_bezier(0., 0.42, 0.58, 1., progress) as f32;
+                let mut current = origin;
+                for i in 0..COUNT {
+                    current[i] = origin[i] * (1. - eased) + target[i] * eased;
+                }
+                current
+            },
+            None => target,
+        };
+
+        self.current.insert(current)
+    }
+
+    /// Palette of the last frame.
+    pub fn colors(&self) -> Option<&List> {
+        self.current.as_ref()
+    }
+
+    /// Check if a transition is currently animated.
+    pub fn active(&self) -> bool {
+        self.animation.is_some()
+    }
+}
+
+/// One dimensional cubic bezier curve for easing animations.
+pub fn cubic_bezier(p0: f64, p1: f64, p2: f64, p3: f64, x: f64) -> f64 {
+    (1.0 - x).powi(3) * p0
+        + 3.0 * (1.0 - x).powi(2) * x * p1
+        + 3.0 * (1.0 - x) * x.powi(2) * p2
+        + x.powi(3) * p3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::ansi::NamedColor;
+
+    use crate::config::color::Colors;
+
+    fn bounds(line: i32) -> Match {
+        Point::new(Line(line), Column(2))..=Point::new(Line(line), Column(5))
+    }
+
+    #[test]
+    fn hover_fades_previous_hint() {
+        let mut animation = HoverAnimation::default();
+        animation.hover(None, Some(&bounds(0)));
+        assert!(animation.sweeping());
+        assert!(animation.fade.is_none());
+
+        animation.hover(Some(&bounds(0)), Some(&bounds(1)));
+        assert_eq!(animation.sweep.as_ref().map(|(bounds, _)| bounds), Some(&bounds(1)));
+        assert_eq!(animation.fade.as_ref().map(|(bounds, ..)| bounds), Some(&bounds(0)));
+
+        // Leaving a hint fades its fully drawn underline.
+        let mut animation = HoverAnimation::default();
+        animation.hover(Some(&bounds(0)), None);
+        assert!(!animation.sweeping());
+        assert_eq!(animation.fade.map(|(_, _, width)| width), Some(1.));
+    }
+
+    #[test]
+    fn sweep_follows_hovered_hint() {
+        let size_info = SizeInfo::new(100., 100., 10., 10., 0., 0., false);
+        let metrics = Metrics {
+            average_advance: 10.,
+            line_height: 10.,
+            descent: -2.,
+            underline_position: -1.,
+            underline_thickness: 1.,
+            strikeout_position: 4.,
+            strikeout_thickness: 1.,
+        };
+
+        let mut animation = HoverAnimation::default();
+        animation.hover(None, Some(&bounds(0)));
+        let rects = animation.rects(Some(&bounds(0)), 0, &metrics, &size_info);
+        assert_eq!(rects.len(), 1);
+        assert!(rects[0].width <= 40.);
+
+        // Sweeps of hints which are no longer hovered are dropped.
+        let rects = animation.rects(None, 0, &metrics, &size_info);
+        assert!(rects.is_empty());
+        assert!(!animation.active());
+    }
+
+    #[test]
+    fn palette_transition() {
+        let origin = List::from(&Colors::default());
+        let mut target = origin;
+        target[NamedColor::Background] = Rgb::new(0xff, 0xff, 0xff);
+
+        // The initial palette is used without transition.
+        let mut animation = PaletteAnimation::default();
+        animation.frame(origin, Duration::from_secs(60));
+        assert!(!animation.active());
+
+        // Changes are applied immediately without a duration.
+        assert!(animation.frame(target, Duration::ZERO) == &target);
+        assert!(!animation.active());
+
+        let colors = *animation.frame(origin, Duration::from_secs(60));
+        assert!(animation.active());
+        assert!(colors != origin);
+        assert_eq!(colors[NamedColor::Foreground], origin[NamedColor::Foreground]);
+    }
+}
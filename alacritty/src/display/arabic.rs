@@ -0,0 +1,205 @@
+//! Contextual shaping of Arabic text.
+//!
+//! Arabic letters are replaced by their isolated, final, initial or medial presentation forms,
+//! depending on the letters they're joined to. Every letter keeps its own cell, so shaping never
+//! changes the width of the text.
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Line, Point};
+use alacritty_terminal::term::{Term, TermMode};
+
+/// How a letter joins its neighbors.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Joining {
+    /// Joins the letters on both sides.
+    Dual,
+    /// Only joins the preceding letter.
+    Right,
+    /// Joins both sides without changing its own form, like the tatweel.
+    Causing,
+    /// Never joins other letters.
+    None,
+}
+
+/// Isolated presentation form and joining type of the letters from U+0621 to U+064A.
+///
+/// The final, initial and medial forms directly follow the isolated one. Letters without
+/// presentation forms are stored as `0`.
+const LETTERS: [(u32, Joining); 42] = [
+    (0xfe80, Joining::None),
+    (0xfe81, Joining::Right),
+    (0xfe83, Joining::Right),
+    (0xfe85, Joining::Right),
+    (0xfe87, Joining::Right),
+    (0xfe89, Joining::Dual),
+    (0xfe8d, Joining::Right),
+    (0xfe8f, Joining::Dual),
+    (0xfe93, Joining::Right),
+    (0xfe95, Joining::Dual),
+    (0xfe99, Joining::Dual),
+    (0xfe9d, Joining::Dual),
+    (0xfea1, Joining::Dual),
+    (0xfea5, Joining::Dual),
+    (0xfea9, Joining::Right),
+    (0xfeab, Joining::Right),
+    (0xfead, Joining::Right),
+    (0xfeaf, Joining::Right),
+    (0xfeb1, Joining::Dual),
+    (0xfeb5, Joining::Dual),
+    (0xfeb9, Joining::Dual),
+    (0xfebd, Joining::Dual),
+    (0xfec1, Joining::Dual),
+    (0xfec5, Joining::Dual),
+    (0xfec9, Joining::Dual),
+    (0xfecd, Joining::Dual),
+    (0, Joining::Dual),
+    (0, Joining::Dual),
+    (0, Joining::Dual),
+    (0, Joining::Dual),
+    (0, Joining::Dual),
+    (0, Joining::Causing),
+    (0xfed1, Joining::Dual),
+    (0xfed5, Joining::Dual),
+    (0xfed9, Joining::Dual),
+    (0xfedd, Joining::Dual),
+    (0xfee1, Joining::Dual),
+    (0xfee5, Joining::Dual),
+    (0xfee9, Joining::Dual),
+    (0xfeed, Joining::Right),
+    (0xfeef, Joining::Right),
+    (0xfef1, Joining::Dual),
+];
+
+/// Isolated presentation form and joining type of a letter.
+fn letter(c: char) -> (u32, Joining) {
+    match c {
+        '\u{621}'..='\u{64a}' => LETTERS[c as usize - 0x621],
+        // Persian letters, with presentation forms in a separate block.
+        '\u{67e}' => (0xfb56, Joining::Dual),
+        '\u{686}' => (0xfb7a, Joining::Dual),
+        '\u{698}' => (0xfb8a, Joining::Right),
+        '\u{6a9}' => (0xfb8e, Joining::Dual),
+        '\u{6af}' => (0xfb92, Joining::Dual),
+        '\u{6cc}' => (0xfbfc, Joining::Dual),
+        _ => (0, Joining::None),
+    }
+}
+
+/// Shape the Arabic letters of a line, unless it doesn't contain any.
+///
+/// Characters are expected in logical order, or in visual order when `reversed` is set, as used
+/// by applications which reorder text themselves.
+pub fn shape(chars: &[char], reversed: bool) -> Option<Vec<char>> {
+    if chars.iter().all(|&c| letter(c).0 == 0) {
+        return None;
+    }
+
+    let joining = |index: Option<usize>| {
+        index.and_then(|index| chars.get(index)).map_or(Joining::None, |&c| letter(c).1)
+    };
+
+    let shaped = (0..chars.len())
+        .map(|i| {
+            let (form, own_joining) = letter(chars[i]);
+            if form == 0 {
+                return chars[i];
+            }
+
+            let (previous, next) = if reversed {
+                (i.checked_add(1), i.checked_sub(1))
+            } else {
+                (i.checked_sub(1), i.checked_add(1))
+            };
+            let joins_previous = own_joining != Joining::None
+                && matches!(joining(previous), Joining::Dual | Joining::Causing);
+            let joins_next = own_joining == Joining::Dual
+                && matches!(joining(next), Joining::Dual | Joining::Right | Joining::Causing);
+
+            let offset = match (joins_previous, joins_next) {
+                (false, false) => 0,
+                (true, false) => 1,
+                (false, true) => 2,
+                (true, true) => 3,
+            };
+            char::from_u32(form + offset).unwrap_or(chars[i])
+        })
+        .collect();
+
+    Some(shaped)
+}
+
+/// Shaped Arabic text of all lines in the viewport.
+#[derive(Debug, Default)]
+pub struct ArabicShaping {
+    /// Shaped characters of each line in the viewport containing Arabic letters.
+    lines: Vec<Option<Vec<char>>>,
+}
+
+impl ArabicShaping {
+    /// Update the shaped text for the terminal's viewport.
+    pub fn update<T>(&mut self, term: &Term<T>) {
+        self.lines.clear();
+
+        // Applications reordering text themselves write it in visual order.
+        let reversed = term.mode().contains(TermMode::BIDI_EXPLICIT);
+
+        let display_offset = term.grid().display_offset();
+        let mut chars = Vec::with_capacity(term.columns());
+        for viewport_line in 0..term.screen_lines() {
+            let line = Line(viewport_line as i32 - display_offset as i32);
+
+            chars.clear();
+            chars.extend(term.grid()[line][..].iter().map(|cell| cell.c));
+            self.lines.push(shape(&chars, reversed));
+        }
+
+        if self.lines.iter().all(Option::is_none) {
+            self.lines.clear();
+        }
+    }
+
+    /// Check if any line in the viewport contains Arabic letters.
+    pub fn active(&self) -> bool {
+        !self.lines.is_empty()
+    }
+
+    /// Shaped character at a viewport position, if it's part of a line with Arabic letters.
+    pub fn character(&self, point: Point<usize>) -> Option<char> {
+        self.lines.get(point.line)?.as_ref()?.get(point.column.0).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shaped(text: &str, reversed: bool) -> Option<String> {
+        let chars: Vec<_> = text.chars().collect();
+        shape(&chars, reversed).map(|chars| chars.into_iter().collect())
+    }
+
+    #[test]
+    fn latin_is_unchanged() {
+        assert_eq!(shaped("hello", false), None);
+    }
+
+    #[test]
+    fn joining_forms() {
+        // Beh, Teh, Beh: initial, medial and final form.
+        assert_eq!(shaped("\u{628}\u{62a}\u{628}", false).unwrap(), "\u{fe91}\u{fe98}\u{fe90}");
+
+        // Isolated letter surrounded by Latin text.
+        assert_eq!(shaped("a\u{628} b", false).unwrap(), "a\u{fe8f} b");
+
+        // Alef only joins the preceding letter, so the following Beh is initial.
+        assert_eq!(
+            shaped("\u{628}\u{627}\u{628}\u{62a}", false).unwrap(),
+            "\u{fe91}\u{fe8e}\u{fe91}\u{fe96}"
+        );
+    }
+
+    #[test]
+    fn visual_order() {
+        assert_eq!(shaped("\u{62a}\u{628}", true).unwrap(), "\u{fe96}\u{fe91}");
+    }
+}
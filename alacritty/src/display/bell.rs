@@ -1,6 +1,7 @@
 use std::time::{Duration, Instant};
 
 use crate::config::bell::{BellAnimation, BellConfig};
+use crate::display::animation::cubic_bezier;
 
 pub struct VisualBell {
     /// Visual bell animation.
@@ -113,10 +114,3 @@ impl From<&BellConfig> for VisualBell {
         }
     }
 }
-
-fn cubic_bezier(p0: f64, p1: f64, p2: f64, p3: f64, x: f64) -> f64 {
-    (1.0 - x).powi(3) * p0
-        + 3.0 * (1.0 - x).powi(2) * x * p1
-        + 3.0 * (1.0 - x) * x.powi(2) * p2
-        + x.powi(3) * p3
-}
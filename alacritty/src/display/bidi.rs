@@ -0,0 +1,258 @@
+//! Reordering of bidirectional text for display.
+//!
+//! This implements a simplified version of the Unicode Bidirectional Algorithm, treating every
+//! line as a separate paragraph with left-to-right base direction. Explicit embeddings and
+//! isolates are not supported, since the terminal is in charge of the line's layout.
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::{self, Term, TermMode};
+
+/// Bidirectional class of a character, reduced to the classes relevant for reordering.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BidiClass {
+    Left,
+    Right,
+    Number,
+    Neutral,
+}
+
+impl BidiClass {
+    pub fn of(c: char) -> Self {
+        match c {
+            '0'..='9' | '\u{660}'..='\u{669}' | '\u{6f0}'..='\u{6f9}' => Self::Number,
+            '\u{590}'..='\u{8ff}'
+            | '\u{fb1d}'..='\u{fdff}'
+            | '\u{fe70}'..='\u{feff}'
+            | '\u{10800}'..='\u{10fff}'
+            | '\u{1e800}'..='\u{1efff}' => Self::Right,
+            c if c.is_alphabetic() => Self::Left,
+            _ => Self::Neutral,
+        }
+    }
+
+    /// Class of a cell, keeping wide characters and their spacers in logical order.
+    fn of_cell(cell: &Cell) -> Self {
+        let wide = Flags::WIDE_CHAR | Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER;
+        if cell.flags.intersects(wide) {
+            Self::Left
+        } else {
+            Self::of(cell.c)
+        }
+    }
+}
+
+/// Visual layout of a line containing right-to-left text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BidiLine {
+    /// Logical column at each visual column.
+    logical: Vec<usize>,
+
+    /// Visual column of each logical column.
+    visual: Vec<usize>,
+
+    /// Embedding level of each logical column, odd levels are right-to-left.
+    levels: Vec<u8>,
+}
+
+impl BidiLine {
+    /// Layout of a line, unless it only contains left-to-right text.
+    pub fn new(classes: &[BidiClass]) -> Option<Self> {
+        if !classes.contains(&BidiClass::Right) {
+            return None;
+        }
+
+        // Numbers are laid out left-to-right, even inside of right-to-left text.
+        let mut levels = vec![0; classes.len()];
+        let mut last_strong = BidiClass::Left;
+        for (level, class) in levels.iter_mut().zip(classes) {
+            *level = match class {
+                BidiClass::Left | BidiClass::Right => {
+                    last_strong = *class;
+                    u8::from(last_strong == BidiClass::Right)
+                },
+                BidiClass::Number if last_strong == BidiClass::Right => 2,
+                _ => 0,
+            };
+        }
+
+        // Neutrals between right-to-left text are right-to-left, everything else falls back to
+        // the left-to-right base direction.
+        let is_rtl = |levels: &[u8], i: usize| classes[i] != BidiClass::Neutral && levels[i] > 0;
+        let mut start = 0;
+        while start < classes.len() {
+            if classes[start] != BidiClass::Neutral {
+                start += 1;
+                continue;
+            }
+
+            let end = (start..classes.len())
+                .find(|&i| classes[i] != BidiClass::Neutral)
+                .unwrap_or(classes.len());
+            if start > 0
+                && end < classes.len()
+                && is_rtl(&levels, start - 1)
+                && is_rtl(&levels, end)
+            {
+                levels[start..end].fill(1);
+            }
+            start = end;
+        }
+
+        // Reverse runs from the highest to the lowest right-to-left level.
+        let mut logical: Vec<usize> = (0..classes.len()).collect();
+        let max_level = levels.iter().copied().max().unwrap_or_default();
+        for level in (1..=max_level).rev() {
+            let mut i = 0;
+            while i < logical.len() {
+                let run = logical[i..].iter().take_while(|&&column| levels[column] >= level);
+                let run_len = run.count();
+                logical[i..i + run_len].reverse();
+                i += run_len.max(1);
+            }
+        }
+
+        let mut visual = vec![0; logical.len()];
+        for (visual_column, logical_column) in logical.iter().enumerate() {
+            visual[*logical_column] = visual_column;
+        }
+
+        Some(Self { logical, visual, levels })
+    }
+
+    /// Visual column of a logical column.
+    pub fn visual_column(&self, column: Column) -> Column {
+        Column(self.visual.get(column.0).copied().unwrap_or(column.0))
+    }
+
+    /// Logical column of a visual column.
+    pub fn logical_column(&self, column: Column) -> Column {
+        Column(self.logical.get(column.0).copied().unwrap_or(column.0))
+    }
+
+    /// Check if a logical column is displayed right-to-left.
+    pub fn is_rtl(&self, column: Column) -> bool {
+        self.levels.get(column.0).map_or(false, |level| level % 2 == 1)
+    }
+}
+
+/// Visual layout of all lines in the viewport.
+#[derive(Debug, Default)]
+pub struct BidiLayout {
+    /// Layout of each line in the viewport containing right-to-left text.
+    lines: Vec<Option<BidiLine>>,
+}
+
+impl BidiLayout {
+    /// Update the layout for the terminal's viewport.
+    ///
+    /// Lines are kept in logical order while applications reorder text themselves, which they
+    /// request using the explicit bidirectional mode (BDSM).
+    pub fn update<T>(&mut self, term: &Term<T>) {
+        self.lines.clear();
+        if term.mode().contains(TermMode::BIDI_EXPLICIT) {
+            return;
+        }
+
+        let display_offset = term.grid().display_offset();
+        let mut classes = Vec::with_capacity(term.columns());
+        for viewport_line in 0..term.screen_lines() {
+            let line = Line(viewport_line as i32 - display_offset as i32);
+            let row = &term.grid()[line];
+
+            classes.clear();
+            classes.extend(row[..].iter().map(BidiClass::of_cell));
+            self.lines.push(BidiLine::new(&classes));
+        }
+
+        // Drop the lines if there's no right-to-left text at all.
+        if self.lines.iter().all(Option::is_none) {
+            self.lines.clear();
+        }
+    }
+
+    /// Check if any line in the viewport is reordered.
+    pub fn active(&self) -> bool {
+        !self.lines.is_empty()
+    }
+
+    /// Layout of a viewport line, if it contains right-to-left text.
+    pub fn line(&self, line: usize) -> Option<&BidiLine> {
+        self.lines.get(line)?.as_ref()
+    }
+
+    /// Logical terminal point at a visual position.
+    pub fn logical_point(&self, point: Point, display_offset: usize) -> Point {
+        let bidi_line = term::point_to_viewport(display_offset, point)
+            .and_then(|viewport_point| self.line(viewport_point.line));
+        match bidi_line {
+            Some(bidi_line) => Point::new(point.line, bidi_line.logical_column(point.column)),
+            None => point,
+        }
+    }
+}
+
+/// Character displayed in place of a character inside of right-to-left text.
+pub fn mirror(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(text: &str) -> Option<BidiLine> {
+        let classes: Vec<_> = text.chars().map(BidiClass::of).collect();
+        BidiLine::new(&classes)
+    }
+
+    /// Text of a line in visual order.
+    fn visual(text: &str) -> String {
+        let chars: Vec<_> = text.chars().collect();
+        let line = layout(text).unwrap();
+        (0..chars.len()).map(|column| chars[line.logical_column(Column(column)).0]).collect()
+    }
+
+    #[test]
+    fn left_to_right_only() {
+        assert_eq!(layout("hello (world) 123"), None);
+    }
+
+    #[test]
+    fn right_to_left_runs() {
+        assert_eq!(visual("abc שלום def"), "abc םולש def");
+        assert_eq!(visual("שלום עולם"), "םלוע םולש");
+
+        // Trailing neutrals stay at the end of the line.
+        assert_eq!(visual("שלום  "), "םולש  ");
+    }
+
+    #[test]
+    fn numbers_inside_right_to_left_text() {
+        assert_eq!(visual("שלום 123 עולם"), "םלוע 123 םולש");
+        assert_eq!(visual("abc 123 שלום"), "abc 123 םולש");
+    }
+
+    #[test]
+    fn columns_round_trip() {
+        let line = layout("ab שלום cd").unwrap();
+        assert_eq!(line.visual_column(Column(3)), Column(6));
+        assert_eq!(line.logical_column(Column(6)), Column(3));
+        assert!(line.is_rtl(Column(3)));
+        assert!(!line.is_rtl(Column(0)));
+        assert!(!line.is_rtl(Column(2)));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::string::{ShortenDirection, StrShortener};
+
+/// Keys used to pick entries, in order of the clipboard history.
+const PICKER_KEYS: &str = "1234567890";
+
+/// Text shown when there is nothing to pick from.
+const EMPTY_HISTORY_TEXT: &str = "Clipboard history is empty";
+
+/// Overlay for picking an entry of the clipboard history to paste.
+#[derive(Default)]
+pub struct ClipboardPicker {
+    /// Entries available for picking, newest first.
+    entries: Vec<String>,
+
+    /// Whether the picker is currently shown.
+    active: bool,
+}
+
+impl ClipboardPicker {
+    /// Start picking from the clipboard history.
+    pub fn start(&mut self, entries: impl IntoIterator<Item = String>) {
+        self.entries = entries.into_iter().take(PICKER_KEYS.len()).collect();
+        self.active = true;
+    }
+
+    /// Close the picker.
+    pub fn stop(&mut self) {
+        self.entries.clear();
+        self.active = false;
+    }
+
+    /// Check if the picker is currently shown.
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    /// Handle keyboard input, returning the picked entry.
+    ///
+    /// Any key which does not pick an entry closes the picker.
+    pub fn keyboard_input(&mut self, c: char) -> Option<String> {
+        let entry = PICKER_KEYS
+            .find(c)
+            .filter(|&index| index < self.entries.len())
+            .map(|index| self.entries.swap_remove(index));
+
+        self.stop();
+
+        entry
+    }
+
+    /// Lines of text displayed by the picker, padded to the number of columns.
+    pub fn lines(&self, columns: usize) -> Vec<String> {
+        if self.entries.is_empty() {
+            return vec![format!("{:<1$}", EMPTY_HISTORY_TEXT, columns)];
+        }
+
+        self.entries
+            .iter()
+            .zip(PICKER_KEYS.chars())
+            .map(|(entry, key)| {
+                // Show multiline entries on a single line.
+                let text = format!("{key}: {}", entry.replace(['\r', '\n', '\t'], " "));
+                let text: String =
+                    StrShortener::new(&text, columns, ShortenDirection::Right, Some('…')).collect();
+                format!("{:<1$}", text, columns)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pick_entry() {
+        let mut picker = ClipboardPicker::default();
+
+        picker.start(vec![String::from("new"), String::from("old")]);
+        assert!(picker.active());
+        assert_eq!(picker.keyboard_input('2'), Some(String::from("old")));
+        assert!(!picker.active());
+
+        picker.start(vec![String::from("new")]);
+        assert_eq!(picker.keyboard_input('2'), None);
+        assert!(!picker.active());
+    }
+
+    #[test]
+    fn picker_lines() {
+        let mut picker = ClipboardPicker::default();
+        assert_eq!(picker.lines(30), vec![format!("{:<30}", EMPTY_HISTORY_TEXT)]);
+
+        picker.start(vec![String::from("a\nb"), String::from("0123456789")]);
+        assert_eq!(picker.lines(8), vec!["1: a b  ", "2: 0123…"]);
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use alacritty_terminal::term::ClipboardType;
+
+use crate::string::{ShortenDirection, StrShortener};
+
+/// Formatter for the clipboard content sent back to the application.
+pub type ClipboardFormatter = Arc<dyn Fn(&str) -> String + Sync + Send + 'static>;
+
+/// Prompt asking the user to allow an application to read the clipboard.
+#[derive(Default)]
+pub struct ClipboardPrompt {
+    /// Pending clipboard read.
+    request: Option<(ClipboardType, ClipboardFormatter)>,
+}
+
+impl ClipboardPrompt {
+    /// Ask for permission to read the clipboard.
+    ///
+    /// This replaces any previous request which has not been answered yet.
+    pub fn start(&mut self, ty: ClipboardType, format: ClipboardFormatter) {
+        self.request = Some((ty, format));
+    }
+
+    /// Check if the prompt is currently shown.
+    pub fn active(&self) -> bool {
+        self.request.is_some()
+    }
+
+    /// Handle keyboard input, returning the request if it was allowed.
+    ///
+    /// Any key other than `y` denies the request.
+    pub fn keyboard_input(&mut self, c: char) -> Option<(ClipboardType, ClipboardFormatter)> {
+        let request = self.request.take()?;
+        c.eq_ignore_ascii_case(&'y').then_some(request)
+    }
+
+    /// Lines of text displayed by the prompt, padded to the number of columns.
+    pub fn lines(&self, columns: usize) -> Vec<String> {
+        let clipboard = match self.request {
+            Some((ClipboardType::Selection, _)) => "selection",
+            Some((ClipboardType::Clipboard, _)) => "clipboard",
+            None => return Vec::new(),
+        };
+
+        let text = format!("Allow the application to read the {clipboard}? [y/N]");
+        let text: String =
+            StrShortener::new(&text, columns, ShortenDirection::Right, Some('…')).collect();
+        vec![format!("{:<1$}", text, columns)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answer_prompt() {
+        let mut prompt = ClipboardPrompt::default();
+        assert!(!prompt.active());
+        assert!(prompt.lines(10).is_empty());
+
+        prompt.start(ClipboardType::Clipboard, Arc::new(|text| text.to_owned()));
+        assert!(prompt.active());
+        let (ty, format) = prompt.keyboard_input('Y').unwrap();
+        assert_eq!(ty, ClipboardType::Clipboard);
+        assert_eq!(format("text"), "text");
+        assert!(!prompt.active());
+
+        prompt.start(ClipboardType::Selection, Arc::new(|text| text.to_owned()));
+        assert!(prompt.keyboard_input('n').is_none());
+        assert!(!prompt.active());
+        assert!(prompt.keyboard_input('y').is_none());
+    }
+}
@@ -10,7 +10,30 @@ use crate::config::color::Colors;
 /// Factor for automatic computation of dim colors.
 pub const DIM_FACTOR: f32 = 0.66;
 
-#[derive(Copy, Clone)]
+/// Minimum contrast of text against its background in high contrast mode.
+pub const MIN_HIGH_CONTRAST: f64 = 7.;
+
+/// Normal and bright colors of the high contrast palette, on a black background.
+const HIGH_CONTRAST_COLORS: [Rgb; 16] = [
+    Rgb::new(0x00, 0x00, 0x00),
+    Rgb::new(0xff, 0x80, 0x80),
+    Rgb::new(0x00, 0xff, 0x00),
+    Rgb::new(0xff, 0xff, 0x00),
+    Rgb::new(0x80, 0xa0, 0xff),
+    Rgb::new(0xff, 0x80, 0xff),
+    Rgb::new(0x00, 0xff, 0xff),
+    Rgb::new(0xff, 0xff, 0xff),
+    Rgb::new(0xa0, 0xa0, 0xa0),
+    Rgb::new(0xff, 0xa0, 0xa0),
+    Rgb::new(0x80, 0xff, 0x80),
+    Rgb::new(0xff, 0xff, 0x80),
+    Rgb::new(0xa0, 0xc0, 0xff),
+    Rgb::new(0xff, 0xa0, 0xff),
+    Rgb::new(0x80, 0xff, 0xff),
+    Rgb::new(0xff, 0xff, 0xff),
+];
+
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub struct List([Rgb; COUNT]);
 
 impl<'a> From<&'a Colors> for List {
@@ -27,6 +50,39 @@ impl<'a> From<&'a Colors> for List {
 }
 
 impl List {
+    /// Colors with guaranteed contrast, overriding the configured palette.
+    pub fn high_contrast(colors: &Colors) -> List {
+        let mut list = List::from(colors);
+
+        for (index, color) in HIGH_CONTRAST_COLORS.iter().enumerate() {
+            list[index] = *color;
+        }
+
+        // Dim colors would reduce the contrast.
+        for index in 0..8 {
+            list[NamedColor::DimBlack as usize + index] = HIGH_CONTRAST_COLORS[index];
+        }
+
+        let white = HIGH_CONTRAST_COLORS[NamedColor::White as usize];
+        list[NamedColor::Foreground] = white;
+        list[NamedColor::BrightForeground] = white;
+        list[NamedColor::DimForeground] = white;
+        list[NamedColor::Background] = HIGH_CONTRAST_COLORS[NamedColor::Black as usize];
+
+        list
+    }
+
+    /// Black or white, whichever has more contrast against a color.
+    pub fn contrasting(color: Rgb) -> Rgb {
+        let black = Rgb::new(0x00, 0x00, 0x00);
+        let white = Rgb::new(0xff, 0xff, 0xff);
+        if color.contrast(*white) >= color.contrast(*black) {
+            white
+        } else {
+            black
+        }
+    }
+
     pub fn fill_named(&mut self, colors: &Colors) {
         // Normals.
         self[NamedColor::Black] = colors.normal.black;
@@ -165,3 +221,21 @@ impl IndexMut<NamedColor> for List {
         &mut self.0[idx as usize]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_contrast_palette() {
+        let list = List::high_contrast(&Colors::default());
+        let background = list[NamedColor::Background];
+
+        for index in (1..16).chain([NamedColor::Foreground as usize]) {
+            assert!(list[index].contrast(*background) >= MIN_HIGH_CONTRAST, "color {index}");
+        }
+
+        assert_eq!(List::contrasting(Rgb::new(0x20, 0x20, 0x40)), Rgb::new(0xff, 0xff, 0xff));
+        assert_eq!(List::contrasting(Rgb::new(0xe0, 0xe0, 0x40)), Rgb::new(0x00, 0x00, 0x00));
+    }
+}
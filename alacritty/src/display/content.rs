@@ -13,11 +13,12 @@ use alacritty_terminal::term::search::{Match, RegexSearch};
 use alacritty_terminal::term::{self, RenderableContent as TerminalContent, Term, TermMode};
 
 use crate::config::UiConfig;
-use crate::display::color::{List, DIM_FACTOR};
+use crate::display::color::{List, DIM_FACTOR, MIN_HIGH_CONTRAST};
 use crate::display::cursor::CursorRects;
+use crate::display::highlight::HighlightState;
 use crate::display::hint::{self, HintState};
 use crate::display::Display;
-use crate::event::SearchState;
+use crate::event::{match_in_scope, SearchState};
 
 use log::debug;
 
@@ -34,9 +35,16 @@ pub struct RenderableContent<'a> {
     cursor_point: Point<usize>,
     search: Option<HintMatches<'a>>,
     hint: Option<Hint<'a>>,
+    highlights: Option<&'a HighlightState>,
     config: &'a UiConfig,
     colors: &'a List,
+
+    /// Apply the terminal's color overrides on top of `colors`.
+    term_colors: bool,
+
     focused_match: Option<&'a Match>,
+    high_contrast: bool,
+    rounded_selection: bool,
 }
 
 impl<'a> RenderableContent<'a> {
@@ -46,7 +54,9 @@ impl<'a> RenderableContent<'a> {
         term: &'a Term<T>,
         search_state: &'a SearchState,
     ) -> Self {
-        let search = search_state.dfas().map(|dfas| HintMatches::visible_regex_matches(term, dfas));
+        let search = search_state
+            .dfas()
+            .map(|dfas| HintMatches::visible_regex_matches(term, dfas, search_state.scope()));
         let focused_match = search_state.focused_match();
         let terminal_content = term.renderable_content();
 
@@ -75,8 +85,15 @@ impl<'a> RenderableContent<'a> {
             None
         };
 
+        display.highlights.update(term);
+        let highlights = Some(&display.highlights).filter(|highlights| highlights.active());
+
         Self {
-            colors: &display.colors,
+            rounded_selection: config.terminal_config.selection.rounded,
+            high_contrast: display.high_contrast(),
+            // The palette of the focused pane already includes the terminal's overrides.
+            colors: display.palette_animation.colors().unwrap_or(&display.colors),
+            term_colors: false,
             cursor: RenderableCursor::new_hidden(),
             terminal_content,
             focused_match,
@@ -85,6 +102,38 @@ impl<'a> RenderableContent<'a> {
             search,
             config,
             hint,
+            highlights,
+        }
+    }
+
+    /// Content of a terminal in a pane without keyboard focus.
+    ///
+    /// Search matches, hints and the cursor are only shown for the focused pane.
+    pub fn unfocused<T: EventListener>(
+        config: &'a UiConfig,
+        colors: &'a List,
+        term: &'a Term<T>,
+        high_contrast: bool,
+    ) -> Self {
+        let terminal_content = term.renderable_content();
+        let display_offset = terminal_content.display_offset;
+        let cursor_point =
+            term::point_to_viewport(display_offset, terminal_content.cursor.point).unwrap();
+
+        Self {
+            cursor: RenderableCursor::new_hidden(),
+            cursor_shape: CursorShape::Hidden,
+            rounded_selection: false,
+            focused_match: None,
+            search: None,
+            hint: None,
+            highlights: None,
+            terminal_content,
+            cursor_point,
+            config,
+            colors,
+            term_colors: true,
+            high_contrast,
         }
     }
 
@@ -103,7 +152,8 @@ impl<'a> RenderableContent<'a> {
 
     /// Get the RGB value for a color index.
     pub fn color(&self, color: usize) -> Rgb {
-        self.terminal_content.colors[color].unwrap_or(self.colors[color])
+        let term_color = self.terminal_content.colors[color].filter(|_| self.term_colors);
+        term_color.unwrap_or(self.colors[color])
     }
 
     pub fn selection_range(&self) -> Option<SelectionRange> {
@@ -112,11 +162,12 @@ impl<'a> RenderableContent<'a> {
 
     /// Assemble the information required to render the terminal cursor.
     fn renderable_cursor(&mut self, cell: &RenderableCell) -> RenderableCursor {
-        // Cursor colors.
+        // Cursor colors, with the normal cursor's colors depending on its shape.
+        let colors = &self.config.colors;
         let color = if self.terminal_content.mode.contains(TermMode::VI) {
-            self.config.colors.vi_mode_cursor
+            colors.vi_mode_cursor
         } else {
-            self.config.colors.cursor
+            colors.cursor_shapes.apply(self.cursor_shape, colors.cursor)
         };
         let cursor_color =
             self.terminal_content.colors[NamedColor::Cursor].map_or(color.background, CellRgb::Rgb);
@@ -143,6 +194,7 @@ impl<'a> RenderableContent<'a> {
             point: self.cursor_point,
             cursor_color,
             text_color,
+            cell_background: cell.bg,
         }
     }
 }
@@ -163,7 +215,8 @@ impl<'a> Iterator for RenderableContent<'a> {
             if self.cursor_point == cell.point {
                 // Store the cursor which should be rendered.
                 self.cursor = self.renderable_cursor(&cell);
-                if self.cursor.shape == CursorShape::Block {
+                let transparent = self.config.terminal_config.cursor.transparent_block();
+                if self.cursor.shape == CursorShape::Block && !transparent {
                     cell.fg = self.cursor.text_color;
                     cell.bg = self.cursor.cursor_color;
 
@@ -212,7 +265,7 @@ impl RenderableCell {
             mem::swap(&mut fg, &mut bg);
             1.0
         } else {
-            Self::compute_bg_alpha(content.config, cell.bg)
+            Self::compute_bg_alpha(content, cell.bg, bg)
         };
 
         let is_selected = content.terminal_content.selection.map_or(false, |selection| {
@@ -225,6 +278,10 @@ impl RenderableCell {
 
         let display_offset = content.terminal_content.display_offset;
         let viewport_start = Point::new(Line(-(display_offset as i32)), Column(0));
+        let mut flags = cell.flags;
+
+        // Convert cell point to viewport position.
+        let point = term::point_to_viewport(display_offset, cell.point).unwrap();
         let colors = &content.config.colors;
         let mut character = cell.c;
 
@@ -250,6 +307,11 @@ impl RenderableCell {
                 bg = content.color(NamedColor::Foreground as usize);
                 bg_alpha = 1.0;
             }
+
+            // Leave the background to the selection's region, drawn below all cells.
+            if content.rounded_selection {
+                bg_alpha = 0.;
+            }
         } else if content.search.as_mut().map_or(false, |search| search.advance(cell.point)) {
             let focused = content.focused_match.map_or(false, |fm| fm.contains(&cell.point));
             let (config_fg, config_bg) = if focused {
@@ -258,24 +320,41 @@ impl RenderableCell {
                 (colors.search.matches.foreground, colors.search.matches.background)
             };
             Self::compute_cell_rgb(&mut fg, &mut bg, &mut bg_alpha, config_fg, config_bg);
+        } else if let Some(rule) =
+            content.highlights.and_then(|highlights| highlights.rule_at(point))
+        {
+            let config_fg = rule.foreground.unwrap_or(CellRgb::CellForeground);
+            let config_bg = rule.background.unwrap_or(CellRgb::CellBackground);
+            Self::compute_cell_rgb(&mut fg, &mut bg, &mut bg_alpha, config_fg, config_bg);
+
+            if rule.bold {
+                flags.insert(Flags::BOLD);
+            }
+            if rule.underline {
+                flags.insert(Flags::UNDERLINE);
+            }
         }
 
-        // Apply transparency to all renderable cells if `transparent_background_colors` is set
-        if bg_alpha > 0. && content.config.colors.transparent_background_colors {
+        if content.high_contrast {
+            // Enforce contrast regardless of the colors requested by the application.
+            if !cell.flags.contains(Flags::HIDDEN) && fg.contrast(*bg) < MIN_HIGH_CONTRAST {
+                fg = List::contrasting(bg);
+            }
+        } else if bg_alpha > 0. && content.config.colors.transparent_background_colors {
+            // Apply transparency to all renderable cells if `transparent_background_colors` is set
             bg_alpha = content.config.window_opacity();
         }
 
-        // Convert cell point to viewport position.
-        let cell_point = cell.point;
-        let point = term::point_to_viewport(display_offset, cell_point).unwrap();
-
-        let flags = cell.flags;
-        let underline = cell
+        let mut underline = cell
             .underline_color()
             .map_or(fg, |underline| Self::compute_fg_rgb(content, underline, flags));
+        if content.high_contrast && underline.contrast(*bg) < MIN_HIGH_CONTRAST {
+            underline = fg;
+        }
 
         let zerowidth = cell.zerowidth();
-        let hyperlink = cell.hyperlink();
+        let hyperlinks = content.terminal_content.hyperlinks;
+        let hyperlink = cell.hyperlink().and_then(|id| hyperlinks.get(id)).cloned();
 
         let extra = (zerowidth.is_some() || hyperlink.is_some()).then(|| {
             Box::new(RenderableCellExtra {
@@ -313,78 +392,90 @@ impl RenderableCell {
 
     /// Get the RGB color from a cell's foreground color.
     fn compute_fg_rgb(content: &RenderableContent<'_>, fg: Color, flags: Flags) -> Rgb {
-        let config = &content.config;
-        match fg {
-            Color::Spec(rgb) => match flags & Flags::DIM {
-                Flags::DIM => {
-                    let rgb: Rgb = rgb.into();
-                    rgb * DIM_FACTOR
-                },
-                _ => rgb.into(),
-            },
-            Color::Named(ansi) => {
-                match (config.draw_bold_text_with_bright_colors(), flags & Flags::DIM_BOLD) {
-                    // If no bright foreground is set, treat it like the BOLD flag doesn't exist.
-                    (_, Flags::DIM_BOLD)
-                        if ansi == NamedColor::Foreground
-                            && config.colors.primary.bright_foreground.is_none() =>
-                    {
-                        content.color(NamedColor::DimForeground as usize)
-                    },
-                    // Draw bold text in bright colors *and* contains bold flag.
-                    (true, Flags::BOLD) => content.color(ansi.to_bright() as usize),
-                    // Cell is marked as dim and not bold.
-                    (_, Flags::DIM) | (false, Flags::DIM_BOLD) => {
-                        content.color(ansi.to_dim() as usize)
-                    },
-                    // None of the above, keep original color..
-                    _ => content.color(ansi as usize),
-                }
-            },
-            Color::Indexed(idx) => {
-                let idx = match (
-                    config.draw_bold_text_with_bright_colors(),
-                    flags & Flags::DIM_BOLD,
-                    idx,
-                ) {
-                    (true, Flags::BOLD, 0..=7) => idx as usize + 8,
-                    (false, Flags::DIM, 8..=15) => idx as usize - 8,
-                    (false, Flags::DIM, 0..=7) => NamedColor::DimBlack as usize + idx as usize,
-                    _ => idx as usize,
-                };
-
-                content.color(idx)
-            },
-        }
+        foreground_rgb(content.config, |index| content.color(index), fg, flags)
     }
 
     /// Get the RGB color from a cell's background color.
     #[inline]
     fn compute_bg_rgb(content: &RenderableContent<'_>, bg: Color) -> Rgb {
-        match bg {
-            Color::Spec(rgb) => rgb.into(),
-            Color::Named(ansi) => content.color(ansi as usize),
-            Color::Indexed(idx) => content.color(idx as usize),
-        }
+        background_rgb(|index| content.color(index), bg)
     }
 
     /// Compute background alpha based on cell's original color.
     ///
     /// Since an RGB color matching the background should not be transparent, this is computed
     /// using the named input color, rather than checking the RGB of the background after its color
-    /// is computed.
+    /// is computed. Only `colors.color_key` makes such colors transparent as well.
     #[inline]
-    fn compute_bg_alpha(config: &UiConfig, bg: Color) -> f32 {
-        if bg == Color::Named(NamedColor::Background) {
+    fn compute_bg_alpha(content: &RenderableContent<'_>, bg: Color, bg_rgb: Rgb) -> f32 {
+        let color_key = content.config.colors.color_key
+            && bg_rgb == content.color(NamedColor::Background as usize);
+        if bg == Color::Named(NamedColor::Background) || color_key {
             0.
-        } else if config.colors.transparent_background_colors {
-            config.window_opacity()
+        } else if content.config.colors.transparent_background_colors && !content.high_contrast {
+            content.config.window_opacity()
         } else {
             1.
         }
     }
 }
 
+/// Get the RGB color from a cell's foreground color, with `color` looking up indexed colors.
+pub fn foreground_rgb(
+    config: &UiConfig,
+    color: impl Fn(usize) -> Rgb,
+    fg: Color,
+    flags: Flags,
+) -> Rgb {
+    match fg {
+        Color::Spec(rgb) => match flags & Flags::DIM {
+            Flags::DIM => {
+                let rgb: Rgb = rgb.into();
+                rgb * DIM_FACTOR
+            },
+            _ => rgb.into(),
+        },
+        Color::Named(ansi) => {
+            match (config.draw_bold_text_with_bright_colors(), flags & Flags::DIM_BOLD) {
+                // If no bright foreground is set, treat it like the BOLD flag doesn't exist.
+                (_, Flags::DIM_BOLD)
+                    if ansi == NamedColor::Foreground
+                        && config.colors.primary.bright_foreground.is_none() =>
+                {
+                    color(NamedColor::DimForeground as usize)
+                },
+                // Draw bold text in bright colors *and* contains bold flag.
+                (true, Flags::BOLD) => color(ansi.to_bright() as usize),
+                // Cell is marked as dim and not bold.
+                (_, Flags::DIM) | (false, Flags::DIM_BOLD) => color(ansi.to_dim() as usize),
+                // None of the above, keep original color..
+                _ => color(ansi as usize),
+            }
+        },
+        Color::Indexed(idx) => {
+            let idx =
+                match (config.draw_bold_text_with_bright_colors(), flags & Flags::DIM_BOLD, idx) {
+                    (true, Flags::BOLD, 0..=7) => idx as usize + 8,
+                    (false, Flags::DIM, 8..=15) => idx as usize - 8,
+                    (false, Flags::DIM, 0..=7) => NamedColor::DimBlack as usize + idx as usize,
+                    _ => idx as usize,
+                };
+
+            color(idx)
+        },
+    }
+}
+
+/// Get the RGB color from a cell's background color, with `color` looking up indexed colors.
+#[inline]
+pub fn background_rgb(color: impl Fn(usize) -> Rgb, bg: Color) -> Rgb {
+    match bg {
+        Color::Spec(rgb) => rgb.into(),
+        Color::Named(ansi) => color(ansi as usize),
+        Color::Indexed(idx) => color(idx as usize),
+    }
+}
+
 /// Cursor storing all information relevant for rendering.
 #[derive(Debug, PartialEq, Copy, Clone, Default)]
 pub struct RenderableCursor {
@@ -394,6 +485,9 @@ pub struct RenderableCursor {
     pub cursor_color: Rgb,
     text_color: Rgb,
     is_wide: bool,
+
+    /// Background of the cell below the cursor, before it's replaced by a block cursor.
+    cell_background: Rgb,
 }
 
 impl RenderableCursor {
@@ -404,14 +498,24 @@ impl RenderableCursor {
         let text_color = Rgb::default();
         let is_wide = false;
         let point = Point::default();
-        Self { shape, data, cursor_color, text_color, is_wide, point }
+        let cell_background = Rgb::default();
+        Self { shape, data, cursor_color, text_color, is_wide, point, cell_background }
     }
 }
 
 impl RenderableCursor {
     pub fn new(point: Point<usize>, shape: CursorShape, cursor_color: Rgb, is_wide: bool) -> Self {
         let data = CursorRects::default();
-        Self { shape, data, cursor_color, text_color: cursor_color, is_wide, point }
+        let cell_background = Rgb::default();
+        Self {
+            shape,
+            data,
+            cursor_color,
+            text_color: cursor_color,
+            is_wide,
+            point,
+            cell_background,
+        }
     }
 
     pub fn color(&self) -> Rgb {
@@ -429,6 +533,10 @@ impl RenderableCursor {
     pub fn point(&self) -> Point<usize> {
         self.point
     }
+
+    pub fn cell_background(&self) -> Rgb {
+        self.cell_background
+    }
 }
 
 /// Regex hints for keyboard shortcuts.
@@ -493,8 +601,10 @@ impl<'a> HintMatches<'a> {
     }
 
     /// Create from regex matches on term visable part.
-    fn visible_regex_matches<T>(term: &Term<T>, dfas: &RegexSearch) -> Self {
-        let matches = hint::visible_regex_match_iter(term, dfas).collect::<Vec<_>>();
+    fn visible_regex_matches<T>(term: &Term<T>, dfas: &RegexSearch, scope: Option<&Match>) -> Self {
+        let matches = hint::visible_regex_match_iter(term, dfas)
+            .filter(|regex_match| scope.map_or(true, |scope| match_in_scope(regex_match, scope)))
+            .collect::<Vec<_>>();
         Self::new(matches)
     }
 
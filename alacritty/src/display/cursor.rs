@@ -1,5 +1,6 @@
 //! Convert a cursor into an iterator of rects.
-use alacritty_terminal::index::Point;
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Point};
 
 use alacritty_terminal::ansi::CursorShape;
 use alacritty_terminal::term::color::Rgb;
@@ -8,15 +9,24 @@ use crate::display::content::RenderableCursor;
 use crate::display::SizeInfo;
 use crate::renderer::quads::{QuadPoint, RenderQuad};
 
+/// Luminance of the cells below an adaptive cursor, above which the cursor turns dark.
+const DARK_CURSOR_LUMINANCE: f64 = 0.25;
+
+/// Luminance of the cells below an adaptive cursor, below which the cursor turns light.
+const LIGHT_CURSOR_LUMINANCE: f64 = 0.12;
+
 /// Trait for conversion into the iterator.
 pub trait IntoRects {
     /// Consume the cursor for an iterator of rects.
-    fn quads(&mut self, size_info: &SizeInfo, thickness: f32) -> RenderQuad;
+    ///
+    /// The cursor glides towards its position when `animate` is set.
+    fn quads(&mut self, size_info: &SizeInfo, thickness: f32, animate: bool) -> RenderQuad;
 }
 
 impl IntoRects for RenderableCursor {
-    fn quads(&mut self, size_info: &SizeInfo, thickness: f32) -> RenderQuad {
-        self.data.update(self.shape(), size_info, thickness, self.point(), self.is_wide());
+    fn quads(&mut self, size_info: &SizeInfo, thickness: f32, animate: bool) -> RenderQuad {
+        let (shape, point, is_wide) = (self.shape(), self.point(), self.is_wide());
+        self.data.update(shape, size_info, thickness, point, is_wide, animate);
 
         RenderQuad::new(self.data.positions, self.color(), 1.0)
     }
@@ -38,6 +48,7 @@ impl CursorRects {
         thickness: f32,
         point: Point<usize>,
         is_wide: bool,
+        animate: bool,
     ) {
         let x = point.column.0 as f32 * size_info.cell_width() + size_info.padding_x();
         let y = point.line as f32 * size_info.cell_height() + size_info.padding_y();
@@ -54,10 +65,17 @@ impl CursorRects {
         let target = match shape {
             CursorShape::Beam => beam(x, y, height, thickness),
             CursorShape::Underline => underline(x, y, width, height, thickness),
-            //CursorShape::HollowBlock => hollow(x, y, width, height, thickness),
+            // CursorShape::HollowBlock => hollow(x, y, width, height, thickness),
             _ => beam(x, y, height, width),
         };
 
+        // Jump straight to the target without animation.
+        if !animate {
+            self.positions = target;
+            self.vels = Default::default();
+            return;
+        }
+
         for i in 0..4 {
             let diffx = target[i].x - self.positions[i].x;
             let diffy = target[i].y - self.positions[i].y;
@@ -79,12 +97,63 @@ impl CursorRects {
     }
 }
 
+impl CursorRects {
+    /// Move the cursor to the same cell after the cell dimensions changed.
+    pub fn reproject(&mut self, old: &SizeInfo, new: &SizeInfo) {
+        let scale_x = new.cell_width() / old.cell_width();
+        let scale_y = new.cell_height() / old.cell_height();
+
+        for position in &mut self.positions {
+            position.x = (position.x - old.padding_x()) * scale_x + new.padding_x();
+            position.y = (position.y - old.padding_y()) * scale_y + new.padding_y();
+        }
+    }
+}
+
 impl From<RenderQuad> for CursorRects {
     fn from(rect: RenderQuad) -> Self {
         Self { positions: Default::default(), vels: Default::default(), index: 0 }
     }
 }
 
+/// Cursor color contrasting with the cells below it.
+///
+/// The color only flips once the luminance clearly passes the point of equal contrast to black
+/// and white, to keep it from flickering while gliding over backgrounds close to that point.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct AdaptiveColor {
+    dark: bool,
+}
+
+impl AdaptiveColor {
+    /// Cursor color for the background below the cursor.
+    pub fn update(&mut self, background: Rgb) -> Rgb {
+        let luminance = background.luminance();
+        if luminance > DARK_CURSOR_LUMINANCE {
+            self.dark = true;
+        } else if luminance < LIGHT_CURSOR_LUMINANCE {
+            self.dark = false;
+        }
+
+        if self.dark {
+            Rgb::new(0x00, 0x00, 0x00)
+        } else {
+            Rgb::new(0xff, 0xff, 0xff)
+        }
+    }
+}
+
+/// Viewport cell below the center of a cursor quad.
+pub fn quad_cell(quad: &RenderQuad, size_info: &SizeInfo) -> Point<usize> {
+    let x = quad.points.iter().map(|point| point.x).sum::<f32>() / 4.;
+    let y = quad.points.iter().map(|point| point.y).sum::<f32>() / 4.;
+
+    let column = ((x - size_info.padding_x()) / size_info.cell_width()).max(0.) as usize;
+    let line = ((y - size_info.padding_y()) / size_info.cell_height()).max(0.) as usize;
+    let column = Column(column.min(size_info.last_column().0));
+    Point::new(line.min(size_info.screen_lines().saturating_sub(1)), column)
+}
+
 /// Create an iterator yielding a single beam rect.
 fn beam(x: f32, y: f32, height: f32, thickness: f32) -> [QuadPoint; 4] {
     [
@@ -107,7 +176,7 @@ fn underline(x: f32, y: f32, width: f32, height: f32, thickness: f32) -> [QuadPo
 }
 
 // Create an iterator yielding a rect for each side of the hollow block cursor.
-//fn hollow(x: f32, y: f32, width: f32, height: f32, thickness: f32, color: Rgb) -> CursorRects {
+// fn hollow(x: f32, y: f32, width: f32, height: f32, thickness: f32, color: Rgb) -> CursorRects {
 //    let top_line = RenderRect::new(x, y, width, thickness, color, 1.);
 //
 //    let vertical_y = y + thickness;
@@ -125,3 +194,48 @@ fn underline(x: f32, y: f32, width: f32, height: f32, thickness: f32) -> [QuadPo
 //        index: 0,
 //    }
 //}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::index::Column;
+
+    #[test]
+    fn reproject_to_same_cell() {
+        let old = SizeInfo::new(200., 200., 10., 20., 5., 5., false);
+        let new = SizeInfo::new(400., 400., 20., 40., 10., 10., false);
+
+        let mut cursor = CursorRects::default();
+        cursor.update(CursorShape::Block, &old, 0.15, Point::new(2, Column(3)), false, false);
+        cursor.reproject(&old, &new);
+
+        let mut expected = CursorRects::default();
+        expected.update(CursorShape::Block, &new, 0.15, Point::new(2, Column(3)), false, false);
+        assert_eq!(cursor.positions, expected.positions);
+    }
+
+    #[test]
+    fn adaptive_color_hysteresis() {
+        let (black, white) = (Rgb::new(0x00, 0x00, 0x00), Rgb::new(0xff, 0xff, 0xff));
+        let mut color = AdaptiveColor::default();
+        assert_eq!(color.update(black), white);
+        assert_eq!(color.update(white), black);
+
+        // Backgrounds close to the middle keep the previous color.
+        let gray = Rgb::new(0x70, 0x70, 0x70);
+        assert_eq!(color.update(gray), black);
+        assert_eq!(color.update(black), white);
+        assert_eq!(color.update(gray), white);
+    }
+
+    #[test]
+    fn cell_below_cursor() {
+        let size_info = SizeInfo::new(200., 200., 10., 20., 5., 5., false);
+        let mut cursor = CursorRects::default();
+        cursor.update(CursorShape::Beam, &size_info, 0.15, Point::new(2, Column(3)), false, false);
+
+        let quad = RenderQuad::new(cursor.positions, Rgb::default(), 1.);
+        assert_eq!(quad_cell(&quad, &size_info), Point::new(2, Column(3)));
+    }
+}
@@ -1,4 +1,5 @@
 use std::cmp;
+use std::collections::VecDeque;
 use std::iter::Peekable;
 
 use glutin::surface::Rect;
@@ -6,6 +7,10 @@ use glutin::surface::Rect;
 use alacritty_terminal::term::{LineDamageBounds, TermDamageIterator};
 
 use crate::display::SizeInfo;
+use crate::renderer::quads::RenderQuad;
+
+/// Maximum buffer age for which only the damaged region is redrawn.
+const MAX_BUFFER_AGE: usize = 4;
 
 /// Iterator which converts `alacritty_terminal` damage information into renderer damaged rects.
 pub struct RenderDamageIterator<'a> {
@@ -69,6 +74,65 @@ impl<'a> Iterator for RenderDamageIterator<'a> {
     }
 }
 
+/// Damaged regions of the most recently presented frames.
+///
+/// With buffer age, the buffer drawn into still contains an older frame. Bringing it up to date
+/// requires redrawing everything damaged since that frame was presented.
+#[derive(Default, Debug)]
+pub struct DamageHistory {
+    frames: VecDeque<Rect>,
+}
+
+impl DamageHistory {
+    /// Record the damaged region of a presented frame.
+    pub fn push(&mut self, damage: Rect) {
+        if self.frames.len() == MAX_BUFFER_AGE {
+            self.frames.pop_back();
+        }
+        self.frames.push_front(damage);
+    }
+
+    /// Region which has to be redrawn to bring a buffer of age `age` up to date with `damage`.
+    ///
+    /// Returns `None` if the buffer's content is unknown and the full frame must be redrawn.
+    pub fn redraw_region(&self, age: u32, damage: Rect) -> Option<Rect> {
+        let age = age as usize;
+        if age == 0 || age > self.frames.len() + 1 {
+            return None;
+        }
+
+        let previous_frames = self.frames.iter().take(age - 1);
+        Some(previous_frames.fold(damage, |region, &frame| merge_rects(region, frame)))
+    }
+}
+
+/// Smallest rectangle containing all `rects`.
+pub fn bounding_rect(rects: &[Rect]) -> Option<Rect> {
+    rects.iter().copied().reduce(merge_rects)
+}
+
+/// Damaged rectangle of a quad.
+pub fn quad_damage(quad: &RenderQuad, size_info: &SizeInfo) -> Rect {
+    let (mut left, mut right) = (f32::MAX, f32::MIN);
+    let (mut top, mut bottom) = (f32::MAX, f32::MIN);
+    for point in &quad.points {
+        left = left.min(point.x);
+        right = right.max(point.x);
+        top = top.min(point.y);
+        bottom = bottom.max(point.y);
+    }
+
+    // Include a pixel around the quad for antialiasing, clamped to the window.
+    let left = (left.floor() as i32 - 1).max(0);
+    let right = (right.ceil() as i32 + 1).min(size_info.width() as i32);
+    let top = (top.floor() as i32 - 1).max(0);
+    let bottom = (bottom.ceil() as i32 + 1).min(size_info.height() as i32);
+
+    // Damage uses a bottom-left origin.
+    let y = size_info.height() as i32 - bottom;
+    Rect::new(left, y, (right - left).max(0), (bottom - top).max(0))
+}
+
 /// Check if two given [`glutin::surface::Rect`] overlap.
 fn rects_overlap(lhs: Rect, rhs: Rect) -> bool {
     !(
@@ -97,6 +161,49 @@ fn merge_rects(lhs: Rect, rhs: Rect) -> Rect {
 mod tests {
     use super::*;
 
+    use crate::renderer::quads::QuadPoint;
+
+    #[test]
+    fn damage_history_redraw_region() {
+        let mut history = DamageHistory::default();
+        let damage = Rect::new(10, 10, 10, 10);
+
+        // Content of new buffers is unknown.
+        assert_eq!(history.redraw_region(0, damage), None);
+        assert_eq!(history.redraw_region(2, damage), None);
+
+        // The previous frame is still in the buffer.
+        assert_eq!(history.redraw_region(1, damage), Some(damage));
+
+        history.push(Rect::new(0, 0, 5, 5));
+        assert_eq!(history.redraw_region(2, damage), Some(Rect::new(0, 0, 20, 20)));
+
+        for _ in 0..MAX_BUFFER_AGE {
+            history.push(damage);
+        }
+        assert_eq!(history.redraw_region(MAX_BUFFER_AGE as u32 + 1, damage), Some(damage));
+        assert_eq!(history.redraw_region(MAX_BUFFER_AGE as u32 + 2, damage), None);
+    }
+
+    #[test]
+    fn quad_damage_rect() {
+        let size_info = SizeInfo::new(100., 100., 10., 10., 0., 0., false);
+        let points = [
+            QuadPoint { x: 10., y: 20. },
+            QuadPoint { x: 12., y: 20. },
+            QuadPoint { x: 12., y: 30. },
+            QuadPoint { x: 10., y: 30. },
+        ];
+        let quad = RenderQuad::new(points, Default::default(), 1.);
+        assert_eq!(quad_damage(&quad, &size_info), Rect::new(9, 69, 4, 12));
+
+        // Quads are clamped to the window.
+        let (left, right) = (QuadPoint { x: -5., y: 95. }, QuadPoint { x: 105., y: 105. });
+        let points = [left, right, points[2], points[3]];
+        let quad = RenderQuad::new(points, Default::default(), 1.);
+        assert_eq!(quad_damage(&quad, &size_info), Rect::new(0, 0, 100, 71));
+    }
+
     #[test]
     fn damage_rect_math() {
         let rect_side = 10;
@@ -0,0 +1,561 @@
+//! Serialization of terminal content with its colors and text attributes.
+
+use std::fmt::Write;
+use std::mem;
+
+use alacritty_terminal::ansi::{Color, CursorShape, NamedColor};
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::selection::SelectionRange;
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::color::Rgb;
+use alacritty_terminal::term::Term;
+
+use crate::config::UiConfig;
+use crate::display::color::List;
+use crate::display::content::{background_rgb, foreground_rgb, RenderableCursor};
+
+/// Text attributes preserved in formatted text.
+const STYLE_FLAGS: Flags =
+    Flags::BOLD.union(Flags::ITALIC).union(Flags::ALL_UNDERLINES).union(Flags::STRIKEOUT);
+
+/// Text with its colors resolved to RGB, which can be converted to rich text formats.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormattedText {
+    lines: Vec<Vec<Span>>,
+    /// Number of columns of the widest possible line.
+    columns: usize,
+    cursor: Option<RenderableCursor>,
+    foreground: Rgb,
+    background: Rgb,
+    font_family: String,
+    font_size: f32,
+}
+
+impl FormattedText {
+    /// Formatted text of the terminal's active selection.
+    pub fn from_selection<T>(term: &Term<T>, config: &UiConfig, colors: &List) -> Option<Self> {
+        let range = term.selection.as_ref().and_then(|selection| selection.to_range(term))?;
+        Some(Self::from_range(term, config, colors, range))
+    }
+
+    /// Formatted text of the terminal's viewport, including the `cursor`.
+    pub fn from_screen<T>(
+        term: &Term<T>,
+        config: &UiConfig,
+        colors: &List,
+        cursor: RenderableCursor,
+    ) -> Self {
+        let display_offset = term.grid().display_offset() as i32;
+        let start = Point::new(Line(-display_offset), Column(0));
+        let end =
+            Point::new(Line(term.screen_lines() as i32 - 1 - display_offset), term.last_column());
+        let range = SelectionRange::new(start, end, true);
+
+        let mut text = Self::from_range(term, config, colors, range);
+        if cursor.shape() != CursorShape::Hidden {
+            text.cursor = Some(cursor);
+        }
+        text
+    }
+
+    /// Formatted text between two points.
+    ///
+    /// Lines are only joined where the terminal wrapped them, unless `range` is a block.
+    fn from_range<T>(
+        term: &Term<T>,
+        config: &UiConfig,
+        colors: &List,
+        range: SelectionRange,
+    ) -> Self {
+        let color = |index: usize| term.colors()[index].unwrap_or(colors[index]);
+        let background = color(NamedColor::Background as usize);
+        let last_column = term.last_column();
+
+        let mut lines = Vec::new();
+        let mut spans = Vec::new();
+        for line in (range.start.line.0..=range.end.line.0).map(Line::from) {
+            let start = if range.is_block || line == range.start.line {
+                range.start.column
+            } else {
+                Column(0)
+            };
+            let end = if range.is_block || line == range.end.line {
+                range.end.column
+            } else {
+                last_column
+            };
+
+            let row = &term.grid()[line];
+            for column in (start.0..=end.0).map(Column::from) {
+                let cell = &row[column];
+                if cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+                {
+                    continue;
+                }
+
+                let style = Style::new(config, color, background, cell);
+                // Tabs are followed by blank cells up to the next tab stop.
+                let c = if cell.c == '\t' { ' ' } else { cell.c };
+                push_text(&mut spans, style, c, cell.zerowidth().unwrap_or_default());
+
+                let width = if cell.flags.contains(Flags::WIDE_CHAR) { 2 } else { 1 };
+                spans.last_mut().unwrap().columns += width;
+            }
+
+            // Continue wrapped lines on the same output line.
+            let wrapped = !range.is_block
+                && line != range.end.line
+                && row[last_column].flags.contains(Flags::WRAPLINE);
+            if !wrapped {
+                trim_end(&mut spans);
+                lines.push(mem::take(&mut spans));
+            }
+        }
+
+        let columns = if range.is_block {
+            range.end.column.0 - range.start.column.0 + 1
+        } else {
+            term.columns()
+        };
+        let font = &config.font;
+        Self {
+            foreground: color(NamedColor::Foreground as usize),
+            font_family: font.normal().family.clone(),
+            font_size: font.size().as_f32_pts(),
+            cursor: None,
+            background,
+            columns,
+            lines,
+        }
+    }
+
+    /// Convert to an HTML fragment.
+    pub fn html(&self) -> String {
+        let mut html = format!(
+            "<meta charset=\"utf-8\"><pre style=\"font-family: '{}', monospace; font-size: {}pt; \
+             color: {}; background-color: {};\">",
+            escape_html(&self.font_family),
+            self.font_size,
+            hex(self.foreground),
+            hex(self.background),
+        );
+
+        for (i, line) in self.lines.iter().enumerate() {
+            if i != 0 {
+                html.push('\n');
+            }
+
+            for span in line {
+                let mut css = String::new();
+                if span.style.fg != self.foreground {
+                    let _ = write!(css, "color: {}; ", hex(span.style.fg));
+                }
+                if let Some(bg) = span.style.bg {
+                    let _ = write!(css, "background-color: {}; ", hex(bg));
+                }
+                if span.style.flags.contains(Flags::BOLD) {
+                    css.push_str("font-weight: bold; ");
+                }
+                if span.style.flags.contains(Flags::ITALIC) {
+                    css.push_str("font-style: italic; ");
+                }
+                if let Some(decoration) = text_decoration(span.style.flags) {
+                    let _ = write!(css, "text-decoration: {decoration}; ");
+                }
+
+                let text = escape_html(&span.text);
+                if css.is_empty() {
+                    html.push_str(&text);
+                } else {
+                    let _ = write!(html, "<span style=\"{}\">{text}</span>", css.trim_end());
+                }
+            }
+        }
+
+        html.push_str("</pre>");
+        html
+    }
+
+    /// Convert to an SVG image, with cells of `cell_width` by `cell_height` pixels.
+    ///
+    /// The font size is in points, so the cell size should be in logical pixels.
+    pub fn svg(&self, cell_width: f32, cell_height: f32) -> String {
+        let width = self.columns as f32 * cell_width;
+        let height = self.lines.len() as f32 * cell_height;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\"'{}', monospace\" font-size=\"{}pt\" \
+             xml:space=\"preserve\">\n",
+            escape_html(&self.font_family),
+            self.font_size,
+        );
+        let background = hex(self.background);
+        let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"{background}\"/>");
+
+        // Draw all backgrounds first, since glyphs might extend beyond their cells.
+        for (row, line) in self.lines.iter().enumerate() {
+            let y = row as f32 * cell_height;
+            let mut column = 0;
+            for span in line {
+                if let Some(bg) = span.style.bg {
+                    let x = column as f32 * cell_width;
+                    let span_width = span.columns as f32 * cell_width;
+                    let _ = writeln!(
+                        svg,
+                        "<rect x=\"{x}\" y=\"{y}\" width=\"{span_width}\" \
+                         height=\"{cell_height}\" fill=\"{}\"/>",
+                        hex(bg)
+                    );
+                }
+                column += span.columns;
+            }
+        }
+
+        if let Some(cursor) = self.cursor {
+            let _ = writeln!(svg, "{}", cursor_rect(&cursor, cell_width, cell_height));
+        }
+
+        for (row, line) in self.lines.iter().enumerate() {
+            // Center glyphs vertically within their cells.
+            let y = (row as f32 + 0.5) * cell_height;
+            let mut column = 0;
+            for span in line {
+                let x = column as f32 * cell_width;
+                column += span.columns;
+
+                if span.text.trim().is_empty() && span.style.is_blank() {
+                    continue;
+                }
+
+                // Stretch the text across its cells, in case the font is missing.
+                let _ = write!(
+                    svg,
+                    "<text x=\"{x}\" y=\"{y}\" dominant-baseline=\"central\" fill=\"{}\" \
+                     textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\"",
+                    hex(span.style.fg),
+                    span.columns as f32 * cell_width,
+                );
+                if span.style.flags.contains(Flags::BOLD) {
+                    svg.push_str(" font-weight=\"bold\"");
+                }
+                if span.style.flags.contains(Flags::ITALIC) {
+                    svg.push_str(" font-style=\"italic\"");
+                }
+                if let Some(decoration) = text_decoration(span.style.flags) {
+                    let _ = write!(svg, " text-decoration=\"{decoration}\"");
+                }
+                let _ = writeln!(svg, ">{}</text>", escape_html(&span.text));
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Convert to an RTF document.
+    pub fn rtf(&self) -> String {
+        // Index zero is reserved for the automatic color.
+        let mut colors = vec![self.foreground, self.background];
+        for style in self.lines.iter().flatten().map(|span| span.style) {
+            for color in [Some(style.fg), style.bg].into_iter().flatten() {
+                if !colors.contains(&color) {
+                    colors.push(color);
+                }
+            }
+        }
+        let index = |color: Rgb| colors.iter().position(|c| *c == color).unwrap() + 1;
+
+        let mut rtf = String::from("{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0\\fmodern ");
+        rtf.push_str(&escape_rtf(&self.font_family));
+        rtf.push_str(";}}{\\colortbl;");
+        for color in &colors {
+            let _ = write!(rtf, "\\red{}\\green{}\\blue{};", color.r, color.g, color.b);
+        }
+        let _ = writeln!(
+            rtf,
+            "}}\\f0\\fs{}\\cf1\\cb2\\highlight2",
+            (self.font_size * 2.).round() as u32
+        );
+
+        for (i, line) in self.lines.iter().enumerate() {
+            if i != 0 {
+                rtf.push_str("\\line\n");
+            }
+
+            for span in line {
+                let bg = index(span.style.bg.unwrap_or(self.background));
+                let _ = write!(rtf, "{{\\cf{}\\cb{bg}\\highlight{bg}", index(span.style.fg));
+                let attributes = [
+                    (Flags::BOLD, "\\b"),
+                    (Flags::ITALIC, "\\i"),
+                    (Flags::ALL_UNDERLINES, "\\ul"),
+                    (Flags::STRIKEOUT, "\\strike"),
+                ];
+                for (flags, control) in attributes {
+                    if span.style.flags.intersects(flags) {
+                        rtf.push_str(control);
+                    }
+                }
+                rtf.push(' ');
+                rtf.push_str(&escape_rtf(&span.text));
+                rtf.push('}');
+            }
+        }
+
+        rtf.push('}');
+        rtf
+    }
+}
+
+/// Characters sharing the same style.
+#[derive(Debug, Clone, PartialEq)]
+struct Span {
+    style: Style,
+    text: String,
+    /// Number of cells occupied by the text.
+    columns: usize,
+}
+
+/// Resolved colors and attributes of a cell.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Style {
+    fg: Rgb,
+    /// Background color, unless it's the default background.
+    bg: Option<Rgb>,
+    flags: Flags,
+}
+
+impl Style {
+    fn new(config: &UiConfig, color: impl Fn(usize) -> Rgb, background: Rgb, cell: &Cell) -> Self {
+        let mut fg = foreground_rgb(config, &color, cell.fg, cell.flags);
+        let mut bg = (cell.bg != Color::Named(NamedColor::Background))
+            .then(|| background_rgb(&color, cell.bg));
+
+        if cell.flags.contains(Flags::INVERSE) {
+            bg = Some(mem::replace(&mut fg, bg.unwrap_or(background)));
+        }
+
+        if cell.flags.contains(Flags::HIDDEN) {
+            fg = bg.unwrap_or(background);
+        }
+
+        Self { fg, bg, flags: cell.flags & STYLE_FLAGS }
+    }
+
+    /// Check if blank characters with this style are invisible.
+    fn is_blank(&self) -> bool {
+        self.bg.is_none() && !self.flags.intersects(Flags::ALL_UNDERLINES | Flags::STRIKEOUT)
+    }
+}
+
+/// Append characters to the last span, starting a new one if the style changed.
+fn push_text(spans: &mut Vec<Span>, style: Style, c: char, zerowidth: &[char]) {
+    let span = match spans.last_mut() {
+        Some(span) if span.style == style => span,
+        _ => {
+            spans.push(Span { style, text: String::new(), columns: 0 });
+            spans.last_mut().unwrap()
+        },
+    };
+
+    span.text.push(c);
+    span.text.extend(zerowidth);
+}
+
+/// Remove invisible trailing whitespace from a line.
+fn trim_end(spans: &mut Vec<Span>) {
+    while let Some(span) = spans.last_mut() {
+        if !span.style.is_blank() {
+            return;
+        }
+
+        let trimmed = span.text.trim_end_matches(' ').len();
+        span.columns -= span.text.len() - trimmed;
+        span.text.truncate(trimmed);
+        if !span.text.is_empty() {
+            return;
+        }
+
+        spans.pop();
+    }
+}
+
+/// SVG element drawing the cursor.
+fn cursor_rect(cursor: &RenderableCursor, cell_width: f32, cell_height: f32) -> String {
+    let point = cursor.point();
+    let x = point.column.0 as f32 * cell_width;
+    let y = point.line as f32 * cell_height;
+    let width = if cursor.is_wide() { cell_width * 2. } else { cell_width };
+    let thickness = (cell_height / 10.).max(1.);
+    let color = hex(cursor.color());
+
+    let (x, y, width, height) = match cursor.shape() {
+        CursorShape::Block => (x, y, width, cell_height),
+        CursorShape::Underline => (x, y + cell_height - thickness, width, thickness),
+        CursorShape::Beam => (x, y, thickness, cell_height),
+        CursorShape::HollowBlock | CursorShape::Hidden => {
+            // Strokes are centered on the outline of the rectangle.
+            let (x, y) = (x + thickness / 2., y + thickness / 2.);
+            return format!(
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{}\" fill=\"none\" \
+                 stroke=\"{color}\" stroke-width=\"{thickness}\"/>",
+                width - thickness,
+                cell_height - thickness,
+            );
+        },
+    };
+
+    format!("<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" fill=\"{color}\"/>")
+}
+
+/// CSS text decoration of underlined and struck out text.
+fn text_decoration(flags: Flags) -> Option<String> {
+    let decorations = [(Flags::ALL_UNDERLINES, "underline"), (Flags::STRIKEOUT, "line-through")];
+    let decorations: Vec<_> = decorations
+        .into_iter()
+        .filter(|(decoration_flags, _)| flags.intersects(*decoration_flags))
+        .map(|(_, decoration)| decoration)
+        .collect();
+
+    (!decorations.is_empty()).then(|| decorations.join(" "))
+}
+
+/// CSS hex notation of a color.
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape RTF control characters, encoding everything outside of ASCII as UTF-16 code units.
+fn escape_rtf(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            ' '..='~' => escaped.push(c),
+            c => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    // Code units are signed, followed by a `?` for readers without unicode support.
+                    let _ = write!(escaped, "\\u{}?", *unit as i16);
+                }
+            },
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FG: Rgb = Rgb::new(0xff, 0xff, 0xff);
+    const BG: Rgb = Rgb::new(0, 0, 0);
+    const RED: Rgb = Rgb::new(0xff, 0, 0);
+
+    fn span(style: Style, text: &str) -> Span {
+        Span { style, text: text.into(), columns: text.chars().count() }
+    }
+
+    fn text(lines: Vec<Vec<(Style, &str)>>) -> FormattedText {
+        let lines: Vec<Vec<_>> = lines
+            .into_iter()
+            .map(|line| line.into_iter().map(|(style, text)| span(style, text)).collect())
+            .collect();
+
+        FormattedText {
+            columns: 10,
+            cursor: None,
+            lines,
+            foreground: FG,
+            background: BG,
+            font_family: String::from("Mono"),
+            font_size: 11.,
+        }
+    }
+
+    #[test]
+    fn html() {
+        let plain = Style { fg: FG, bg: None, flags: Flags::empty() };
+        let error = Style { fg: RED, bg: Some(FG), flags: Flags::BOLD | Flags::UNDERCURL };
+        let text = text(vec![vec![(plain, "a <b> & "), (error, "err")], vec![(plain, "c")]]);
+
+        assert_eq!(
+            text.html(),
+            "<meta charset=\"utf-8\"><pre style=\"font-family: 'Mono', monospace; font-size: \
+             11pt; color: #ffffff; background-color: #000000;\">a &lt;b&gt; &amp; <span \
+             style=\"color: #ff0000; background-color: #ffffff; font-weight: bold; \
+             text-decoration: underline;\">err</span>\nc</pre>"
+        );
+    }
+
+    #[test]
+    fn rtf() {
+        let plain = Style { fg: FG, bg: None, flags: Flags::empty() };
+        let error = Style { fg: RED, bg: None, flags: Flags::ITALIC };
+        let text = text(vec![vec![(plain, "{x}\\ "), (error, "é😀")], vec![]]);
+
+        assert_eq!(
+            text.rtf(),
+            "{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0\\fmodern \
+             Mono;}}{\\colortbl;\\red255\\green255\\blue255;\\red0\\green0\\blue0;\\red255\\\
+             green0\\blue0;}\\f0\\fs22\\cf1\\cb2\\highlight2\n{\\cf1\\cb2\\highlight2 \\{x\\}\\\\ \
+             }{\\cf3\\cb2\\highlight2\\i \\u233?\\u-10179?\\u-8704?}\\line\n}"
+        );
+    }
+
+    #[test]
+    fn svg() {
+        let plain = Style { fg: FG, bg: None, flags: Flags::empty() };
+        let marked = Style { fg: BG, bg: Some(RED), flags: Flags::STRIKEOUT };
+        let mut text = text(vec![vec![(plain, "$ "), (marked, "x")]]);
+        text.cursor =
+            Some(RenderableCursor::new(Point::new(0, Column(3)), CursorShape::Beam, FG, false));
+
+        assert_eq!(
+            text.svg(8., 16.),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"80\" height=\"16\" viewBox=\"0 0 \
+             80 16\" font-family=\"'Mono', monospace\" font-size=\"11pt\" \
+             xml:space=\"preserve\">\n<rect width=\"100%\" height=\"100%\" \
+             fill=\"#000000\"/>\n<rect x=\"16\" y=\"0\" width=\"8\" height=\"16\" \
+             fill=\"#ff0000\"/>\n<rect x=\"24\" y=\"0\" width=\"1.6\" height=\"16\" \
+             fill=\"#ffffff\"/>\n<text x=\"0\" y=\"8\" dominant-baseline=\"central\" \
+             fill=\"#ffffff\" textLength=\"16\" lengthAdjust=\"spacingAndGlyphs\">$ \
+             </text>\n<text x=\"16\" y=\"8\" dominant-baseline=\"central\" fill=\"#000000\" \
+             textLength=\"8\" lengthAdjust=\"spacingAndGlyphs\" \
+             text-decoration=\"line-through\">x</text>\n</svg>\n"
+        );
+    }
+
+    #[test]
+    fn trailing_blanks() {
+        let plain = Style { fg: FG, bg: None, flags: Flags::empty() };
+        let marked = Style { fg: FG, bg: Some(RED), flags: Flags::empty() };
+
+        let mut spans = vec![span(plain, "a  "), span(plain, "   ")];
+        trim_end(&mut spans);
+        assert_eq!(spans, vec![span(plain, "a")]);
+
+        let mut spans = vec![span(marked, "a  ")];
+        trim_end(&mut spans);
+        assert_eq!(spans[0].text, "a  ");
+    }
+}
@@ -0,0 +1,34 @@
+//! Buffers reused between frames.
+
+use crate::display::content::RenderableCell;
+use crate::renderer::quads::RenderQuad;
+use crate::renderer::rects::{RenderLines, RenderRect};
+
+/// Buffers filled while drawing a frame.
+///
+/// The buffers are cleared between frames without releasing their memory, so drawing a frame
+/// does not need to allocate once the window's content has settled.
+#[derive(Default)]
+pub struct FrameBuffers {
+    /// Visible cells of the grid.
+    pub cells: Vec<RenderableCell>,
+
+    /// Underlines and strikeouts of the visible cells.
+    pub lines: RenderLines,
+
+    /// Rectangles drawn above the grid.
+    pub rects: Vec<RenderRect>,
+
+    /// Quads drawn above the grid.
+    pub quads: Vec<RenderQuad>,
+}
+
+impl FrameBuffers {
+    /// Remove the content of the previous frame.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.lines.clear();
+        self.rects.clear();
+        self.quads.clear();
+    }
+}
@@ -0,0 +1,177 @@
+//! Styling of text matching the configured highlighting rules.
+//!
+//! Matches are cached by the text of each line, so only lines which changed since the last frame
+//! have to be searched again. Matches never span multiple lines.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Direction, Line, Point};
+use alacritty_terminal::term::search::RegexIter;
+use alacritty_terminal::term::Term;
+
+use crate::config::highlights::HighlightRule;
+
+/// Highlighted text in the viewport.
+#[derive(Debug, Default)]
+pub struct HighlightState {
+    /// Configured highlighting rules.
+    rules: Vec<HighlightRule>,
+
+    /// Whether each rule is currently applied.
+    enabled: Vec<bool>,
+
+    /// Matches of each line in the viewport.
+    lines: Vec<LineMatches>,
+}
+
+/// Highlighted text of a single line.
+#[derive(Debug, Default, Clone)]
+struct LineMatches {
+    /// Hash of the line's text.
+    hash: u64,
+
+    /// Matching columns and the index of their rule, ordered by rule.
+    matches: Vec<(RangeInclusive<Column>, usize)>,
+}
+
+impl HighlightState {
+    pub fn new(rules: &[HighlightRule]) -> Self {
+        let mut highlights = Self::default();
+        highlights.update_config(rules);
+        highlights
+    }
+
+    /// Update to new highlighting rules, resetting rules toggled at runtime.
+    pub fn update_config(&mut self, rules: &[HighlightRule]) {
+        if self.rules != rules {
+            self.enabled = rules.iter().map(|rule| rule.enabled).collect();
+            self.rules = rules.to_vec();
+            self.lines.clear();
+        }
+    }
+
+    /// Check if any rule is applied.
+    pub fn active(&self) -> bool {
+        self.enabled.contains(&true)
+    }
+
+    /// Toggle the rule at an index.
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(enabled) = self.enabled.get_mut(index) {
+            *enabled = !*enabled;
+            self.lines.clear();
+        }
+    }
+
+    /// Update the matches for the terminal's viewport.
+    pub fn update<T>(&mut self, term: &Term<T>) {
+        if !self.active() {
+            self.lines.clear();
+            return;
+        }
+
+        // Reuse the matches of unchanged lines, even after they've been scrolled.
+        let cache: HashMap<u64, LineMatches> =
+            self.lines.drain(..).map(|line| (line.hash, line)).collect();
+
+        let display_offset = term.grid().display_offset();
+        for viewport_line in 0..term.screen_lines() {
+            let line = Line(viewport_line as i32 - display_offset as i32);
+
+            let mut hasher = DefaultHasher::new();
+            for cell in &term.grid()[line][..] {
+                cell.c.hash(&mut hasher);
+            }
+            let hash = hasher.finish();
+
+            let line_matches = match cache.get(&hash) {
+                Some(line_matches) => line_matches.clone(),
+                None => LineMatches { hash, matches: self.line_matches(term, line) },
+            };
+            self.lines.push(line_matches);
+        }
+    }
+
+    /// Rule styling the cell at a viewport position.
+    pub fn rule_at(&self, point: Point<usize>) -> Option<&HighlightRule> {
+        let line = self.lines.get(point.line)?;
+        let (_, index) =
+            line.matches.iter().find(|(columns, _)| columns.contains(&point.column))?;
+        self.rules.get(*index)
+    }
+
+    /// Search a line for matches of all enabled rules.
+    fn line_matches<T>(&self, term: &Term<T>, line: Line) -> Vec<(RangeInclusive<Column>, usize)> {
+        let start = Point::new(line, Column(0));
+        let end = Point::new(line, term.last_column());
+
+        let mut matches = Vec::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            if !self.enabled[index] {
+                continue;
+            }
+
+            rule.regex.with_compiled(|regex| {
+                for regex_match in RegexIter::new(start, end, Direction::Right, term, regex) {
+                    let columns = regex_match.start().column..=regex_match.end().column;
+                    matches.push((columns, index));
+                }
+            });
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::term::test::mock_term;
+
+    use crate::config::highlights::Highlights;
+
+    fn highlights() -> HighlightState {
+        let highlights: Highlights = toml::from_str(
+            r#"
+            rules = [
+                { regex = "ERROR", bold = true },
+                { regex = "[A-Z]+", underline = true, enabled = false },
+            ]
+            "#,
+        )
+        .unwrap();
+        HighlightState::new(&highlights.rules)
+    }
+
+    #[test]
+    fn matching_rules() {
+        let term = mock_term("an ERROR here\r\nOK\r\n");
+        let mut highlights = highlights();
+        highlights.update(&term);
+
+        let rule_at = |highlights: &HighlightState, line, column| {
+            highlights.rule_at(Point::new(line, Column(column))).map(|rule| rule.bold)
+        };
+        assert_eq!(rule_at(&highlights, 0, 2), None);
+        assert_eq!(rule_at(&highlights, 0, 3), Some(true));
+        assert_eq!(rule_at(&highlights, 0, 7), Some(true));
+        assert_eq!(rule_at(&highlights, 0, 8), None);
+        assert_eq!(rule_at(&highlights, 1, 0), None);
+
+        // Earlier rules take precedence over the toggled rule.
+        highlights.toggle(1);
+        highlights.update(&term);
+        assert_eq!(rule_at(&highlights, 0, 3), Some(true));
+        assert_eq!(rule_at(&highlights, 1, 0), Some(false));
+
+        highlights.toggle(0);
+        highlights.toggle(1);
+        assert!(!highlights.active());
+        highlights.update(&term);
+        assert_eq!(rule_at(&highlights, 0, 3), None);
+    }
+}
@@ -1,17 +1,19 @@
 use std::cmp::Reverse;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use ahash::RandomState;
 use winit::keyboard::ModifiersState;
 
 use alacritty_terminal::grid::{BidirectionalIterator, Dimensions};
 use alacritty_terminal::index::{Boundary, Column, Direction, Line, Point};
-use alacritty_terminal::term::cell::Hyperlink;
+use alacritty_terminal::term::cell::{Hyperlink, HyperlinkId};
 use alacritty_terminal::term::search::{Match, RegexIter, RegexSearch};
 use alacritty_terminal::term::{Term, TermMode};
 
-use crate::config::ui_config::{Hint, HintAction};
+use crate::config::ui_config::{Hint, HintAction, HintLabelOrder, Hints, LazyRegex};
 use crate::config::UiConfig;
 
 /// Maximum number of linewraps followed outside of the viewport during search highlighting.
@@ -20,6 +22,15 @@ pub const MAX_SEARCH_LINES: usize = 100;
 /// Percentage of characters in the hints alphabet used for the last character.
 const HINT_SPLIT_PERCENTAGE: f32 = 0.5;
 
+/// Regex for path candidates, which require at least a `/` or `.` in them.
+const PATH_REGEX: &str = r"[~.]?/?[\w.+@~-]*[/.][\w./+@~-]+";
+
+/// Time after which the existence of a path candidate is checked again.
+const PATH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum number of path candidates with a cached existence check.
+const MAX_CACHED_PATHS: usize = 1024;
+
 /// Keyboard regex hint state.
 pub struct HintState {
     /// Hint currently in use.
@@ -28,6 +39,12 @@ pub struct HintState {
     /// Alphabet for hint labels.
     alphabet: String,
 
+    /// Order in which matches receive labels.
+    label_order: HintLabelOrder,
+
+    /// Use labels of equal length for all matches.
+    uniform_labels: bool,
+
     /// Visible matches.
     matches: Vec<Match>,
 
@@ -36,17 +53,31 @@ pub struct HintState {
 
     /// Keys pressed for hint selection.
     keys: Vec<char>,
+
+    /// Alternate action selected for the next hint.
+    alternate_action: Option<HintAction>,
+
+    /// Directory relative paths are resolved against.
+    working_directory: Option<PathBuf>,
+
+    /// Path candidates for path hints.
+    paths: PathHints,
 }
 
 impl HintState {
     /// Initialize an inactive hint state.
-    pub fn new<S: Into<String>>(alphabet: S) -> Self {
+    pub fn new(hints: &Hints) -> Self {
         Self {
-            alphabet: alphabet.into(),
+            alphabet: hints.alphabet().into(),
+            label_order: hints.label_order,
+            uniform_labels: hints.uniform_labels,
             hint: Default::default(),
             matches: Default::default(),
             labels: Default::default(),
             keys: Default::default(),
+            alternate_action: Default::default(),
+            working_directory: Default::default(),
+            paths: Default::default(),
         }
     }
 
@@ -56,7 +87,8 @@ impl HintState {
     }
 
     /// Start the hint selection process.
-    pub fn start(&mut self, hint: Hint) {
+    pub fn start(&mut self, hint: Hint, working_directory: Option<PathBuf>) {
+        self.working_directory = working_directory;
         self.hint = Some(hint);
     }
 
@@ -65,6 +97,7 @@ impl HintState {
         self.matches.clear();
         self.labels.clear();
         self.keys.clear();
+        self.alternate_action = None;
         self.hint = None;
     }
 
@@ -99,6 +132,22 @@ impl HintState {
             });
         }
 
+        // Add paths to existing files.
+        if hint.content.paths {
+            let working_directory = self.working_directory.as_deref();
+            self.matches.extend(self.paths.visible_matches(term, working_directory));
+        }
+
+        // Add output of recent commands which is at least partially visible.
+        if hint.content.command_outputs > 0 {
+            let viewport_start = Line(-(term.grid().display_offset() as i32));
+            let viewport_end = viewport_start + term.bottommost_line();
+            let outputs = term.command_outputs(hint.content.command_outputs);
+            self.matches.extend(outputs.into_iter().filter(|output| {
+                output.end().line >= viewport_start && output.start().line <= viewport_end
+            }));
+        }
+
         // Cancel highlight with no visible matches.
         if self.matches.is_empty() {
             self.stop();
@@ -109,14 +158,43 @@ impl HintState {
         self.matches.sort_by_key(|bounds| (*bounds.start(), Reverse(*bounds.end())));
         self.matches.dedup_by_key(|bounds| *bounds.start());
 
-        let mut generator = HintLabels::new(&self.alphabet, HINT_SPLIT_PERCENTAGE);
         let match_count = self.matches.len();
         let keys_len = self.keys.len();
 
+        // Order in which matches are assigned labels, starting with the shortest one.
+        let mut order: Vec<usize> = (0..match_count).collect();
+        match self.label_order {
+            HintLabelOrder::BottomFirst => order.reverse(),
+            HintLabelOrder::TopFirst => (),
+            HintLabelOrder::ClosestToCursor => {
+                let cursor = if term.mode().contains(TermMode::VI) {
+                    term.vi_mode_cursor.point
+                } else {
+                    term.grid().cursor.point
+                };
+
+                let columns = term.columns() as i64;
+                let matches = &self.matches;
+                order.sort_by_key(|&i| {
+                    let start = matches[i].start();
+                    let lines = (start.line.0 - cursor.line.0) as i64;
+                    (lines * columns + start.column.0 as i64 - cursor.column.0 as i64).abs()
+                });
+            },
+        }
+
+        let alphabet: Vec<char> = self.alphabet.chars().collect();
+        let uniform_length = uniform_label_length(alphabet.len(), match_count);
+        let mut generator = HintLabels::new(&self.alphabet, HINT_SPLIT_PERCENTAGE);
+
         // Get the label for each match.
         self.labels.resize(match_count, Vec::new());
-        for i in (0..match_count).rev() {
-            let mut label = generator.next();
+        for (n, i) in order.into_iter().enumerate() {
+            let mut label = if self.uniform_labels {
+                uniform_label(&alphabet, n, uniform_length)
+            } else {
+                generator.next()
+            };
             if label.len() >= keys_len && label[..keys_len] == self.keys[..] {
                 self.labels[i] = label.split_off(keys_len);
             } else {
@@ -129,9 +207,7 @@ impl HintState {
     pub fn keyboard_input<T>(&mut self, term: &Term<T>, c: char) -> Option<HintMatch> {
         match c {
             // Use backspace to remove the last character pressed.
-            '\x08' | '\x1f' => {
-                self.keys.pop();
-            },
+            '\x08' | '\x1f' if self.keys.pop().is_none() => self.alternate_action = None,
             // Cancel hint highlighting on ESC/Ctrl+c.
             '\x1b' | '\x03' => self.stop(),
             _ => (),
@@ -142,6 +218,15 @@ impl HintState {
 
         let hint = self.hint.as_ref()?;
 
+        // Select an alternate action before the first label character.
+        if self.keys.is_empty() {
+            let alternate = hint.alternate_actions.iter().find(|alternate| alternate.key == c);
+            if let Some(alternate) = alternate {
+                self.alternate_action = Some(alternate.action.clone());
+                return None;
+            }
+        }
+
         // Find the last label starting with the input character.
         let mut labels = self.labels.iter().enumerate().rev();
         let (index, label) = labels.find(|(_, label)| !label.is_empty() && label[0] == c)?;
@@ -149,7 +234,20 @@ impl HintState {
         // Check if the selected label is fully matched.
         if label.len() == 1 {
             let bounds = self.matches[index].clone();
-            let action = hint.action.clone();
+
+            // Hyperlinks take precedence over regex matches.
+            let hyperlink = term.hyperlink_at(*bounds.start());
+            let regex = hint.content.regex.clone().filter(|_| hyperlink.is_none());
+            let text = term.bounds_to_string(*bounds.start(), *bounds.end());
+            let working_directory = self.working_directory.as_deref();
+            let path =
+                hint.content.paths.then(|| self.paths.resolve(&text, working_directory)).flatten();
+
+            let url = hyperlink.as_ref().map_or(text.as_str(), |hyperlink| hyperlink.uri());
+            let action = match self.alternate_action.take() {
+                Some(action) => action,
+                None => hint.action_for(url).clone(),
+            };
 
             // Exit hint mode unless it requires explicit dismissal.
             if hint.persist {
@@ -158,9 +256,7 @@ impl HintState {
                 self.stop();
             }
 
-            // Hyperlinks take precedence over regex matches.
-            let hyperlink = term.grid()[*bounds.start()].hyperlink();
-            Some(HintMatch { action, bounds, hyperlink })
+            Some(HintMatch { action, bounds, hyperlink, regex, path })
         } else {
             // Store character to preserve the selection.
             self.keys.push(c);
@@ -179,17 +275,27 @@ impl HintState {
         &self.matches
     }
 
-    /// Update the alphabet used for hint labels.
-    pub fn update_alphabet(&mut self, alphabet: &str) {
-        if self.alphabet != alphabet {
-            self.alphabet = alphabet.to_owned();
+    /// Path candidates for path hints.
+    pub fn paths_mut(&mut self) -> &mut PathHints {
+        &mut self.paths
+    }
+
+    /// Update the hint label configuration.
+    pub fn update_config(&mut self, hints: &Hints) {
+        if self.alphabet != hints.alphabet()
+            || self.label_order != hints.label_order
+            || self.uniform_labels != hints.uniform_labels
+        {
+            self.alphabet = hints.alphabet().to_owned();
+            self.label_order = hints.label_order;
+            self.uniform_labels = hints.uniform_labels;
             self.keys.clear();
         }
     }
 }
 
 /// Hint match which was selected by the user.
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct HintMatch {
     /// Action for handling the text.
     action: HintAction,
@@ -198,8 +304,24 @@ pub struct HintMatch {
     bounds: Match,
 
     hyperlink: Option<Hyperlink>,
+
+    /// Regex which produced the match, used for extracting capture groups.
+    regex: Option<LazyRegex>,
+
+    /// Resolved path of a file path match.
+    path: Option<PathBuf>,
 }
 
+impl PartialEq for HintMatch {
+    fn eq(&self, other: &Self) -> bool {
+        // The compiled regex can't be compared, so the action identifies the hint instead.
+        self.action == other.action
+            && self.bounds == other.bounds
+            && self.hyperlink == other.hyperlink
+    }
+}
+impl Eq for HintMatch {}
+
 impl HintMatch {
     #[inline]
     pub fn should_highlight(&self, point: Point, pointed_hyperlink: Option<&Hyperlink>) -> bool {
@@ -219,6 +341,37 @@ impl HintMatch {
     pub fn hyperlink(&self) -> Option<&Hyperlink> {
         self.hyperlink.as_ref()
     }
+
+    /// Absolute path of the file matched by a path hint.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Extract the regex capture groups from the hint's text.
+    pub fn captures(&self, text: &str) -> Option<Vec<Option<String>>> {
+        self.regex.as_ref()?.with_compiled(|regex| regex.captures(text)).flatten()
+    }
+}
+
+/// Minimum label length required to give every match a unique uniform label.
+fn uniform_label_length(alphabet_len: usize, match_count: usize) -> usize {
+    let mut length = 1;
+    let mut capacity = alphabet_len;
+    while capacity < match_count {
+        length += 1;
+        capacity = capacity.saturating_mul(alphabet_len);
+    }
+    length
+}
+
+/// Create the nth label of a fixed length, using the alphabet's characters as digits.
+fn uniform_label(alphabet: &[char], mut index: usize, length: usize) -> Vec<char> {
+    let mut label = vec![alphabet[0]; length];
+    for c in label.iter_mut().rev() {
+        *c = alphabet[index % alphabet.len()];
+        index /= alphabet.len();
+    }
+    label
 }
 
 /// Generator for creating new hint labels.
@@ -286,6 +439,92 @@ impl HintLabels {
     }
 }
 
+/// Path candidates for path hints.
+///
+/// Checking whether a file exists requires a filesystem access, so the result is cached for
+/// [`PATH_CHECK_INTERVAL`] instead of checking the visible paths on every frame.
+#[derive(Default)]
+pub struct PathHints {
+    /// Compiled regex for path candidates.
+    regex: Option<RegexSearch>,
+
+    /// Absolute path candidates, with the result and time of their last existence check.
+    checked: HashMap<PathBuf, (bool, Instant)>,
+}
+
+impl PathHints {
+    /// Iterate over all visible paths of existing files.
+    fn visible_matches<'a, T>(
+        &'a mut self,
+        term: &'a Term<T>,
+        working_directory: Option<&'a Path>,
+    ) -> impl Iterator<Item = Match> + 'a {
+        let regex = self.regex.get_or_insert_with(|| RegexSearch::new(PATH_REGEX).unwrap());
+        let checked = &mut self.checked;
+
+        visible_regex_match_iter(term, regex).filter(move |rm| {
+            let text = term.bounds_to_string(*rm.start(), *rm.end());
+            resolve_path(checked, &text, working_directory).is_some()
+        })
+    }
+
+    /// Retrieve the path of an existing file at the specified point, with its range.
+    fn path_at<T>(
+        &mut self,
+        term: &Term<T>,
+        point: Point,
+        working_directory: Option<&Path>,
+    ) -> Option<(PathBuf, Match)> {
+        let regex = self.regex.get_or_insert_with(|| RegexSearch::new(PATH_REGEX).unwrap());
+        let bounds = regex_match_at(term, point, regex, false)?;
+        let text = term.bounds_to_string(*bounds.start(), *bounds.end());
+        let path = resolve_path(&mut self.checked, &text, working_directory)?;
+        Some((path, bounds))
+    }
+
+    /// Resolve a path candidate, returning its absolute path if the file exists.
+    fn resolve(&mut self, text: &str, working_directory: Option<&Path>) -> Option<PathBuf> {
+        resolve_path(&mut self.checked, text, working_directory)
+    }
+}
+
+/// Resolve a path candidate, returning its absolute path if the file exists.
+fn resolve_path(
+    checked: &mut HashMap<PathBuf, (bool, Instant)>,
+    text: &str,
+    working_directory: Option<&Path>,
+) -> Option<PathBuf> {
+    let path = match text.strip_prefix("~/") {
+        Some(relative) => home::home_dir()?.join(relative),
+        None => match working_directory {
+            Some(working_directory) => working_directory.join(text),
+            None => PathBuf::from(text),
+        },
+    };
+
+    // Relative paths can't be resolved without a working directory.
+    if !path.is_absolute() {
+        return None;
+    }
+
+    // Drop outdated checks once the cache is full.
+    let now = Instant::now();
+    if checked.len() >= MAX_CACHED_PATHS && !checked.contains_key(&path) {
+        checked.retain(|_, (_, time)| now.duration_since(*time) < PATH_CHECK_INTERVAL);
+        if checked.len() >= MAX_CACHED_PATHS {
+            checked.clear();
+        }
+    }
+
+    let (exists, time) = checked.entry(path.clone()).or_insert_with(|| (path.exists(), now));
+    if now.duration_since(*time) >= PATH_CHECK_INTERVAL {
+        *exists = path.exists();
+        *time = now;
+    }
+
+    exists.then_some(path)
+}
+
 /// Iterate over all visible regex matches.
 pub fn visible_regex_match_iter<'a, T>(
     term: &'a Term<T>,
@@ -308,16 +547,13 @@ pub fn visible_unique_hyperlinks_iter<T>(term: &Term<T>) -> impl Iterator<Item =
     let mut display_iter = term.grid().display_iter().peekable();
 
     // Avoid creating hints for the same hyperlinks, but from a different places.
-    let mut unique_hyperlinks = HashSet::<Hyperlink, RandomState>::default();
+    let mut unique_hyperlinks = HashSet::<HyperlinkId, RandomState>::default();
 
     iter::from_fn(move || {
         // Find the start of the next unique hyperlink.
         let (cell, hyperlink) = display_iter.find_map(|cell| {
             let hyperlink = cell.hyperlink()?;
-            (!unique_hyperlinks.contains(&hyperlink)).then(|| {
-                unique_hyperlinks.insert(hyperlink.clone());
-                (cell, hyperlink)
-            })
+            unique_hyperlinks.insert(hyperlink).then_some((cell, hyperlink))
         })?;
 
         let start = cell.point;
@@ -327,7 +563,7 @@ pub fn visible_unique_hyperlinks_iter<T>(term: &Term<T>) -> impl Iterator<Item =
         while let Some(next_cell) = display_iter.peek() {
             // Cell at display iter doesn't match, yield the hyperlink and start over with
             // `find_map`.
-            if next_cell.hyperlink().as_ref() != Some(&hyperlink) {
+            if next_cell.hyperlink() != Some(hyperlink) {
                 break;
             }
 
@@ -361,29 +597,37 @@ fn regex_match_at<T>(
 pub fn highlighted_at<T>(
     term: &Term<T>,
     config: &UiConfig,
+    paths: &mut PathHints,
     point: Point,
     mouse_mods: ModifiersState,
 ) -> Option<HintMatch> {
     let mouse_mode = term.mode().intersects(TermMode::MOUSE_MODE);
 
     config.hints.enabled.iter().find_map(|hint| {
-        // Check if all required modifiers are pressed.
-        let highlight = hint.mouse.map_or(false, |mouse| {
-            mouse.enabled
-                && mouse_mods.contains(mouse.mods.0)
-                && (!mouse_mode || mouse_mods.contains(ModifiersState::SHIFT))
-        });
-        if !highlight {
+        let mouse = hint.mouse.filter(|mouse| mouse.enabled)?;
+        if mouse_mode && !mouse_mods.contains(ModifiersState::SHIFT) {
             return None;
         }
 
+        // Check if all modifiers required by the hint or its scheme's launcher are pressed.
+        let launcher_mods = |url: &str| {
+            let mods = hint.launcher(url).and_then(|launcher| launcher.mods);
+            mouse_mods.contains(mods.unwrap_or(mouse.mods).0)
+        };
+
         if let Some((hyperlink, bounds)) =
             hint.content.hyperlinks.then(|| hyperlink_at(term, point)).flatten()
         {
+            if !launcher_mods(hyperlink.uri()) {
+                return None;
+            }
+
             return Some(HintMatch {
                 bounds,
-                action: hint.action.clone(),
+                action: hint.action_for(hyperlink.uri()).clone(),
                 hyperlink: Some(hyperlink),
+                regex: None,
+                path: None,
             });
         }
 
@@ -391,7 +635,32 @@ pub fn highlighted_at<T>(
             regex.with_compiled(|regex| regex_match_at(term, point, regex, hint.post_processing))
         });
         if let Some(bounds) = bounds.flatten() {
-            return Some(HintMatch { bounds, action: hint.action.clone(), hyperlink: None });
+            let text = term.bounds_to_string(*bounds.start(), *bounds.end());
+            if !launcher_mods(&text) {
+                return None;
+            }
+
+            let regex = hint.content.regex.clone();
+            let action = hint.action_for(&text).clone();
+            return Some(HintMatch { bounds, action, hyperlink: None, regex, path: None });
+        }
+
+        // Relative paths are resolved against the directory reported by the shell.
+        let path = hint.content.paths.then(|| paths.path_at(term, point, term.working_directory()));
+        if let Some((path, bounds)) = path.flatten() {
+            let text = path.to_string_lossy();
+            if !launcher_mods(&text) {
+                return None;
+            }
+
+            let action = hint.action_for(&text).clone();
+            return Some(HintMatch {
+                bounds,
+                action,
+                hyperlink: None,
+                regex: None,
+                path: Some(path),
+            });
         }
 
         None
@@ -400,7 +669,7 @@ pub fn highlighted_at<T>(
 
 /// Retrieve the hyperlink with its range, if there is one at the specified point.
 fn hyperlink_at<T>(term: &Term<T>, point: Point) -> Option<(Hyperlink, Match)> {
-    let hyperlink = term.grid()[point].hyperlink()?;
+    let id = term.grid()[point].hyperlink()?;
 
     let viewport_start = Line(-(term.grid().display_offset() as i32));
     let viewport_end = viewport_start + term.bottommost_line();
@@ -417,9 +686,8 @@ fn hyperlink_at<T>(term: &Term<T>, point: Point) -> Option<(Hyperlink, Match)> {
         let next_line = match_start.line - 1i32;
         // Iterate over all the cells in the grid's line and check if any of those cells contains
         // the hyperlink we've found at original `point`.
-        let line_contains_hyperlink = grid[next_line]
-            .into_iter()
-            .any(|cell| cell.hyperlink().map_or(false, |h| h == hyperlink));
+        let line_contains_hyperlink =
+            grid[next_line].into_iter().any(|cell| cell.hyperlink() == Some(id));
 
         // There's no hyperlink on the next line, break.
         if !line_contains_hyperlink {
@@ -433,9 +701,8 @@ fn hyperlink_at<T>(term: &Term<T>, point: Point) -> Option<(Hyperlink, Match)> {
     while match_end.line < viewport_end {
         let next_line = match_end.line + 1i32;
 
-        let line_contains_hyperlink = grid[next_line]
-            .into_iter()
-            .any(|cell| cell.hyperlink().map_or(false, |h| h == hyperlink));
+        let line_contains_hyperlink =
+            grid[next_line].into_iter().any(|cell| cell.hyperlink() == Some(id));
 
         if !line_contains_hyperlink {
             break;
@@ -444,6 +711,7 @@ fn hyperlink_at<T>(term: &Term<T>, point: Point) -> Option<(Hyperlink, Match)> {
         match_end.line = next_line;
     }
 
+    let hyperlink = term.hyperlinks().get(id)?.clone();
     Some((hyperlink, match_start..=match_end))
 }
 
@@ -593,6 +861,8 @@ mod tests {
     use alacritty_terminal::index::{Column, Line};
     use alacritty_terminal::term::test::mock_term;
 
+    use crate::config::ui_config::HintInternalAction;
+
     use super::*;
 
     #[test]
@@ -634,6 +904,70 @@ mod tests {
         assert_eq!(generator.next(), vec!['3', '3', '3', '1']);
     }
 
+    #[test]
+    fn uniform_label_generation() {
+        let alphabet = ['a', 'b', 'c'];
+
+        assert_eq!(uniform_label_length(3, 3), 1);
+        assert_eq!(uniform_label_length(3, 4), 2);
+        assert_eq!(uniform_label_length(3, 10), 3);
+
+        assert_eq!(uniform_label(&alphabet, 0, 2), vec!['a', 'a']);
+        assert_eq!(uniform_label(&alphabet, 1, 2), vec!['a', 'b']);
+        assert_eq!(uniform_label(&alphabet, 5, 2), vec!['b', 'c']);
+        assert_eq!(uniform_label(&alphabet, 8, 2), vec!['c', 'c']);
+    }
+
+    #[test]
+    fn hint_alternate_action() {
+        let term = mock_term("foo bar");
+        let hint: Hint = toml::from_str(
+            r#"
+            regex = "foo"
+            action = "Copy"
+            alternate_actions = [{ key = "y", action = "Paste" }]
+            "#,
+        )
+        .unwrap();
+
+        let mut state = HintState::new(&Hints::default());
+        state.start(hint, None);
+
+        assert_eq!(state.keyboard_input(&term, 'y'), None);
+        let hint_match = state.keyboard_input(&term, 'j').unwrap();
+        assert_eq!(hint_match.action(), &HintAction::Action(HintInternalAction::Paste));
+    }
+
+    #[test]
+    fn resolve_existing_paths() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut paths = PathHints::default();
+
+        let path = paths.resolve("Cargo.toml", Some(manifest_dir));
+        assert_eq!(path, Some(manifest_dir.join("Cargo.toml")));
+
+        assert_eq!(paths.resolve("missing.toml", Some(manifest_dir)), None);
+        assert_eq!(paths.resolve("Cargo.toml", None), None);
+    }
+
+    #[test]
+    fn path_at_point() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let term = mock_term("see Cargo.toml and missing.toml");
+        let mut paths = PathHints::default();
+
+        let (path, bounds) =
+            paths.path_at(&term, Point::new(Line(0), Column(6)), Some(manifest_dir)).unwrap();
+        assert_eq!(path, manifest_dir.join("Cargo.toml"));
+        assert_eq!(
+            bounds,
+            Match::new(Point::new(Line(0), Column(4)), Point::new(Line(0), Column(13)))
+        );
+
+        assert_eq!(paths.path_at(&term, Point::new(Line(0), Column(22)), Some(manifest_dir)), None);
+        assert_eq!(paths.path_at(&term, Point::new(Line(0), Column(1)), Some(manifest_dir)), None);
+    }
+
     #[test]
     fn closed_bracket_does_not_result_in_infinite_iterator() {
         let term = mock_term(" ) ");
@@ -0,0 +1,325 @@
+//! Overview of the entire scrollback, drawn over the right edge of the terminal.
+
+use std::ops::Range;
+
+use alacritty_terminal::ansi::{Color, NamedColor};
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Direction, Line, Point};
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::color::Rgb;
+use alacritty_terminal::term::search::RegexIter;
+use alacritty_terminal::term::{Term, TermMode};
+
+use crate::config::window::MinimapConfig;
+use crate::config::UiConfig;
+use crate::display::color::List;
+use crate::display::{content, SizeInfo};
+use crate::event::{match_in_scope, SearchState};
+use crate::renderer::quads::{QuadPoint, RenderQuad};
+
+/// Maximum height of a single line in logical pixels.
+const MAX_LINE_HEIGHT: f32 = 2.;
+
+/// Maximum number of lines sampled for a row covering multiple lines.
+const MAX_SAMPLED_LINES: usize = 4;
+
+/// Maximum number of search matches marked on the minimap.
+const MAX_MATCHES: usize = 1000;
+
+/// Opacity of the minimap's background, hiding the text below it.
+const BACKGROUND_ALPHA: f32 = 0.9;
+
+/// Opacity of the downsampled terminal content.
+const CONTENT_ALPHA: f32 = 0.8;
+
+/// Opacity of the viewport indicator.
+const VIEWPORT_ALPHA: f32 = 0.15;
+
+/// Opacity of search match markers.
+const MATCH_ALPHA: f32 = 0.6;
+
+/// Position and scale of the minimap in pixels, relative to the terminal's viewport.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Minimap {
+    x: f32,
+    y: f32,
+    width: f32,
+
+    /// Height of each row in pixels.
+    row_height: f32,
+
+    /// Number of rows, each covering one or more lines.
+    rows: usize,
+
+    /// Total number of lines, including scrollback.
+    total_lines: usize,
+
+    /// Number of lines in the scrollback history.
+    history_size: usize,
+}
+
+impl Minimap {
+    /// Minimap of a terminal, unless it's disabled or showing the alternate screen.
+    pub fn new<T>(
+        config: &MinimapConfig,
+        size_info: &SizeInfo,
+        scale_factor: f32,
+        term: &Term<T>,
+    ) -> Option<Self> {
+        if !config.enabled || term.mode().contains(TermMode::ALT_SCREEN) {
+            return None;
+        }
+
+        let total_lines = term.grid().total_lines();
+        let history_size = term.grid().history_size();
+        Self::with_lines(config, size_info, scale_factor, total_lines, history_size)
+    }
+
+    fn with_lines(
+        config: &MinimapConfig,
+        size_info: &SizeInfo,
+        scale_factor: f32,
+        total_lines: usize,
+        history_size: usize,
+    ) -> Option<Self> {
+        let width = (f32::from(config.width) * scale_factor).round().min(size_info.width() / 2.);
+        let height = size_info.screen_lines() as f32 * size_info.cell_height();
+
+        // Shrink lines until all of them fit, with each row covering multiple lines afterwards.
+        let max_line_height = (MAX_LINE_HEIGHT * scale_factor).round().max(1.);
+        let row_height = (height / total_lines as f32).floor().clamp(1., max_line_height);
+        let rows = ((height / row_height) as usize).min(total_lines);
+        if width < 1. || rows == 0 {
+            return None;
+        }
+
+        Some(Self {
+            x: size_info.width() - width,
+            y: size_info.padding_y(),
+            width,
+            row_height,
+            rows,
+            total_lines,
+            history_size,
+        })
+    }
+
+    /// Check if a position in pixels is on top of the minimap.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        let bottom = self.y + self.rows as f32 * self.row_height;
+        x >= self.x && x < self.x + self.width && y >= self.y && y < bottom
+    }
+
+    /// Display offset centering the viewport around the lines at a vertical position.
+    pub fn display_offset_at(&self, y: f32, screen_lines: usize) -> usize {
+        let row = (((y - self.y) / self.row_height).max(0.) as usize).min(self.rows - 1);
+        let lines = self.row_lines(row);
+        let center = (lines.start + lines.end) / 2;
+
+        let top = center.saturating_sub(screen_lines / 2).min(self.history_size);
+        self.history_size - top
+    }
+
+    /// Quads showing the terminal's content, its viewport and all search matches.
+    pub fn quads<T>(
+        &self,
+        term: &Term<T>,
+        config: &UiConfig,
+        colors: &List,
+        search_state: &SearchState,
+    ) -> Vec<RenderQuad> {
+        let color = |index: usize| term.colors()[index].unwrap_or(colors[index]);
+        let foreground = color(NamedColor::Foreground as usize);
+        let background = color(NamedColor::Background as usize);
+
+        let mut quads = Vec::new();
+        quads.push(self.quad(self.x, self.width, 0..self.rows, background, BACKGROUND_ALPHA));
+
+        // Downsample the cells covered by each row into segments of equal color.
+        let columns = term.columns();
+        let segments = (self.width as usize).min(columns);
+        let segment_width = self.width / segments as f32;
+        for row in 0..self.rows {
+            let lines = self.row_lines(row);
+            let step = (lines.len() / MAX_SAMPLED_LINES).max(1);
+            let sampled: Vec<_> =
+                lines.step_by(step).map(|line| &term.grid()[self.line(line)]).collect();
+
+            let mut run: Option<(usize, Rgb)> = None;
+            for segment in 0..=segments {
+                let rgb = (segment < segments)
+                    .then(|| {
+                        let cells = sampled.iter().flat_map(|cells| {
+                            segment_columns(segment, segments, columns)
+                                .map(move |i| &cells[Column(i)])
+                        });
+                        dominant_color(cells.filter_map(|cell| cell_color(config, color, cell)))
+                    })
+                    .flatten();
+
+                match run {
+                    Some((_, run_rgb)) if Some(run_rgb) == rgb => continue,
+                    Some((start, run_rgb)) => {
+                        let x = self.x + start as f32 * segment_width;
+                        let width = (segment - start) as f32 * segment_width;
+                        quads.push(self.quad(x, width, row..row + 1, run_rgb, CONTENT_ALPHA));
+                    },
+                    None => (),
+                }
+                run = rgb.map(|rgb| (segment, rgb));
+            }
+        }
+
+        // Mark the lines visible in the viewport.
+        let top = self.history_size - term.grid().display_offset();
+        let bottom = top + term.screen_lines() - 1;
+        let viewport_rows = self.row_of(top)..self.row_of(bottom) + 1;
+        quads.push(self.quad(self.x, self.width, viewport_rows, foreground, VIEWPORT_ALPHA));
+
+        // Mark the lines containing search matches.
+        if let Some(dfas) = search_state.dfas() {
+            let start = Point::new(term.topmost_line(), Column(0));
+            let end = Point::new(term.bottommost_line(), term.last_column());
+            let scope = search_state.scope();
+            let search_colors = &config.colors.search;
+            let matches = search_colors.matches.background.color(foreground, background);
+            let focused = search_colors.focused_match.background.color(foreground, background);
+
+            let mut last_row = None;
+            for regex_match in RegexIter::new(start, end, Direction::Right, term, dfas)
+                .filter(|regex_match| {
+                    scope.map_or(true, |scope| match_in_scope(regex_match, scope))
+                })
+                .take(MAX_MATCHES)
+            {
+                let row = self.row_of(self.line_index(regex_match.start().line));
+                if last_row.replace(row) != Some(row) {
+                    quads.push(self.quad(self.x, self.width, row..row + 1, matches, MATCH_ALPHA));
+                }
+            }
+
+            if let Some(focused_match) = search_state.focused_match() {
+                let row = self.row_of(self.line_index(focused_match.start().line));
+                quads.push(self.quad(self.x, self.width, row..row + 1, focused, 1.));
+            }
+        }
+
+        quads
+    }
+
+    /// Indices of the lines covered by a row, starting at the top of the scrollback.
+    fn row_lines(&self, row: usize) -> Range<usize> {
+        row * self.total_lines / self.rows..(row + 1) * self.total_lines / self.rows
+    }
+
+    /// Row covering the line at an index.
+    fn row_of(&self, index: usize) -> usize {
+        index * self.rows / self.total_lines
+    }
+
+    /// Grid line at an index.
+    fn line(&self, index: usize) -> Line {
+        Line(index as i32 - self.history_size as i32)
+    }
+
+    /// Index of a grid line.
+    fn line_index(&self, line: Line) -> usize {
+        (line.0 + self.history_size as i32).max(0) as usize
+    }
+
+    /// Quad spanning `rows`.
+    fn quad(&self, x: f32, width: f32, rows: Range<usize>, color: Rgb, alpha: f32) -> RenderQuad {
+        let top = self.y + rows.start as f32 * self.row_height;
+        let bottom = self.y + rows.end as f32 * self.row_height;
+        let points = [
+            QuadPoint { x, y: top },
+            QuadPoint { x: x + width, y: top },
+            QuadPoint { x: x + width, y: bottom },
+            QuadPoint { x, y: bottom },
+        ];
+        RenderQuad::new(points, color, alpha)
+    }
+}
+
+/// Columns downsampled into a segment.
+fn segment_columns(segment: usize, segments: usize, columns: usize) -> Range<usize> {
+    segment * columns / segments..(segment + 1) * columns / segments
+}
+
+/// Visible color of a cell, ignoring blank cells on the default background.
+fn cell_color(config: &UiConfig, color: impl Fn(usize) -> Rgb, cell: &Cell) -> Option<Rgb> {
+    let inverse = cell.flags.contains(Flags::INVERSE);
+    if !inverse && cell.bg != Color::Named(NamedColor::Background) {
+        return Some(content::background_rgb(color, cell.bg));
+    }
+
+    let blank = cell.c == ' ' || cell.c == '\t' || cell.flags.contains(Flags::HIDDEN);
+    if blank && !inverse {
+        return None;
+    }
+
+    Some(content::foreground_rgb(config, color, cell.fg, cell.flags))
+}
+
+/// Most frequent color, preferring the first one in case of a tie.
+fn dominant_color(colors: impl Iterator<Item = Rgb>) -> Option<Rgb> {
+    let mut counts: Vec<(Rgb, usize)> = Vec::new();
+    for rgb in colors {
+        match counts.iter_mut().find(|(counted, _)| *counted == rgb) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((rgb, 1)),
+        }
+    }
+
+    let max = counts.iter().map(|(_, count)| *count).max()?;
+    counts.into_iter().find(|(_, count)| *count == max).map(|(rgb, _)| rgb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_minimap(total_lines: usize, history_size: usize) -> Minimap {
+        let config = MinimapConfig { enabled: true, width: 80 };
+        let size_info = SizeInfo::new(800., 110., 10., 10., 0., 5., false);
+        Minimap::with_lines(&config, &size_info, 1., total_lines, history_size).unwrap()
+    }
+
+    #[test]
+    fn rows_cover_all_lines() {
+        // Short history is shown at the full line height.
+        let minimap = test_minimap(20, 10);
+        assert_eq!((minimap.rows, minimap.row_height), (20, 2.));
+        assert_eq!(minimap.row_lines(3), 3..4);
+
+        // Longer history is compressed into one pixel per row.
+        let minimap = test_minimap(1000, 990);
+        assert_eq!((minimap.rows, minimap.row_height), (100, 1.));
+        assert_eq!(minimap.row_lines(0), 0..10);
+        assert_eq!(minimap.row_lines(99), 990..1000);
+        assert_eq!(minimap.row_of(995), 99);
+    }
+
+    #[test]
+    fn click_centers_viewport() {
+        let minimap = test_minimap(1000, 990);
+        assert!(minimap.contains(750., 5.));
+        assert!(!minimap.contains(700., 5.));
+        assert!(!minimap.contains(750., 105.));
+
+        // Middle of the scrollback.
+        assert_eq!(minimap.display_offset_at(55., 10), 990 - 500);
+
+        // Clicks close to the edges are clamped to the scrollback.
+        assert_eq!(minimap.display_offset_at(5., 10), 990);
+        assert_eq!(minimap.display_offset_at(104., 10), 0);
+    }
+
+    #[test]
+    fn most_frequent_color() {
+        let (red, blue) = (Rgb::new(0xff, 0, 0), Rgb::new(0, 0, 0xff));
+        assert_eq!(dominant_color([red, blue, blue].into_iter()), Some(blue));
+        assert_eq!(dominant_color([red, blue].into_iter()), Some(red));
+        assert_eq!(dominant_color([].into_iter()), None);
+    }
+}
@@ -1,18 +1,23 @@
 //! The display subsystem including window management, font rasterization, and
 //! GPU drawing.
 
+use std::cell::RefCell;
 use std::cmp;
 use std::fmt::{self, Formatter};
 use std::mem::{self, ManuallyDrop};
 use std::num::NonZeroU32;
-use std::ops::{Deref, DerefMut};
-use std::time::{Duration, Instant};
+use std::ops::{Deref, DerefMut, RangeInclusive};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 use glutin::context::{NotCurrentContext, PossiblyCurrentContext};
 use glutin::prelude::*;
 use glutin::surface::{Rect as DamageRect, Surface, SwapInterval, WindowSurface};
 
-use log::{debug, info};
+use log::{debug, error, info};
 use parking_lot::MutexGuard;
 use serde::{Deserialize, Serialize};
 use winit::dpi::PhysicalSize;
@@ -20,48 +25,83 @@ use winit::keyboard::ModifiersState;
 use winit::window::raw_window_handle::RawWindowHandle;
 use winit::window::CursorIcon;
 
-use crossfont::{self, Rasterize, Rasterizer};
+use crossfont::{self, Rasterize, Rasterizer, Size};
 use unicode_width::UnicodeWidthChar;
 
-use alacritty_terminal::ansi::{CursorShape, NamedColor};
+use alacritty_terminal::ansi::{CursorShape, NamedColor, Progress, ProgressState};
 use alacritty_terminal::config::MAX_SCROLLBACK_LINES;
 use alacritty_terminal::event::{EventListener, OnResize, WindowSize};
 use alacritty_terminal::grid::Dimensions as TermDimensions;
 use alacritty_terminal::index::{Column, Direction, Line, Point};
 use alacritty_terminal::selection::{Selection, SelectionRange};
 use alacritty_terminal::term::cell::Flags;
-use alacritty_terminal::term::color::Rgb;
+use alacritty_terminal::term::color::{Rgb, COUNT};
+use alacritty_terminal::term::regis::{self, RegisPoint};
+use alacritty_terminal::term::search::CaseSensitivity;
 use alacritty_terminal::term::{self, Term, TermDamage, TermMode, MIN_COLUMNS, MIN_SCREEN_LINES};
 
+use crate::config::color::{ColorFilter, HighContrast};
 use crate::config::font::Font;
-use crate::config::window::Dimensions;
 #[cfg(not(windows))]
 use crate::config::window::StartupMode;
+use crate::config::window::{Dimensions, WindowConfig};
 use crate::config::UiConfig;
+#[cfg(feature = "accessibility")]
+use crate::display::accessibility::Accessibility;
+use crate::display::animation::{HoverAnimation, JumpAnimation, PaletteAnimation};
+use crate::display::arabic::ArabicShaping;
 use crate::display::bell::VisualBell;
+use crate::display::bidi::BidiLayout;
+use crate::display::clipboard_picker::ClipboardPicker;
+use crate::display::clipboard_prompt::ClipboardPrompt;
 use crate::display::color::List;
 use crate::display::content::{RenderableContent, RenderableCursor};
-use crate::display::cursor::IntoRects;
-use crate::display::damage::RenderDamageIterator;
+use crate::display::cursor::{AdaptiveColor, IntoRects};
+use crate::display::damage::{DamageHistory, RenderDamageIterator};
+use crate::display::frame::FrameBuffers;
+use crate::display::highlight::HighlightState;
 use crate::display::hint::{HintMatch, HintState};
 use crate::display::meter::Meter;
+use crate::display::minimap::Minimap;
+use crate::display::plugin_overlay::PluginOverlay;
+use crate::display::stats::{RenderStats, Stats};
+use crate::display::title_bar::TitleBarHit;
 use crate::display::window::Window;
 use crate::event::{Event, EventType, Mouse, SearchState};
 use crate::message_bar::{MessageBuffer, MessageType};
-use crate::renderer::quads::RenderQuad;
+use crate::pane::{Broadcast, Pane, PaneRect};
+use crate::renderer::quads::{QuadPoint, RenderQuad};
 use crate::renderer::rects::{RenderLine, RenderLines, RenderRect};
+use crate::renderer::region::{RegionSpan, RenderRegion};
+use crate::renderer::timer::RenderPass;
 use crate::renderer::{self, GlyphCache, Renderer};
 use crate::scheduler::{Scheduler, TimerId, Topic};
 use crate::string::{ShortenDirection, StrShortener};
 
+#[cfg(feature = "accessibility")]
+pub mod accessibility;
+pub mod animation;
+pub mod arabic;
+pub mod bidi;
+pub mod clipboard_picker;
+pub mod clipboard_prompt;
+pub mod color;
 pub mod content;
 pub mod cursor;
+pub mod formatted;
+pub mod highlight;
 pub mod hint;
+pub mod minimap;
+pub mod plugin_overlay;
+pub mod screenshot;
+pub mod stats;
+pub mod timestamp;
+pub mod title_bar;
 pub mod window;
 
 mod bell;
-mod color;
 mod damage;
+mod frame;
 mod meter;
 
 /// Label for the forward terminal search bar.
@@ -70,12 +110,36 @@ const FORWARD_SEARCH_LABEL: &str = "Search: ";
 /// Label for the backward terminal search bar.
 const BACKWARD_SEARCH_LABEL: &str = "Backward Search: ";
 
+/// Search bar indicator for case sensitive search.
+const CASE_SENSITIVE_LABEL: &str = "[Case] ";
+
+/// Search bar indicator for case insensitive search.
+const CASE_INSENSITIVE_LABEL: &str = "[NoCase] ";
+
+/// Search bar indicator for whole word search.
+const WHOLE_WORD_LABEL: &str = "[Word] ";
+
+/// Search bar indicator for fuzzy search.
+const FUZZY_LABEL: &str = "[Fuzzy] ";
+
+/// Search bar indicator for search restricted to a region.
+const SCOPE_LABEL: &str = "[Scope] ";
+
 /// The character used to shorten the visible text like uri preview or search regex.
 const SHORTENER: char = '…';
 
 /// Color which is used to highlight damaged rects when debugging.
 const DAMAGE_RECT_COLOR: Rgb = Rgb::new(255, 0, 255);
 
+/// Brightness of a rounded selection's border, relative to its fill color.
+const BORDER_FACTOR: f32 = 0.7;
+
+/// Opacity of the arrow pointing at new output below the viewport.
+const NEW_OUTPUT_ALPHA: f32 = 0.8;
+
+/// Minimum time between presented frames while saving battery.
+const BATTERY_FRAME_INTERVAL: Duration = Duration::from_micros(1_000_000 / 30);
+
 #[derive(Debug)]
 pub enum Error {
     /// Error with window management.
@@ -339,8 +403,15 @@ impl DisplayUpdate {
 pub struct Display {
     pub window: Window,
 
+    /// Dimensions of the focused terminal.
     pub size_info: SizeInfo,
 
+    /// Area of the focused pane, when the window is split into multiple panes or has a title bar.
+    pub pane_rect: Option<PaneRect>,
+
+    /// Whether Alacritty's title bar replaces client-side decorations.
+    title_bar: bool,
+
     /// Hint highlighted by the mouse.
     pub highlighted_hint: Option<HintMatch>,
 
@@ -357,9 +428,57 @@ pub struct Display {
     /// Mapped RGB values for each terminal color.
     pub colors: List,
 
+    /// When colors are replaced by the high contrast palette.
+    high_contrast: HighContrast,
+
+    /// Whether the system asks for high contrast.
+    system_high_contrast: bool,
+
+    /// Filter applied to the rendered frame for color vision deficiencies.
+    pub color_filter: ColorFilter,
+
     /// State of the keyboard hints.
     pub hint_state: HintState,
 
+    /// Text styled by the highlighting rules.
+    pub highlights: HighlightState,
+
+    /// Overlay for pasting from the clipboard history.
+    pub clipboard_picker: ClipboardPicker,
+
+    /// Prompt for clipboard reads requested through OSC 52.
+    pub clipboard_prompt: ClipboardPrompt,
+
+    /// Quads and text drawn by Lua plugins.
+    pub plugin_overlay: PluginOverlay,
+
+    /// Animation for jumps between search matches and vi mode marks.
+    pub jump_animation: JumpAnimation,
+
+    /// Animated underline of the hint hovered by the mouse.
+    pub hover_animation: HoverAnimation,
+
+    /// Transition between the color palettes of the focused terminal.
+    pub palette_animation: PaletteAnimation,
+
+    /// Visual order of lines containing right-to-left text.
+    pub bidi: BidiLayout,
+
+    /// Contextual forms of Arabic letters.
+    pub arabic_shaping: ArabicShaping,
+
+    /// New output arrived below the viewport while it was scrolled back.
+    pub new_output_below: bool,
+
+    /// Show the time each line was written at.
+    pub show_timestamps: bool,
+
+    /// Path of a screenshot to take of the next frame.
+    pub pending_screenshot: Option<PathBuf>,
+
+    /// Terminals receiving the input of this window.
+    pub broadcast: Broadcast,
+
     /// Unprocessed display updates.
     pub pending_update: DisplayUpdate,
 
@@ -372,7 +491,20 @@ pub struct Display {
     /// The state of the timer for frame scheduling.
     pub frame_timer: FrameTimer,
 
-    cursor: RenderableCursor,
+    /// Minimum time between presented frames, limiting the frame rate.
+    frame_interval: Option<Duration>,
+
+    /// Whether animations should be throttled while on battery power.
+    battery_saver: bool,
+
+    /// Whether the system is running on battery power.
+    on_battery: bool,
+
+    /// Animated cursor of the focused terminal.
+    pub cursor: RenderableCursor,
+
+    /// Color of the animated cursor when it adapts to the cells below it.
+    adaptive_cursor_color: AdaptiveColor,
 
     // Mouse point position when highlighting hints.
     hint_mouse_point: Option<Point>,
@@ -386,29 +518,69 @@ pub struct Display {
     debug_damage: bool,
     damage_rects: Vec<DamageRect>,
     next_frame_damage_rects: Vec<DamageRect>,
-    glyph_cache: GlyphCache,
+
+    /// Damage of recently presented frames, for redrawing only parts of reused buffers.
+    damage_history: DamageHistory,
+    glyph_cache: ManuallyDrop<Rc<RefCell<GlyphCache>>>,
     meter: Meter,
+    stats: Stats,
+
+    /// Buffers reused between frames.
+    frame_buffers: FrameBuffers,
+
+    /// Size of the window in physical pixels.
+    window_size: PhysicalSize<f32>,
+
+    /// Text exposed to screen readers.
+    #[cfg(feature = "accessibility")]
+    pub accessibility: Option<Accessibility>,
 }
 
 impl Display {
+    /// Create a new display.
+    ///
+    /// When `shared` is present, `gl_context` must be sharing objects with its context. This
+    /// allows reusing its shader programs and, if the font matches, its glyph cache.
     pub fn new(
         window: Window,
         gl_context: NotCurrentContext,
         config: &UiConfig,
+        shared: Option<&Display>,
     ) -> Result<Display, Error> {
         let raw_window_handle = window.raw_window_handle();
 
+        // Screen readers must be connected before the window is shown.
+        #[cfg(feature = "accessibility")]
+        let accessibility = Accessibility::new(raw_window_handle);
+
         let scale_factor = window.scale_factor as f32;
-        let rasterizer = Rasterizer::new(scale_factor)?;
 
-        debug!("Loading \"{}\" font", &config.font.normal().family);
-        let mut glyph_cache = GlyphCache::new(rasterizer, &config.font)?;
+        // Reuse the glyphs of the shared display when they'd be rasterized identically.
+        let shared_glyph_cache = shared
+            .filter(|shared| shared.window.scale_factor == window.scale_factor)
+            .map(|shared| &*shared.glyph_cache)
+            .filter(|glyph_cache| glyph_cache.borrow().font() == &config.font)
+            .cloned();
 
-        let metrics = glyph_cache.font_metrics();
+        let (glyph_cache, load_glyphs) = match shared_glyph_cache {
+            Some(glyph_cache) => (glyph_cache, false),
+            None => {
+                let rasterizer = Rasterizer::new(scale_factor)?;
+
+                debug!("Loading \"{}\" font", &config.font.normal().family);
+                let glyph_cache = GlyphCache::new(rasterizer, &config.font, scale_factor)?;
+
+                (Rc::new(RefCell::new(glyph_cache)), true)
+            },
+        };
+
+        let metrics = glyph_cache.borrow().font_metrics();
         let (cell_width, cell_height) = compute_cell_size(config, &metrics);
 
         // Resize the window to account for the user configured size.
-        if let Some(dimensions) = config.window.dimensions() {
+        //
+        // Dropdown windows are sized by the monitor they're docked to instead.
+        if let Some(dimensions) = config.window.dimensions().filter(|_| !window.is_dropdown()) {
             let size = window_size(config, dimensions, cell_width, cell_height, scale_factor);
             window.request_inner_size(size);
         }
@@ -424,13 +596,16 @@ impl Display {
         let context = gl_context.make_current(&surface)?;
 
         // Create renderer.
-        let mut renderer = Renderer::new(&context, config.debug.renderer)?;
+        let shared_renderer = shared.map(|shared| &*shared.renderer);
+        let mut renderer = Renderer::new(&context, config.debug.renderer, shared_renderer)?;
 
         // Load font common glyphs to accelerate rendering.
-        debug!("Filling glyph cache with common glyphs");
-        renderer.with_loader(|mut api| {
-            glyph_cache.reset_glyph_cache(&mut api);
-        });
+        if load_glyphs {
+            debug!("Filling glyph cache with common glyphs");
+            renderer.with_loader(|mut api| {
+                glyph_cache.borrow_mut().reset_glyph_cache(&mut api);
+            });
+        }
 
         let padding = config.window.padding(window.scale_factor as f32);
         let viewport_size = window.inner_size();
@@ -471,7 +646,7 @@ impl Display {
         }
 
         // Set resize increments for the newly created window.
-        if config.window.resize_increments {
+        if config.window.resize_increments && !window.is_dropdown() {
             window.set_resize_increments(PhysicalSize::new(cell_width, cell_height));
         }
 
@@ -480,13 +655,15 @@ impl Display {
         #[allow(clippy::single_match)]
         #[cfg(not(windows))]
         match config.window.startup_mode {
+            _ if window.is_dropdown() => (),
             #[cfg(target_os = "macos")]
             StartupMode::SimpleFullscreen => window.set_simple_fullscreen(true),
             StartupMode::Maximized if !is_wayland => window.set_maximized(true),
             _ => (),
         }
 
-        let hint_state = HintState::new(config.hints.alphabet());
+        let hint_state = HintState::new(&config.hints);
+        let highlights = HighlightState::new(&config.highlights.rules);
 
         let debug_damage = config.debug.highlight_damage;
         let (damage_rects, next_frame_damage_rects) = if is_wayland || debug_damage {
@@ -506,33 +683,113 @@ impl Display {
             context: ManuallyDrop::new(Replaceable::new(context)),
             surface: ManuallyDrop::new(surface),
             renderer: ManuallyDrop::new(renderer),
-            glyph_cache,
+            glyph_cache: ManuallyDrop::new(glyph_cache),
             hint_state,
+            highlights,
+            clipboard_picker: Default::default(),
+            clipboard_prompt: Default::default(),
+            plugin_overlay: Default::default(),
+            jump_animation: Default::default(),
+            hover_animation: Default::default(),
+            palette_animation: Default::default(),
+            bidi: Default::default(),
+            arabic_shaping: Default::default(),
+            new_output_below: false,
+            show_timestamps: config.timestamps.visible,
+            pending_screenshot: None,
+            broadcast: Default::default(),
             cursor: RenderableCursor::new(
                 Point { line: 0, column: Column(0) },
                 CursorShape::Block,
                 Rgb::default(),
                 false,
             ),
+            adaptive_cursor_color: Default::default(),
             meter: Meter::new(),
+            stats: Default::default(),
+            frame_buffers: Default::default(),
+            window_size: PhysicalSize::new(size_info.width(), size_info.height()),
+            pane_rect: None,
+            title_bar: false,
             size_info,
             ime: Ime::new(),
             highlighted_hint: None,
             vi_highlighted_hint: None,
             cursor_hidden: false,
             frame_timer: FrameTimer::new(),
+            frame_interval: config.window.frame_interval(),
+            battery_saver: config.window.battery_saver,
+            on_battery: false,
             visual_bell: VisualBell::from(&config.bell),
-            colors: List::from(&config.colors),
+            colors: match config.colors.high_contrast {
+                HighContrast::On => List::high_contrast(&config.colors),
+                _ => List::from(&config.colors),
+            },
+            high_contrast: config.colors.high_contrast,
+            system_high_contrast: false,
+            color_filter: config.colors.color_filter,
             pending_update: Default::default(),
             pending_renderer_update: Default::default(),
             debug_damage,
             damage_rects,
             raw_window_handle,
             next_frame_damage_rects,
+            damage_history: Default::default(),
             hint_mouse_point: None,
+            #[cfg(feature = "accessibility")]
+            accessibility,
         })
     }
 
+    /// Size of the window in physical pixels.
+    pub fn window_size(&self) -> PhysicalSize<f32> {
+        self.window_size
+    }
+
+    /// Replace client-side decorations forced on the window by the compositor.
+    pub fn update_title_bar(&mut self, config: &WindowConfig) {
+        let title_bar = self.window.update_client_decorations(config);
+        if title_bar != self.title_bar {
+            self.title_bar = title_bar;
+            self.pending_update.dirty = true;
+        }
+    }
+
+    /// Height of the title bar in physical pixels, zero without title bar.
+    pub fn title_bar_height(&self) -> f32 {
+        if self.title_bar && !self.window.is_fullscreen() {
+            title_bar::height(self.window.scale_factor as f32)
+        } else {
+            0.
+        }
+    }
+
+    /// Part of the title bar at a position in the window.
+    pub fn title_bar_hit(&self, x: f32, y: f32) -> Option<TitleBarHit> {
+        if self.title_bar_height() == 0. {
+            return None;
+        }
+
+        title_bar::hit(x, y, self.window_size.width, self.window.scale_factor as f32)
+    }
+
+    /// Overview of the terminal's scrollback, if it is shown.
+    pub fn minimap<T>(&self, config: &UiConfig, term: &Term<T>) -> Option<Minimap> {
+        let scale_factor = self.window.scale_factor as f32;
+        Minimap::new(&config.window.minimap, &self.size_info, scale_factor, term)
+    }
+
+    /// Size of the window in physical pixels after changing its scale factor.
+    ///
+    /// The logical size of the window is preserved.
+    pub fn scaled_window_size(&self, scale_factor: f64) -> PhysicalSize<u32> {
+        let size = self.window.inner_size();
+        let ratio = scale_factor / self.window.scale_factor;
+        let width = (f64::from(size.width) * ratio).round().max(1.) as u32;
+        let height = (f64::from(size.height) * ratio).round().max(1.) as u32;
+        PhysicalSize::new(width, height)
+    }
+
     #[inline]
     pub fn gl_context(&self) -> &PossiblyCurrentContext {
         self.context.get()
@@ -576,11 +833,25 @@ impl Display {
     ///
     /// This will return a tuple of the cell width and height.
     fn update_font_size(
-        glyph_cache: &mut GlyphCache,
+        glyph_cache: &mut Rc<RefCell<GlyphCache>>,
         scale_factor: f64,
         config: &UiConfig,
         font: &Font,
     ) -> (f32, f32) {
+        // Stop sharing glyphs with other windows, since they're still using the old font.
+        if Rc::strong_count(glyph_cache) > 1 {
+            match Rasterizer::new(scale_factor as f32)
+                .and_then(|rasterizer| GlyphCache::new(rasterizer, font, scale_factor as f32))
+            {
+                Ok(detached) => *glyph_cache = Rc::new(RefCell::new(detached)),
+                Err(err) => {
+                    error!("Unable to create glyph cache for new font: {}", err);
+                    return compute_cell_size(config, &glyph_cache.borrow().font_metrics());
+                },
+            }
+        }
+
+        let mut glyph_cache = glyph_cache.borrow_mut();
         let _ = glyph_cache.update_font_size(font, scale_factor);
 
         // Compute new cell sizes.
@@ -589,7 +860,7 @@ impl Display {
 
     /// Reset glyph cache.
     fn reset_glyph_cache(&mut self) {
-        let cache = &mut self.glyph_cache;
+        let mut cache = self.glyph_cache.borrow_mut();
         self.renderer.with_loader(|mut api| {
             cache.reset_glyph_cache(&mut api);
         });
@@ -630,12 +901,16 @@ impl Display {
             info!("Cell size: {} x {}", cell_width, cell_height);
         }
 
-        let (mut width, mut height) = (self.size_info.width(), self.size_info.height());
         if let Some(dimensions) = pending_update.dimensions() {
-            width = dimensions.width as f32;
-            height = dimensions.height as f32;
+            self.window_size = PhysicalSize::new(dimensions.width as f32, dimensions.height as f32);
         }
 
+        // Split windows only use the focused pane's area for the terminal.
+        let (width, height) = match self.pane_rect {
+            Some(rect) => (rect.width, rect.height),
+            None => (self.window_size.width, self.window_size.height),
+        };
+
         let padding = config.window.padding(self.window.scale_factor as f32);
 
         let mut new_size = SizeInfo::new(
@@ -664,6 +939,13 @@ impl Display {
         // Resize terminal.
         terminal.resize(new_size);
 
+        // Keep the animated cursor on its cell when the cell dimensions change, instead of letting
+        // it glide in from its previous pixel position.
+        if cell_width != self.size_info.cell_width() || cell_height != self.size_info.cell_height()
+        {
+            self.cursor.data.reproject(&self.size_info, &new_size);
+        }
+
         // Queue renderer update if terminal dimensions/padding changed.
         if new_size != self.size_info {
             let renderer_update = self.pending_renderer_update.get_or_insert(Default::default());
@@ -685,8 +967,8 @@ impl Display {
 
         // Resize renderer.
         if renderer_update.resize {
-            let width = NonZeroU32::new(self.size_info.width() as u32).unwrap();
-            let height = NonZeroU32::new(self.size_info.height() as u32).unwrap();
+            let width = NonZeroU32::new(self.window_size.width as u32).unwrap();
+            let height = NonZeroU32::new(self.window_size.height as u32).unwrap();
             self.surface.resize(&self.context, width, height);
         }
 
@@ -718,34 +1000,47 @@ impl Display {
     /// Damage the entire window.
     fn fully_damage(&mut self) {
         let screen_rect =
-            DamageRect::new(0, 0, self.size_info.width() as i32, self.size_info.height() as i32);
+            DamageRect::new(0, 0, self.window_size.width as i32, self.window_size.height as i32);
 
         self.damage_rects.push(screen_rect);
     }
 
+    /// Collect the damage of the terminal content.
+    ///
+    /// Returns `false` if the terminal content is unchanged since the last frame.
     fn update_damage<T: EventListener>(
         &mut self,
         terminal: &mut MutexGuard<'_, Term<T>>,
         selection_range: Option<SelectionRange>,
         search_state: &SearchState,
-    ) {
+    ) -> bool {
         let requires_full_damage = self.visual_bell.intensity() != 0.
             || self.hint_state.active()
+            || self.clipboard_picker.active()
+            || self.clipboard_prompt.active()
+            || self.jump_animation.active()
+            || self.hover_animation.active()
+            || self.palette_animation.active()
+            || self.bidi.active()
+            || self.arabic_shaping.active()
             || search_state.regex().is_some();
         if requires_full_damage {
             terminal.mark_fully_damaged();
         }
 
         self.damage_highlighted_hints(terminal);
-        match terminal.damage(selection_range) {
-            TermDamage::Full => self.fully_damage(),
+        let content_damaged = match terminal.damage(selection_range) {
+            TermDamage::Full => {
+                self.fully_damage();
+                true
+            },
             TermDamage::Partial(damaged_lines) => {
+                let num_rects = self.damage_rects.len();
                 let damaged_rects = RenderDamageIterator::new(damaged_lines, self.size_info.into());
-                for damaged_rect in damaged_rects {
-                    self.damage_rects.push(damaged_rect);
-                }
+                self.damage_rects.extend(damaged_rects);
+                self.damage_rects.len() > num_rects
             },
-        }
+        };
         terminal.reset_damage();
 
         // Ensure that the content requiring full damage is cleaned up again on the next frame.
@@ -755,6 +1050,8 @@ impl Display {
 
         // Damage highlighted hints for the next frame as well, so we'll clear them.
         self.damage_highlighted_hints(terminal);
+
+        content_damaged
     }
 
     /// Draw the screen.
@@ -762,6 +1059,7 @@ impl Display {
     /// A reference to Term whose state is being drawn must be provided.
     ///
     /// This call may block if vsync is enabled.
+    #[allow(clippy::too_many_arguments)]
     pub fn draw<T: EventListener>(
         &mut self,
         mut terminal: MutexGuard<'_, Term<T>>,
@@ -769,45 +1067,162 @@ impl Display {
         message_buffer: &MessageBuffer,
         config: &UiConfig,
         search_state: &SearchState,
+        panes: &[Pane],
+        font_size: Size,
     ) {
+        let frame_start = Instant::now();
+
+        let jump_frame = self.jump_animation.frame();
+
+        // Reuse the buffers of the previous frame.
+        let mut frame = mem::take(&mut self.frame_buffers);
+        frame.clear();
+
+        // Transition to colors changed by escape sequences or the config.
+        let mut palette = self.colors;
+        for i in 0..COUNT {
+            if let Some(color) = terminal.colors()[i] {
+                palette[i] = color;
+            }
+        }
+        let transition =
+            if config.window.reduce_motion { Duration::ZERO } else { config.colors.transition() };
+        self.palette_animation.frame(palette, transition);
+
         // Collect renderable content before the terminal is dropped.
         let mut content = RenderableContent::new(config, self, &terminal, search_state);
-        let mut grid_cells = Vec::new();
-        for cell in &mut content {
-            grid_cells.push(cell);
-        }
+        frame.cells.extend(&mut content);
         let selection_range = content.selection_range();
         let foreground_color = content.color(NamedColor::Foreground as usize);
         let background_color = content.color(NamedColor::Background as usize);
         let display_offset = content.display_offset();
 
-        let cursor_color = content.color(NamedColor::Foreground as usize);
-        self.cursor.cursor_color = cursor_color;
+        // Draw the cursor in its configured colors, once its cell is known.
+        let renderable_cursor = content.cursor();
+        self.cursor.cursor_color = match renderable_cursor.shape() {
+            CursorShape::Hidden => foreground_color,
+            _ => renderable_cursor.color(),
+        };
 
         self.cursor.point.line = terminal.grid().cursor.point.line.0 as usize;
         self.cursor.point.column = terminal.grid().cursor.point.column;
 
+        // Reorder lines containing right-to-left text, including the cursor's position.
+        self.arabic_shaping.update(&terminal);
+        self.bidi.update(&terminal);
+        if let Some(bidi_line) = self.bidi.line(self.cursor.point.line) {
+            self.cursor.point.column = bidi_line.visual_column(self.cursor.point.column);
+        }
+
         let cursor_point = terminal.grid().cursor.point;
         let total_lines = terminal.grid().total_lines();
-        let metrics = self.glyph_cache.font_metrics();
+        let scrollback_memory = terminal.scrollback_memory();
+        let metrics = self.line_metrics(self.glyph_cache.borrow().font_metrics());
         let size_info = self.size_info;
 
+        // Merge the selected cells into a single rounded region.
+        let selection_region = selection_range
+            .filter(|_| config.terminal_config.selection.rounded)
+            .and_then(|range| {
+                let colors = &config.colors.selection;
+                let fill = colors.background.color(foreground_color, background_color);
+                self.selection_region(config, range, display_offset, fill)
+            });
+
         let vi_mode = terminal.mode().contains(TermMode::VI);
         let vi_cursor_point = if vi_mode { Some(terminal.vi_mode_cursor.point) } else { None };
 
+        let content_damaged = !self.collect_damage()
+            || self.update_damage(&mut terminal, selection_range, search_state);
+
+        let progress = terminal.progress().filter(|_| config.window.progress_bar);
+
+        // Collect finished commands visible in the viewport.
+        let finished_commands = if config.window.command_status {
+            let top = Line(-(display_offset as i32));
+            terminal.finished_commands(top..=top + size_info.bottommost_line().0)
+        } else {
+            Vec::new()
+        };
+
+        // Collect bookmarked lines visible in the viewport.
+        let top = Line(-(display_offset as i32));
+        let bookmarks = terminal.bookmarks(top..=top + size_info.bottommost_line().0);
+
+        // Downsample the scrollback for the minimap.
+        let minimap_quads = self
+            .minimap(config, &terminal)
+            .map(|minimap| minimap.quads(&terminal, config, &self.colors, search_state))
+            .unwrap_or_default();
+
+        // Draw ReGIS graphics with the thickness of underlines.
+        let regis_quads = self.regis_quads(&terminal, metrics.underline_thickness);
+
+        // Collect the times at which visible lines started.
+        let timestamps = if self.show_timestamps {
+            let now = SystemTime::now();
+            let grid = terminal.grid();
+            (0..size_info.screen_lines())
+                .filter_map(|viewport_line| {
+                    let line = top + viewport_line;
+                    let wrapped = line > grid.topmost_line()
+                        && grid[line - 1i32][grid.last_column()].flags.contains(Flags::WRAPLINE);
+                    let time = grid.timestamp(line).filter(|_| !wrapped)?;
+                    Some((viewport_line, timestamp::format(time, now, config.timestamps.format)))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Advance the cursor animation before drawing, so its damage is known up front.
+        let cursor_config = config.terminal_config.cursor;
+        let mut cursor_thickness = cursor_config.thickness();
+        if self.high_contrast() {
+            cursor_thickness = (cursor_thickness * 2.).min(1.);
+        }
+        let mut cursor_quad =
+            self.cursor.quads(&size_info, cursor_thickness, cursor_config.animation);
+        cursor_quad.alpha = cursor_config.block_opacity();
+
+        // Contrast the cursor with the cell it's currently gliding over.
+        if config.colors.adaptive_cursor && renderable_cursor.shape() != CursorShape::Hidden {
+            let point = cursor::quad_cell(&cursor_quad, &size_info);
+            let background = if point == renderable_cursor.point() {
+                renderable_cursor.cell_background()
+            } else {
+                frame
+                    .cells
+                    .binary_search_by_key(&point, |cell| cell.point)
+                    .ok()
+                    .map(|index| &frame.cells[index])
+                    .filter(|cell| cell.bg_alpha > 0.)
+                    .map_or(background_color, |cell| cell.bg)
+            };
+            cursor_quad.color = self.adaptive_cursor_color.update(background);
+        }
         if self.collect_damage() {
-            self.update_damage(&mut terminal, selection_range, search_state);
+            // Damage the cursor for the next frame as well, to clear it once it has moved.
+            let cursor_damage = damage::quad_damage(&cursor_quad, &size_info);
+            self.damage_rects.push(cursor_damage);
+            self.next_frame_damage_rects.push(cursor_damage);
         }
 
-        // TODO: use min and max instead
-        let mut cursor_rect = DamageRect::new(
-            self.cursor.data.positions[0].x as i32 - 20,
-            size_info.height() as i32 - self.cursor.data.positions[0].y as i32 - 50,
-            self.cursor.data.positions[2].x as i32 - self.cursor.data.positions[0].x as i32 + 40,
-            self.cursor.data.positions[2].y as i32 - self.cursor.data.positions[0].y as i32 + 100,
-        );
+        // Point out new output until the viewport is back at the bottom.
+        self.new_output_below &= display_offset != 0;
+        let new_output_quad =
+            self.new_output_below.then(|| new_output_indicator(&size_info, foreground_color));
+        if let Some(quad) = new_output_quad.as_ref().filter(|_| self.collect_damage()) {
+            let indicator_damage = damage::quad_damage(quad, &size_info);
+            self.damage_rects.push(indicator_damage);
+            self.next_frame_damage_rects.push(indicator_damage);
+        }
 
-        self.damage_rects.push(cursor_rect);
+        // Expose the visible text to screen readers.
+        #[cfg(feature = "accessibility")]
+        if let Some(accessibility) = &mut self.accessibility {
+            accessibility.update(&terminal, &size_info, selection_range);
+        }
 
         // Drop terminal as early as possible to free lock.
         drop(terminal);
@@ -815,8 +1230,46 @@ impl Display {
         // Make sure this window's OpenGL context is active.
         self.make_current();
 
-        self.renderer.clear(background_color, config.window_opacity());
-        let mut lines = RenderLines::new();
+        // Upload glyphs which finished rasterizing in the background.
+        let glyphs_loaded = {
+            let glyph_cache = &mut *self.glyph_cache.borrow_mut();
+            self.renderer.with_loader(|mut api| {
+                glyph_cache.compact_atlas(&mut api);
+                glyph_cache.load_rasterized(&mut api)
+            })
+        };
+
+        // Replace the placeholders of all cells drawn before their glyphs were ready.
+        if glyphs_loaded {
+            self.fully_damage();
+        }
+
+        // Only redraw the damaged region when nothing but quads changed since the last frame.
+        //
+        // Screenshots always require the entire frame to be redrawn.
+        let partial = !content_damaged && !glyphs_loaded && self.pending_screenshot.is_none();
+        let redraw_region = partial.then(|| self.partial_redraw_region()).flatten();
+        self.renderer.set_scissor(redraw_region);
+
+        let (width, height) = (self.window_size.width as i32, self.window_size.height as i32);
+        self.renderer.begin_color_filter(width, height, self.color_filter);
+        self.renderer.clear(background_color, self.window_opacity(config));
+
+        // Draw the terminals of all other panes before moving to the focused one.
+        //
+        // Setting the viewport also ensures macOS hasn't reset it.
+        self.draw_unfocused_panes(panes, font_size);
+        let (x, y) = self.viewport_origin(self.pane_rect);
+        self.renderer.set_viewport_origin(x, y);
+        self.renderer.set_viewport(&size_info);
+
+        // Draw the rounded selection below the text of the selected cells.
+        if let Some(region) = &selection_region {
+            self.renderer.draw_region(&size_info, region);
+        }
+
+        // Measure the GPU time of render passes for the render timer.
+        let time_passes = config.debug.render_timer;
 
         // Optimize loop hint comparator.
         let has_highlighted_hint =
@@ -826,27 +1279,41 @@ impl Display {
         {
             let _sampler = self.meter.sampler();
 
-            // Ensure macOS hasn't reset our viewport.
-            #[cfg(target_os = "macos")]
-            self.renderer.set_viewport(&size_info);
+            let glyph_cache = &mut *self.glyph_cache.borrow_mut();
+            self.stats.record_memory(scrollback_memory, glyph_cache.atlas_memory());
 
-            let glyph_cache = &mut self.glyph_cache;
             let highlighted_hint = &self.highlighted_hint;
             let vi_highlighted_hint = &self.vi_highlighted_hint;
 
+            // The underline of a newly hovered hint is drawn by its animation instead.
+            let hover_sweeping = self.hover_animation.sweeping();
+            let hover_animation = &mut self.hover_animation;
+
+            let bidi_layout = &self.bidi;
+            let arabic_shaping = &self.arabic_shaping;
+
+            if time_passes {
+                self.renderer.begin_pass(RenderPass::Text);
+            }
+
             self.renderer.draw_cells(
                 &size_info,
                 glyph_cache,
-                grid_cells.into_iter().map(|mut cell| {
+                frame.cells.drain(..).map(|mut cell| {
                     // Underline hints hovered by mouse or vi mode cursor.
                     let point = term::viewport_to_point(display_offset, cell.point);
 
                     if has_highlighted_hint {
                         let hyperlink =
                             cell.extra.as_ref().and_then(|extra| extra.hyperlink.as_ref());
-                        if highlighted_hint
+                        let hovered = highlighted_hint
                             .as_ref()
-                            .map_or(false, |hint| hint.should_highlight(point, hyperlink))
+                            .map_or(false, |hint| hint.should_highlight(point, hyperlink));
+                        if hovered {
+                            hover_animation.set_color(cell.underline);
+                        }
+
+                        if (hovered && !hover_sweeping)
                             || vi_highlighted_hint
                                 .as_ref()
                                 .map_or(false, |hint| hint.should_highlight(point, hyperlink))
@@ -855,15 +1322,44 @@ impl Display {
                         }
                     }
 
+                    // Join Arabic letters, before they're moved to their visual position.
+                    if let Some(c) = arabic_shaping.character(cell.point) {
+                        cell.character = c;
+                    }
+
+                    // Move cells of right-to-left text to their visual position.
+                    if let Some(bidi_line) = bidi_layout.line(cell.point.line) {
+                        if bidi_line.is_rtl(cell.point.column) {
+                            cell.character = bidi::mirror(cell.character);
+                        }
+                        cell.point.column = bidi_line.visual_column(cell.point.column);
+                    }
+
                     // Update underline/strikeout.
-                    lines.update(&cell);
+                    frame.lines.update(&cell);
 
                     cell
                 }),
             );
+
+            if time_passes {
+                self.renderer.end_pass(RenderPass::Text);
+            }
         }
 
-        let mut rects = lines.rects(&metrics, &size_info);
+        let FrameBuffers { lines, rects, quads, .. } = &mut frame;
+        lines.rects(rects, &metrics, &size_info);
+
+        // Sweep in the underline of hovered hints, fading it out again once they're left.
+        if self.hover_animation.active() {
+            let hovered = self.highlighted_hint.as_ref().map(HintMatch::bounds);
+            rects.extend(self.hover_animation.rects(hovered, display_offset, &metrics, &size_info));
+        }
+
+        // Show when lines were written, without obscuring the vi mode cursor.
+        let vi_cursor_viewport_point =
+            vi_cursor_point.and_then(|point| term::point_to_viewport(display_offset, point));
+        self.draw_timestamps(config, timestamps, vi_cursor_viewport_point);
 
         if let Some(vi_cursor_point) = vi_cursor_point {
             // Indicate vi mode by showing the cursor's position in the top right corner.
@@ -877,9 +1373,36 @@ impl Display {
             self.draw_line_indicator(config, total_lines, None, display_offset);
         };
 
+        // Draw vector graphics above the text, but below the cursor.
+        quads.extend(regis_quads);
+
         // Draw cursor.
-        let mut quads = Vec::new();
-        quads.push(self.cursor.quads(&size_info, config.terminal_config.cursor.thickness()));
+        quads.push(cursor_quad);
+
+        // Draw the minimap above the terminal's content.
+        quads.extend(minimap_quads);
+
+        quads.extend(new_output_quad);
+
+        // Mark the exit status of finished commands in the gutter.
+        self.draw_command_status(config, finished_commands, display_offset, rects);
+
+        // Mark bookmarked lines in the right padding.
+        self.draw_bookmarks(config, bookmarks, display_offset, rects);
+
+        if let Some(progress) = progress {
+            self.draw_progress(config, progress, rects);
+        }
+
+        // Draw quads added by plugins.
+        self.draw_plugin_quads(rects);
+
+        // Highlight the target of an animated jump.
+        if let Some(jump_frame) = jump_frame {
+            let focused_match = config.colors.search.focused_match.background;
+            let color = focused_match.color(foreground_color, background_color);
+            rects.extend(jump_frame.highlight_rects(&size_info, color));
+        }
 
         // Push visual bell after url/underline/strikeout rects.
         let visual_bell_intensity = self.visual_bell.intensity();
@@ -898,12 +1421,30 @@ impl Display {
         // Handle IME positioning and search bar rendering.
         let ime_position = match search_state.regex() {
             Some(regex) => {
-                let search_label = match search_state.direction() {
+                // Indicate search options which differ from the default.
+                let options = search_state.options();
+                let mut search_label = String::new();
+                match options.case_sensitivity {
+                    CaseSensitivity::Smart => (),
+                    CaseSensitivity::Sensitive => search_label.push_str(CASE_SENSITIVE_LABEL),
+                    CaseSensitivity::Insensitive => search_label.push_str(CASE_INSENSITIVE_LABEL),
+                }
+                if options.whole_word {
+                    search_label.push_str(WHOLE_WORD_LABEL);
+                }
+                if options.fuzzy {
+                    search_label.push_str(FUZZY_LABEL);
+                }
+                if search_state.scope().is_some() {
+                    search_label.push_str(SCOPE_LABEL);
+                }
+
+                search_label.push_str(match search_state.direction() {
                     Direction::Right => FORWARD_SEARCH_LABEL,
                     Direction::Left => BACKWARD_SEARCH_LABEL,
-                };
+                });
 
-                let search_text = Self::format_search(regex, search_label, size_info.columns());
+                let search_text = Self::format_search(regex, &search_label, size_info.columns());
 
                 // Render the search bar.
                 self.draw_search(config, &search_text);
@@ -914,9 +1455,11 @@ impl Display {
 
                 // Add cursor to search bar if IME is not active.
                 if self.ime.preedit().is_none() {
-                    let fg = config.colors.footer_bar_foreground();
+                    let color = config.colors.search_cursor();
                     let shape = CursorShape::Underline;
-                    //quads.push(cursor.quads(&size_info, config.terminal_config.cursor.thickness()));
+                    let mut cursor =
+                        RenderableCursor::new(Point::new(line, column), shape, color, false);
+                    quads.push(cursor.quads(&size_info, cursor_thickness, false));
                 }
 
                 Some(Point::new(line, column))
@@ -937,14 +1480,18 @@ impl Display {
                     (foreground_color, background_color)
                 };
 
-                self.draw_ime_preview(point, fg, bg, &mut rects, &mut quads, config);
+                self.draw_ime_preview(point, fg, bg, rects, quads, config);
             }
         }
 
         if self.debug_damage {
-            self.highlight_damage(&mut rects);
+            self.highlight_damage(rects);
         }
 
+        // Count the submitted primitives, including the message bar background.
+        let num_rects = rects.len() + usize::from(message_buffer.message().is_some());
+        self.stats.record_primitives(num_rects, quads.len());
+
         if let Some(message) = message_buffer.message() {
             let search_offset = usize::from(search_state.regex().is_some());
             let text = message.text(&size_info);
@@ -968,10 +1515,10 @@ impl Display {
             self.renderer.draw_rects(&size_info, &metrics, rects);
 
             // Draw rectangles.
-            self.renderer.draw_quads(&size_info, &metrics, quads);
+            self.draw_quads(&size_info, &metrics, quads, time_passes);
 
             // Relay messages to the user.
-            let glyph_cache = &mut self.glyph_cache;
+            let glyph_cache = &mut *self.glyph_cache.borrow_mut();
             let fg = config.colors.primary.background;
             for (i, message_text) in text.iter().enumerate() {
                 let point = Point::new(start_line + i, Column(0));
@@ -984,25 +1531,90 @@ impl Display {
                     glyph_cache,
                 );
             }
+
+            // Underline the action buttons in the last line.
+            let buttons = message.buttons(&size_info);
+            let thickness = metrics.underline_thickness.max(1.);
+            let bottom = size_info.cell_height() * (start_line + text.len()) as f32;
+            let y = size_info.padding_y() + bottom - thickness / 2.;
+            let button_quads: Vec<_> = buttons
+                .into_iter()
+                .map(|(columns, _)| {
+                    let x = |column: usize| {
+                        size_info.cell_width().mul_add(column as f32, size_info.padding_x())
+                    };
+                    let start = QuadPoint { x: x(columns.start), y };
+                    let end = QuadPoint { x: x(columns.end), y };
+                    RenderQuad::line(start, end, thickness, fg, 1.)
+                })
+                .collect();
+            self.renderer.draw_quads(&size_info, &metrics, &button_quads);
         } else {
             // Draw rectangles.
             self.renderer.draw_rects(&size_info, &metrics, rects);
 
             // Draw rectangles.
-            self.renderer.draw_quads(&size_info, &metrics, quads);
+            self.draw_quads(&size_info, &metrics, quads, time_passes);
+        }
+
+        self.frame_buffers = frame;
+
+        if time_passes {
+            self.renderer.finish_pass_timing();
+            self.stats.record_pass_times(self.renderer.pass_times());
         }
 
         self.draw_render_timer(config);
 
+        // Draw text added by plugins, below any prompts which require input.
+        self.draw_plugin_text(config);
+
+        // Draw clipboard prompt or history picker above the terminal content.
+        if self.clipboard_prompt.active() {
+            let lines = self.clipboard_prompt.lines(self.size_info.columns());
+            self.draw_overlay(config, &lines);
+        } else if self.clipboard_picker.active() {
+            let lines = self.clipboard_picker.lines(self.size_info.columns());
+            self.draw_overlay(config, &lines);
+        }
+
         // Draw hyperlink uri preview.
         if has_highlighted_hint {
             let cursor_point = vi_cursor_point.or(Some(cursor_point));
             self.draw_hyperlink_preview(config, cursor_point, display_offset);
         }
 
+        // Separate panes of split windows.
+        self.draw_pane_borders(config, panes, &metrics);
+
+        // Frame windows which share their input with other terminals.
+        if self.broadcast.active() {
+            self.draw_broadcast_frame(config, &metrics);
+        }
+
+        if self.title_bar_height() > 0. {
+            self.draw_title_bar(config, &metrics);
+        }
+
+        self.renderer.end_color_filter(&self.size_info);
+
+        // Remove the restriction to the damaged region, before anything else is drawn.
+        if redraw_region.is_some() {
+            self.renderer.set_scissor(None);
+        }
+
+        // Capture the frame before presenting it, since the buffer is undefined afterwards.
+        if let Some(path) = self.pending_screenshot.take() {
+            let (width, height) = (self.window_size.width as u32, self.window_size.height as u32);
+            let pixels = self.renderer.read_pixels(width, height);
+            screenshot::save(path, width, height, pixels);
+        }
+
         // Notify winit that we're about to present.
         self.window.pre_present_notify();
 
+        let damage = self.damaged_share();
+
         // Clearing debug highlights from the previous frame requires full redraw.
         self.swap_buffers();
 
@@ -1015,24 +1627,146 @@ impl Display {
 
         // XXX: Request the new frame after swapping buffers, so the
         // time to finish OpenGL operations is accounted for in the timeout.
-        if matches!(
-            self.raw_window_handle,
-            RawWindowHandle::AppKit(_) | RawWindowHandle::Xlib(_) | RawWindowHandle::Xcb(_)
-        ) {
+        //
+        // Other platforms pace frames themselves, unless the frame rate is limited.
+        if self.present_interval().is_some()
+            || matches!(
+                self.raw_window_handle,
+                RawWindowHandle::AppKit(_) | RawWindowHandle::Xlib(_) | RawWindowHandle::Xcb(_)
+            )
+        {
             self.request_frame(scheduler);
         }
 
+        // Remember the damage, to update buffers which are reused a few frames later.
+        if self.collect_damage() {
+            let damage = damage::bounding_rect(&self.damage_rects);
+            self.damage_history.push(damage.unwrap_or_else(|| DamageRect::new(0, 0, 0, 0)));
+        }
+
         self.damage_rects.clear();
 
         // Append damage rects we've enqueued for the next frame.
         mem::swap(&mut self.damage_rects, &mut self.next_frame_damage_rects);
+
+        self.stats.record_frame(frame_start.elapsed(), damage);
+    }
+
+    /// Check if presenting frames is limited to a maximum frame rate.
+    pub fn frame_rate_limited(&self) -> bool {
+        self.present_interval().is_some()
+    }
+
+    /// Update whether the system is running on battery power.
+    pub fn set_on_battery(&mut self, on_battery: bool) {
+        self.on_battery = on_battery;
+    }
+
+    /// Check if animations are throttled to save battery.
+    pub fn power_saving(&self) -> bool {
+        self.on_battery && self.battery_saver
+    }
+
+    /// Minimum time between presented frames.
+    fn present_interval(&self) -> Option<Duration> {
+        let battery_interval = self.power_saving().then_some(BATTERY_FRAME_INTERVAL);
+        match (self.frame_interval, battery_interval) {
+            (Some(frame_interval), Some(battery_interval)) => {
+                Some(frame_interval.max(battery_interval))
+            },
+            (frame_interval, battery_interval) => frame_interval.or(battery_interval),
+        }
+    }
+
+    /// Check if glyphs are still being rasterized in the background.
+    pub fn glyphs_pending(&self) -> bool {
+        self.glyph_cache.borrow().rasterization_pending()
+    }
+
+    /// Block until all submitted rendering commands have been executed by the GPU.
+    pub fn wait_for_gpu(&self) {
+        self.renderer.finish();
+    }
+
+    /// Performance metrics of this window.
+    pub fn stats(&self) -> RenderStats {
+        self.stats.report(self.meter.average(), self.glyph_cache.borrow().lookups())
+    }
+
+    /// Font size of the focused terminal.
+    pub fn font_size(&self) -> Size {
+        self.glyph_cache.borrow().font_size
+    }
+
+    /// Cell dimensions of a terminal drawn at a different font size than the focused terminal.
+    pub fn cell_size(&self, config: &UiConfig, font_size: Size) -> (f32, f32) {
+        match self.glyph_cache.borrow_mut().metrics_at(font_size) {
+            Ok(metrics) => compute_cell_size(config, &metrics),
+            Err(err) => {
+                error!("Unable to load font size {:?}: {}", font_size, err);
+                (self.size_info.cell_width(), self.size_info.cell_height())
+            },
+        }
+    }
+
+    /// Track the bytes read from the PTY through its event loop's counter.
+    pub fn set_pty_counter(&mut self, counter: Arc<AtomicUsize>) {
+        self.stats.set_pty_counter(counter);
     }
 
     /// Update to a new configuration.
     pub fn update_config(&mut self, config: &UiConfig) {
         self.debug_damage = config.debug.highlight_damage;
+        self.frame_interval = config.window.frame_interval();
+        self.battery_saver = config.window.battery_saver;
         self.visual_bell.update_config(&config.bell);
-        self.colors = List::from(&config.colors);
+        self.high_contrast = config.colors.high_contrast;
+        self.color_filter = config.colors.color_filter;
+        self.update_colors(config);
+        self.hint_state.update_config(&config.hints);
+        self.highlights.update_config(&config.highlights.rules);
+    }
+
+    /// Update whether the system asks for high contrast.
+    pub fn set_system_high_contrast(&mut self, high_contrast: bool, config: &UiConfig) {
+        self.system_high_contrast = high_contrast;
+        self.update_colors(config);
+    }
+
+    /// Check if colors are replaced by the high contrast palette.
+    pub fn high_contrast(&self) -> bool {
+        match self.high_contrast {
+            HighContrast::Off => false,
+            HighContrast::On => true,
+            HighContrast::System => self.system_high_contrast,
+        }
+    }
+
+    /// Opacity of the window background, which is opaque in high contrast mode.
+    pub fn window_opacity(&self, config: &UiConfig) -> f32 {
+        if self.high_contrast() {
+            1.
+        } else {
+            config.window_opacity()
+        }
+    }
+
+    /// Rebuild the color palette.
+    fn update_colors(&mut self, config: &UiConfig) {
+        self.colors = if self.high_contrast() {
+            List::high_contrast(&config.colors)
+        } else {
+            List::from(&config.colors)
+        };
+    }
+
+    /// Font metrics used for drawing underlines and strikeouts.
+    fn line_metrics(&self, metrics: crossfont::Metrics) -> crossfont::Metrics {
+        if self.high_contrast() {
+            thick_lines(metrics)
+        } else {
+            metrics
+        }
     }
 
     /// Update the mouse/vi mode cursor hint highlighting.
@@ -1049,7 +1783,7 @@ impl Display {
         let vi_highlighted_hint = if term.mode().contains(TermMode::VI) {
             let mods = ModifiersState::all();
             let point = term.vi_mode_cursor.point;
-            hint::highlighted_at(term, config, point, mods)
+            hint::highlighted_at(term, config, self.hint_state.paths_mut(), point, mods)
         } else {
             None
         };
@@ -1059,13 +1793,15 @@ impl Display {
         // Abort if mouse highlighting conditions are not met.
         if !mouse.inside_text_area || !term.selection.as_ref().map_or(true, Selection::is_empty) {
             dirty |= self.highlighted_hint.is_some();
+            self.animate_hover(config, None);
             self.highlighted_hint = None;
             return dirty;
         }
 
         // Find highlighted hint at mouse position.
         let point = mouse.point(&self.size_info, term.grid().display_offset());
-        let highlighted_hint = hint::highlighted_at(term, config, point, modifiers);
+        let paths = self.hint_state.paths_mut();
+        let highlighted_hint = hint::highlighted_at(term, config, paths, point, modifiers);
 
         // Update cursor shape.
         if highlighted_hint.is_some() {
@@ -1084,11 +1820,45 @@ impl Display {
         }
 
         dirty |= self.highlighted_hint != highlighted_hint;
+        self.animate_hover(config, highlighted_hint.as_ref());
         self.highlighted_hint = highlighted_hint;
 
         dirty
     }
 
+    /// Quads for the lines of ReGIS graphics, scaled to the text area.
+    fn regis_quads<T>(&self, term: &Term<T>, thickness: f32) -> Vec<RenderQuad> {
+        let size_info = &self.size_info;
+        let scale_x = size_info.columns() as f32 * size_info.cell_width() / regis::SCREEN_WIDTH;
+        let scale_y =
+            size_info.screen_lines() as f32 * size_info.cell_height() / regis::SCREEN_HEIGHT;
+        let quad_point = |point: RegisPoint| QuadPoint {
+            x: size_info.padding_x() + point.x * scale_x,
+            y: size_info.padding_y() + point.y * scale_y,
+        };
+
+        term.regis()
+            .lines()
+            .iter()
+            .map(|line| {
+                let index = line.color as usize;
+                let color = term.colors()[index].unwrap_or(self.colors[index]);
+                let (start, end) = (quad_point(line.start), quad_point(line.end));
+                RenderQuad::line(start, end, thickness.max(1.), color, 1.)
+            })
+            .collect()
+    }
+
+    /// Animate the underline when the hint hovered by the mouse changes.
+    fn animate_hover(&mut self, config: &UiConfig, hovered: Option<&HintMatch>) {
+        if config.window.reduce_motion || self.highlighted_hint.as_ref() == hovered {
+            return;
+        }
+
+        let previous = self.highlighted_hint.as_ref().map(HintMatch::bounds);
+        self.hover_animation.hover(previous, hovered.map(HintMatch::bounds));
+    }
+
     #[inline(never)]
     fn draw_ime_preview(
         &mut self,
@@ -1112,12 +1882,14 @@ impl Display {
 
         // Get the visible preedit.
         let visible_text: String = match (preedit.cursor_byte_offset, preedit.cursor_end_offset) {
-            (Some(byte_offset), Some(end_offset)) if end_offset > num_cols => StrShortener::new(
-                &preedit.text[byte_offset..],
-                num_cols,
-                ShortenDirection::Right,
-                Some(SHORTENER),
-            ),
+            (Some((byte_offset, _)), Some((end_offset, _))) if end_offset > num_cols => {
+                StrShortener::new(
+                    &preedit.text[byte_offset..],
+                    num_cols,
+                    ShortenDirection::Right,
+                    Some(SHORTENER),
+                )
+            },
             _ => {
                 StrShortener::new(&preedit.text, num_cols, ShortenDirection::Left, Some(SHORTENER))
             },
@@ -1132,7 +1904,7 @@ impl Display {
         let start = Point::new(point.line, Column(start));
         let end = Point::new(point.line, Column(end - 1));
 
-        let glyph_cache = &mut self.glyph_cache;
+        let glyph_cache = &mut *self.glyph_cache.borrow_mut();
         let metrics = glyph_cache.font_metrics();
 
         self.renderer.draw_string(
@@ -1152,25 +1924,74 @@ impl Display {
 
         // Add underline for preedit text.
         let underline = RenderLine { start, end, color: fg };
-        rects.extend(underline.rects(Flags::UNDERLINE, &metrics, &self.size_info));
+        underline.rects(rects, Flags::UNDERLINE, &metrics, &self.size_info);
 
-        let ime_popup_point = match preedit.cursor_end_offset {
-            Some(cursor_end_offset) if cursor_end_offset != 0 => {
-                let is_wide = preedit.text[preedit.cursor_byte_offset.unwrap_or_default()..]
+        // Compute the visible columns covered by the preedit cursor.
+        let cursor_columns = match (preedit.cursor_byte_offset, preedit.cursor_end_offset) {
+            // Text was shortened at its end, so the cursor starts at the first column.
+            (Some((start_byte, end_byte)), Some((start_offset, _))) if start_offset > num_cols => {
+                let width = preedit.text[start_byte..end_byte]
                     .chars()
-                    .next()
-                    .map(|ch| ch.width() == Some(2))
-                    .unwrap_or_default();
+                    .fold(0, |acc, ch| acc + ch.width().unwrap_or(1));
+                Some((0, cmp::min(width, visible_len)))
+            },
+            (Some(_), Some((start_offset, end_offset))) => Some((
+                visible_len.saturating_sub(start_offset),
+                visible_len.saturating_sub(end_offset),
+            )),
+            _ => None,
+        };
+
+        let ime_popup_point = match cursor_columns {
+            // Highlight the clause which is currently being converted.
+            Some((cursor_start, cursor_end)) if cursor_start < cursor_end => {
+                let highlight_start = Point::new(start.line, start.column + cursor_start);
+                let highlight = visible_text
+                    .chars()
+                    .scan((0, false), |(column, after_wide), ch| {
+                        // Spacers following wide chars share the wide char's columns.
+                        if mem::take(after_wide) {
+                            return Some((*column - 2, ch));
+                        }
 
-                let cursor_column = Column(
-                    (end.column.0 as isize - cursor_end_offset as isize + 1).max(0) as usize,
+                        let char_column = *column;
+                        let width = ch.width().unwrap_or(1);
+                        *after_wide = width == 2;
+                        *column += width;
+
+                        Some((char_column, ch))
+                    })
+                    .skip_while(|(column, _)| *column < cursor_start)
+                    .take_while(|(column, _)| *column < cursor_end)
+                    .map(|(_, ch)| ch);
+                self.renderer.draw_string(
+                    highlight_start,
+                    bg,
+                    fg,
+                    highlight,
+                    &self.size_info,
+                    &mut self.glyph_cache.borrow_mut(),
                 );
-                let cursor_point = Point::new(point.line, cursor_column);
-                cursor_point
+
+                highlight_start
+            },
+            // Draw a beam cursor at the insertion point.
+            Some((cursor_start, _)) => {
+                let cursor_point = Point::new(start.line, start.column + cursor_start);
+                let thickness = config.terminal_config.cursor.thickness();
+                let width = (self.size_info.cell_width() * thickness).round().max(1.);
+                let x = self.size_info.padding_x()
+                    + cursor_point.column.0 as f32 * self.size_info.cell_width();
+                let y = self.size_info.padding_y()
+                    + cursor_point.line as f32 * self.size_info.cell_height();
+                rects.push(RenderRect::new(x, y, width, self.size_info.cell_height(), fg, 1.));
+
+                cmp::min(cursor_point, end)
             },
-            _ => end,
+            None => start,
         };
 
+        // Report the candidate window position to the platform.
         self.window.update_ime_position(ime_popup_point, &self.size_info);
     }
 
@@ -1260,7 +2081,8 @@ impl Display {
                 self.next_frame_damage_rects.push(uri_preview_damage);
             }
 
-            self.renderer.draw_string(point, fg, bg, uri, &self.size_info, &mut self.glyph_cache);
+            let glyph_cache = &mut *self.glyph_cache.borrow_mut();
+            self.renderer.draw_string(point, fg, bg, uri, &self.size_info, glyph_cache);
         }
     }
 
@@ -1282,8 +2104,225 @@ impl Display {
             bg,
             text.chars(),
             &self.size_info,
-            &mut self.glyph_cache,
+            &mut self.glyph_cache.borrow_mut(),
+        );
+    }
+
+    /// Draw lines of an overlay, like the clipboard history picker, at the top of the window.
+    #[inline(never)]
+    fn draw_overlay(&mut self, config: &UiConfig, lines: &[String]) {
+        let num_cols = self.size_info.columns();
+        let num_lines = self.size_info.screen_lines();
+
+        let fg = config.colors.footer_bar_foreground();
+        let bg = config.colors.footer_bar_background();
+        for (line, text) in lines.iter().take(num_lines).enumerate() {
+            let point = Point::new(line, Column(0));
+
+            // Damage the overlay for the next frame as well, so it's removed once closed.
+            if self.collect_damage() {
+                let overlay_damage = self.damage_from_point(point, num_cols as u32);
+                self.damage_rects.push(overlay_damage);
+                self.next_frame_damage_rects.push(overlay_damage);
+            }
+
+            let glyph_cache = &mut *self.glyph_cache.borrow_mut();
+            self.renderer.draw_string(point, fg, bg, text.chars(), &self.size_info, glyph_cache);
+        }
+    }
+
+    /// Draw the terminals of panes without keyboard focus.
+    ///
+    /// Panes without a font size of their own are drawn at `font_size`.
+    fn draw_unfocused_panes(&mut self, panes: &[Pane], font_size: Size) {
+        let high_contrast = self.high_contrast();
+        let mut rects = Vec::new();
+        for pane in panes {
+            let config = &*pane.config;
+            let size_info = pane.size_info;
+            let (x, y) = self.viewport_origin(Some(pane.rect));
+            self.renderer.set_viewport_origin(x, y);
+            self.renderer.set_viewport(&size_info);
+
+            // Switch the glyph cache to the font size of the pane.
+            let font_size = pane.font_size.unwrap_or(font_size);
+            let glyph_cache = &mut *self.glyph_cache.borrow_mut();
+            let metrics = match glyph_cache.metrics_at(font_size) {
+                Ok(metrics) => metrics,
+                Err(err) => {
+                    error!("Unable to load font size {:?}: {}", font_size, err);
+                    glyph_cache.font_metrics()
+                },
+            };
+            let (focused_size, focused_metrics) = glyph_cache.set_draw_size(font_size, metrics);
+
+            let colors = if high_contrast {
+                List::high_contrast(&config.colors)
+            } else {
+                List::from(&config.colors)
+            };
+            let mut terminal = pane.terminal.lock();
+            let content = RenderableContent::unfocused(config, &colors, &terminal, high_contrast);
+            let background_color = content.color(NamedColor::Background as usize);
+
+            // Fill the pane with its terminal's background color.
+            let region = DamageRect::new(x, y, pane.rect.width as i32, pane.rect.height as i32);
+            self.renderer.set_scissor(Some(region));
+            let opacity = if high_contrast { 1. } else { config.window_opacity() };
+            self.renderer.clear(background_color, opacity);
+            self.renderer.set_scissor(None);
+
+            let mut lines = RenderLines::new();
+            self.renderer.draw_cells(
+                &size_info,
+                glyph_cache,
+                content.inspect(|cell| lines.update(cell)),
+            );
+            glyph_cache.set_draw_size(focused_size, focused_metrics);
+
+            let metrics = if high_contrast { thick_lines(metrics) } else { metrics };
+            rects.clear();
+            lines.rects(&mut rects, &metrics, &size_info);
+            self.renderer.draw_rects(&size_info, &metrics, &rects);
+
+            // Damage is not tracked for split windows.
+            terminal.reset_damage();
+        }
+    }
+
+    /// Draw the borders between panes, highlighting the focused pane.
+    fn draw_pane_borders(
+        &mut self,
+        config: &UiConfig,
+        panes: &[Pane],
+        metrics: &crossfont::Metrics,
+    ) {
+        let focused_rect = match self.pane_rect {
+            Some(focused_rect) if !panes.is_empty() => focused_rect,
+            _ => return,
+        };
+
+        let border_width = f32::from(config.panes.border_width) * self.window.scale_factor as f32;
+        let rects: Vec<_> = panes
+            .iter()
+            .flat_map(|pane| pane_border_rects(pane.rect, border_width, config.panes.border_color))
+            .collect();
+        let quads =
+            pane_border_quads(focused_rect, border_width, config.panes.focused_border_color);
+
+        // Borders are positioned relative to the entire window.
+        let window_size = self.window_size_info();
+        self.renderer.set_viewport_origin(0, 0);
+        self.renderer.draw_rects(&window_size, metrics, &rects);
+        self.renderer.draw_quads(&window_size, metrics, &quads);
+
+        let (x, y) = self.viewport_origin(self.pane_rect);
+        self.renderer.set_viewport_origin(x, y);
+        self.renderer.set_viewport(&self.size_info);
+    }
+
+    /// Draw a frame along the window's edges, indicating broadcasted input.
+    fn draw_broadcast_frame(&mut self, config: &UiConfig, metrics: &crossfont::Metrics) {
+        let scale_factor = self.window.scale_factor as f32;
+        let width = f32::from(config.panes.border_width.max(1)) * scale_factor;
+        let window_rect = PaneRect::new(0., 0., self.window_size.width, self.window_size.height);
+        let rects = pane_border_rects(window_rect.expand(-width), width, config.colors.broadcast());
+
+        let window_size = self.window_size_info();
+        self.renderer.set_viewport_origin(0, 0);
+        self.renderer.draw_rects(&window_size, metrics, &rects);
+
+        let (x, y) = self.viewport_origin(self.pane_rect);
+        self.renderer.set_viewport_origin(x, y);
+        self.renderer.set_viewport(&self.size_info);
+    }
+
+    /// Draw the title bar replacing client-side decorations.
+    fn draw_title_bar(&mut self, config: &UiConfig, metrics: &crossfont::Metrics) {
+        let scale_factor = self.window.scale_factor as f32;
+        let height = title_bar::height(scale_factor);
+        let (cell_width, cell_height) = (self.size_info.cell_width(), self.size_info.cell_height());
+        let padding_y = ((height - cell_height) / 2.).floor().max(0.);
+        let size_info = SizeInfo::new(
+            self.window_size.width,
+            height,
+            cell_width,
+            cell_height,
+            0.,
+            padding_y,
+            false,
+        );
+
+        let fg = config.colors.footer_bar_foreground();
+        let bg = config.colors.footer_bar_background();
+
+        let title_bar_rect = PaneRect::new(0., 0., self.window_size.width, height);
+        let (x, y) = self.viewport_origin(Some(title_bar_rect));
+        self.renderer.set_viewport_origin(x, y);
+
+        let background = RenderRect::new(0., 0., size_info.width(), height, bg, 1.);
+        self.renderer.draw_rects(&size_info, metrics, &[background]);
+
+        // Center the title, without overlapping the window buttons.
+        let buttons_columns = (title_bar::buttons_width(scale_factor) / cell_width).ceil() as usize;
+        let max_columns = size_info.columns().saturating_sub(2 * buttons_columns);
+        let title: Vec<char> = self.window.title().chars().take(max_columns).collect();
+        let column = Column((size_info.columns() - title.len()) / 2);
+        self.renderer.draw_string(
+            Point::new(0, column),
+            fg,
+            bg,
+            title.into_iter(),
+            &size_info,
+            &mut self.glyph_cache.borrow_mut(),
         );
+
+        let quads = title_bar::button_quads(size_info.width(), scale_factor, fg);
+        self.renderer.draw_quads(&size_info, metrics, &quads);
+
+        let (x, y) = self.viewport_origin(self.pane_rect);
+        self.renderer.set_viewport_origin(x, y);
+        self.renderer.set_viewport(&self.size_info);
+    }
+
+    /// Size information covering the entire window, rather than just the focused pane.
+    fn window_size_info(&self) -> SizeInfo {
+        SizeInfo::new(
+            self.window_size.width,
+            self.window_size.height,
+            self.size_info.cell_width(),
+            self.size_info.cell_height(),
+            0.,
+            0.,
+            false,
+        )
+    }
+
+    /// Bottom-left corner of a pane's area in OpenGL window coordinates.
+    fn viewport_origin(&self, pane_rect: Option<PaneRect>) -> (i32, i32) {
+        match pane_rect {
+            Some(rect) => (rect.x as i32, (self.window_size.height - rect.y - rect.height) as i32),
+            None => (0, 0),
+        }
+    }
+
+    /// Draw quads, measuring their GPU time if requested.
+    fn draw_quads(
+        &mut self,
+        size_info: &SizeInfo,
+        metrics: &crossfont::Metrics,
+        quads: &[RenderQuad],
+        time: bool,
+    ) {
+        if time {
+            self.renderer.begin_pass(RenderPass::Quads);
+        }
+
+        self.renderer.draw_quads(size_info, metrics, quads);
+
+        if time {
+            self.renderer.end_pass(RenderPass::Quads);
+        }
     }
 
     /// Draw render timer.
@@ -1293,7 +2332,7 @@ impl Display {
             return;
         }
 
-        let timing = format!("{:.3} usec", self.meter.average());
+        let timing = self.stats().to_string();
         let point = Point::new(self.size_info.screen_lines().saturating_sub(2), Column(0));
         let fg = config.colors.primary.background;
         let bg = config.colors.normal.red;
@@ -1308,7 +2347,7 @@ impl Display {
             self.next_frame_damage_rects.push(render_timer_damage)
         }
 
-        let glyph_cache = &mut self.glyph_cache;
+        let glyph_cache = &mut *self.glyph_cache.borrow_mut();
         self.renderer.draw_string(point, fg, bg, timing.chars(), &self.size_info, glyph_cache);
     }
 
@@ -1350,11 +2389,267 @@ impl Display {
 
         // Do not render anything if it would obscure the vi mode cursor.
         if obstructed_column.map_or(true, |obstructed_column| obstructed_column < column) {
-            let glyph_cache = &mut self.glyph_cache;
+            let glyph_cache = &mut *self.glyph_cache.borrow_mut();
+            self.renderer.draw_string(point, fg, bg, text.chars(), &self.size_info, glyph_cache);
+        }
+    }
+
+    /// Draw the times at which lines were written at the right end of each line.
+    fn draw_timestamps(
+        &mut self,
+        config: &UiConfig,
+        timestamps: Vec<(usize, String)>,
+        vi_cursor_point: Option<Point<usize>>,
+    ) {
+        let colors = &config.colors;
+        let fg = colors.line_indicator.foreground.unwrap_or(colors.primary.background);
+        let bg = colors.line_indicator.background.unwrap_or(colors.primary.foreground);
+
+        for (line, text) in timestamps {
+            let width = text.chars().count();
+            let column = Column(self.size_info.columns().saturating_sub(width));
+            let point = Point::new(line, column);
+
+            // Do not render anything if it would obscure the vi mode cursor.
+            let obstructed = vi_cursor_point
+                .map_or(false, |cursor| cursor.line == line && cursor.column >= column);
+            if obstructed {
+                continue;
+            }
+
+            // Relative times change width, so the previous frame's timestamp must be cleared too.
+            if self.collect_damage() {
+                let damage = self.damage_from_point(point, width as u32);
+                self.damage_rects.push(damage);
+                self.next_frame_damage_rects.push(damage);
+            }
+
+            let glyph_cache = &mut *self.glyph_cache.borrow_mut();
             self.renderer.draw_string(point, fg, bg, text.chars(), &self.size_info, glyph_cache);
         }
     }
 
+    /// Draw the exit status of finished commands left of their lines.
+    fn draw_command_status(
+        &mut self,
+        config: &UiConfig,
+        commands: Vec<(RangeInclusive<Line>, Option<i32>)>,
+        display_offset: usize,
+        rects: &mut Vec<RenderRect>,
+    ) {
+        let size_info = self.size_info;
+
+        // Use the left padding when possible, to avoid obscuring any text.
+        let width = (size_info.cell_width() / 4.).floor().max(1.);
+        let x = (size_info.padding_x() - width).max(0.);
+
+        for (lines, exit_code) in commands {
+            let color = match exit_code {
+                Some(0) => config.colors.command_success(),
+                Some(_) => Some(config.colors.command_failure()),
+                None => None,
+            };
+            let color = match color {
+                Some(color) => color,
+                None => continue,
+            };
+
+            // Clamp the command to the viewport.
+            let first = (lines.start().0 + display_offset as i32).max(0);
+            let last = (lines.end().0 + display_offset as i32).min(size_info.bottommost_line().0);
+            if first > last {
+                continue;
+            }
+
+            let y = size_info.padding_y() + first as f32 * size_info.cell_height();
+            let height = (last - first + 1) as f32 * size_info.cell_height();
+            rects.push(RenderRect::new(x, y, width, height, color, 1.));
+
+            if self.collect_damage() {
+                let damage_y = size_info.height() - y - height;
+                let damage =
+                    DamageRect::new(x as i32, damage_y as i32, width as i32, height as i32);
+                self.damage_rects.push(damage);
+                self.next_frame_damage_rects.push(damage);
+            }
+        }
+    }
+
+    /// Draw an indicator right of all bookmarked lines.
+    fn draw_bookmarks(
+        &mut self,
+        config: &UiConfig,
+        bookmarks: Vec<Line>,
+        display_offset: usize,
+        rects: &mut Vec<RenderRect>,
+    ) {
+        let size_info = self.size_info;
+
+        // Use the right padding when possible, to avoid obscuring any text.
+        let width = (size_info.cell_width() / 4.).floor().max(1.);
+        let text_end = size_info.padding_x() + size_info.columns() as f32 * size_info.cell_width();
+        let x = text_end.min(size_info.width() - width);
+        let height = size_info.cell_height();
+
+        for line in bookmarks {
+            let y = size_info.padding_y() + (line.0 + display_offset as i32) as f32 * height;
+            rects.push(RenderRect::new(x, y, width, height, config.colors.bookmark(), 1.));
+
+            if self.collect_damage() {
+                let damage_y = size_info.height() - y - height;
+                let damage =
+                    DamageRect::new(x as i32, damage_y as i32, width as i32, height as i32);
+                self.damage_rects.push(damage);
+                self.next_frame_damage_rects.push(damage);
+            }
+        }
+    }
+
+    /// Region covered by a selection in the viewport, drawn with rounded corners.
+    fn selection_region(
+        &mut self,
+        config: &UiConfig,
+        range: SelectionRange,
+        display_offset: usize,
+        fill: Rgb,
+    ) -> Option<RenderRegion> {
+        let size_info = self.size_info;
+        let first_line = range.start.line.0 + display_offset as i32;
+        let last_line = range.end.line.0 + display_offset as i32;
+        let top = first_line.max(0);
+        let bottom = last_line.min(size_info.bottommost_line().0);
+
+        let (cell_width, cell_height) = (size_info.cell_width(), size_info.cell_height());
+        let spans: Vec<_> = (top..=bottom)
+            .map(|line| {
+                let start = if range.is_block || line == first_line {
+                    range.start.column
+                } else {
+                    Column(0)
+                };
+                let end = if range.is_block || line == last_line {
+                    range.end.column
+                } else {
+                    size_info.last_column()
+                };
+
+                let left = size_info.padding_x() + start.0 as f32 * cell_width;
+                let right = size_info.padding_x() + (end.0 + 1) as f32 * cell_width;
+                let y = size_info.padding_y() + line as f32 * cell_height;
+                RegionSpan::new(left, y, right, y + cell_height)
+            })
+            .collect();
+
+        let mut region = RenderRegion::new(&spans, (cell_width / 3.).floor(), fill)?;
+        if config.terminal_config.selection.border {
+            let width = (self.window.scale_factor as f32).round().max(1.);
+            region = region.with_border(fill * BORDER_FACTOR, width);
+        }
+
+        // Damage the entire region, since fillets reach into the cells next to the selection.
+        if self.collect_damage() {
+            let bounds = region.bounds();
+            let (width, height) = (bounds.right - bounds.left, bounds.bottom - bounds.top);
+            let y = size_info.height() - bounds.bottom;
+            let damage = DamageRect::new(bounds.left as i32, y as i32, width as i32, height as i32);
+            self.damage_rects.push(damage);
+            self.next_frame_damage_rects.push(damage);
+        }
+
+        Some(region)
+    }
+
+    /// Draw a progress bar at the top of the window.
+    fn draw_progress(
+        &mut self,
+        config: &UiConfig,
+        progress: Progress,
+        rects: &mut Vec<RenderRect>,
+    ) {
+        let size_info = self.size_info;
+        let height = (size_info.cell_height() / 8.).floor().max(2.);
+
+        let colors = &config.colors.normal;
+        let (color, alpha, percent) = match progress.state {
+            ProgressState::Normal => (colors.blue, 1., progress.percent),
+            ProgressState::Error => (colors.red, 1., progress.percent),
+            ProgressState::Paused => (colors.yellow, 1., progress.percent),
+            ProgressState::Indeterminate => (colors.blue, 0.5, 100),
+        };
+
+        let width = size_info.width() * percent as f32 / 100.;
+        rects.push(RenderRect::new(0., 0., width, height, color, alpha));
+
+        if self.collect_damage() {
+            let y = size_info.height() - height;
+            let damage = DamageRect::new(0, y as i32, size_info.width() as i32, height as i32);
+            self.damage_rects.push(damage);
+            self.next_frame_damage_rects.push(damage);
+        }
+    }
+
+    /// Draw the quads of the plugin overlay, clipped to the viewport.
+    fn draw_plugin_quads(&mut self, rects: &mut Vec<RenderRect>) {
+        let size_info = self.size_info;
+
+        for quad in self.plugin_overlay.quads() {
+            let lines = quad.lines.min(size_info.screen_lines().saturating_sub(quad.line));
+            let columns = quad.columns.min(size_info.columns().saturating_sub(quad.column));
+            if lines == 0 || columns == 0 {
+                continue;
+            }
+
+            let x = size_info.padding_x() + quad.column as f32 * size_info.cell_width();
+            let y = size_info.padding_y() + quad.line as f32 * size_info.cell_height();
+            let width = columns as f32 * size_info.cell_width();
+            let height = lines as f32 * size_info.cell_height();
+            rects.push(RenderRect::new(x, y, width, height, quad.color, quad.alpha));
+
+            if self.collect_damage() {
+                let damage_y = size_info.height() - y - height;
+                let damage =
+                    DamageRect::new(x as i32, damage_y as i32, width as i32, height as i32);
+                self.damage_rects.push(damage);
+                self.next_frame_damage_rects.push(damage);
+            }
+        }
+    }
+
+    /// Draw the text of the plugin overlay, clipped to the viewport.
+    fn draw_plugin_text(&mut self, config: &UiConfig) {
+        let num_cols = self.size_info.columns();
+        let num_lines = self.size_info.screen_lines();
+
+        for text in self.plugin_overlay.texts() {
+            if text.line >= num_lines || text.column >= num_cols {
+                continue;
+            }
+
+            let point = Point::new(text.line, Column(text.column));
+            let max_width = num_cols - text.column;
+            let text_chars: String =
+                StrShortener::new(&text.text, max_width, ShortenDirection::Right, None).collect();
+
+            if self.collect_damage() {
+                let damage = self.damage_from_point(point, max_width as u32);
+                self.damage_rects.push(damage);
+                self.next_frame_damage_rects.push(damage);
+            }
+
+            let fg = text.fg.unwrap_or_else(|| config.colors.footer_bar_foreground());
+            let bg = text.bg.unwrap_or_else(|| config.colors.footer_bar_background());
+            let glyph_cache = &mut *self.glyph_cache.borrow_mut();
+            self.renderer.draw_string(
+                point,
+                fg,
+                bg,
+                text_chars.chars(),
+                &self.size_info,
+                glyph_cache,
+            );
+        }
+    }
+
     /// Damage `len` starting from a `point`.
     ///
     /// This method also enqueues damage for the next frame automatically.
@@ -1386,8 +2681,42 @@ impl Display {
 
     /// Returns `true` if damage information should be collected, `false` otherwise.
     #[inline]
+    /// Region which has to be redrawn to bring the next buffer up to date.
+    ///
+    /// Returns `None` if the full frame has to be redrawn.
+    fn partial_redraw_region(&self) -> Option<DamageRect> {
+        // Damage highlights of previous frames have to be cleared.
+        if !self.collect_damage() || self.debug_damage {
+            return None;
+        }
+
+        let damage = damage::bounding_rect(&self.damage_rects)?;
+        self.damage_history.redraw_region(self.surface.buffer_age(), damage)
+    }
+
     fn collect_damage(&self) -> bool {
-        matches!(self.raw_window_handle, RawWindowHandle::Wayland(_)) || self.debug_damage
+        // Damage is tracked relative to the window, which only matches unsplit windows.
+        self.pane_rect.is_none()
+            && !self.broadcast.active()
+            && (matches!(self.raw_window_handle, RawWindowHandle::Wayland(_)) || self.debug_damage)
+    }
+
+    /// Share of the window covered by the current frame's damage.
+    ///
+    /// Overlapping damage is counted multiple times, so this is only an upper bound.
+    fn damaged_share(&self) -> f64 {
+        if !self.collect_damage() {
+            return 1.;
+        }
+
+        let window_area = f64::from(self.window_size.width * self.window_size.height);
+        let damaged_area: f64 = self
+            .damage_rects
+            .iter()
+            .map(|rect| f64::from(rect.width.max(0)) * f64::from(rect.height.max(0)))
+            .sum();
+
+        (damaged_area / window_area).min(1.)
     }
 
     /// Highlight damaged rects.
@@ -1405,7 +2734,7 @@ impl Display {
         }
     }
 
-    /// Requst a new frame for a window on Wayland.
+    /// Request a new frame once the next frame can be presented.
     fn request_frame(&mut self, scheduler: &mut Scheduler) {
         // Mark that we've used a frame.
         self.window.has_frame = false;
@@ -1422,7 +2751,13 @@ impl Display {
         let monitor_vblank_interval =
             Duration::from_micros((1000. * monitor_vblank_interval) as u64);
 
-        let swap_timeout = self.frame_timer.compute_timeout(monitor_vblank_interval);
+        // Never present faster than the configured frame rate.
+        let refresh_interval = match self.present_interval() {
+            Some(frame_interval) => frame_interval.max(monitor_vblank_interval),
+            None => monitor_vblank_interval,
+        };
+
+        let swap_timeout = self.frame_timer.compute_timeout(refresh_interval);
 
         let window_id = self.window.id();
         let timer_id = TimerId::new(Topic::Frame, window_id);
@@ -1439,6 +2774,7 @@ impl Drop for Display {
         self.make_current();
         unsafe {
             ManuallyDrop::drop(&mut self.renderer);
+            ManuallyDrop::drop(&mut self.glyph_cache);
             ManuallyDrop::drop(&mut self.context);
             ManuallyDrop::drop(&mut self.surface);
         }
@@ -1491,26 +2827,26 @@ pub struct Preedit {
     /// The preedit text.
     text: String,
 
-    /// Byte offset for cursor start into the preedit text.
+    /// Byte range of the cursor into the preedit text.
     ///
-    /// `None` means that the cursor is invisible.
-    cursor_byte_offset: Option<usize>,
+    /// `None` means that the cursor is invisible. When the start and end are equal, the cursor is
+    /// a caret, otherwise it highlights the clause which is currently being converted.
+    cursor_byte_offset: Option<(usize, usize)>,
 
-    /// The cursor offset from the end of the preedit in char width.
-    cursor_end_offset: Option<usize>,
+    /// The offsets of the cursor's start and end from the end of the preedit in char width.
+    cursor_end_offset: Option<(usize, usize)>,
 }
 
 impl Preedit {
-    pub fn new(text: String, cursor_byte_offset: Option<usize>) -> Self {
-        let cursor_end_offset = if let Some(byte_offset) = cursor_byte_offset {
-            // Convert byte offset into char offset.
-            let cursor_end_offset =
-                text[byte_offset..].chars().fold(0, |acc, ch| acc + ch.width().unwrap_or(1));
+    pub fn new(text: String, cursor_byte_offset: Option<(usize, usize)>) -> Self {
+        let cursor_end_offset = cursor_byte_offset.map(|(start, end)| {
+            // Convert byte offsets into char offsets.
+            let width_from = |offset: usize| {
+                text[offset..].chars().fold(0, |acc, ch| acc + ch.width().unwrap_or(1))
+            };
 
-            Some(cursor_end_offset)
-        } else {
-            None
-        };
+            (width_from(start), width_from(end))
+        });
 
         Self { text, cursor_byte_offset, cursor_end_offset }
     }
@@ -1631,6 +2967,13 @@ fn compute_cell_size(config: &UiConfig, metrics: &crossfont::Metrics) -> (f32, f
     )
 }
 
+/// Double the thickness of underlines and strikeouts.
+fn thick_lines(mut metrics: crossfont::Metrics) -> crossfont::Metrics {
+    metrics.underline_thickness *= 2.;
+    metrics.strikeout_thickness *= 2.;
+    metrics
+}
+
 /// Calculate the size of the window given padding, terminal dimensions and cell size.
 fn window_size(
     config: &UiConfig,
@@ -1649,3 +2992,71 @@ fn window_size(
 
     PhysicalSize::new(width as u32, height as u32)
 }
+
+/// Rects outlining an unfocused pane.
+fn pane_border_rects(rect: PaneRect, width: f32, color: Rgb) -> [RenderRect; 4] {
+    let outer = rect.expand(width);
+    [
+        RenderRect::new(outer.x, outer.y, outer.width, width, color, 1.),
+        RenderRect::new(outer.x, rect.y + rect.height, outer.width, width, color, 1.),
+        RenderRect::new(outer.x, rect.y, width, rect.height, color, 1.),
+        RenderRect::new(rect.x + rect.width, rect.y, width, rect.height, color, 1.),
+    ]
+}
+
+/// Arrow at the bottom of the terminal, pointing at new output below the viewport.
+fn new_output_indicator(size_info: &SizeInfo, color: Rgb) -> RenderQuad {
+    let width = size_info.cell_width() * 2.;
+    let height = size_info.cell_height() / 2.;
+    let bottom = size_info.padding_y() + size_info.screen_lines() as f32 * size_info.cell_height()
+        - height / 2.;
+    let center = size_info.width() / 2.;
+
+    let tip = QuadPoint { x: center, y: bottom };
+    let points = [
+        QuadPoint { x: center - width / 2., y: bottom - height },
+        QuadPoint { x: center + width / 2., y: bottom - height },
+        tip,
+        tip,
+    ];
+    RenderQuad::new(points, color, NEW_OUTPUT_ALPHA)
+}
+
+/// Quads outlining the focused pane, with mitered corners.
+fn pane_border_quads(rect: PaneRect, width: f32, color: Rgb) -> [RenderQuad; 4] {
+    let outer = rect.expand(width);
+    let point = |x, y| QuadPoint { x, y };
+
+    let (left, top) = (rect.x, rect.y);
+    let (right, bottom) = (rect.x + rect.width, rect.y + rect.height);
+    let (outer_left, outer_top) = (outer.x, outer.y);
+    let (outer_right, outer_bottom) = (outer.x + outer.width, outer.y + outer.height);
+
+    [
+        [
+            point(outer_left, outer_top),
+            point(outer_right, outer_top),
+            point(right, top),
+            point(left, top),
+        ],
+        [
+            point(left, bottom),
+            point(right, bottom),
+            point(outer_right, outer_bottom),
+            point(outer_left, outer_bottom),
+        ],
+        [
+            point(outer_left, outer_top),
+            point(left, top),
+            point(left, bottom),
+            point(outer_left, outer_bottom),
+        ],
+        [
+            point(right, top),
+            point(outer_right, outer_top),
+            point(outer_right, outer_bottom),
+            point(right, bottom),
+        ],
+    ]
+    .map(|points| RenderQuad::new(points, color, 1.))
+}
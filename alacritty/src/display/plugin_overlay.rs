@@ -0,0 +1,63 @@
+use alacritty_terminal::term::color::Rgb;
+
+/// Rectangle covering a range of cells in the viewport.
+#[cfg_attr(not(feature = "lua"), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlayQuad {
+    pub line: usize,
+    pub column: usize,
+    pub lines: usize,
+    pub columns: usize,
+    pub color: Rgb,
+    pub alpha: f32,
+}
+
+/// Text drawn on top of the cells in the viewport.
+#[cfg_attr(not(feature = "lua"), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlayText {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+
+    /// Foreground color, using the footer bar colors when missing.
+    pub fg: Option<Rgb>,
+
+    /// Background color, using the footer bar colors when missing.
+    pub bg: Option<Rgb>,
+}
+
+/// Quads and text drawn by plugins above the terminal content.
+///
+/// Everything drawn persists across frames until the overlay is cleared.
+#[derive(Default, Debug)]
+pub struct PluginOverlay {
+    quads: Vec<OverlayQuad>,
+    texts: Vec<OverlayText>,
+}
+
+impl PluginOverlay {
+    /// Add a quad to the overlay.
+    pub fn push_quad(&mut self, quad: OverlayQuad) {
+        self.quads.push(quad);
+    }
+
+    /// Add text to the overlay.
+    pub fn push_text(&mut self, text: OverlayText) {
+        self.texts.push(text);
+    }
+
+    /// Remove everything from the overlay.
+    pub fn clear(&mut self) {
+        self.quads.clear();
+        self.texts.clear();
+    }
+
+    pub fn quads(&self) -> &[OverlayQuad] {
+        &self.quads
+    }
+
+    pub fn texts(&self) -> &[OverlayText] {
+        &self.texts
+    }
+}
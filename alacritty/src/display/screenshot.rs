@@ -0,0 +1,91 @@
+//! Screenshots of the rendered frame.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{error, info};
+use png::{BitDepth, ColorType, Encoder, EncodingError};
+
+use alacritty_terminal::thread;
+
+/// Default path of a new screenshot, relative to Alacritty's working directory.
+pub fn default_path() -> PathBuf {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    PathBuf::from(format!("alacritty-screenshot-{}.png", timestamp.as_millis()))
+}
+
+/// Write premultiplied RGBA `pixels`, ordered from the top row to the bottom, to a PNG file.
+///
+/// Encoding happens in the background, to avoid blocking the event loop with large windows.
+pub fn save(path: PathBuf, width: u32, height: u32, mut pixels: Vec<u8>) {
+    thread::spawn_named("screenshot", move || {
+        unpremultiply(&mut pixels);
+
+        let result = File::create(&path)
+            .map_err(EncodingError::from)
+            .and_then(|file| encode(BufWriter::new(file), width, height, &pixels));
+        match result {
+            Ok(()) => info!("Wrote screenshot to {path:?}"),
+            Err(err) => error!("Unable to write screenshot to {path:?}: {err}"),
+        }
+    });
+}
+
+/// Encode RGBA pixels as PNG.
+fn encode<W: Write>(
+    writer: W,
+    width: u32,
+    height: u32,
+    pixels: &[u8],
+) -> Result<(), EncodingError> {
+    let mut encoder = Encoder::new(writer, width, height);
+    encoder.set_color(ColorType::Rgba);
+    encoder.set_depth(BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels)?;
+    writer.finish()
+}
+
+/// Convert premultiplied to straight alpha, which is expected by PNG.
+fn unpremultiply(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = u16::from(pixel[3]);
+        if alpha == 0 || alpha == 255 {
+            continue;
+        }
+
+        for channel in &mut pixel[..3] {
+            *channel = (u16::from(*channel) * 255 / alpha).min(255) as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use png::Decoder;
+
+    #[test]
+    fn straight_alpha() {
+        let mut pixels = vec![10, 20, 30, 255, 50, 0, 25, 127, 0, 0, 0, 0];
+        unpremultiply(&mut pixels);
+        assert_eq!(pixels, [10, 20, 30, 255, 100, 0, 50, 127, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn encode_rgba() {
+        let pixels = [255, 0, 0, 255, 0, 255, 0, 128];
+        let mut png = Vec::new();
+        encode(&mut png, 1, 2, &pixels).unwrap();
+
+        let mut reader = Decoder::new(png.as_slice()).read_info().unwrap();
+        let mut decoded = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut decoded).unwrap();
+        assert_eq!((info.width, info.height, info.color_type), (1, 2, ColorType::Rgba));
+        assert_eq!(decoded, pixels);
+    }
+}
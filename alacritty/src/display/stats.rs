@@ -0,0 +1,287 @@
+//! Performance metrics of a window.
+
+use std::fmt::{self, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::display::meter::Meter;
+use crate::renderer::timer::PassTimes;
+
+/// Minimum interval over which the PTY read throughput is measured.
+const THROUGHPUT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Collector for the performance metrics of a window.
+#[derive(Default)]
+pub struct Stats {
+    /// Average time spent drawing a frame.
+    frame_meter: Meter,
+
+    /// Number of frames drawn.
+    frames: u64,
+
+    /// Damaged share of the window in the last frame.
+    damage: f64,
+
+    /// Number of rectangles drawn in the last frame.
+    rects: usize,
+
+    /// Number of quads drawn in the last frame.
+    quads: usize,
+
+    /// GPU time of the last measured render passes.
+    pass_times: PassTimes,
+
+    /// Memory used by the scrollback history in the last frame, in bytes.
+    scrollback_memory: usize,
+
+    /// Memory used by the glyph atlas textures in the last frame, in bytes.
+    glyph_atlas_memory: usize,
+
+    /// Total number of bytes read from the PTY, updated by the PTY event loop.
+    pty_bytes: Option<Arc<AtomicUsize>>,
+
+    /// Bytes read from the PTY per second.
+    pty_throughput: f64,
+
+    /// Time and byte count at the start of the current throughput interval.
+    throughput_start: Option<(Instant, usize)>,
+}
+
+impl Stats {
+    /// Track the bytes read from the PTY through its event loop's counter.
+    pub fn set_pty_counter(&mut self, counter: Arc<AtomicUsize>) {
+        self.pty_bytes = Some(counter);
+        self.throughput_start = None;
+    }
+
+    /// Record a drawn frame.
+    pub fn record_frame(&mut self, duration: Duration, damage: f64) {
+        self.frame_meter.add_sample(duration);
+        self.frames += 1;
+        self.damage = damage;
+
+        // Restart the throughput interval once it has elapsed.
+        let now = Instant::now();
+        let pty_bytes = self.pty_bytes();
+        match self.throughput_start {
+            Some((start, start_bytes)) if now.duration_since(start) >= THROUGHPUT_INTERVAL => {
+                self.pty_throughput = throughput(start, start_bytes, now, pty_bytes);
+                self.throughput_start = Some((now, pty_bytes));
+            },
+            Some(_) => (),
+            None => self.throughput_start = Some((now, pty_bytes)),
+        }
+    }
+
+    /// Record the number of primitives submitted for the current frame.
+    pub fn record_primitives(&mut self, rects: usize, quads: usize) {
+        self.rects = rects;
+        self.quads = quads;
+    }
+
+    /// Record the GPU time of render passes.
+    pub fn record_pass_times(&mut self, pass_times: PassTimes) {
+        self.pass_times = pass_times;
+    }
+
+    /// Record the memory used by the window's content.
+    pub fn record_memory(&mut self, scrollback: usize, glyph_atlas: usize) {
+        self.scrollback_memory = scrollback;
+        self.glyph_atlas_memory = glyph_atlas;
+    }
+
+    /// Snapshot of all metrics.
+    ///
+    /// The `grid_time` is the average time spent drawing the grid in microseconds, while
+    /// `glyph_lookups` are the glyph cache's hits and misses.
+    pub fn report(&self, grid_time: f64, glyph_lookups: (u64, u64)) -> RenderStats {
+        let pty_bytes = self.pty_bytes();
+
+        // Include output received since the last frame when no frames are being drawn.
+        let now = Instant::now();
+        let pty_throughput = match self.throughput_start {
+            Some((start, start_bytes)) if now.duration_since(start) >= THROUGHPUT_INTERVAL => {
+                throughput(start, start_bytes, now, pty_bytes)
+            },
+            _ => self.pty_throughput,
+        };
+
+        let (glyph_cache_hits, glyph_cache_misses) = glyph_lookups;
+        let lookups = glyph_cache_hits + glyph_cache_misses;
+        let glyph_cache_hit_rate =
+            if lookups == 0 { 1. } else { glyph_cache_hits as f64 / lookups as f64 };
+
+        RenderStats {
+            frames: self.frames,
+            frame_time: self.frame_meter.average(),
+            grid_time,
+            damage: self.damage,
+            pty_bytes,
+            pty_throughput,
+            glyph_cache_hits,
+            glyph_cache_misses,
+            glyph_cache_hit_rate,
+            rects: self.rects,
+            quads: self.quads,
+            gpu_text_time: self.pass_times.text.map(|time| time.as_secs_f64() * 1_000_000.),
+            gpu_quad_time: self.pass_times.quads.map(|time| time.as_secs_f64() * 1_000_000.),
+            scrollback_memory: self.scrollback_memory,
+            glyph_atlas_memory: self.glyph_atlas_memory,
+        }
+    }
+
+    fn pty_bytes(&self) -> usize {
+        self.pty_bytes.as_ref().map_or(0, |bytes| bytes.load(Ordering::Relaxed))
+    }
+}
+
+/// Performance metrics reported by the `stats` IPC subcommand.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct RenderStats {
+    /// Number of frames drawn.
+    pub frames: u64,
+
+    /// Average time spent drawing a frame, in microseconds.
+    pub frame_time: f64,
+
+    /// Average time spent drawing the grid, in microseconds.
+    pub grid_time: f64,
+
+    /// Damaged share of the window in the last frame, from 0 to 1.
+    pub damage: f64,
+
+    /// Total number of bytes read from the PTY.
+    pub pty_bytes: usize,
+
+    /// Bytes read from the PTY per second.
+    pub pty_throughput: f64,
+
+    /// Number of glyph lookups served from the glyph cache.
+    pub glyph_cache_hits: u64,
+
+    /// Number of glyph lookups which required rasterization.
+    pub glyph_cache_misses: u64,
+
+    /// Share of glyph lookups served from the glyph cache, from 0 to 1.
+    pub glyph_cache_hit_rate: f64,
+
+    /// Number of rectangles drawn in the last frame.
+    pub rects: usize,
+
+    /// Number of quads drawn in the last frame.
+    pub quads: usize,
+
+    /// GPU time of the last measured text pass, in microseconds.
+    pub gpu_text_time: Option<f64>,
+
+    /// GPU time of the last measured quad pass, in microseconds.
+    pub gpu_quad_time: Option<f64>,
+
+    /// Memory used by the scrollback history, in bytes.
+    pub scrollback_memory: usize,
+
+    /// Memory used by the glyph atlas textures, in bytes.
+    ///
+    /// Glyphs are shared between windows using the same font, so they report the same atlas.
+    pub glyph_atlas_memory: usize,
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "grid {:.0}us | frame {:.0}us | damage {:.0}% | pty {}/s | glyphs {:.1}% | rects {} | \
+             quads {} | scrollback {} | atlas {}",
+            self.grid_time,
+            self.frame_time,
+            self.damage * 100.,
+            format_bytes(self.pty_throughput),
+            self.glyph_cache_hit_rate * 100.,
+            self.rects,
+            self.quads,
+            format_bytes(self.scrollback_memory as f64),
+            format_bytes(self.glyph_atlas_memory as f64),
+        )?;
+
+        if let Some(text) = self.gpu_text_time {
+            write!(f, " | gpu text {text:.0}us")?;
+        }
+
+        if let Some(quads) = self.gpu_quad_time {
+            write!(f, " | gpu quads {quads:.0}us")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Bytes per second read between two byte counts.
+fn throughput(start: Instant, start_bytes: usize, end: Instant, end_bytes: usize) -> f64 {
+    let elapsed = end.duration_since(start).as_secs_f64();
+    end_bytes.saturating_sub(start_bytes) as f64 / elapsed
+}
+
+/// Format a number of bytes using binary prefixes.
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024. && unit + 1 < UNITS.len() {
+        value /= 1024.;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{value:.0}{}", UNITS[unit])
+    } else {
+        format!("{value:.1}{}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_formatting() {
+        assert_eq!(format_bytes(0.), "0B");
+        assert_eq!(format_bytes(1023.), "1023B");
+        assert_eq!(format_bytes(1536.), "1.5KiB");
+        assert_eq!(format_bytes(3. * 1024. * 1024.), "3.0MiB");
+        assert_eq!(format_bytes(2048. * 1024. * 1024. * 1024.), "2048.0GiB");
+    }
+
+    #[test]
+    fn glyph_cache_hit_rate() {
+        let stats = Stats::default();
+        assert_eq!(stats.report(0., (0, 0)).glyph_cache_hit_rate, 1.);
+        assert_eq!(stats.report(0., (3, 1)).glyph_cache_hit_rate, 0.75);
+    }
+
+    #[test]
+    fn gpu_pass_times() {
+        let mut stats = Stats::default();
+        assert!(!stats.report(0., (0, 0)).to_string().contains("gpu"));
+
+        let text = Some(Duration::from_micros(120));
+        stats.record_pass_times(PassTimes { text, quads: None });
+
+        let report = stats.report(0., (0, 0));
+        assert_eq!(report.gpu_text_time, Some(120.));
+        assert!(report.to_string().ends_with(" | gpu text 120us"));
+    }
+
+    #[test]
+    fn pty_bytes_from_counter() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut stats = Stats::default();
+        stats.set_pty_counter(counter.clone());
+
+        counter.fetch_add(42, Ordering::Relaxed);
+        assert_eq!(stats.report(0., (0, 0)).pty_bytes, 42);
+    }
+}
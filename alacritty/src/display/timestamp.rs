@@ -0,0 +1,142 @@
+//! Formatting of the times at which lines were written.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::timestamps::TimestampFormat;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Format the time a line was written at for display next to it.
+pub fn format(time: SystemTime, now: SystemTime, format: TimestampFormat) -> String {
+    match format {
+        TimestampFormat::Absolute => {
+            let time = DateTime::local(time);
+            format!("{:02}:{:02}:{:02}", time.hour, time.minute, time.second)
+        },
+        TimestampFormat::Relative => {
+            let elapsed = now.duration_since(time).unwrap_or_default().as_secs();
+            match elapsed {
+                0..=59 => format!("{elapsed}s ago"),
+                60..=3599 => format!("{}m ago", elapsed / 60),
+                3600..=86399 => format!("{}h ago", elapsed / 3600),
+                _ => format!("{}d ago", elapsed / 86400),
+            }
+        },
+    }
+}
+
+/// Format the local date and time a line was written at.
+pub fn format_date_time(time: SystemTime) -> String {
+    let time = DateTime::local(time);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        time.year, time.month, time.day, time.hour, time.minute, time.second
+    )
+}
+
+/// Calendar date and time of day.
+#[derive(Debug, PartialEq, Eq)]
+struct DateTime {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+}
+
+impl DateTime {
+    /// Convert a system time to the local timezone.
+    fn local(time: SystemTime) -> Self {
+        let seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(err) => -(err.duration().as_secs() as i64),
+        };
+
+        Self::from_seconds(seconds + utc_offset(seconds))
+    }
+
+    /// Convert seconds since the UNIX epoch, ignoring leap seconds.
+    fn from_seconds(seconds: i64) -> Self {
+        let days = seconds.div_euclid(SECONDS_PER_DAY);
+        let seconds = seconds.rem_euclid(SECONDS_PER_DAY) as u32;
+
+        // Convert days to a civil date, see http://howardhinnant.github.io/date_algorithms.html.
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+        let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year,
+            month: month as u32,
+            day,
+            hour: seconds / 3600,
+            minute: seconds / 60 % 60,
+            second: seconds % 60,
+        }
+    }
+}
+
+/// Offset of the local timezone from UTC in seconds.
+#[cfg(unix)]
+fn utc_offset(seconds: i64) -> i64 {
+    let time = seconds as libc::time_t;
+    let mut tm = unsafe { std::mem::zeroed::<libc::tm>() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return 0;
+    }
+
+    tm.tm_gmtoff as i64
+}
+
+/// Offset of the local timezone from UTC in seconds.
+#[cfg(not(unix))]
+fn utc_offset(_seconds: i64) -> i64 {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[test]
+    fn civil_date() {
+        let epoch = DateTime { year: 1970, month: 1, day: 1, hour: 0, minute: 0, second: 0 };
+        assert_eq!(DateTime::from_seconds(0), epoch);
+
+        let time = DateTime { year: 2023, month: 11, day: 14, hour: 22, minute: 13, second: 20 };
+        assert_eq!(DateTime::from_seconds(1_700_000_000), time);
+
+        let time = DateTime { year: 2000, month: 2, day: 29, hour: 23, minute: 59, second: 59 };
+        assert_eq!(DateTime::from_seconds(951_868_799), time);
+
+        let time = DateTime { year: 1969, month: 12, day: 31, hour: 23, minute: 59, second: 59 };
+        assert_eq!(DateTime::from_seconds(-1), time);
+    }
+
+    #[test]
+    fn relative_time() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let relative =
+            |seconds| format(now - Duration::from_secs(seconds), now, TimestampFormat::Relative);
+
+        assert_eq!(relative(0), "0s ago");
+        assert_eq!(relative(59), "59s ago");
+        assert_eq!(relative(60), "1m ago");
+        assert_eq!(relative(7200), "2h ago");
+        assert_eq!(relative(86400 * 3), "3d ago");
+
+        // Lines written in the future, due to clock changes.
+        let future = now + Duration::from_secs(5);
+        assert_eq!(format(future, now, TimestampFormat::Relative), "0s ago");
+    }
+}
@@ -0,0 +1,128 @@
+//! Minimal title bar replacing client-side decorations.
+//!
+//! The title bar is only drawn on Wayland, when the compositor refused to draw server-side
+//! decorations and `window.client_decorations` asks for it instead of the toolkit's frame.
+
+use alacritty_terminal::term::color::Rgb;
+
+use crate::renderer::quads::{QuadPoint, RenderQuad};
+
+/// Height of the title bar in logical pixels.
+const HEIGHT: f32 = 28.;
+
+/// Size of the button icons relative to the title bar height.
+const ICON_SIZE: f32 = 0.3;
+
+/// Buttons of the title bar, from right to left.
+const BUTTONS: [TitleBarButton; 3] =
+    [TitleBarButton::Close, TitleBarButton::Maximize, TitleBarButton::Minimize];
+
+/// Window button of the title bar.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TitleBarButton {
+    Minimize,
+    Maximize,
+    Close,
+}
+
+/// Part of the title bar at a position.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TitleBarHit {
+    /// Area moving the window when dragged.
+    Drag,
+
+    /// Window button.
+    Button(TitleBarButton),
+}
+
+/// Height of the title bar in physical pixels.
+pub fn height(scale_factor: f32) -> f32 {
+    (HEIGHT * scale_factor).round()
+}
+
+/// Number of pixels reserved for the buttons at the right edge of the title bar.
+pub fn buttons_width(scale_factor: f32) -> f32 {
+    BUTTONS.len() as f32 * height(scale_factor)
+}
+
+/// Part of a title bar spanning `width` pixels at a position in physical pixels.
+pub fn hit(x: f32, y: f32, width: f32, scale_factor: f32) -> Option<TitleBarHit> {
+    let height = height(scale_factor);
+    if x < 0. || x >= width || y < 0. || y >= height {
+        return None;
+    }
+
+    let index = ((width - x) / height) as usize;
+    match BUTTONS.get(index) {
+        Some(button) => Some(TitleBarHit::Button(*button)),
+        None => Some(TitleBarHit::Drag),
+    }
+}
+
+/// Icons of the window buttons, in pixels relative to the title bar.
+pub fn button_quads(width: f32, scale_factor: f32, color: Rgb) -> Vec<RenderQuad> {
+    let height = height(scale_factor);
+    let thickness = scale_factor.round().max(1.);
+    let half = (height * ICON_SIZE / 2.).round();
+
+    let mut quads = Vec::new();
+    for (index, button) in BUTTONS.iter().enumerate() {
+        let center_x = (width - (index as f32 + 0.5) * height).round();
+        let center_y = (height / 2.).round();
+        let (left, right) = (center_x - half, center_x + half);
+        let (top, bottom) = (center_y - half, center_y + half);
+
+        let lines: &[(f32, f32, f32, f32)] = match button {
+            TitleBarButton::Minimize => &[(left, bottom, right, bottom)],
+            TitleBarButton::Maximize => &[
+                (left, top, right, top),
+                (right, top, right, bottom),
+                (right, bottom, left, bottom),
+                (left, bottom, left, top),
+            ],
+            TitleBarButton::Close => &[(left, top, right, bottom), (right, top, left, bottom)],
+        };
+
+        for &(x1, y1, x2, y2) in lines {
+            let points = line(QuadPoint { x: x1, y: y1 }, QuadPoint { x: x2, y: y2 }, thickness);
+            quads.push(RenderQuad::new(points, color, 1.));
+        }
+    }
+
+    quads
+}
+
+/// Corners of a line between two points.
+fn line(from: QuadPoint, to: QuadPoint, thickness: f32) -> [QuadPoint; 4] {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+
+    // Offset perpendicular to the line, extending it by half its thickness at both ends.
+    let (nx, ny) = (-dy / length * thickness / 2., dx / length * thickness / 2.);
+    let (ex, ey) = (dx / length * thickness / 2., dy / length * thickness / 2.);
+
+    [
+        QuadPoint { x: from.x + nx - ex, y: from.y + ny - ey },
+        QuadPoint { x: to.x + nx + ex, y: to.y + ny + ey },
+        QuadPoint { x: to.x - nx + ex, y: to.y - ny + ey },
+        QuadPoint { x: from.x - nx - ex, y: from.y - ny - ey },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hit_buttons() {
+        let width = 400.;
+
+        assert_eq!(hit(399., 10., width, 1.), Some(TitleBarHit::Button(TitleBarButton::Close)));
+        assert_eq!(hit(360., 10., width, 1.), Some(TitleBarHit::Button(TitleBarButton::Maximize)));
+        assert_eq!(hit(330., 10., width, 1.), Some(TitleBarHit::Button(TitleBarButton::Minimize)));
+        assert_eq!(hit(300., 10., width, 1.), Some(TitleBarHit::Drag));
+        assert_eq!(hit(0., 27., width, 1.), Some(TitleBarHit::Drag));
+        assert_eq!(hit(0., 28., width, 1.), None);
+        assert_eq!(hit(0., 50., width, 2.), Some(TitleBarHit::Drag));
+    }
+}
@@ -1,6 +1,9 @@
 #[cfg(not(any(target_os = "macos", windows)))]
-use winit::platform::startup_notify::{
-    self, EventLoopExtStartupNotify, WindowBuilderExtStartupNotify,
+use {
+    std::env,
+    winit::platform::startup_notify::{
+        self, EventLoopExtStartupNotify, WindowBuilderExtStartupNotify, WindowExtStartupNotify,
+    },
 };
 
 #[cfg(all(not(feature = "x11"), not(any(target_os = "macos", windows))))]
@@ -10,39 +13,60 @@ use winit::platform::wayland::WindowBuilderExtWayland;
 #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
 use {
     std::io::Cursor,
+    std::ffi::CString,
     winit::platform::x11::{WindowBuilderExtX11, EventLoopWindowTargetExtX11},
     winit::window::raw_window_handle::{HasRawDisplayHandle, RawDisplayHandle},
     glutin::platform::x11::X11VisualInfo,
-    x11_dl::xlib::{Display as XDisplay, PropModeReplace, XErrorEvent, Xlib},
+    x11_dl::xlib::{
+        Atom, ClientMessage, CurrentTime, Display as XDisplay, PropModeReplace, SubstructureNotifyMask,
+        SubstructureRedirectMask, XClientMessageEvent, XErrorEvent, XEvent, Xlib, XA_ATOM,
+    },
     winit::window::Icon,
-    png::Decoder,
+    png::{Decoder, Transformations},
+    std::fs,
 };
 
 use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[cfg(target_os = "macos")]
 use {
-    cocoa::appkit::NSColorSpace,
+    alacritty_terminal::ansi::{Progress, ProgressState},
+    cocoa::appkit::{NSApp, NSColorSpace, NSWindowCollectionBehavior},
     cocoa::base::{id, nil, NO, YES},
+    cocoa::foundation::NSString,
     objc::{msg_send, sel, sel_impl},
     winit::platform::macos::{OptionAsAlt, WindowBuilderExtMacOS, WindowExtMacOS},
 };
 
 use winit::dpi::{PhysicalPosition, PhysicalSize};
-use winit::event_loop::EventLoopWindowTarget;
+use winit::event_loop::{AsyncRequestSerial, EventLoopWindowTarget};
 use winit::monitor::MonitorHandle;
-#[cfg(windows)]
-use winit::platform::windows::IconExtWindows;
 use winit::window::raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
 use winit::window::{
-    CursorIcon, Fullscreen, ImePurpose, UserAttentionType, Window as WinitWindow, WindowBuilder,
-    WindowId,
+    ActivationToken, CursorIcon, Fullscreen, ImePurpose, UserAttentionType, Window as WinitWindow,
+    WindowBuilder, WindowId, WindowLevel,
+};
+#[cfg(windows)]
+use {
+    std::mem,
+    windows_sys::Win32::Foundation::HWND,
+    windows_sys::Win32::Graphics::Dwm::{
+        DwmExtendFrameIntoClientArea, DwmSetWindowAttribute, DWMSBT_MAINWINDOW, DWMSBT_NONE,
+        DWMSBT_TABBEDWINDOW, DWMSBT_TRANSIENTWINDOW, DWMWA_SYSTEMBACKDROP_TYPE,
+    },
+    windows_sys::Win32::UI::Controls::MARGINS,
+    winit::platform::windows::IconExtWindows,
 };
 
 use alacritty_terminal::index::Point;
 
-use crate::config::window::{Decorations, Identity, WindowConfig};
+#[cfg(windows)]
+use crate::config::window::Backdrop;
+use crate::config::window::{ClientDecorations, Decorations, Identity, WindowConfig};
 use crate::config::UiConfig;
+use crate::display::animation::{self, Animation};
 use crate::display::SizeInfo;
 
 /// Window icon for `_NET_WM_ICON` property.
@@ -114,8 +138,20 @@ pub struct Window {
     /// Current window title.
     title: String,
 
+    /// Icon the window was created with.
+    icon: Option<PathBuf>,
+
     current_mouse_cursor: CursorIcon,
     mouse_visible: bool,
+
+    /// Docking state of dropdown windows.
+    dropdown: Option<Dropdown>,
+
+    always_on_top: bool,
+    sticky: bool,
+
+    /// Whether the toolkit's client-side decorations were hidden.
+    decorations_replaced: bool,
 }
 
 impl Window {
@@ -132,6 +168,7 @@ impl Window {
         #[rustfmt::skip]
         #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
         x11_visual: Option<X11VisualInfo>,
+        dropdown: bool,
     ) -> Result<Window> {
         let identity = identity.clone();
         let mut window_builder = Window::get_platform_window(
@@ -148,6 +185,22 @@ impl Window {
                 .with_position(PhysicalPosition::<i32>::from((position.x, position.y)));
         }
 
+        // Dock dropdown windows to the top of the primary monitor, hidden above its edge.
+        let dropdown = dropdown.then(|| {
+            let monitor =
+                event_loop.primary_monitor().or_else(|| event_loop.available_monitors().next());
+            Dropdown::new(monitor, config.window.dropdown.height.as_f32())
+        });
+        if let Some(dropdown) = &dropdown {
+            window_builder = window_builder
+                .with_decorations(false)
+                .with_window_level(WindowLevel::AlwaysOnTop)
+                .with_inner_size(dropdown.size)
+                .with_position(dropdown.hidden_position());
+        } else if config.window.always_on_top {
+            window_builder = window_builder.with_window_level(WindowLevel::AlwaysOnTop);
+        }
+
         #[cfg(not(any(target_os = "macos", windows)))]
         if let Some(token) = event_loop.read_token_from_env() {
             log::debug!("Activating window with token: {token:?}");
@@ -164,8 +217,8 @@ impl Window {
             .with_theme(config.window.decorations_theme_variant)
             .with_visible(false)
             .with_transparent(true)
-            .with_maximized(config.window.maximized())
-            .with_fullscreen(config.window.fullscreen())
+            .with_maximized(config.window.maximized() && dropdown.is_none())
+            .with_fullscreen(config.window.fullscreen().filter(|_| dropdown.is_none()))
             .build(event_loop)?;
 
         // Text cursor.
@@ -182,6 +235,9 @@ impl Window {
         #[cfg(target_os = "macos")]
         use_srgb_color_space(&window);
 
+        #[cfg(windows)]
+        set_backdrop(&window, config.window.backdrop);
+
         // On X11, embed the window inside another if the parent ID has been set.
         #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
         if let Some(parent_window_id) = event_loop.is_x11().then_some(config.window.embed).flatten()
@@ -189,18 +245,120 @@ impl Window {
             x_embed_window(&window, parent_window_id);
         }
 
+        // Set the initial EWMH states, since window managers ignore requests to change the state
+        // of windows which aren't mapped yet.
+        //
+        // Dropdown windows are additionally kept out of task bars and visible on every workspace.
+        #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
+        if event_loop.is_x11() {
+            let mut states = Vec::new();
+            if dropdown.is_some() {
+                states.extend(["_NET_WM_STATE_SKIP_TASKBAR", "_NET_WM_STATE_SKIP_PAGER"]);
+            }
+            if dropdown.is_some() || config.window.always_on_top {
+                states.push("_NET_WM_STATE_ABOVE");
+            }
+            if dropdown.is_some() || config.window.sticky {
+                states.push("_NET_WM_STATE_STICKY");
+            }
+            if !states.is_empty() {
+                x_set_wm_state(&window, &states);
+            }
+        }
+
         let scale_factor = window.scale_factor();
         log::info!("Window scale factor: {}", scale_factor);
 
-        Ok(Self {
+        #[allow(unused_mut)]
+        let mut window = Self {
             current_mouse_cursor,
             mouse_visible: true,
             requested_redraw: false,
             window,
             title: identity.title,
+            icon: identity.icon,
             has_frame: true,
             scale_factor,
-        })
+            always_on_top: config.window.always_on_top,
+            sticky: config.window.sticky,
+            decorations_replaced: false,
+            dropdown,
+        };
+
+        #[cfg(target_os = "macos")]
+        window.set_sticky(window.sticky || window.is_dropdown());
+
+        Ok(window)
+    }
+
+    /// Check if this window is docked to the top of the screen.
+    #[inline]
+    pub fn is_dropdown(&self) -> bool {
+        self.dropdown.is_some()
+    }
+
+    /// Slide a dropdown window in or out.
+    pub fn toggle_dropdown(&mut self, config: &WindowConfig) {
+        let dropdown = match &mut self.dropdown {
+            Some(dropdown) => dropdown,
+            None => return,
+        };
+
+        dropdown.shown = !dropdown.shown;
+        if dropdown.shown {
+            // Dock to the monitor the window is on, in case its resolution changed.
+            let docked =
+                Dropdown::new(self.window.current_monitor(), config.dropdown.height.as_f32());
+            dropdown.position = docked.position;
+            dropdown.size = docked.size;
+            let _ = self.window.request_inner_size(dropdown.size);
+
+            self.window.set_visible(true);
+            activate_window(&self.window);
+        }
+
+        // Windows can't position themselves on Wayland, so they're only shown and hidden.
+        let is_wayland = matches!(self.window.raw_window_handle(), RawWindowHandle::Wayland(_));
+        let duration = if config.reduce_motion || is_wayland {
+            Duration::ZERO
+        } else {
+            config.dropdown.duration()
+        };
+        dropdown.animation = Some(Animation::new(duration));
+
+        self.request_redraw();
+    }
+
+    /// Move a dropdown window to the next frame of its slide animation.
+    ///
+    /// Returns `true` while the window is still sliding.
+    pub fn animate_dropdown(&mut self) -> bool {
+        let dropdown = match &mut self.dropdown {
+            Some(dropdown) => dropdown,
+            None => return false,
+        };
+
+        let progress = match dropdown.animation {
+            Some(animation) => animation.progress(),
+            None => return false,
+        };
+
+        let eased = animation::cubic_bezier(0.215, 0.61, 0.355, 1.0, progress);
+        let visible = if dropdown.shown { eased } else { 1. - eased };
+        let offset = ((1. - visible) * f64::from(dropdown.size.height)).round() as i32;
+        let position = PhysicalPosition::new(dropdown.position.x, dropdown.position.y - offset);
+        self.window.set_outer_position(position);
+
+        if progress < 1. {
+            return true;
+        }
+
+        dropdown.animation = None;
+        if !dropdown.shown {
+            self.window.set_visible(false);
+        }
+
+        false
     }
 
     #[inline]
@@ -213,6 +371,12 @@ impl Window {
         let _ = self.window.request_inner_size(size);
     }
 
+    #[cfg(unix)]
+    #[inline]
+    pub fn outer_position(&self) -> Option<PhysicalPosition<i32>> {
+        self.window.outer_position().ok()
+    }
+
     #[inline]
     pub fn inner_size(&self) -> PhysicalSize<u32> {
         self.window.inner_size()
@@ -268,23 +432,12 @@ impl Window {
             X11VisualInfo,
         >,
     ) -> WindowBuilder {
-        #[cfg(feature = "x11")]
-        let icon = {
-            let mut decoder = Decoder::new(Cursor::new(WINDOW_ICON));
-            decoder.set_transformations(png::Transformations::normalize_to_color8());
-            let mut reader = decoder.read_info().expect("invalid embedded icon");
-            let mut buf = vec![0; reader.output_buffer_size()];
-            let _ = reader.next_frame(&mut buf);
-            Icon::from_rgba(buf, reader.info().width, reader.info().height)
-                .expect("invalid embedded icon format")
-        };
-
         let builder = WindowBuilder::new()
             .with_name(&identity.class.general, &identity.class.instance)
             .with_decorations(window_config.decorations != Decorations::None);
 
         #[cfg(feature = "x11")]
-        let builder = builder.with_window_icon(Some(icon));
+        let builder = builder.with_window_icon(load_icon(identity.icon.as_deref()));
 
         #[cfg(feature = "x11")]
         let builder = match x11_visual {
@@ -296,12 +449,10 @@ impl Window {
     }
 
     #[cfg(windows)]
-    pub fn get_platform_window(_: &Identity, window_config: &WindowConfig) -> WindowBuilder {
-        let icon = winit::window::Icon::from_resource(IDI_ICON, None);
-
+    pub fn get_platform_window(identity: &Identity, window_config: &WindowConfig) -> WindowBuilder {
         WindowBuilder::new()
             .with_decorations(window_config.decorations != Decorations::None)
-            .with_window_icon(icon.ok())
+            .with_window_icon(load_icon(identity.icon.as_deref()))
     }
 
     #[cfg(target_os = "macos")]
@@ -331,6 +482,19 @@ impl Window {
         }
     }
 
+    /// Request a token for activating the windows of launched applications.
+    ///
+    /// The token is delivered through [`WindowEvent::ActivationTokenDone`].
+    ///
+    /// [`WindowEvent::ActivationTokenDone`]: winit::event::WindowEvent::ActivationTokenDone
+    pub fn request_activation_token(&self) -> Option<AsyncRequestSerial> {
+        #[cfg(not(any(target_os = "macos", windows)))]
+        return self.window.request_activation_token().ok();
+
+        #[cfg(any(target_os = "macos", windows))]
+        None
+    }
+
     pub fn set_urgent(&self, is_urgent: bool) {
         let attention = if is_urgent { Some(UserAttentionType::Critical) } else { None };
 
@@ -345,6 +509,12 @@ impl Window {
         self.window.set_transparent(transparent);
     }
 
+    /// Change the system backdrop shown through transparent areas of the window.
+    #[cfg(windows)]
+    pub fn set_backdrop(&self, backdrop: Backdrop) {
+        set_backdrop(&self.window, backdrop);
+    }
+
     pub fn set_maximized(&self, maximized: bool) {
         self.window.set_maximized(maximized);
     }
@@ -367,6 +537,114 @@ impl Window {
         self.set_maximized(!self.window.is_maximized());
     }
 
+    /// Replace the client-side decorations the compositor forced on the window.
+    ///
+    /// Windows with full decorations request server-side decorations on Wayland, which the
+    /// toolkit replaces with its own frame on compositors refusing them. Returns `true` while
+    /// Alacritty's title bar should be drawn instead.
+    pub fn update_client_decorations(&mut self, config: &WindowConfig) -> bool {
+        if config.decorations != Decorations::Full || self.is_dropdown() {
+            return false;
+        }
+
+        let replace = config.client_decorations != ClientDecorations::Toolkit;
+        if self.decorations_replaced && !replace {
+            self.decorations_replaced = false;
+            self.window.set_decorations(true);
+        } else if !self.decorations_replaced && replace && self.has_toolkit_decorations() {
+            self.decorations_replaced = true;
+            self.window.set_decorations(false);
+        }
+
+        self.decorations_replaced && config.client_decorations == ClientDecorations::TitleBar
+    }
+
+    /// Check if the toolkit draws its own frame around the window.
+    fn has_toolkit_decorations(&self) -> bool {
+        matches!(self.raw_window_handle(), RawWindowHandle::Wayland(_))
+            && self.window.is_decorated()
+            && self.window.outer_size() != self.window.inner_size()
+    }
+
+    /// Start moving the window with the mouse.
+    pub fn drag_window(&self) {
+        let _ = self.window.drag_window();
+    }
+
+    #[inline]
+    pub fn is_fullscreen(&self) -> bool {
+        self.window.fullscreen().is_some()
+    }
+
+    /// Keep the window above all other windows.
+    ///
+    /// Dropdown windows always stay on top. This has no effect on Wayland.
+    pub fn set_always_on_top(&mut self, always_on_top: bool) {
+        self.always_on_top = always_on_top;
+
+        let level = if always_on_top || self.is_dropdown() {
+            WindowLevel::AlwaysOnTop
+        } else {
+            WindowLevel::Normal
+        };
+        self.window.set_window_level(level);
+    }
+
+    /// Toggle whether the window is kept above all other windows.
+    pub fn toggle_always_on_top(&mut self) {
+        self.set_always_on_top(!self.always_on_top);
+    }
+
+    /// Show the window on all workspaces.
+    ///
+    /// Dropdown windows are always shown on all workspaces. This has no effect on Wayland and
+    /// Windows.
+    pub fn set_sticky(&mut self, sticky: bool) {
+        self.sticky = sticky;
+        let sticky = sticky || self.is_dropdown();
+
+        #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
+        x_change_wm_state(&self.window, "_NET_WM_STATE_STICKY", sticky);
+
+        #[cfg(target_os = "macos")]
+        {
+            let raw_window = match self.raw_window_handle() {
+                RawWindowHandle::AppKit(handle) => handle.ns_window as id,
+                _ => return,
+            };
+
+            unsafe {
+                let behavior: NSWindowCollectionBehavior =
+                    msg_send![raw_window, collectionBehavior];
+                let all_spaces =
+                    NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces;
+                let behavior = if sticky { behavior | all_spaces } else { behavior & !all_spaces };
+                let _: () = msg_send![raw_window, setCollectionBehavior: behavior];
+            }
+        }
+
+        #[cfg(any(windows, all(not(feature = "x11"), not(target_os = "macos"))))]
+        let _ = sticky;
+    }
+
+    /// Toggle whether the window is shown on all workspaces.
+    pub fn toggle_sticky(&mut self) {
+        self.set_sticky(!self.sticky);
+    }
+
+    /// Change the window icon, restoring the initial icon without a path.
+    ///
+    /// This has no effect on Wayland and macOS.
+    pub fn set_icon(&self, path: Option<&Path>) {
+        let path = path.or(self.icon.as_deref());
+
+        #[cfg(any(windows, all(feature = "x11", not(target_os = "macos"))))]
+        self.window.set_window_icon(load_icon(path));
+
+        #[cfg(not(any(windows, all(feature = "x11", not(target_os = "macos")))))]
+        let _ = path;
+    }
+
     /// Inform windowing system about presenting to the window.
     ///
     /// Should be called right before presenting to the window with e.g. `eglSwapBuffers`.
@@ -436,6 +714,26 @@ impl Window {
         }
     }
 
+    /// Show the progress of a long running operation as badge on the dock icon.
+    #[cfg(target_os = "macos")]
+    pub fn set_progress(&self, progress: Option<Progress>) {
+        let label = progress.map(|progress| match progress.state {
+            ProgressState::Normal | ProgressState::Paused => format!("{}%", progress.percent),
+            ProgressState::Error => String::from("!"),
+            ProgressState::Indeterminate => String::from("…"),
+        });
+
+        unsafe {
+            let label = match label {
+                Some(label) => NSString::alloc(nil).init_str(&label),
+                None => nil,
+            };
+
+            let dock_tile: id = msg_send![NSApp(), dockTile];
+            let _: () = msg_send![dock_tile, setBadgeLabel: label];
+        }
+    }
+
     /// Select tab at the given `index`.
     #[cfg(target_os = "macos")]
     pub fn select_tab_at_index(&self, index: usize) {
@@ -504,6 +802,257 @@ fn x_embed_window(window: &WinitWindow, parent_id: std::os::raw::c_ulong) {
     }
 }
 
+/// Set the EWMH `_NET_WM_STATE` of an X11 window before it is mapped.
+#[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
+fn x_set_wm_state(window: &WinitWindow, states: &[&str]) {
+    let xlib_display = window.raw_display_handle();
+    let xlib_window = window.raw_window_handle();
+    let (xlib_display, xlib_window) = match (xlib_display, xlib_window) {
+        (RawDisplayHandle::Xlib(display), RawWindowHandle::Xlib(window)) => {
+            (display.display, window.window)
+        },
+        _ => return,
+    };
+
+    let xlib = match Xlib::open() {
+        Ok(xlib) => xlib,
+        Err(err) => {
+            log::warn!("Unable to set window state: {}", err);
+            return;
+        },
+    };
+
+    unsafe {
+        let intern_atom = |name: &str| {
+            let name = CString::new(name).unwrap();
+            (xlib.XInternAtom)(xlib_display as *mut _, name.as_ptr(), 0)
+        };
+
+        let atoms: Vec<Atom> = states.iter().map(|state| intern_atom(state)).collect();
+        (xlib.XChangeProperty)(
+            xlib_display as _,
+            xlib_window,
+            intern_atom("_NET_WM_STATE"),
+            XA_ATOM,
+            32,
+            PropModeReplace,
+            atoms.as_ptr() as *const _,
+            atoms.len() as i32,
+        );
+        (xlib.XFlush)(xlib_display as _);
+    }
+}
+
+/// Ask the window manager to activate an X11 window, as requested by the user.
+///
+/// Returns `false` for non-X11 windows.
+#[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
+fn x_activate_window(window: &WinitWindow) -> bool {
+    let xlib_display = window.raw_display_handle();
+    let xlib_window = window.raw_window_handle();
+    let (xlib_display, xlib_window) = match (xlib_display, xlib_window) {
+        (RawDisplayHandle::Xlib(display), RawWindowHandle::Xlib(window)) => {
+            (display.display, window.window)
+        },
+        _ => return false,
+    };
+
+    let xlib = match Xlib::open() {
+        Ok(xlib) => xlib,
+        Err(err) => {
+            log::warn!("Unable to activate window: {}", err);
+            return false;
+        },
+    };
+
+    unsafe {
+        let name = CString::new("_NET_ACTIVE_WINDOW").unwrap();
+        let active_window = (xlib.XInternAtom)(xlib_display as *mut _, name.as_ptr(), 0);
+
+        // Pager source indication, since focus stealing prevention would otherwise refuse
+        // requests from the application which isn't focused yet.
+        let mut message: XClientMessageEvent = std::mem::zeroed();
+        message.type_ = ClientMessage;
+        message.window = xlib_window;
+        message.message_type = active_window;
+        message.format = 32;
+        message.data.set_long(0, 2);
+        message.data.set_long(1, CurrentTime as _);
+
+        let root = (xlib.XDefaultRootWindow)(xlib_display as _);
+        (xlib.XSendEvent)(
+            xlib_display as _,
+            root,
+            0,
+            SubstructureRedirectMask | SubstructureNotifyMask,
+            &mut XEvent::from(message),
+        );
+        (xlib.XFlush)(xlib_display as _);
+    }
+
+    true
+}
+
+/// Add or remove an EWMH `_NET_WM_STATE` of a mapped X11 window.
+#[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
+fn x_change_wm_state(window: &WinitWindow, state: &str, enable: bool) {
+    let xlib_display = window.raw_display_handle();
+    let xlib_window = window.raw_window_handle();
+    let (xlib_display, xlib_window) = match (xlib_display, xlib_window) {
+        (RawDisplayHandle::Xlib(display), RawWindowHandle::Xlib(window)) => {
+            (display.display, window.window)
+        },
+        _ => return,
+    };
+
+    let xlib = match Xlib::open() {
+        Ok(xlib) => xlib,
+        Err(err) => {
+            log::warn!("Unable to change window state: {}", err);
+            return;
+        },
+    };
+
+    unsafe {
+        let intern_atom = |name: &str| {
+            let name = CString::new(name).unwrap();
+            (xlib.XInternAtom)(xlib_display as *mut _, name.as_ptr(), 0)
+        };
+
+        // Ask the window manager to change the state, with `_NET_WM_STATE_ADD` or `_REMOVE`.
+        let mut message: XClientMessageEvent = std::mem::zeroed();
+        message.type_ = ClientMessage;
+        message.window = xlib_window;
+        message.message_type = intern_atom("_NET_WM_STATE");
+        message.format = 32;
+        message.data.set_long(0, enable as _);
+        message.data.set_long(1, intern_atom(state) as _);
+        message.data.set_long(3, 1);
+
+        let root = (xlib.XDefaultRootWindow)(xlib_display as _);
+        (xlib.XSendEvent)(
+            xlib_display as _,
+            root,
+            0,
+            SubstructureRedirectMask | SubstructureNotifyMask,
+            &mut XEvent::from(message),
+        );
+        (xlib.XFlush)(xlib_display as _);
+    }
+}
+
+/// Load a PNG window icon, falling back to the embedded icon.
+#[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
+fn load_icon(path: Option<&Path>) -> Option<Icon> {
+    if let Some(path) = path {
+        match fs::read(path).map_err(|err| err.to_string()).and_then(|png| decode_icon(&png)) {
+            Ok(icon) => return Some(icon),
+            Err(err) => log::warn!("Unable to load window icon {path:?}: {err}"),
+        }
+    }
+
+    Some(decode_icon(WINDOW_ICON).expect("invalid embedded icon"))
+}
+
+/// Decode a PNG image into a window icon.
+#[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
+fn decode_icon(png: &[u8]) -> std::result::Result<Icon, String> {
+    let mut decoder = Decoder::new(Cursor::new(png));
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|err| err.to_string())?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    reader.next_frame(&mut buf).map_err(|err| err.to_string())?;
+    Icon::from_rgba(buf, reader.info().width, reader.info().height).map_err(|err| err.to_string())
+}
+
+/// Apply the DWM system backdrop, requiring Windows 11.
+#[cfg(windows)]
+fn set_backdrop(window: &WinitWindow, backdrop: Backdrop) {
+    let hwnd = match window.raw_window_handle() {
+        RawWindowHandle::Win32(handle) => handle.hwnd as HWND,
+        _ => return,
+    };
+
+    let backdrop_type = match backdrop {
+        Backdrop::None => DWMSBT_NONE,
+        Backdrop::Mica => DWMSBT_MAINWINDOW,
+        Backdrop::Acrylic => DWMSBT_TRANSIENTWINDOW,
+        Backdrop::Tabbed => DWMSBT_TABBEDWINDOW,
+    };
+
+    unsafe {
+        // Extend the frame into the whole client area, so the backdrop shows through it.
+        let inset = if backdrop == Backdrop::None { 0 } else { -1 };
+        let margins = MARGINS {
+            cxLeftWidth: inset,
+            cxRightWidth: inset,
+            cyTopHeight: inset,
+            cyBottomHeight: inset,
+        };
+        DwmExtendFrameIntoClientArea(hwnd, &margins);
+
+        let result = DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_SYSTEMBACKDROP_TYPE as _,
+            &backdrop_type as *const _ as *const _,
+            mem::size_of_val(&backdrop_type) as u32,
+        );
+        if result < 0 && backdrop != Backdrop::None {
+            log::warn!("Unable to set window backdrop: 0x{:X}", result);
+        }
+    }
+}
+
+/// Load an `.ico` window icon, falling back to the embedded icon.
+#[cfg(windows)]
+fn load_icon(path: Option<&Path>) -> Option<winit::window::Icon> {
+    if let Some(path) = path {
+        match winit::window::Icon::from_path(path, None) {
+            Ok(icon) => return Some(icon),
+            Err(err) => log::warn!("Unable to load window icon {path:?}: {err}"),
+        }
+    }
+
+    winit::window::Icon::from_resource(IDI_ICON, None).ok()
+}
+
+/// Raise and focus a window on behalf of the user.
+fn activate_window(window: &WinitWindow) {
+    #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
+    if x_activate_window(window) {
+        return;
+    }
+
+    window.focus_window();
+}
+
+/// Environment variables passing an activation token to a launched application.
+#[cfg(not(any(target_os = "macos", windows)))]
+pub fn activation_token_env(token: ActivationToken) -> Vec<(&'static str, String)> {
+    // The token's value is only exposed through the environment.
+    let token = unsafe {
+        startup_notify::set_activation_token_env(token);
+        let token = env::var("XDG_ACTIVATION_TOKEN").unwrap_or_default();
+        startup_notify::reset_activation_token_env();
+        token
+    };
+
+    vec![("XDG_ACTIVATION_TOKEN", token.clone()), ("DESKTOP_STARTUP_ID", token)]
+}
+
+/// Environment variables passing an activation token to a launched application.
+#[cfg(any(target_os = "macos", windows))]
+pub fn activation_token_env(_token: ActivationToken) -> Vec<(&'static str, String)> {
+    Vec::new()
+}
+
+/// Use an activation token received from another process for the next window.
+#[cfg(not(any(target_os = "macos", windows)))]
+pub fn set_activation_token_env(token: &str) {
+    env::set_var("XDG_ACTIVATION_TOKEN", token);
+    env::set_var("DESKTOP_STARTUP_ID", token);
+}
+
 #[cfg(target_os = "macos")]
 fn use_srgb_color_space(window: &WinitWindow) {
     let raw_window = match window.raw_window_handle() {
@@ -521,3 +1070,39 @@ unsafe extern "C" fn xembed_error_handler(_: *mut XDisplay, _: *mut XErrorEvent)
     log::error!("Could not embed into specified window.");
     std::process::exit(1);
 }
+
+/// Area of a window docked to the top edge of a monitor.
+#[derive(Debug, Default)]
+struct Dropdown {
+    /// Position of the top left corner while the window is fully shown.
+    position: PhysicalPosition<i32>,
+
+    /// Size of the window.
+    size: PhysicalSize<u32>,
+
+    /// Whether the window is shown or sliding in.
+    shown: bool,
+
+    /// Slide animation of the last toggle.
+    animation: Option<Animation>,
+}
+
+impl Dropdown {
+    /// Dock to a monitor, covering its full width and a fraction of its height.
+    fn new(monitor: Option<MonitorHandle>, height: f32) -> Self {
+        let (position, monitor_size) = match monitor {
+            Some(monitor) => (monitor.position(), monitor.size()),
+            None => (Default::default(), PhysicalSize::new(800, 600)),
+        };
+
+        let height = (monitor_size.height as f32 * height).round().max(1.) as u32;
+        let size = PhysicalSize::new(monitor_size.width, height);
+
+        Self { position, size, ..Default::default() }
+    }
+
+    /// Position of the window while it's hidden above the top edge of the monitor.
+    fn hidden_position(&self) -> PhysicalPosition<i32> {
+        PhysicalPosition::new(self.position.x, self.position.y - self.size.height as i32)
+    }
+}
@@ -1,7 +1,7 @@
 //! Process window events.
 
 use std::borrow::Cow;
-use std::cmp::{max, min};
+use std::cmp::{max, min, Reverse};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::error::Error;
 use std::ffi::OsStr;
@@ -10,47 +10,64 @@ use std::fmt::Debug;
 use std::os::unix::io::RawFd;
 use std::path::PathBuf;
 use std::rc::Rc;
-use std::time::{Duration, Instant};
-use std::{env, f32, mem};
+#[cfg(unix)]
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, f32, fs, mem};
 
 use ahash::RandomState;
 use crossfont::{self, Size};
 use log::{debug, error, info, warn};
+use parking_lot::Mutex;
 use winit::event::{
     ElementState, Event as WinitEvent, Ime, Modifiers, MouseButton, StartCause,
     Touch as TouchEvent, WindowEvent,
 };
 use winit::event_loop::{
-    ControlFlow, DeviceEvents, EventLoop, EventLoopProxy, EventLoopWindowTarget,
+    AsyncRequestSerial, ControlFlow, DeviceEvents, EventLoop, EventLoopProxy, EventLoopWindowTarget,
 };
+use winit::keyboard::KeyCode;
 use winit::window::raw_window_handle::HasRawDisplayHandle;
-use winit::window::WindowId;
+use winit::window::{ActivationToken, WindowId};
 
-use alacritty_terminal::config::LOG_TARGET_CONFIG;
+use alacritty_terminal::config::{Osc52Read, Program, LOG_TARGET_CONFIG};
 use alacritty_terminal::event::{Event as TerminalEvent, EventListener, Notify};
 use alacritty_terminal::event_loop::Notifier;
 use alacritty_terminal::grid::{Dimensions, Scroll};
 use alacritty_terminal::index::{Boundary, Column, Direction, Line, Point, Side};
+use alacritty_terminal::replay::ReplayControl;
 use alacritty_terminal::selection::{Selection, SelectionType};
-use alacritty_terminal::term::search::{Match, RegexSearch};
+use alacritty_terminal::term::search::{
+    CaseSensitivity, Match, RegexIter, RegexSearch, SearchOptions,
+};
 use alacritty_terminal::term::{self, ClipboardType, Term, TermMode};
+use alacritty_terminal::vi_mode::{ViOperator, ViTextObject};
 
 #[cfg(unix)]
-use crate::cli::IpcConfig;
+use crate::cli::{IpcBroadcast, IpcConfig, IpcDumpHistory, IpcGetText, IpcSearch, IpcSendText};
 use crate::cli::{Options as CliOptions, WindowOptions};
 use crate::clipboard::Clipboard;
-use crate::config::ui_config::{HintAction, HintInternalAction};
+use crate::config::ui_config::{self, HintAction, HintInternalAction};
 use crate::config::{self, UiConfig};
 #[cfg(not(windows))]
 use crate::daemon::foreground_process_path;
-use crate::daemon::spawn_daemon;
+use crate::daemon::{spawn_daemon, spawn_with_input};
+#[cfg(all(feature = "dbus", not(any(target_os = "macos", windows))))]
+use crate::dbus;
+use crate::display::formatted::FormattedText;
 use crate::display::hint::HintMatch;
-use crate::display::window::Window;
-use crate::display::{Display, Preedit, SizeInfo};
+use crate::display::window::{self, Window};
+use crate::display::{screenshot, timestamp, Display, Preedit, SizeInfo};
 use crate::input::{self, ActionContext as _, FONT_SIZE_STEP};
-use crate::message_bar::{Message, MessageBuffer};
+#[cfg(unix)]
+use crate::ipc::{IpcState, IpcWindow, SocketReply};
+use crate::message_bar::{Message, MessageAction, MessageBuffer, MessageType};
+use crate::pane::{Pane, PaneAction, PaneDestination, PaneDirection, PaneId};
 use crate::scheduler::{Scheduler, TimerId, Topic};
+use crate::scripting::{ScriptEvent, ScriptRequest, Scripting};
 use crate::window_context::WindowContext;
+use crate::{notification, search_history, sound};
 
 /// Duration after the last user input until an unlimited search is performed.
 pub const TYPING_SEARCH_DELAY: Duration = Duration::from_millis(500);
@@ -58,8 +75,8 @@ pub const TYPING_SEARCH_DELAY: Duration = Duration::from_millis(500);
 /// Maximum number of lines for the blocking search while still typing the search regex.
 const MAX_SEARCH_WHILE_TYPING: Option<usize> = Some(1000);
 
-/// Maximum number of search terms stored in the history.
-const MAX_SEARCH_HISTORY_SIZE: usize = 255;
+/// Maximum number of fuzzy search matches which are ranked.
+const MAX_FUZZY_MATCHES: usize = 1000;
 
 /// Touch zoom speed.
 const TOUCH_ZOOM_FACTOR: f32 = 0.01;
@@ -70,13 +87,29 @@ pub struct Event {
     /// Limit event to a specific window.
     window_id: Option<WindowId>,
 
+    /// Pane whose terminal emitted the event.
+    pane_id: Option<PaneId>,
+
     /// Event payload.
     payload: EventType,
 }
 
 impl Event {
     pub fn new<I: Into<Option<WindowId>>>(payload: EventType, window_id: I) -> Self {
-        Self { window_id: window_id.into(), payload }
+        Self { window_id: window_id.into(), pane_id: None, payload }
+    }
+
+    /// Pane whose terminal emitted the event.
+    pub fn pane_id(&self) -> Option<PaneId> {
+        self.pane_id
+    }
+
+    pub fn payload(&self) -> &EventType {
+        &self.payload
+    }
+
+    pub fn into_payload(self) -> EventType {
+        self.payload
     }
 }
 
@@ -93,13 +126,39 @@ pub enum EventType {
     ConfigReload(PathBuf),
     Message(Message),
     Scroll(Scroll),
-    CreateWindow(WindowOptions),
+    CreateWindow(Box<WindowOptions>),
     #[cfg(unix)]
     IpcConfig(IpcConfig),
+    #[cfg(unix)]
+    IpcSearch(IpcSearch),
+    #[cfg(unix)]
+    IpcDumpHistory(IpcDumpHistory),
+    #[cfg(unix)]
+    IpcScreenshot(PathBuf),
+    #[cfg(unix)]
+    IpcGetText(IpcGetText, Sender<SocketReply>),
+    #[cfg(unix)]
+    IpcGetState(Sender<SocketReply>),
+    #[cfg(unix)]
+    IpcStats(Sender<SocketReply>),
+    #[cfg(unix)]
+    IpcListWindows(Sender<Vec<IpcWindow>>),
+    #[cfg(unix)]
+    IpcSendText(IpcSendText),
+    #[cfg(unix)]
+    IpcGetConfig(String, Sender<SocketReply>),
+    #[cfg(unix)]
+    IpcBroadcast(IpcBroadcast),
     BlinkCursor,
     BlinkCursorTimeout,
     SearchNext,
     Frame,
+    PowerSourceChanged(bool),
+    HighContrastChanged(bool),
+    Pane(PaneAction),
+    BroadcastInput(Vec<u8>),
+    MovePane(PaneDestination, PaneDirection),
+    ToggleDropdown,
 }
 
 impl From<TerminalEvent> for EventType {
@@ -135,6 +194,21 @@ pub struct SearchState {
 
     /// Compiled search automatons.
     dfas: Option<RegexSearch>,
+
+    /// Case sensitivity and whole word matching.
+    options: SearchOptions,
+
+    /// Region the search is restricted to.
+    scope: Option<Match>,
+
+    /// Fuzzy search matches, starting with the best one.
+    ranked_matches: Vec<Match>,
+
+    /// Index of the focused match in the ranked fuzzy matches.
+    ranked_index: usize,
+
+    /// Whether only the most recent lines were searched for fuzzy matches.
+    ranking_limited: bool,
 }
 
 impl SearchState {
@@ -158,6 +232,99 @@ impl SearchState {
         self.dfas.as_ref()
     }
 
+    /// Case sensitivity and whole word matching options.
+    pub fn options(&self) -> SearchOptions {
+        self.options
+    }
+
+    /// Region the search is restricted to.
+    pub fn scope(&self) -> Option<&Match> {
+        self.scope.as_ref()
+    }
+
+    /// Find the next search match within the search scope.
+    fn search_next<T>(
+        &self,
+        term: &Term<T>,
+        origin: Point,
+        direction: Direction,
+        side: Side,
+        limit: Option<usize>,
+    ) -> Option<Match> {
+        let dfas = self.dfas.as_ref()?;
+        let scope = match &self.scope {
+            Some(scope) => scope,
+            None => return term.search_next(dfas, origin, direction, side, limit),
+        };
+
+        // Search towards the end of the scope first, then wrap around to its other side.
+        let scope_lines = (scope.end().line - scope.start().line).0 as usize;
+        let wrapped_origin = match direction {
+            Direction::Right => *scope.start(),
+            Direction::Left => *scope.end(),
+        };
+        let origins = [Some(origin).filter(|origin| scope.contains(origin)), Some(wrapped_origin)];
+
+        origins.into_iter().flatten().find_map(|origin| {
+            term.search_next(dfas, origin, direction, side, Some(scope_lines))
+                .filter(|regex_match| match_in_scope(regex_match, scope))
+        })
+    }
+
+    /// Rank fuzzy search matches by their score.
+    ///
+    /// Matches are collected starting at the bottom, only searching through the last `limit` lines
+    /// when a limit is specified.
+    fn rank_matches<T>(&mut self, term: &Term<T>, limit: Option<usize>) {
+        self.ranked_matches.clear();
+        self.ranked_index = 0;
+        self.ranking_limited = false;
+
+        let dfas = match self.dfas.as_ref().filter(|_| self.options.fuzzy) {
+            Some(dfas) => dfas,
+            None => return,
+        };
+
+        let (mut start, end) = match &self.scope {
+            Some(scope) => (*scope.start(), *scope.end()),
+            None => (
+                Point::new(term.topmost_line(), Column(0)),
+                Point::new(term.bottommost_line(), term.last_column()),
+            ),
+        };
+
+        // Skip everything above the last `limit` lines.
+        let limit_line = limit.map(|limit| end.line - limit.saturating_sub(1) as i32);
+        if let Some(limit_line) = limit_line.filter(|&line| line > start.line) {
+            start = Point::new(limit_line, Column(0));
+            self.ranking_limited = true;
+        }
+
+        // Search one line at a time, since the most recent matches should be ranked first.
+        let scope = self.scope.as_ref();
+        let mut matches = Vec::new();
+        let mut line_end = end;
+        while line_end >= start && matches.len() < MAX_FUZZY_MATCHES {
+            let line_start = max(term.line_search_left(line_end), start);
+            let line_matches = RegexIter::new(line_start, line_end, Direction::Right, term, dfas)
+                .filter(|regex_match| {
+                    scope.map_or(true, |scope| match_in_scope(regex_match, scope))
+                })
+                .take(MAX_FUZZY_MATCHES - matches.len())
+                .filter_map(|regex_match| {
+                    let text = term.bounds_to_string(*regex_match.start(), *regex_match.end());
+                    Some((dfas.fuzzy_score(&text)?, regex_match))
+                });
+            matches.extend(line_matches);
+
+            line_end = Point::new(line_start.line - 1, term.last_column());
+        }
+
+        // Prefer the most recent output for matches with equal score.
+        matches.sort_by_key(|(score, regex_match)| Reverse((*score, *regex_match.start())));
+        self.ranked_matches = matches.into_iter().map(|(_, regex_match)| regex_match).collect();
+    }
+
     /// Search regex text if a search is active.
     fn regex_mut(&mut self) -> Option<&mut String> {
         self.history_index.and_then(move |index| self.history.get_mut(index))
@@ -174,10 +341,23 @@ impl Default for SearchState {
             history: Default::default(),
             origin: Default::default(),
             dfas: Default::default(),
+            options: Default::default(),
+            scope: Default::default(),
+            ranked_matches: Default::default(),
+            ranked_index: Default::default(),
+            ranking_limited: Default::default(),
         }
     }
 }
 
+/// Process waiting for an activation token before it is started.
+#[derive(Debug)]
+pub struct PendingLaunch {
+    serial: AsyncRequestSerial,
+    program: String,
+    args: Vec<String>,
+}
+
 pub struct ActionContext<'a, N, T> {
     pub notifier: &'a mut N,
     pub terminal: &'a mut Term<T>,
@@ -185,6 +365,7 @@ pub struct ActionContext<'a, N, T> {
     pub mouse: &'a mut Mouse,
     pub touch: &'a mut TouchPurpose,
     pub modifiers: &'a mut Modifiers,
+    pub pending_releases: &'a mut PendingReleases,
     pub display: &'a mut Display,
     pub message_buffer: &'a mut MessageBuffer,
     pub config: &'a UiConfig,
@@ -196,7 +377,12 @@ pub struct ActionContext<'a, N, T> {
     pub font_size: &'a mut Size,
     pub dirty: &'a mut bool,
     pub occluded: &'a mut bool,
+    pub child_exited: &'a mut bool,
+    pub spawned: Instant,
     pub preserve_title: bool,
+    pub replay: bool,
+    pub scripting: &'a mut Scripting,
+    pub pending_launches: &'a mut Vec<PendingLaunch>,
     #[cfg(not(windows))]
     pub master_fd: RawFd,
     #[cfg(not(windows))]
@@ -209,6 +395,16 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         self.notifier.notify(val);
     }
 
+    /// Write user input to the PTY, mirroring it to other terminals while broadcasting.
+    fn write_input<B: Into<Cow<'static, [u8]>>>(&self, val: B) {
+        let val = val.into();
+        if self.display.broadcast.active() {
+            let event = EventType::BroadcastInput(val.to_vec());
+            let _ = self.event_proxy.send_event(Event::new(event, self.display.window.id()));
+        }
+        self.write_to_pty(val);
+    }
+
     /// Request a redraw.
     #[inline]
     fn mark_dirty(&mut self) {
@@ -251,16 +447,81 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 
     // Copy text selection.
     fn copy_selection(&mut self, ty: ClipboardType) {
-        let text = match self.terminal.selection_to_string().filter(|s| !s.is_empty()) {
+        let text = match self.selection_text() {
             Some(text) => text,
             None => return,
         };
 
-        if ty == ClipboardType::Selection && self.config.terminal_config.selection.save_to_clipboard
-        {
-            self.clipboard.store(ClipboardType::Clipboard, text.clone());
+        match ty {
+            ClipboardType::Selection => {
+                let target = self.config.terminal_config.selection.save_to_clipboard.target();
+                if target.clipboard() {
+                    self.store_clipboard(ClipboardType::Clipboard, text.clone());
+                }
+                if target.selection() {
+                    self.store_clipboard(ClipboardType::Selection, text);
+                }
+            },
+            ClipboardType::Clipboard => self.store_clipboard(ty, text),
+        }
+
+        self.clear_copied_selection();
+    }
+
+    fn copy_selection_formatted(&mut self) {
+        let text = match self.selection_text() {
+            Some(text) => text,
+            None => return,
+        };
+        let formatted =
+            match FormattedText::from_selection(self.terminal, self.config, &self.display.colors) {
+                Some(formatted) => formatted,
+                None => return,
+            };
+
+        self.run_copy_hook(ClipboardType::Clipboard, &text);
+        self.clipboard.store_formatted(text, formatted.html(), formatted.rtf());
+
+        self.clear_copied_selection();
+    }
+
+    fn copy_selection_ansi(&mut self) {
+        if let Some(text) = self.terminal.selection_to_ansi().filter(|text| !text.is_empty()) {
+            self.store_clipboard(ClipboardType::Clipboard, text);
+            self.clear_copied_selection();
+        }
+    }
+
+    fn animate_jump(&mut self, origin_offset: usize, target: Match) {
+        if self.config.window.reduce_motion {
+            return;
+        }
+
+        let target_offset = self.terminal.grid().display_offset();
+        self.display.jump_animation.start(origin_offset, target_offset, target);
+        *self.dirty = true;
+    }
+
+    fn advance_jump_animation(&mut self) {
+        let display_offset = match self.display.jump_animation.advance() {
+            Some(display_offset) => display_offset,
+            None => return,
+        };
+
+        // Keep the vi mode cursor at the jump's target while the viewport catches up.
+        let vi_point = self.terminal.vi_mode_cursor.point;
+        self.scroll(Scroll::Delta(
+            display_offset as i32 - self.terminal.grid().display_offset() as i32,
+        ));
+        self.terminal.vi_mode_cursor.point = vi_point;
+
+        *self.dirty = true;
+    }
+
+    fn copy_selection_to_register(&mut self, name: char) {
+        if let Some(text) = self.selection_text() {
+            self.clipboard.store_register(name, text);
         }
-        self.clipboard.store(ty, text);
     }
 
     fn selection_is_empty(&self) -> bool {
@@ -296,6 +557,11 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         *self.dirty = true;
     }
 
+    fn logical_point(&mut self, point: Point) -> Point {
+        let display_offset = self.terminal.grid().display_offset();
+        self.display.bidi.logical_point(point, display_offset)
+    }
+
     fn start_selection(&mut self, ty: SelectionType, point: Point, side: Side) {
         self.terminal.selection = Some(Selection::new(ty, point, side));
         *self.dirty = true;
@@ -318,6 +584,34 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         }
     }
 
+    fn vi_text_object(&mut self, operator: ViOperator, object: ViTextObject, inner: bool) {
+        let point = self.terminal.vi_mode_cursor.point;
+        let range = match object.range(self.terminal, point, inner) {
+            Some(range) => range,
+            None => return,
+        };
+
+        let mut selection = Selection::new(SelectionType::Simple, *range.start(), Side::Left);
+        selection.update(*range.end(), Side::Right);
+        self.terminal.selection = Some(selection);
+        *self.dirty = true;
+
+        match operator {
+            ViOperator::Yank { register: Some(name) } => {
+                self.copy_selection_to_register(name);
+                self.clear_selection();
+            },
+            ViOperator::Yank { register: None } => {
+                self.copy_selection(ClipboardType::Clipboard);
+                self.clear_selection();
+            },
+            ViOperator::Select => {
+                self.terminal.vi_mode_cursor.point = *range.end();
+                self.copy_selection(ClipboardType::Selection);
+            },
+        }
+    }
+
     #[inline]
     fn mouse_mode(&self) -> bool {
         self.terminal.mode().intersects(TermMode::MOUSE_MODE)
@@ -344,6 +638,11 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         self.modifiers
     }
 
+    #[inline]
+    fn pending_releases(&mut self) -> &mut PendingReleases {
+        self.pending_releases
+    }
+
     #[inline]
     fn window(&mut self) -> &mut Window {
         &mut self.display.window
@@ -378,8 +677,7 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
                 break;
             }
 
-            // On unix, the working directory of the foreground shell is used by `start_daemon`.
-            #[cfg(not(windows))]
+            // Replace the working directory with the one of the current terminal.
             if arg == "--working-directory" {
                 let _ = env_args.next();
                 continue;
@@ -388,45 +686,51 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
             args.push(arg);
         }
 
+        if let Some(working_directory) = self.working_directory() {
+            args.push("--working-directory".into());
+            args.push(working_directory.to_string_lossy().into_owned());
+        }
+
         self.spawn_daemon(&alacritty, &args);
     }
 
     #[cfg(not(windows))]
     fn create_new_window(&mut self, #[cfg(target_os = "macos")] tabbing_id: Option<String>) {
         let mut options = WindowOptions::default();
-        if let Ok(working_directory) = foreground_process_path(self.master_fd, self.shell_pid) {
-            options.terminal_options.working_directory = Some(working_directory);
-        }
+        options.terminal_options.working_directory = self.working_directory();
 
         #[cfg(target_os = "macos")]
         {
             options.window_tabbing_id = tabbing_id;
         }
 
-        let _ = self.event_proxy.send_event(Event::new(EventType::CreateWindow(options), None));
+        let event = Event::new(EventType::CreateWindow(Box::new(options)), None);
+        let _ = self.event_proxy.send_event(event);
     }
 
     #[cfg(windows)]
     fn create_new_window(&mut self) {
-        let _ = self
-            .event_proxy
-            .send_event(Event::new(EventType::CreateWindow(WindowOptions::default()), None));
+        let mut options = WindowOptions::default();
+        options.terminal_options.working_directory = self.working_directory();
+
+        let event = Event::new(EventType::CreateWindow(Box::new(options)), None);
+        let _ = self.event_proxy.send_event(event);
     }
 
-    fn spawn_daemon<I, S>(&self, program: &str, args: I)
+    fn spawn_daemon<I, S>(&mut self, program: &str, args: I)
     where
         I: IntoIterator<Item = S> + Debug + Copy,
         S: AsRef<OsStr>,
     {
-        #[cfg(not(windows))]
-        let result = spawn_daemon(program, args, self.master_fd, self.shell_pid);
-        #[cfg(windows)]
-        let result = spawn_daemon(program, args);
-
-        match result {
-            Ok(_) => debug!("Launched {} with args {:?}", program, args),
-            Err(_) => warn!("Unable to launch {} with args {:?}", program, args),
+        // Launch once an activation token allows the application to take focus.
+        if let Some(serial) = self.display.window.request_activation_token() {
+            let program = program.to_owned();
+            let args = args.into_iter().map(|arg| arg.as_ref().to_string_lossy().into()).collect();
+            self.pending_launches.push(PendingLaunch { serial, program, args });
+            return;
         }
+
+        self.spawn_daemon_with_env(program, args, &[]);
     }
 
     fn change_font_size(&mut self, delta: f32) {
@@ -448,12 +752,46 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         }
     }
 
+    fn message_action(&mut self, action: MessageAction) {
+        match action {
+            MessageAction::DismissAll => {
+                if !self.message_buffer.is_empty() {
+                    self.display.pending_update.dirty = true;
+                    self.message_buffer.clear();
+                }
+            },
+            MessageAction::OpenConfig => {
+                let config_path = match self.config.config_paths.first() {
+                    Some(config_path) => config_path.clone(),
+                    None => {
+                        warn!("No configuration file to open");
+                        return;
+                    },
+                };
+
+                let opener = ui_config::default_opener();
+                let mut args = opener.args().to_vec();
+                args.push(config_path.to_string_lossy().into_owned());
+                self.spawn_daemon(opener.program(), &args);
+            },
+        }
+    }
+
     #[inline]
     fn start_search(&mut self, direction: Direction) {
+        // Pick up searches from other windows.
+        let search_config = &self.config.search;
+        if search_config.persistent_history {
+            let history = search_history::load(search_config.history_size);
+            if !history.is_empty() {
+                self.search_state.history = history;
+            }
+        }
+
         // Only create new history entry if the previous regex wasn't empty.
         if self.search_state.history.get(0).map_or(true, |regex| !regex.is_empty()) {
             self.search_state.history.push_front(String::new());
-            self.search_state.history.truncate(MAX_SEARCH_HISTORY_SIZE);
+            self.search_state.history.truncate(search_config.history_size.saturating_add(1));
         }
 
         self.search_state.history_index = Some(0);
@@ -588,8 +926,62 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         self.update_search();
     }
 
+    #[inline]
+    fn search_toggle_case_sensitivity(&mut self) {
+        let case_sensitivity = &mut self.search_state.options.case_sensitivity;
+        *case_sensitivity = match case_sensitivity {
+            CaseSensitivity::Smart => CaseSensitivity::Sensitive,
+            CaseSensitivity::Sensitive => CaseSensitivity::Insensitive,
+            CaseSensitivity::Insensitive => CaseSensitivity::Smart,
+        };
+        self.update_search();
+    }
+
+    #[inline]
+    fn search_toggle_whole_word(&mut self) {
+        self.search_state.options.whole_word = !self.search_state.options.whole_word;
+        self.update_search();
+    }
+
+    #[inline]
+    fn search_toggle_fuzzy(&mut self) {
+        self.search_state.options.fuzzy = !self.search_state.options.fuzzy;
+        self.update_search();
+    }
+
+    #[inline]
+    fn search_toggle_scope(&mut self) {
+        if self.search_state.scope.take().is_none() {
+            // Restrict search to the selection, or the output of the last command.
+            let selection = self.terminal.selection.as_ref();
+            self.search_state.scope = match selection.and_then(|s| s.to_range(self.terminal)) {
+                Some(range) => Some(range.start..=range.end),
+                None => self.terminal.command_outputs(1).pop(),
+            };
+        }
+        self.update_search();
+    }
+
     #[inline]
     fn advance_search_origin(&mut self, direction: Direction) {
+        let origin_offset = self.terminal.grid().display_offset();
+
+        // Cycle through fuzzy matches by their rank.
+        let match_count = self.search_state.ranked_matches.len();
+        if self.search_state.options.fuzzy && match_count > 0 {
+            let ranked_index = &mut self.search_state.ranked_index;
+            *ranked_index = match direction {
+                Direction::Right => (*ranked_index + 1) % match_count,
+                Direction::Left => (*ranked_index + match_count - 1) % match_count,
+            };
+            self.goto_match(None);
+
+            if let Some(focused_match) = self.search_state.focused_match.clone() {
+                self.animate_jump(origin_offset, focused_match);
+            }
+            return;
+        }
+
         // Use focused match as new search origin if available.
         if let Some(focused_match) = &self.search_state.focused_match {
             let new_origin = match direction {
@@ -611,11 +1003,13 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         // If we found a match, we set the search origin right in front of it to make sure that
         // after modifications to the regex the search is started without moving the focused match
         // around.
-        let focused_match = match &self.search_state.focused_match {
+        let focused_match = match self.search_state.focused_match.clone() {
             Some(focused_match) => focused_match,
             None => return,
         };
 
+        self.animate_jump(origin_offset, focused_match.clone());
+
         // Set new origin to the left/right of the match, depending on search direction.
         let new_origin = match self.search_state.direction {
             Direction::Right => *focused_match.start(),
@@ -635,10 +1029,19 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 
     /// Find the next search match.
     fn search_next(&mut self, origin: Point, direction: Direction, side: Side) -> Option<Match> {
-        self.search_state
-            .dfas
-            .as_ref()
-            .and_then(|dfas| self.terminal.search_next(dfas, origin, direction, side, None))
+        loop {
+            let regex_match =
+                self.search_state.search_next(self.terminal, origin, direction, side, None);
+
+            // Page in history stored on disk when the search wrapped around without a match.
+            let wrapped = regex_match.as_ref().map_or(true, |m| *m.start() >= origin);
+            if direction == Direction::Right
+                || !wrapped
+                || self.terminal.restore_history(self.terminal.screen_lines()) == 0
+            {
+                return regex_match;
+            }
+        }
     }
 
     #[inline]
@@ -651,6 +1054,14 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         self.search_state.history_index.is_some()
     }
 
+    fn replaying(&self) -> bool {
+        self.replay
+    }
+
+    fn child_exited(&self) -> bool {
+        *self.child_exited
+    }
+
     /// Handle keyboard typing start.
     ///
     /// This will temporarily disable some features like terminal cursor blinking or the mouse
@@ -694,21 +1105,40 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         }
 
         let hint_bounds = hint.bounds();
-        let text = match hint.hyperlink() {
-            Some(hyperlink) => hyperlink.uri().to_owned(),
-            None => self.terminal.bounds_to_string(*hint_bounds.start(), *hint_bounds.end()),
+        let text = match (hint.hyperlink(), hint.path()) {
+            (Some(hyperlink), _) => hyperlink.uri().to_owned(),
+            (None, Some(path)) => path.to_string_lossy().into_owned(),
+            (None, None) => {
+                self.terminal.bounds_to_string(*hint_bounds.start(), *hint_bounds.end())
+            },
         };
 
         match &hint.action() {
             // Launch an external program.
             HintAction::Command(command) => {
                 let mut args = command.args().to_vec();
-                args.push(text);
+
+                // Avoid building the capture group regex without any potential placeholders.
+                let captures = if args.iter().any(|arg| arg.contains('{')) {
+                    hint.captures(&text).unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+
+                // Substitute capture groups, or attach the text when there are no placeholders.
+                if args.iter().any(|arg| has_capture_placeholder(arg, captures.len())) {
+                    for arg in &mut args {
+                        *arg = substitute_captures(arg, &captures);
+                    }
+                } else {
+                    args.push(text);
+                }
+
                 self.spawn_daemon(command.program(), &args);
             },
             // Copy the text to the clipboard.
             HintAction::Action(HintInternalAction::Copy) => {
-                self.clipboard.store(ClipboardType::Clipboard, text);
+                self.store_clipboard(ClipboardType::Clipboard, text);
             },
             // Write the text to the PTY/search.
             HintAction::Action(HintInternalAction::Paste) => self.paste(&text, true),
@@ -728,7 +1158,36 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
                 self.terminal.vi_goto_point(*hint_bounds.start());
                 self.mark_dirty();
             },
+            // Write the text to the PTY as if it was typed.
+            HintAction::Action(HintInternalAction::Type) => self.paste(&text, false),
+            // Search for the text.
+            HintAction::Action(HintInternalAction::Search) => {
+                if !self.search_active() {
+                    self.start_search(Direction::Right);
+                }
+
+                for c in escape_regex(&text).chars() {
+                    self.search_input(c);
+                }
+            },
+        }
+    }
+
+    /// Working directory of the terminal's foreground process.
+    ///
+    /// The directory reported by the shell through OSC 7 is preferred, as long as it exists on
+    /// this machine.
+    fn working_directory(&self) -> Option<PathBuf> {
+        let reported = self.terminal.working_directory().filter(|path| path.is_dir());
+        if let Some(working_directory) = reported {
+            return Some(working_directory.to_owned());
         }
+
+        #[cfg(not(windows))]
+        return foreground_process_path(self.master_fd, self.shell_pid).ok();
+
+        #[cfg(windows)]
+        return None;
     }
 
     /// Expand the selection to the current mouse cursor position.
@@ -772,11 +1231,140 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         self.on_typing_start();
         self.clear_selection();
 
-        if self.terminal().grid().display_offset() != 0 {
+        let scroll_to_bottom = self.config.terminal_config.scrolling.scroll_to_bottom;
+        if scroll_to_bottom.keypress && self.terminal().grid().display_offset() != 0 {
             self.scroll(Scroll::Bottom);
         }
     }
 
+    /// Write the scrollback history and screen content to a file.
+    ///
+    /// Without a path, a new file is created in Alacritty's working directory.
+    fn dump_history(&mut self, path: Option<PathBuf>, ansi: bool, timestamps: bool) {
+        let path = path.unwrap_or_else(|| {
+            let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            let extension = if ansi { "ansi" } else { "txt" };
+            PathBuf::from(format!("alacritty-history-{}.{extension}", timestamp.as_secs()))
+        });
+
+        let format: fn(SystemTime) -> String = timestamp::format_date_time;
+        let text = self.terminal.history_to_string(ansi, timestamps.then_some(format));
+        match fs::write(&path, text) {
+            Ok(()) => info!("Wrote scrollback history to {path:?}"),
+            Err(err) => error!("Unable to write scrollback history to {path:?}: {err}"),
+        }
+    }
+
+    /// Write the next rendered frame to a PNG file.
+    ///
+    /// Without a path, a new file is created in Alacritty's working directory.
+    fn screenshot(&mut self, path: Option<PathBuf>) {
+        self.display.pending_screenshot = Some(path.unwrap_or_else(screenshot::default_path));
+        *self.dirty = true;
+    }
+
+    /// Write the selection, or the visible screen without one, to an SVG file.
+    ///
+    /// The file is created in Alacritty's working directory.
+    fn export_svg(&mut self) {
+        let colors = &self.display.colors;
+        let formatted = FormattedText::from_selection(self.terminal, self.config, colors)
+            .unwrap_or_else(|| {
+                let cursor = self.display.cursor;
+                FormattedText::from_screen(self.terminal, self.config, colors, cursor)
+            });
+
+        // Convert the cell size to logical pixels, to match the font size in points.
+        let scale_factor = self.display.window.scale_factor as f32;
+        let cell_width = self.display.size_info.cell_width() / scale_factor;
+        let cell_height = self.display.size_info.cell_height() / scale_factor;
+        let svg = formatted.svg(cell_width, cell_height);
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let path = PathBuf::from(format!("alacritty-screen-{}.svg", timestamp.as_millis()));
+        match fs::write(&path, svg) {
+            Ok(()) => info!("Wrote SVG export to {path:?}"),
+            Err(err) => error!("Unable to write SVG export to {path:?}: {err}"),
+        }
+    }
+
+    /// Open the scrollback history in a new window running the configured pager.
+    fn open_history_in_pager(&mut self) {
+        let pager = &self.config.pager;
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let extension = if pager.ansi { "ansi" } else { "txt" };
+        let pid = std::process::id();
+        let file_name = format!("alacritty-history-{pid}-{}.{extension}", timestamp.as_nanos());
+        let path = env::temp_dir().join(file_name);
+
+        let format: fn(SystemTime) -> String = timestamp::format_date_time;
+        let timestamps = self.display.show_timestamps.then_some(format);
+        let text = self.terminal.history_to_string(pager.ansi, timestamps);
+        if let Err(err) = fs::write(&path, text) {
+            error!("Unable to write scrollback history to {path:?}: {err}");
+            return;
+        }
+
+        let mut options = WindowOptions::default();
+        options.terminal_options.working_directory = self.working_directory();
+        options.terminal_options.command = pager.command();
+        options.terminal_options.command.push(path.to_string_lossy().into_owned());
+        options.temporary_file = Some(path);
+
+        let event = Event::new(EventType::CreateWindow(Box::new(options)), None);
+        let _ = self.event_proxy.send_event(event);
+    }
+
+    /// Start recording the terminal output, or stop the active recording.
+    ///
+    /// Recordings are created in Alacritty's working directory.
+    fn toggle_recording(&mut self) {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let path = PathBuf::from(format!("alacritty-{}.cast", timestamp.as_secs()));
+        let path = env::current_dir().map(|cwd| cwd.join(&path)).unwrap_or(path);
+        self.notifier.toggle_recording(path);
+    }
+
+    fn control_replay(&mut self, control: ReplayControl) {
+        self.notifier.control_replay(control);
+    }
+
+    /// Split, focus, resize or close panes of this window.
+    fn pane_action(&mut self, action: PaneAction) {
+        let event = Event::new(EventType::Pane(action), self.display.window.id());
+        let _ = self.event_proxy.send_event(event);
+    }
+
+    /// Move the focused pane into another window.
+    fn move_pane(&mut self, destination: PaneDestination) {
+        let event = EventType::MovePane(destination, PaneDirection::default());
+        let event = Event::new(event, self.display.window.id());
+        let _ = self.event_proxy.send_event(event);
+    }
+
+    /// Show or hide dropdown windows.
+    fn toggle_dropdown(&mut self) {
+        let _ = self.event_proxy.send_event(Event::new(EventType::ToggleDropdown, None));
+    }
+
+    /// Close the window with all of its panes.
+    fn close_window(&mut self) {
+        let event = Event::new(TerminalEvent::Exit.into(), self.display.window.id());
+        let _ = self.event_proxy.send_event(event);
+    }
+
+    /// Start or stop writing input to all panes of the window.
+    fn toggle_broadcast_input(&mut self) {
+        self.display.broadcast.panes = !self.display.broadcast.panes;
+        *self.dirty = true;
+    }
+
+    /// Run an action registered by a Lua plugin.
+    fn run_script_action(&mut self, name: &str) {
+        let requests = self.scripting.run_action(name, self.terminal);
+        self.apply_script_requests(requests);
+    }
+
     /// Paste a text into the terminal.
     fn paste(&mut self, text: &str, bracketed: bool) {
         if self.search_active() {
@@ -786,7 +1374,7 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         } else if bracketed && self.terminal().mode().contains(TermMode::BRACKETED_PASTE) {
             self.on_terminal_input_start();
 
-            self.write_to_pty(&b"\x1b[200~"[..]);
+            self.write_input(&b"\x1b[200~"[..]);
 
             // Write filtered escape sequences.
             //
@@ -794,9 +1382,9 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
             // paste end escape `\x1b[201~` and `\x03` since some shells incorrectly terminate
             // bracketed paste on its receival.
             let filtered = text.replace(['\x1b', '\x03'], "");
-            self.write_to_pty(filtered.into_bytes());
+            self.write_input(filtered.into_bytes());
 
-            self.write_to_pty(&b"\x1b[201~"[..]);
+            self.write_input(&b"\x1b[201~"[..]);
         } else {
             self.on_terminal_input_start();
 
@@ -806,7 +1394,7 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
             // pasting... since that's neither practical nor sensible (and probably an impossible
             // task to solve in a general way), we'll just replace line breaks (windows and unix
             // style) with a single carriage return (\r, which is what the Enter key produces).
-            self.write_to_pty(text.replace("\r\n", "\r").replace('\n', "\r").into_bytes());
+            self.write_input(text.replace("\r\n", "\r").replace('\n', "\r").into_bytes());
         }
     }
 
@@ -861,6 +1449,17 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 }
 
 impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
+    /// Text of the current selection, for copying it.
+    fn selection_text(&self) -> Option<String> {
+        let text = self.terminal.selection_to_string().filter(|s| !s.is_empty())?;
+
+        if self.config.terminal_config.selection.trim_trailing_whitespace {
+            Some(text.split('\n').map(str::trim_end).collect::<Vec<_>>().join("\n"))
+        } else {
+            Some(text)
+        }
+    }
+
     fn update_search(&mut self) {
         let regex = match self.search_state.regex() {
             Some(regex) => regex,
@@ -878,7 +1477,9 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
             self.search_state.dfas = None;
         } else {
             // Create search dfas for the new regex string.
-            self.search_state.dfas = RegexSearch::new(regex).ok();
+            self.search_state.dfas =
+                RegexSearch::with_options(regex, self.search_state.options).ok();
+            self.search_state.rank_matches(self.terminal, MAX_SEARCH_WHILE_TYPING);
 
             // Update search highlighting.
             self.goto_match(MAX_SEARCH_WHILE_TYPING);
@@ -913,18 +1514,36 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
 
     /// Jump to the first regex match from the search origin.
     fn goto_match(&mut self, mut limit: Option<usize>) {
-        let dfas = match &self.search_state.dfas {
-            Some(dfas) => dfas,
-            None => return,
-        };
+        if self.search_state.dfas.is_none() {
+            return;
+        }
 
         // Limit search only when enough lines are available to run into the limit.
         limit = limit.filter(|&limit| limit <= self.terminal.total_lines());
 
         // Jump to the next match.
         let direction = self.search_state.direction;
-        let clamped_origin = self.search_state.origin.grid_clamp(self.terminal, Boundary::Grid);
-        match self.terminal.search_next(dfas, clamped_origin, direction, Side::Left, limit) {
+        let origin = self.search_state.origin.grid_clamp(self.terminal, Boundary::Grid);
+        let regex_match = if self.search_state.options.fuzzy {
+            // Rank all matches once the search is no longer limited, keeping the focused one.
+            if limit.is_none() && self.search_state.ranking_limited {
+                let search_state = &mut self.search_state;
+                let focused_match = search_state.ranked_matches.get(search_state.ranked_index);
+                let focused_match = focused_match.cloned();
+                search_state.rank_matches(self.terminal, None);
+                let ranked_matches = &search_state.ranked_matches;
+                search_state.ranked_index = focused_match
+                    .and_then(|focused| ranked_matches.iter().position(|m| *m == focused))
+                    .unwrap_or_default();
+            }
+
+            let ranked_matches = &self.search_state.ranked_matches;
+            ranked_matches.get(self.search_state.ranked_index).cloned()
+        } else {
+            self.search_state.search_next(self.terminal, origin, direction, Side::Left, limit)
+        };
+
+        match regex_match {
             Some(regex_match) => {
                 let old_offset = self.terminal.grid().display_offset() as i32;
 
@@ -943,9 +1562,15 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
                 let display_offset = self.terminal.grid().display_offset();
                 self.search_state.display_offset_delta += old_offset - display_offset as i32;
 
-                // Since we found a result, we require no delayed re-search.
+                // Since we found a result, we only require a delayed re-search to rank the
+                // remaining fuzzy matches.
                 let timer_id = TimerId::new(Topic::DelayedSearch, self.display.window.id());
-                self.scheduler.unschedule(timer_id);
+                if !self.search_state.ranking_limited {
+                    self.scheduler.unschedule(timer_id);
+                } else if !self.scheduler.scheduled(timer_id) {
+                    let event = Event::new(EventType::SearchNext, self.display.window.id());
+                    self.scheduler.schedule(event, TYPING_SEARCH_DELAY, false, timer_id);
+                }
             },
             // Reset viewport only when we know there is no match, to prevent unnecessary jumping.
             None if limit.is_none() => self.search_reset_state(),
@@ -965,18 +1590,296 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
         *self.dirty = true;
     }
 
-    /// Cleanup the search state.
-    fn exit_search(&mut self) {
-        let vi_mode = self.terminal.mode().contains(TermMode::VI);
-        self.window().set_ime_allowed(!vi_mode);
+    /// Run a search requested over IPC.
+    #[cfg(unix)]
+    fn ipc_search(&mut self, ipc_search: IpcSearch) {
+        // Discard any search the user is currently typing.
+        if self.search_active() {
+            self.search_reset_state();
+            self.exit_search();
+        }
 
-        self.terminal.mark_fully_damaged();
-        self.display.pending_update.dirty = true;
-        self.search_state.history_index = None;
+        let direction = if ipc_search.backwards { Direction::Left } else { Direction::Right };
+        self.start_search(direction);
 
-        // Clear focused match.
-        self.search_state.focused_match = None;
-    }
+        if let Some(regex) = self.search_state.regex_mut() {
+            *regex = ipc_search.regex;
+        }
+        self.update_search();
+
+        // Search the entire scrollback without waiting for the delayed search.
+        if self.search_state.focused_match.is_none() {
+            self.goto_match(None);
+        }
+
+        if !ipc_search.select {
+            return;
+        }
+
+        // Select the match and stop searching, like cancelling a search outside of vi mode.
+        if let Some(focused_match) = self.search_state.focused_match.clone() {
+            let (start, end) = (*focused_match.start(), *focused_match.end());
+            self.start_selection(SelectionType::Simple, start, Side::Left);
+            self.update_selection(end, Side::Right);
+            self.copy_selection(ClipboardType::Selection);
+
+            if self.terminal.mode().contains(TermMode::VI) {
+                self.terminal.vi_mode_cursor.point = end;
+            }
+        }
+
+        self.search_state.dfas = None;
+        self.exit_search();
+    }
+
+    /// Write text received over IPC to the PTY.
+    #[cfg(unix)]
+    fn ipc_send_text(&mut self, ipc_send_text: IpcSendText) {
+        let text = ipc_send_text.text.unwrap_or_default();
+        if ipc_send_text.bracketed {
+            self.paste(&text, true);
+        } else {
+            self.on_terminal_input_start();
+            self.write_input(text.into_bytes());
+        }
+    }
+
+    /// Update the synchronized input of the window over IPC.
+    #[cfg(unix)]
+    fn ipc_broadcast(&mut self, ipc_broadcast: IpcBroadcast) {
+        let broadcast = &mut self.display.broadcast;
+        match ipc_broadcast {
+            IpcBroadcast { off: true, .. } => *broadcast = Default::default(),
+            IpcBroadcast { panes: false, group: None, .. } => broadcast.panes = !broadcast.panes,
+            IpcBroadcast { panes, group, .. } => {
+                broadcast.panes |= panes;
+                broadcast.group = group.or(broadcast.group.take());
+            },
+        }
+
+        *self.dirty = true;
+    }
+
+    /// Get the terminal text requested over IPC.
+    #[cfg(unix)]
+    fn ipc_get_text(&self, ipc_get_text: IpcGetText) -> SocketReply {
+        if ipc_get_text.selection {
+            return match self.terminal.selection_to_string() {
+                Some(text) => SocketReply::Text(text),
+                None => SocketReply::Error(String::from("no active selection")),
+            };
+        }
+
+        let display_offset = self.terminal.grid().display_offset() as i32;
+        let screen_top = Line(-display_offset);
+        let screen_bottom = screen_top + (self.terminal.screen_lines() as i32 - 1);
+
+        let (start, end) = match ipc_get_text.history {
+            Some(lines) => {
+                let lines = i32::try_from(lines).unwrap_or(i32::MAX);
+                let start = max(self.terminal.topmost_line(), Line(-lines));
+                (start, self.terminal.bottommost_line())
+            },
+            None => (screen_top, screen_bottom),
+        };
+
+        let start = Point::new(start, Column(0));
+        let end = Point::new(end, self.terminal.last_column());
+        let mut text = self.terminal.bounds_to_string(start, end);
+        text.push('\n');
+
+        SocketReply::Text(text)
+    }
+
+    /// Get the terminal state requested over IPC.
+    #[cfg(unix)]
+    fn ipc_get_state(&self) -> IpcState {
+        let size_info = self.display.size_info;
+        let cursor = self.terminal.grid().cursor.point;
+        let modes = self
+            .terminal
+            .mode()
+            .iter_names()
+            .filter(|(_, mode)| mode.bits().is_power_of_two())
+            .map(|(name, _)| name.to_lowercase())
+            .collect();
+
+        IpcState {
+            window_id: self.display.window.id().into(),
+            title: self.display.window.title().to_owned(),
+            working_directory: self.working_directory(),
+            columns: size_info.columns(),
+            screen_lines: size_info.screen_lines(),
+            width: size_info.width() as u32,
+            height: size_info.height() as u32,
+            cursor_line: cursor.line.0,
+            cursor_column: cursor.column.0,
+            display_offset: self.terminal.grid().display_offset(),
+            modes,
+        }
+    }
+
+    /// Start a new process in the background, with additional environment variables.
+    fn spawn_daemon_with_env<I, S>(&self, program: &str, args: I, envs: &[(&str, String)])
+    where
+        I: IntoIterator<Item = S> + Debug + Copy,
+        S: AsRef<OsStr>,
+    {
+        #[cfg(not(windows))]
+        let result = spawn_daemon(program, args, envs, self.master_fd, self.shell_pid);
+        #[cfg(windows)]
+        let result = spawn_daemon(program, args, envs);
+
+        match result {
+            Ok(_) => debug!("Launched {} with args {:?}", program, args),
+            Err(_) => warn!("Unable to launch {} with args {:?}", program, args),
+        }
+    }
+
+    /// Start a process waiting for an activation token.
+    fn launch_pending(&mut self, serial: AsyncRequestSerial, token: ActivationToken) {
+        let index = match self.pending_launches.iter().position(|launch| launch.serial == serial) {
+            Some(index) => index,
+            None => return,
+        };
+
+        let launch = self.pending_launches.swap_remove(index);
+        let envs = window::activation_token_env(token);
+        self.spawn_daemon_with_env(&launch.program, &launch.args, &envs);
+    }
+
+    /// Run the command hooked to a terminal event.
+    ///
+    /// The event's details are passed to the command through environment variables.
+    fn run_hook(&self, hook: &Program, event: &str, details: &[(&str, String)]) {
+        let mut envs = vec![
+            ("ALACRITTY_EVENT", event.to_owned()),
+            ("ALACRITTY_WINDOW_ID", u64::from(self.display.window.id()).to_string()),
+        ];
+        envs.extend_from_slice(details);
+
+        self.spawn_daemon_with_env(hook.program(), hook.args(), &envs);
+    }
+
+    /// Store text in a clipboard, mirroring it to the copy hook.
+    fn store_clipboard(&mut self, ty: ClipboardType, text: String) {
+        self.run_copy_hook(ty, &text);
+        self.clipboard.store(ty, text);
+    }
+
+    /// Pass text stored in a clipboard to the copy hook.
+    fn run_copy_hook(&self, ty: ClipboardType, text: &str) {
+        let hooks = &self.config.hooks;
+        match &hooks.copy {
+            Some(hook) if !text.is_empty() && text.len() <= hooks.copy_max_size => {
+                let clipboard = match ty {
+                    ClipboardType::Clipboard => "clipboard",
+                    ClipboardType::Selection => "selection",
+                };
+                let envs = [
+                    ("ALACRITTY_EVENT", String::from("copy")),
+                    ("ALACRITTY_WINDOW_ID", u64::from(self.display.window.id()).to_string()),
+                    ("ALACRITTY_CLIPBOARD", String::from(clipboard)),
+                ];
+
+                match spawn_with_input(hook.program(), hook.args(), &envs, text.to_owned()) {
+                    Ok(_) => debug!("Launched copy hook {}", hook.program()),
+                    Err(err) => warn!("Unable to launch copy hook {}: {}", hook.program(), err),
+                }
+            },
+            Some(_) if !text.is_empty() => debug!("Not passing {} bytes to copy hook", text.len()),
+            _ => (),
+        }
+    }
+
+    /// Clear the selection after copying it, if enabled.
+    fn clear_copied_selection(&mut self) {
+        // Vi mode selections are kept, since they're modified in place while copying.
+        let clear_after_copy = self.config.terminal_config.selection.clear_after_copy;
+        if clear_after_copy && !self.terminal.mode().contains(TermMode::VI) {
+            self.clear_selection();
+        }
+    }
+
+    /// Report the exit of a held terminal's child process.
+    fn on_child_exit(&mut self, exit_code: Option<i32>) {
+        *self.child_exited = true;
+
+        let duration = Some(self.spawned.elapsed());
+        let focused = self.terminal.is_focused;
+        notification::command_finished(
+            self.config,
+            &self.display.window,
+            focused,
+            duration,
+            exit_code,
+        );
+
+        let (text, ty) = match exit_code {
+            Some(0) => (String::from("Process exited successfully"), MessageType::Warning),
+            Some(code) => (format!("Process exited with status {code}"), MessageType::Error),
+            None => (String::from("Process exited abnormally"), MessageType::Error),
+        };
+        let message = Message::new(format!("{text}, press Enter to close"), ty);
+        self.message_buffer.push(message);
+        self.display.pending_update.dirty = true;
+    }
+
+    /// Notify Lua plugins and D-Bus clients about a terminal event.
+    fn notify_event(&mut self, event: ScriptEvent) {
+        #[cfg(all(feature = "dbus", not(any(target_os = "macos", windows))))]
+        dbus::emit_signal(self.display.window.id(), &event);
+
+        let requests = self.scripting.emit(event, self.terminal);
+        self.apply_script_requests(requests);
+    }
+
+    /// Match new terminal output against the output handlers of Lua plugins.
+    fn scan_script_output(&mut self) {
+        let requests = self.scripting.scan_output(self.terminal);
+        self.apply_script_requests(requests);
+    }
+
+    /// Apply changes requested by Lua plugins.
+    fn apply_script_requests(&mut self, requests: Vec<ScriptRequest>) {
+        for request in requests {
+            match request {
+                ScriptRequest::Write(text) => self.paste(&text, false),
+                ScriptRequest::Paste(text) => self.paste(&text, true),
+                ScriptRequest::DrawQuad(quad) => self.display.plugin_overlay.push_quad(quad),
+                ScriptRequest::DrawText(text) => self.display.plugin_overlay.push_text(text),
+                ScriptRequest::ClearOverlay => self.display.plugin_overlay.clear(),
+            }
+
+            *self.dirty = true;
+        }
+    }
+
+    /// Cleanup the search state.
+    fn exit_search(&mut self) {
+        let vi_mode = self.terminal.mode().contains(TermMode::VI);
+        self.window().set_ime_allowed(!vi_mode);
+
+        self.terminal.mark_fully_damaged();
+        self.display.pending_update.dirty = true;
+
+        // Move the last regex to the front of the history, removing duplicates.
+        let history = &mut self.search_state.history;
+        let regex = self.search_state.history_index.take().map(|index| history[index].clone());
+        if let Some(regex) = regex.filter(|regex| !regex.is_empty()) {
+            let history_size = self.config.search.history_size;
+            history.retain(|entry| !entry.is_empty());
+            search_history::push_entry(history, regex.clone(), history_size);
+
+            if self.config.search.persistent_history {
+                search_history::store(&regex, history_size);
+            }
+        }
+
+        // Clear focused match and search scope.
+        self.search_state.focused_match = None;
+        self.search_state.scope = None;
+    }
 
     /// Update the cursor blinking state.
     fn update_cursor_blinking(&mut self) {
@@ -991,7 +1894,8 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
         let terminal_blinking = self.terminal.cursor_style().blinking;
         let mut blinking = cursor_style.blinking_override().unwrap_or(terminal_blinking);
         blinking &= (vi_mode || self.terminal().mode().contains(TermMode::SHOW_CURSOR))
-            && self.display().ime.preedit().is_none();
+            && self.display().ime.preedit().is_none()
+            && !self.display().power_saving();
 
         // Update cursor blinking state.
         let window_id = self.display.window.id();
@@ -1034,6 +1938,56 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
     }
 }
 
+/// Check if an argument contains a `{N}` placeholder for one of the capture groups.
+fn has_capture_placeholder(arg: &str, group_count: usize) -> bool {
+    (0..group_count).any(|index| arg.contains(&format!("{{{index}}}")))
+}
+
+/// Replace all `{N}` placeholders with the text of the Nth capture group.
+///
+/// Groups which did not participate in the match are replaced with an empty string.
+fn substitute_captures(arg: &str, captures: &[Option<String>]) -> String {
+    let mut substituted = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        substituted.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        // Copy anything that isn't a placeholder for an existing group literally.
+        let placeholder = rest
+            .split_once('}')
+            .filter(|(index, _)| index.bytes().all(|byte| byte.is_ascii_digit()))
+            .and_then(|(index, tail)| Some((captures.get(index.parse::<usize>().ok()?)?, tail)));
+
+        match placeholder {
+            Some((capture, tail)) => {
+                substituted.push_str(capture.as_deref().unwrap_or_default());
+                rest = tail;
+            },
+            None => substituted.push('{'),
+        }
+    }
+    substituted.push_str(rest);
+    substituted
+}
+
+/// Check if a search match is entirely within the search scope.
+pub fn match_in_scope(regex_match: &Match, scope: &Match) -> bool {
+    scope.contains(regex_match.start()) && scope.contains(regex_match.end())
+}
+
+/// Escape all regex meta characters, to search for the text literally.
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 /// Identified purpose of the touch input.
 #[derive(Debug)]
 pub enum TouchPurpose {
@@ -1113,6 +2067,7 @@ pub struct Mouse {
     pub block_hint_launcher: bool,
     pub hint_highlight_dirty: bool,
     pub inside_text_area: bool,
+    pub minimap_drag: bool,
     pub x: usize,
     pub y: usize,
 }
@@ -1130,6 +2085,7 @@ impl Default for Mouse {
             hint_highlight_dirty: Default::default(),
             block_hint_launcher: Default::default(),
             inside_text_area: Default::default(),
+            minimap_drag: Default::default(),
             lines_scrolled: Default::default(),
             accumulated_scroll: Default::default(),
             x: Default::default(),
@@ -1173,6 +2129,16 @@ pub struct AccumulatedScroll {
     pub y: f64,
 }
 
+/// Actions of release bindings, waiting for the key or button whose press triggered them.
+#[derive(Default, Debug)]
+pub struct PendingReleases {
+    /// Actions run once the physical key is released.
+    pub keys: Vec<(KeyCode, config::Action)>,
+
+    /// Actions run once the mouse button is released.
+    pub buttons: Vec<(MouseButton, config::Action)>,
+}
+
 impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
     /// Handle events from winit.
     pub fn handle_event(&mut self, event: WinitEvent<Event>) {
@@ -1199,38 +2165,105 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                 },
                 EventType::Terminal(event) => match event {
                     TerminalEvent::Title(title) => {
+                        if let Some(hook) = &self.ctx.config.hooks.title_changed {
+                            let details = [("ALACRITTY_TITLE", title.clone())];
+                            self.ctx.run_hook(hook, "title_changed", &details);
+                        }
+
+                        self.ctx.notify_event(ScriptEvent::Title(title.clone()));
+
                         if !self.ctx.preserve_title && self.ctx.config.window.dynamic_title {
                             self.ctx.window().set_title(title);
                         }
                     },
                     TerminalEvent::ResetTitle => {
                         let window_config = &self.ctx.config.window;
+                        if let Some(hook) = &self.ctx.config.hooks.title_changed {
+                            let title = window_config.identity.title.clone();
+                            self.ctx.run_hook(hook, "title_changed", &[("ALACRITTY_TITLE", title)]);
+                        }
+
                         if window_config.dynamic_title {
                             self.ctx.display.window.set_title(window_config.identity.title.clone());
                         }
+
+                        let title = self.ctx.config.window.identity.title.clone();
+                        self.ctx.notify_event(ScriptEvent::Title(title));
+                    },
+                    TerminalEvent::Icon(name) => {
+                        // Only icons listed in the config can be selected by applications.
+                        let icons = &self.ctx.config.window.icons;
+                        match icons.get(&name) {
+                            Some(path) => self.ctx.display.window.set_icon(Some(path)),
+                            None if name.is_empty() => self.ctx.display.window.set_icon(None),
+                            None => debug!("Ignoring unknown window icon {name:?}"),
+                        }
+                    },
+                    TerminalEvent::CommandFinished(exit_code) => {
+                        let duration = self.ctx.terminal.last_command_duration();
+
+                        if let Some(hook) = &self.ctx.config.hooks.command_finished {
+                            let code = exit_code.map(|code| code.to_string());
+                            let duration =
+                                duration.map(|duration| duration.as_millis().to_string());
+                            let details = [
+                                ("ALACRITTY_EXIT_CODE", code.unwrap_or_default()),
+                                ("ALACRITTY_COMMAND_DURATION", duration.unwrap_or_default()),
+                            ];
+                            self.ctx.run_hook(hook, "command_finished", &details);
+                        }
+
+                        let focused = self.ctx.terminal.is_focused;
+                        let window = &self.ctx.display.window;
+                        notification::command_finished(
+                            self.ctx.config,
+                            window,
+                            focused,
+                            duration,
+                            exit_code,
+                        );
+
+                        self.ctx.notify_event(ScriptEvent::CommandFinished(exit_code));
                     },
+                    TerminalEvent::PromptStart => self.ctx.notify_event(ScriptEvent::Prompt),
                     TerminalEvent::Bell => {
                         // Set window urgency hint when window is not focused.
                         let focused = self.ctx.terminal.is_focused;
-                        if !focused && self.ctx.terminal.mode().contains(TermMode::URGENCY_HINTS) {
-                            self.ctx.window().set_urgent(true);
-                        }
+                        let urgency_hints =
+                            self.ctx.terminal.mode().contains(TermMode::URGENCY_HINTS);
+                        let window = &self.ctx.display.window;
+                        notification::bell(self.ctx.config, window, focused, urgency_hints);
 
                         // Ring visual bell.
                         self.ctx.display.visual_bell.ring();
 
+                        // Play bell sound.
+                        sound::play_bell(&self.ctx.config.bell);
+
                         // Execute bell command.
                         if let Some(bell_command) = &self.ctx.config.bell.command {
-                            self.ctx.spawn_daemon(bell_command.program(), bell_command.args());
+                            self.ctx.run_hook(bell_command, "bell", &[]);
                         }
+
+                        self.ctx.notify_event(ScriptEvent::Bell);
+                    },
+                    TerminalEvent::ProgressChange(_progress) => {
+                        #[cfg(target_os = "macos")]
+                        self.ctx.window().set_progress(_progress);
+
+                        *self.ctx.dirty = true;
                     },
                     TerminalEvent::ClipboardStore(clipboard_type, content) => {
                         if self.ctx.terminal.is_focused {
-                            self.ctx.clipboard.store(clipboard_type, content);
+                            self.ctx.store_clipboard(clipboard_type, content);
                         }
                     },
                     TerminalEvent::ClipboardLoad(clipboard_type, format) => {
-                        if self.ctx.terminal.is_focused {
+                        let osc52_read = self.ctx.config.terminal_config.terminal.osc52_read;
+                        if self.ctx.terminal.is_focused && osc52_read == Osc52Read::Ask {
+                            self.ctx.display.clipboard_prompt.start(clipboard_type, format);
+                            *self.ctx.dirty = true;
+                        } else if self.ctx.terminal.is_focused {
                             let text = format(self.ctx.clipboard.load(clipboard_type).as_str());
                             self.ctx.write_to_pty(text.into_bytes());
                         }
@@ -1247,18 +2280,74 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                     TerminalEvent::PtyWrite(text) => self.ctx.write_to_pty(text.into_bytes()),
                     TerminalEvent::MouseCursorDirty => self.reset_mouse_cursor(),
                     TerminalEvent::CursorBlinkingChange => self.ctx.update_cursor_blinking(),
-                    TerminalEvent::Exit | TerminalEvent::Wakeup => (),
+                    TerminalEvent::Wakeup => {
+                        let focused = self.ctx.terminal.is_focused;
+                        let display_offset = self.ctx.terminal.grid().display_offset();
+
+                        // Follow new output, or point it out while scrolled back.
+                        let scrolling = self.ctx.config.terminal_config.scrolling;
+                        if scrolling.scroll_to_bottom.output && display_offset != 0 {
+                            self.ctx.scroll(Scroll::Bottom);
+                        } else if display_offset != 0 {
+                            self.ctx.display.new_output_below = true;
+                        }
+
+                        let window = &self.ctx.display.window;
+                        notification::scrolled_output(
+                            self.ctx.config,
+                            window,
+                            focused,
+                            display_offset,
+                        );
+
+                        self.ctx.scan_script_output();
+                    },
+                    TerminalEvent::ChildExit(exit_code) => self.ctx.on_child_exit(exit_code),
+                    TerminalEvent::Exit => (),
+                },
+                #[cfg(unix)]
+                EventType::IpcSearch(ipc_search) => self.ctx.ipc_search(ipc_search),
+                #[cfg(unix)]
+                EventType::IpcDumpHistory(ipc_dump) => {
+                    let IpcDumpHistory { path, ansi, timestamps, .. } = ipc_dump;
+                    self.ctx.dump_history(Some(path), ansi, timestamps)
                 },
                 #[cfg(unix)]
-                EventType::IpcConfig(_) => (),
+                EventType::IpcScreenshot(path) => self.ctx.screenshot(Some(path)),
+                #[cfg(unix)]
+                EventType::IpcSendText(ipc_send_text) => self.ctx.ipc_send_text(ipc_send_text),
+                #[cfg(unix)]
+                EventType::IpcBroadcast(ipc_broadcast) => self.ctx.ipc_broadcast(ipc_broadcast),
+                #[cfg(unix)]
+                EventType::IpcGetText(ipc_get_text, reply) => {
+                    let _ = reply.send(self.ctx.ipc_get_text(ipc_get_text));
+                },
+                #[cfg(unix)]
+                EventType::IpcGetState(reply) => {
+                    let _ = reply.send(SocketReply::State(self.ctx.ipc_get_state()));
+                },
+                #[cfg(unix)]
+                EventType::IpcStats(reply) => {
+                    let _ = reply.send(SocketReply::Stats(self.ctx.display.stats()));
+                },
+                #[cfg(unix)]
+                EventType::IpcConfig(_)
+                | EventType::IpcGetConfig(..)
+                | EventType::IpcListWindows(_) => (),
                 EventType::Message(_)
                 | EventType::ConfigReload(_)
                 | EventType::CreateWindow(_)
-                | EventType::Frame => (),
+                | EventType::Frame
+                | EventType::PowerSourceChanged(_)
+                | EventType::HighContrastChanged(_)
+                | EventType::Pane(_)
+                | EventType::BroadcastInput(_)
+                | EventType::MovePane(..)
+                | EventType::ToggleDropdown => (),
             },
             WinitEvent::WindowEvent { event, .. } => {
                 match event {
-                    WindowEvent::CloseRequested => self.ctx.terminal.exit(),
+                    WindowEvent::CloseRequested => self.ctx.close_window(),
                     WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                         self.ctx.window().scale_factor = scale_factor;
 
@@ -1277,6 +2366,10 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                         }
 
                         self.ctx.display.pending_update.set_dimensions(size);
+
+                        // Replace the toolkit's frame once the compositor refused server-side
+                        // decorations.
+                        self.ctx.display.update_title_bar(&self.ctx.config.window);
                     },
                     WindowEvent::KeyboardInput { event, is_synthetic: false, .. } => {
                         self.key_input(event);
@@ -1298,6 +2391,11 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                     WindowEvent::Focused(is_focused) => {
                         self.ctx.terminal.is_focused = is_focused;
 
+                        #[cfg(feature = "accessibility")]
+                        if let Some(accessibility) = &self.ctx.display.accessibility {
+                            accessibility.set_focused(is_focused);
+                        }
+
                         // When the unfocused hollow is used we must redraw on focus change.
                         if self.ctx.config.terminal_config.cursor.unfocused_hollow {
                             *self.ctx.dirty = true;
@@ -1310,13 +2408,20 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
 
                         self.ctx.update_cursor_blinking();
                         self.on_focus_change(is_focused);
+
+                        if let Some(hook) = &self.ctx.config.hooks.focus_changed {
+                            let details = [("ALACRITTY_FOCUSED", is_focused.to_string())];
+                            self.ctx.run_hook(hook, "focus_changed", &details);
+                        }
+
+                        self.ctx.notify_event(ScriptEvent::Focus(is_focused));
                     },
                     WindowEvent::Occluded(occluded) => {
                         *self.ctx.occluded = occluded;
                     },
                     WindowEvent::DroppedFile(path) => {
-                        let path: String = path.to_string_lossy().into();
-                        self.ctx.paste(&(path + " "), true);
+                        let text = self.ctx.config.mouse.dropped_files.format(&path);
+                        self.ctx.paste(&text, true);
                     },
                     WindowEvent::CursorLeft { .. } => {
                         self.ctx.mouse.inside_text_area = false;
@@ -1336,7 +2441,7 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                             let preedit = if text.is_empty() {
                                 None
                             } else {
-                                Some(Preedit::new(text, cursor_offset.map(|offset| offset.0)))
+                                Some(Preedit::new(text, cursor_offset))
                             };
 
                             if self.ctx.display.ime.preedit() != preedit.as_ref() {
@@ -1354,8 +2459,10 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                             *self.ctx.dirty = true;
                         },
                     },
+                    WindowEvent::ActivationTokenDone { serial, token } => {
+                        self.ctx.launch_pending(serial, token);
+                    },
                     WindowEvent::KeyboardInput { is_synthetic: true, .. }
-                    | WindowEvent::ActivationTokenDone { .. }
                     | WindowEvent::TouchpadPressure { .. }
                     | WindowEvent::TouchpadMagnify { .. }
                     | WindowEvent::TouchpadRotate { .. }
@@ -1390,6 +2497,15 @@ pub struct Processor {
     global_ipc_options: Vec<String>,
     cli_options: CliOptions,
     config: Rc<UiConfig>,
+
+    /// Whether the system is running on battery power.
+    on_battery: bool,
+
+    /// Whether the system asks for high contrast.
+    high_contrast: bool,
+
+    /// Windows ordered from the most recently focused one.
+    focus_history: Vec<WindowId>,
 }
 
 impl Processor {
@@ -1407,6 +2523,9 @@ impl Processor {
             windows: Default::default(),
             #[cfg(unix)]
             global_ipc_options: Default::default(),
+            on_battery: Default::default(),
+            high_contrast: Default::default(),
+            focus_history: Default::default(),
         }
     }
 
@@ -1429,27 +2548,125 @@ impl Processor {
     }
 
     /// Create a new terminal window.
+    ///
+    /// The window hosts `moved_pane` when it is given, instead of spawning a new terminal.
     pub fn create_window(
         &mut self,
         event_loop: &EventLoopWindowTarget<Event>,
         proxy: EventLoopProxy<Event>,
         options: WindowOptions,
-    ) -> Result<(), Box<dyn Error>> {
-        let window = self.windows.iter().next().as_ref().unwrap().1;
+        moved_pane: Option<Pane>,
+    ) -> Result<WindowId, Box<dyn Error>> {
+        // Focus the window with the activation token of the application which requested it.
+        #[cfg(not(any(target_os = "macos", windows)))]
+        if let Some(token) = &options.activation_token {
+            window::set_activation_token_env(token);
+        }
 
         #[allow(unused_mut)]
-        let mut window_context =
-            window.additional(event_loop, proxy, self.config.clone(), options)?;
+        let mut window_context = match (self.windows.values().next(), moved_pane) {
+            (Some(window), moved_pane) => {
+                window.additional(event_loop, proxy, self.config.clone(), options, moved_pane)?
+            },
+            // Load the GL platform again, since the daemon can run without any windows.
+            (None, _) => WindowContext::initial(event_loop, proxy, self.config.clone(), options)?,
+        };
 
         // Apply global IPC options.
         #[cfg(unix)]
         {
             let options = self.global_ipc_options.clone();
-            let ipc_config = IpcConfig { options, window_id: None, reset: false };
+            let ipc_config = IpcConfig { options, ..IpcConfig::default() };
             window_context.update_ipc_config(self.config.clone(), ipc_config);
         }
 
-        self.windows.insert(window_context.id(), window_context);
+        if self.on_battery {
+            window_context.set_on_battery(true);
+        }
+
+        if self.high_contrast {
+            window_context.set_system_high_contrast(true);
+        }
+
+        let window_id = window_context.id();
+        self.windows.insert(window_id, window_context);
+        Ok(window_id)
+    }
+
+    /// Move the focused pane of the window `source` into another window.
+    ///
+    /// Windows left without any panes are closed, while moving the only pane of a window into a
+    /// new window is ignored.
+    fn move_pane(
+        &mut self,
+        event_loop: &EventLoopWindowTarget<Event>,
+        proxy: EventLoopProxy<Event>,
+        scheduler: &mut Scheduler,
+        source: WindowId,
+        destination: PaneDestination,
+        direction: PaneDirection,
+    ) -> Result<(), Box<dyn Error>> {
+        let target = match destination {
+            PaneDestination::NewWindow => None,
+            PaneDestination::LastFocused => {
+                let mut history = self.focus_history.iter();
+                match history.find(|id| **id != source && self.windows.contains_key(id)) {
+                    Some(window_id) => Some(*window_id),
+                    None => return Ok(()),
+                }
+            },
+            PaneDestination::Window(window_id)
+                if window_id == source || !self.windows.contains_key(&window_id) =>
+            {
+                return Ok(())
+            },
+            PaneDestination::Window(window_id) => Some(window_id),
+        };
+
+        let window_context = match self.windows.get_mut(&source) {
+            Some(window_context) => window_context,
+            None => return Ok(()),
+        };
+
+        // Continue the cursor animation from its position in the old window.
+        let cursor = window_context.display.cursor;
+
+        let pane = match (window_context.take_pane(), target) {
+            (Some(pane), _) => pane,
+            (None, None) => return Ok(()),
+            // Merge the window into the target when its last pane is moved.
+            (None, Some(_)) => match self.windows.remove(&source) {
+                Some(window_context) => {
+                    scheduler.unschedule_window(source);
+                    self.focus_history.retain(|id| *id != source);
+                    window_context.into_pane()
+                },
+                None => return Ok(()),
+            },
+        };
+
+        let window_id = match target {
+            Some(window_id) => {
+                if let Some(window_context) = self.windows.get_mut(&window_id) {
+                    window_context.insert_pane(pane, direction);
+                }
+                window_id
+            },
+            None => {
+                // Ensure no context is current when creating the window, like `CreateWindow`.
+                for window_context in self.windows.values_mut() {
+                    window_context.display.make_not_current();
+                }
+
+                let options = WindowOptions::default();
+                self.create_window(event_loop, proxy, options, Some(pane))?
+            },
+        };
+
+        if let Some(window_context) = self.windows.get_mut(&window_id) {
+            window_context.display.cursor = cursor;
+        }
+
         Ok(())
     }
 
@@ -1459,7 +2676,7 @@ impl Processor {
     pub fn run(
         &mut self,
         event_loop: EventLoop<Event>,
-        initial_window_options: WindowOptions,
+        initial_window_options: Vec<WindowOptions>,
     ) -> Result<(), Box<dyn Error>> {
         let proxy = event_loop.create_proxy();
         let mut scheduler = Scheduler::new(proxy.clone());
@@ -1487,40 +2704,67 @@ impl Processor {
                     // Creating window inside event loop is required for platforms like macOS to
                     // properly initialize state, like tab management. Othwerwise the first
                     // window won't handle tabs.
-                    let initial_window_options = match initial_window_options.take() {
-                        Some(initial_window_options) => initial_window_options,
+                    let mut initial_window_options = match initial_window_options.take() {
+                        Some(initial_window_options) => initial_window_options.into_iter(),
                         None => return,
                     };
 
-                    if let Err(err) = self.create_initial_window(
-                        event_loop,
-                        proxy.clone(),
-                        initial_window_options,
-                    ) {
+                    // Wait for windows to be requested over IPC in daemon mode.
+                    if self.cli_options.daemon() {
+                        info!("Initialisation complete, waiting for windows");
+                        return;
+                    }
+
+                    let options = initial_window_options.next().unwrap_or_default();
+                    if let Err(err) = self.create_initial_window(event_loop, proxy.clone(), options)
+                    {
                         // Log the error right away since we can't return it.
                         eprintln!("Error: {}", err);
                         *control_flow = ControlFlow::ExitWithCode(1);
                         return;
                     }
 
+                    // Open the remaining windows of the startup layout.
+                    for options in initial_window_options {
+                        if let Err(err) =
+                            self.create_window(event_loop, proxy.clone(), options, None)
+                        {
+                            error!("Could not open window: {:?}", err);
+                        }
+                    }
+
                     info!("Initialisation complete");
                 },
                 // NOTE: This event bypasses batching to minimize input latency.
                 WinitEvent::UserEvent(Event {
                     window_id: Some(window_id),
                     payload: EventType::Terminal(TerminalEvent::Wakeup),
+                    ..
                 }) => {
                     if let Some(window_context) = self.windows.get_mut(&window_id) {
                         window_context.dirty = true;
                         if window_context.display.window.has_frame {
                             window_context.display.window.request_redraw();
                         }
+
+                        // Match the new output against plugins with the next batch of events.
+                        if window_context.plugins_watch_output() {
+                            let event = Event::new(TerminalEvent::Wakeup.into(), window_id);
+                            window_context.handle_event(
+                                event_loop,
+                                &proxy,
+                                &mut clipboard,
+                                &mut scheduler,
+                                event.into(),
+                            );
+                        }
                     }
                 },
                 // NOTE: This event bypasses batching to minimize input latency.
                 WinitEvent::UserEvent(Event {
                     window_id: Some(window_id),
                     payload: EventType::Frame,
+                    ..
                 }) => {
                     if let Some(window_context) = self.windows.get_mut(&window_id) {
                         window_context.display.window.has_frame = true;
@@ -1532,8 +2776,18 @@ impl Processor {
                 // Check for shutdown.
                 WinitEvent::UserEvent(Event {
                     window_id: Some(window_id),
+                    pane_id,
                     payload: EventType::Terminal(TerminalEvent::Exit),
                 }) => {
+                    // Keep the window open while it still has other panes.
+                    if let Some((window_context, pane_id)) =
+                        self.windows.get_mut(&window_id).zip(pane_id)
+                    {
+                        if window_context.close_pane(pane_id) {
+                            return;
+                        }
+                    }
+
                     // Remove the closed terminal.
                     let window_context = match self.windows.remove(&window_id) {
                         Some(window_context) => window_context,
@@ -1542,6 +2796,7 @@ impl Processor {
 
                     // Unschedule pending events.
                     scheduler.unschedule_window(window_context.id());
+                    self.focus_history.retain(|id| *id != window_id);
 
                     // Shutdown if no more terminals are open.
                     if self.windows.is_empty() {
@@ -1550,7 +2805,10 @@ impl Processor {
                             window_context.write_ref_test_results();
                         }
 
-                        *control_flow = ControlFlow::Exit;
+                        // Keep the daemon alive to host future windows.
+                        if !self.cli_options.daemon() {
+                            *control_flow = ControlFlow::Exit;
+                        }
                     }
                 },
                 WinitEvent::RedrawRequested(window_id) => {
@@ -1608,14 +2866,37 @@ impl Processor {
                         }
                     }
                 },
+                // Throttle animations of all windows on battery power.
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::PowerSourceChanged(on_battery),
+                    ..
+                }) => {
+                    self.on_battery = on_battery;
+
+                    for window_context in self.windows.values_mut() {
+                        window_context.set_on_battery(on_battery);
+                    }
+                },
+                // Follow the system's high contrast setting in all windows.
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::HighContrastChanged(high_contrast),
+                    ..
+                }) => {
+                    self.high_contrast = high_contrast;
+
+                    for window_context in self.windows.values_mut() {
+                        window_context.set_system_high_contrast(high_contrast);
+                    }
+                },
                 // Process IPC config update.
                 #[cfg(unix)]
                 WinitEvent::UserEvent(Event {
                     payload: EventType::IpcConfig(ipc_config),
                     window_id,
+                    ..
                 }) => {
                     // Persist global options for future windows.
-                    if window_id.is_none() {
+                    if window_id.is_none() && !ipc_config.pane {
                         if ipc_config.reset {
                             self.global_ipc_options.clear();
                         } else {
@@ -1631,6 +2912,78 @@ impl Processor {
                         window_context.update_ipc_config(self.config.clone(), ipc_config.clone());
                     }
                 },
+                // Report config options of the requested or focused window over IPC.
+                #[cfg(unix)]
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::IpcGetConfig(option, reply),
+                    window_id,
+                    ..
+                }) => {
+                    let window_context = match window_id {
+                        Some(window_id) => self.windows.get(&window_id),
+                        None => self.windows.values().find(|window| window.focused()),
+                    };
+                    if let Some(window_context) = window_context {
+                        let _ = reply.send(window_context.ipc_config_value(&option));
+                    }
+                },
+                // Report all windows over IPC.
+                #[cfg(unix)]
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::IpcListWindows(reply), ..
+                }) => {
+                    let windows = self.windows.values().map(WindowContext::ipc_window).collect();
+                    let _ = reply.send(windows);
+                },
+                // Process IPC search, history dumps, queries and input in the focused window.
+                #[cfg(unix)]
+                WinitEvent::UserEvent(
+                    event @ Event {
+                        payload:
+                            EventType::IpcSearch(_)
+                            | EventType::IpcDumpHistory(_)
+                            | EventType::IpcScreenshot(_)
+                            | EventType::IpcGetText(..)
+                            | EventType::IpcGetState(_)
+                            | EventType::IpcStats(_)
+                            | EventType::IpcSendText(_)
+                            | EventType::IpcBroadcast(_),
+                        window_id: None,
+                        ..
+                    },
+                ) => {
+                    let window_context =
+                        self.windows.values_mut().find(|window_context| window_context.focused());
+                    if let Some(window_context) = window_context {
+                        window_context.handle_event(
+                            event_loop,
+                            &proxy,
+                            &mut clipboard,
+                            &mut scheduler,
+                            event.into(),
+                        );
+                    }
+                },
+                // Mirror synchronized input to the other panes and windows of a broadcast.
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::BroadcastInput(bytes),
+                    window_id: Some(window_id),
+                    ..
+                }) => {
+                    let broadcast = match self.windows.get_mut(&window_id) {
+                        Some(window_context) => {
+                            window_context.write_broadcast_input(&bytes, false);
+                            window_context.display.broadcast.clone()
+                        },
+                        None => return,
+                    };
+
+                    for (_, window_context) in self.windows.iter_mut().filter(|(id, window)| {
+                        **id != window_id && window.display.broadcast.shares_group(&broadcast)
+                    }) {
+                        window_context.write_broadcast_input(&bytes, true);
+                    }
+                },
                 // Create a new terminal window.
                 WinitEvent::UserEvent(Event {
                     payload: EventType::CreateWindow(options), ..
@@ -1643,10 +2996,69 @@ impl Processor {
                         window_context.display.make_not_current();
                     }
 
-                    if let Err(err) = self.create_window(event_loop, proxy.clone(), options) {
+                    let options = *options;
+                    if let Err(err) = self.create_window(event_loop, proxy.clone(), options, None) {
                         error!("Could not open window: {:?}", err);
                     }
                 },
+                // Move panes between windows.
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::MovePane(destination, direction),
+                    window_id,
+                    ..
+                }) => {
+                    // Move the focused window's pane when no window was requested.
+                    let source = window_id.or_else(|| {
+                        self.windows.values().find(|window| window.focused()).map(WindowContext::id)
+                    });
+                    let source = match source {
+                        Some(source) => source,
+                        None => return,
+                    };
+
+                    if let Err(err) = self.move_pane(
+                        event_loop,
+                        proxy.clone(),
+                        &mut scheduler,
+                        source,
+                        destination,
+                        direction,
+                    ) {
+                        error!("Could not move pane: {:?}", err);
+                    }
+                },
+                // Show or hide dropdown windows.
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::ToggleDropdown,
+                    window_id,
+                    ..
+                }) => {
+                    let mut dropdowns = self
+                        .windows
+                        .values_mut()
+                        .filter(|window| window.display.window.is_dropdown())
+                        .filter(|window| window_id.map_or(true, |id| window.id() == id))
+                        .peekable();
+
+                    // Open a new dropdown window when there is none to toggle.
+                    if dropdowns.peek().is_none() && window_id.is_none() {
+                        for window_context in self.windows.values_mut() {
+                            window_context.display.make_not_current();
+                        }
+
+                        let options = WindowOptions { dropdown: true, ..WindowOptions::default() };
+                        if let Err(err) =
+                            self.create_window(event_loop, proxy.clone(), options, None)
+                        {
+                            error!("Could not open dropdown window: {:?}", err);
+                        }
+                        return;
+                    }
+
+                    for window_context in dropdowns {
+                        window_context.toggle_dropdown();
+                    }
+                },
                 // Process events affecting all windows.
                 WinitEvent::UserEvent(event @ Event { window_id: None, .. }) => {
                     for window_context in self.windows.values_mut() {
@@ -1662,6 +3074,13 @@ impl Processor {
                 // Process window-specific events.
                 WinitEvent::WindowEvent { window_id, .. }
                 | WinitEvent::UserEvent(Event { window_id: Some(window_id), .. }) => {
+                    // Remember the focus order, to attach panes to the last focused window.
+                    if let WinitEvent::WindowEvent { event: WindowEvent::Focused(true), .. } = event
+                    {
+                        self.focus_history.retain(|id| *id != window_id);
+                        self.focus_history.insert(0, window_id);
+                    }
+
                     if let Some(window_context) = self.windows.get_mut(&window_id) {
                         window_context.handle_event(
                             event_loop,
@@ -1705,22 +3124,70 @@ impl Processor {
 #[derive(Debug, Clone)]
 pub struct EventProxy {
     proxy: EventLoopProxy<Event>,
-    window_id: WindowId,
+
+    /// Window and pane receiving the events, shared by all clones to follow moved panes.
+    target: Arc<Mutex<(WindowId, PaneId)>>,
 }
 
 impl EventProxy {
-    pub fn new(proxy: EventLoopProxy<Event>, window_id: WindowId) -> Self {
-        Self { proxy, window_id }
+    pub fn new(proxy: EventLoopProxy<Event>, window_id: WindowId, pane_id: PaneId) -> Self {
+        Self { proxy, target: Arc::new(Mutex::new((window_id, pane_id))) }
+    }
+
+    /// Send all future events to a different pane.
+    pub fn retarget(&self, window_id: WindowId, pane_id: PaneId) {
+        *self.target.lock() = (window_id, pane_id);
     }
 
     /// Send an event to the event loop.
     pub fn send_event(&self, event: EventType) {
-        let _ = self.proxy.send_event(Event::new(event, self.window_id));
+        let (window_id, pane_id) = *self.target.lock();
+        let mut event = Event::new(event, window_id);
+        event.pane_id = Some(pane_id);
+        let _ = self.proxy.send_event(event);
     }
 }
 
 impl EventListener for EventProxy {
     fn send_event(&self, event: TerminalEvent) {
-        let _ = self.proxy.send_event(Event::new(event.into(), self.window_id));
+        EventProxy::send_event(self, event.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::term::test::mock_term;
+
+    #[test]
+    fn rank_matches_from_bottom() {
+        let term = mock_term("ab \r\nab \r\nab ");
+        let mut search_state = SearchState::default();
+        search_state.options.fuzzy = true;
+        search_state.dfas = RegexSearch::with_options("ab", search_state.options).ok();
+
+        search_state.rank_matches(&term, Some(2));
+        assert!(search_state.ranking_limited);
+        assert_eq!(search_state.ranked_matches, vec![
+            Point::new(Line(2), Column(0))..=Point::new(Line(2), Column(1)),
+            Point::new(Line(1), Column(0))..=Point::new(Line(1), Column(1)),
+        ]);
+
+        search_state.rank_matches(&term, None);
+        assert!(!search_state.ranking_limited);
+        assert_eq!(search_state.ranked_matches.len(), 3);
+    }
+
+    #[test]
+    fn substitute_captures_once() {
+        let captures = [Some(String::from("{1}")), Some(String::from("b")), None];
+        assert_eq!(substitute_captures("{0}-{1}-{2}", &captures), "{1}-b-");
+    }
+
+    #[test]
+    fn substitute_captures_ignores_invalid_placeholders() {
+        let captures = [Some(String::from("a"))];
+        assert_eq!(substitute_captures("{{0}} {1} {+0} {x", &captures), "{a} {1} {+0} {x");
     }
 }
@@ -12,6 +12,8 @@ use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::mem;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::{Duration, Instant};
 
 use log::debug;
@@ -21,39 +23,59 @@ use winit::event::{
     TouchPhase,
 };
 use winit::event_loop::EventLoopWindowTarget;
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", windows))]
 use winit::keyboard::ModifiersKeyState;
 use winit::keyboard::{Key, ModifiersState};
 #[cfg(target_os = "macos")]
 use winit::platform::macos::{EventLoopWindowTargetExtMacOS, OptionAsAlt};
 use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
+#[cfg(windows)]
+use winit::platform::scancode::KeyCodeExtScancode;
 use winit::window::CursorIcon;
+#[cfg(windows)]
+use {
+    windows_sys::Win32::System::Console::{
+        ENHANCED_KEY, LEFT_ALT_PRESSED, LEFT_CTRL_PRESSED, RIGHT_ALT_PRESSED, RIGHT_CTRL_PRESSED,
+        SHIFT_PRESSED,
+    },
+    windows_sys::Win32::UI::Input::KeyboardAndMouse::{MapVirtualKeyW, MAPVK_VSC_TO_VK_EX},
+};
 
 use alacritty_terminal::ansi::{ClearMode, Handler};
 use alacritty_terminal::event::EventListener;
 use alacritty_terminal::grid::{Dimensions, Scroll};
-use alacritty_terminal::index::{Boundary, Column, Direction, Point, Side};
+use alacritty_terminal::index::{Boundary, Column, Direction, Line, Point, Side};
+use alacritty_terminal::replay::ReplayControl;
 use alacritty_terminal::selection::SelectionType;
 use alacritty_terminal::term::search::Match;
 use alacritty_terminal::term::{ClipboardType, Term, TermMode};
-use alacritty_terminal::vi_mode::ViMotion;
+use alacritty_terminal::vi_mode::{ViMotion, ViOperator, ViPending, ViTextObject};
 
 use crate::clipboard::Clipboard;
 use crate::config::{
-    Action, BindingKey, BindingMode, MouseAction, SearchAction, UiConfig, ViAction,
+    Action, BindingKey, BindingMode, MouseAction, ReplayAction, SearchAction, UiConfig, ViAction,
 };
 use crate::display::hint::HintMatch;
+use crate::display::minimap::Minimap;
 use crate::display::window::Window;
 use crate::display::{Display, SizeInfo};
 use crate::event::{
-    ClickState, Event, EventType, Mouse, TouchPurpose, TouchZoom, TYPING_SEARCH_DELAY,
+    ClickState, Event, EventType, Mouse, PendingReleases, TouchPurpose, TouchZoom,
+    TYPING_SEARCH_DELAY,
 };
-use crate::message_bar::{self, Message};
+use crate::message_bar::{self, Message, MessageAction};
+use crate::pane::{PaneAction, PaneDestination, PaneDirection};
 use crate::scheduler::{Scheduler, TimerId, Topic};
 
 /// Font size change interval.
 pub const FONT_SIZE_STEP: f32 = 0.5;
 
+/// Seconds skipped when seeking through a replayed session.
+const REPLAY_SEEK_STEP: f64 = 5.;
+
+/// Factor by which the replay speed is changed.
+const REPLAY_SPEED_STEP: f64 = 2.;
+
 /// Interval for mouse scrolling during selection outside of the boundaries.
 const SELECTION_SCROLLING_INTERVAL: Duration = Duration::from_millis(15);
 
@@ -80,18 +102,31 @@ pub struct Processor<T: EventListener, A: ActionContext<T>> {
 
 pub trait ActionContext<T: EventListener> {
     fn write_to_pty<B: Into<Cow<'static, [u8]>>>(&self, _data: B) {}
+    fn write_input<B: Into<Cow<'static, [u8]>>>(&self, data: B) {
+        self.write_to_pty(data);
+    }
     fn mark_dirty(&mut self) {}
     fn size_info(&self) -> SizeInfo;
     fn copy_selection(&mut self, _ty: ClipboardType) {}
+    fn copy_selection_formatted(&mut self) {}
+    fn copy_selection_ansi(&mut self) {}
     fn start_selection(&mut self, _ty: SelectionType, _point: Point, _side: Side) {}
     fn toggle_selection(&mut self, _ty: SelectionType, _point: Point, _side: Side) {}
     fn update_selection(&mut self, _point: Point, _side: Side) {}
+    fn logical_point(&mut self, point: Point) -> Point {
+        point
+    }
+    fn vi_text_object(&mut self, _operator: ViOperator, _object: ViTextObject, _inner: bool) {}
+    fn copy_selection_to_register(&mut self, _name: char) {}
+    fn animate_jump(&mut self, _origin_offset: usize, _target: Match) {}
+    fn advance_jump_animation(&mut self) {}
     fn clear_selection(&mut self) {}
     fn selection_is_empty(&self) -> bool;
     fn mouse_mut(&mut self) -> &mut Mouse;
     fn mouse(&self) -> &Mouse;
     fn touch_purpose(&mut self) -> &mut TouchPurpose;
     fn modifiers(&mut self) -> &mut Modifiers;
+    fn pending_releases(&mut self) -> &mut PendingReleases;
     fn scroll(&mut self, _scroll: Scroll) {}
     fn window(&mut self) -> &mut Window;
     fn display(&mut self) -> &mut Display;
@@ -105,6 +140,7 @@ pub trait ActionContext<T: EventListener> {
     fn change_font_size(&mut self, _delta: f32) {}
     fn reset_font_size(&mut self) {}
     fn pop_message(&mut self) {}
+    fn message_action(&mut self, _action: MessageAction) {}
     fn message(&self) -> Option<&Message>;
     fn config(&self) -> &UiConfig;
     fn event_loop(&self) -> &EventLoopWindowTarget<Event>;
@@ -118,6 +154,10 @@ pub trait ActionContext<T: EventListener> {
     fn search_pop_word(&mut self) {}
     fn search_history_previous(&mut self) {}
     fn search_history_next(&mut self) {}
+    fn search_toggle_case_sensitivity(&mut self) {}
+    fn search_toggle_whole_word(&mut self) {}
+    fn search_toggle_scope(&mut self) {}
+    fn search_toggle_fuzzy(&mut self) {}
     fn search_next(&mut self, origin: Point, direction: Direction, side: Side) -> Option<Match>;
     fn advance_search_origin(&mut self, _direction: Direction) {}
     fn search_direction(&self) -> Direction;
@@ -129,7 +169,28 @@ pub trait ActionContext<T: EventListener> {
     fn expand_selection(&mut self) {}
     fn on_terminal_input_start(&mut self) {}
     fn paste(&mut self, _text: &str, _bracketed: bool) {}
-    fn spawn_daemon<I, S>(&self, _program: &str, _args: I)
+    fn dump_history(&mut self, _path: Option<PathBuf>, _ansi: bool, _timestamps: bool) {}
+    fn screenshot(&mut self, _path: Option<PathBuf>) {}
+    fn export_svg(&mut self) {}
+    fn open_history_in_pager(&mut self) {}
+    fn toggle_recording(&mut self) {}
+    fn run_script_action(&mut self, _name: &str) {}
+    fn replaying(&self) -> bool {
+        false
+    }
+    fn child_exited(&self) -> bool {
+        false
+    }
+    fn control_replay(&mut self, _control: ReplayControl) {}
+    fn pane_action(&mut self, _action: PaneAction) {}
+    fn move_pane(&mut self, _destination: PaneDestination) {}
+    fn close_window(&mut self) {}
+    fn toggle_broadcast_input(&mut self) {}
+    fn toggle_dropdown(&mut self) {}
+    fn working_directory(&self) -> Option<PathBuf> {
+        None
+    }
+    fn spawn_daemon<I, S>(&mut self, _program: &str, _args: I)
     where
         I: IntoIterator<Item = S> + Debug + Copy,
         S: AsRef<OsStr>,
@@ -138,6 +199,68 @@ pub trait ActionContext<T: EventListener> {
 }
 
 impl Action {
+    /// Move the vi mode cursor, animating the jump to its new position.
+    fn animate_vi_jump<T, A, F>(ctx: &mut A, jump: F)
+    where
+        A: ActionContext<T>,
+        T: EventListener,
+        F: FnOnce(&mut Term<T>),
+    {
+        let origin_offset = ctx.terminal().grid().display_offset();
+        let origin = ctx.terminal().vi_mode_cursor.point;
+
+        jump(ctx.terminal_mut());
+
+        let point = ctx.terminal().vi_mode_cursor.point;
+        if point != origin {
+            ctx.animate_jump(origin_offset, point..=point);
+        }
+        ctx.mark_dirty();
+    }
+
+    /// Scroll to the closest shell prompt in the specified direction.
+    fn scroll_to_prompt<T, A>(ctx: &mut A, direction: Direction)
+    where
+        A: ActionContext<T>,
+        T: EventListener,
+    {
+        let term = ctx.terminal();
+        let vi_mode = term.mode().contains(TermMode::VI);
+        let origin = if vi_mode {
+            term.vi_mode_cursor.point.line
+        } else {
+            Line(-(term.grid().display_offset() as i32))
+        };
+
+        let prompt = match term.prompt_line(origin, direction) {
+            Some(prompt) => prompt,
+            None => return,
+        };
+
+        if vi_mode {
+            let point = Point::new(prompt, Column(0));
+            Self::animate_vi_jump(ctx, |term| term.vi_jump_to_point(point));
+        } else {
+            // Move the prompt to the top of the viewport.
+            ctx.scroll(Scroll::Delta((origin - prompt).0));
+        }
+    }
+
+    fn jump_to_bookmark<T, A>(ctx: &mut A, direction: Direction)
+    where
+        A: ActionContext<T>,
+        T: EventListener,
+    {
+        let term = ctx.terminal();
+        let line = match term.bookmark_line(term.vi_mode_cursor.point.line, direction) {
+            Some(line) => line,
+            None => return,
+        };
+
+        let point = Point::new(line, Column(0));
+        Self::animate_vi_jump(ctx, |term| term.vi_jump_to_point(point));
+    }
+
     fn toggle_selection<T, A>(ctx: &mut A, ty: SelectionType)
     where
         A: ActionContext<T>,
@@ -162,8 +285,10 @@ impl<T: EventListener> Execute<T> for Action {
         match self {
             Action::Esc(s) => ctx.paste(s, false),
             Action::Command(program) => ctx.spawn_daemon(program.program(), program.args()),
+            Action::Script(name) => ctx.run_script_action(name),
             Action::Hint(hint) => {
-                ctx.display().hint_state.start(hint.clone());
+                let working_directory = hint.content.paths.then(|| ctx.working_directory());
+                ctx.display().hint_state.start(hint.clone(), working_directory.flatten());
                 ctx.mark_dirty();
             },
             Action::ToggleViMode => {
@@ -211,8 +336,10 @@ impl<T: EventListener> Execute<T> for Action {
                     Direction::Left => vi_point.sub(terminal, Boundary::None, 1),
                 };
 
+                let origin_offset = ctx.terminal().grid().display_offset();
                 if let Some(regex_match) = ctx.search_next(origin, direction, Side::Left) {
-                    ctx.terminal_mut().vi_goto_point(*regex_match.start());
+                    ctx.terminal_mut().vi_jump_to_point(*regex_match.start());
+                    ctx.animate_jump(origin_offset, regex_match);
                     ctx.mark_dirty();
                 }
             },
@@ -227,8 +354,10 @@ impl<T: EventListener> Execute<T> for Action {
                     Direction::Left => vi_point.sub(terminal, Boundary::None, 1),
                 };
 
+                let origin_offset = ctx.terminal().grid().display_offset();
                 if let Some(regex_match) = ctx.search_next(origin, direction, Side::Left) {
-                    ctx.terminal_mut().vi_goto_point(*regex_match.start());
+                    ctx.terminal_mut().vi_jump_to_point(*regex_match.start());
+                    ctx.animate_jump(origin_offset, regex_match);
                     ctx.mark_dirty();
                 }
             },
@@ -236,8 +365,10 @@ impl<T: EventListener> Execute<T> for Action {
                 let terminal = ctx.terminal();
                 let origin = terminal.vi_mode_cursor.point.sub(terminal, Boundary::None, 1);
 
+                let origin_offset = terminal.grid().display_offset();
                 if let Some(regex_match) = ctx.search_next(origin, Direction::Left, Side::Left) {
-                    ctx.terminal_mut().vi_goto_point(*regex_match.start());
+                    ctx.terminal_mut().vi_jump_to_point(*regex_match.start());
+                    ctx.animate_jump(origin_offset, regex_match);
                     ctx.mark_dirty();
                 }
             },
@@ -245,11 +376,52 @@ impl<T: EventListener> Execute<T> for Action {
                 let terminal = ctx.terminal();
                 let origin = terminal.vi_mode_cursor.point.add(terminal, Boundary::None, 1);
 
+                let origin_offset = terminal.grid().display_offset();
                 if let Some(regex_match) = ctx.search_next(origin, Direction::Right, Side::Right) {
-                    ctx.terminal_mut().vi_goto_point(*regex_match.end());
+                    ctx.terminal_mut().vi_jump_to_point(*regex_match.end());
+                    ctx.animate_jump(origin_offset, regex_match);
                     ctx.mark_dirty();
                 }
             },
+            Action::Vi(ViAction::JumpOlder) => {
+                Self::animate_vi_jump(ctx, |terminal| terminal.vi_jump(Direction::Left));
+            },
+            Action::Vi(ViAction::JumpNewer) => {
+                Self::animate_vi_jump(ctx, |terminal| terminal.vi_jump(Direction::Right));
+            },
+            Action::Vi(ViAction::ToggleBookmark) => {
+                let terminal = ctx.terminal_mut();
+                let line = terminal.vi_mode_cursor.point.line;
+                terminal.toggle_bookmark(line);
+                ctx.mark_dirty();
+            },
+            Action::Vi(ViAction::PreviousBookmark) => Self::jump_to_bookmark(ctx, Direction::Left),
+            Action::Vi(ViAction::NextBookmark) => Self::jump_to_bookmark(ctx, Direction::Right),
+            Action::Vi(ViAction::Yank) if ctx.terminal().selection.is_none() => {
+                let operator = ViOperator::Yank { register: None };
+                let pending = ViPending::TextObject { operator, inner: None };
+                ctx.terminal_mut().vi_mode_cursor.pending = Some(pending);
+            },
+            Action::Vi(action @ (ViAction::SelectInner | ViAction::SelectAround))
+                if ctx.terminal().selection.is_some() =>
+            {
+                let inner = Some(*action == ViAction::SelectInner);
+                let pending = ViPending::TextObject { operator: ViOperator::Select, inner };
+                ctx.terminal_mut().vi_mode_cursor.pending = Some(pending);
+            },
+            // Text objects are only copied without, and selected with an active selection.
+            Action::Vi(ViAction::Yank | ViAction::SelectInner | ViAction::SelectAround) => (),
+            Action::Vi(ViAction::SetMark) => {
+                ctx.terminal_mut().vi_mode_cursor.pending = Some(ViPending::SetMark);
+            },
+            Action::Vi(action @ (ViAction::GotoMark | ViAction::GotoMarkLine)) => {
+                let pending = ViPending::GotoMark { line: *action == ViAction::GotoMarkLine };
+                ctx.terminal_mut().vi_mode_cursor.pending = Some(pending);
+            },
+            Action::Vi(ViAction::Register) => {
+                let pending = ViPending::Register { name: None };
+                ctx.terminal_mut().vi_mode_cursor.pending = Some(pending);
+            },
             Action::Vi(ViAction::CenterAroundViCursor) => {
                 let term = ctx.terminal();
                 let display_offset = term.grid().display_offset() as i32;
@@ -279,10 +451,33 @@ impl<T: EventListener> Execute<T> for Action {
             Action::Search(SearchAction::SearchDeleteWord) => ctx.search_pop_word(),
             Action::Search(SearchAction::SearchHistoryPrevious) => ctx.search_history_previous(),
             Action::Search(SearchAction::SearchHistoryNext) => ctx.search_history_next(),
+            Action::Search(SearchAction::SearchToggleCaseSensitivity) => {
+                ctx.search_toggle_case_sensitivity()
+            },
+            Action::Search(SearchAction::SearchToggleWholeWord) => ctx.search_toggle_whole_word(),
+            Action::Search(SearchAction::SearchToggleScope) => ctx.search_toggle_scope(),
+            Action::Search(SearchAction::SearchToggleFuzzy) => ctx.search_toggle_fuzzy(),
             Action::Mouse(MouseAction::ExpandSelection) => ctx.expand_selection(),
+            action @ Action::Replay(_) if !ctx.replaying() => {
+                debug!("Ignoring {action:?}: No session is being replayed");
+            },
+            Action::Replay(action) => {
+                let control = match action {
+                    ReplayAction::ReplayTogglePause => ReplayControl::TogglePause,
+                    ReplayAction::ReplaySeekBackward => ReplayControl::Seek(-REPLAY_SEEK_STEP),
+                    ReplayAction::ReplaySeekForward => ReplayControl::Seek(REPLAY_SEEK_STEP),
+                    ReplayAction::ReplaySpeedUp => ReplayControl::ScaleSpeed(REPLAY_SPEED_STEP),
+                    ReplayAction::ReplaySlowDown => {
+                        ReplayControl::ScaleSpeed(1. / REPLAY_SPEED_STEP)
+                    },
+                };
+                ctx.control_replay(control);
+            },
             Action::SearchForward => ctx.start_search(Direction::Right),
             Action::SearchBackward => ctx.start_search(Direction::Left),
             Action::Copy => ctx.copy_selection(ClipboardType::Clipboard),
+            Action::CopyFormatted => ctx.copy_selection_formatted(),
+            Action::CopyAnsi => ctx.copy_selection_ansi(),
             #[cfg(not(any(target_os = "macos", windows)))]
             Action::CopySelection => ctx.copy_selection(ClipboardType::Selection),
             Action::ClearSelection => ctx.clear_selection(),
@@ -294,8 +489,15 @@ impl<T: EventListener> Execute<T> for Action {
                 let text = ctx.clipboard_mut().load(ClipboardType::Selection);
                 ctx.paste(&text, true);
             },
+            Action::ClipboardHistory => {
+                let entries: Vec<_> = ctx.clipboard_mut().history().iter().cloned().collect();
+                ctx.display().clipboard_picker.start(entries);
+                ctx.mark_dirty();
+            },
             Action::ToggleFullscreen => ctx.window().toggle_fullscreen(),
             Action::ToggleMaximized => ctx.window().toggle_maximized(),
+            Action::ToggleAlwaysOnTop => ctx.window().toggle_always_on_top(),
+            Action::ToggleSticky => ctx.window().toggle_sticky(),
             #[cfg(target_os = "macos")]
             Action::ToggleSimpleFullscreen => ctx.window().toggle_simple_fullscreen(),
             #[cfg(target_os = "macos")]
@@ -305,7 +507,7 @@ impl<T: EventListener> Execute<T> for Action {
             #[cfg(not(target_os = "macos"))]
             Action::Hide => ctx.window().set_visible(false),
             Action::Minimize => ctx.window().set_minimized(true),
-            Action::Quit => ctx.terminal_mut().exit(),
+            Action::Quit => ctx.close_window(),
             Action::IncreaseFontSize => ctx.change_font_size(FONT_SIZE_STEP),
             Action::DecreaseFontSize => ctx.change_font_size(FONT_SIZE_STEP * -1.),
             Action::ResetFontSize => ctx.reset_font_size(),
@@ -364,8 +566,93 @@ impl<T: EventListener> Execute<T> for Action {
                 term.vi_motion(ViMotion::FirstOccupied);
                 ctx.mark_dirty();
             },
+            Action::ScrollToPreviousPrompt => Self::scroll_to_prompt(ctx, Direction::Left),
+            Action::ScrollToNextPrompt => Self::scroll_to_prompt(ctx, Direction::Right),
+            Action::SelectLastOutput => {
+                let output = match ctx.terminal().command_outputs(1).pop() {
+                    Some(output) => output,
+                    None => return,
+                };
+
+                ctx.start_selection(SelectionType::Simple, *output.start(), Side::Left);
+                ctx.update_selection(*output.end(), Side::Right);
+                ctx.copy_selection(ClipboardType::Selection);
+
+                let term = ctx.terminal_mut();
+                if term.mode().contains(TermMode::VI) {
+                    term.vi_mode_cursor.point = *output.end();
+                }
+                term.scroll_to_point(*output.start());
+                ctx.mark_dirty();
+            },
             Action::ClearHistory => ctx.terminal_mut().clear_screen(ClearMode::Saved),
+            Action::ClearScreenAndHistory => {
+                let terminal = ctx.terminal_mut();
+                let line = terminal.grid().cursor.point.line;
+                terminal.clear_above(line);
+            },
+            Action::ClearHistoryAbovePrompt => {
+                let terminal = ctx.terminal_mut();
+                let line = terminal.grid().cursor.point.line;
+                if let Some(prompt) = terminal.prompt_line(line + 1, Direction::Left) {
+                    terminal.clear_above(prompt);
+                }
+            },
+            Action::DumpHistory => {
+                let timestamps = ctx.display().show_timestamps;
+                ctx.dump_history(None, false, timestamps);
+            },
+            Action::DumpHistoryAnsi => {
+                let timestamps = ctx.display().show_timestamps;
+                ctx.dump_history(None, true, timestamps);
+            },
+            Action::ToggleHighlight(index) => {
+                ctx.display().highlights.toggle(*index);
+                ctx.terminal_mut().mark_fully_damaged();
+                ctx.mark_dirty();
+            },
+            Action::ToggleTimestamps => {
+                let display = ctx.display();
+                display.show_timestamps = !display.show_timestamps;
+                ctx.terminal_mut().mark_fully_damaged();
+                ctx.mark_dirty();
+            },
+            Action::CycleColorFilter => {
+                let display = ctx.display();
+                display.color_filter = display.color_filter.next();
+                ctx.terminal_mut().mark_fully_damaged();
+                ctx.mark_dirty();
+            },
+            Action::OpenHistoryInPager => ctx.open_history_in_pager(),
+            Action::Screenshot => ctx.screenshot(None),
+            Action::ExportSvg => ctx.export_svg(),
+            Action::ToggleRecording => ctx.toggle_recording(),
+            Action::SplitRight => {
+                let working_directory = ctx.working_directory();
+                ctx.pane_action(PaneAction::Split(PaneDirection::Right, working_directory));
+            },
+            Action::SplitDown => {
+                let working_directory = ctx.working_directory();
+                ctx.pane_action(PaneAction::Split(PaneDirection::Down, working_directory));
+            },
+            Action::FocusPaneLeft => ctx.pane_action(PaneAction::Focus(PaneDirection::Left)),
+            Action::FocusPaneRight => ctx.pane_action(PaneAction::Focus(PaneDirection::Right)),
+            Action::FocusPaneUp => ctx.pane_action(PaneAction::Focus(PaneDirection::Up)),
+            Action::FocusPaneDown => ctx.pane_action(PaneAction::Focus(PaneDirection::Down)),
+            Action::ResizePaneLeft => ctx.pane_action(PaneAction::Resize(PaneDirection::Left)),
+            Action::ResizePaneRight => ctx.pane_action(PaneAction::Resize(PaneDirection::Right)),
+            Action::ResizePaneUp => ctx.pane_action(PaneAction::Resize(PaneDirection::Up)),
+            Action::ResizePaneDown => ctx.pane_action(PaneAction::Resize(PaneDirection::Down)),
+            Action::ClosePane => ctx.pane_action(PaneAction::Close),
+            Action::DetachPane => ctx.move_pane(PaneDestination::NewWindow),
+            Action::AttachPane => ctx.move_pane(PaneDestination::LastFocused),
+            Action::PaneConfig(options) => ctx.pane_action(PaneAction::Config(options.clone())),
+            Action::ResetPaneConfig => ctx.pane_action(PaneAction::ResetConfig),
+            Action::ToggleBroadcastInput => ctx.toggle_broadcast_input(),
+            Action::ToggleDropdown => ctx.toggle_dropdown(),
             Action::ClearLogNotice => ctx.pop_message(),
+            Action::ClearAllLogNotices => ctx.message_action(MessageAction::DismissAll),
+            Action::OpenConfig => ctx.message_action(MessageAction::OpenConfig),
             #[cfg(not(target_os = "macos"))]
             Action::CreateNewWindow => ctx.create_new_window(),
             Action::SpawnNewInstance => ctx.spawn_new_instance(),
@@ -416,6 +703,12 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
         let (x, y) = position.into();
 
+        // Keep following the mouse while the minimap is dragged.
+        if self.ctx.mouse().minimap_drag {
+            self.minimap_scroll(y as f32);
+            return;
+        }
+
         let lmb_pressed = self.ctx.mouse().left_button_state == ElementState::Pressed;
         let rmb_pressed = self.ctx.mouse().right_button_state == ElementState::Pressed;
         if !self.ctx.selection_is_empty() && (lmb_pressed || rmb_pressed) {
@@ -424,6 +717,7 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
         let display_offset = self.ctx.terminal().grid().display_offset();
         let old_point = self.ctx.mouse().point(&size_info, display_offset);
+        let old_position = (self.ctx.mouse().x, self.ctx.mouse().y);
 
         let x = x.clamp(0, size_info.width() as i32 - 1) as usize;
         let y = y.clamp(0, size_info.height() as i32 - 1) as usize;
@@ -434,7 +728,10 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         let cell_side = self.cell_side(x);
 
         let point = self.ctx.mouse().point(&size_info, display_offset);
-        let cell_changed = old_point != point;
+
+        // Pixel mouse reports are sent for movement within a cell too.
+        let pixel_mode = self.ctx.terminal().mode().contains(TermMode::SGR_PIXELS_MOUSE);
+        let cell_changed = old_point != point || (pixel_mode && old_position != (x, y));
 
         // If the mouse hasn't changed cells, do nothing.
         if !cell_changed
@@ -460,6 +757,8 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         if (lmb_pressed || rmb_pressed)
             && (self.ctx.modifiers().state().shift_key() || !self.ctx.mouse_mode())
         {
+            // Select the cell displayed below the mouse, even inside of right-to-left text.
+            let point = self.ctx.logical_point(point);
             self.ctx.update_selection(point, cell_side);
         } else if cell_changed
             && self.ctx.terminal().mode().intersects(TermMode::MOUSE_MOTION | TermMode::MOUSE_DRAG)
@@ -521,7 +820,9 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         }
 
         // Report mouse events.
-        if self.ctx.terminal().mode().contains(TermMode::SGR_MOUSE) {
+        if self.ctx.terminal().mode().contains(TermMode::SGR_PIXELS_MOUSE) {
+            self.sgr_pixels_mouse_report(button + mods, state);
+        } else if self.ctx.terminal().mode().contains(TermMode::SGR_MOUSE) {
             self.sgr_mouse_report(point, button + mods, state);
         } else if let ElementState::Released = state {
             self.normal_mouse_report(point, 3 + mods);
@@ -574,6 +875,25 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         self.ctx.write_to_pty(msg.into_bytes());
     }
 
+    /// Report the mouse position in physical pixels, relative to the text area.
+    ///
+    /// This matches the text area size reported by `CSI 14 t`, regardless of the scale factor.
+    fn sgr_pixels_mouse_report(&mut self, button: u8, state: ElementState) {
+        let c = match state {
+            ElementState::Pressed => 'M',
+            ElementState::Released => 'm',
+        };
+
+        let size_info = self.ctx.size_info();
+        let x = self.ctx.mouse().x.saturating_sub(size_info.padding_x() as usize);
+        let y = self.ctx.mouse().y.saturating_sub(size_info.padding_y() as usize);
+        let x = x.min(size_info.columns() * size_info.cell_width() as usize - 1);
+        let y = y.min(size_info.screen_lines() * size_info.cell_height() as usize - 1);
+
+        let msg = format!("\x1b[<{};{};{}{}", button, x + 1, y + 1, c);
+        self.ctx.write_to_pty(msg.into_bytes());
+    }
+
     fn on_mouse_press(&mut self, button: MouseButton) {
         // Handle mouse mode.
         if !self.ctx.modifiers().state().shift_key() && self.ctx.mouse_mode() {
@@ -609,6 +929,7 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             // Load mouse point, treating message bar and padding as the closest cell.
             let display_offset = self.ctx.terminal().grid().display_offset();
             let point = self.ctx.mouse().point(&self.ctx.size_info(), display_offset);
+            let point = self.ctx.logical_point(point);
 
             if let MouseButton::Left = button {
                 self.on_left_click(point)
@@ -937,6 +1258,17 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             _ => (),
         }
 
+        // Scroll to the lines below the mouse when the minimap is clicked.
+        if button == MouseButton::Left {
+            let minimap_drag = state == ElementState::Pressed && self.minimap_hit();
+            self.ctx.mouse_mut().minimap_drag = minimap_drag;
+            if minimap_drag {
+                let y = self.ctx.mouse().y as f32;
+                self.minimap_scroll(y);
+                return;
+            }
+        }
+
         // Skip normal mouse events if the message bar has been clicked.
         if self.message_bar_cursor_state() == Some(CursorIcon::Pointer)
             && state == ElementState::Pressed
@@ -946,7 +1278,10 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             let current_lines = self.ctx.message().map_or(0, |m| m.text(&size).len());
 
             self.ctx.clear_selection();
-            self.ctx.pop_message();
+            match self.message_bar_action() {
+                Some(action) => self.ctx.message_action(action),
+                None => self.ctx.pop_message(),
+            }
 
             // Reset cursor when message bar height changed or all messages are gone.
             let new_lines = self.ctx.message().map_or(0, |m| m.text(&size).len());
@@ -969,9 +1304,12 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
                 ElementState::Pressed => {
                     // Process mouse press before bindings to update the `click_state`.
                     self.on_mouse_press(button);
-                    self.process_mouse_bindings(button);
+                    self.process_mouse_bindings(button, state);
+                },
+                ElementState::Released => {
+                    self.on_mouse_release(button);
+                    self.process_mouse_bindings(button, state);
                 },
-                ElementState::Released => self.on_mouse_release(button),
             }
         }
     }
@@ -980,11 +1318,29 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
     ///
     /// The provided mode, mods, and key must match what is allowed by a binding
     /// for its action to be executed.
-    fn process_mouse_bindings(&mut self, button: MouseButton) {
-        let mode = BindingMode::new(self.ctx.terminal().mode(), self.ctx.search_active());
+    fn process_mouse_bindings(&mut self, button: MouseButton, state: ElementState) {
+        // Button releases only run the release bindings which consumed the matching press.
+        if state == ElementState::Released {
+            let pending = &mut self.ctx.pending_releases().buttons;
+            let (actions, remaining): (Vec<_>, _) =
+                mem::take(pending).into_iter().partition(|(b, _)| *b == button);
+            *pending = remaining;
+
+            for (_, action) in actions {
+                action.execute(&mut self.ctx);
+            }
+            return;
+        }
+
+        let mode = BindingMode::new(
+            self.ctx.terminal().mode(),
+            self.ctx.search_active(),
+            self.ctx.replaying(),
+        );
         let mouse_mode = self.ctx.mouse_mode();
         let mods = self.ctx.modifiers().state();
 
+        let mut triggered = Vec::new();
         for i in 0..self.ctx.config().mouse_bindings().len() {
             let mut binding = self.ctx.config().mouse_bindings()[i].clone();
 
@@ -994,15 +1350,43 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             }
 
             if binding.is_triggered_by(mode, mods, &button) {
-                binding.action.execute(&mut self.ctx);
+                triggered.push(binding);
             }
         }
+
+        // Release bindings consume the press, running their action once the button is released.
+        if triggered.iter().any(|binding| binding.release) {
+            let pending = &mut self.ctx.pending_releases().buttons;
+            pending.retain(|(b, _)| *b != button);
+            let releases = triggered.into_iter().filter(|binding| binding.release);
+            pending.extend(releases.map(|binding| (button, binding.action)));
+            return;
+        }
+
+        for binding in triggered {
+            binding.action.execute(&mut self.ctx);
+        }
     }
 
     /// Process key input.
     pub fn key_input(&mut self, key: KeyEvent) {
         // IME input will be applied on commit and shouldn't trigger key bindings.
-        if key.state == ElementState::Released || self.ctx.display().ime.preedit().is_some() {
+        if self.ctx.display().ime.preedit().is_some() {
+            return;
+        }
+
+        if key.state == ElementState::Released {
+            // Release bindings consume the key release, like they consumed the press.
+            if self.process_key_bindings(&key) {
+                return;
+            }
+
+            // Key releases are only reported in ConPTY's win32-input-mode.
+            #[cfg(windows)]
+            if self.win32_input_active() {
+                self.win32_key_input(&key);
+            }
+
             return;
         }
 
@@ -1016,6 +1400,33 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             return;
         }
 
+        // All key bindings are disabled while picking from the clipboard history.
+        if self.ctx.display().clipboard_picker.active() {
+            let picker = &mut self.ctx.display().clipboard_picker;
+            if let Some(entry) = text.chars().next().and_then(|c| picker.keyboard_input(c)) {
+                self.ctx.paste(&entry, true);
+            }
+            self.ctx.mark_dirty();
+            return;
+        }
+
+        // All key bindings are disabled while asking for clipboard access.
+        if self.ctx.display().clipboard_prompt.active() {
+            let prompt = &mut self.ctx.display().clipboard_prompt;
+            if let Some((ty, format)) = text.chars().next().and_then(|c| prompt.keyboard_input(c)) {
+                let text = format(self.ctx.clipboard_mut().load(ty).as_str());
+                self.ctx.write_to_pty(text.into_bytes());
+            }
+            self.ctx.mark_dirty();
+            return;
+        }
+
+        // Close held terminals once their child process has exited.
+        if self.ctx.child_exited() && matches!(key.logical_key, Key::Enter | Key::Escape) {
+            self.ctx.terminal_mut().exit();
+            return;
+        }
+
         // Reset search delay when the user is still typing.
         if self.ctx.search_active() {
             let timer_id = TimerId::new(Topic::DelayedSearch, self.ctx.window().id());
@@ -1025,6 +1436,14 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             }
         }
 
+        // Pending vi mode operators consume their text object before any key bindings.
+        if self.ctx.terminal().mode().contains(TermMode::VI)
+            && !self.ctx.search_active()
+            && self.vi_operator_input(text)
+        {
+            return;
+        }
+
         // Key bindings suppress the character input.
         if self.process_key_bindings(&key) {
             return;
@@ -1038,6 +1457,13 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             return;
         }
 
+        // Report keys with full fidelity, including the ones without any text.
+        #[cfg(windows)]
+        if self.win32_input_active() && self.win32_key_input(&key) {
+            self.ctx.on_terminal_input_start();
+            return;
+        }
+
         // Vi mode on its own doesn't have any input, the search input was done before.
         if self.ctx.terminal().mode().contains(TermMode::VI) || text.is_empty() {
             return;
@@ -1051,7 +1477,140 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         }
         bytes.extend_from_slice(text.as_bytes());
 
+        self.ctx.write_input(bytes);
+    }
+
+    /// Check if key events are reported in ConPTY's win32-input-mode.
+    #[cfg(windows)]
+    fn win32_input_active(&self) -> bool {
+        let mode = self.ctx.terminal().mode();
+        mode.contains(TermMode::WIN32_INPUT)
+            && !mode.contains(TermMode::VI)
+            && !self.ctx.search_active()
+    }
+
+    /// Report a key event as Win32 `INPUT_RECORD` in ConPTY's win32-input-mode.
+    ///
+    /// Returns `false` if the key has no scancode.
+    #[cfg(windows)]
+    fn win32_key_input(&mut self, key: &KeyEvent) -> bool {
+        let scancode = match key.physical_key.to_scancode() {
+            Some(scancode) => scancode,
+            None => return false,
+        };
+        let virtual_key = unsafe { MapVirtualKeyW(scancode, MAPVK_VSC_TO_VK_EX) };
+
+        let modifiers = self.ctx.modifiers();
+        let mut control_state = 0;
+        for (state, flag) in [
+            (modifiers.ralt_state(), RIGHT_ALT_PRESSED),
+            (modifiers.lalt_state(), LEFT_ALT_PRESSED),
+            (modifiers.rcontrol_state(), RIGHT_CTRL_PRESSED),
+            (modifiers.lcontrol_state(), LEFT_CTRL_PRESSED),
+        ] {
+            if state == ModifiersKeyState::Pressed {
+                control_state |= flag;
+            }
+        }
+        if modifiers.state().shift_key() {
+            control_state |= SHIFT_PRESSED;
+        }
+        if scancode & 0xe000 == 0xe000 {
+            control_state |= ENHANCED_KEY;
+        }
+
+        let key_down = u8::from(key.state == ElementState::Pressed);
+        let text = if key_down == 1 { key.text_with_all_modifiers() } else { None };
+
+        // Characters outside of the BMP are reported as one record per UTF-16 code unit.
+        let mut units: Vec<u16> = text.unwrap_or_default().encode_utf16().collect();
+        if units.is_empty() {
+            units.push(0);
+        }
+
+        let mut bytes = Vec::new();
+        for unit in units {
+            let record = format!(
+                "\x1b[{};{};{};{};{};1_",
+                virtual_key,
+                scancode & 0xff,
+                unit,
+                key_down,
+                control_state
+            );
+            bytes.extend_from_slice(record.as_bytes());
+        }
         self.ctx.write_to_pty(bytes);
+
+        true
+    }
+
+    /// Continue multi-key vi mode commands started by a binding, like `yi"`, `vap`, `ma` or `"ay`.
+    ///
+    /// Returns `true` when the input was consumed.
+    fn vi_operator_input(&mut self, text: &str) -> bool {
+        // Modifiers pressed to type the next character don't cancel the pending command.
+        if text.is_empty() {
+            return false;
+        }
+
+        let pending = self.ctx.terminal_mut().vi_mode_cursor.pending.take();
+        let has_selection = self.ctx.terminal().selection.is_some();
+
+        // Operators and text objects are only triggered by plain characters.
+        let mods = self.ctx.modifiers().state();
+        let mut chars = text.chars();
+        let c = match (chars.next(), chars.next()) {
+            (Some(c), None) if !mods.intersects(ModifiersState::CONTROL | ModifiersState::ALT) => c,
+            _ => return pending.is_some(),
+        };
+
+        let pending = match (pending, c) {
+            (Some(ViPending::TextObject { operator, inner: Some(inner) }), c) => {
+                if let Some(object) = ViTextObject::from_char(c) {
+                    self.ctx.vi_text_object(operator, object, inner);
+                }
+                return true;
+            },
+            (Some(ViPending::TextObject { operator, inner: None }), 'i' | 'a') => {
+                ViPending::TextObject { operator, inner: Some(c == 'i') }
+            },
+            (Some(ViPending::SetMark), c) => {
+                let terminal = self.ctx.terminal_mut();
+                let point = terminal.vi_mode_cursor.point;
+                terminal.vi_marks.set(c, point);
+                return true;
+            },
+            (Some(ViPending::GotoMark { line }), c) => {
+                Action::animate_vi_jump(&mut self.ctx, |terminal| terminal.vi_goto_mark(c, line));
+                return true;
+            },
+            (Some(ViPending::Register { name: None }), c) if c.is_ascii_lowercase() => {
+                ViPending::Register { name: Some(c) }
+            },
+            (Some(ViPending::Register { name: Some(name) }), 'y') if has_selection => {
+                self.ctx.copy_selection_to_register(name);
+                self.ctx.clear_selection();
+                return true;
+            },
+            (Some(ViPending::Register { name: Some(name) }), 'y') => ViPending::TextObject {
+                operator: ViOperator::Yank { register: Some(name) },
+                inner: None,
+            },
+            (Some(ViPending::Register { name: Some(name) }), 'p') => {
+                let text = self.ctx.clipboard_mut().load_register(name).map(str::to_owned);
+                if let Some(text) = text {
+                    self.ctx.paste(&text, true);
+                }
+                return true;
+            },
+            // Cancel pending commands on any other input.
+            (pending, _) => return pending.is_some(),
+        };
+
+        self.ctx.terminal_mut().vi_mode_cursor.pending = Some(pending);
+
+        true
     }
 
     /// Whether we should send `ESC` due to `Alt` being pressed.
@@ -1063,28 +1622,63 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
     #[cfg(target_os = "macos")]
     fn alt_send_esc(&mut self) -> bool {
         let option_as_alt = self.ctx.config().window.option_as_alt();
-        self.ctx.modifiers().state().alt_key()
-            && (option_as_alt == OptionAsAlt::Both
-                || (option_as_alt == OptionAsAlt::OnlyLeft
-                    && self.ctx.modifiers().lalt_state() == ModifiersKeyState::Pressed)
-                || (option_as_alt == OptionAsAlt::OnlyRight
-                    && self.ctx.modifiers().ralt_state() == ModifiersKeyState::Pressed))
+        self.ctx.modifiers().state().alt_key() && self.option_is_alt(option_as_alt)
+    }
+
+    /// Check if the pressed `Option` keys are treated as `Alt`.
+    #[cfg(target_os = "macos")]
+    fn option_is_alt(&self, option_as_alt: OptionAsAlt) -> bool {
+        let modifiers = self.ctx.modifiers();
+        option_as_alt == OptionAsAlt::Both
+            || (option_as_alt == OptionAsAlt::OnlyLeft
+                && modifiers.lalt_state() == ModifiersKeyState::Pressed)
+            || (option_as_alt == OptionAsAlt::OnlyRight
+                && modifiers.ralt_state() == ModifiersKeyState::Pressed)
     }
 
     /// Attempt to find a binding and execute its action.
     ///
     /// The provided mode, mods, and key must match what is allowed by a binding
     /// for its action to be executed.
+    ///
+    /// Key releases only run the release bindings which consumed the matching key press.
     fn process_key_bindings(&mut self, key: &KeyEvent) -> bool {
-        let mode = BindingMode::new(self.ctx.terminal().mode(), self.ctx.search_active());
+        if key.state == ElementState::Released {
+            let pending = &mut self.ctx.pending_releases().keys;
+            let (actions, remaining): (Vec<_>, _) =
+                mem::take(pending).into_iter().partition(|(k, _)| *k == key.physical_key);
+            *pending = remaining;
+
+            let consumed = !actions.is_empty();
+            for (_, action) in actions {
+                action.execute(&mut self.ctx);
+            }
+            return consumed;
+        }
+
+        let mode = BindingMode::new(
+            self.ctx.terminal().mode(),
+            self.ctx.search_active(),
+            self.ctx.replaying(),
+        );
         let mods = self.ctx.modifiers().state();
 
         // Don't suppress char if no bindings were triggered.
         let mut suppress_chars = None;
 
+        // ConPTY encodes key events itself in win32-input-mode.
+        #[cfg(windows)]
+        let win32_input = self.win32_input_active();
+
+        let mut triggered = Vec::new();
         for i in 0..self.ctx.config().key_bindings().len() {
             let binding = &self.ctx.config().key_bindings()[i];
 
+            #[cfg(windows)]
+            if win32_input && matches!(binding.action, Action::Esc(_)) {
+                continue;
+            }
+
             // We don't want the key without modifier, because it means something else most of
             // the time. However what we want is to manually lowercase the character to account
             // for both small and capital latters on regular characters at the same time.
@@ -1094,32 +1688,58 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
                 key.logical_key.clone()
             };
 
+            // Bindings overriding `Option` as `Alt` either match the key `Option` composed
+            // characters with, or don't see `Alt` at all.
+            #[cfg(target_os = "macos")]
+            let (mods, logical_key) = match binding.option_as_alt {
+                Some(option_as_alt) if mods.alt_key() => {
+                    if self.option_is_alt(option_as_alt.into()) {
+                        let key = match key.key_without_modifiers() {
+                            Key::Character(ch) => Key::Character(ch.to_lowercase().into()),
+                            key => key,
+                        };
+                        (mods, key)
+                    } else {
+                        (mods - ModifiersState::ALT, logical_key)
+                    }
+                },
+                _ => (mods, logical_key),
+            };
+
             let key = match (&binding.trigger, logical_key) {
                 (BindingKey::Scancode(_), _) => BindingKey::Scancode(key.physical_key),
                 (_, code) => BindingKey::Keycode { key: code, location: key.location },
             };
 
             if binding.is_triggered_by(mode, mods, &key) {
-                // Pass through the key if any of the bindings has the `ReceiveChar` action.
-                *suppress_chars.get_or_insert(true) &= binding.action != Action::ReceiveChar;
-
-                // Binding was triggered; run the action.
-                binding.action.clone().execute(&mut self.ctx);
+                triggered.push(binding.clone());
             }
         }
 
+        // Release bindings consume the press, running their action once the key is released.
+        if triggered.iter().any(|binding| binding.release) {
+            let pending = &mut self.ctx.pending_releases().keys;
+            pending.retain(|(k, _)| *k != key.physical_key);
+            let releases = triggered.into_iter().filter(|binding| binding.release);
+            pending.extend(releases.map(|binding| (key.physical_key, binding.action)));
+            return true;
+        }
+
+        for binding in triggered {
+            // Pass through the key if any of the bindings has the `ReceiveChar` action.
+            *suppress_chars.get_or_insert(true) &= binding.action != Action::ReceiveChar;
+
+            // Binding was triggered; run the action.
+            binding.action.execute(&mut self.ctx);
+        }
+
         suppress_chars.unwrap_or(false)
     }
 
     /// Check mouse icon state in relation to the message bar.
     fn message_bar_cursor_state(&self) -> Option<CursorIcon> {
-        // Since search is above the message bar, the button is offset by search's height.
-        let search_height = usize::from(self.ctx.search_active());
-
-        // Calculate Y position of the end of the last terminal line.
         let size = self.ctx.size_info();
-        let terminal_end = size.padding_y() as usize
-            + size.cell_height() as usize * (size.screen_lines() + search_height);
+        let terminal_end = self.message_bar_start();
 
         let mouse = self.ctx.mouse();
         let display_offset = self.ctx.terminal().grid().display_offset();
@@ -1127,8 +1747,9 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
         if self.ctx.message().is_none() || (mouse.y <= terminal_end) {
             None
-        } else if mouse.y <= terminal_end + size.cell_height() as usize
-            && point.column + message_bar::CLOSE_BUTTON_TEXT.len() >= size.columns()
+        } else if (mouse.y <= terminal_end + size.cell_height() as usize
+            && point.column + message_bar::CLOSE_BUTTON_TEXT.len() >= size.columns())
+            || self.message_bar_action().is_some()
         {
             Some(CursorIcon::Pointer)
         } else {
@@ -1136,19 +1757,81 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         }
     }
 
+    /// Action of the message bar button below the mouse.
+    fn message_bar_action(&self) -> Option<MessageAction> {
+        let terminal_end = self.message_bar_start();
+        let mouse = self.ctx.mouse();
+        if mouse.y <= terminal_end {
+            return None;
+        }
+
+        let size = self.ctx.size_info();
+        let line = (mouse.y - terminal_end) / size.cell_height() as usize;
+        let display_offset = self.ctx.terminal().grid().display_offset();
+        let column = self.ctx.mouse().point(&size, display_offset).column;
+        self.ctx.message()?.action_at(&size, line, column.0)
+    }
+
+    /// Y position of the end of the last terminal line, where the message bar starts.
+    fn message_bar_start(&self) -> usize {
+        // Since search is above the message bar, the button is offset by search's height.
+        let search_height = usize::from(self.ctx.search_active());
+
+        let size = self.ctx.size_info();
+        size.padding_y() as usize
+            + size.cell_height() as usize * (size.screen_lines() + search_height)
+    }
+
+    /// Minimap of the terminal, if it is shown.
+    fn minimap(&mut self) -> Option<Minimap> {
+        if !self.ctx.config().window.minimap.enabled {
+            return None;
+        }
+
+        let scale_factor = self.ctx.window().scale_factor as f32;
+        let config = &self.ctx.config().window.minimap;
+        Minimap::new(config, &self.ctx.size_info(), scale_factor, self.ctx.terminal())
+    }
+
+    /// Check if the mouse is on top of the minimap.
+    fn minimap_hit(&mut self) -> bool {
+        let (x, y) = (self.ctx.mouse().x as f32, self.ctx.mouse().y as f32);
+        self.minimap().map_or(false, |minimap| minimap.contains(x, y))
+    }
+
+    /// Scroll the viewport to the lines shown at a vertical position of the minimap.
+    fn minimap_scroll(&mut self, y: f32) {
+        let minimap = match self.minimap() {
+            Some(minimap) => minimap,
+            None => return,
+        };
+
+        let display_offset = minimap.display_offset_at(y, self.ctx.size_info().screen_lines());
+        let delta = display_offset as i32 - self.ctx.terminal().grid().display_offset() as i32;
+        self.ctx.scroll(Scroll::Delta(delta));
+    }
+
     /// Icon state of the cursor.
     fn cursor_state(&mut self) -> CursorIcon {
         let display_offset = self.ctx.terminal().grid().display_offset();
         let point = self.ctx.mouse().point(&self.ctx.size_info(), display_offset);
-        let hyperlink = self.ctx.terminal().grid()[point].hyperlink();
+        let hyperlink = self.ctx.terminal().hyperlink_at(point);
+        let pointer_shape = self.ctx.terminal().mouse_pointer_shape();
 
         // Function to check if mouse is on top of a hint.
         let hint_highlighted = |hint: &HintMatch| hint.should_highlight(point, hyperlink.as_ref());
 
         if let Some(mouse_state) = self.message_bar_cursor_state() {
             mouse_state
+        } else if self.minimap_hit() {
+            CursorIcon::Default
         } else if self.ctx.display().highlighted_hint.as_ref().map_or(false, hint_highlighted) {
             CursorIcon::Pointer
+        } else if let Some(icon) = pointer_shape
+            .filter(|_| !self.ctx.modifiers().state().shift_key())
+            .and_then(|shape| CursorIcon::from_str(shape).ok())
+        {
+            icon
         } else if !self.ctx.modifiers().state().shift_key() && self.ctx.mouse_mode() {
             CursorIcon::Default
         } else {
@@ -1217,6 +1900,7 @@ mod tests {
         pub clipboard: &'a mut Clipboard,
         pub message_buffer: &'a mut MessageBuffer,
         pub modifiers: Modifiers,
+        pub pending_releases: PendingReleases,
         config: &'a UiConfig,
     }
 
@@ -1267,6 +1951,11 @@ mod tests {
             self.mouse
         }
 
+        #[inline]
+        fn pending_releases(&mut self) -> &mut PendingReleases {
+            &mut self.pending_releases
+        }
+
         #[inline]
         fn mouse(&self) -> &Mouse {
             self.mouse
@@ -1355,6 +2044,7 @@ mod tests {
                     clipboard: &mut clipboard,
                     modifiers: Default::default(),
                     message_buffer: &mut message_buffer,
+                    pending_releases: Default::default(),
                     config: &cfg,
                 };
 
@@ -1525,9 +2215,53 @@ mod tests {
         input_delay: Duration::ZERO,
     }
 
+    #[test]
+    fn release_binding_waits_for_press() {
+        let mut clipboard = Clipboard::new_nop();
+        let mut cfg = UiConfig::default();
+        let action = Action::ToggleViMode;
+        cfg.mouse.bindings.0 = vec![Binding {
+            trigger: MouseButton::Middle,
+            mods: ModifiersState::empty(),
+            mode: BindingMode::empty(),
+            notmode: BindingMode::empty(),
+            action: action.clone(),
+            option_as_alt: None,
+            release: true,
+        }];
+        let size = SizeInfo::new(21.0, 51.0, 3.0, 3.0, 0., 0., false);
+
+        let mut terminal = Term::new(&cfg.terminal_config, &size, MockEventProxy);
+        let mut mouse = Mouse::default();
+        let mut message_buffer = MessageBuffer::default();
+
+        let context = ActionContext {
+            terminal: &mut terminal,
+            mouse: &mut mouse,
+            size_info: &size,
+            clipboard: &mut clipboard,
+            modifiers: Default::default(),
+            message_buffer: &mut message_buffer,
+            pending_releases: Default::default(),
+            config: &cfg,
+        };
+        let mut processor = Processor::new(context);
+
+        // Releases without a matching press are ignored.
+        processor.process_mouse_bindings(MouseButton::Middle, ElementState::Released);
+        assert!(processor.ctx.pending_releases.buttons.is_empty());
+
+        // The press is consumed until the button is released.
+        processor.process_mouse_bindings(MouseButton::Middle, ElementState::Pressed);
+        assert_eq!(processor.ctx.pending_releases.buttons, vec![(MouseButton::Middle, action)]);
+
+        processor.process_mouse_bindings(MouseButton::Middle, ElementState::Released);
+        assert!(processor.ctx.pending_releases.buttons.is_empty());
+    }
+
     test_process_binding! {
         name: process_binding_nomode_shiftmod_require_shift,
-        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty(), option_as_alt: None, release: false },
         triggers: true,
         mode: BindingMode::empty(),
         mods: ModifiersState::SHIFT,
@@ -1535,7 +2269,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_nomod_require_shift,
-        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty(), option_as_alt: None, release: false },
         triggers: false,
         mode: BindingMode::empty(),
         mods: ModifiersState::empty(),
@@ -1543,7 +2277,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_controlmod,
-        binding: Binding { trigger: KEY, mods: ModifiersState::CONTROL, action: Action::from("\x1b[1;5D"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::CONTROL, action: Action::from("\x1b[1;5D"), mode: BindingMode::empty(), notmode: BindingMode::empty(), option_as_alt: None, release: false },
         triggers: true,
         mode: BindingMode::empty(),
         mods: ModifiersState::CONTROL,
@@ -1551,7 +2285,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_nomod_require_not_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1b[D"), mode: BindingMode::empty(), notmode: BindingMode::APP_CURSOR },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1b[D"), mode: BindingMode::empty(), notmode: BindingMode::APP_CURSOR, option_as_alt: None, release: false },
         triggers: true,
         mode: BindingMode::empty(),
         mods: ModifiersState::empty(),
@@ -1559,7 +2293,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_appcursormode_nomod_require_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty(), option_as_alt: None, release: false },
         triggers: true,
         mode: BindingMode::APP_CURSOR,
         mods: ModifiersState::empty(),
@@ -1567,7 +2301,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_nomod_require_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty(), option_as_alt: None, release: false },
         triggers: false,
         mode: BindingMode::empty(),
         mods: ModifiersState::empty(),
@@ -1575,7 +2309,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_appcursormode_appkeypadmode_nomod_require_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty(), option_as_alt: None, release: false },
         triggers: true,
         mode: BindingMode::APP_CURSOR | BindingMode::APP_KEYPAD,
         mods: ModifiersState::empty(),
@@ -1583,7 +2317,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_fail_with_extra_mods,
-        binding: Binding { trigger: KEY, mods: ModifiersState::SUPER, action: Action::from("arst"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::SUPER, action: Action::from("arst"), mode: BindingMode::empty(), notmode: BindingMode::empty(), option_as_alt: None, release: false },
         triggers: false,
         mode: BindingMode::empty(),
         mods: ModifiersState::ALT | ModifiersState::SUPER,
@@ -1,38 +1,147 @@
 //! Alacritty socket IPC.
 
 use std::ffi::OsStr;
-use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Result as IoResult, Write};
+use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Read, Result as IoResult, Write};
+use std::net::Shutdown;
+use std::os::unix::io::{FromRawFd, RawFd};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
 use std::{env, fs, process};
 
-use log::warn;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
 use winit::event_loop::EventLoopProxy;
 use winit::window::WindowId;
 
 use alacritty_terminal::thread;
 
-use crate::cli::{Options, SocketMessage};
+use crate::cli::{IpcConfig, Options, SocketMessage, WindowOptions};
+use crate::display::stats::RenderStats;
 use crate::event::{Event, EventType};
+use crate::layout::Layout;
+use crate::pane::PaneDestination;
+
+/// Reply to an IPC message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SocketReply {
+    /// Terminal text content.
+    Text(String),
+
+    /// Terminal state.
+    State(IpcState),
+
+    /// All windows of the Alacritty instance.
+    Windows(Vec<IpcWindow>),
+
+    /// Performance metrics of a window.
+    Stats(RenderStats),
+
+    /// Message could not be handled.
+    Error(String),
+}
+
+/// Terminal state reported by the `get-state` IPC subcommand.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcState {
+    /// ID of the window.
+    pub window_id: u64,
+
+    /// Window title.
+    pub title: String,
+
+    /// Working directory of the foreground process.
+    pub working_directory: Option<PathBuf>,
+
+    /// Number of columns.
+    pub columns: usize,
+
+    /// Number of visible lines.
+    pub screen_lines: usize,
+
+    /// Width of the window in pixels.
+    pub width: u32,
+
+    /// Height of the window in pixels.
+    pub height: u32,
+
+    /// Cursor line, relative to the top of the active screen.
+    pub cursor_line: i32,
+
+    /// Cursor column.
+    pub cursor_column: usize,
+
+    /// Number of lines the viewport is scrolled up into the history.
+    pub display_offset: usize,
+
+    /// Active terminal modes.
+    pub modes: Vec<String>,
+}
+
+/// Window reported by the `list-windows` IPC subcommand.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcWindow {
+    /// ID of the window.
+    pub window_id: u64,
+
+    /// Name assigned to the window.
+    pub name: Option<String>,
+
+    /// Window title.
+    pub title: String,
+
+    /// Whether the window has keyboard focus.
+    pub focused: bool,
+
+    /// Horizontal position of the window in pixels, if supported by the platform.
+    pub x: Option<i32>,
+
+    /// Vertical position of the window in pixels, if supported by the platform.
+    pub y: Option<i32>,
+
+    /// Width of the window in pixels.
+    pub width: u32,
+
+    /// Height of the window in pixels.
+    pub height: u32,
+}
 
 /// Environment variable name for the IPC socket path.
 const ALACRITTY_SOCKET_ENV: &str = "ALACRITTY_SOCKET";
 
+/// First file descriptor passed by the service manager during socket activation.
+const LISTEN_FDS_START: RawFd = 3;
+
 /// Create an IPC socket.
+///
+/// This will return the path of the socket file, if it was created by Alacritty.
 pub fn spawn_ipc_socket(options: &Options, event_proxy: EventLoopProxy<Event>) -> Option<PathBuf> {
-    // Create the IPC socket and export its path as env variable if necessary.
-    let socket_path = options.socket.clone().unwrap_or_else(|| {
-        let mut path = socket_dir();
-        path.push(format!("{}-{}.sock", socket_prefix(), process::id()));
-        path
-    });
-    env::set_var(ALACRITTY_SOCKET_ENV, socket_path.as_os_str());
+    let (listener, socket_path) = match activated_listener() {
+        // Use the socket passed by the service manager, which is also responsible for its cleanup.
+        Some(listener) => {
+            let addr = listener.local_addr().ok();
+            if let Some(path) = addr.as_ref().and_then(|addr| addr.as_pathname()) {
+                env::set_var(ALACRITTY_SOCKET_ENV, path.as_os_str());
+            }
 
-    let listener = match UnixListener::bind(&socket_path) {
-        Ok(listener) => listener,
-        Err(err) => {
-            warn!("Unable to create socket: {:?}", err);
-            return None;
+            (listener, None)
+        },
+        None => {
+            // Create the IPC socket and export its path as env variable if necessary.
+            let socket_path = options.socket.clone().unwrap_or_else(|| {
+                let mut path = socket_dir();
+                path.push(format!("{}-{}.sock", socket_prefix(), process::id()));
+                path
+            });
+            env::set_var(ALACRITTY_SOCKET_ENV, socket_path.as_os_str());
+
+            match UnixListener::bind(&socket_path) {
+                Ok(listener) => (listener, Some(socket_path)),
+                Err(err) => {
+                    warn!("Unable to create socket: {:?}", err);
+                    return None;
+                },
+            }
         },
     };
 
@@ -57,36 +166,237 @@ pub fn spawn_ipc_socket(options: &Options, event_proxy: EventLoopProxy<Event>) -
                 },
             };
 
-            // Handle IPC events.
-            match message {
-                SocketMessage::CreateWindow(options) => {
-                    let event = Event::new(EventType::CreateWindow(options), None);
-                    let _ = event_proxy.send_event(event);
-                },
-                SocketMessage::Config(ipc_config) => {
-                    let window_id = ipc_config
-                        .window_id
-                        .and_then(|id| u64::try_from(id).ok())
-                        .map(WindowId::from);
-                    let event = Event::new(EventType::IpcConfig(ipc_config), window_id);
-                    let _ = event_proxy.send_event(event);
-                },
+            if let Some(reply) = handle_message(message, &event_proxy) {
+                write_reply(stream.get_mut(), &reply);
             }
         }
     });
 
-    Some(socket_path)
+    socket_path
+}
+
+/// Handle an IPC message.
+///
+/// This will wait for and return the reply for messages which query Alacritty's state.
+pub fn handle_message(
+    message: SocketMessage,
+    event_proxy: &EventLoopProxy<Event>,
+) -> Option<SocketReply> {
+    // Find the window targeted by the message.
+    let window_id = match target_window(&message, event_proxy) {
+        Ok(window_id) => window_id,
+        Err(err) => return Some(SocketReply::Error(err)),
+    };
+
+    match message {
+        SocketMessage::CreateWindow(options) => {
+            let event = Event::new(EventType::CreateWindow(options), None);
+            let _ = event_proxy.send_event(event);
+        },
+        SocketMessage::Config(IpcConfig { get: Some(option), .. }) => {
+            let (reply_tx, reply_rx) = mpsc::channel();
+            let event = Event::new(EventType::IpcGetConfig(option, reply_tx), window_id);
+            let _ = event_proxy.send_event(event);
+            return Some(receive_reply(reply_rx));
+        },
+        SocketMessage::Config(ipc_config) => {
+            let event = Event::new(EventType::IpcConfig(ipc_config), window_id);
+            let _ = event_proxy.send_event(event);
+        },
+        SocketMessage::Search(ipc_search) => {
+            let event = Event::new(EventType::IpcSearch(ipc_search), window_id);
+            let _ = event_proxy.send_event(event);
+        },
+        SocketMessage::DumpHistory(ipc_dump) => {
+            let event = Event::new(EventType::IpcDumpHistory(ipc_dump), window_id);
+            let _ = event_proxy.send_event(event);
+        },
+        SocketMessage::Screenshot(ipc_screenshot) => {
+            let event = Event::new(EventType::IpcScreenshot(ipc_screenshot.path), window_id);
+            let _ = event_proxy.send_event(event);
+        },
+        SocketMessage::GetText(ipc_get_text) => {
+            let (reply_tx, reply_rx) = mpsc::channel();
+            let event = Event::new(EventType::IpcGetText(ipc_get_text, reply_tx), window_id);
+            let _ = event_proxy.send_event(event);
+            return Some(receive_reply(reply_rx));
+        },
+        SocketMessage::GetState(_) => {
+            let (reply_tx, reply_rx) = mpsc::channel();
+            let event = Event::new(EventType::IpcGetState(reply_tx), window_id);
+            let _ = event_proxy.send_event(event);
+            return Some(receive_reply(reply_rx));
+        },
+        SocketMessage::SendText(ipc_send_text) => {
+            let event = Event::new(EventType::IpcSendText(ipc_send_text), window_id);
+            let _ = event_proxy.send_event(event);
+        },
+        SocketMessage::Broadcast(ipc_broadcast) => {
+            let event = Event::new(EventType::IpcBroadcast(ipc_broadcast), window_id);
+            let _ = event_proxy.send_event(event);
+        },
+        SocketMessage::Layout(ipc_layout) => {
+            let layout = match Layout::load(&ipc_layout.path) {
+                Ok(layout) => layout,
+                Err(err) => return Some(SocketReply::Error(err)),
+            };
+
+            for options in layout.window_options(&WindowOptions::default()) {
+                let event = Event::new(EventType::CreateWindow(Box::new(options)), None);
+                let _ = event_proxy.send_event(event);
+            }
+        },
+        SocketMessage::MovePane(ipc_move_pane) => {
+            let destination = match &ipc_move_pane.target {
+                Some(target) => match find_window(target, event_proxy) {
+                    Ok(window_id) => PaneDestination::Window(window_id),
+                    Err(err) => return Some(SocketReply::Error(err)),
+                },
+                None => PaneDestination::NewWindow,
+            };
+
+            let event = EventType::MovePane(destination, ipc_move_pane.direction);
+            let _ = event_proxy.send_event(Event::new(event, window_id));
+        },
+        SocketMessage::Toggle(_) => {
+            let _ = event_proxy.send_event(Event::new(EventType::ToggleDropdown, window_id));
+        },
+        SocketMessage::ListWindows => {
+            return Some(SocketReply::Windows(list_windows(event_proxy)));
+        },
+        SocketMessage::Stats(_) => {
+            let (reply_tx, reply_rx) = mpsc::channel();
+            let event = Event::new(EventType::IpcStats(reply_tx), window_id);
+            let _ = event_proxy.send_event(event);
+            return Some(receive_reply(reply_rx));
+        },
+    }
+
+    None
+}
+
+/// Get the window targeted by a message.
+///
+/// Windows are looked up by name before their ID. Messages without any target are handled by the
+/// focused window, or all windows for configuration changes.
+fn target_window(
+    message: &SocketMessage,
+    event_proxy: &EventLoopProxy<Event>,
+) -> Result<Option<WindowId>, String> {
+    let (window_id, window) = match message {
+        SocketMessage::Config(ipc_config) => (ipc_config.window_id, &ipc_config.window),
+        SocketMessage::Search(ipc_search) => (ipc_search.window_id, &ipc_search.window),
+        SocketMessage::DumpHistory(ipc_dump) => (ipc_dump.window_id, &ipc_dump.window),
+        SocketMessage::Screenshot(ipc_screenshot) => {
+            (ipc_screenshot.window_id, &ipc_screenshot.window)
+        },
+        SocketMessage::GetText(ipc_get_text) => (ipc_get_text.window_id, &ipc_get_text.window),
+        SocketMessage::GetState(ipc_get_state) => (ipc_get_state.window_id, &ipc_get_state.window),
+        SocketMessage::SendText(ipc_send_text) => (ipc_send_text.window_id, &ipc_send_text.window),
+        SocketMessage::Broadcast(ipc_broadcast) => (ipc_broadcast.window_id, &ipc_broadcast.window),
+        SocketMessage::MovePane(ipc_move_pane) => (ipc_move_pane.window_id, &ipc_move_pane.window),
+        SocketMessage::Toggle(ipc_toggle) => (ipc_toggle.window_id, &ipc_toggle.window),
+        SocketMessage::Stats(ipc_stats) => (ipc_stats.window_id, &ipc_stats.window),
+        SocketMessage::CreateWindow(_) | SocketMessage::Layout(_) | SocketMessage::ListWindows => {
+            return Ok(None)
+        },
+    };
+
+    match window {
+        Some(window) => find_window(window, event_proxy).map(Some),
+        None => Ok(window_id.and_then(|id| u64::try_from(id).ok()).map(WindowId::from)),
+    }
+}
+
+/// Find a window by its name or ID.
+fn find_window(window: &str, event_proxy: &EventLoopProxy<Event>) -> Result<WindowId, String> {
+    let windows = list_windows(event_proxy);
+    windows
+        .iter()
+        .find(|ipc_window| ipc_window.name.as_deref() == Some(window))
+        .or_else(|| windows.iter().find(|ipc_window| ipc_window.window_id.to_string() == window))
+        .map(|ipc_window| WindowId::from(ipc_window.window_id))
+        .ok_or_else(|| format!("no window named {:?} found", window))
+}
+
+/// Get all windows of this Alacritty instance.
+fn list_windows(event_proxy: &EventLoopProxy<Event>) -> Vec<IpcWindow> {
+    let (reply_tx, reply_rx) = mpsc::channel();
+    let event = Event::new(EventType::IpcListWindows(reply_tx), None);
+    let _ = event_proxy.send_event(event);
+    reply_rx.recv().unwrap_or_default()
+}
+
+/// Wait for the reply to a message.
+fn receive_reply(reply_rx: Receiver<SocketReply>) -> SocketReply {
+    // The sender is dropped without reply when no window could handle the message.
+    reply_rx.recv().unwrap_or_else(|_| SocketReply::Error(String::from("no matching window found")))
+}
+
+/// Write a reply back to the client.
+fn write_reply(stream: &mut UnixStream, reply: &SocketReply) {
+    match serde_json::to_string(reply) {
+        Ok(reply) => {
+            let _ = stream.write_all(reply.as_bytes());
+            let _ = stream.flush();
+        },
+        Err(err) => warn!("Failed to serialize IPC reply: {}", err),
+    }
+}
+
+/// Get the IPC listener passed by the service manager through socket activation.
+fn activated_listener() -> Option<UnixListener> {
+    // Ignore sockets which were meant for a different process.
+    let pid = env::var("LISTEN_PID").ok()?;
+    if pid.parse::<u32>().ok()? != process::id() {
+        return None;
+    }
+    let fds = env::var("LISTEN_FDS").ok()?.parse::<u32>().ok()?;
+
+    // Prevent shells and their children from picking up the activation environment.
+    env::remove_var("LISTEN_PID");
+    env::remove_var("LISTEN_FDS");
+    env::remove_var("LISTEN_FDNAMES");
+
+    if fds == 0 {
+        return None;
+    } else if fds > 1 {
+        warn!("Ignoring {} additional sockets passed through socket activation", fds - 1);
+    }
+
+    info!("Using socket passed through socket activation");
+
+    unsafe {
+        // Avoid leaking the socket into spawned processes.
+        libc::fcntl(LISTEN_FDS_START, libc::F_SETFD, libc::FD_CLOEXEC);
+
+        Some(UnixListener::from_raw_fd(LISTEN_FDS_START))
+    }
 }
 
 /// Send a message to the active Alacritty socket.
-pub fn send_message(socket: Option<PathBuf>, message: SocketMessage) -> IoResult<()> {
+///
+/// This will return the reply for messages which query Alacritty's state.
+pub fn send_message(
+    socket: Option<PathBuf>,
+    message: SocketMessage,
+) -> IoResult<Option<SocketReply>> {
     let mut socket = find_socket(socket)?;
 
     let message = serde_json::to_string(&message)?;
     socket.write_all(message[..].as_bytes())?;
     let _ = socket.flush();
 
-    Ok(())
+    // Signal the end of the message, then wait for Alacritty to close the connection.
+    socket.shutdown(Shutdown::Write)?;
+    let mut reply = String::new();
+    socket.read_to_string(&mut reply)?;
+
+    if reply.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(serde_json::from_str(&reply)?))
 }
 
 /// Directory for the IPC socket file.
@@ -0,0 +1,244 @@
+//! Startup layouts describing windows, tabs and split panes.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::cli::{TerminalOptions, WindowOptions};
+use crate::pane::PaneDirection;
+
+/// Windows opened together from a layout file.
+#[derive(Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Layout {
+    #[serde(default)]
+    windows: Vec<LayoutWindow>,
+}
+
+impl Layout {
+    /// Load a layout from a TOML or YAML file.
+    ///
+    /// Relative working directories are resolved from the directory of the layout file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("Unable to read layout {path:?}: {err}"))?;
+
+        let extension = path.extension().unwrap_or_default();
+        let layout: Result<Self, String> = if extension == "yaml" || extension == "yml" {
+            serde_yaml::from_str(&contents).map_err(|err| err.to_string())
+        } else {
+            toml::from_str(&contents).map_err(|err| err.to_string())
+        };
+        let mut layout = layout.map_err(|err| format!("Invalid layout {path:?}: {err}"))?;
+
+        if layout.windows.is_empty() {
+            return Err(format!("Layout {path:?} does not contain any windows"));
+        }
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        for window in &mut layout.windows {
+            window.pane.resolve_paths(directory);
+        }
+
+        Ok(layout)
+    }
+
+    /// Options for every window of the layout, extending the options passed on the CLI.
+    pub fn window_options(self, base: &WindowOptions) -> Vec<WindowOptions> {
+        let mut windows: Vec<WindowOptions> = Vec::with_capacity(self.windows.len());
+
+        for window in self.windows {
+            let mut options = base.clone();
+
+            window.pane.override_terminal_options(&mut options.terminal_options);
+            options.window_identity.title = window.title.or(options.window_identity.title);
+            options.window_identity.name = window.name.or(options.window_identity.name);
+            options.splits = window.pane.splits;
+
+            // Group tabs with the window preceding them.
+            #[cfg(target_os = "macos")]
+            {
+                let previous = windows.last().and_then(|last| last.window_tabbing_id.clone());
+                options.window_tabbing_id = match previous {
+                    Some(tabbing_id) if window.tab => Some(tabbing_id),
+                    _ => Some(format!("alacritty-layout-{}-{}", std::process::id(), windows.len())),
+                };
+            }
+
+            windows.push(options);
+        }
+
+        windows
+    }
+}
+
+/// Window opened by a layout.
+#[derive(Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct LayoutWindow {
+    /// Window title.
+    title: Option<String>,
+
+    /// Name used to target the window through IPC.
+    name: Option<String>,
+
+    /// Open the window as a tab of the previous window, on macOS.
+    #[serde(default)]
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    tab: bool,
+
+    /// Terminal of the window's first pane.
+    #[serde(flatten)]
+    pane: LayoutPane,
+}
+
+/// Pane split off another pane of a layout.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LayoutSplit {
+    /// Side of the split pane the new pane is opened on.
+    pub direction: PaneDirection,
+
+    /// Terminal of the new pane.
+    #[serde(flatten)]
+    pub pane: LayoutPane,
+}
+
+/// Terminal hosted by a pane of a layout.
+#[derive(Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct LayoutPane {
+    /// Working directory of the shell.
+    working_directory: Option<PathBuf>,
+
+    /// Command and args executed instead of the shell.
+    #[serde(default)]
+    command: Vec<String>,
+
+    /// Additional environment variables of the shell.
+    #[serde(default)]
+    env: HashMap<String, String>,
+
+    /// Remain open after the command exits.
+    #[serde(default)]
+    hold: bool,
+
+    /// Panes split off this pane, in the order they are opened.
+    #[serde(default)]
+    pub splits: Vec<LayoutSplit>,
+}
+
+impl LayoutPane {
+    /// Terminal options for spawning the pane's shell.
+    pub fn terminal_options(&self) -> TerminalOptions {
+        let mut options = TerminalOptions::default();
+        self.override_terminal_options(&mut options);
+        options
+    }
+
+    /// Override the [`TerminalOptions`] with the options of this pane.
+    fn override_terminal_options(&self, options: &mut TerminalOptions) {
+        if self.working_directory.is_some() {
+            options.working_directory = self.working_directory.clone();
+        }
+
+        if !self.command.is_empty() {
+            options.command = self.command.clone();
+        }
+
+        options.env.extend(self.env.iter().map(|(key, value)| (key.clone(), value.clone())));
+        options.hold |= self.hold;
+    }
+
+    /// Resolve working directories relative to the home or layout directory.
+    fn resolve_paths(&mut self, directory: &Path) {
+        if let Some(path) = &mut self.working_directory {
+            match (path.strip_prefix("~/"), home::home_dir()) {
+                (Ok(stripped), Some(home_dir)) => *path = home_dir.join(stripped),
+                _ => *path = directory.join(&path),
+            }
+        }
+
+        for split in &mut self.splits {
+            split.pane.resolve_paths(directory);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_splits() {
+        let layout: Layout = toml::from_str(
+            r#"
+            [[windows]]
+            title = "dev"
+            working_directory = "project"
+            command = ["nvim"]
+
+            [[windows.splits]]
+            direction = "Right"
+            command = ["cargo", "watch"]
+
+            [[windows.splits.splits]]
+            direction = "Down"
+            env = { RUST_LOG = "debug" }
+
+            [[windows]]
+            tab = true
+            "#,
+        )
+        .unwrap();
+
+        let mut windows = layout.window_options(&WindowOptions::default()).into_iter();
+
+        let window = windows.next().unwrap();
+        assert_eq!(window.window_identity.title.as_deref(), Some("dev"));
+        assert_eq!(window.terminal_options.working_directory, Some(PathBuf::from("project")));
+        assert_eq!(window.terminal_options.command, vec![String::from("nvim")]);
+
+        let split = &window.splits[0];
+        assert_eq!(split.direction, PaneDirection::Right);
+        assert_eq!(split.pane.terminal_options().command, vec!["cargo", "watch"]);
+
+        let nested = &split.pane.splits[0];
+        assert_eq!(nested.direction, PaneDirection::Down);
+        assert_eq!(nested.pane.terminal_options().env, vec![(
+            String::from("RUST_LOG"),
+            String::from("debug")
+        )]);
+
+        let tab = windows.next().unwrap();
+        assert_eq!(tab.terminal_options, TerminalOptions::default());
+        assert!(tab.splits.is_empty());
+        assert!(windows.next().is_none());
+    }
+
+    #[test]
+    fn unknown_field() {
+        assert!(toml::from_str::<Layout>("[[windows]]\nfoo = 1").is_err());
+        assert!(toml::from_str::<Layout>("[[windows]]\n[[windows.splits]]").is_err());
+    }
+
+    #[test]
+    fn relative_working_directory() {
+        let mut pane = LayoutPane {
+            working_directory: Some(PathBuf::from("src")),
+            splits: vec![LayoutSplit {
+                direction: PaneDirection::Down,
+                pane: LayoutPane {
+                    working_directory: Some(PathBuf::from("/tmp")),
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        };
+        pane.resolve_paths(Path::new("/layouts"));
+
+        assert_eq!(pane.working_directory, Some(PathBuf::from("/layouts/src")));
+        assert_eq!(pane.splits[0].pane.working_directory, Some(PathBuf::from("/tmp")));
+    }
+}
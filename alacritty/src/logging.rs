@@ -20,7 +20,7 @@ use alacritty_terminal::config::LOG_TARGET_CONFIG;
 
 use crate::cli::Options;
 use crate::event::{Event, EventType};
-use crate::message_bar::{Message, MessageType};
+use crate::message_bar::{Message, MessageAction, MessageType};
 
 /// Logging target for IPC config error messages.
 pub const LOG_TARGET_IPC_CONFIG: &str = "alacritty_log_ipc_config";
@@ -112,6 +112,10 @@ impl Logger {
         );
 
         let mut message = Message::new(message, message_type);
+        if record.target() == LOG_TARGET_CONFIG {
+            message = message.with_action(MessageAction::OpenConfig);
+        }
+        message = message.with_action(MessageAction::DismissAll);
         message.set_target(record.target().to_owned());
 
         let _ = event_proxy.send_event(Event::new(EventType::Message(message), None));
@@ -0,0 +1,24 @@
+use cocoa::base::{id, nil, BOOL};
+use cocoa::foundation::NSString;
+use objc::{class, msg_send, sel, sel_impl};
+
+/// Pasteboard types of the plain text, HTML and RTF representations.
+const TYPES: [&str; 3] = ["public.utf8-plain-text", "public.html", "public.rtf"];
+
+/// Replace the general pasteboard's content with plain text, HTML and RTF.
+pub fn set_formatted(text: &str, html: &str, rtf: &str) {
+    unsafe {
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        let _: isize = msg_send![pasteboard, clearContents];
+
+        for (content, ty) in [text, html, rtf].into_iter().zip(TYPES) {
+            let content = NSString::alloc(nil).init_str(content);
+            let ty = NSString::alloc(nil).init_str(ty);
+
+            let _: BOOL = msg_send![pasteboard, setString: content forType: ty];
+
+            let _: () = msg_send![content, release];
+            let _: () = msg_send![ty, release];
+        }
+    }
+}
@@ -12,13 +12,13 @@
 #[cfg(not(any(feature = "x11", feature = "wayland", target_os = "macos", windows)))]
 compile_error!(r#"at least one of the "x11"/"wayland" features must be enabled"#);
 
-#[cfg(target_os = "macos")]
+#[cfg(unix)]
 use std::env;
 use std::error::Error;
 use std::fmt::Write as _;
-use std::fs;
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::{fs, mem};
 
 use log::info;
 #[cfg(windows)]
@@ -27,26 +27,38 @@ use winit::event_loop::EventLoopBuilder as WinitEventLoopBuilder;
 #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
 use winit::platform::x11::EventLoopWindowTargetExtX11;
 
+use alacritty_terminal::replay::Header;
 use alacritty_terminal::tty;
 
+mod bench;
 mod cli;
 mod clipboard;
 mod config;
+mod contrast;
 mod daemon;
+#[cfg(all(feature = "dbus", not(any(target_os = "macos", windows))))]
+mod dbus;
 mod display;
 mod event;
 mod input;
 #[cfg(unix)]
 mod ipc;
+mod layout;
 mod logging;
 #[cfg(target_os = "macos")]
 mod macos;
 mod message_bar;
 mod migrate;
+mod notification;
+mod pane;
 #[cfg(windows)]
 mod panic;
+mod power;
 mod renderer;
 mod scheduler;
+mod scripting;
+mod search_history;
+mod sound;
 mod string;
 mod window_context;
 
@@ -55,11 +67,14 @@ mod gl {
     include!(concat!(env!("OUT_DIR"), "/gl_bindings.rs"));
 }
 
+use crate::cli::{options_as_value, BenchOptions, Options, ReplayOptions, Subcommands};
 #[cfg(unix)]
-use crate::cli::MessageOptions;
-use crate::cli::{Options, Subcommands};
-use crate::config::{monitor, UiConfig};
+use crate::cli::{MessageOptions, SocketMessage};
+use crate::config::{monitor, serde_utils, UiConfig};
 use crate::event::{Event, Processor};
+#[cfg(unix)]
+use crate::ipc::SocketReply;
+use crate::layout::Layout;
 #[cfg(target_os = "macos")]
 use crate::macos::locale;
 
@@ -76,12 +91,14 @@ fn main() -> Result<(), Box<dyn Error>> {
     }
 
     // Load command line options.
-    let options = Options::new();
+    let mut options = Options::new();
 
-    match options.subcommands {
+    match options.subcommands.take() {
         #[cfg(unix)]
         Some(Subcommands::Msg(options)) => msg(options)?,
         Some(Subcommands::Migrate(options)) => migrate::migrate(options),
+        Some(Subcommands::Replay(replay_options)) => replay(options, replay_options)?,
+        Some(Subcommands::Bench(bench_options)) => bench(options, bench_options)?,
         None => alacritty(options)?,
     }
 
@@ -90,8 +107,74 @@ fn main() -> Result<(), Box<dyn Error>> {
 
 /// `msg` subcommand entrypoint.
 #[cfg(unix)]
-fn msg(options: MessageOptions) -> Result<(), Box<dyn Error>> {
-    ipc::send_message(options.socket, options.message).map_err(|err| err.into())
+fn msg(mut options: MessageOptions) -> Result<(), Box<dyn Error>> {
+    // Resolve paths relative to the client, since Alacritty runs in a different directory.
+    if let SocketMessage::DumpHistory(ipc_dump) = &mut options.message {
+        ipc_dump.path = env::current_dir()?.join(&ipc_dump.path);
+    }
+    if let SocketMessage::Screenshot(ipc_screenshot) = &mut options.message {
+        ipc_screenshot.path = env::current_dir()?.join(&ipc_screenshot.path);
+    }
+    if let SocketMessage::Layout(ipc_layout) = &mut options.message {
+        ipc_layout.path = env::current_dir()?.join(&ipc_layout.path);
+    }
+
+    // Pass the client's activation token on, allowing new windows to take focus.
+    #[cfg(not(target_os = "macos"))]
+    if let SocketMessage::CreateWindow(window_options) = &mut options.message {
+        window_options.activation_token =
+            env::var("XDG_ACTIVATION_TOKEN").or_else(|_| env::var("DESKTOP_STARTUP_ID")).ok();
+    }
+
+    // Read the text from STDIN, since Alacritty can't access the client's input.
+    if let SocketMessage::SendText(ipc_send_text) = &mut options.message {
+        if ipc_send_text.text.is_none() {
+            ipc_send_text.text = Some(io::read_to_string(io::stdin())?);
+        }
+    }
+
+    match ipc::send_message(options.socket, options.message)? {
+        Some(SocketReply::Text(text)) => write!(io::stdout(), "{}", text)?,
+        Some(SocketReply::State(state)) => {
+            writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&state)?)?
+        },
+        Some(SocketReply::Windows(windows)) => {
+            writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&windows)?)?
+        },
+        Some(SocketReply::Stats(stats)) => {
+            writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&stats)?)?
+        },
+        Some(SocketReply::Error(err)) => return Err(err.into()),
+        None => (),
+    }
+
+    Ok(())
+}
+
+/// `replay` subcommand entrypoint.
+fn replay(mut options: Options, replay_options: ReplayOptions) -> Result<(), Box<dyn Error>> {
+    // Size the window to fit the recording, unless the dimensions were set explicitly.
+    let header = Header::load(&replay_options.path)?;
+    let dimensions =
+        format!("window.dimensions = {{ columns = {}, lines = {} }}", header.width, header.height);
+    let config_options = mem::take(&mut options.config_options).0;
+    options.config_options.0 = serde_utils::merge(options_as_value(&[dimensions]), config_options);
+
+    options.window_options.replay = Some(replay_options.path);
+
+    alacritty(options)
+}
+
+/// `bench` subcommand entrypoint.
+fn bench(mut options: Options, bench_options: BenchOptions) -> Result<(), Box<dyn Error>> {
+    if bench_options.headless {
+        bench::run_headless(&bench_options);
+        return Ok(());
+    }
+
+    options.window_options.bench = Some(bench_options);
+
+    alacritty(options)
 }
 
 /// Temporary files stored for Alacritty.
@@ -147,6 +230,12 @@ fn alacritty(options: Options) -> Result<(), Box<dyn Error>> {
     // Update the log level from config.
     log::set_max_level(config.debug.log_level);
 
+    // Load the startup layout before changing the working directory.
+    let window_options = match &options.layout {
+        Some(path) => Layout::load(path)?.window_options(&options.window_options),
+        None => vec![options.window_options.clone()],
+    };
+
     // Set environment variables.
     tty::setup_env(&config.terminal_config);
 
@@ -166,6 +255,12 @@ fn alacritty(options: Options) -> Result<(), Box<dyn Error>> {
         monitor::watch(config.config_paths.clone(), window_event_loop.create_proxy());
     }
 
+    // Watch the power source, to throttle animations on battery.
+    power::watch(window_event_loop.create_proxy());
+
+    // Watch the system's high contrast setting.
+    contrast::watch(window_event_loop.create_proxy());
+
     // Create the IPC socket listener.
     #[cfg(unix)]
     let socket_path = if config.ipc_socket {
@@ -174,6 +269,12 @@ fn alacritty(options: Options) -> Result<(), Box<dyn Error>> {
         None
     };
 
+    // Register the D-Bus interface.
+    #[cfg(all(feature = "dbus", not(any(target_os = "macos", windows))))]
+    if config.ipc_dbus {
+        dbus::spawn_dbus_service(window_event_loop.create_proxy());
+    }
+
     // Setup automatic RAII cleanup for our files.
     let log_cleanup = log_file.filter(|_| !config.debug.persistent_logging);
     let _files = TemporaryFiles {
@@ -183,7 +284,6 @@ fn alacritty(options: Options) -> Result<(), Box<dyn Error>> {
     };
 
     // Event processor.
-    let window_options = options.window_options.clone();
     let mut processor = Processor::new(config, options, &window_event_loop);
 
     // Start event loop and block until shutdown.
@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::ops::Range;
 
 use unicode_width::UnicodeWidthChar;
 
@@ -10,6 +11,7 @@ pub const CLOSE_BUTTON_TEXT: &str = "[X]";
 const CLOSE_BUTTON_PADDING: usize = 1;
 const MIN_FREE_LINES: usize = 3;
 const TRUNCATED_MESSAGE: &str = "[MESSAGE TRUNCATED]";
+const ACTION_BUTTON_PADDING: usize = 1;
 
 /// Message for display in the MessageBuffer.
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -17,6 +19,7 @@ pub struct Message {
     text: String,
     ty: MessageType,
     target: Option<String>,
+    actions: Vec<MessageAction>,
 }
 
 /// Purpose of the message.
@@ -29,20 +32,50 @@ pub enum MessageType {
     Warning,
 }
 
+/// Action offered by a button below the message.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum MessageAction {
+    /// Open the configuration file.
+    OpenConfig,
+
+    /// Remove all queued messages.
+    DismissAll,
+}
+
+impl MessageAction {
+    /// Text of the action's button.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OpenConfig => "[Open config]",
+            Self::DismissAll => "[Dismiss all]",
+        }
+    }
+}
+
 impl Message {
     /// Create a new message.
     pub fn new(text: String, ty: MessageType) -> Message {
-        Message { text, ty, target: None }
+        Message { text, ty, target: None, actions: Vec::new() }
+    }
+
+    /// Offer an action with a button below the message.
+    pub fn with_action(mut self, action: MessageAction) -> Self {
+        self.actions.push(action);
+        self
     }
 
     /// Formatted message text lines.
     pub fn text(&self, size_info: &SizeInfo) -> Vec<String> {
         let num_cols = size_info.columns();
-        let total_lines =
-            (size_info.height() - 2. * size_info.padding_y()) / size_info.cell_height();
-        let max_lines = (total_lines as usize).saturating_sub(MIN_FREE_LINES);
+        let mut max_lines = Self::max_lines(size_info);
         let button_len = CLOSE_BUTTON_TEXT.chars().count();
 
+        // Reserve the last line for the action buttons.
+        let buttons = self.buttons(size_info);
+        if !buttons.is_empty() {
+            max_lines -= 1;
+        }
+
         // Split line to fit the screen.
         let mut lines = Vec::new();
         let mut line = String::new();
@@ -105,9 +138,61 @@ impl Message {
             }
         }
 
+        if let Some((last, _)) = buttons.last() {
+            let mut line = vec![' '; last.end];
+            for (columns, action) in &buttons {
+                line.splice(columns.clone(), action.label().chars());
+            }
+            lines.push(Self::pad_text(line.into_iter().collect(), num_cols));
+        }
+
         lines
     }
 
+    /// Columns of the action buttons shown in the last line.
+    ///
+    /// Buttons are hidden when there's not enough space for them.
+    pub fn buttons(&self, size_info: &SizeInfo) -> Vec<(Range<usize>, MessageAction)> {
+        let mut buttons = Vec::new();
+        if Self::max_lines(size_info) <= 1 {
+            return buttons;
+        }
+
+        let mut start = 0;
+        for action in &self.actions {
+            let end = start + action.label().len();
+            if end > size_info.columns() {
+                break;
+            }
+
+            buttons.push((start..end, *action));
+            start = end + ACTION_BUTTON_PADDING;
+        }
+        buttons
+    }
+
+    /// Action of the button at a position relative to the start of the message.
+    pub fn action_at(
+        &self,
+        size_info: &SizeInfo,
+        line: usize,
+        column: usize,
+    ) -> Option<MessageAction> {
+        if line + 1 != self.text(size_info).len() {
+            return None;
+        }
+
+        let mut buttons = self.buttons(size_info).into_iter();
+        buttons.find(|(columns, _)| columns.contains(&column)).map(|(_, action)| action)
+    }
+
+    /// Maximum number of lines used by a message.
+    fn max_lines(size_info: &SizeInfo) -> usize {
+        let total_lines =
+            (size_info.height() - 2. * size_info.padding_y()) / size_info.cell_height();
+        (total_lines as usize).saturating_sub(MIN_FREE_LINES)
+    }
+
     /// Message type.
     #[inline]
     pub fn ty(&self) -> MessageType {
@@ -176,6 +261,12 @@ impl MessageBuffer {
             .collect();
     }
 
+    /// Remove all messages.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
     /// Add a new message to the queue.
     #[inline]
     pub fn push(&mut self, message: Message) {
@@ -410,4 +501,33 @@ mod tests {
 
         assert_eq!(num_messages, 2);
     }
+
+    #[test]
+    fn action_buttons() {
+        let message = Message::new("foo".into(), MessageType::Error)
+            .with_action(MessageAction::OpenConfig)
+            .with_action(MessageAction::DismissAll);
+        let size = SizeInfo::new(30., 10., 1., 1., 0., 0., false);
+
+        let lines = message.text(&size);
+        assert_eq!(lines, vec![
+            String::from("foo                        [X]"),
+            String::from("[Open config] [Dismiss all]   "),
+        ]);
+
+        assert_eq!(message.action_at(&size, 1, 0), Some(MessageAction::OpenConfig));
+        assert_eq!(message.action_at(&size, 1, 13), None);
+        assert_eq!(message.action_at(&size, 1, 26), Some(MessageAction::DismissAll));
+        assert_eq!(message.action_at(&size, 0, 0), None);
+
+        // Buttons which don't fit are hidden.
+        let size = SizeInfo::new(20., 10., 1., 1., 0., 0., false);
+        assert_eq!(message.buttons(&size), vec![(0..13, MessageAction::OpenConfig)]);
+        assert_eq!(message.text(&size)[1], "[Open config]       ");
+
+        // Buttons are hidden without space for an additional line.
+        let size = SizeInfo::new(30., (MIN_FREE_LINES + 1) as f32, 1., 1., 0., 0., false);
+        assert_eq!(message.text(&size), vec![String::from("foo                        [X]")]);
+        assert_eq!(message.action_at(&size, 0, 0), None);
+    }
 }
@@ -0,0 +1,111 @@
+//! Notifications about commands which finished and other events in unfocused windows.
+
+use std::time::Duration;
+
+use crate::config::UiConfig;
+use crate::display::window::Window;
+
+/// Notify about a finished command, if it ran for long enough while the window was unfocused.
+///
+/// Commands without known `duration` are ignored.
+pub fn command_finished(
+    config: &UiConfig,
+    window: &Window,
+    focused: bool,
+    duration: Option<Duration>,
+    exit_code: Option<i32>,
+) {
+    let notifications = &config.notifications;
+    let duration = match duration {
+        Some(duration) if notifications.command_finished && !focused => duration,
+        _ => return,
+    };
+
+    if duration < notifications.command_threshold() {
+        return;
+    }
+
+    window.set_urgent(true);
+
+    if notifications.desktop {
+        let summary = match exit_code {
+            Some(0) => String::from("Command finished"),
+            Some(code) => format!("Command failed with status {code}"),
+            None => String::from("Command failed"),
+        };
+        let body = format!("{} ran for {}", window.title(), format_duration(duration));
+        show(summary, body);
+    }
+}
+
+/// Set the urgency hint for a bell, unless it's disabled by the config or the application.
+pub fn bell(config: &UiConfig, window: &Window, focused: bool, urgency_hints: bool) {
+    if config.notifications.bell && urgency_hints && !focused {
+        window.set_urgent(true);
+    }
+}
+
+/// Set the urgency hint for new output which arrived while scrolled back in the history.
+pub fn scrolled_output(config: &UiConfig, window: &Window, focused: bool, display_offset: usize) {
+    if config.notifications.scrolled_output && display_offset != 0 && !focused {
+        window.set_urgent(true);
+    }
+}
+
+/// Format a duration with its two most significant units.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..=59 => format!("{seconds}s"),
+        60..=3599 => format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {}m", seconds / 3600, seconds / 60 % 60),
+    }
+}
+
+/// Show a desktop notification through the XDG notification service.
+#[cfg(all(feature = "dbus", not(any(target_os = "macos", windows))))]
+fn show(summary: String, body: String) {
+    use std::collections::HashMap;
+
+    use log::warn;
+    use zbus::blocking::Connection;
+    use zbus::zvariant::Value;
+
+    use alacritty_terminal::thread;
+
+    // Avoid blocking the event loop while the notification service is starting.
+    thread::spawn_named("desktop notification", move || {
+        let result = Connection::session().and_then(|connection| {
+            let hints: HashMap<&str, Value<'_>> = HashMap::new();
+            connection.call_method(
+                Some("org.freedesktop.Notifications"),
+                "/org/freedesktop/Notifications",
+                Some("org.freedesktop.Notifications"),
+                "Notify",
+                &("Alacritty", 0u32, "Alacritty", summary, body, Vec::<&str>::new(), hints, -1i32),
+            )
+        });
+
+        if let Err(err) = result {
+            warn!("Unable to show desktop notification: {err}");
+        }
+    });
+}
+
+/// Show a desktop notification.
+///
+/// Only the urgency hint is used on platforms without notification support.
+#[cfg(not(all(feature = "dbus", not(any(target_os = "macos", windows)))))]
+fn show(_summary: String, _body: String) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_units() {
+        assert_eq!(format_duration(Duration::from_millis(12_500)), "12s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 5s");
+        assert_eq!(format_duration(Duration::from_secs(3 * 3600 + 61)), "3h 1m");
+    }
+}
@@ -0,0 +1,619 @@
+//! Split panes hosting independent terminals within a single window.
+
+use std::error::Error;
+use std::mem;
+#[cfg(not(windows))]
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Instant;
+
+use clap::ValueEnum;
+use crossfont::Size;
+use log::error;
+use serde::{Deserialize, Serialize};
+use winit::event_loop::EventLoopProxy;
+use winit::window::WindowId;
+
+use alacritty_config::SerdeReplace;
+use alacritty_terminal::config::PtyConfig;
+use alacritty_terminal::event_loop::{EventLoop as PtyEventLoop, Msg, Notifier};
+use alacritty_terminal::sync::FairMutex;
+use alacritty_terminal::term::Term;
+use alacritty_terminal::tty;
+
+use crate::config::{serde_utils, UiConfig};
+use crate::display::SizeInfo;
+use crate::event::{Event, EventProxy, SearchState};
+use crate::logging::LOG_TARGET_IPC_CONFIG;
+
+/// Smallest share of a split which can be assigned to one of its sides.
+const MIN_SPLIT_RATIO: f32 = 0.1;
+
+/// Config options which can be overridden for a single pane.
+const PANE_OPTIONS: [&str; 5] =
+    ["font.size", "colors", "window.padding", "window.dynamic_padding", "cursor"];
+
+/// Check if a config option like `colors.primary.background="#000000"` can be set per pane.
+pub fn is_pane_option(option: &str) -> bool {
+    let key = option.split('=').next().unwrap_or_default().trim();
+    PANE_OPTIONS.iter().any(|pane_option| {
+        key.strip_prefix(pane_option).map_or(false, |rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+/// Unique identifier of a pane within its window.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PaneId(usize);
+
+impl PaneId {
+    /// Identifier following this one.
+    pub fn next(self) -> Self {
+        Self(self.0 + 1)
+    }
+}
+
+/// Direction of a pane relative to another pane.
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum PaneDirection {
+    Left,
+    #[default]
+    Right,
+    Up,
+    Down,
+}
+
+impl PaneDirection {
+    /// Axis along which this direction moves.
+    fn axis(self) -> Axis {
+        match self {
+            Self::Left | Self::Right => Axis::Horizontal,
+            Self::Up | Self::Down => Axis::Vertical,
+        }
+    }
+
+    /// Whether this direction points towards the end of its axis.
+    fn is_forward(self) -> bool {
+        matches!(self, Self::Right | Self::Down)
+    }
+}
+
+/// Pane operations requested by bindings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaneAction {
+    /// Open a new pane next to the focused one, starting in the given working directory.
+    Split(PaneDirection, Option<PathBuf>),
+
+    /// Focus the closest pane in a direction.
+    Focus(PaneDirection),
+
+    /// Move the border of the focused pane in a direction.
+    Resize(PaneDirection),
+
+    /// Close the focused pane.
+    Close,
+
+    /// Override config options of the focused pane.
+    Config(Vec<String>),
+
+    /// Remove all config overrides of the focused pane.
+    ResetConfig,
+}
+
+/// Window receiving a pane moved out of its window.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PaneDestination {
+    /// Open the pane in a new window.
+    NewWindow,
+
+    /// Split the focused pane of the previously focused window.
+    LastFocused,
+
+    /// Split the focused pane of a specific window.
+    Window(WindowId),
+}
+
+/// Synchronized input of a window, mirroring keystrokes to other terminals.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Broadcast {
+    /// Write input to every pane of the window, instead of just the focused one.
+    pub panes: bool,
+
+    /// Name of the window group sharing its input.
+    pub group: Option<String>,
+}
+
+impl Broadcast {
+    /// Whether input of the window reaches any other terminal.
+    pub fn active(&self) -> bool {
+        self.panes || self.group.is_some()
+    }
+
+    /// Whether input of a window is shared with this window.
+    pub fn shares_group(&self, other: &Broadcast) -> bool {
+        self.group.is_some() && self.group == other.group
+    }
+}
+
+/// Area of a pane in physical pixels, relative to the top left corner of the window.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct PaneRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PaneRect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Check if a point is within the pane.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        let (x, y) = (x as f32, y as f32);
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Rect grown by `amount` on every side.
+    pub fn expand(&self, amount: f32) -> Self {
+        Self::new(
+            self.x - amount,
+            self.y - amount,
+            self.width + 2. * amount,
+            self.height + 2. * amount,
+        )
+    }
+
+    /// Length of the overlap with another rect along the given axis.
+    fn overlap(&self, other: &Self, axis: Axis) -> f32 {
+        let (start, end, other_start, other_end) = match axis {
+            Axis::Horizontal => (self.x, self.x + self.width, other.x, other.x + other.width),
+            Axis::Vertical => (self.y, self.y + self.height, other.y, other.y + other.height),
+        };
+
+        end.min(other_end) - start.max(other_start)
+    }
+}
+
+/// Axis along which a split divides its area.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Axis {
+    /// Panes are placed next to each other.
+    Horizontal,
+
+    /// Panes are placed above each other.
+    Vertical,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Pane(PaneId),
+    Split { axis: Axis, ratio: f32, first: Box<Node>, second: Box<Node> },
+}
+
+impl Node {
+    fn rects(&self, area: PaneRect, gap: f32, rects: &mut Vec<(PaneId, PaneRect)>) {
+        let (axis, ratio, first, second) = match self {
+            Node::Pane(id) => {
+                rects.push((*id, area));
+                return;
+            },
+            Node::Split { axis, ratio, first, second } => (*axis, *ratio, first, second),
+        };
+
+        let (first_area, second_area) = match axis {
+            Axis::Horizontal => {
+                let available = (area.width - gap).max(0.);
+                let width = (available * ratio).round();
+                let second_x = area.x + width + gap;
+                (
+                    PaneRect::new(area.x, area.y, width, area.height),
+                    PaneRect::new(second_x, area.y, available - width, area.height),
+                )
+            },
+            Axis::Vertical => {
+                let available = (area.height - gap).max(0.);
+                let height = (available * ratio).round();
+                let second_y = area.y + height + gap;
+                (
+                    PaneRect::new(area.x, area.y, area.width, height),
+                    PaneRect::new(area.x, second_y, area.width, available - height),
+                )
+            },
+        };
+
+        first.rects(first_area, gap, rects);
+        second.rects(second_area, gap, rects);
+    }
+
+    /// Replace the pane `target` with a split containing it and `new`.
+    fn split(&mut self, target: PaneId, new: PaneId, direction: PaneDirection) -> bool {
+        match self {
+            Node::Pane(id) if *id == target => {
+                let (first, second) =
+                    if direction.is_forward() { (target, new) } else { (new, target) };
+                *self = Node::Split {
+                    axis: direction.axis(),
+                    ratio: 0.5,
+                    first: Box::new(Node::Pane(first)),
+                    second: Box::new(Node::Pane(second)),
+                };
+                true
+            },
+            Node::Pane(_) => false,
+            Node::Split { first, second, .. } => {
+                first.split(target, new, direction) || second.split(target, new, direction)
+            },
+        }
+    }
+
+    /// Remove the pane `target`, giving its area to its sibling.
+    fn remove(&mut self, target: PaneId) -> bool {
+        let (first, second) = match self {
+            Node::Pane(_) => return false,
+            Node::Split { first, second, .. } => (first, second),
+        };
+
+        let sibling = if **first == Node::Pane(target) {
+            mem::replace(&mut **second, Node::Pane(target))
+        } else if **second == Node::Pane(target) {
+            mem::replace(&mut **first, Node::Pane(target))
+        } else {
+            return first.remove(target) || second.remove(target);
+        };
+
+        *self = sibling;
+        true
+    }
+
+    /// Move the closest divider along `axis` in the ancestry of `target`.
+    ///
+    /// Returns `None` if `target` is not part of this node, otherwise whether a divider was moved.
+    fn resize(&mut self, target: PaneId, axis: Axis, delta: f32) -> Option<bool> {
+        match self {
+            Node::Pane(id) => (*id == target).then_some(false),
+            Node::Split { axis: split_axis, ratio, first, second } => {
+                let resized = match first.resize(target, axis, delta) {
+                    Some(resized) => resized,
+                    None => second.resize(target, axis, delta)?,
+                };
+
+                if !resized && *split_axis == axis {
+                    *ratio = (*ratio + delta).clamp(MIN_SPLIT_RATIO, 1. - MIN_SPLIT_RATIO);
+                    return Some(true);
+                }
+
+                Some(resized)
+            },
+        }
+    }
+}
+
+/// Arrangement of all panes within a window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaneLayout {
+    root: Node,
+}
+
+impl PaneLayout {
+    pub fn new(pane: PaneId) -> Self {
+        Self { root: Node::Pane(pane) }
+    }
+
+    /// Check if the window contains more than one pane.
+    pub fn is_split(&self) -> bool {
+        matches!(self.root, Node::Split { .. })
+    }
+
+    /// Divide the area of `target` between it and the new pane `new`.
+    ///
+    /// The new pane is placed in `direction` of the existing one.
+    pub fn split(&mut self, target: PaneId, new: PaneId, direction: PaneDirection) {
+        self.root.split(target, new, direction);
+    }
+
+    /// Remove a pane from the layout.
+    ///
+    /// Returns `false` if the pane is the last one in the layout.
+    pub fn remove(&mut self, target: PaneId) -> bool {
+        self.root.remove(target)
+    }
+
+    /// Move the border of `target` in `direction` by a fraction of its split's area.
+    pub fn resize(&mut self, target: PaneId, direction: PaneDirection, step: f32) {
+        let delta = if direction.is_forward() { step } else { -step };
+        self.root.resize(target, direction.axis(), delta);
+    }
+
+    /// Area of every pane, leaving `gap` pixels between neighboring panes.
+    pub fn rects(&self, area: PaneRect, gap: f32) -> Vec<(PaneId, PaneRect)> {
+        let mut rects = Vec::new();
+        self.root.rects(area, gap, &mut rects);
+        rects
+    }
+
+    /// Closest pane in `direction` of `origin`.
+    pub fn neighbor(
+        &self,
+        origin: PaneId,
+        direction: PaneDirection,
+        area: PaneRect,
+        gap: f32,
+    ) -> Option<PaneId> {
+        let rects = self.rects(area, gap);
+        let (_, origin) = rects.iter().find(|(id, _)| *id == origin)?;
+
+        // Only panes sharing an edge with the origin are candidates.
+        let candidate = |rect: &PaneRect| -> Option<(f32, f32)> {
+            let distance = match direction {
+                PaneDirection::Left => origin.x - (rect.x + rect.width),
+                PaneDirection::Right => rect.x - (origin.x + origin.width),
+                PaneDirection::Up => origin.y - (rect.y + rect.height),
+                PaneDirection::Down => rect.y - (origin.y + origin.height),
+            };
+            let overlap = rect.overlap(origin, direction.axis().perpendicular());
+
+            (distance >= 0. && overlap > 0.).then_some((distance, overlap))
+        };
+
+        // Prefer the closest pane, then the one sharing the longest edge.
+        rects
+            .iter()
+            .filter_map(|(id, rect)| Some((*id, candidate(rect)?)))
+            .min_by(|(_, (distance, overlap)), (_, (other_distance, other_overlap))| {
+                distance.total_cmp(other_distance).then_with(|| other_overlap.total_cmp(overlap))
+            })
+            .map(|(id, _)| id)
+    }
+}
+
+impl Axis {
+    fn perpendicular(self) -> Self {
+        match self {
+            Axis::Horizontal => Axis::Vertical,
+            Axis::Vertical => Axis::Horizontal,
+        }
+    }
+}
+
+/// Terminal hosted in one pane of a window.
+pub struct Pane {
+    pub id: PaneId,
+    pub terminal: Arc<FairMutex<Term<EventProxy>>>,
+    pub notifier: Notifier,
+    pub event_proxy: EventProxy,
+    pub search_state: SearchState,
+    pub child_exited: bool,
+
+    /// Time at which the pane's process was started.
+    pub spawned: Instant,
+
+    /// Area covered by the pane while it is not focused.
+    pub rect: PaneRect,
+
+    /// Dimensions of the terminal while the pane is not focused.
+    pub size_info: SizeInfo,
+
+    /// Config options overriding the window's config for this pane.
+    pub config_overrides: Vec<toml::Value>,
+
+    /// Config of the window with the pane's overrides applied.
+    pub config: Rc<UiConfig>,
+
+    /// Font size of the pane, if it doesn't use the window's font size.
+    pub font_size: Option<Size>,
+
+    #[cfg(not(windows))]
+    pub master_fd: RawFd,
+    #[cfg(not(windows))]
+    pub shell_pid: u32,
+}
+
+impl Pane {
+    /// Spawn a shell in a new pane.
+    pub fn spawn(
+        id: PaneId,
+        config: &Rc<UiConfig>,
+        pty_config: &PtyConfig,
+        size_info: SizeInfo,
+        proxy: EventLoopProxy<Event>,
+        window_id: WindowId,
+    ) -> Result<Self, Box<dyn Error>> {
+        let event_proxy = EventProxy::new(proxy, window_id, id);
+
+        let terminal = Term::new(&config.terminal_config, &size_info, event_proxy.clone());
+        let terminal = Arc::new(FairMutex::new(terminal));
+
+        let pty = tty::new(pty_config, size_info.into(), window_id.into())?;
+
+        #[cfg(not(windows))]
+        let (master_fd, shell_pid) = (pty.file().as_raw_fd(), pty.child().id());
+
+        let mut event_loop = PtyEventLoop::new(
+            Arc::clone(&terminal),
+            event_proxy.clone(),
+            pty,
+            pty_config.hold,
+            pty_config.hold_on_error,
+            config.debug.ref_test,
+        );
+
+        let pty_limits = &config.terminal_config.terminal;
+        event_loop.set_read_limits(pty_limits.pty_read_buffer, pty_limits.pty_chunk_size);
+
+        let notifier = Notifier(event_loop.channel());
+        let _io_thread = event_loop.spawn();
+
+        Ok(Self {
+            id,
+            terminal,
+            notifier,
+            event_proxy,
+            size_info,
+            config: config.clone(),
+            #[cfg(not(windows))]
+            master_fd,
+            #[cfg(not(windows))]
+            shell_pid,
+            search_state: Default::default(),
+            child_exited: Default::default(),
+            spawned: Instant::now(),
+            rect: Default::default(),
+            config_overrides: Default::default(),
+            font_size: Default::default(),
+        })
+    }
+
+    /// Apply the pane's config overrides on top of its window's config.
+    pub fn update_config(&mut self, window_config: &Rc<UiConfig>) {
+        self.config = window_config.clone();
+        self.font_size = None;
+
+        if !self.config_overrides.is_empty() {
+            let mut config = (**window_config).clone();
+
+            // Apply each option, removing broken ones.
+            self.config_overrides.retain(|option| match config.replace(option.clone()) {
+                Ok(_) => true,
+                Err(err) => {
+                    error!(
+                        target: LOG_TARGET_IPC_CONFIG,
+                        "Unable to override option '{}': {}", option, err
+                    );
+                    false
+                },
+            });
+
+            let sets_font_size =
+                |option: &toml::Value| serde_utils::get_option(option, "font.size").is_some();
+            if self.config_overrides.iter().any(sets_font_size) {
+                self.font_size = Some(config.font.size());
+            }
+
+            self.config = Rc::new(config);
+        }
+
+        self.terminal.lock().update_config(&self.config.terminal_config);
+    }
+}
+
+impl Drop for Pane {
+    fn drop(&mut self) {
+        // Shutdown the terminal's PTY.
+        let _ = self.notifier.0.send(Msg::Shutdown);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: PaneRect = PaneRect { x: 0., y: 0., width: 100., height: 50. };
+
+    #[test]
+    fn split_rects() {
+        let mut layout = PaneLayout::new(PaneId(0));
+        assert!(!layout.is_split());
+        assert_eq!(layout.rects(AREA, 2.), vec![(PaneId(0), AREA)]);
+
+        layout.split(PaneId(0), PaneId(1), PaneDirection::Right);
+        layout.split(PaneId(1), PaneId(2), PaneDirection::Down);
+        assert!(layout.is_split());
+
+        assert_eq!(layout.rects(AREA, 2.), vec![
+            (PaneId(0), PaneRect::new(0., 0., 49., 50.)),
+            (PaneId(1), PaneRect::new(51., 0., 49., 24.)),
+            (PaneId(2), PaneRect::new(51., 26., 49., 24.)),
+        ]);
+    }
+
+    #[test]
+    fn split_before() {
+        let mut layout = PaneLayout::new(PaneId(0));
+        layout.split(PaneId(0), PaneId(1), PaneDirection::Up);
+
+        assert_eq!(layout.rects(AREA, 0.), vec![
+            (PaneId(1), PaneRect::new(0., 0., 100., 25.)),
+            (PaneId(0), PaneRect::new(0., 25., 100., 25.)),
+        ]);
+    }
+
+    #[test]
+    fn remove_pane() {
+        let mut layout = PaneLayout::new(PaneId(0));
+        layout.split(PaneId(0), PaneId(1), PaneDirection::Right);
+        layout.split(PaneId(1), PaneId(2), PaneDirection::Down);
+
+        assert!(layout.remove(PaneId(1)));
+        assert_eq!(layout.rects(AREA, 0.), vec![
+            (PaneId(0), PaneRect::new(0., 0., 50., 50.)),
+            (PaneId(2), PaneRect::new(50., 0., 50., 50.)),
+        ]);
+
+        assert!(layout.remove(PaneId(0)));
+        assert!(!layout.remove(PaneId(2)));
+        assert_eq!(layout.rects(AREA, 0.), vec![(PaneId(2), AREA)]);
+    }
+
+    #[test]
+    fn resize_closest_divider() {
+        let mut layout = PaneLayout::new(PaneId(0));
+        layout.split(PaneId(0), PaneId(1), PaneDirection::Right);
+        layout.split(PaneId(1), PaneId(2), PaneDirection::Down);
+
+        layout.resize(PaneId(2), PaneDirection::Left, 0.2);
+        layout.resize(PaneId(2), PaneDirection::Up, 0.9);
+
+        assert_eq!(layout.rects(AREA, 0.), vec![
+            (PaneId(0), PaneRect::new(0., 0., 30., 50.)),
+            (PaneId(1), PaneRect::new(30., 0., 70., 5.)),
+            (PaneId(2), PaneRect::new(30., 5., 70., 45.)),
+        ]);
+    }
+
+    #[test]
+    fn neighbors() {
+        let mut layout = PaneLayout::new(PaneId(0));
+        layout.split(PaneId(0), PaneId(1), PaneDirection::Right);
+        layout.split(PaneId(1), PaneId(2), PaneDirection::Down);
+
+        let neighbor = |id, direction| layout.neighbor(PaneId(id), direction, AREA, 2.);
+        assert_eq!(neighbor(0, PaneDirection::Right), Some(PaneId(1)));
+        assert_eq!(neighbor(0, PaneDirection::Left), None);
+        assert_eq!(neighbor(2, PaneDirection::Up), Some(PaneId(1)));
+        assert_eq!(neighbor(2, PaneDirection::Left), Some(PaneId(0)));
+        assert_eq!(neighbor(1, PaneDirection::Down), Some(PaneId(2)));
+        assert_eq!(neighbor(1, PaneDirection::Right), None);
+    }
+
+    #[test]
+    fn pane_options() {
+        assert!(is_pane_option("font.size=8"));
+        assert!(is_pane_option("colors.primary.background=\"#000000\""));
+        assert!(is_pane_option("colors={ primary = { foreground = \"#ffffff\" } }"));
+        assert!(is_pane_option(" window.padding.x = 2"));
+        assert!(is_pane_option("cursor.animation=false"));
+
+        assert!(!is_pane_option("font.normal.family=\"Fira Code\""));
+        assert!(!is_pane_option("font={ size = 8 }"));
+        assert!(!is_pane_option("window.opacity=0.5"));
+        assert!(!is_pane_option("cursorline=true"));
+    }
+
+    #[test]
+    fn broadcast_groups() {
+        let panes = Broadcast { panes: true, group: None };
+        let servers = Broadcast { panes: false, group: Some(String::from("servers")) };
+        let other = Broadcast { panes: true, group: Some(String::from("other")) };
+
+        assert!(!Broadcast::default().active());
+        assert!(panes.active());
+        assert!(servers.active());
+
+        assert!(servers.shares_group(&servers.clone()));
+        assert!(!servers.shares_group(&other));
+        assert!(!panes.shares_group(&Broadcast::default()));
+    }
+}
@@ -0,0 +1,155 @@
+//! Detection of the system's power source.
+
+#[cfg(target_os = "linux")]
+use std::fs;
+use std::time::Duration;
+
+use winit::event_loop::EventLoopProxy;
+
+use alacritty_terminal::thread;
+
+use crate::event::{Event, EventType};
+
+/// Interval between checks of the power source.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Watch the power source, notifying the event loop when the system switches between AC and
+/// battery power.
+pub fn watch(event_proxy: EventLoopProxy<Event>) {
+    thread::spawn_named("power monitor", move || {
+        let mut was_on_battery = false;
+
+        loop {
+            let on_battery = on_battery();
+            if on_battery != was_on_battery {
+                was_on_battery = on_battery;
+
+                let event = Event::new(EventType::PowerSourceChanged(on_battery), None);
+                if event_proxy.send_event(event).is_err() {
+                    break;
+                }
+            }
+
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// Check if the system is running on battery power.
+///
+/// This reads the same power supply information used by upower.
+#[cfg(target_os = "linux")]
+fn on_battery() -> bool {
+    let supplies = match fs::read_dir("/sys/class/power_supply") {
+        Ok(supplies) => supplies,
+        Err(_) => return false,
+    };
+
+    let mut discharging = false;
+    for supply in supplies.flatten() {
+        let path = supply.path();
+        let read = |name: &str| fs::read_to_string(path.join(name)).unwrap_or_default();
+
+        match read("type").trim() {
+            "Mains" | "USB" if read("online").trim() == "1" => return false,
+            // Ignore batteries of peripherals like mice and keyboards.
+            "Battery" if read("scope").trim() != "Device" => {
+                discharging |= read("status").trim() == "Discharging";
+            },
+            _ => (),
+        }
+    }
+
+    discharging
+}
+
+/// Check if the system is running on battery power.
+#[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
+fn on_battery() -> bool {
+    let mut acline: libc::c_int = 1;
+    let mut size = std::mem::size_of::<libc::c_int>();
+    let name = b"hw.acpi.acline\0";
+
+    let result = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr().cast(),
+            (&mut acline as *mut libc::c_int).cast(),
+            &mut size,
+            std::ptr::null(),
+            0,
+        )
+    };
+
+    result == 0 && acline == 0
+}
+
+/// Check if the system is running on battery power.
+#[cfg(target_os = "macos")]
+fn on_battery() -> bool {
+    use std::ffi::{c_char, c_void, CStr};
+
+    /// Power source type reported while running on battery.
+    const BATTERY_POWER: &[u8] = b"Battery Power";
+
+    /// CoreFoundation's `kCFStringEncodingUTF8`.
+    const UTF8_ENCODING: u32 = 0x0800_0100;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPSCopyPowerSourcesInfo() -> *const c_void;
+        fn IOPSGetProvidingPowerSourceType(snapshot: *const c_void) -> *const c_void;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: *const c_void);
+        fn CFStringGetCString(
+            string: *const c_void,
+            buffer: *mut c_char,
+            buffer_size: isize,
+            encoding: u32,
+        ) -> u8;
+    }
+
+    unsafe {
+        let snapshot = IOPSCopyPowerSourcesInfo();
+        if snapshot.is_null() {
+            return false;
+        }
+
+        // The source type follows the get rule, so it's released with the snapshot.
+        let source_type = IOPSGetProvidingPowerSourceType(snapshot);
+        let mut buffer = [0 as c_char; 32];
+        let (buffer_ptr, buffer_size) = (buffer.as_mut_ptr(), buffer.len() as isize);
+        let converted = !source_type.is_null()
+            && CFStringGetCString(source_type, buffer_ptr, buffer_size, UTF8_ENCODING) != 0;
+
+        CFRelease(snapshot);
+
+        converted && CStr::from_ptr(buffer.as_ptr()).to_bytes() == BATTERY_POWER
+    }
+}
+
+/// Check if the system is running on battery power.
+#[cfg(windows)]
+fn on_battery() -> bool {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    let result = unsafe { GetSystemPowerStatus(&mut status) };
+
+    // The AC line status is `1` when online and `255` when unknown.
+    result != 0 && status.ACLineStatus == 0
+}
+
+/// Check if the system is running on battery power.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "macos",
+    windows
+)))]
+fn on_battery() -> bool {
+    false
+}
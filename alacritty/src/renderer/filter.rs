@@ -0,0 +1,333 @@
+//! Color vision deficiency filters.
+//!
+//! Frames are rendered into an offscreen texture, which is then drawn to the window with its
+//! colors transformed by a single matrix in linear RGB.
+
+use std::{mem, ptr};
+
+use crate::config::color::ColorFilter;
+use crate::gl;
+use crate::gl::types::*;
+use crate::renderer::shader::{ShaderError, ShaderProgram, ShaderVersion};
+use crate::renderer::{self, cstr};
+
+/// Shader sources for the filter program.
+static FILTER_SHADER_F: &str = include_str!("../../res/filter.f.glsl");
+static FILTER_SHADER_V: &str = include_str!("../../res/filter.v.glsl");
+
+/// Corner positions of a quad covering the entire viewport, drawn as triangle strip.
+const VERTICES: [f32; 8] = [-1., -1., 1., -1., -1., 1., 1., 1.];
+
+/// Color vision simulation matrices for a severity of 1.0.
+///
+/// From "A Physiologically-based Model for Simulation of Color Vision Deficiency" by Machado,
+/// Oliveira and Fernandes.
+const PROTANOPIA: Matrix =
+    [[0.152_286, 1.052_583, -0.204_868], [0.114_503, 0.786_281, 0.099_216], [
+        -0.003_882, -0.048_116, 1.051_998,
+    ]];
+const DEUTERANOPIA: Matrix =
+    [[0.367_322, 0.860_646, -0.227_968], [0.280_085, 0.672_501, 0.047_413], [
+        -0.011_820, 0.042_940, 0.968_881,
+    ]];
+const TRITANOPIA: Matrix =
+    [[1.255_528, -0.076_749, -0.178_779], [-0.078_411, 0.930_809, 0.147_602], [
+        0.004_733, 0.691_367, 0.303_900,
+    ]];
+
+/// Redistribution of the color information lost to a deficiency onto visible channels.
+const ERROR_SHIFT: Matrix = [[0., 0., 0.], [0.7, 1., 0.], [0.7, 0., 1.]];
+
+/// Row-major 3x3 color matrix.
+pub type Matrix = [[f32; 3]; 3];
+
+/// Linear RGB transformation applied by a filter.
+///
+/// Returns `None` if the filter leaves colors unchanged.
+pub fn color_matrix(filter: ColorFilter) -> Option<Matrix> {
+    let matrix = match filter {
+        ColorFilter::None => return None,
+        ColorFilter::Protanopia => PROTANOPIA,
+        ColorFilter::Deuteranopia => DEUTERANOPIA,
+        ColorFilter::Tritanopia => TRITANOPIA,
+        ColorFilter::ProtanopiaCorrection => daltonize(&PROTANOPIA),
+        ColorFilter::DeuteranopiaCorrection => daltonize(&DEUTERANOPIA),
+        ColorFilter::TritanopiaCorrection => daltonize(&TRITANOPIA),
+    };
+    Some(matrix)
+}
+
+/// Correction for a deficiency's `simulation`.
+///
+/// The difference between the original and the simulated color is shifted onto the channels
+/// which can still be told apart, which results in `I + SHIFT * (I - simulation)`.
+fn daltonize(simulation: &Matrix) -> Matrix {
+    let mut matrix = [[0.; 3]; 3];
+    for (row, values) in matrix.iter_mut().enumerate() {
+        for (column, value) in values.iter_mut().enumerate() {
+            let error = (0..3).map(|i| {
+                let identity = if i == column { 1. } else { 0. };
+                ERROR_SHIFT[row][i] * (identity - simulation[i][column])
+            });
+            *value = error.sum::<f32>() + if row == column { 1. } else { 0. };
+        }
+    }
+    matrix
+}
+
+#[derive(Debug)]
+pub struct FilterRenderer {
+    // GL buffer objects.
+    vao: GLuint,
+    vbo: GLuint,
+
+    /// Offscreen target of the unfiltered frame.
+    framebuffer: GLuint,
+    texture: GLuint,
+
+    /// Size of the offscreen texture.
+    size: (i32, i32),
+
+    /// Color transformation of the frame currently drawn to the offscreen target.
+    active_matrix: Option<Matrix>,
+
+    program: FilterShaderProgram,
+}
+
+impl FilterRenderer {
+    /// Create a new filter renderer.
+    ///
+    /// The shader program of the `shared` renderer will be reused when it is present.
+    pub fn new(
+        shader_version: ShaderVersion,
+        shared: Option<&FilterRenderer>,
+    ) -> Result<Self, renderer::Error> {
+        let mut vao: GLuint = 0;
+        let mut vbo: GLuint = 0;
+
+        let program = match shared {
+            Some(shared) => shared.program.clone(),
+            None => FilterShaderProgram::new(shader_version)?,
+        };
+
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                mem::size_of_val(&VERTICES) as isize,
+                VERTICES.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+
+            // Position.
+            gl::VertexAttribPointer(
+                0,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                (mem::size_of::<f32>() * 2) as i32,
+                ptr::null(),
+            );
+            gl::EnableVertexAttribArray(0);
+
+            // Reset buffer bindings.
+            gl::BindVertexArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+
+        Ok(Self {
+            vao,
+            vbo,
+            framebuffer: 0,
+            texture: 0,
+            size: (0, 0),
+            active_matrix: None,
+            program,
+        })
+    }
+
+    /// Redirect all drawing to the offscreen target, until it's drawn with its colors transformed
+    /// by `matrix`.
+    pub fn begin(&mut self, width: i32, height: i32, matrix: Matrix) {
+        let size = (width, height);
+        if self.framebuffer == 0 {
+            unsafe {
+                gl::GenFramebuffers(1, &mut self.framebuffer);
+                gl::GenTextures(1, &mut self.texture);
+            }
+        }
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+
+            // Reallocate the texture when the window was resized.
+            if self.size != size {
+                self.size = size;
+
+                gl::BindTexture(gl::TEXTURE_2D, self.texture);
+                gl::TexImage2D(
+                    gl::TEXTURE_2D,
+                    0,
+                    gl::RGBA as i32,
+                    size.0,
+                    size.1,
+                    0,
+                    gl::RGBA,
+                    gl::UNSIGNED_BYTE,
+                    ptr::null(),
+                );
+
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+
+                gl::FramebufferTexture2D(
+                    gl::FRAMEBUFFER,
+                    gl::COLOR_ATTACHMENT0,
+                    gl::TEXTURE_2D,
+                    self.texture,
+                    0,
+                );
+
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+            }
+        }
+
+        self.active_matrix = Some(matrix);
+    }
+
+    /// Draw the offscreen target to the window.
+    pub fn end(&mut self) {
+        let matrix = match self.active_matrix.take() {
+            Some(matrix) => matrix,
+            None => return,
+        };
+
+        // OpenGL expects column-major matrices.
+        let mut columns = [0.; 9];
+        for (row, values) in matrix.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                columns[column * 3 + row] = *value;
+            }
+        }
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, self.size.0, self.size.1);
+
+            // Replace the window's content, including its alpha.
+            gl::Disable(gl::BLEND);
+
+            gl::UseProgram(self.program.id());
+            gl::Uniform1i(self.program.u_frame, 0);
+            gl::UniformMatrix3fv(self.program.u_color_matrix, 1, gl::FALSE, columns.as_ptr());
+
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::BindVertexArray(self.vao);
+
+            gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+
+            // Reset state.
+            gl::BindVertexArray(0);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::UseProgram(0);
+            gl::Enable(gl::BLEND);
+        }
+    }
+}
+
+impl Drop for FilterRenderer {
+    fn drop(&mut self) {
+        unsafe {
+            if self.framebuffer != 0 {
+                gl::DeleteFramebuffers(1, &self.framebuffer);
+                gl::DeleteTextures(1, &self.texture);
+            }
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
+
+/// Color filter program.
+#[derive(Clone, Debug)]
+struct FilterShaderProgram {
+    /// Shader program.
+    program: ShaderProgram,
+
+    /// Texture unit of the unfiltered frame.
+    u_frame: GLint,
+
+    /// Color transformation matrix.
+    u_color_matrix: GLint,
+}
+
+impl FilterShaderProgram {
+    fn new(shader_version: ShaderVersion) -> Result<Self, ShaderError> {
+        let program = ShaderProgram::new(shader_version, None, FILTER_SHADER_V, FILTER_SHADER_F)?;
+
+        Ok(Self {
+            u_frame: program.get_uniform_location(cstr!("frame"))?,
+            u_color_matrix: program.get_uniform_location(cstr!("colorMatrix"))?,
+            program,
+        })
+    }
+
+    fn id(&self) -> GLuint {
+        self.program.id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(matrix: &Matrix, color: [f32; 3]) -> [f32; 3] {
+        matrix.map(|row| row.iter().zip(color).map(|(factor, value)| factor * value).sum())
+    }
+
+    #[test]
+    fn filters_preserve_grays() {
+        let filters = [
+            ColorFilter::Protanopia,
+            ColorFilter::Deuteranopia,
+            ColorFilter::Tritanopia,
+            ColorFilter::ProtanopiaCorrection,
+            ColorFilter::DeuteranopiaCorrection,
+            ColorFilter::TritanopiaCorrection,
+        ];
+
+        assert_eq!(color_matrix(ColorFilter::None), None);
+
+        for filter in filters {
+            let matrix = color_matrix(filter).unwrap();
+            for value in transform(&matrix, [0.5; 3]) {
+                assert!((value - 0.5).abs() < 1e-3, "{filter:?} changed gray to {value}");
+            }
+        }
+    }
+
+    #[test]
+    fn correction_separates_red_and_green() {
+        let red = [1., 0., 0.];
+        let green = [0., 1., 0.];
+
+        // Compare the blue channel protanopes see, with and without correction.
+        let corrected = color_matrix(ColorFilter::ProtanopiaCorrection).unwrap();
+        let seen = |color| transform(&PROTANOPIA, color)[2];
+        let seen_corrected = |color| {
+            transform(&PROTANOPIA, transform(&corrected, color).map(|v| v.clamp(0., 1.)))[2]
+        };
+
+        let difference = (seen(red) - seen(green)).abs();
+        let corrected_difference = (seen_corrected(red) - seen_corrected(green)).abs();
+        assert!(corrected_difference > difference * 10.);
+    }
+}
@@ -7,6 +7,7 @@ use ahash::RandomState;
 use crossfont::Metrics;
 use glutin::context::{ContextApi, GlContext, PossiblyCurrentContext};
 use glutin::display::{GetGlDisplay, GlDisplay};
+use glutin::surface::Rect;
 use log::{debug, error, info, warn, LevelFilter};
 use once_cell::sync::OnceCell;
 use unicode_width::UnicodeWidthChar;
@@ -15,19 +16,27 @@ use alacritty_terminal::index::Point;
 use alacritty_terminal::term::cell::Flags;
 use alacritty_terminal::term::color::Rgb;
 
+use crate::config::color::ColorFilter;
 use crate::config::debug::RendererPreference;
 use crate::display::content::RenderableCell;
 use crate::display::SizeInfo;
 use crate::gl;
+use crate::renderer::filter::FilterRenderer;
 use crate::renderer::quads::{QuadRenderer, RenderQuad};
 use crate::renderer::rects::{RectRenderer, RenderRect};
+use crate::renderer::region::{RegionRenderer, RenderRegion};
 use crate::renderer::shader::ShaderError;
+use crate::renderer::timer::{GpuTimer, PassTimes, RenderPass};
 
+mod filter;
 pub mod platform;
+mod program_cache;
 pub mod quads;
 pub mod rects;
+pub mod region;
 mod shader;
 mod text;
+pub mod timer;
 
 pub use text::{GlyphCache, LoaderApi};
 
@@ -86,6 +95,14 @@ pub struct Renderer {
     text_renderer: TextRendererProvider,
     rect_renderer: RectRenderer,
     quad_renderer: QuadRenderer,
+    region_renderer: RegionRenderer,
+    filter_renderer: FilterRenderer,
+
+    /// GPU timer for render passes, when timer queries are supported.
+    gpu_timer: Option<GpuTimer>,
+
+    /// Bottom-left corner of the drawn area, relative to the bottom-left corner of the window.
+    viewport_origin: (i32, i32),
 }
 
 impl Renderer {
@@ -93,9 +110,13 @@ impl Renderer {
     ///
     /// This will automatically pick between the GLES2 and GLSL3 renderer based on the GPU's
     /// supported OpenGL version.
+    ///
+    /// When `shared` is present, its context must be sharing objects with `context`, which allows
+    /// reusing its shader programs instead of compiling them again.
     pub fn new(
         context: &PossiblyCurrentContext,
         renderer_prefernce: Option<RendererPreference>,
+        shared: Option<&Renderer>,
     ) -> Result<Self, Error> {
         // We need to load OpenGL functions once per instance, but only after we make our context
         // current due to WGL limitations.
@@ -129,18 +150,33 @@ impl Renderer {
             None => (shader_version.as_ref() >= "3.3" && !is_gles_context, true),
         };
 
-        let (text_renderer, rect_renderer, quad_renderer) = if use_glsl3 {
-            let text_renderer = TextRendererProvider::Glsl3(Glsl3Renderer::new()?);
-            let rect_renderer = RectRenderer::new(ShaderVersion::Glsl3)?;
-            let quad_renderer = QuadRenderer::new(ShaderVersion::Glsl3)?;
-            (text_renderer, rect_renderer, quad_renderer)
-        } else {
-            let text_renderer =
-                TextRendererProvider::Gles2(Gles2Renderer::new(allow_dsb, is_gles_context)?);
-            let rect_renderer = RectRenderer::new(ShaderVersion::Gles2)?;
-            let quad_renderer = QuadRenderer::new(ShaderVersion::Gles2)?;
-            (text_renderer, rect_renderer, quad_renderer)
+        let shader_version = if use_glsl3 { ShaderVersion::Glsl3 } else { ShaderVersion::Gles2 };
+
+        // Programs can only be reused when they were built for the same shader version.
+        let shared = shared.filter(|shared| shared.shader_version() == shader_version);
+
+        let text_renderer = match shared.map(|shared| &shared.text_renderer) {
+            Some(TextRendererProvider::Glsl3(shared)) => {
+                TextRendererProvider::Glsl3(Glsl3Renderer::new(Some(shared))?)
+            },
+            Some(TextRendererProvider::Gles2(shared)) => {
+                let renderer = Gles2Renderer::new(allow_dsb, is_gles_context, Some(shared))?;
+                TextRendererProvider::Gles2(renderer)
+            },
+            None if use_glsl3 => TextRendererProvider::Glsl3(Glsl3Renderer::new(None)?),
+            None => {
+                let renderer = Gles2Renderer::new(allow_dsb, is_gles_context, None)?;
+                TextRendererProvider::Gles2(renderer)
+            },
         };
+        let rect_renderer =
+            RectRenderer::new(shader_version, shared.map(|shared| &shared.rect_renderer))?;
+        let quad_renderer =
+            QuadRenderer::new(shader_version, shared.map(|shared| &shared.quad_renderer))?;
+        let region_renderer =
+            RegionRenderer::new(shader_version, shared.map(|shared| &shared.region_renderer))?;
+        let filter_renderer =
+            FilterRenderer::new(shader_version, shared.map(|shared| &shared.filter_renderer))?;
 
         // Enable debug logging for OpenGL as well.
         if log::max_level() >= LevelFilter::Debug && GlExtensions::contains("GL_KHR_debug") {
@@ -152,7 +188,28 @@ impl Renderer {
             }
         }
 
-        Ok(Self { text_renderer, rect_renderer, quad_renderer })
+        // Timer queries are core since OpenGL 3.3, but not available in GLES2 contexts.
+        let gpu_timer = (!is_gles_context
+            && (gl_version.as_ref() >= "3.3" || GlExtensions::contains("GL_ARB_timer_query")))
+        .then(GpuTimer::new);
+
+        Ok(Self {
+            text_renderer,
+            rect_renderer,
+            quad_renderer,
+            region_renderer,
+            filter_renderer,
+            gpu_timer,
+            viewport_origin: (0, 0),
+        })
+    }
+
+    /// Shader version used by the renderer's programs.
+    fn shader_version(&self) -> ShaderVersion {
+        match self.text_renderer {
+            TextRendererProvider::Glsl3(_) => ShaderVersion::Glsl3,
+            TextRendererProvider::Gles2(_) => ShaderVersion::Gles2,
+        }
     }
 
     pub fn draw_cells<I: Iterator<Item = RenderableCell>>(
@@ -222,7 +279,7 @@ impl Renderer {
     }
 
     /// Draw all rectangles simultaneously to prevent excessive program swaps.
-    pub fn draw_rects(&mut self, size_info: &SizeInfo, metrics: &Metrics, rects: Vec<RenderRect>) {
+    pub fn draw_rects(&mut self, size_info: &SizeInfo, metrics: &Metrics, rects: &[RenderRect]) {
         if rects.is_empty() {
             return;
         }
@@ -230,7 +287,8 @@ impl Renderer {
         // Prepare rect rendering state.
         unsafe {
             // Remove padding from viewport.
-            gl::Viewport(0, 0, size_info.width() as i32, size_info.height() as i32);
+            let (x, y) = self.viewport_origin;
+            gl::Viewport(x, y, size_info.width() as i32, size_info.height() as i32);
             gl::BlendFuncSeparate(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA, gl::SRC_ALPHA, gl::ONE);
         }
 
@@ -247,7 +305,7 @@ impl Renderer {
     }
 
     /// Draw all rectangles simultaneously to prevent excessive program swaps.
-    pub fn draw_quads(&mut self, size_info: &SizeInfo, metrics: &Metrics, quads: Vec<RenderQuad>) {
+    pub fn draw_quads(&mut self, size_info: &SizeInfo, metrics: &Metrics, quads: &[RenderQuad]) {
         if quads.is_empty() {
             return;
         }
@@ -255,7 +313,8 @@ impl Renderer {
         // Prepare rect rendering state.
         unsafe {
             // Remove padding from viewport.
-            gl::Viewport(0, 0, size_info.width() as i32, size_info.height() as i32);
+            let (x, y) = self.viewport_origin;
+            gl::Viewport(x, y, size_info.width() as i32, size_info.height() as i32);
             gl::BlendFuncSeparate(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA, gl::SRC_ALPHA, gl::ONE);
         }
 
@@ -271,6 +330,28 @@ impl Renderer {
         }
     }
 
+    /// Draw a region with rounded outline.
+    pub fn draw_region(&mut self, size_info: &SizeInfo, region: &RenderRegion) {
+        // Prepare region rendering state.
+        unsafe {
+            // Remove padding from viewport.
+            let (x, y) = self.viewport_origin;
+            gl::Viewport(x, y, size_info.width() as i32, size_info.height() as i32);
+            gl::BlendFuncSeparate(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA, gl::SRC_ALPHA, gl::ONE);
+        }
+
+        self.region_renderer.draw(size_info, region);
+
+        // Activate regular state again.
+        unsafe {
+            // Reset blending strategy.
+            gl::BlendFunc(gl::SRC1_COLOR, gl::ONE_MINUS_SRC1_COLOR);
+
+            // Restore viewport with padding.
+            self.set_viewport(size_info);
+        }
+    }
+
     /// Fill the window with `color` and `alpha`.
     pub fn clear(&self, color: Rgb, alpha: f32) {
         unsafe {
@@ -290,13 +371,103 @@ impl Renderer {
         }
     }
 
+    /// Read the window's content as RGBA rows, starting at the top of the window.
+    pub fn read_pixels(&self, width: u32, height: u32) -> Vec<u8> {
+        let stride = width as usize * 4;
+        let mut pixels = vec![0u8; stride * height as usize];
+
+        unsafe {
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_mut_ptr() as *mut _,
+            );
+        }
+
+        // OpenGL starts with the bottom row.
+        pixels.chunks_exact(stride).rev().flatten().copied().collect()
+    }
+
+    /// Draw everything up to [`Self::end_color_filter`] offscreen, to apply `filter` to it.
+    ///
+    /// The offscreen target covers a window of `width` by `height` pixels. Drawing goes directly to
+    /// the window when the filter leaves colors unchanged.
+    pub fn begin_color_filter(&mut self, width: i32, height: i32, filter: ColorFilter) {
+        if let Some(matrix) = filter::color_matrix(filter) {
+            self.filter_renderer.begin(width, height, matrix);
+        }
+    }
+
+    /// Draw the filtered frame to the window.
+    pub fn end_color_filter(&mut self, size_info: &SizeInfo) {
+        self.filter_renderer.end();
+
+        // Restore viewport with padding.
+        self.set_viewport(size_info);
+    }
+
+    /// Restrict drawing to a region of the window, or draw to the entire window with `None`.
+    ///
+    /// The region's origin is in the bottom-left corner of the window.
+    pub fn set_scissor(&self, region: Option<Rect>) {
+        unsafe {
+            match region {
+                Some(region) => {
+                    gl::Enable(gl::SCISSOR_TEST);
+                    gl::Scissor(region.x, region.y, region.width, region.height);
+                },
+                None => gl::Disable(gl::SCISSOR_TEST),
+            }
+        }
+    }
+
+    /// Start measuring the GPU time of a render pass.
+    pub fn begin_pass(&mut self, pass: RenderPass) {
+        if let Some(gpu_timer) = &mut self.gpu_timer {
+            gpu_timer.begin(pass);
+        }
+    }
+
+    /// Stop measuring the GPU time of a render pass.
+    pub fn end_pass(&mut self, pass: RenderPass) {
+        if let Some(gpu_timer) = &mut self.gpu_timer {
+            gpu_timer.end(pass);
+        }
+    }
+
+    /// Collect the GPU time of render passes from previous frames.
+    pub fn finish_pass_timing(&mut self) {
+        if let Some(gpu_timer) = &mut self.gpu_timer {
+            gpu_timer.finish_frame();
+        }
+    }
+
+    /// GPU time of the last measured render passes.
+    pub fn pass_times(&self) -> PassTimes {
+        self.gpu_timer.as_ref().map(GpuTimer::pass_times).unwrap_or_default()
+    }
+
+    /// Move the drawn area, to draw terminals which do not cover the entire window.
+    ///
+    /// The origin is the bottom-left corner of the area, relative to the bottom-left corner of the
+    /// window. It is applied on the next call to [`Self::set_viewport`].
+    pub fn set_viewport_origin(&mut self, x: i32, y: i32) {
+        self.viewport_origin = (x, y);
+    }
+
     /// Set the viewport for cell rendering.
     #[inline]
     pub fn set_viewport(&self, size: &SizeInfo) {
+        let (x, y) = self.viewport_origin;
         unsafe {
             gl::Viewport(
-                size.padding_x() as i32,
-                size.padding_y() as i32,
+                x + size.padding_x() as i32,
+                y + size.padding_y() as i32,
                 size.width() as i32 - 2 * size.padding_x() as i32,
                 size.height() as i32 - 2 * size.padding_y() as i32,
             );
@@ -4,7 +4,8 @@ use std::num::NonZeroU32;
 
 use glutin::config::{ColorBufferType, Config, ConfigTemplateBuilder, GetGlConfig};
 use glutin::context::{
-    ContextApi, ContextAttributesBuilder, GlProfile, NotCurrentContext, Version,
+    ContextApi, ContextAttributesBuilder, GlProfile, NotCurrentContext, PossiblyCurrentContext,
+    Version,
 };
 use glutin::display::{Display, DisplayApiPreference, GetGlDisplay};
 use glutin::error::Result as GlutinResult;
@@ -95,24 +96,33 @@ pub fn pick_gl_config(
     Err(String::from("failed to find suitable GL configuration."))
 }
 
+/// Create the GL context.
+///
+/// When `shared_context` is present, the new context will share its objects like textures and
+/// shader programs.
 pub fn create_gl_context(
     gl_display: &Display,
     gl_config: &Config,
     raw_window_handle: Option<RawWindowHandle>,
+    shared_context: Option<&PossiblyCurrentContext>,
 ) -> GlutinResult<NotCurrentContext> {
     let debug = log::max_level() >= LevelFilter::Debug;
+    let builder = || {
+        let builder = ContextAttributesBuilder::new().with_debug(debug);
+        match shared_context {
+            Some(shared_context) => builder.with_sharing(shared_context),
+            None => builder,
+        }
+    };
     let mut profiles = [
-        ContextAttributesBuilder::new()
-            .with_debug(debug)
+        builder()
             .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
             .build(raw_window_handle),
         // Try gles before OpenGL 2.1 as it tends to be more stable.
-        ContextAttributesBuilder::new()
-            .with_debug(debug)
+        builder()
             .with_context_api(ContextApi::Gles(Some(Version::new(2, 0))))
             .build(raw_window_handle),
-        ContextAttributesBuilder::new()
-            .with_debug(debug)
+        builder()
             .with_profile(GlProfile::Compatibility)
             .with_context_api(ContextApi::OpenGl(Some(Version::new(2, 1))))
             .build(raw_window_handle),
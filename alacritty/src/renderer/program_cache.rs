@@ -0,0 +1,154 @@
+//! On-disk cache for linked shader program binaries.
+//!
+//! Binaries are only valid for the driver which created them, so they're keyed by the OpenGL
+//! vendor, renderer and version, alongside the program's sources. Drivers are free to reject
+//! binaries at any time, in which case the program is compiled from source again.
+
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::CStr;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use log::{debug, warn};
+use once_cell::sync::OnceCell;
+
+use crate::gl;
+use crate::gl::types::*;
+
+/// Size of the binary format header preceding the program binary.
+const FORMAT_SIZE: usize = std::mem::size_of::<GLenum>();
+
+/// Path of the cached binary for a program built from `sources`.
+///
+/// Returns `None` if program binaries aren't supported by the driver.
+pub fn path(sources: &[&str]) -> Option<PathBuf> {
+    let driver = driver()?;
+
+    let mut hasher = DefaultHasher::new();
+    driver.hash(&mut hasher);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    sources.hash(&mut hasher);
+
+    let file_name = format!("{:016x}.bin", hasher.finish());
+    cache_dir().map(|path| path.join(file_name))
+}
+
+/// Directory containing the cached program binaries.
+#[cfg(not(windows))]
+fn cache_dir() -> Option<PathBuf> {
+    xdg::BaseDirectories::with_prefix("alacritty")
+        .ok()
+        .map(|xdg| xdg.get_cache_home().join("shaders"))
+}
+
+/// Directory containing the cached program binaries.
+#[cfg(windows)]
+fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|path| path.join("alacritty").join("shaders"))
+}
+
+/// Load a cached binary into `program`.
+///
+/// Returns `false` if no usable binary was found, in which case the program has to be linked
+/// from source.
+pub fn load(path: &Path, program: GLuint) -> bool {
+    let data = match fs::read(path) {
+        Ok(data) if data.len() > FORMAT_SIZE => data,
+        _ => return false,
+    };
+
+    let (format, binary) = data.split_at(FORMAT_SIZE);
+    let format = GLenum::from_le_bytes(format.try_into().unwrap());
+
+    let mut success: GLint = 0;
+    unsafe {
+        gl::ProgramBinary(program, format, binary.as_ptr().cast(), binary.len() as GLsizei);
+        gl::GetProgramiv(program, gl::LINK_STATUS, &mut success);
+    }
+
+    if success != GLint::from(gl::TRUE) {
+        debug!("Discarding outdated shader program binary {path:?}");
+        let _ = fs::remove_file(path);
+        return false;
+    }
+
+    true
+}
+
+/// Request the program binary to be retrievable once `program` is linked.
+pub fn prepare(program: GLuint) {
+    unsafe {
+        gl::ProgramParameteri(program, gl::PROGRAM_BINARY_RETRIEVABLE_HINT, GLint::from(gl::TRUE));
+    }
+}
+
+/// Store the binary of the linked `program`.
+pub fn store(path: &Path, program: GLuint) {
+    let mut length: GLint = 0;
+    unsafe {
+        gl::GetProgramiv(program, gl::PROGRAM_BINARY_LENGTH, &mut length);
+    }
+
+    if length <= 0 {
+        return;
+    }
+
+    let mut format: GLenum = 0;
+    let mut actual_length: GLsizei = 0;
+    let mut binary = vec![0u8; length as usize];
+    unsafe {
+        gl::GetProgramBinary(
+            program,
+            length,
+            &mut actual_length,
+            &mut format,
+            binary.as_mut_ptr().cast(),
+        );
+    }
+    binary.truncate(actual_length.max(0) as usize);
+
+    if let Err(err) = write(path, format, &binary) {
+        warn!("Unable to cache shader program binary {path:?}: {err}");
+    }
+}
+
+/// Write a program binary, prefixed by its format.
+fn write(path: &Path, format: GLenum, binary: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut data = Vec::with_capacity(FORMAT_SIZE + binary.len());
+    data.extend_from_slice(&format.to_le_bytes());
+    data.extend_from_slice(binary);
+
+    // Write to a temporary file first, so other instances never read partial binaries.
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, data)?;
+    fs::rename(tmp_path, path)
+}
+
+/// Identification of the current driver, if it supports program binaries.
+fn driver() -> Option<&'static str> {
+    static DRIVER: OnceCell<Option<String>> = OnceCell::new();
+
+    DRIVER
+        .get_or_init(|| unsafe {
+            if !gl::ProgramBinary::is_loaded() || !gl::GetProgramBinary::is_loaded() {
+                return None;
+            }
+
+            let mut formats: GLint = 0;
+            gl::GetIntegerv(gl::NUM_PROGRAM_BINARY_FORMATS, &mut formats);
+            if formats <= 0 {
+                return None;
+            }
+
+            let vendor = CStr::from_ptr(gl::GetString(gl::VENDOR) as *mut _).to_string_lossy();
+            let renderer = CStr::from_ptr(gl::GetString(gl::RENDERER) as *mut _).to_string_lossy();
+            let version = CStr::from_ptr(gl::GetString(gl::VERSION) as *mut _).to_string_lossy();
+            Some(format!("{vendor}\n{renderer}\n{version}"))
+        })
+        .as_deref()
+}
@@ -27,6 +27,30 @@ impl RenderQuad {
     pub fn new(points: [QuadPoint; 4], color: Rgb, alpha: f32) -> Self {
         RenderQuad { points, color, alpha }
     }
+
+    /// Straight line of `width` pixels between two points.
+    pub fn line(start: QuadPoint, end: QuadPoint, width: f32, color: Rgb, alpha: f32) -> Self {
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let length = dx.hypot(dy);
+
+        // Offset perpendicular to the line, extending points into a square of `width`.
+        let half = width / 2.;
+        let (nx, ny) =
+            if length > 0. { (-dy / length * half, dx / length * half) } else { (0., half) };
+        let (start, end) = if length > 0. {
+            (start, end)
+        } else {
+            (QuadPoint { x: start.x - half, y: start.y }, QuadPoint { x: end.x + half, y: end.y })
+        };
+
+        let points = [
+            QuadPoint { x: start.x + nx, y: start.y + ny },
+            QuadPoint { x: end.x + nx, y: end.y + ny },
+            QuadPoint { x: end.x - nx, y: end.y - ny },
+            QuadPoint { x: start.x - nx, y: start.y - ny },
+        ];
+        RenderQuad { points, color, alpha }
+    }
 }
 
 /// Shader sources for rect rendering program.
@@ -48,7 +72,7 @@ struct Vertex {
 }
 
 /// Rectangle drawing program.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct QuadShaderProgram {
     /// Shader program.
     program: ShaderProgram,
@@ -141,11 +165,20 @@ pub struct QuadRenderer {
 }
 
 impl QuadRenderer {
-    pub fn new(shader_version: ShaderVersion) -> Result<Self, renderer::Error> {
+    /// Create a new quad renderer.
+    ///
+    /// The shader program of the `shared` renderer will be reused when it is present.
+    pub fn new(
+        shader_version: ShaderVersion,
+        shared: Option<&QuadRenderer>,
+    ) -> Result<Self, renderer::Error> {
         let mut vao: GLuint = 0;
         let mut vbo: GLuint = 0;
 
-        let rect_program = QuadShaderProgram::new(shader_version)?;
+        let programs = match shared {
+            Some(shared) => shared.programs.clone(),
+            None => QuadShaderProgram::new(shader_version)?,
+        };
 
         unsafe {
             // Allocate buffers.
@@ -187,11 +220,10 @@ impl QuadRenderer {
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
         }
 
-        let programs = rect_program;
         Ok(Self { vao, vbo, programs, vertices: Default::default() })
     }
 
-    pub fn draw(&mut self, size_info: &SizeInfo, metrics: &Metrics, quads: Vec<RenderQuad>) {
+    pub fn draw(&mut self, size_info: &SizeInfo, metrics: &Metrics, quads: &[RenderQuad]) {
         unsafe {
             // Bind VAO to enable vertex attribute slots.
             gl::BindVertexArray(self.vao);
@@ -205,7 +237,7 @@ impl QuadRenderer {
 
         // Build rect vertices vector.
         self.vertices.clear();
-        for quad in &quads {
+        for quad in quads {
             Self::add_quad(&mut self.vertices, half_width, half_height, quad);
         }
 
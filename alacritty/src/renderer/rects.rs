@@ -52,18 +52,21 @@ pub enum RectKind {
 }
 
 impl RenderLine {
-    pub fn rects(&self, flag: Flags, metrics: &Metrics, size: &SizeInfo) -> Vec<RenderRect> {
-        let mut rects = Vec::new();
-
+    /// Append the rects required to draw the line to `rects`.
+    pub fn rects(
+        &self,
+        rects: &mut Vec<RenderRect>,
+        flag: Flags,
+        metrics: &Metrics,
+        size: &SizeInfo,
+    ) {
         let mut start = self.start;
         while start.line < self.end.line {
             let end = Point::new(start.line, size.last_column());
-            Self::push_rects(&mut rects, metrics, size, flag, start, end, self.color);
+            Self::push_rects(rects, metrics, size, flag, start, end, self.color);
             start = Point::new(start.line + 1, Column(0));
         }
-        Self::push_rects(&mut rects, metrics, size, flag, start, self.end, self.color);
-
-        rects
+        Self::push_rects(rects, metrics, size, flag, start, self.end, self.color);
     }
 
     /// Push all rects required to draw the cell's line.
@@ -167,14 +170,22 @@ impl RenderLines {
         Self::default()
     }
 
+    /// Append the rects of all lines to `rects`.
     #[inline]
-    pub fn rects(&self, metrics: &Metrics, size: &SizeInfo) -> Vec<RenderRect> {
-        self.inner
-            .iter()
-            .flat_map(|(flag, lines)| {
-                lines.iter().flat_map(move |line| line.rects(*flag, metrics, size))
-            })
-            .collect()
+    pub fn rects(&self, rects: &mut Vec<RenderRect>, metrics: &Metrics, size: &SizeInfo) {
+        for (flag, lines) in &self.inner {
+            for line in lines {
+                line.rects(rects, *flag, metrics, size);
+            }
+        }
+    }
+
+    /// Remove all lines, keeping the allocated storage for reuse.
+    #[inline]
+    pub fn clear(&mut self) {
+        for lines in self.inner.values_mut() {
+            lines.clear();
+        }
     }
 
     /// Update the stored lines with the next cell info.
@@ -255,14 +266,25 @@ pub struct RectRenderer {
 }
 
 impl RectRenderer {
-    pub fn new(shader_version: ShaderVersion) -> Result<Self, renderer::Error> {
+    /// Create a new rect renderer.
+    ///
+    /// The shader programs of the `shared` renderer will be reused when it is present.
+    pub fn new(
+        shader_version: ShaderVersion,
+        shared: Option<&RectRenderer>,
+    ) -> Result<Self, renderer::Error> {
         let mut vao: GLuint = 0;
         let mut vbo: GLuint = 0;
 
-        let rect_program = RectShaderProgram::new(shader_version, RectKind::Normal)?;
-        let undercurl_program = RectShaderProgram::new(shader_version, RectKind::Undercurl)?;
-        let dotted_program = RectShaderProgram::new(shader_version, RectKind::DottedUnderline)?;
-        let dashed_program = RectShaderProgram::new(shader_version, RectKind::DashedUnderline)?;
+        let programs = match shared {
+            Some(shared) => shared.programs.clone(),
+            None => [
+                RectShaderProgram::new(shader_version, RectKind::Normal)?,
+                RectShaderProgram::new(shader_version, RectKind::Undercurl)?,
+                RectShaderProgram::new(shader_version, RectKind::DottedUnderline)?,
+                RectShaderProgram::new(shader_version, RectKind::DashedUnderline)?,
+            ],
+        };
 
         unsafe {
             // Allocate buffers.
@@ -304,11 +326,10 @@ impl RectRenderer {
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
         }
 
-        let programs = [rect_program, undercurl_program, dotted_program, dashed_program];
         Ok(Self { vao, vbo, programs, vertices: Default::default() })
     }
 
-    pub fn draw(&mut self, size_info: &SizeInfo, metrics: &Metrics, rects: Vec<RenderRect>) {
+    pub fn draw(&mut self, size_info: &SizeInfo, metrics: &Metrics, rects: &[RenderRect]) {
         unsafe {
             // Bind VAO to enable vertex attribute slots.
             gl::BindVertexArray(self.vao);
@@ -322,7 +343,7 @@ impl RectRenderer {
 
         // Build rect vertices vector.
         self.vertices.iter_mut().for_each(|vertices| vertices.clear());
-        for rect in &rects {
+        for rect in rects {
             Self::add_rect(&mut self.vertices[rect.kind as usize], half_width, half_height, rect);
         }
 
@@ -398,7 +419,7 @@ impl Drop for RectRenderer {
 }
 
 /// Rectangle drawing program.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct RectShaderProgram {
     /// Shader program.
     program: ShaderProgram,
@@ -0,0 +1,354 @@
+//! Regions with rounded outlines, drawn from their signed distance field.
+//!
+//! A region is made up of horizontal spans stacked on top of each other, like the lines of a
+//! selection. Its outline is rounded at convex corners and filleted at concave ones.
+
+use std::{mem, ptr};
+
+use alacritty_terminal::term::color::Rgb;
+
+use crate::display::SizeInfo;
+use crate::gl;
+use crate::gl::types::*;
+use crate::renderer::shader::{ShaderError, ShaderProgram, ShaderVersion};
+use crate::renderer::{self, cstr};
+
+/// Shader sources for the region program.
+static REGION_SHADER_F: &str = include_str!("../../res/region.f.glsl");
+static REGION_SHADER_V: &str = include_str!("../../res/region.v.glsl");
+
+/// Maximum number of spans in a region, after merging identical spans.
+pub const MAX_SPANS: usize = 3;
+
+// XXX: These must be in-sync with the fragment shader defines.
+const MAX_RECTS: usize = 2 * MAX_SPANS - 1;
+const MAX_FILLETS: usize = 2 * (MAX_SPANS - 1);
+
+/// Placeholder for unused rectangles and fillets, far outside of the viewport.
+const UNUSED: [f32; 4] = [-1e4, -1e4, -1e4, -1e4];
+
+/// Horizontal span of a region, in pixels.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RegionSpan {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl RegionSpan {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        Self { left, top, right, bottom }
+    }
+
+    fn as_array(&self) -> [f32; 4] {
+        [self.left, self.top, self.right, self.bottom]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderRegion {
+    /// Rectangles whose union forms the region.
+    ///
+    /// Spans touching each other are bridged by an additional rectangle overlapping both, so the
+    /// region isn't rounded where they meet.
+    rects: Vec<[f32; 4]>,
+
+    /// Concave corners, with the direction pointing towards the empty quadrant.
+    fillets: Vec<[f32; 4]>,
+
+    /// Bounding box of the region.
+    bounds: RegionSpan,
+
+    radius: f32,
+    color: Rgb,
+    border: Option<(Rgb, f32)>,
+}
+
+impl RenderRegion {
+    /// Create a region from its spans, ordered from top to bottom.
+    ///
+    /// Vertically adjacent spans with identical horizontal bounds are merged, any spans beyond
+    /// [`MAX_SPANS`] are ignored afterwards.
+    pub fn new(spans: &[RegionSpan], radius: f32, color: Rgb) -> Option<Self> {
+        let mut merged: Vec<RegionSpan> = Vec::new();
+        for span in spans {
+            match merged.last_mut() {
+                Some(last)
+                    if last.bottom == span.top
+                        && last.left == span.left
+                        && last.right == span.right =>
+                {
+                    last.bottom = span.bottom
+                },
+                _ => merged.push(*span),
+            }
+        }
+        merged.truncate(MAX_SPANS);
+
+        let first = merged.first()?;
+        let mut bounds = *first;
+        let mut radius = radius;
+        for span in &merged {
+            bounds.left = bounds.left.min(span.left);
+            bounds.right = bounds.right.max(span.right);
+            bounds.bottom = span.bottom;
+
+            // Keep corners from overlapping each other.
+            radius = radius.min((span.right - span.left) / 2.).min((span.bottom - span.top) / 2.);
+        }
+
+        let mut rects: Vec<_> = merged.iter().map(RegionSpan::as_array).collect();
+        let mut fillets = Vec::new();
+        for pair in merged.windows(2) {
+            let (above, below) = (pair[0], pair[1]);
+            let (left, right) = (above.left.max(below.left), above.right.min(below.right));
+            if above.bottom != below.top || left >= right {
+                continue;
+            }
+
+            rects.push([left, above.top, right, below.bottom]);
+
+            let seam = above.bottom;
+            if above.left > below.left {
+                fillets.push([above.left, seam, -1., -1.]);
+            } else if below.left > above.left {
+                fillets.push([below.left, seam, -1., 1.]);
+            }
+            if above.right < below.right {
+                fillets.push([above.right, seam, 1., -1.]);
+            } else if below.right < above.right {
+                fillets.push([below.right, seam, 1., 1.]);
+            }
+        }
+
+        Some(Self { rects, fillets, bounds, radius: radius.max(0.), color, border: None })
+    }
+
+    /// Outline the region with a border of `width` pixels.
+    pub fn with_border(mut self, color: Rgb, width: f32) -> Self {
+        self.border = Some((color, width));
+        self
+    }
+
+    /// Bounding box of the region in pixels.
+    pub fn bounds(&self) -> RegionSpan {
+        self.bounds
+    }
+}
+
+#[derive(Debug)]
+pub struct RegionRenderer {
+    // GL buffer objects.
+    vao: GLuint,
+    vbo: GLuint,
+
+    program: RegionShaderProgram,
+}
+
+impl RegionRenderer {
+    /// Create a new region renderer.
+    ///
+    /// The shader program of the `shared` renderer will be reused when it is present.
+    pub fn new(
+        shader_version: ShaderVersion,
+        shared: Option<&RegionRenderer>,
+    ) -> Result<Self, renderer::Error> {
+        let mut vao: GLuint = 0;
+        let mut vbo: GLuint = 0;
+
+        let program = match shared {
+            Some(shared) => shared.program.clone(),
+            None => RegionShaderProgram::new(shader_version)?,
+        };
+
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+
+            // Position.
+            gl::VertexAttribPointer(
+                0,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                (mem::size_of::<f32>() * 2) as i32,
+                ptr::null(),
+            );
+            gl::EnableVertexAttribArray(0);
+
+            // Reset buffer bindings.
+            gl::BindVertexArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+
+        Ok(Self { vao, vbo, program })
+    }
+
+    pub fn draw(&mut self, size_info: &SizeInfo, region: &RenderRegion) {
+        // Cover the region, including the antialiased edge around it.
+        let RegionSpan { left, top, right, bottom } = region.bounds;
+        let (left, top, right, bottom) = (left - 1., top - 1., right + 1., bottom + 1.);
+        let vertices = [left, top, right, top, left, bottom, right, bottom];
+
+        let mut rects = [UNUSED; MAX_RECTS];
+        for (rect, region_rect) in rects.iter_mut().zip(&region.rects) {
+            *rect = *region_rect;
+        }
+        let mut fillets = [UNUSED; MAX_FILLETS];
+        for (fillet, region_fillet) in fillets.iter_mut().zip(&region.fillets) {
+            *fillet = *region_fillet;
+        }
+
+        let (border_color, border_width) = region.border.unwrap_or((region.color, 0.));
+        let program = &self.program;
+
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                mem::size_of_val(&vertices) as isize,
+                vertices.as_ptr() as *const _,
+                gl::STREAM_DRAW,
+            );
+
+            gl::UseProgram(program.id());
+            gl::Uniform2f(program.u_viewport_size, size_info.width(), size_info.height());
+            gl::Uniform4fv(program.u_rects, MAX_RECTS as i32, rects.as_ptr().cast());
+            gl::Uniform4fv(program.u_fillets, MAX_FILLETS as i32, fillets.as_ptr().cast());
+            gl::Uniform1f(program.u_radius, region.radius);
+            gl::Uniform1f(program.u_border_width, border_width);
+            set_color(program.u_fill_color, region.color);
+            set_color(program.u_border_color, border_color);
+
+            gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+
+            // Reset state.
+            gl::UseProgram(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for RegionRenderer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
+
+/// Set an opaque color uniform.
+unsafe fn set_color(location: GLint, color: Rgb) {
+    let (r, g, b) = color.as_tuple();
+    gl::Uniform4f(location, f32::from(r) / 255., f32::from(g) / 255., f32::from(b) / 255., 1.);
+}
+
+/// Region drawing program.
+#[derive(Clone, Debug)]
+struct RegionShaderProgram {
+    /// Shader program.
+    program: ShaderProgram,
+
+    /// Size of the viewport in pixels.
+    u_viewport_size: GLint,
+
+    /// Rectangles making up the region.
+    u_rects: GLint,
+
+    /// Concave corners of the region.
+    u_fillets: GLint,
+
+    /// Radius of rounded corners.
+    u_radius: GLint,
+
+    /// Width of the region's border.
+    u_border_width: GLint,
+
+    /// Colors of the region and its border.
+    u_fill_color: GLint,
+    u_border_color: GLint,
+}
+
+impl RegionShaderProgram {
+    fn new(shader_version: ShaderVersion) -> Result<Self, ShaderError> {
+        let program = ShaderProgram::new(shader_version, None, REGION_SHADER_V, REGION_SHADER_F)?;
+
+        Ok(Self {
+            u_viewport_size: program.get_uniform_location(cstr!("viewportSize"))?,
+            u_rects: program.get_uniform_location(cstr!("rects"))?,
+            u_fillets: program.get_uniform_location(cstr!("fillets"))?,
+            u_radius: program.get_uniform_location(cstr!("radius"))?,
+            u_border_width: program.get_uniform_location(cstr!("borderWidth"))?,
+            u_fill_color: program.get_uniform_location(cstr!("fillColor"))?,
+            u_border_color: program.get_uniform_location(cstr!("borderColor"))?,
+            program,
+        })
+    }
+
+    fn id(&self) -> GLuint {
+        self.program.id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_spans() {
+        let spans = [
+            RegionSpan::new(0., 0., 100., 10.),
+            RegionSpan::new(0., 10., 100., 20.),
+            RegionSpan::new(0., 20., 100., 30.),
+        ];
+        let region = RenderRegion::new(&spans, 4., Rgb::default()).unwrap();
+
+        assert_eq!(region.rects, [[0., 0., 100., 30.]]);
+        assert!(region.fillets.is_empty());
+        assert_eq!(region.bounds, RegionSpan::new(0., 0., 100., 30.));
+    }
+
+    #[test]
+    fn selection_outline() {
+        // Selection starting mid-line and ending before the end of the last line.
+        let spans = [
+            RegionSpan::new(30., 0., 100., 10.),
+            RegionSpan::new(0., 10., 100., 20.),
+            RegionSpan::new(0., 20., 100., 30.),
+            RegionSpan::new(0., 30., 60., 40.),
+        ];
+        let region = RenderRegion::new(&spans, 8., Rgb::default()).unwrap();
+
+        assert_eq!(region.rects, [
+            [30., 0., 100., 10.],
+            [0., 10., 100., 30.],
+            [0., 30., 60., 40.],
+            [30., 0., 100., 30.],
+            [0., 10., 60., 40.],
+        ]);
+        assert_eq!(region.fillets, [[30., 10., -1., -1.], [60., 30., 1., 1.]]);
+        assert_eq!(region.bounds, RegionSpan::new(0., 0., 100., 40.));
+
+        // Corners are limited by the height of the lines.
+        assert_eq!(region.radius, 5.);
+    }
+
+    #[test]
+    fn disjoint_spans() {
+        // Two lines, where the second one ends before the first one starts.
+        let spans = [RegionSpan::new(60., 0., 100., 10.), RegionSpan::new(0., 10., 40., 20.)];
+        let region = RenderRegion::new(&spans, 2., Rgb::default()).unwrap();
+
+        assert_eq!(region.rects, [[60., 0., 100., 10.], [0., 10., 40., 20.]]);
+        assert!(region.fillets.is_empty());
+
+        assert_eq!(RenderRegion::new(&[], 2., Rgb::default()), None);
+    }
+}
@@ -1,14 +1,23 @@
 use std::ffi::CStr;
 use std::fmt;
+use std::rc::Rc;
 
 use crate::gl;
 use crate::gl::types::*;
+use crate::renderer::program_cache;
 
 /// A wrapper for a shader program id, with automatic lifetime management.
+///
+/// Cloning the program is cheap, allowing it to be reused by other GL contexts sharing objects
+/// with the one it was created in.
+#[derive(Clone, Debug)]
+pub struct ShaderProgram(Rc<ProgramId>);
+
+/// Owned shader program id, deleted once the last [`ShaderProgram`] using it is dropped.
 #[derive(Debug)]
-pub struct ShaderProgram(GLuint);
+struct ProgramId(GLuint);
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ShaderVersion {
     /// OpenGL 3.3 core shaders.
     Glsl3,
@@ -34,13 +43,27 @@ impl ShaderProgram {
         vertex_shader: &'static str,
         fragment_shader: &'static str,
     ) -> Result<Self, ShaderError> {
+        let program = unsafe { Self(Rc::new(ProgramId(gl::CreateProgram()))) };
+
+        // Skip compilation when the driver accepts the binary of a previous run.
+        let cache_path = program_cache::path(&[
+            shader_version.shader_header(),
+            shader_header.unwrap_or_default(),
+            vertex_shader,
+            fragment_shader,
+        ]);
+        if let Some(cache_path) = &cache_path {
+            if program_cache::load(cache_path, program.id()) {
+                return Ok(program);
+            }
+            program_cache::prepare(program.id());
+        }
+
         let vertex_shader =
             Shader::new(shader_version, shader_header, gl::VERTEX_SHADER, vertex_shader)?;
         let fragment_shader =
             Shader::new(shader_version, shader_header, gl::FRAGMENT_SHADER, fragment_shader)?;
 
-        let program = unsafe { Self(gl::CreateProgram()) };
-
         let mut success: GLint = 0;
         unsafe {
             gl::AttachShader(program.id(), vertex_shader.id());
@@ -53,6 +76,10 @@ impl ShaderProgram {
             return Err(ShaderError::Link(get_program_info_log(program.id())));
         }
 
+        if let Some(cache_path) = &cache_path {
+            program_cache::store(cache_path, program.id());
+        }
+
         Ok(program)
     }
 
@@ -68,11 +95,11 @@ impl ShaderProgram {
 
     /// Get the shader program id.
     pub fn id(&self) -> GLuint {
-        self.0
+        self.0 .0
     }
 }
 
-impl Drop for ShaderProgram {
+impl Drop for ProgramId {
     fn drop(&mut self) {
         unsafe { gl::DeleteProgram(self.0) }
     }
@@ -11,6 +11,9 @@ use super::Glyph;
 /// Size of the Atlas.
 pub const ATLAS_SIZE: i32 = 1024;
 
+/// Memory used by a single atlas' RGBA texture, in bytes.
+pub const ATLAS_MEMORY: usize = ATLAS_SIZE as usize * ATLAS_SIZE as usize * 4;
+
 /// Manages a single texture atlas.
 ///
 /// The strategy for filling an atlas looks roughly like this:
@@ -243,36 +246,48 @@ impl Atlas {
 
         Ok(())
     }
+}
+
+/// Texture atlases holding all glyphs of a glyph cache.
+///
+/// Since textures can be shared between GL contexts, the atlases are owned by the glyph cache
+/// instead of the renderer, allowing multiple windows to reuse the same glyphs.
+#[derive(Debug, Default)]
+pub struct GlyphAtlas {
+    atlas: Vec<Atlas>,
+    current_atlas: usize,
+}
 
+impl GlyphAtlas {
     /// Load a glyph into a texture atlas.
     ///
     /// If the current atlas is full, a new one will be created.
-    #[inline]
     pub fn load_glyph(
+        &mut self,
         active_tex: &mut GLuint,
-        atlas: &mut Vec<Atlas>,
-        current_atlas: &mut usize,
+        is_gles_context: bool,
         rasterized: &RasterizedGlyph,
     ) -> Glyph {
-        // At least one atlas is guaranteed to be in the `self.atlas` list; thus
-        // the unwrap.
-        match atlas[*current_atlas].insert(rasterized, active_tex) {
+        // Lazily create the first atlas, since the GL context might not exist at creation time.
+        if self.atlas.is_empty() {
+            self.atlas.push(Atlas::new(ATLAS_SIZE, is_gles_context));
+            *active_tex = 0;
+        }
+
+        match self.atlas[self.current_atlas].insert(rasterized, active_tex) {
             Ok(glyph) => glyph,
             Err(AtlasInsertError::Full) => {
-                // Get the context type before adding a new Atlas.
-                let is_gles_context = atlas[*current_atlas].is_gles_context;
-
                 // Advance the current Atlas index.
-                *current_atlas += 1;
-                if *current_atlas == atlas.len() {
+                self.current_atlas += 1;
+                if self.current_atlas == self.atlas.len() {
                     let new = Atlas::new(ATLAS_SIZE, is_gles_context);
                     *active_tex = 0; // Atlas::new binds a texture. Ugh this is sloppy.
-                    atlas.push(new);
+                    self.atlas.push(new);
                 }
-                Atlas::load_glyph(active_tex, atlas, current_atlas, rasterized)
+                self.load_glyph(active_tex, is_gles_context, rasterized)
             },
             Err(AtlasInsertError::GlyphTooLarge) => Glyph {
-                tex_id: atlas[*current_atlas].id,
+                tex_id: self.atlas[self.current_atlas].id,
                 multicolor: false,
                 top: 0,
                 left: 0,
@@ -286,12 +301,23 @@ impl Atlas {
         }
     }
 
-    #[inline]
-    pub fn clear_atlas(atlas: &mut [Atlas], current_atlas: &mut usize) {
-        for atlas in atlas.iter_mut() {
+    /// Mark all atlases as empty, without freeing their textures.
+    pub fn clear(&mut self) {
+        for atlas in self.atlas.iter_mut() {
             atlas.clear();
         }
-        *current_atlas = 0;
+        self.current_atlas = 0;
+    }
+
+    /// Mark all atlases as empty, freeing the textures of all but the first one.
+    pub fn compact(&mut self) {
+        self.atlas.truncate(1);
+        self.clear();
+    }
+
+    /// Memory used by the atlas textures, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.atlas.len() * ATLAS_MEMORY
     }
 }
 
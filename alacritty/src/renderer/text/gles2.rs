@@ -13,10 +13,10 @@ use crate::gl::types::*;
 use crate::renderer::shader::{ShaderProgram, ShaderVersion};
 use crate::renderer::{cstr, Error, GlExtensions};
 
-use super::atlas::{Atlas, ATLAS_SIZE};
+use super::atlas::GlyphAtlas;
 use super::{
-    glsl3, Glyph, LoadGlyph, LoaderApi, RenderingGlyphFlags, RenderingPass, TextRenderApi,
-    TextRenderBatch, TextRenderer, TextShader,
+    glsl3, update_projection, Glyph, LoadGlyph, LoaderApi, RenderingGlyphFlags, RenderingPass,
+    TextRenderApi, TextRenderBatch, TextRenderer, TextShader,
 };
 
 // Shader source.
@@ -29,15 +29,22 @@ pub struct Gles2Renderer {
     vao: GLuint,
     vbo: GLuint,
     ebo: GLuint,
-    atlas: Vec<Atlas>,
     batch: Batch,
-    current_atlas: usize,
     active_tex: GLuint,
     dual_source_blending: bool,
+    is_gles_context: bool,
 }
 
 impl Gles2Renderer {
-    pub fn new(allow_dsb: bool, is_gles_context: bool) -> Result<Self, Error> {
+    /// Create a new GLES2 text renderer.
+    ///
+    /// The shader program of the `shared` renderer will be reused when it is present and uses the
+    /// same blending technique.
+    pub fn new(
+        allow_dsb: bool,
+        is_gles_context: bool,
+        shared: Option<&Gles2Renderer>,
+    ) -> Result<Self, Error> {
         info!("Using OpenGL ES 2.0 renderer");
 
         let dual_source_blending = allow_dsb
@@ -52,7 +59,12 @@ impl Gles2Renderer {
             info!("Using dual source blending");
         }
 
-        let program = TextShaderProgram::new(ShaderVersion::Gles2, dual_source_blending)?;
+        let program = match shared {
+            Some(shared) if shared.dual_source_blending == dual_source_blending => {
+                shared.program.clone()
+            },
+            _ => TextShaderProgram::new(ShaderVersion::Gles2, dual_source_blending)?,
+        };
         let mut vao: GLuint = 0;
         let mut vbo: GLuint = 0;
         let mut ebo: GLuint = 0;
@@ -149,11 +161,10 @@ impl Gles2Renderer {
             vao,
             vbo,
             ebo,
-            atlas: vec![Atlas::new(ATLAS_SIZE, is_gles_context)],
             batch: Batch::new(),
-            current_atlas: 0,
             active_tex: 0,
             dual_source_blending,
+            is_gles_context,
         })
     }
 }
@@ -177,12 +188,16 @@ impl<'a> TextRenderer<'a> for Gles2Renderer {
         &self.program
     }
 
-    fn with_api<'b: 'a, F, T>(&'b mut self, _: &'b SizeInfo, func: F) -> T
+    fn with_api<'b: 'a, F, T>(&'b mut self, size_info: &'b SizeInfo, func: F) -> T
     where
         F: FnOnce(Self::RenderApi) -> T,
     {
         unsafe {
             gl::UseProgram(self.program.id());
+
+            // The program might be shared with other windows, so the projection must be updated.
+            update_projection(self.program.u_projection, size_info);
+
             gl::BindVertexArray(self.vao);
             gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ebo);
             gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
@@ -192,10 +207,9 @@ impl<'a> TextRenderer<'a> for Gles2Renderer {
         let res = func(RenderApi {
             active_tex: &mut self.active_tex,
             batch: &mut self.batch,
-            atlas: &mut self.atlas,
-            current_atlas: &mut self.current_atlas,
             program: &mut self.program,
             dual_source_blending: self.dual_source_blending,
+            is_gles_context: self.is_gles_context,
         });
 
         unsafe {
@@ -210,11 +224,7 @@ impl<'a> TextRenderer<'a> for Gles2Renderer {
     }
 
     fn loader_api(&mut self) -> LoaderApi<'_> {
-        LoaderApi {
-            active_tex: &mut self.active_tex,
-            atlas: &mut self.atlas,
-            current_atlas: &mut self.current_atlas,
-        }
+        LoaderApi { active_tex: &mut self.active_tex, is_gles_context: self.is_gles_context }
     }
 }
 
@@ -340,10 +350,9 @@ impl TextRenderBatch for Batch {
 pub struct RenderApi<'a> {
     active_tex: &'a mut GLuint,
     batch: &'a mut Batch,
-    atlas: &'a mut Vec<Atlas>,
-    current_atlas: &'a mut usize,
     program: &'a mut TextShaderProgram,
     dual_source_blending: bool,
+    is_gles_context: bool,
 }
 
 impl<'a> Drop for RenderApi<'a> {
@@ -355,12 +364,8 @@ impl<'a> Drop for RenderApi<'a> {
 }
 
 impl<'a> LoadGlyph for RenderApi<'a> {
-    fn load_glyph(&mut self, rasterized: &RasterizedGlyph) -> Glyph {
-        Atlas::load_glyph(self.active_tex, self.atlas, self.current_atlas, rasterized)
-    }
-
-    fn clear(&mut self) {
-        Atlas::clear_atlas(self.atlas, self.current_atlas)
+    fn load_glyph(&mut self, atlas: &mut GlyphAtlas, rasterized: &RasterizedGlyph) -> Glyph {
+        atlas.load_glyph(self.active_tex, self.is_gles_context, rasterized)
     }
 }
 
@@ -453,7 +458,7 @@ struct TextVertex {
     bg_a: u8,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TextShaderProgram {
     /// Shader program.
     program: ShaderProgram,
@@ -13,10 +13,10 @@ use crate::gl::types::*;
 use crate::renderer::shader::{ShaderProgram, ShaderVersion};
 use crate::renderer::{cstr, Error};
 
-use super::atlas::{Atlas, ATLAS_SIZE};
+use super::atlas::GlyphAtlas;
 use super::{
-    Glyph, LoadGlyph, LoaderApi, RenderingGlyphFlags, RenderingPass, TextRenderApi,
-    TextRenderBatch, TextRenderer, TextShader,
+    update_projection, Glyph, LoadGlyph, LoaderApi, RenderingGlyphFlags, RenderingPass,
+    TextRenderApi, TextRenderBatch, TextRenderer, TextShader,
 };
 
 // Shader source.
@@ -32,17 +32,21 @@ pub struct Glsl3Renderer {
     vao: GLuint,
     ebo: GLuint,
     vbo_instance: GLuint,
-    atlas: Vec<Atlas>,
-    current_atlas: usize,
     active_tex: GLuint,
     batch: Batch,
 }
 
 impl Glsl3Renderer {
-    pub fn new() -> Result<Self, Error> {
+    /// Create a new GLSL3 text renderer.
+    ///
+    /// The shader program of the `shared` renderer will be reused when it is present.
+    pub fn new(shared: Option<&Glsl3Renderer>) -> Result<Self, Error> {
         info!("Using OpenGL 3.3 renderer");
 
-        let program = TextShaderProgram::new(ShaderVersion::Glsl3)?;
+        let program = match shared {
+            Some(shared) => shared.program.clone(),
+            None => TextShaderProgram::new(ShaderVersion::Glsl3)?,
+        };
         let mut vao: GLuint = 0;
         let mut ebo: GLuint = 0;
         let mut vbo_instance: GLuint = 0;
@@ -132,16 +136,7 @@ impl Glsl3Renderer {
             gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
         }
 
-        Ok(Self {
-            program,
-            vao,
-            ebo,
-            vbo_instance,
-            atlas: vec![Atlas::new(ATLAS_SIZE, false)],
-            current_atlas: 0,
-            active_tex: 0,
-            batch: Batch::new(),
-        })
+        Ok(Self { program, vao, ebo, vbo_instance, active_tex: 0, batch: Batch::new() })
     }
 }
 
@@ -158,6 +153,9 @@ impl<'a> TextRenderer<'a> for Glsl3Renderer {
             gl::UseProgram(self.program.id());
             self.program.set_term_uniforms(size_info);
 
+            // The program might be shared with other windows, so the projection must be updated.
+            update_projection(self.program.u_projection, size_info);
+
             gl::BindVertexArray(self.vao);
             gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ebo);
             gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo_instance);
@@ -167,8 +165,6 @@ impl<'a> TextRenderer<'a> for Glsl3Renderer {
         let res = func(RenderApi {
             active_tex: &mut self.active_tex,
             batch: &mut self.batch,
-            atlas: &mut self.atlas,
-            current_atlas: &mut self.current_atlas,
             program: &mut self.program,
         });
 
@@ -188,11 +184,7 @@ impl<'a> TextRenderer<'a> for Glsl3Renderer {
     }
 
     fn loader_api(&mut self) -> LoaderApi<'_> {
-        LoaderApi {
-            active_tex: &mut self.active_tex,
-            atlas: &mut self.atlas,
-            current_atlas: &mut self.current_atlas,
-        }
+        LoaderApi { active_tex: &mut self.active_tex, is_gles_context: false }
     }
 }
 
@@ -210,8 +202,6 @@ impl Drop for Glsl3Renderer {
 pub struct RenderApi<'a> {
     active_tex: &'a mut GLuint,
     batch: &'a mut Batch,
-    atlas: &'a mut Vec<Atlas>,
-    current_atlas: &'a mut usize,
     program: &'a mut TextShaderProgram,
 }
 
@@ -262,12 +252,8 @@ impl<'a> TextRenderApi<Batch> for RenderApi<'a> {
 }
 
 impl<'a> LoadGlyph for RenderApi<'a> {
-    fn load_glyph(&mut self, rasterized: &RasterizedGlyph) -> Glyph {
-        Atlas::load_glyph(self.active_tex, self.atlas, self.current_atlas, rasterized)
-    }
-
-    fn clear(&mut self) {
-        Atlas::clear_atlas(self.atlas, self.current_atlas)
+    fn load_glyph(&mut self, atlas: &mut GlyphAtlas, rasterized: &RasterizedGlyph) -> Glyph {
+        atlas.load_glyph(self.active_tex, false, rasterized)
     }
 }
 
@@ -405,7 +391,7 @@ impl Batch {
 /// Text drawing program.
 ///
 /// Uniforms are prefixed with "u", and vertex attributes are prefixed with "a".
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TextShaderProgram {
     /// Shader program.
     program: ShaderProgram,
@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::mem;
 
 use ahash::RandomState;
 use crossfont::{
@@ -12,17 +13,20 @@ use crate::config::font::{Font, FontDescription};
 use crate::config::ui_config::Delta;
 use crate::gl::types::*;
 
+use super::atlas::{GlyphAtlas, ATLAS_MEMORY};
 use super::builtin_font;
+use super::rasterizer_pool::{FontStyle, RasterizerPool};
+
+/// Number of glyphs rasterized on the render thread in a single frame.
+///
+/// Additional glyphs missing from the cache are rasterized in the background, showing a blank
+/// placeholder until they're ready.
+const MAX_FRAME_RASTERIZATIONS: usize = 16;
 
 /// `LoadGlyph` allows for copying a rasterized glyph into graphics memory.
 pub trait LoadGlyph {
-    /// Load the rasterized glyph into GPU memory.
-    fn load_glyph(&mut self, rasterized: &RasterizedGlyph) -> Glyph;
-
-    /// Clear any state accumulated from previous loaded glyphs.
-    ///
-    /// This can, for instance, be used to reset the texture Atlas.
-    fn clear(&mut self);
+    /// Load the rasterized glyph into the texture atlas.
+    fn load_glyph(&mut self, atlas: &mut GlyphAtlas, rasterized: &RasterizedGlyph) -> Glyph;
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -74,12 +78,43 @@ pub struct GlyphCache {
     /// Font metrics.
     metrics: Metrics,
 
+    /// Font metrics of other sizes used by panes with their own font size.
+    size_metrics: HashMap<Size, Metrics, RandomState>,
+
     /// Whether to use the built-in font for box drawing characters.
     builtin_box_drawing: bool,
+
+    /// Texture atlases backing the cached glyphs.
+    atlas: GlyphAtlas,
+
+    /// Font configuration used for rasterization.
+    font: Font,
+
+    /// Number of glyphs served from the cache.
+    hits: u64,
+
+    /// Number of glyphs which had to be rasterized.
+    misses: u64,
+
+    /// Threads rasterizing glyphs in the background.
+    rasterizer_pool: RasterizerPool,
+
+    /// Glyphs currently rasterized in the background.
+    pending: HashSet<GlyphKey, RandomState>,
+
+    /// Glyphs rasterized on the render thread since the start of the frame.
+    frame_rasterizations: usize,
+
+    /// Blank glyph shown while a glyph is rasterized in the background.
+    placeholder: Option<Glyph>,
 }
 
 impl GlyphCache {
-    pub fn new(mut rasterizer: Rasterizer, font: &Font) -> Result<GlyphCache, crossfont::Error> {
+    pub fn new(
+        mut rasterizer: Rasterizer,
+        font: &Font,
+        scale_factor: f32,
+    ) -> Result<GlyphCache, crossfont::Error> {
         let (regular, bold, italic, bold_italic) = Self::compute_font_keys(font, &mut rasterizer)?;
 
         // Need to load at least one glyph for the face before calling metrics.
@@ -100,7 +135,16 @@ impl GlyphCache {
             font_offset: font.offset,
             glyph_offset: font.glyph_offset,
             metrics,
+            size_metrics: Default::default(),
             builtin_box_drawing: font.builtin_box_drawing,
+            atlas: Default::default(),
+            font: font.clone(),
+            hits: 0,
+            misses: 0,
+            rasterizer_pool: RasterizerPool::new(font, scale_factor),
+            pending: Default::default(),
+            frame_rasterizations: 0,
+            placeholder: None,
         })
     }
 
@@ -109,12 +153,13 @@ impl GlyphCache {
 
         // Cache all ascii characters.
         for i in 32u8..=126u8 {
-            self.get(GlyphKey { font_key: font, character: i as char, size }, loader, true);
+            let glyph_key = GlyphKey { font_key: font, character: i as char, size };
+            self.load(glyph_key, loader, true, false);
         }
     }
 
     /// Computes font keys for (Regular, Bold, Italic, Bold Italic).
-    fn compute_font_keys(
+    pub(super) fn compute_font_keys(
         font: &Font,
         rasterizer: &mut Rasterizer,
     ) -> Result<(FontKey, FontKey, FontKey, FontKey), crossfont::Error> {
@@ -187,23 +232,46 @@ impl GlyphCache {
     ///
     /// This will fail when the glyph could not be rasterized. Usually this is due to the glyph
     /// not being present in any font.
+    ///
+    /// When many glyphs are missing from the cache at once, they're rasterized in the background
+    /// and a blank placeholder is returned until they have been loaded by
+    /// [`Self::load_rasterized`].
     pub fn get<L: ?Sized>(
         &mut self,
         glyph_key: GlyphKey,
         loader: &mut L,
         show_missing: bool,
     ) -> Glyph
+    where
+        L: LoadGlyph,
+    {
+        self.load(glyph_key, loader, show_missing, true)
+    }
+
+    fn load<L: ?Sized>(
+        &mut self,
+        glyph_key: GlyphKey,
+        loader: &mut L,
+        show_missing: bool,
+        background: bool,
+    ) -> Glyph
     where
         L: LoadGlyph,
     {
         // Try to load glyph from cache.
         if let Some(glyph) = self.cache.get(&glyph_key) {
+            self.hits += 1;
             return *glyph;
         };
 
-        // Rasterize the glyph using the built-in font for special characters or the user's font
-        // for everything else.
-        let rasterized = self
+        if self.pending.contains(&glyph_key) {
+            return self.placeholder(loader);
+        }
+
+        self.misses += 1;
+
+        // Rasterize the glyph using the built-in font for special characters.
+        let builtin = self
             .builtin_box_drawing
             .then(|| {
                 builtin_font::builtin_glyph(
@@ -213,9 +281,122 @@ impl GlyphCache {
                     &self.glyph_offset,
                 )
             })
-            .flatten()
-            .map_or_else(|| self.rasterizer.get_glyph(glyph_key), Ok);
+            .flatten();
+
+        // Move rasterization of bursts of new glyphs off the render thread.
+        if builtin.is_none()
+            && background
+            && self.frame_rasterizations >= MAX_FRAME_RASTERIZATIONS
+            && self.rasterize_in_background(glyph_key)
+        {
+            return self.placeholder(loader);
+        }
+
+        let rasterized = match builtin {
+            Some(builtin) => Ok(builtin),
+            None => {
+                self.frame_rasterizations += 1;
+                self.rasterizer.get_glyph(glyph_key)
+            },
+        };
+
+        self.insert(glyph_key, rasterized, loader, show_missing)
+    }
+
+    /// Load glyphs which finished rasterizing in the background.
+    ///
+    /// This should be called once before drawing each frame. Returns `true` if any glyphs were
+    /// loaded, since cells drawn with their placeholders have to be redrawn.
+    pub fn load_rasterized<L: ?Sized>(&mut self, loader: &mut L) -> bool
+    where
+        L: LoadGlyph,
+    {
+        self.frame_rasterizations = 0;
+
+        let mut loaded = false;
+        while let Some(job) = self.rasterizer_pool.next_rasterized() {
+            if self.pending.remove(&job.glyph_key) {
+                self.insert(job.glyph_key, job.result, loader, true);
+                loaded = true;
+            }
+        }
+        loaded
+    }
+
+    /// Drop all glyphs once the atlas textures exceed their memory limit.
+    ///
+    /// This should be called before drawing a frame, since it invalidates all loaded glyphs.
+    pub fn compact_atlas<L: LoadGlyph>(&mut self, loader: &mut L) {
+        if self.font.max_atlas_memory == 0 {
+            return;
+        }
+
+        // Allow at least two atlases, to avoid clearing glyphs on every frame.
+        let max_memory = (self.font.max_atlas_memory * 1024 * 1024).max(2 * ATLAS_MEMORY);
+        if self.atlas.memory_usage() > max_memory {
+            info!("Glyph atlas exceeded its memory limit, clearing all glyphs");
+            self.atlas.compact();
+            self.reset_glyph_cache(loader);
+        }
+    }
+
+    /// Memory used by the glyph atlas textures, in bytes.
+    pub fn atlas_memory(&self) -> usize {
+        self.atlas.memory_usage()
+    }
+
+    /// Check if glyphs are still being rasterized in the background.
+    pub fn rasterization_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Queue a glyph for rasterization in the background.
+    ///
+    /// Returns `false` if the glyph has to be rasterized on the render thread instead.
+    fn rasterize_in_background(&mut self, glyph_key: GlyphKey) -> bool {
+        let style = match glyph_key.font_key {
+            font_key if font_key == self.font_key => FontStyle::Regular,
+            font_key if font_key == self.bold_key => FontStyle::Bold,
+            font_key if font_key == self.italic_key => FontStyle::Italic,
+            font_key if font_key == self.bold_italic_key => FontStyle::BoldItalic,
+            _ => return false,
+        };
+
+        if !self.rasterizer_pool.rasterize(glyph_key, style) {
+            return false;
+        }
+
+        self.pending.insert(glyph_key);
+
+        true
+    }
+
+    /// Blank glyph shown while rasterizing glyphs in the background.
+    fn placeholder<L: ?Sized>(&mut self, loader: &mut L) -> Glyph
+    where
+        L: LoadGlyph,
+    {
+        match self.placeholder {
+            Some(placeholder) => placeholder,
+            None => {
+                let placeholder = self.load_glyph(loader, Default::default());
+                self.placeholder = Some(placeholder);
+                placeholder
+            },
+        }
+    }
 
+    /// Insert a rasterized glyph into the cache.
+    fn insert<L: ?Sized>(
+        &mut self,
+        glyph_key: GlyphKey,
+        rasterized: Result<RasterizedGlyph, RasterizerError>,
+        loader: &mut L,
+        show_missing: bool,
+    ) -> Glyph
+    where
+        L: LoadGlyph,
+    {
         let glyph = match rasterized {
             Ok(rasterized) => self.load_glyph(loader, rasterized),
             // Load fallback glyph.
@@ -242,7 +423,7 @@ impl GlyphCache {
     /// Load glyph into the atlas.
     ///
     /// This will apply all transforms defined for the glyph cache to the rasterized glyph before
-    pub fn load_glyph<L: ?Sized>(&self, loader: &mut L, mut glyph: RasterizedGlyph) -> Glyph
+    pub fn load_glyph<L: ?Sized>(&mut self, loader: &mut L, mut glyph: RasterizedGlyph) -> Glyph
     where
         L: LoadGlyph,
     {
@@ -260,13 +441,15 @@ impl GlyphCache {
         }
 
         // Add glyph to cache.
-        loader.load_glyph(&glyph)
+        loader.load_glyph(&mut self.atlas, &glyph)
     }
 
     /// Reset currently cached data in both GL and the registry to default state.
     pub fn reset_glyph_cache<L: LoadGlyph>(&mut self, loader: &mut L) {
-        loader.clear();
+        self.atlas.clear();
         self.cache = Default::default();
+        self.pending.clear();
+        self.placeholder = None;
 
         self.load_common_glyphs(loader);
     }
@@ -304,15 +487,54 @@ impl GlyphCache {
         self.italic_key = italic;
         self.bold_italic_key = bold_italic;
         self.metrics = metrics;
+        self.size_metrics.clear();
         self.builtin_box_drawing = font.builtin_box_drawing;
+        self.font = font.clone();
+
+        // Discard glyphs rasterized in the background with the previous font.
+        self.rasterizer_pool.update_font(font, scale_factor as f32);
+        self.pending.clear();
 
         Ok(())
     }
 
+    /// Font configuration used for rasterization.
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
     pub fn font_metrics(&self) -> crossfont::Metrics {
         self.metrics
     }
 
+    /// Font metrics at a size other than the current font size.
+    pub fn metrics_at(&mut self, size: Size) -> Result<Metrics, crossfont::Error> {
+        if size == self.font_size {
+            return Ok(self.metrics);
+        } else if let Some(metrics) = self.size_metrics.get(&size) {
+            return Ok(*metrics);
+        }
+
+        // Need to load at least one glyph for the face before calling metrics.
+        self.rasterizer.get_glyph(GlyphKey { font_key: self.font_key, character: 'm', size })?;
+        let metrics = self.rasterizer.metrics(self.font_key, size)?;
+        self.size_metrics.insert(size, metrics);
+
+        Ok(metrics)
+    }
+
+    /// Change the size new glyphs are drawn at, returning the previous size and metrics.
+    ///
+    /// NOTE: The previous size should be restored once drawing at this size is done.
+    pub fn set_draw_size(&mut self, size: Size, metrics: Metrics) -> (Size, Metrics) {
+        (mem::replace(&mut self.font_size, size), mem::replace(&mut self.metrics, metrics))
+    }
+
+    /// Number of glyph lookups served from the cache and lookups which required rasterization.
+    pub fn lookups(&self) -> (u64, u64) {
+        (self.hits, self.misses)
+    }
+
     /// Prefetch glyphs that are almost guaranteed to be loaded anyways.
     pub fn load_common_glyphs<L: LoadGlyph>(&mut self, loader: &mut L) {
         self.load_glyphs_for_font(self.font_key, loader);
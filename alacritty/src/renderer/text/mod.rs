@@ -13,8 +13,9 @@ mod builtin_font;
 mod gles2;
 mod glsl3;
 pub mod glyph_cache;
+mod rasterizer_pool;
 
-use atlas::Atlas;
+use atlas::GlyphAtlas;
 pub use gles2::Gles2Renderer;
 pub use glsl3::Glsl3Renderer;
 pub use glyph_cache::GlyphCache;
@@ -182,17 +183,12 @@ pub trait TextShader {
 #[derive(Debug)]
 pub struct LoaderApi<'a> {
     active_tex: &'a mut GLuint,
-    atlas: &'a mut Vec<Atlas>,
-    current_atlas: &'a mut usize,
+    is_gles_context: bool,
 }
 
 impl<'a> LoadGlyph for LoaderApi<'a> {
-    fn load_glyph(&mut self, rasterized: &RasterizedGlyph) -> Glyph {
-        Atlas::load_glyph(self.active_tex, self.atlas, self.current_atlas, rasterized)
-    }
-
-    fn clear(&mut self) {
-        Atlas::clear_atlas(self.atlas, self.current_atlas)
+    fn load_glyph(&mut self, atlas: &mut GlyphAtlas, rasterized: &RasterizedGlyph) -> Glyph {
+        atlas.load_glyph(self.active_tex, self.is_gles_context, rasterized)
     }
 }
 
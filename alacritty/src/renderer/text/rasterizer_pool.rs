@@ -0,0 +1,195 @@
+//! Background glyph rasterization.
+
+use std::num::NonZeroUsize;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+use crossfont::{
+    Error as RasterizerError, FontKey, GlyphKey, Rasterize, RasterizedGlyph, Rasterizer, Size,
+};
+use log::error;
+use parking_lot::Mutex;
+
+use alacritty_terminal::thread as named_thread;
+
+use crate::config::font::Font;
+
+use super::glyph_cache::GlyphCache;
+
+/// Maximum number of rasterization threads.
+const MAX_THREADS: usize = 4;
+
+/// Font style of a glyph, used to find the matching font of each thread's rasterizer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FontStyle {
+    Regular = 0,
+    Bold = 1,
+    Italic = 2,
+    BoldItalic = 3,
+}
+
+/// Fonts used for rasterization.
+#[derive(Debug)]
+struct FontSetup {
+    font: Font,
+    scale_factor: f32,
+
+    /// Incremented whenever the fonts change, to discard glyphs of outdated fonts.
+    generation: u64,
+}
+
+/// Glyph which should be rasterized.
+struct Job {
+    setup: Arc<FontSetup>,
+    glyph_key: GlyphKey,
+    style: FontStyle,
+}
+
+/// Result of a rasterization job.
+pub struct RasterizedJob {
+    pub glyph_key: GlyphKey,
+    pub result: Result<RasterizedGlyph, RasterizerError>,
+    generation: u64,
+}
+
+/// Pool of threads rasterizing glyphs in the background.
+///
+/// Rasterizers can't be moved between threads, so every thread loads the fonts with its own
+/// rasterizer. Threads are only spawned once the first glyph is requested.
+pub struct RasterizerPool {
+    setup: Arc<FontSetup>,
+    jobs: Option<Sender<Job>>,
+    results: Option<Receiver<RasterizedJob>>,
+}
+
+impl RasterizerPool {
+    pub fn new(font: &Font, scale_factor: f32) -> Self {
+        let setup = FontSetup { font: font.clone(), scale_factor, generation: 0 };
+        Self { setup: Arc::new(setup), jobs: None, results: None }
+    }
+
+    /// Use new fonts for all following jobs.
+    ///
+    /// Glyphs which are still being rasterized with the previous fonts are discarded.
+    pub fn update_font(&mut self, font: &Font, scale_factor: f32) {
+        let generation = self.setup.generation + 1;
+        let setup = FontSetup { font: font.clone(), scale_factor, generation };
+        self.setup = Arc::new(setup);
+    }
+
+    /// Queue a glyph for rasterization.
+    ///
+    /// Returns `false` if no rasterization thread could be started.
+    pub fn rasterize(&mut self, glyph_key: GlyphKey, style: FontStyle) -> bool {
+        if self.jobs.is_none() {
+            self.spawn_threads();
+        }
+
+        let job = Job { setup: self.setup.clone(), glyph_key, style };
+        match &self.jobs {
+            Some(jobs) => jobs.send(job).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Take the next rasterized glyph, skipping glyphs of outdated fonts.
+    pub fn next_rasterized(&mut self) -> Option<RasterizedJob> {
+        let results = self.results.as_ref()?;
+        loop {
+            match results.try_recv() {
+                Ok(job) if job.generation == self.setup.generation => return Some(job),
+                Ok(_) => continue,
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    self.jobs = None;
+                    self.results = None;
+                    return None;
+                },
+            }
+        }
+    }
+
+    fn spawn_threads(&mut self) {
+        let num_threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let num_threads = num_threads.saturating_sub(1).clamp(1, MAX_THREADS);
+
+        let (jobs_tx, jobs_rx) = mpsc::channel();
+        let (results_tx, results_rx) = mpsc::channel();
+        let jobs_rx = Arc::new(Mutex::new(jobs_rx));
+
+        for _ in 0..num_threads {
+            let jobs_rx = jobs_rx.clone();
+            let results_tx = results_tx.clone();
+            named_thread::spawn_named("glyph rasterizer", move || work(jobs_rx, results_tx));
+        }
+
+        self.jobs = Some(jobs_tx);
+        self.results = Some(results_rx);
+    }
+}
+
+/// Fonts loaded by a rasterization thread.
+struct ThreadFonts {
+    rasterizer: Rasterizer,
+
+    /// Font keys indexed by [`FontStyle`].
+    keys: [FontKey; 4],
+
+    generation: u64,
+}
+
+impl ThreadFonts {
+    fn new(setup: &FontSetup) -> Result<Self, RasterizerError> {
+        let mut rasterizer = Rasterizer::new(setup.scale_factor)?;
+        let (regular, bold, italic, bold_italic) =
+            GlyphCache::compute_font_keys(&setup.font, &mut rasterizer)?;
+        let keys = [regular, bold, italic, bold_italic];
+        Ok(Self { rasterizer, keys, generation: setup.generation })
+    }
+
+    fn rasterize(
+        &mut self,
+        style: FontStyle,
+        character: char,
+        size: Size,
+    ) -> Result<RasterizedGlyph, RasterizerError> {
+        let font_key = self.keys[style as usize];
+        self.rasterizer.get_glyph(GlyphKey { font_key, character, size })
+    }
+}
+
+/// Rasterize glyphs until the pool is dropped.
+fn work(jobs: Arc<Mutex<Receiver<Job>>>, results: Sender<RasterizedJob>) {
+    let mut fonts: Option<ThreadFonts> = None;
+
+    loop {
+        // Release the lock before rasterizing, so other threads can take the next job.
+        let job = match jobs.lock().recv() {
+            Ok(job) => job,
+            Err(_) => break,
+        };
+
+        let generation = job.setup.generation;
+        if fonts.as_ref().map_or(true, |fonts| fonts.generation != generation) {
+            fonts = match ThreadFonts::new(&job.setup) {
+                Ok(fonts) => Some(fonts),
+                Err(err) => {
+                    error!("Unable to load fonts for glyph rasterization: {}", err);
+                    None
+                },
+            };
+        }
+
+        let GlyphKey { character, size, .. } = job.glyph_key;
+        let result = match &mut fonts {
+            Some(fonts) => fonts.rasterize(job.style, character, size),
+            None => Err(RasterizerError::UnknownFontKey),
+        };
+
+        let rasterized = RasterizedJob { glyph_key: job.glyph_key, result, generation };
+        if results.send(rasterized).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! GPU time spent in individual render passes.
+
+use std::time::Duration;
+
+use crate::gl;
+use crate::gl::types::*;
+
+/// Number of frames whose queries can be in flight at once.
+///
+/// Results are read back a few frames late, to avoid stalling the pipeline while waiting for the
+/// GPU to catch up.
+const QUERY_FRAMES: usize = 3;
+
+/// Render passes measured by the GPU timer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RenderPass {
+    /// Terminal grid text.
+    Text = 0,
+    /// Quads, like the cursor and undercurls.
+    Quads = 1,
+}
+
+/// Number of measured render passes.
+const PASSES: usize = 2;
+
+/// GPU time of the most recently completed frame for every render pass.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PassTimes {
+    pub text: Option<Duration>,
+    pub quads: Option<Duration>,
+}
+
+/// Timer for render passes using `GL_TIME_ELAPSED` queries.
+#[derive(Debug)]
+pub struct GpuTimer {
+    /// Query objects for every in-flight frame and render pass.
+    queries: [[GLuint; PASSES]; QUERY_FRAMES],
+
+    /// Queries which were issued and whose result hasn't been read yet.
+    issued: [[bool; PASSES]; QUERY_FRAMES],
+
+    /// Index of the frame queries are currently issued for.
+    frame: usize,
+
+    /// Last measured time of every render pass.
+    times: [Option<Duration>; PASSES],
+}
+
+impl GpuTimer {
+    pub fn new() -> Self {
+        let mut queries = [[0; PASSES]; QUERY_FRAMES];
+        unsafe {
+            gl::GenQueries((QUERY_FRAMES * PASSES) as GLsizei, queries.as_mut_ptr().cast());
+        }
+
+        Self { queries, issued: Default::default(), frame: 0, times: Default::default() }
+    }
+
+    /// Start measuring a render pass.
+    ///
+    /// Only one pass can be measured at a time.
+    pub fn begin(&mut self, pass: RenderPass) {
+        unsafe {
+            gl::BeginQuery(gl::TIME_ELAPSED, self.queries[self.frame][pass as usize]);
+        }
+    }
+
+    /// Stop measuring a render pass.
+    pub fn end(&mut self, pass: RenderPass) {
+        unsafe {
+            gl::EndQuery(gl::TIME_ELAPSED);
+        }
+        self.issued[self.frame][pass as usize] = true;
+    }
+
+    /// Advance to the next frame, collecting the results of the oldest frame's queries.
+    pub fn finish_frame(&mut self) {
+        self.frame = (self.frame + 1) % QUERY_FRAMES;
+
+        for pass in 0..PASSES {
+            if !self.issued[self.frame][pass] {
+                continue;
+            }
+            self.issued[self.frame][pass] = false;
+
+            let query = self.queries[self.frame][pass];
+            unsafe {
+                let mut available = 0;
+                gl::GetQueryObjectiv(query, gl::QUERY_RESULT_AVAILABLE, &mut available);
+
+                // Drop results which are still not available, rather than waiting for them.
+                if available == 0 {
+                    continue;
+                }
+
+                let mut elapsed: GLuint64 = 0;
+                gl::GetQueryObjectui64v(query, gl::QUERY_RESULT, &mut elapsed);
+                self.times[pass] = Some(Duration::from_nanos(elapsed));
+            }
+        }
+    }
+
+    /// Last measured time of every render pass.
+    pub fn pass_times(&self) -> PassTimes {
+        PassTimes {
+            text: self.times[RenderPass::Text as usize],
+            quads: self.times[RenderPass::Quads as usize],
+        }
+    }
+}
+
+impl Drop for GpuTimer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteQueries((QUERY_FRAMES * PASSES) as GLsizei, self.queries.as_ptr().cast());
+        }
+    }
+}
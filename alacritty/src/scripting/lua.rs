@@ -0,0 +1,364 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use log::{error, warn};
+use mlua::{
+    Error as LuaError, Function, HookTriggers, IntoLuaMulti, Lua, RegistryKey, Result as LuaResult,
+    Table, Value,
+};
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::color::Rgb;
+use alacritty_terminal::term::search::RegexSearch;
+use alacritty_terminal::term::Term;
+
+use crate::config::scripting::Scripting as ScriptingConfig;
+use crate::display::plugin_overlay::{OverlayQuad, OverlayText};
+use crate::scripting::{ScriptEvent, ScriptRequest};
+
+/// Maximum time plugin code may run before it is interrupted.
+const MAX_RUNTIME: Duration = Duration::from_secs(1);
+
+/// Number of Lua instructions between checks of the plugin runtime.
+const RUNTIME_CHECK_INTERVAL: u32 = 10_000;
+
+/// Maximum number of lines matched against output handlers at once.
+const MAX_SCANNED_LINES: usize = 1_000;
+
+/// Handlers registered by plugins.
+#[derive(Default)]
+struct Handlers {
+    actions: HashMap<String, RegistryKey>,
+    events: Vec<(&'static str, RegistryKey)>,
+    output: Vec<(RegexSearch, RegistryKey)>,
+}
+
+/// Lua state shared by all plugins of a window.
+pub struct Runtime {
+    lua: Lua,
+    handlers: Rc<RefCell<Handlers>>,
+
+    /// Time at which the running plugin code was entered.
+    started: Rc<Cell<Instant>>,
+
+    /// Index of the next line matched against output handlers, counted from the first line
+    /// scrolled off the screen.
+    next_line: usize,
+}
+
+impl Runtime {
+    pub fn new(config: &ScriptingConfig) -> Self {
+        let runtime = Self {
+            lua: Lua::new(),
+            handlers: Default::default(),
+            started: Rc::new(Cell::new(Instant::now())),
+            next_line: 0,
+        };
+
+        // Interrupt plugins which take too long, instead of freezing the terminal.
+        let started = runtime.started.clone();
+        let triggers = HookTriggers::new().every_nth_instruction(RUNTIME_CHECK_INTERVAL);
+        runtime.lua.set_hook(triggers, move |_, _| {
+            if started.get().elapsed() > MAX_RUNTIME {
+                Err(LuaError::RuntimeError("plugin exceeded its maximum runtime".into()))
+            } else {
+                Ok(())
+            }
+        });
+
+        if let Err(err) = runtime.register_api() {
+            error!("Unable to initialize Lua plugins: {}", err);
+            return runtime;
+        }
+
+        for path in &config.plugins {
+            if let Err(err) = runtime.load(path) {
+                error!("Unable to load plugin {:?}: {}", path, err);
+            }
+        }
+
+        runtime
+    }
+
+    /// Run an action registered by a plugin.
+    pub fn run_action<T>(&self, name: &str, terminal: &Term<T>) -> Vec<ScriptRequest> {
+        let handler = self.handlers.borrow().actions.get(name).and_then(|key| self.handler(key));
+        match handler {
+            Some(handler) => self.call(handler, terminal, ()),
+            None => {
+                warn!("No plugin registered the action '{}'", name);
+                Vec::new()
+            },
+        }
+    }
+
+    /// Notify plugins about a terminal event.
+    pub fn emit<T>(&self, event: ScriptEvent, terminal: &Term<T>) -> Vec<ScriptRequest> {
+        let handlers: Vec<_> = self
+            .handlers
+            .borrow()
+            .events
+            .iter()
+            .filter(|(name, _)| *name == event.name())
+            .filter_map(|(_, key)| self.handler(key))
+            .collect();
+
+        let mut requests = Vec::new();
+        for handler in handlers {
+            requests.extend(match &event {
+                ScriptEvent::Bell | ScriptEvent::Prompt => self.call(handler, terminal, ()),
+                ScriptEvent::CommandFinished(code) => self.call(handler, terminal, *code),
+                ScriptEvent::Title(title) => self.call(handler, terminal, title.as_str()),
+                ScriptEvent::Focus(focused) => self.call(handler, terminal, *focused),
+            });
+        }
+        requests
+    }
+
+    /// Check if any output handlers are registered.
+    pub fn watches_output(&self) -> bool {
+        !self.handlers.borrow().output.is_empty()
+    }
+
+    /// Match all lines completed since the last scan against the output handlers.
+    pub fn scan_output<T>(&mut self, terminal: &Term<T>) -> Vec<ScriptRequest> {
+        let grid = terminal.grid();
+        let scrolled_lines = grid.scrolled_lines();
+        let cursor_line = scrolled_lines + grid.cursor.point.line.0 as usize;
+
+        // Skip lines which were rotated out of the history, or would take too long to scan.
+        let first = self
+            .next_line
+            .min(cursor_line)
+            .max(scrolled_lines.saturating_sub(grid.history_size()))
+            .max(cursor_line.saturating_sub(MAX_SCANNED_LINES));
+        self.next_line = cursor_line;
+
+        let mut matches = Vec::new();
+        let handlers = self.handlers.borrow();
+        for index in first..cursor_line {
+            let line = Line((index as i64 - scrolled_lines as i64) as i32);
+            let start = Point::new(line, Column(0));
+            let end = Point::new(line, terminal.last_column());
+
+            for (regex, key) in &handlers.output {
+                let regex_match = match terminal.regex_search_right(regex, start, end) {
+                    Some(regex_match) => regex_match,
+                    None => continue,
+                };
+
+                if let Some(handler) = self.handler(key) {
+                    let text = terminal.bounds_to_string(*regex_match.start(), *regex_match.end());
+                    matches.push((handler, text, line));
+                }
+            }
+        }
+        drop(handlers);
+
+        let mut requests = Vec::new();
+        for (handler, text, line) in matches {
+            let line_text = lines_text(terminal, line, line);
+            requests.extend(self.call(handler, terminal, (text, line_text, line.0)));
+        }
+        requests
+    }
+
+    /// Add the `alacritty` table used by plugins to register their handlers.
+    fn register_api(&self) -> LuaResult<()> {
+        let lua = &self.lua;
+        let alacritty = lua.create_table()?;
+
+        let handlers = self.handlers.clone();
+        let action = lua.create_function(move |lua, (name, handler): (String, Function<'_>)| {
+            let key = lua.create_registry_value(handler)?;
+            handlers.borrow_mut().actions.insert(name, key);
+            Ok(())
+        })?;
+        alacritty.set("action", action)?;
+
+        let handlers = self.handlers.clone();
+        let on = lua.create_function(move |lua, (event, handler): (String, Function<'_>)| {
+            let name = ScriptEvent::NAMES
+                .into_iter()
+                .find(|name| *name == event)
+                .ok_or_else(|| LuaError::RuntimeError(format!("unknown event '{event}'")))?;
+            let key = lua.create_registry_value(handler)?;
+            handlers.borrow_mut().events.push((name, key));
+            Ok(())
+        })?;
+        alacritty.set("on", on)?;
+
+        let handlers = self.handlers.clone();
+        let on_output =
+            lua.create_function(move |lua, (regex, handler): (String, Function<'_>)| {
+                let regex = RegexSearch::new(&regex).map_err(|err| {
+                    LuaError::RuntimeError(format!("invalid regex '{regex}': {err}"))
+                })?;
+                let key = lua.create_registry_value(handler)?;
+                handlers.borrow_mut().output.push((regex, key));
+                Ok(())
+            })?;
+        alacritty.set("on_output", on_output)?;
+
+        lua.globals().set("alacritty", alacritty)
+    }
+
+    /// Run a plugin file.
+    fn load(&self, path: &Path) -> LuaResult<()> {
+        // Resolve paths relative to user's home directory.
+        let path = match (path.strip_prefix("~/"), home::home_dir()) {
+            (Ok(stripped), Some(home_dir)) => home_dir.join(stripped),
+            _ => path.to_path_buf(),
+        };
+
+        let source = fs::read_to_string(&path).map_err(LuaError::external)?;
+
+        self.started.set(Instant::now());
+        self.lua.load(&source).set_name(format!("@{}", path.display())).exec()
+    }
+
+    /// Get a handler from the Lua registry.
+    fn handler(&self, key: &RegistryKey) -> Option<Function<'_>> {
+        match self.lua.registry_value(key) {
+            Ok(handler) => Some(handler),
+            Err(err) => {
+                error!("Invalid plugin handler: {}", err);
+                None
+            },
+        }
+    }
+
+    /// Call a plugin handler.
+    ///
+    /// The handler is passed a context table for accessing the terminal as its first argument,
+    /// followed by `args`.
+    fn call<'lua, T, A>(
+        &'lua self,
+        handler: Function<'lua>,
+        terminal: &Term<T>,
+        args: A,
+    ) -> Vec<ScriptRequest>
+    where
+        A: IntoLuaMulti<'lua>,
+    {
+        let requests = RefCell::new(Vec::new());
+        let push = |request| requests.borrow_mut().push(request);
+
+        self.started.set(Instant::now());
+        let result = self.lua.scope(|scope| {
+            let context = self.lua.create_table()?;
+
+            let text = scope.create_function(|_, (first, last): (i32, Option<i32>)| {
+                Ok(lines_text(terminal, Line(first), Line(last.unwrap_or(first))))
+            })?;
+            context.set("text", text)?;
+
+            let selection = scope.create_function(|_, ()| Ok(terminal.selection_to_string()))?;
+            context.set("selection", selection)?;
+
+            let cursor = scope.create_function(|_, ()| {
+                let point = terminal.grid().cursor.point;
+                Ok((point.line.0, point.column.0))
+            })?;
+            context.set("cursor", cursor)?;
+
+            let size =
+                scope.create_function(|_, ()| Ok((terminal.screen_lines(), terminal.columns())))?;
+            context.set("size", size)?;
+
+            let display_offset =
+                scope.create_function(|_, ()| Ok(terminal.grid().display_offset()))?;
+            context.set("display_offset", display_offset)?;
+
+            let write = scope.create_function(|_, text: String| {
+                push(ScriptRequest::Write(text));
+                Ok(())
+            })?;
+            context.set("write", write)?;
+
+            let paste = scope.create_function(|_, text: String| {
+                push(ScriptRequest::Paste(text));
+                Ok(())
+            })?;
+            context.set("paste", paste)?;
+
+            let draw_quad = scope.create_function(|_, quad: Table<'_>| {
+                push(ScriptRequest::DrawQuad(overlay_quad(quad)?));
+                Ok(())
+            })?;
+            context.set("draw_quad", draw_quad)?;
+
+            let draw_text = scope.create_function(|_, text: Table<'_>| {
+                push(ScriptRequest::DrawText(overlay_text(text)?));
+                Ok(())
+            })?;
+            context.set("draw_text", draw_text)?;
+
+            let clear_overlay = scope.create_function(|_, ()| {
+                push(ScriptRequest::ClearOverlay);
+                Ok(())
+            })?;
+            context.set("clear_overlay", clear_overlay)?;
+
+            let mut args = args.into_lua_multi(&self.lua)?;
+            args.push_front(Value::Table(context));
+            handler.call::<_, ()>(args)
+        });
+
+        if let Err(err) = result {
+            error!("Plugin error: {}", err);
+        }
+
+        requests.into_inner()
+    }
+}
+
+/// Text of all lines from `first` to `last`, clamped to the lines available.
+fn lines_text<T>(terminal: &Term<T>, first: Line, last: Line) -> String {
+    let first = first.max(terminal.topmost_line());
+    let last = last.min(terminal.bottommost_line());
+    if first > last {
+        return String::new();
+    }
+
+    let start = Point::new(first, Column(0));
+    let end = Point::new(last, terminal.last_column());
+    terminal.bounds_to_string(start, end)
+}
+
+/// Parse the quad passed to `draw_quad`.
+fn overlay_quad(quad: Table<'_>) -> LuaResult<OverlayQuad> {
+    Ok(OverlayQuad {
+        line: quad.get("line")?,
+        column: quad.get("column")?,
+        lines: quad.get::<_, Option<usize>>("lines")?.unwrap_or(1),
+        columns: quad.get::<_, Option<usize>>("columns")?.unwrap_or(1),
+        color: parse_color(&quad.get::<_, String>("color")?)?,
+        alpha: quad.get::<_, Option<f32>>("alpha")?.unwrap_or(1.).clamp(0., 1.),
+    })
+}
+
+/// Parse the text passed to `draw_text`.
+fn overlay_text(text: Table<'_>) -> LuaResult<OverlayText> {
+    let fg = text.get::<_, Option<String>>("fg")?;
+    let bg = text.get::<_, Option<String>>("bg")?;
+
+    Ok(OverlayText {
+        line: text.get("line")?,
+        column: text.get("column")?,
+        text: text.get("text")?,
+        fg: fg.as_deref().map(parse_color).transpose()?,
+        bg: bg.as_deref().map(parse_color).transpose()?,
+    })
+}
+
+/// Parse a color in the `#RRGGBB` format.
+fn parse_color(color: &str) -> LuaResult<Rgb> {
+    Rgb::from_str(color).map_err(|_| LuaError::RuntimeError(format!("invalid color '{color}'")))
+}
@@ -0,0 +1,136 @@
+//! Lua plugins extending the terminal with custom actions, event handlers and overlays.
+//!
+//! Plugins never access the terminal directly. They read its content while they are running and
+//! return [`ScriptRequest`]s, which are applied by the window once the plugin has finished.
+
+#[cfg(not(feature = "lua"))]
+use log::warn;
+
+use alacritty_terminal::term::Term;
+
+use crate::config::scripting::Scripting as ScriptingConfig;
+use crate::display::plugin_overlay::{OverlayQuad, OverlayText};
+
+#[cfg(feature = "lua")]
+mod lua;
+
+/// Terminal events plugins can react to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptEvent {
+    /// Terminal bell rang.
+    Bell,
+
+    /// Shell prompt reported through OSC 133 has started.
+    Prompt,
+
+    /// Shell command reported through OSC 133 has finished, with its exit code.
+    CommandFinished(Option<i32>),
+
+    /// Window title has changed.
+    Title(String),
+
+    /// Window gained or lost focus.
+    Focus(bool),
+}
+
+#[cfg_attr(not(feature = "lua"), allow(dead_code))]
+impl ScriptEvent {
+    /// Names of all events, as used by plugins to register handlers.
+    pub const NAMES: [&'static str; 5] = ["bell", "prompt", "command_finished", "title", "focus"];
+
+    /// Name used by plugins to register handlers for this event.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bell => "bell",
+            Self::Prompt => "prompt",
+            Self::CommandFinished(_) => "command_finished",
+            Self::Title(_) => "title",
+            Self::Focus(_) => "focus",
+        }
+    }
+}
+
+/// Changes requested by a plugin.
+#[cfg_attr(not(feature = "lua"), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptRequest {
+    /// Write bytes to the PTY, as if they were typed.
+    Write(String),
+
+    /// Paste text, respecting bracketed paste mode.
+    Paste(String),
+
+    /// Add a quad to the overlay.
+    DrawQuad(OverlayQuad),
+
+    /// Add text to the overlay.
+    DrawText(OverlayText),
+
+    /// Remove everything from the overlay.
+    ClearOverlay,
+}
+
+/// Plugins loaded for a window.
+pub struct Scripting {
+    #[cfg(feature = "lua")]
+    runtime: Option<lua::Runtime>,
+}
+
+impl Scripting {
+    /// Load all configured plugins.
+    pub fn new(config: &ScriptingConfig) -> Self {
+        #[cfg(feature = "lua")]
+        {
+            let runtime = (!config.plugins.is_empty()).then(|| lua::Runtime::new(config));
+            Self { runtime }
+        }
+
+        #[cfg(not(feature = "lua"))]
+        {
+            if !config.plugins.is_empty() {
+                warn!("Ignoring plugins, Alacritty was built without Lua support");
+            }
+            Self {}
+        }
+    }
+
+    /// Run an action registered by a plugin.
+    pub fn run_action<T>(&self, _name: &str, _terminal: &Term<T>) -> Vec<ScriptRequest> {
+        #[cfg(feature = "lua")]
+        if let Some(runtime) = &self.runtime {
+            return runtime.run_action(_name, _terminal);
+        }
+
+        Vec::new()
+    }
+
+    /// Notify plugins about a terminal event.
+    pub fn emit<T>(&self, _event: ScriptEvent, _terminal: &Term<T>) -> Vec<ScriptRequest> {
+        #[cfg(feature = "lua")]
+        if let Some(runtime) = &self.runtime {
+            return runtime.emit(_event, _terminal);
+        }
+
+        Vec::new()
+    }
+
+    /// Check if any plugin is interested in new terminal output.
+    pub fn watches_output(&self) -> bool {
+        #[cfg(feature = "lua")]
+        if let Some(runtime) = &self.runtime {
+            return runtime.watches_output();
+        }
+
+        false
+    }
+
+    /// Match lines written since the last scan against the output handlers of all plugins.
+    pub fn scan_output<T>(&mut self, _terminal: &Term<T>) -> Vec<ScriptRequest> {
+        #[cfg(feature = "lua")]
+        if let Some(runtime) = &mut self.runtime {
+            return runtime.scan_output(_terminal);
+        }
+
+        Vec::new()
+    }
+}
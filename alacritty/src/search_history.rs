@@ -0,0 +1,131 @@
+//! Search history persisted across windows and restarts.
+
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use log::warn;
+
+/// Name of the file storing the search history.
+const HISTORY_FILE_NAME: &str = "search_history";
+
+/// Load the search history, starting with the most recent entry.
+pub fn load(history_size: usize) -> VecDeque<String> {
+    let path = match history_path() {
+        Some(path) => path,
+        None => return VecDeque::new(),
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(history) => parse(&history, history_size),
+        Err(err) if err.kind() == ErrorKind::NotFound => VecDeque::new(),
+        Err(err) => {
+            warn!("Unable to read search history {:?}: {}", path, err);
+            VecDeque::new()
+        },
+    }
+}
+
+/// Add a new regex to the front of the persistent search history.
+pub fn store(regex: &str, history_size: usize) {
+    if let Err(err) = try_store(regex, history_size) {
+        warn!("Unable to write search history: {}", err);
+    }
+}
+
+fn try_store(regex: &str, history_size: usize) -> io::Result<()> {
+    let path = history_path()
+        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "no history directory"))?;
+
+    // Merge with the history file, to keep searches from other windows.
+    let mut history = match fs::read_to_string(&path) {
+        Ok(history) => parse(&history, history_size),
+        Err(err) if err.kind() == ErrorKind::NotFound => VecDeque::new(),
+        Err(err) => return Err(err),
+    };
+    push_entry(&mut history, regex.into(), history_size);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut contents = String::new();
+    for entry in history {
+        contents.push_str(&entry);
+        contents.push('\n');
+    }
+
+    // Replace the file atomically, so other windows never read a partially written history.
+    let tmp_path = path.with_extension(format!("{}.tmp", process::id()));
+    let result =
+        write_private(&tmp_path, contents.as_bytes()).and_then(|_| fs::rename(&tmp_path, &path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Write `contents` to a file which is only accessible by the current user.
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options.open(path)?.write_all(contents)
+}
+
+/// Add an entry to the front of the history, removing any duplicates.
+pub fn push_entry(history: &mut VecDeque<String>, entry: String, history_size: usize) {
+    history.retain(|old_entry| old_entry != &entry);
+    history.push_front(entry);
+    history.truncate(history_size);
+}
+
+/// Parse the search history file's contents.
+fn parse(contents: &str, history_size: usize) -> VecDeque<String> {
+    let mut history = VecDeque::new();
+    for line in contents.lines().rev().filter(|line| !line.is_empty()) {
+        push_entry(&mut history, line.into(), usize::MAX);
+    }
+    history.truncate(history_size);
+    history
+}
+
+/// Location of the search history file.
+#[cfg(not(windows))]
+fn history_path() -> Option<PathBuf> {
+    xdg::BaseDirectories::with_prefix("alacritty")
+        .ok()
+        .and_then(|xdg| xdg.place_state_file(HISTORY_FILE_NAME).ok())
+}
+
+/// Location of the search history file.
+#[cfg(windows)]
+fn history_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|path| path.join("alacritty").join(HISTORY_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_deduplicated() {
+        let mut history = VecDeque::from([String::from("b"), String::from("a")]);
+        push_entry(&mut history, String::from("a"), 2);
+        assert_eq!(history, [String::from("a"), String::from("b")]);
+
+        push_entry(&mut history, String::from("c"), 2);
+        assert_eq!(history, [String::from("c"), String::from("a")]);
+    }
+
+    #[test]
+    fn parse_history() {
+        let history = parse("first\n\nsecond\nfirst\nthird\n", 2);
+        assert_eq!(history, [String::from("first"), String::from("second")]);
+    }
+}
@@ -0,0 +1,171 @@
+//! Playback of the audible bell.
+
+#[cfg(feature = "audio")]
+use std::error::Error;
+#[cfg(feature = "audio")]
+use std::fs::File;
+#[cfg(feature = "audio")]
+use std::io::BufReader;
+use std::path::Path;
+#[cfg(feature = "audio")]
+use std::path::PathBuf;
+#[cfg(feature = "audio")]
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "audio")]
+use log::error;
+use log::warn;
+#[cfg(feature = "audio")]
+use rodio::{Decoder, OutputStream, Source};
+
+#[cfg(feature = "audio")]
+use alacritty_terminal::thread;
+
+use crate::config::bell::BellConfig;
+
+/// Player of the bell sound, shared by all windows.
+static PLAYER: Mutex<Option<Player>> = Mutex::new(None);
+
+/// Play the configured bell sound.
+///
+/// Sounds are dropped while the previous sound is more recent than `bell.sound_interval`.
+pub fn play_bell(config: &BellConfig) {
+    let path = match &config.sound {
+        Some(path) => path,
+        None => return,
+    };
+
+    let mut player = PLAYER.lock().unwrap();
+    let player = player.get_or_insert_with(Player::new);
+    if player.rate_limit.allow(Instant::now(), config.sound_interval()) {
+        player.play(path, config.volume.as_f32());
+    }
+}
+
+/// Handle to the audio thread.
+struct Player {
+    #[cfg(feature = "audio")]
+    sender: Sender<Sound>,
+    rate_limit: RateLimit,
+}
+
+impl Player {
+    #[cfg(feature = "audio")]
+    fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn_named("bell sound", move || play_sounds(receiver));
+
+        Self { sender, rate_limit: RateLimit::default() }
+    }
+
+    #[cfg(not(feature = "audio"))]
+    fn new() -> Self {
+        warn!("Unable to play bell sound, Alacritty was built without the `audio` feature");
+        Self { rate_limit: RateLimit::default() }
+    }
+
+    #[cfg(feature = "audio")]
+    fn play(&self, path: &Path, volume: f32) {
+        let sound = Sound { path: path.to_owned(), volume };
+        if self.sender.send(sound).is_err() {
+            warn!("Unable to play bell sound, the audio thread is gone");
+        }
+    }
+
+    #[cfg(not(feature = "audio"))]
+    fn play(&self, _path: &Path, _volume: f32) {}
+}
+
+/// Sound queued for playback.
+#[cfg(feature = "audio")]
+struct Sound {
+    path: PathBuf,
+    volume: f32,
+}
+
+/// Play sounds until all senders are gone.
+///
+/// The output device is only opened once the first sound is played and kept open afterwards.
+#[cfg(feature = "audio")]
+fn play_sounds(receiver: Receiver<Sound>) {
+    let mut output = None;
+    for sound in receiver {
+        if output.is_none() {
+            match OutputStream::try_default() {
+                Ok(stream) => output = Some(stream),
+                Err(err) => {
+                    error!("Unable to open audio output: {err}");
+                    continue;
+                },
+            }
+        }
+
+        let source = match decode(&sound.path) {
+            Ok(source) => source,
+            Err(err) => {
+                error!("Unable to load bell sound {:?}: {err}", sound.path);
+                continue;
+            },
+        };
+
+        let (_stream, handle) = output.as_ref().unwrap();
+        if let Err(err) = handle.play_raw(source.amplify(sound.volume).convert_samples()) {
+            error!("Unable to play bell sound: {err}");
+        }
+    }
+}
+
+/// Decode the sound file at `path`, which may start with `~/` for the home directory.
+#[cfg(feature = "audio")]
+fn decode(path: &Path) -> Result<Decoder<BufReader<File>>, Box<dyn Error>> {
+    let path = match (path.strip_prefix("~/"), home::home_dir()) {
+        (Ok(stripped), Some(home_dir)) => home_dir.join(stripped),
+        _ => path.to_owned(),
+    };
+
+    let file = File::open(path)?;
+    Ok(Decoder::new(BufReader::new(file))?)
+}
+
+/// Limit for how often the bell sound is played.
+#[derive(Default, Debug)]
+struct RateLimit {
+    last: Option<Instant>,
+}
+
+impl RateLimit {
+    /// Check if a sound may be played at `now`, recording it as played if so.
+    fn allow(&mut self, now: Instant, interval: Duration) -> bool {
+        if self.last.map_or(false, |last| now.saturating_duration_since(last) < interval) {
+            return false;
+        }
+
+        self.last = Some(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit() {
+        let interval = Duration::from_millis(100);
+        let start = Instant::now();
+        let mut rate_limit = RateLimit::default();
+
+        assert!(rate_limit.allow(start, interval));
+        assert!(!rate_limit.allow(start + Duration::from_millis(50), interval));
+        assert!(rate_limit.allow(start + Duration::from_millis(100), interval));
+
+        // Dropped sounds don't extend the limit.
+        assert!(!rate_limit.allow(start + Duration::from_millis(150), interval));
+        assert!(rate_limit.allow(start + Duration::from_millis(200), interval));
+
+        // Without interval, every sound is played.
+        assert!(rate_limit.allow(start + Duration::from_millis(200), Duration::ZERO));
+    }
+}
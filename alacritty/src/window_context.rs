@@ -1,13 +1,17 @@
 //! Terminal window context.
 
 use std::error::Error;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Write;
-use std::mem;
 #[cfg(not(windows))]
-use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+#[cfg(not(windows))]
+use std::process;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Instant;
+use std::{iter, mem};
 
 use crossfont::Size;
 use glutin::config::GetGlConfig;
@@ -15,35 +19,52 @@ use glutin::context::NotCurrentContext;
 use glutin::display::GetGlDisplay;
 #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
 use glutin::platform::x11::X11GlConfigExt;
-use log::{error, info};
+use log::{error, info, warn};
 use serde_json as json;
-use winit::event::{Event as WinitEvent, Modifiers};
+use winit::dpi::PhysicalPosition;
+use winit::event::{
+    DeviceId, ElementState, Event as WinitEvent, Modifiers, MouseButton, WindowEvent,
+};
 use winit::event_loop::{EventLoopProxy, EventLoopWindowTarget};
 use winit::window::raw_window_handle::HasRawDisplayHandle;
 use winit::window::WindowId;
 
 use alacritty_config::SerdeReplace;
-use alacritty_terminal::event::Event as TerminalEvent;
+use alacritty_terminal::config::PtyConfig;
+use alacritty_terminal::event::{Event as TerminalEvent, Notify, OnResize};
 use alacritty_terminal::event_loop::{EventLoop as PtyEventLoop, Msg, Notifier};
 use alacritty_terminal::grid::{Dimensions, Scroll};
 use alacritty_terminal::index::Direction;
+use alacritty_terminal::replay::{Cast, Player};
 use alacritty_terminal::sync::FairMutex;
 use alacritty_terminal::term::test::TermSize;
 use alacritty_terminal::term::{Term, TermMode};
 use alacritty_terminal::tty;
 
+use crate::bench::Bench;
 #[cfg(unix)]
 use crate::cli::IpcConfig;
 use crate::cli::WindowOptions;
 use crate::clipboard::Clipboard;
-use crate::config::UiConfig;
+use crate::config::{serde_utils, UiConfig};
+use crate::display::color::List;
+use crate::display::title_bar::{TitleBarButton, TitleBarHit};
 use crate::display::window::Window;
-use crate::display::Display;
-use crate::event::{ActionContext, Event, EventProxy, Mouse, SearchState, TouchPurpose};
+use crate::display::{Display, SizeInfo};
+use crate::event::{
+    ActionContext, Event, EventProxy, EventType, Mouse, PendingLaunch, PendingReleases,
+    SearchState, TouchPurpose,
+};
+use crate::input::{self, ActionContext as _};
+#[cfg(unix)]
+use crate::ipc::{IpcWindow, SocketReply};
+use crate::layout::LayoutSplit;
 use crate::logging::LOG_TARGET_IPC_CONFIG;
 use crate::message_bar::MessageBuffer;
+use crate::pane::{self, Pane, PaneAction, PaneDirection, PaneId, PaneLayout, PaneRect};
 use crate::scheduler::Scheduler;
-use crate::{input, renderer};
+use crate::scripting::Scripting;
+use crate::{notification, renderer};
 
 /// Event context for one individual Alacritty window.
 pub struct WindowContext {
@@ -51,21 +72,32 @@ pub struct WindowContext {
     pub display: Display,
     pub dirty: bool,
     event_queue: Vec<WinitEvent<Event>>,
-    terminal: Arc<FairMutex<Term<EventProxy>>>,
+    /// Pane with keyboard focus.
+    pane: Pane,
+    /// All other panes of this window.
+    panes: Vec<Pane>,
+    layout: PaneLayout,
+    last_pane_id: PaneId,
+    pty_config: PtyConfig,
     cursor_blink_timed_out: bool,
     modifiers: Modifiers,
-    search_state: SearchState,
-    notifier: Notifier,
     font_size: Size,
     mouse: Mouse,
     touch: TouchPurpose,
+    pending_releases: PendingReleases,
     occluded: bool,
     preserve_title: bool,
-    #[cfg(not(windows))]
-    master_fd: RawFd,
-    #[cfg(not(windows))]
-    shell_pid: u32,
     ipc_config: Vec<toml::Value>,
+    /// File removed once the window is closed.
+    _temporary_file: Option<TemporaryFile>,
+    #[cfg(unix)]
+    name: Option<String>,
+    scripting: Scripting,
+    pending_launches: Vec<PendingLaunch>,
+    replay: bool,
+    bench: Option<Bench>,
+    /// Last mouse position relative to the window.
+    cursor_position: PhysicalPosition<f64>,
     config: Rc<UiConfig>,
 }
 
@@ -85,7 +117,7 @@ impl WindowContext {
         // Windows has different order of GL platform initialization compared to any other platform;
         // it requires the window first.
         #[cfg(windows)]
-        let window = Window::new(event_loop, &config, &identity)?;
+        let window = Window::new(event_loop, &config, &identity, options.dropdown)?;
         #[cfg(windows)]
         let raw_window_handle = Some(window.raw_window_handle());
 
@@ -105,22 +137,30 @@ impl WindowContext {
             gl_config.x11_visual(),
             #[cfg(target_os = "macos")]
             &options.window_tabbing_id,
+            options.dropdown,
         )?;
 
         // Create context.
-        let gl_context =
-            renderer::platform::create_gl_context(&gl_display, &gl_config, raw_window_handle)?;
+        let gl_context = renderer::platform::create_gl_context(
+            &gl_display,
+            &gl_config,
+            raw_window_handle,
+            None,
+        )?;
 
-        Self::new(window, gl_context, config, options, proxy)
+        Self::new(window, gl_context, config, options, proxy, None, None)
     }
 
     /// Create additional context with the graphics platform other windows are using.
+    ///
+    /// The new window hosts `moved_pane` when it is given, instead of spawning a new terminal.
     pub fn additional(
         &self,
         event_loop: &EventLoopWindowTarget<Event>,
         proxy: EventLoopProxy<Event>,
         config: Rc<UiConfig>,
         options: WindowOptions,
+        moved_pane: Option<Pane>,
     ) -> Result<Self, Box<dyn Error>> {
         // Get any window and take its GL config and display to build a new context.
         let (gl_display, gl_config) = {
@@ -139,28 +179,49 @@ impl WindowContext {
             gl_config.x11_visual(),
             #[cfg(target_os = "macos")]
             &options.window_tabbing_id,
+            options.dropdown,
         )?;
 
-        // Create context.
-        let raw_window_handle = window.raw_window_handle();
-        let gl_context = renderer::platform::create_gl_context(
+        // Create context sharing GL objects with the existing window, to reuse its resources.
+        let raw_window_handle = Some(window.raw_window_handle());
+        let shared_context = Some(self.display.gl_context());
+        let (gl_context, shared) = match renderer::platform::create_gl_context(
             &gl_display,
             &gl_config,
-            Some(raw_window_handle),
-        )?;
+            raw_window_handle,
+            shared_context,
+        ) {
+            Ok(gl_context) => (gl_context, Some(&self.display)),
+            Err(err) => {
+                warn!("Unable to share GL context with other windows: {}", err);
+                let gl_context = renderer::platform::create_gl_context(
+                    &gl_display,
+                    &gl_config,
+                    raw_window_handle,
+                    None,
+                )?;
+                (gl_context, None)
+            },
+        };
 
-        Self::new(window, gl_context, config, options, proxy)
+        Self::new(window, gl_context, config, options, proxy, shared, moved_pane)
     }
 
     /// Create a new terminal window context.
+    ///
+    /// The window hosts `moved_pane` when it is given, instead of spawning a new terminal.
     fn new(
         window: Window,
         context: NotCurrentContext,
         config: Rc<UiConfig>,
         options: WindowOptions,
         proxy: EventLoopProxy<Event>,
+        shared: Option<&Display>,
+        moved_pane: Option<Pane>,
     ) -> Result<Self, Box<dyn Error>> {
+        // Pass the current `env` section, since it could have changed since startup.
         let mut pty_config = config.terminal_config.pty_config.clone();
+        pty_config.env = config.terminal_config.env.clone();
         options.terminal_options.override_pty_config(&mut pty_config);
 
         let preserve_title = options.window_identity.title.is_some();
@@ -168,7 +229,7 @@ impl WindowContext {
         // Create a display.
         //
         // The display manages a window and can draw the terminal.
-        let display = Display::new(window, context, &config)?;
+        let mut display = Display::new(window, context, &config, shared)?;
 
         info!(
             "PTY dimensions: {:?} x {:?}",
@@ -176,78 +237,195 @@ impl WindowContext {
             display.size_info.columns()
         );
 
-        let event_proxy = EventProxy::new(proxy, display.window.id());
-
-        // Create the terminal.
-        //
-        // This object contains all of the state about what's being displayed. It's
-        // wrapped in a clonable mutex since both the I/O loop and display need to
-        // access it.
-        let terminal = Term::new(&config.terminal_config, &display.size_info, event_proxy.clone());
-        let terminal = Arc::new(FairMutex::new(terminal));
-
-        // Create the PTY.
-        //
-        // The PTY forks a process to run the shell on the slave side of the
-        // pseudoterminal. A file descriptor for the master side is retained for
-        // reading/writing to the shell.
-        let pty = tty::new(&pty_config, display.size_info.into(), display.window.id().into())?;
-
-        #[cfg(not(windows))]
-        let master_fd = pty.file().as_raw_fd();
-        #[cfg(not(windows))]
-        let shell_pid = pty.child().id();
-
-        // Create the pseudoterminal I/O loop.
-        //
-        // PTY I/O is ran on another thread as to not occupy cycles used by the
-        // renderer and input processing. Note that access to the terminal state is
-        // synchronized since the I/O loop updates the state, and the display
-        // consumes it periodically.
-        let event_loop = PtyEventLoop::new(
-            Arc::clone(&terminal),
-            event_proxy.clone(),
-            pty,
-            pty_config.hold,
-            config.debug.ref_test,
-        );
-
-        // The event loop channel allows write requests from the event processor
-        // to be sent to the pty loop and ultimately written to the pty.
-        let loop_tx = event_loop.channel();
-
-        // Kick off the I/O thread.
-        let _io_thread = event_loop.spawn();
+        let pane_id = PaneId::default();
+        let pane = match moved_pane {
+            Some(mut pane) => {
+                // Route the terminal's events to this window and fit it to its new size.
+                pane.update_config(&config);
+                pane.id = pane_id;
+                pane.event_proxy.retarget(display.window.id(), pane_id);
+                pane.notifier.on_resize(display.size_info.into());
+                pane.terminal.lock().resize(display.size_info);
+                pane.size_info = display.size_info;
+                display.pending_update.dirty = true;
+                pane
+            },
+            None => Self::spawn_pane(&mut display, &config, &options, &pty_config, &proxy)?,
+        };
 
         // Start cursor blinking, in case `Focused` isn't sent on startup.
         if config.terminal_config.cursor.style().blinking {
-            event_proxy.send_event(TerminalEvent::CursorBlinkingChange.into());
+            pane.event_proxy.send_event(TerminalEvent::CursorBlinkingChange.into());
         }
 
         let font_size = config.font.size();
+        let scripting = Scripting::new(&config.scripting);
+
+        // Panes split off later start a fresh shell, which shouldn't outlive it.
+        pty_config.hold = false;
+        pty_config.hold_on_error = false;
 
         // Create context for the Alacritty window.
-        Ok(WindowContext {
+        let mut window_context = WindowContext {
             preserve_title,
             font_size,
-            terminal,
+            scripting,
+            pending_launches: Default::default(),
             display,
-            #[cfg(not(windows))]
-            master_fd,
-            #[cfg(not(windows))]
-            shell_pid,
+            pane,
+            pty_config,
             config,
-            notifier: Notifier(loop_tx),
+            panes: Default::default(),
+            layout: PaneLayout::new(pane_id),
+            last_pane_id: pane_id,
+            replay: options.replay.is_some(),
+            bench: options.bench.as_ref().map(Bench::new),
+            _temporary_file: options.temporary_file.map(TemporaryFile),
+            #[cfg(unix)]
+            name: options.window_identity.name,
             cursor_blink_timed_out: Default::default(),
             message_buffer: Default::default(),
-            search_state: Default::default(),
             event_queue: Default::default(),
             ipc_config: Default::default(),
             modifiers: Default::default(),
             mouse: Default::default(),
+            cursor_position: Default::default(),
             touch: Default::default(),
+            pending_releases: Default::default(),
             dirty: Default::default(),
             occluded: Default::default(),
+        };
+
+        // Apply the config overrides of a moved pane.
+        window_context.apply_pane_config();
+
+        // Open the panes of the startup layout.
+        window_context.spawn_layout_splits(pane_id, options.splits, &proxy);
+
+        // Slide dropdown windows in from the top of the screen.
+        window_context.toggle_dropdown();
+
+        Ok(window_context)
+    }
+
+    /// Spawn the terminal of a new window's first pane.
+    fn spawn_pane(
+        display: &mut Display,
+        config: &Rc<UiConfig>,
+        options: &WindowOptions,
+        pty_config: &PtyConfig,
+        proxy: &EventLoopProxy<Event>,
+    ) -> Result<Pane, Box<dyn Error>> {
+        let pane_id = PaneId::default();
+        let event_proxy = EventProxy::new(proxy.clone(), display.window.id(), pane_id);
+
+        // Create the terminal.
+        //
+        // This object contains all of the state about what's being displayed. It's
+        // wrapped in a clonable mutex since both the I/O loop and display need to
+        // access it.
+        let terminal = Term::new(&config.terminal_config, &display.size_info, event_proxy.clone());
+        let terminal = Arc::new(FairMutex::new(terminal));
+
+        #[cfg(not(windows))]
+        let (master_fd, shell_pid);
+
+        let loop_tx = match (&options.replay, &options.bench) {
+            // Replay a recording or run a benchmark instead of running a shell.
+            //
+            // The player takes the place of the PTY I/O loop, so input written to
+            // the terminal is discarded. Benchmarks feed the terminal while drawing, so
+            // their player has nothing to replay.
+            (replay, bench) if replay.is_some() || bench.is_some() => {
+                let cast = match replay {
+                    Some(path) => Cast::load(path)?,
+                    None => {
+                        let size = &display.size_info;
+                        Cast::empty(size.columns(), size.screen_lines())
+                    },
+                };
+
+                // Without a shell, Alacritty itself is the foreground process.
+                #[cfg(not(windows))]
+                {
+                    master_fd = -1;
+                    shell_pid = process::id();
+                }
+
+                let player = Player::new(cast, Arc::clone(&terminal), event_proxy.clone());
+                let loop_tx = player.channel();
+                let _replay_thread = player.spawn();
+                loop_tx
+            },
+            _ => {
+                // Create the PTY.
+                //
+                // The PTY forks a process to run the shell on the slave side of the
+                // pseudoterminal. A file descriptor for the master side is retained for
+                // reading/writing to the shell.
+                let pty =
+                    tty::new(pty_config, display.size_info.into(), display.window.id().into())?;
+
+                #[cfg(not(windows))]
+                {
+                    master_fd = pty.file().as_raw_fd();
+                    shell_pid = pty.child().id();
+                }
+
+                // Create the pseudoterminal I/O loop.
+                //
+                // PTY I/O is ran on another thread as to not occupy cycles used by the
+                // renderer and input processing. Note that access to the terminal state is
+                // synchronized since the I/O loop updates the state, and the display
+                // consumes it periodically.
+                let mut event_loop = PtyEventLoop::new(
+                    Arc::clone(&terminal),
+                    event_proxy.clone(),
+                    pty,
+                    pty_config.hold,
+                    pty_config.hold_on_error,
+                    config.debug.ref_test,
+                );
+
+                let pty_limits = &config.terminal_config.terminal;
+                event_loop.set_read_limits(pty_limits.pty_read_buffer, pty_limits.pty_chunk_size);
+
+                // The event loop channel allows write requests from the event processor
+                // to be sent to the pty loop and ultimately written to the pty.
+                let loop_tx = event_loop.channel();
+
+                // Track the PTY output for the performance statistics.
+                display.set_pty_counter(event_loop.read_counter());
+
+                // Start recording before any output is read from the PTY.
+                if let Some(path) = &options.terminal_options.record {
+                    let _ = loop_tx.send(Msg::ToggleRecording(path.clone()));
+                }
+
+                // Kick off the I/O thread.
+                let _io_thread = event_loop.spawn();
+
+                loop_tx
+            },
+        };
+
+        Ok(Pane {
+            id: pane_id,
+            terminal,
+            notifier: Notifier(loop_tx),
+            event_proxy,
+            search_state: Default::default(),
+            child_exited: Default::default(),
+            spawned: Instant::now(),
+            rect: Default::default(),
+            size_info: display.size_info,
+            config: config.clone(),
+            config_overrides: Default::default(),
+            font_size: Default::default(),
+            #[cfg(not(windows))]
+            master_fd,
+            #[cfg(not(windows))]
+            shell_pid,
         })
     }
 
@@ -269,7 +447,7 @@ impl WindowContext {
                             target: LOG_TARGET_IPC_CONFIG,
                             "Unable to override option '{}': {}", option, err
                         );
-                        self.ipc_config.swap_remove(i);
+                        self.ipc_config.remove(i);
                     },
                     Ok(_) => i += 1,
                 }
@@ -278,8 +456,10 @@ impl WindowContext {
             self.config = Rc::new(config);
         }
 
-        self.display.update_config(&self.config);
-        self.terminal.lock().update_config(&self.config.terminal_config);
+        for pane in iter::once(&mut self.pane).chain(&mut self.panes) {
+            pane.update_config(&self.config);
+        }
+        self.apply_pane_config();
 
         // Reload cursor if its thickness has changed.
         if (old_config.terminal_config.cursor.thickness()
@@ -296,7 +476,8 @@ impl WindowContext {
                 self.font_size = self.config.font.size();
             }
 
-            let font = self.config.font.clone().with_size(self.font_size);
+            let font_size = self.pane.font_size.unwrap_or(self.font_size);
+            let font = self.config.font.clone().with_size(font_size);
             self.display.pending_update.set_font(font);
         }
 
@@ -305,6 +486,7 @@ impl WindowContext {
         if window_config.padding(1.) != self.config.window.padding(1.)
             || window_config.dynamic_padding != self.config.window.dynamic_padding
             || window_config.resize_increments != self.config.window.resize_increments
+            || old_config.panes.border_width != self.config.panes.border_width
         {
             self.display.pending_update.dirty = true;
         }
@@ -323,7 +505,7 @@ impl WindowContext {
             self.display.window.set_title(self.config.window.identity.title.clone());
         }
 
-        let opaque = self.config.window_opacity() >= 1.;
+        let opaque = self.display.window_opacity(&self.config) >= 1.;
 
         // Disable shadows for transparent windows on macOS.
         #[cfg(target_os = "macos")]
@@ -332,11 +514,38 @@ impl WindowContext {
         #[cfg(target_os = "macos")]
         self.display.window.set_option_as_alt(self.config.window.option_as_alt());
 
+        #[cfg(windows)]
+        self.display.window.set_backdrop(self.config.window.backdrop);
+
         // Change opacity state.
         self.display.window.set_transparent(!opaque);
 
-        // Update hint keys.
-        self.display.hint_state.update_alphabet(self.config.hints.alphabet());
+        self.display.update_title_bar(&self.config.window);
+
+        // Only override runtime changes to the window level when the config changed.
+        if old_config.window.always_on_top != self.config.window.always_on_top {
+            self.display.window.set_always_on_top(self.config.window.always_on_top);
+        }
+        if old_config.window.sticky != self.config.window.sticky {
+            self.display.window.set_sticky(self.config.window.sticky);
+        }
+
+        // Reload plugins when they have changed.
+        if old_config.scripting != self.config.scripting {
+            self.display.plugin_overlay.clear();
+            self.scripting = Scripting::new(&self.config.scripting);
+        }
+
+        // Update cursor blinking.
+        let event = Event::new(TerminalEvent::CursorBlinkingChange.into(), None);
+        self.event_queue.push(event.into());
+
+        self.dirty = true;
+    }
+
+    /// Update the power source, throttling animations while on battery.
+    pub fn set_on_battery(&mut self, on_battery: bool) {
+        self.display.set_on_battery(on_battery);
 
         // Update cursor blinking.
         let event = Event::new(TerminalEvent::CursorBlinkingChange.into(), None);
@@ -345,9 +554,21 @@ impl WindowContext {
         self.dirty = true;
     }
 
+    /// Update whether the system asks for high contrast.
+    pub fn set_system_high_contrast(&mut self, high_contrast: bool) {
+        self.display.set_system_high_contrast(high_contrast, &self.pane.config);
+        self.display.window.set_transparent(self.display.window_opacity(&self.config) < 1.);
+        self.dirty = true;
+    }
+
     /// Update the IPC config overrides.
     #[cfg(unix)]
     pub fn update_ipc_config(&mut self, config: Rc<UiConfig>, ipc_config: IpcConfig) {
+        if ipc_config.pane {
+            self.update_pane_config(&ipc_config.options, ipc_config.reset);
+            return;
+        }
+
         // Clear previous IPC errors.
         self.message_buffer.remove_target(LOG_TARGET_IPC_CONFIG);
 
@@ -356,7 +577,7 @@ impl WindowContext {
         } else {
             for option in &ipc_config.options {
                 // Try and parse option as toml.
-                match toml::from_str(option) {
+                match serde_utils::parse_option(option) {
                     Ok(value) => self.ipc_config.push(value),
                     Err(err) => error!(
                         target: LOG_TARGET_IPC_CONFIG,
@@ -370,33 +591,157 @@ impl WindowContext {
         self.update_config(config);
     }
 
+    /// Override config options of the focused pane.
+    ///
+    /// With `reset`, all previous overrides of the pane are removed first.
+    pub fn update_pane_config(&mut self, options: &[String], reset: bool) {
+        // Clear previous IPC errors.
+        self.message_buffer.remove_target(LOG_TARGET_IPC_CONFIG);
+
+        if reset {
+            self.pane.config_overrides.clear();
+        }
+
+        for option in options {
+            if !pane::is_pane_option(option) {
+                error!(target: LOG_TARGET_IPC_CONFIG, "'{}': Option can't be set per pane", option);
+                continue;
+            }
+
+            match serde_utils::parse_option(option) {
+                Ok(value) => self.pane.config_overrides.push(value),
+                Err(err) => error!(
+                    target: LOG_TARGET_IPC_CONFIG,
+                    "'{}': Invalid pane config value: {:?}", option, err
+                ),
+            }
+        }
+
+        self.pane.update_config(&self.config);
+        self.apply_pane_config();
+    }
+
+    /// Apply the config of the focused pane to the display.
+    fn apply_pane_config(&mut self) {
+        self.display.update_config(&self.pane.config);
+
+        // Switch to the font size of the focused pane.
+        let font_size = self.pane.font_size.unwrap_or(self.font_size);
+        let display_font_size = match self.display.pending_update.font() {
+            Some(font) => font.size(),
+            None => self.display.font_size(),
+        };
+        if font_size != display_font_size {
+            let font = self.pane.config.font.clone().with_size(font_size);
+            self.display.pending_update.set_font(font);
+        }
+
+        // Update padding and cursor blinking.
+        self.display.pending_update.dirty = true;
+        let event = Event::new(TerminalEvent::CursorBlinkingChange.into(), None);
+        self.event_queue.push(event.into());
+
+        self.dirty = true;
+    }
+
+    /// Get the value of a config option, including IPC overrides.
+    #[cfg(unix)]
+    pub fn ipc_config_value(&self, option: &str) -> SocketReply {
+        let mut value: Option<toml::Value> = None;
+        for layer in self.config.source.iter().chain(&self.ipc_config) {
+            let layer_value = match serde_utils::get_option(layer, option) {
+                Some(layer_value) => layer_value.clone(),
+                None => continue,
+            };
+
+            // Tables are merged, while all other values are replaced by later overrides.
+            value = Some(match value.take() {
+                Some(base @ toml::Value::Table(_)) if layer_value.is_table() => {
+                    serde_utils::merge(base, layer_value)
+                },
+                _ => layer_value,
+            });
+        }
+
+        match value {
+            Some(value) => SocketReply::Text(format!("{}\n", value)),
+            None => SocketReply::Error(format!("option '{}' is not set", option)),
+        }
+    }
+
     /// Draw the window.
     pub fn draw(&mut self, scheduler: &mut Scheduler) {
         self.display.window.requested_redraw = false;
 
+        // Keep redrawing until dropdown windows finished sliding in or out.
+        if self.display.window.animate_dropdown() {
+            self.display.window.request_redraw();
+        }
+
         if self.occluded {
             return;
         }
 
+        // Defer drawing until the frame rate limit allows presenting the next frame.
+        //
+        // Animations are based on real time, so they still progress at the configured rate.
+        if !self.display.window.has_frame && self.display.frame_rate_limited() {
+            self.dirty = true;
+            return;
+        }
+
         self.dirty = false;
 
         // Force the display to process any pending display update.
         self.display.process_renderer_update();
 
-        // Request immediate re-draw if visual bell animation is not finished yet.
-        if !self.display.visual_bell.completed() {
+        // Request immediate re-draw if visual bell or an animation is not finished yet, or
+        // placeholders of glyphs rasterized in the background still have to be replaced.
+        if !self.display.visual_bell.completed()
+            || self.display.jump_animation.active()
+            || self.display.hover_animation.active()
+            || self.display.palette_animation.active()
+            || self.display.glyphs_pending()
+        {
             self.display.window.request_redraw();
         }
 
+        // Feed the next benchmark frame, exiting once all workloads are finished.
+        let mut terminal = self.pane.terminal.lock();
+        if let Some(bench) = &mut self.bench {
+            if !bench.advance(&mut *terminal) {
+                bench.report();
+                terminal.exit();
+                self.bench = None;
+            }
+        }
+
         // Redraw the window.
-        let terminal = self.terminal.lock();
+        let start = Instant::now();
         self.display.draw(
             terminal,
             scheduler,
             &self.message_buffer,
-            &self.config,
-            &self.search_state,
+            &self.pane.config,
+            &self.pane.search_state,
+            &self.panes,
+            self.font_size,
         );
+
+        // Keep drawing frames as fast as possible while benchmarking.
+        if let Some(bench) = &mut self.bench {
+            let cpu_time = start.elapsed();
+            self.display.wait_for_gpu();
+            bench.record_frame(cpu_time, start.elapsed() - cpu_time);
+
+            self.dirty = true;
+            self.display.window.request_redraw();
+        }
+    }
+
+    /// Check if any plugin is interested in new terminal output.
+    pub fn plugins_watch_output(&self) -> bool {
+        self.scripting.watches_output()
     }
 
     /// Process events for this terminal window.
@@ -411,41 +756,140 @@ impl WindowContext {
         match event {
             WinitEvent::AboutToWait | WinitEvent::RedrawRequested(_) => {
                 // Skip further event handling with no staged updates.
-                if self.event_queue.is_empty() {
+                if self.event_queue.is_empty()
+                    && !self.display.pending_update.dirty
+                    && !self.display.jump_animation.active()
+                {
                     return;
                 }
 
                 // Continue to process all pending events.
             },
+            // Events of other panes' terminals don't concern the focused terminal.
+            WinitEvent::UserEvent(event)
+                if event.pane_id().map_or(false, |pane_id| pane_id != self.pane.id) =>
+            {
+                self.handle_unfocused_pane_event(event);
+                return;
+            },
+            WinitEvent::UserEvent(event) if matches!(event.payload(), EventType::Pane(_)) => {
+                // Deliver earlier input to the pane which was focused when it was received.
+                self.process_event_queue(event_loop, event_proxy, clipboard, scheduler);
+
+                if let EventType::Pane(action) = event.into_payload() {
+                    self.handle_pane_action(event_proxy, action);
+                }
+                return;
+            },
+            WinitEvent::WindowEvent {
+                window_id,
+                event: WindowEvent::CursorMoved { device_id, position },
+            } if self.display.pane_rect.is_some() => {
+                self.cursor_position = position;
+
+                let event = self.pane_cursor_event(device_id);
+                self.event_queue.push(WinitEvent::WindowEvent { window_id, event });
+                return;
+            },
+            WinitEvent::WindowEvent {
+                event:
+                    WindowEvent::MouseInput {
+                        state: ElementState::Pressed,
+                        button: MouseButton::Left,
+                        ..
+                    },
+                ..
+            } if self.title_bar_click(event_proxy) => return,
+            WinitEvent::WindowEvent {
+                window_id,
+                event: WindowEvent::MouseInput { device_id, state: ElementState::Pressed, button },
+            } if self.display.pane_rect.is_some() => {
+                // Focus panes when they're clicked.
+                let (x, y) = (self.cursor_position.x, self.cursor_position.y);
+                let clicked = self.panes.iter().find(|pane| pane.rect.contains(x, y));
+                if let Some(pane_id) = clicked.map(|pane| pane.id) {
+                    self.process_event_queue(event_loop, event_proxy, clipboard, scheduler);
+                    self.focus_pane(pane_id);
+
+                    let event = self.pane_cursor_event(device_id);
+                    self.event_queue.push(WinitEvent::WindowEvent { window_id, event });
+                }
+
+                let event =
+                    WindowEvent::MouseInput { device_id, state: ElementState::Pressed, button };
+                self.event_queue.push(WinitEvent::WindowEvent { window_id, event });
+                return;
+            },
+            WinitEvent::WindowEvent {
+                window_id,
+                event: WindowEvent::ScaleFactorChanged { scale_factor, mut inner_size_writer },
+            } => {
+                // Winit only accepts a new window size while the event is dispatched.
+                let size = self.display.scaled_window_size(scale_factor);
+                let _ = inner_size_writer.request_inner_size(size);
+
+                // Apply the new size together with the new font, so no frame is drawn with only
+                // one of them adjusted to the new scale factor.
+                let event = WindowEvent::ScaleFactorChanged { scale_factor, inner_size_writer };
+                self.event_queue.push(WinitEvent::WindowEvent { window_id, event });
+                let event = WindowEvent::Resized(size);
+                self.event_queue.push(WinitEvent::WindowEvent { window_id, event });
+                return;
+            },
             event => {
                 self.event_queue.push(event);
                 return;
             },
         }
 
-        let mut terminal = self.terminal.lock();
+        self.process_event_queue(event_loop, event_proxy, clipboard, scheduler);
 
-        let old_is_searching = self.search_state.history_index.is_some();
+        // Request a redraw.
+        //
+        // Even though redraw requests are squashed in winit, we try not to
+        // request more if we haven't received a new frame request yet.
+        if self.dirty && !self.occluded && !matches!(event, WinitEvent::RedrawRequested(_)) {
+            self.display.window.request_redraw();
+        }
+    }
+
+    /// Apply all queued events to the focused terminal.
+    fn process_event_queue(
+        &mut self,
+        event_loop: &EventLoopWindowTarget<Event>,
+        event_proxy: &EventLoopProxy<Event>,
+        clipboard: &mut Clipboard,
+        scheduler: &mut Scheduler,
+    ) {
+        let mut terminal = self.pane.terminal.lock();
+
+        let old_is_searching = self.pane.search_state.history_index.is_some();
 
         let context = ActionContext {
             cursor_blink_timed_out: &mut self.cursor_blink_timed_out,
             message_buffer: &mut self.message_buffer,
-            search_state: &mut self.search_state,
+            search_state: &mut self.pane.search_state,
             modifiers: &mut self.modifiers,
-            font_size: &mut self.font_size,
-            notifier: &mut self.notifier,
+            font_size: self.pane.font_size.as_mut().unwrap_or(&mut self.font_size),
+            notifier: &mut self.pane.notifier,
             display: &mut self.display,
             mouse: &mut self.mouse,
             touch: &mut self.touch,
+            pending_releases: &mut self.pending_releases,
             dirty: &mut self.dirty,
             occluded: &mut self.occluded,
+            child_exited: &mut self.pane.child_exited,
+            spawned: self.pane.spawned,
             terminal: &mut terminal,
             #[cfg(not(windows))]
-            master_fd: self.master_fd,
+            master_fd: self.pane.master_fd,
             #[cfg(not(windows))]
-            shell_pid: self.shell_pid,
+            shell_pid: self.pane.shell_pid,
             preserve_title: self.preserve_title,
-            config: &self.config,
+            replay: self.replay,
+            scripting: &mut self.scripting,
+            pending_launches: &mut self.pending_launches,
+            config: &self.pane.config,
             event_proxy,
             event_loop,
             clipboard,
@@ -457,48 +901,463 @@ impl WindowContext {
             processor.handle_event(event);
         }
 
+        // Scroll the viewport towards the target of an animated jump.
+        processor.ctx.advance_jump_animation();
+
         // Process DisplayUpdate events.
         if self.display.pending_update.dirty {
+            Self::layout_panes(
+                &self.layout,
+                self.pane.id,
+                &mut self.panes,
+                &mut self.display,
+                &self.config,
+            );
             Self::submit_display_update(
                 &mut terminal,
                 &mut self.display,
-                &mut self.notifier,
+                &mut self.pane.notifier,
                 &self.message_buffer,
-                &self.search_state,
+                &self.pane.search_state,
                 old_is_searching,
-                &self.config,
+                &self.pane.config,
             );
+            Self::resize_panes(&mut self.panes, &self.display, self.font_size);
             self.dirty = true;
         }
 
         if self.dirty || self.mouse.hint_highlight_dirty {
             self.dirty |= self.display.update_highlighted_hints(
                 &terminal,
-                &self.config,
+                &self.pane.config,
                 &self.mouse,
                 self.modifiers.state(),
             );
             self.mouse.hint_highlight_dirty = false;
         }
+    }
 
-        // Request a redraw.
-        //
-        // Even though redraw requests are squashed in winit, we try not to
-        // request more if we haven't received a new frame request yet.
-        if self.dirty && !self.occluded && !matches!(event, WinitEvent::RedrawRequested(_)) {
-            self.display.window.request_redraw();
+    /// Handle events emitted by the terminal of a pane without keyboard focus.
+    fn handle_unfocused_pane_event(&mut self, event: Event) {
+        let window_focused = self.pane.terminal.lock().is_focused;
+        let pane = match self.panes.iter_mut().find(|pane| Some(pane.id) == event.pane_id()) {
+            Some(pane) => pane,
+            None => return,
+        };
+
+        let event = match event.into_payload() {
+            EventType::Terminal(event) => event,
+            _ => return,
+        };
+
+        match event {
+            TerminalEvent::PtyWrite(text) => pane.notifier.notify(text.into_bytes()),
+            TerminalEvent::ColorRequest(index, format) => {
+                let colors = List::from(&pane.config.colors);
+                let color = pane.terminal.lock().colors()[index].unwrap_or(colors[index]);
+                pane.notifier.notify(format(color).into_bytes());
+            },
+            TerminalEvent::TextAreaSizeRequest(format) => {
+                pane.notifier.notify(format(pane.size_info.into()).into_bytes());
+            },
+            TerminalEvent::ChildExit(exit_code) => {
+                pane.child_exited = true;
+
+                let duration = Some(pane.spawned.elapsed());
+                let window = &self.display.window;
+                notification::command_finished(
+                    &pane.config,
+                    window,
+                    window_focused,
+                    duration,
+                    exit_code,
+                );
+            },
+            TerminalEvent::CommandFinished(exit_code) => {
+                let duration = pane.terminal.lock().last_command_duration();
+                let window = &self.display.window;
+                notification::command_finished(
+                    &pane.config,
+                    window,
+                    window_focused,
+                    duration,
+                    exit_code,
+                );
+            },
+            TerminalEvent::Bell => {
+                // Set window urgency hint when window is not focused.
+                let urgency_hints = pane.terminal.lock().mode().contains(TermMode::URGENCY_HINTS);
+                let window = &self.display.window;
+                notification::bell(&pane.config, window, window_focused, urgency_hints);
+            },
+            TerminalEvent::Wakeup => {
+                let mut terminal = pane.terminal.lock();
+                let display_offset = terminal.grid().display_offset();
+                let scroll_to_bottom = pane.config.terminal_config.scrolling.scroll_to_bottom;
+                if scroll_to_bottom.output && display_offset != 0 {
+                    terminal.scroll_display(Scroll::Bottom);
+                }
+                drop(terminal);
+
+                let window = &self.display.window;
+                notification::scrolled_output(&pane.config, window, window_focused, display_offset);
+            },
+            // The window's title, clipboard and cursor only follow the focused terminal.
+            _ => (),
         }
     }
 
+    /// Split, focus, resize or close panes.
+    fn handle_pane_action(&mut self, event_proxy: &EventLoopProxy<Event>, action: PaneAction) {
+        match action {
+            PaneAction::Split(direction, working_directory) => {
+                let mut pty_config = self.pty_config.clone();
+                pty_config.working_directory = working_directory;
+
+                match self.split_pane(self.pane.id, direction, &pty_config, event_proxy) {
+                    Some(pane_id) => self.focus_pane(pane_id),
+                    None => return,
+                }
+            },
+            PaneAction::Focus(direction) => {
+                let (area, gap) = Self::pane_area(&self.display, &self.config);
+                if let Some(pane_id) = self.layout.neighbor(self.pane.id, direction, area, gap) {
+                    self.focus_pane(pane_id);
+                }
+            },
+            PaneAction::Resize(direction) => {
+                self.layout.resize(self.pane.id, direction, self.config.panes.resize_step);
+            },
+            PaneAction::Close => {
+                // Closing the last pane closes the window.
+                if !self.close_pane(self.pane.id) {
+                    let event = Event::new(TerminalEvent::Exit.into(), self.id());
+                    let _ = event_proxy.send_event(event);
+                }
+            },
+            PaneAction::Config(options) => self.update_pane_config(&options, false),
+            PaneAction::ResetConfig => self.update_pane_config(&[], true),
+        }
+
+        self.display.pending_update.dirty = true;
+    }
+
+    /// Open a new pane next to the pane `target`.
+    fn split_pane(
+        &mut self,
+        target: PaneId,
+        direction: PaneDirection,
+        pty_config: &PtyConfig,
+        event_proxy: &EventLoopProxy<Event>,
+    ) -> Option<PaneId> {
+        let pane_id = self.last_pane_id.next();
+
+        let size_info = self.display.size_info;
+        let proxy = event_proxy.clone();
+        let pane = match Pane::spawn(pane_id, &self.config, pty_config, size_info, proxy, self.id())
+        {
+            Ok(pane) => pane,
+            Err(err) => {
+                error!("Unable to split pane: {}", err);
+                return None;
+            },
+        };
+
+        self.last_pane_id = pane_id;
+        self.layout.split(target, pane_id, direction);
+        self.panes.push(pane);
+
+        Some(pane_id)
+    }
+
+    /// Open the panes of a layout, splitting them off the pane `target`.
+    fn spawn_layout_splits(
+        &mut self,
+        target: PaneId,
+        splits: Vec<LayoutSplit>,
+        event_proxy: &EventLoopProxy<Event>,
+    ) {
+        for split in splits {
+            // Layout panes start the configured shell, rather than the window's command.
+            let mut pty_config = self.config.terminal_config.pty_config.clone();
+            pty_config.env = self.config.terminal_config.env.clone();
+            split.pane.terminal_options().override_pty_config(&mut pty_config);
+
+            if let Some(pane_id) =
+                self.split_pane(target, split.direction, &pty_config, event_proxy)
+            {
+                self.spawn_layout_splits(pane_id, split.pane.splits, event_proxy);
+                self.display.pending_update.dirty = true;
+            }
+        }
+    }
+
+    /// Remove a pane from this window.
+    ///
+    /// Returns `false` if this is the last pane of the window.
+    pub fn close_pane(&mut self, pane_id: PaneId) -> bool {
+        // Ignore panes which were closed already.
+        if pane_id != self.pane.id && self.panes.iter().all(|pane| pane.id != pane_id) {
+            return true;
+        }
+
+        if !self.layout.remove(pane_id) {
+            return false;
+        }
+
+        if pane_id == self.pane.id {
+            let next_pane = self.panes[0].id;
+            self.focus_pane(next_pane);
+        }
+        self.panes.retain(|pane| pane.id != pane_id);
+
+        self.display.pending_update.dirty = true;
+
+        true
+    }
+
+    /// Remove the focused pane from this window, without closing its terminal.
+    ///
+    /// Returns `None` if this is the last pane of the window.
+    pub fn take_pane(&mut self) -> Option<Pane> {
+        let pane_id = self.pane.id;
+        if !self.layout.remove(pane_id) {
+            return None;
+        }
+
+        let next_pane = self.panes[0].id;
+        self.focus_pane(next_pane);
+
+        let index = self.panes.iter().position(|pane| pane.id == pane_id)?;
+        Some(self.panes.remove(index))
+    }
+
+    /// Close this window, keeping the terminal of its last pane alive.
+    pub fn into_pane(self) -> Pane {
+        self.pane.terminal.lock().is_focused = false;
+        self.pane
+    }
+
+    /// Add a pane moved from another window next to the focused pane, focusing it.
+    pub fn insert_pane(&mut self, mut pane: Pane, direction: PaneDirection) {
+        let pane_id = self.last_pane_id.next();
+        self.last_pane_id = pane_id;
+
+        pane.id = pane_id;
+        pane.event_proxy.retarget(self.id(), pane_id);
+        pane.size_info = self.display.size_info;
+        pane.update_config(&self.config);
+
+        self.layout.split(self.pane.id, pane_id, direction);
+        self.panes.push(pane);
+        self.focus_pane(pane_id);
+    }
+
+    /// Write input mirrored from another terminal of a broadcast.
+    ///
+    /// The focused pane only receives input from other windows, since input of this window is
+    /// already written to it.
+    pub fn write_broadcast_input(&mut self, bytes: &[u8], include_focused: bool) {
+        if include_focused {
+            self.pane.notifier.notify(bytes.to_vec());
+        }
+
+        if self.display.broadcast.panes {
+            for pane in &self.panes {
+                pane.notifier.notify(bytes.to_vec());
+            }
+        }
+    }
+
+    /// Move keyboard focus to another pane.
+    fn focus_pane(&mut self, pane_id: PaneId) {
+        let index = match self.panes.iter().position(|pane| pane.id == pane_id) {
+            Some(index) => index,
+            None => return,
+        };
+
+        mem::swap(&mut self.pane, &mut self.panes[index]);
+
+        // Store the dimensions of the previously focused terminal with its pane.
+        let old_pane = &mut self.panes[index];
+        old_pane.rect = self.display.pane_rect.unwrap_or_default();
+        old_pane.size_info = self.display.size_info;
+        self.display.pane_rect = Some(self.pane.rect);
+        self.display.size_info = self.pane.size_info;
+
+        // Transfer keyboard focus between the terminals.
+        let mut old_terminal = old_pane.terminal.lock();
+        let is_focused = mem::replace(&mut old_terminal.is_focused, false);
+        if old_terminal.mode().contains(TermMode::FOCUS_IN_OUT) {
+            old_pane.notifier.notify(&b"\x1b[O"[..]);
+        }
+        drop(old_terminal);
+
+        let mut terminal = self.pane.terminal.lock();
+        terminal.is_focused = is_focused;
+        if is_focused && terminal.mode().contains(TermMode::FOCUS_IN_OUT) {
+            self.pane.notifier.notify(&b"\x1b[I"[..]);
+        }
+        drop(terminal);
+
+        self.display.highlighted_hint = None;
+        self.display.vi_highlighted_hint = None;
+
+        self.apply_pane_config();
+    }
+
+    /// Handle a left click on the title bar.
+    ///
+    /// Returns `false` when the click was outside of the title bar.
+    fn title_bar_click(&mut self, event_proxy: &EventLoopProxy<Event>) -> bool {
+        let (x, y) = (self.cursor_position.x as f32, self.cursor_position.y as f32);
+        let hit = match self.display.title_bar_hit(x, y) {
+            Some(hit) => hit,
+            None => return false,
+        };
+
+        let window = &self.display.window;
+        match hit {
+            TitleBarHit::Drag => window.drag_window(),
+            TitleBarHit::Button(TitleBarButton::Minimize) => window.set_minimized(true),
+            TitleBarHit::Button(TitleBarButton::Maximize) => window.toggle_maximized(),
+            TitleBarHit::Button(TitleBarButton::Close) => {
+                let event = Event::new(TerminalEvent::Exit.into(), window.id());
+                let _ = event_proxy.send_event(event);
+            },
+        }
+
+        true
+    }
+
+    /// Mouse motion event for the focused pane at the last cursor position.
+    ///
+    /// Leaving the focused pane is only reported while no mouse button is held down, to allow
+    /// dragging selections across the border.
+    fn pane_cursor_event(&self, device_id: DeviceId) -> WindowEvent {
+        let rect = self.display.pane_rect.unwrap_or_default();
+        let mut position = self.cursor_position;
+
+        let button_pressed = [
+            self.mouse.left_button_state,
+            self.mouse.middle_button_state,
+            self.mouse.right_button_state,
+        ]
+        .contains(&ElementState::Pressed);
+        if !button_pressed && !rect.contains(position.x, position.y) {
+            return WindowEvent::CursorLeft { device_id };
+        }
+
+        position.x -= f64::from(rect.x);
+        position.y -= f64::from(rect.y);
+        WindowEvent::CursorMoved { device_id, position }
+    }
+
+    /// Area shared by all panes and the gap left between them.
+    fn pane_area(display: &Display, config: &UiConfig) -> (PaneRect, f32) {
+        let window_size = display
+            .pending_update
+            .dimensions()
+            .map(|dimensions| dimensions.cast())
+            .unwrap_or_else(|| display.window_size());
+        let title_bar_height = display.title_bar_height();
+        let height = window_size.height - title_bar_height;
+        let area = PaneRect::new(0., title_bar_height, window_size.width, height);
+        let gap = f32::from(config.panes.border_width) * display.window.scale_factor as f32;
+
+        (area, gap)
+    }
+
+    /// Distribute the window's area between its panes.
+    fn layout_panes(
+        layout: &PaneLayout,
+        focused: PaneId,
+        panes: &mut [Pane],
+        display: &mut Display,
+        config: &UiConfig,
+    ) {
+        // Keep the terminal of unsplit windows below the title bar.
+        if !layout.is_split() {
+            let has_title_bar = display.title_bar_height() > 0.;
+            display.pane_rect = has_title_bar.then(|| Self::pane_area(display, config).0);
+            return;
+        }
+
+        let (area, gap) = Self::pane_area(display, config);
+        for (pane_id, rect) in layout.rects(area, gap) {
+            match panes.iter_mut().find(|pane| pane.id == pane_id) {
+                Some(pane) => pane.rect = rect,
+                None if pane_id == focused => display.pane_rect = Some(rect),
+                None => (),
+            }
+        }
+    }
+
+    /// Resize the terminals of unfocused panes to fit their area.
+    ///
+    /// Panes without a font size of their own use `font_size`.
+    fn resize_panes(panes: &mut [Pane], display: &Display, font_size: Size) {
+        for pane in panes {
+            let config = &pane.config;
+            let padding = config.window.padding(display.window.scale_factor as f32);
+            let font_size = pane.font_size.unwrap_or(font_size);
+            let (cell_width, cell_height) = display.cell_size(config, font_size);
+            let size_info = SizeInfo::new(
+                pane.rect.width,
+                pane.rect.height,
+                cell_width,
+                cell_height,
+                padding.0,
+                padding.1,
+                config.window.dynamic_padding,
+            );
+
+            if size_info != pane.size_info {
+                pane.notifier.on_resize(size_info.into());
+                pane.terminal.lock().resize(size_info);
+                pane.size_info = size_info;
+            }
+        }
+    }
+
+    /// Show or hide the window, if it is a dropdown window.
+    pub fn toggle_dropdown(&mut self) {
+        self.display.window.toggle_dropdown(&self.config.window);
+    }
+
     /// ID of this terminal context.
     pub fn id(&self) -> WindowId {
         self.display.window.id()
     }
 
+    /// Check if this terminal's window has keyboard focus.
+    pub fn focused(&self) -> bool {
+        self.pane.terminal.lock().is_focused
+    }
+
+    /// Describe this terminal's window for IPC clients.
+    #[cfg(unix)]
+    pub fn ipc_window(&self) -> IpcWindow {
+        let window = &self.display.window;
+        let position = window.outer_position();
+        let size = window.inner_size();
+
+        IpcWindow {
+            window_id: self.id().into(),
+            name: self.name.clone(),
+            title: window.title().to_owned(),
+            focused: self.focused(),
+            x: position.map(|position| position.x),
+            y: position.map(|position| position.y),
+            width: size.width,
+            height: size.height,
+        }
+    }
+
     /// Write the ref test results to the disk.
     pub fn write_ref_test_results(&self) {
         // Dump grid state.
-        let mut grid = self.terminal.lock().grid().clone();
+        let mut grid = self.pane.terminal.lock().grid().clone();
         grid.initialize_all();
         grid.truncate();
 
@@ -563,9 +1422,11 @@ impl WindowContext {
     }
 }
 
-impl Drop for WindowContext {
+/// File which only exists for one window, removed once the window is closed.
+struct TemporaryFile(PathBuf);
+
+impl Drop for TemporaryFile {
     fn drop(&mut self) {
-        // Shutdown the terminal's PTY.
-        let _ = self.notifier.0.send(Msg::Shutdown);
+        let _ = fs::remove_file(&self.0);
     }
 }
@@ -55,6 +55,16 @@ impl<'de, T: Deserialize<'de>> SerdeReplace for Option<T> {
 
 impl<'de, T: Deserialize<'de>> SerdeReplace for HashMap<String, T> {
     fn replace(&mut self, value: Value) -> Result<(), Box<dyn Error>> {
-        replace_simple(self, value)
+        match value {
+            // Replace individual entries, keeping all other keys.
+            Value::Table(table) => {
+                for (key, value) in table {
+                    self.insert(key, T::deserialize(value)?);
+                }
+
+                Ok(())
+            },
+            value => replace_simple(self, value),
+        }
     }
 }
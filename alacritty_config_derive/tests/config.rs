@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use log::{Level, Log, Metadata, Record};
@@ -37,6 +38,7 @@ struct Test {
     enom_big: TestEnum,
     #[config(deprecated)]
     enom_error: TestEnum,
+    map: HashMap<String, usize>,
     #[config(removed = "it's gone")]
     gone: bool,
 }
@@ -52,6 +54,7 @@ impl Default for Test {
             enom_small: TestEnum::default(),
             enom_big: TestEnum::default(),
             enom_error: TestEnum::default(),
+            map: HashMap::new(),
             gone: false,
         }
     }
@@ -198,3 +201,15 @@ fn replace_flatten() {
 
     assert_eq!(test.flatten.flatty, 7);
 }
+
+#[test]
+fn replace_map_entries() {
+    let mut test = Test::default();
+    test.map.insert(String::from("one"), 1);
+
+    let value = toml::from_str("map.two=2").unwrap();
+    test.replace(value).unwrap();
+
+    assert_eq!(test.map.get("one"), Some(&1));
+    assert_eq!(test.map.get("two"), Some(&2));
+}
@@ -1,7 +1,455 @@
 //! ANSI Terminal Stream Parsing.
 
+use std::mem;
+use std::path::PathBuf;
+
+use log::debug;
+
 pub use vte::ansi::*;
 
+/// Maximum number of bytes buffered for a single extended escape sequence.
+const MAX_EXTENDED_SEQUENCE_LENGTH: usize = 4096;
+
+/// Maximum number of bytes buffered for a DCS string, allowing for long ReGIS drawings.
+const MAX_DCS_LENGTH: usize = 1024 * 1024;
+
+/// Handler for escape sequences which are not supported by the `vte` parser.
+pub trait ExtendedHandler {
+    /// OSC 22 to change the mouse pointer shape, `None` resets it to the default.
+    fn set_mouse_pointer_shape(&mut self, _shape: Option<&str>) {}
+
+    /// OSC 133 shell integration mark at the current cursor position.
+    fn prompt_mark(&mut self, _mark: PromptMark) {}
+
+    /// OSC 1 icon name of the window.
+    fn set_icon_name(&mut self, _name: &str) {}
+
+    /// OSC 7 working directory of the shell, `None` if it was reported as invalid.
+    fn set_working_directory(&mut self, _path: Option<PathBuf>) {}
+
+    /// OSC 9;4 progress of a long running operation, `None` removes it.
+    fn set_progress(&mut self, _progress: Option<Progress>) {}
+
+    /// XTGETTCAP query for the value of the terminfo capability `name`.
+    fn request_capability(&mut self, _name: &str) {}
+
+    /// DECRQSS query for the state of a control function, like `m` for SGR.
+    fn request_setting(&mut self, _setting: &str) {}
+
+    /// DECSET or DECRST of a private mode.
+    ///
+    /// This is called for every private mode, including the ones already handled by `vte`.
+    fn set_extended_mode(&mut self, _mode: u16, _enabled: bool) {}
+
+    /// DECRQM query for the state of a private mode.
+    fn report_extended_mode(&mut self, _mode: u16) {}
+
+    /// SM or RM of an ANSI mode.
+    ///
+    /// This is called for every ANSI mode, including the ones already handled by `vte`.
+    fn set_ansi_mode(&mut self, _mode: u16, _enabled: bool) {}
+
+    /// XTWINOPS report of the window title, or the icon title if `icon` is `true`.
+    fn report_title(&mut self, _icon: bool) {}
+
+    /// ENQ request for the answerback message.
+    fn answerback(&mut self) {}
+
+    /// DCS p string of ReGIS graphics commands.
+    fn regis(&mut self, _commands: &str) {}
+}
+
+/// Progress reported through ConEmu's OSC 9;4.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Progress {
+    /// State of the operation.
+    pub state: ProgressState,
+
+    /// Completed percentage, from 0 to 100.
+    pub percent: u8,
+}
+
+/// State of an operation reporting its progress.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ProgressState {
+    /// Operation is progressing normally (`1`).
+    Normal,
+    /// Operation has failed (`2`).
+    Error,
+    /// Operation is running, without known progress (`3`).
+    Indeterminate,
+    /// Operation is paused (`4`).
+    Paused,
+}
+
+/// Shell integration marks reported through OSC 133.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PromptMark {
+    /// Start of the prompt (`A`).
+    PromptStart,
+    /// Start of the command input, after the prompt (`B`).
+    CommandStart,
+    /// Start of the command's output (`C`).
+    OutputStart,
+    /// End of the command, with its exit code if reported (`D`).
+    CommandEnd(Option<i32>),
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+enum ExtendedState {
+    #[default]
+    Ground,
+    Escape,
+    OscString,
+    OscEscape,
+    DcsString,
+    DcsEscape,
+    CsiString,
+}
+
+/// Parser for escape sequences which are not handled by `vte`.
+///
+/// This is fed the same bytes as the [`Processor`] and only dispatches the sequences `vte`
+/// ignores, leaving all other parsing to `vte`.
+#[derive(Debug, Default)]
+pub struct ExtendedProcessor {
+    state: ExtendedState,
+    buffer: Vec<u8>,
+
+    /// Bytes held back by the [`Processor`] during a synchronized update.
+    sync_buffer: Vec<u8>,
+}
+
+impl ExtendedProcessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process a new byte from the PTY, after it was passed to `parser`.
+    ///
+    /// While `parser` buffers a synchronized update, the byte is held back as well and only
+    /// dispatched once the update ends.
+    pub fn advance<T, H>(&mut self, parser: &Processor<T>, handler: &mut H, byte: u8)
+    where
+        T: Timeout,
+        H: ExtendedHandler,
+    {
+        if parser.sync_bytes_count() > 0 {
+            self.sync_buffer.push(byte);
+        } else if !self.sync_buffer.is_empty() {
+            // This byte terminated the synchronized update.
+            self.sync_buffer.push(byte);
+            self.stop_sync(handler);
+        } else {
+            self.advance_byte(handler, byte);
+        }
+    }
+
+    /// End a synchronized update, after [`Processor::stop_sync`].
+    ///
+    /// Sequences of the update are dispatched after all sequences handled by `vte`.
+    pub fn stop_sync<H: ExtendedHandler>(&mut self, handler: &mut H) {
+        let mut sync_buffer = mem::take(&mut self.sync_buffer);
+        for byte in sync_buffer.drain(..) {
+            self.advance_byte(handler, byte);
+        }
+
+        // Keep the allocation for the next synchronized update.
+        self.sync_buffer = sync_buffer;
+    }
+
+    fn advance_byte<H: ExtendedHandler>(&mut self, handler: &mut H, byte: u8) {
+        self.state = match (self.state, byte) {
+            // CAN and SUB abort any sequence in progress.
+            (_, 0x18 | 0x1a) => ExtendedState::Ground,
+            // ENQ is executed like other C0 controls, except inside of strings.
+            (ExtendedState::Ground | ExtendedState::Escape | ExtendedState::CsiString, 0x05) => {
+                handler.answerback();
+                self.state
+            },
+            (ExtendedState::Ground, 0x1b) => ExtendedState::Escape,
+            (ExtendedState::Ground, _) => ExtendedState::Ground,
+            (ExtendedState::Escape, b']') => {
+                self.buffer.clear();
+                ExtendedState::OscString
+            },
+            (ExtendedState::Escape, b'P') => {
+                self.buffer.clear();
+                ExtendedState::DcsString
+            },
+            (ExtendedState::Escape, b'[') => {
+                self.buffer.clear();
+                ExtendedState::CsiString
+            },
+            (ExtendedState::Escape, 0x1b) => ExtendedState::Escape,
+            (ExtendedState::Escape, _) => ExtendedState::Ground,
+            (ExtendedState::OscString, 0x07) => {
+                self.osc_dispatch(handler);
+                ExtendedState::Ground
+            },
+            (ExtendedState::OscString, 0x1b) => ExtendedState::OscEscape,
+            (ExtendedState::OscString, _) => {
+                if self.buffer.len() < MAX_EXTENDED_SEQUENCE_LENGTH {
+                    self.buffer.push(byte);
+                }
+                ExtendedState::OscString
+            },
+            (ExtendedState::OscEscape, b'\\') => {
+                self.osc_dispatch(handler);
+                ExtendedState::Ground
+            },
+            (ExtendedState::CsiString, 0x1b) => ExtendedState::Escape,
+            // Parameters and intermediates.
+            (ExtendedState::CsiString, 0x20..=0x3f) => {
+                if self.buffer.len() < MAX_EXTENDED_SEQUENCE_LENGTH {
+                    self.buffer.push(byte);
+                }
+                ExtendedState::CsiString
+            },
+            (ExtendedState::CsiString, 0x40..=0x7e) => {
+                self.csi_dispatch(handler, byte);
+                ExtendedState::Ground
+            },
+            // C0 controls are executed without interrupting the sequence.
+            (ExtendedState::CsiString, 0x00..=0x1f | 0x7f) => ExtendedState::CsiString,
+            (ExtendedState::CsiString, _) => ExtendedState::Ground,
+            (ExtendedState::DcsString, 0x1b) => ExtendedState::DcsEscape,
+            (ExtendedState::DcsString, _) => {
+                if self.buffer.len() < MAX_DCS_LENGTH {
+                    self.buffer.push(byte);
+                }
+                ExtendedState::DcsString
+            },
+            (ExtendedState::DcsEscape, b'\\') => {
+                self.dcs_dispatch(handler);
+                ExtendedState::Ground
+            },
+            // Any other escape terminates the sequence and starts a new one.
+            (ExtendedState::OscEscape | ExtendedState::DcsEscape, b']') => {
+                self.buffer.clear();
+                ExtendedState::OscString
+            },
+            (ExtendedState::OscEscape | ExtendedState::DcsEscape, b'P') => {
+                self.buffer.clear();
+                ExtendedState::DcsString
+            },
+            (ExtendedState::OscEscape | ExtendedState::DcsEscape, _) => ExtendedState::Ground,
+        };
+    }
+
+    fn csi_dispatch<H: ExtendedHandler>(&mut self, handler: &mut H, action: u8) {
+        if self.buffer.len() >= MAX_EXTENDED_SEQUENCE_LENGTH {
+            return;
+        }
+
+        // Window title reports.
+        if action == b't' {
+            match &self.buffer[..] {
+                b"20" => handler.report_title(true),
+                b"21" => handler.report_title(false),
+                _ => (),
+            }
+            return;
+        }
+
+        let parse_mode = |mode: &[u8]| std::str::from_utf8(mode).ok()?.parse::<u16>().ok();
+
+        // Only private modes are handled otherwise, apart from setting ANSI modes.
+        let params = match self.buffer.strip_prefix(b"?") {
+            Some(params) => params,
+            None => {
+                if action == b'h' || action == b'l' {
+                    for mode in self.buffer.split(|&byte| byte == b';').filter_map(parse_mode) {
+                        handler.set_ansi_mode(mode, action == b'h');
+                    }
+                }
+                return;
+            },
+        };
+
+        match action {
+            b'h' | b'l' => {
+                for mode in params.split(|&byte| byte == b';').filter_map(parse_mode) {
+                    handler.set_extended_mode(mode, action == b'h');
+                }
+            },
+            b'p' => {
+                if let Some(mode) = params.strip_suffix(b"$").and_then(parse_mode) {
+                    handler.report_extended_mode(mode);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn dcs_dispatch<H: ExtendedHandler>(&mut self, handler: &mut H) {
+        // ReGIS graphics, with an optional numeric parameter before the `p`.
+        let params_end = self.buffer.iter().position(|byte| !byte.is_ascii_digit());
+        if let Some(commands) = params_end.and_then(|end| self.buffer[end..].strip_prefix(b"p")) {
+            if self.buffer.len() >= MAX_DCS_LENGTH {
+                debug!("Ignoring ReGIS exceeding {} bytes", MAX_DCS_LENGTH);
+                return;
+            }
+
+            match std::str::from_utf8(commands) {
+                Ok(commands) => handler.regis(commands),
+                Err(_) => debug!("Ignoring invalid ReGIS commands"),
+            }
+            return;
+        }
+
+        if self.buffer.len() >= MAX_EXTENDED_SEQUENCE_LENGTH {
+            debug!("Ignoring extended DCS exceeding {} bytes", MAX_EXTENDED_SEQUENCE_LENGTH);
+            return;
+        }
+
+        // XTGETTCAP terminfo capability query, with hex encoded names.
+        if let Some(names) = self.buffer.strip_prefix(b"+q") {
+            for name in names.split(|&byte| byte == b';') {
+                match decode_hex(name) {
+                    Some(name) => handler.request_capability(&name),
+                    None => debug!("Ignoring invalid XTGETTCAP name {:?}", name),
+                }
+            }
+        }
+
+        // DECRQSS control function state query.
+        if let Some(setting) = self.buffer.strip_prefix(b"$q") {
+            match std::str::from_utf8(setting) {
+                Ok(setting) => handler.request_setting(setting),
+                Err(_) => debug!("Ignoring invalid DECRQSS setting {:?}", setting),
+            }
+        }
+    }
+
+    fn osc_dispatch<H: ExtendedHandler>(&mut self, handler: &mut H) {
+        if self.buffer.len() >= MAX_EXTENDED_SEQUENCE_LENGTH {
+            debug!("Ignoring extended OSC exceeding {} bytes", MAX_EXTENDED_SEQUENCE_LENGTH);
+            return;
+        }
+
+        let mut params = self.buffer.splitn(2, |&byte| byte == b';');
+        let kind = params.next().unwrap_or_default();
+        let payload = params.next();
+
+        // Set mouse pointer shape.
+        if kind == b"22" {
+            let shape = payload.and_then(|payload| std::str::from_utf8(payload).ok());
+            handler.set_mouse_pointer_shape(shape.filter(|shape| !shape.is_empty()));
+        }
+
+        // Icon name, OSC 0 sets the title instead.
+        if kind == b"1" {
+            match std::str::from_utf8(payload.unwrap_or_default()) {
+                Ok(name) => handler.set_icon_name(name),
+                Err(_) => debug!("Ignoring invalid icon name {:?}", payload),
+            }
+        }
+
+        // Shell working directory.
+        if kind == b"7" {
+            let url = payload.and_then(|payload| std::str::from_utf8(payload).ok());
+            handler.set_working_directory(url.and_then(parse_file_url));
+        }
+
+        // ConEmu progress report, other OSC 9 sequences are desktop notifications.
+        if kind == b"9" {
+            let mut params = payload.unwrap_or_default().split(|&byte| byte == b';');
+            if params.next() == Some(b"4") {
+                let state = params.next().unwrap_or_default();
+                let percent = params
+                    .next()
+                    .and_then(|percent| std::str::from_utf8(percent).ok())
+                    .and_then(|percent| percent.parse::<u8>().ok())
+                    .unwrap_or(0)
+                    .min(100);
+
+                let state = match state {
+                    b"0" | b"" => None,
+                    b"1" => Some(ProgressState::Normal),
+                    b"2" => Some(ProgressState::Error),
+                    b"3" => Some(ProgressState::Indeterminate),
+                    b"4" => Some(ProgressState::Paused),
+                    state => {
+                        let state = String::from_utf8_lossy(state);
+                        debug!("Ignoring unknown progress state {:?}", state);
+                        return;
+                    },
+                };
+                handler.set_progress(state.map(|state| Progress { state, percent }));
+            }
+        }
+
+        // Shell integration prompt marks.
+        if kind == b"133" {
+            let mut params = payload.unwrap_or_default().split(|&byte| byte == b';');
+            let mark = match params.next().unwrap_or_default() {
+                b"A" => PromptMark::PromptStart,
+                b"B" => PromptMark::CommandStart,
+                b"C" => PromptMark::OutputStart,
+                b"D" => {
+                    let exit_code = params
+                        .next()
+                        .and_then(|code| std::str::from_utf8(code).ok())
+                        .and_then(|code| code.parse().ok());
+                    PromptMark::CommandEnd(exit_code)
+                },
+                mark => {
+                    debug!("Ignoring unknown prompt mark {:?}", String::from_utf8_lossy(mark));
+                    return;
+                },
+            };
+            handler.prompt_mark(mark);
+        }
+    }
+}
+
+/// Decode a string of hexadecimal byte pairs.
+fn decode_hex(hex: &[u8]) -> Option<String> {
+    if hex.is_empty() || hex.len() % 2 != 0 {
+        return None;
+    }
+
+    let bytes = hex
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Extract the path from a `file://host/path` URL.
+///
+/// The host is ignored, since only the shell knows whether it is the local machine.
+fn parse_file_url(url: &str) -> Option<PathBuf> {
+    let url = url.strip_prefix("file://")?;
+    let path = &url[url.find('/')?..];
+
+    // Decode percent-encoded bytes.
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut chars = path.bytes();
+    while let Some(byte) = chars.next() {
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+
+        let high = (chars.next()? as char).to_digit(16)?;
+        let low = (chars.next()? as char).to_digit(16)?;
+        bytes.push((high * 16 + low) as u8);
+    }
+    let path = String::from_utf8(bytes).ok()?;
+
+    // Strip the leading slash in front of Windows drive letters, like `/C:/Users`.
+    #[cfg(windows)]
+    let path = match path.strip_prefix('/') {
+        Some(stripped) if stripped.get(1..2) == Some(":") => stripped.to_owned(),
+        _ => path,
+    };
+
+    Some(PathBuf::from(path))
+}
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
 pub struct CursorShapeShim(CursorShape);
 
@@ -58,3 +506,233 @@ impl alacritty_config::SerdeReplace for CursorShapeShim {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct MockHandler {
+        pointer_shape: Option<Option<String>>,
+        prompt_marks: Vec<PromptMark>,
+        working_directory: Option<Option<PathBuf>>,
+        progress: Vec<Option<Progress>>,
+        capabilities: Vec<String>,
+        settings: Vec<String>,
+        modes: Vec<(u16, bool)>,
+        ansi_modes: Vec<(u16, bool)>,
+        mode_reports: Vec<u16>,
+        title_reports: Vec<bool>,
+        icon_names: Vec<String>,
+        answerbacks: usize,
+        regis: Vec<String>,
+    }
+
+    impl ExtendedHandler for MockHandler {
+        fn set_mouse_pointer_shape(&mut self, shape: Option<&str>) {
+            self.pointer_shape = Some(shape.map(String::from));
+        }
+
+        fn prompt_mark(&mut self, mark: PromptMark) {
+            self.prompt_marks.push(mark);
+        }
+
+        fn set_working_directory(&mut self, path: Option<PathBuf>) {
+            self.working_directory = Some(path);
+        }
+
+        fn set_icon_name(&mut self, name: &str) {
+            self.icon_names.push(name.to_owned());
+        }
+
+        fn set_progress(&mut self, progress: Option<Progress>) {
+            self.progress.push(progress);
+        }
+
+        fn request_capability(&mut self, name: &str) {
+            self.capabilities.push(name.to_owned());
+        }
+
+        fn request_setting(&mut self, setting: &str) {
+            self.settings.push(setting.to_owned());
+        }
+
+        fn set_extended_mode(&mut self, mode: u16, enabled: bool) {
+            self.modes.push((mode, enabled));
+        }
+
+        fn report_extended_mode(&mut self, mode: u16) {
+            self.mode_reports.push(mode);
+        }
+
+        fn set_ansi_mode(&mut self, mode: u16, enabled: bool) {
+            self.ansi_modes.push((mode, enabled));
+        }
+
+        fn report_title(&mut self, icon: bool) {
+            self.title_reports.push(icon);
+        }
+
+        fn answerback(&mut self) {
+            self.answerbacks += 1;
+        }
+
+        fn regis(&mut self, commands: &str) {
+            self.regis.push(commands.to_owned());
+        }
+    }
+
+    impl Handler for MockHandler {}
+
+    fn parse(bytes: &[u8]) -> MockHandler {
+        let mut handler = MockHandler::default();
+        let mut parser = Processor::<StdSyncHandler>::new();
+        let mut extended_parser = ExtendedProcessor::new();
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+            extended_parser.advance(&parser, &mut handler, *byte);
+        }
+        handler
+    }
+
+    #[test]
+    fn parse_pointer_shape() {
+        let handler = parse(b"\x1b]22;pointer\x07");
+        assert_eq!(handler.pointer_shape, Some(Some(String::from("pointer"))));
+
+        let handler = parse(b"text\x1b]22;crosshair\x1b\\text");
+        assert_eq!(handler.pointer_shape, Some(Some(String::from("crosshair"))));
+    }
+
+    #[test]
+    fn parse_synchronized_update() {
+        let mut handler = MockHandler::default();
+        let mut parser = Processor::<StdSyncHandler>::new();
+        let mut extended_parser = ExtendedProcessor::new();
+        let mut advance = |handler: &mut MockHandler, bytes: &[u8]| {
+            for byte in bytes {
+                parser.advance(handler, *byte);
+                extended_parser.advance(&parser, handler, *byte);
+            }
+        };
+
+        // Sequences are held back until the synchronized update ends.
+        advance(&mut handler, b"\x1bP=1s\x1b\\\x1b]22;pointer\x07");
+        assert_eq!(handler.pointer_shape, None);
+
+        advance(&mut handler, b"\x1bP=2s\x1b\\");
+        assert_eq!(handler.pointer_shape, Some(Some(String::from("pointer"))));
+    }
+
+    #[test]
+    fn parse_pointer_shape_reset() {
+        let handler = parse(b"\x1b]22;\x07");
+        assert_eq!(handler.pointer_shape, Some(None));
+    }
+
+    #[test]
+    fn parse_icon_name() {
+        let handler = parse(b"\x1b]1;build\x07\x1b]0;title\x07\x1b]1;\x1b\\");
+        assert_eq!(handler.icon_names, vec![String::from("build"), String::new()]);
+    }
+
+    #[test]
+    fn parse_aborted_sequence() {
+        let handler = parse(b"\x1b]22;pointer\x18\x07");
+        assert_eq!(handler.pointer_shape, None);
+    }
+
+    #[test]
+    fn parse_prompt_marks() {
+        let handler =
+            parse(b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x1b\\\x1b]133;D;1\x07");
+        assert_eq!(handler.prompt_marks, vec![
+            PromptMark::PromptStart,
+            PromptMark::CommandStart,
+            PromptMark::OutputStart,
+            PromptMark::CommandEnd(Some(1)),
+        ]);
+
+        let handler = parse(b"\x1b]133;A;aid=42\x07\x1b]133;D\x07\x1b]133;X\x07");
+        let expected = vec![PromptMark::PromptStart, PromptMark::CommandEnd(None)];
+        assert_eq!(handler.prompt_marks, expected);
+    }
+
+    #[test]
+    fn parse_progress() {
+        let handler = parse(b"\x1b]9;4;1;42\x07\x1b]9;4;2;250\x07\x1b]9;4;3\x07\x1b]9;4;0\x07");
+        assert_eq!(handler.progress, vec![
+            Some(Progress { state: ProgressState::Normal, percent: 42 }),
+            Some(Progress { state: ProgressState::Error, percent: 100 }),
+            Some(Progress { state: ProgressState::Indeterminate, percent: 0 }),
+            None,
+        ]);
+
+        let handler = parse(b"\x1b]9;notification\x07\x1b]9;4;9;1\x07");
+        assert!(handler.progress.is_empty());
+    }
+
+    #[test]
+    fn parse_capability_queries() {
+        let handler = parse(b"\x1bP+q544E;436f\x1b\\\x1bP+q5\x1b\\\x1bP+q544E\x07");
+        assert_eq!(handler.capabilities, vec![String::from("TN"), String::from("Co")]);
+    }
+
+    #[test]
+    fn parse_setting_queries() {
+        let handler = parse(b"\x1bP$qm\x1b\\\x1bP$q q\x1b\\\x1bPq#0\x1b\\");
+        assert_eq!(handler.settings, vec![String::from("m"), String::from(" q")]);
+    }
+
+    #[test]
+    fn parse_regis() {
+        let handler = parse(b"\x1bPpP[10,20]V[30,40]\x1b\\\x1bP1pS(E)\x1b\\\x1bP+p\x1b\\");
+        assert_eq!(handler.regis, vec![String::from("P[10,20]V[30,40]"), String::from("S(E)")]);
+    }
+
+    #[test]
+    fn parse_private_modes() {
+        let handler = parse(b"\x1b[?2048h\x1b[?1049;2048l\x1b[2048h\x1b[?2048\n$p");
+        assert_eq!(handler.modes, vec![(2048, true), (1049, false), (2048, false)]);
+        assert_eq!(handler.mode_reports, vec![2048]);
+
+        let handler = parse(b"\x1b[?20\x1b[?48h");
+        assert_eq!(handler.modes, vec![(48, true)]);
+    }
+
+    #[test]
+    fn parse_ansi_modes() {
+        let handler = parse(b"\x1b[8h\x1b[4;8l\x1b[?8h\x1b[8$p");
+        assert_eq!(handler.ansi_modes, vec![(8, true), (4, false), (8, false)]);
+        assert_eq!(handler.modes, vec![(8, true)]);
+    }
+
+    #[test]
+    fn parse_title_reports() {
+        let handler = parse(b"\x1b[21t\x1b[20t\x1b[22;0t\x1b[?21t");
+        assert_eq!(handler.title_reports, vec![false, true]);
+    }
+
+    #[test]
+    fn parse_answerback() {
+        let handler = parse(b"\x05\x1b[\x05?2048h\x1b]22;\x05\x07");
+        assert_eq!(handler.answerbacks, 2);
+        assert_eq!(handler.modes, vec![(2048, true)]);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn parse_working_directory() {
+        let handler = parse(b"\x1b]7;file://host/home/user/a%20b\x07");
+        assert_eq!(handler.working_directory, Some(Some(PathBuf::from("/home/user/a b"))));
+
+        let handler = parse(b"\x1b]7;file:///tmp\x1b\\");
+        assert_eq!(handler.working_directory, Some(Some(PathBuf::from("/tmp"))));
+
+        let handler = parse(b"\x1b]7;https://host/tmp\x07");
+        assert_eq!(handler.working_directory, Some(None));
+
+        let handler = parse(b"\x1b]7;file:///tmp%2\x07");
+        assert_eq!(handler.working_directory, Some(None));
+    }
+}
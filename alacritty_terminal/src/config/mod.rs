@@ -9,6 +9,7 @@ use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
 mod scrolling;
 
 use crate::ansi::{CursorShapeShim, CursorStyle};
+use crate::event_loop;
 
 pub use crate::config::scrolling::{Scrolling, MAX_SCROLLBACK_LINES};
 
@@ -38,12 +39,76 @@ pub struct Config {
     pub pty_config: PtyConfig,
 }
 
-#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq, Default)]
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Terminal {
     // OSC 52 handling (clipboard handling).
     pub osc52: Osc52,
+
+    /// Maximum size of text transferred through OSC 52, in bytes.
+    pub osc52_max_size: usize,
+
+    /// Policy for OSC 52 clipboard reads.
+    pub osc52_read: Osc52Read,
+
+    /// Clipboards OSC 52 copies are stored in, instead of the requested one.
+    pub osc52_copy_target: Option<CopyTarget>,
+
+    /// Mouse pointer shapes which can be requested using OSC 22.
+    pub pointer_shapes: Vec<String>,
+
+    /// Allow applications to read the window title.
+    pub report_title: bool,
+
+    /// Message sent in response to ENQ.
+    pub answerback: String,
+
+    /// Max bytes read from the PTY at once.
+    pub pty_read_buffer: usize,
+
+    /// Max bytes parsed without releasing the terminal to the renderer.
+    pub pty_chunk_size: usize,
 }
 
+impl Default for Terminal {
+    fn default() -> Self {
+        Self {
+            osc52: Default::default(),
+            osc52_max_size: DEFAULT_OSC52_MAX_SIZE,
+            osc52_read: Default::default(),
+            osc52_copy_target: Default::default(),
+            pointer_shapes: DEFAULT_POINTER_SHAPES.iter().map(|shape| shape.to_string()).collect(),
+            report_title: Default::default(),
+            answerback: Default::default(),
+            pty_read_buffer: event_loop::READ_BUFFER_SIZE,
+            pty_chunk_size: event_loop::MAX_LOCKED_READ,
+        }
+    }
+}
+
+/// Default maximum size of text transferred through OSC 52, in bytes.
+const DEFAULT_OSC52_MAX_SIZE: usize = 1024 * 1024;
+
+/// Pointer shapes applications are allowed to request by default.
+const DEFAULT_POINTER_SHAPES: &[&str] = &[
+    "default",
+    "text",
+    "pointer",
+    "crosshair",
+    "move",
+    "wait",
+    "progress",
+    "help",
+    "not-allowed",
+    "grab",
+    "grabbing",
+    "col-resize",
+    "row-resize",
+    "ew-resize",
+    "ns-resize",
+    "nesw-resize",
+    "nwse-resize",
+];
+
 #[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq, Default)]
 pub enum Osc52 {
     /// The handling of the escape sequence is disabled.
@@ -60,6 +125,18 @@ pub enum Osc52 {
     CopyPaste,
 }
 
+/// Policy for clipboard reads accepted by [`Osc52`].
+#[derive(ConfigDeserialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Osc52Read {
+    /// Reject all reads.
+    Deny,
+    /// Ask the user before every read.
+    Ask,
+    /// Read without asking.
+    #[default]
+    Allow,
+}
+
 #[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq, Default)]
 pub struct PtyConfig {
     /// Path to a shell program to run on startup.
@@ -68,9 +145,17 @@ pub struct PtyConfig {
     /// Shell startup directory.
     pub working_directory: Option<PathBuf>,
 
+    /// Environment variables of the shell, in addition to Alacritty's.
+    #[config(skip)]
+    pub env: HashMap<String, String>,
+
     /// Remain open after child process exits.
     #[config(skip)]
     pub hold: bool,
+
+    /// Remain open after child process exits with a non-zero exit code.
+    #[config(skip)]
+    pub hold_on_error: bool,
 }
 
 impl PtyConfig {
@@ -82,7 +167,19 @@ impl PtyConfig {
 #[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Selection {
     pub semantic_escape_chars: String,
-    pub save_to_clipboard: bool,
+    pub save_to_clipboard: SaveToClipboard,
+
+    /// Remove trailing whitespace from every line of copied text.
+    pub trim_trailing_whitespace: bool,
+
+    /// Clear the selection after it has been copied.
+    pub clear_after_copy: bool,
+
+    /// Draw selections as a single region with rounded corners.
+    pub rounded: bool,
+
+    /// Outline rounded selections with a border.
+    pub border: bool,
 }
 
 impl Default for Selection {
@@ -90,17 +187,71 @@ impl Default for Selection {
         Self {
             semantic_escape_chars: String::from(",│`|:\"' ()[]{}<>\t"),
             save_to_clipboard: Default::default(),
+            trim_trailing_whitespace: Default::default(),
+            clear_after_copy: Default::default(),
+            rounded: Default::default(),
+            border: Default::default(),
+        }
+    }
+}
+
+/// Clipboards which selected text is automatically copied to.
+#[derive(SerdeReplace, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum SaveToClipboard {
+    /// Legacy boolean option, with `true` copying to both clipboards.
+    Enabled(bool),
+    Target(CopyTarget),
+}
+
+impl Default for SaveToClipboard {
+    fn default() -> Self {
+        Self::Target(CopyTarget::default())
+    }
+}
+
+impl SaveToClipboard {
+    pub fn target(self) -> CopyTarget {
+        match self {
+            Self::Enabled(true) => CopyTarget::Both,
+            Self::Enabled(false) => CopyTarget::Selection,
+            Self::Target(target) => target,
         }
     }
 }
 
+#[derive(ConfigDeserialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CopyTarget {
+    /// Copy only to the selection buffer.
+    #[default]
+    Selection,
+    /// Copy only to the system clipboard.
+    Clipboard,
+    /// Copy to both the selection buffer and the system clipboard.
+    Both,
+}
+
+impl CopyTarget {
+    /// Check if text should be stored in the selection buffer.
+    pub fn selection(self) -> bool {
+        matches!(self, Self::Selection | Self::Both)
+    }
+
+    /// Check if text should be stored in the system clipboard.
+    pub fn clipboard(self) -> bool {
+        matches!(self, Self::Clipboard | Self::Both)
+    }
+}
+
 #[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq)]
 pub struct Cursor {
     pub style: ConfigCursorStyle,
     pub vi_mode_style: Option<ConfigCursorStyle>,
     pub unfocused_hollow: bool,
+    pub animation: bool,
 
     thickness: Percentage,
+    block_opacity: Percentage,
     blink_interval: u64,
     blink_timeout: u8,
 }
@@ -109,7 +260,9 @@ impl Default for Cursor {
     fn default() -> Self {
         Self {
             thickness: Percentage(0.15),
+            block_opacity: Percentage(1.),
             unfocused_hollow: true,
+            animation: true,
             blink_interval: 750,
             blink_timeout: 5,
             style: Default::default(),
@@ -124,6 +277,18 @@ impl Cursor {
         self.thickness.as_f32()
     }
 
+    /// Opacity of the block cursor drawn over the text.
+    #[inline]
+    pub fn block_opacity(self) -> f32 {
+        self.block_opacity.as_f32()
+    }
+
+    /// Check if the text below a block cursor keeps its own colors.
+    #[inline]
+    pub fn transparent_block(self) -> bool {
+        self.block_opacity() < 1.
+    }
+
     #[inline]
     pub fn style(self) -> CursorStyle {
         self.style.into()
@@ -6,17 +6,39 @@ use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
 /// Maximum scrollback amount configurable.
 pub const MAX_SCROLLBACK_LINES: u32 = 100_000;
 
+/// Number of the most recent history lines which are never compressed.
+const UNCOMPRESSED_HISTORY_LINES: usize = 256;
+
 /// Struct for scrolling related settings.
 #[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Scrolling {
     pub multiplier: u8,
 
+    /// Maximum number of lines moved to disk once the history is full.
+    pub disk_history: usize,
+
+    /// Compress history lines which are out of view.
+    pub compress_history: bool,
+
+    /// Maximum memory used by the uncompressed history in MiB, `0` for no limit.
+    pub max_memory: usize,
+
+    /// Events which move the viewport back to the bottom of the scrollback.
+    pub scroll_to_bottom: ScrollToBottom,
+
     history: ScrollingHistory,
 }
 
 impl Default for Scrolling {
     fn default() -> Self {
-        Self { multiplier: 3, history: Default::default() }
+        Self {
+            multiplier: 3,
+            disk_history: 0,
+            compress_history: false,
+            max_memory: 0,
+            scroll_to_bottom: Default::default(),
+            history: Default::default(),
+        }
     }
 }
 
@@ -25,12 +47,37 @@ impl Scrolling {
         self.history.0
     }
 
+    /// Number of history lines which are stored compressed.
+    pub fn compressed_history(self) -> usize {
+        if self.compress_history {
+            (self.history() as usize).saturating_sub(UNCOMPRESSED_HISTORY_LINES)
+        } else {
+            0
+        }
+    }
+
     // Update the history size, used in ref tests.
     pub fn set_history(&mut self, history: u32) {
         self.history = ScrollingHistory(history);
     }
 }
 
+/// Events which move the viewport back to the bottom of the scrollback.
+#[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ScrollToBottom {
+    /// Scroll to the bottom when typing.
+    pub keypress: bool,
+
+    /// Scroll to the bottom when new output arrives.
+    pub output: bool,
+}
+
+impl Default for ScrollToBottom {
+    fn default() -> Self {
+        Self { keypress: true, output: false }
+    }
+}
+
 #[derive(SerdeReplace, Copy, Clone, Debug, PartialEq, Eq)]
 struct ScrollingHistory(u32);
 
@@ -1,7 +1,10 @@
 use std::borrow::Cow;
 use std::fmt::{self, Debug, Formatter};
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::ansi::Progress;
+use crate::replay::ReplayControl;
 use crate::term::color::Rgb;
 use crate::term::ClipboardType;
 
@@ -20,6 +23,9 @@ pub enum Event {
     /// Reset to the default window title.
     ResetTitle,
 
+    /// Window icon change, by name of the icon.
+    Icon(String),
+
     /// Request to store a text string in the clipboard.
     ClipboardStore(ClipboardType, String),
 
@@ -50,6 +56,18 @@ pub enum Event {
     /// Terminal bell ring.
     Bell,
 
+    /// Progress of a long running operation has changed.
+    ProgressChange(Option<Progress>),
+
+    /// Shell prompt reported through OSC 133 has started.
+    PromptStart,
+
+    /// Shell command reported through OSC 133 has finished, with its exit code.
+    CommandFinished(Option<i32>),
+
+    /// Child process exited while the terminal is kept open, with its exit code.
+    ChildExit(Option<i32>),
+
     /// Shutdown request.
     Exit,
 }
@@ -66,8 +84,13 @@ impl Debug for Event {
             Event::CursorBlinkingChange => write!(f, "CursorBlinkingChange"),
             Event::MouseCursorDirty => write!(f, "MouseCursorDirty"),
             Event::ResetTitle => write!(f, "ResetTitle"),
+            Event::Icon(name) => write!(f, "Icon({name})"),
             Event::Wakeup => write!(f, "Wakeup"),
             Event::Bell => write!(f, "Bell"),
+            Event::ProgressChange(progress) => write!(f, "ProgressChange({progress:?})"),
+            Event::PromptStart => write!(f, "PromptStart"),
+            Event::CommandFinished(exit_code) => write!(f, "CommandFinished({exit_code:?})"),
+            Event::ChildExit(exit_code) => write!(f, "ChildExit({exit_code:?})"),
             Event::Exit => write!(f, "Exit"),
         }
     }
@@ -79,6 +102,12 @@ pub trait Notify {
     ///
     /// TODO this needs to be able to error somehow.
     fn notify<B: Into<Cow<'static, [u8]>>>(&self, _: B);
+
+    /// Start recording the PTY output to a file, or stop an active recording.
+    fn toggle_recording(&self, path: PathBuf);
+
+    /// Control the playback of a replayed session.
+    fn control_replay(&self, control: ReplayControl);
 }
 
 #[derive(Copy, Clone, Debug)]
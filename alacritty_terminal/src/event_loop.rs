@@ -5,26 +5,40 @@ use std::collections::VecDeque;
 use std::fs::File;
 use std::io::{self, ErrorKind, Read, Write};
 use std::marker::Send;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread::JoinHandle;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use log::error;
+use log::{error, info};
 #[cfg(not(windows))]
 use mio::unix::UnixReady;
 use mio::{self, Events, PollOpt, Ready};
 use mio_extras::channel::{self, Receiver, Sender};
 
 use crate::event::{self, Event, EventListener, WindowSize};
+use crate::grid::Dimensions;
+use crate::recording::Recorder;
+use crate::replay::ReplayControl;
 use crate::sync::FairMutex;
 use crate::term::Term;
 use crate::{ansi, thread, tty};
 
-/// Max bytes to read from the PTY before forced terminal synchronization.
-const READ_BUFFER_SIZE: usize = 0x10_0000;
+/// Default max bytes to read from the PTY before forced terminal synchronization.
+pub const READ_BUFFER_SIZE: usize = 0x10_0000;
 
-/// Max bytes to read from the PTY while the terminal is locked.
-const MAX_LOCKED_READ: usize = u16::MAX as usize;
+/// Default max bytes to read from the PTY while the terminal is locked.
+pub const MAX_LOCKED_READ: usize = u16::MAX as usize;
+
+/// Minimum bytes to read from the PTY while the terminal is locked.
+const MIN_LOCKED_READ: usize = 0x1000;
+
+/// Target time the terminal is locked for parsing PTY output in one go.
+///
+/// This keeps the terminal responsive to input and animations while large amounts of output are
+/// parsed, since the lock is released at least this often.
+const LOCKED_READ_DURATION: Duration = Duration::from_millis(4);
 
 /// Messages that may be sent to the `EventLoop`.
 #[derive(Debug)]
@@ -37,6 +51,12 @@ pub enum Msg {
 
     /// Instruction to resize the PTY.
     Resize(WindowSize),
+
+    /// Start recording the PTY output to an asciicast file, or stop the active recording.
+    ToggleRecording(PathBuf),
+
+    /// Control the playback of a replayed session, ignored for PTYs.
+    Replay(ReplayControl),
 }
 
 /// The main event!.. loop.
@@ -51,7 +71,15 @@ pub struct EventLoop<T: tty::EventedPty, U: EventListener> {
     terminal: Arc<FairMutex<Term<U>>>,
     event_proxy: U,
     hold: bool,
+    hold_on_error: bool,
     ref_test: bool,
+    recorder: Option<Recorder>,
+    read_bytes: Arc<AtomicUsize>,
+    read_buffer_size: usize,
+    max_locked_read: usize,
+
+    /// Bytes to read while the terminal is locked, adapted to the parsing speed.
+    locked_read: usize,
 }
 
 /// Helper type which tracks how much of a buffer has been written.
@@ -75,6 +103,14 @@ impl event::Notify for Notifier {
 
         let _ = self.0.send(Msg::Input(bytes));
     }
+
+    fn toggle_recording(&self, path: PathBuf) {
+        let _ = self.0.send(Msg::ToggleRecording(path));
+    }
+
+    fn control_replay(&self, control: ReplayControl) {
+        let _ = self.0.send(Msg::Replay(control));
+    }
 }
 
 impl event::OnResize for Notifier {
@@ -92,6 +128,7 @@ pub struct State {
     write_list: VecDeque<Cow<'static, [u8]>>,
     writing: Option<Writing>,
     parser: ansi::Processor,
+    extended_parser: ansi::ExtendedProcessor,
 }
 
 impl State {
@@ -156,6 +193,7 @@ where
         event_proxy: U,
         pty: T,
         hold: bool,
+        hold_on_error: bool,
         ref_test: bool,
     ) -> EventLoop<T, U> {
         let (tx, rx) = channel::channel();
@@ -167,29 +205,94 @@ where
             terminal,
             event_proxy,
             hold,
+            hold_on_error,
             ref_test,
+            recorder: None,
+            read_bytes: Default::default(),
+            read_buffer_size: READ_BUFFER_SIZE,
+            max_locked_read: MAX_LOCKED_READ,
+            locked_read: MAX_LOCKED_READ,
         }
     }
 
+    /// Set the size of the PTY read buffer and the max bytes parsed while the terminal is locked.
+    ///
+    /// This must be called before the event loop is spawned to affect the read buffer.
+    pub fn set_read_limits(&mut self, read_buffer_size: usize, max_locked_read: usize) {
+        self.read_buffer_size = read_buffer_size.max(MIN_LOCKED_READ);
+        self.max_locked_read = max_locked_read.clamp(MIN_LOCKED_READ, self.read_buffer_size);
+        self.locked_read = self.max_locked_read;
+    }
+
     pub fn channel(&self) -> Sender<Msg> {
         self.tx.clone()
     }
 
+    /// Counter for the total number of bytes read from the PTY.
+    pub fn read_counter(&self) -> Arc<AtomicUsize> {
+        self.read_bytes.clone()
+    }
+
     /// Drain the channel.
     ///
     /// Returns `false` when a shutdown message was received.
     fn drain_recv_channel(&mut self, state: &mut State) -> bool {
+        // Only the last of multiple queued resizes is applied, since the PTY reflows its
+        // contents on every resize while the window is resized interactively.
+        let mut resize = None;
+
         while let Ok(msg) = self.rx.try_recv() {
             match msg {
                 Msg::Input(input) => state.write_list.push_back(input),
-                Msg::Resize(window_size) => self.pty.on_resize(window_size),
+                Msg::Resize(window_size) => resize = Some(window_size),
+                Msg::ToggleRecording(path) => self.toggle_recording(path),
+                Msg::Replay(_) => (),
                 Msg::Shutdown => return false,
             }
         }
 
+        if let Some(window_size) = resize {
+            self.pty.on_resize(window_size);
+
+            let (columns, lines) = (window_size.num_cols, window_size.num_lines);
+            self.record(|recorder| recorder.resize(columns.into(), lines.into()));
+        }
+
         true
     }
 
+    /// Start recording to `path`, or stop the active recording.
+    fn toggle_recording(&mut self, path: PathBuf) {
+        if self.recorder.take().is_some() {
+            info!("Stopped recording terminal session");
+            return;
+        }
+
+        let (columns, lines) = {
+            let terminal = self.terminal.lock();
+            (terminal.columns(), terminal.screen_lines())
+        };
+
+        match Recorder::create(&path, columns, lines) {
+            Ok(recorder) => {
+                info!("Recording terminal session to {path:?}");
+                self.recorder = Some(recorder);
+            },
+            Err(err) => error!("Unable to create recording {path:?}: {err}"),
+        }
+    }
+
+    /// Write an event to the active recording, stopping it on failure.
+    fn record<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Recorder) -> io::Result<()>,
+    {
+        if let Some(Err(err)) = self.recorder.as_mut().map(f) {
+            error!("Unable to write recording, stopping it: {err}");
+            self.recorder = None;
+        }
+    }
+
     /// Returns a `bool` indicating whether or not the event loop should continue running.
     #[inline]
     fn channel_event(&mut self, token: mio::Token, state: &mut State) -> bool {
@@ -216,6 +319,7 @@ where
     {
         let mut unprocessed = 0;
         let mut processed = 0;
+        let mut lock_start = None;
 
         // Reserve the next terminal lock for PTY reading.
         let _terminal_lease = Some(self.terminal.lease());
@@ -241,12 +345,16 @@ where
             // Attempt to lock the terminal.
             let terminal = match &mut terminal {
                 Some(terminal) => terminal,
-                None => terminal.insert(match self.terminal.try_lock_unfair() {
-                    // Force block if we are at the buffer size limit.
-                    None if unprocessed >= READ_BUFFER_SIZE => self.terminal.lock_unfair(),
-                    None => continue,
-                    Some(terminal) => terminal,
-                }),
+                None => {
+                    let locked = match self.terminal.try_lock_unfair() {
+                        // Force block if we are at the buffer size limit.
+                        None if unprocessed >= buf.len() => self.terminal.lock_unfair(),
+                        None => continue,
+                        Some(terminal) => terminal,
+                    };
+                    lock_start = Some(Instant::now());
+                    terminal.insert(locked)
+                },
             };
 
             // Write a copy of the bytes to the ref test file.
@@ -254,16 +362,32 @@ where
                 writer.write_all(&buf[..unprocessed]).unwrap();
             }
 
+            // Record the bytes with their timing.
+            if let Some(recorder) = &mut self.recorder {
+                if let Err(err) = recorder.output(&buf[..unprocessed]) {
+                    error!("Unable to write recording, stopping it: {err}");
+                    self.recorder = None;
+                }
+            }
+
             // Parse the incoming bytes.
             for byte in &buf[..unprocessed] {
                 state.parser.advance(&mut **terminal, *byte);
+                state.extended_parser.advance(&state.parser, &mut **terminal, *byte);
             }
 
             processed += unprocessed;
+            self.read_bytes.fetch_add(unprocessed, Ordering::Relaxed);
             unprocessed = 0;
 
             // Assure we're not blocking the terminal too long unnecessarily.
-            if processed >= MAX_LOCKED_READ {
+            if processed >= self.locked_read {
+                // Adapt the chunk size, so parsing the next one takes about the target duration.
+                if let Some(lock_start) = lock_start {
+                    self.locked_read =
+                        next_locked_read(processed, lock_start.elapsed(), self.max_locked_read);
+                }
+
                 break;
             }
         }
@@ -311,7 +435,7 @@ where
     pub fn spawn(mut self) -> JoinHandle<(Self, State)> {
         thread::spawn_named("PTY reader", move || {
             let mut state = State::default();
-            let mut buf = [0u8; READ_BUFFER_SIZE];
+            let mut buf = vec![0u8; self.read_buffer_size];
 
             let mut tokens = (0..).map(Into::into);
 
@@ -346,7 +470,10 @@ where
 
                 // Handle synchronized update timeout.
                 if events.is_empty() {
-                    state.parser.stop_sync(&mut *self.terminal.lock());
+                    let mut terminal = self.terminal.lock();
+                    state.parser.stop_sync(&mut *terminal);
+                    state.extended_parser.stop_sync(&mut *terminal);
+                    drop(terminal);
                     self.event_proxy.send_event(Event::Wakeup);
                     continue;
                 }
@@ -360,10 +487,13 @@ where
                         },
 
                         token if token == self.pty.child_event_token() => {
-                            if let Some(tty::ChildEvent::Exited) = self.pty.next_child_event() {
-                                if self.hold {
+                            let event = self.pty.next_child_event();
+                            if let Some(tty::ChildEvent::Exited(exit_code)) = event {
+                                let failed = exit_code != Some(0);
+                                if self.hold || (self.hold_on_error && failed) {
                                     // With hold enabled, make sure the PTY is drained.
                                     let _ = self.pty_read(&mut state, &mut buf, pipe.as_mut());
+                                    self.event_proxy.send_event(Event::ChildExit(exit_code));
                                 } else {
                                     // Without hold, shutdown the terminal.
                                     self.terminal.lock().exit();
@@ -430,3 +560,41 @@ where
         })
     }
 }
+
+/// Bytes to parse while the terminal is locked, based on the time it took to parse `processed`.
+fn next_locked_read(processed: usize, elapsed: Duration, max_locked_read: usize) -> usize {
+    let elapsed = elapsed.as_secs_f64().max(f64::EPSILON);
+    let target = processed as f64 * LOCKED_READ_DURATION.as_secs_f64() / elapsed;
+    let target = target.min(max_locked_read as f64) as usize;
+
+    // Move halfway to the target size, to avoid jumping around due to noisy measurements.
+    let next = (processed.min(max_locked_read) + target) / 2;
+    next.clamp(MIN_LOCKED_READ.min(max_locked_read), max_locked_read)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locked_read_shrinks_when_slow() {
+        let next = next_locked_read(MAX_LOCKED_READ, LOCKED_READ_DURATION * 4, MAX_LOCKED_READ);
+        assert!(next < MAX_LOCKED_READ);
+        assert!(next >= MIN_LOCKED_READ);
+    }
+
+    #[test]
+    fn locked_read_grows_when_fast() {
+        let next = next_locked_read(MIN_LOCKED_READ, Duration::ZERO, MAX_LOCKED_READ);
+        assert!(next > MIN_LOCKED_READ);
+
+        let next = next_locked_read(MAX_LOCKED_READ, Duration::ZERO, MAX_LOCKED_READ);
+        assert_eq!(next, MAX_LOCKED_READ);
+    }
+
+    #[test]
+    fn locked_read_within_limits() {
+        let next = next_locked_read(MIN_LOCKED_READ, Duration::from_secs(1), MAX_LOCKED_READ);
+        assert_eq!(next, MIN_LOCKED_READ);
+    }
+}
@@ -1,7 +1,9 @@
 //! A specialized 2D grid implementation optimized for use in a terminal.
 
 use std::cmp::{max, min};
+use std::mem;
 use std::ops::{Bound, Deref, Index, IndexMut, Range, RangeBounds};
+use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +19,7 @@ mod tests;
 
 pub use self::row::Row;
 use self::storage::Storage;
+pub use self::storage::{LineData, LineMarks};
 
 pub trait GridCell: Sized {
     /// Check if the cell contains any content.
@@ -133,6 +136,14 @@ pub struct Grid<T> {
 
     /// Maximum number of lines in history.
     max_scroll_limit: usize,
+
+    /// Lines removed from the top of the history, when they should be preserved.
+    #[serde(skip)]
+    evicted: Option<Vec<(Row<T>, LineData)>>,
+
+    /// Total number of lines scrolled off the top of the screen.
+    #[serde(skip)]
+    scrolled_lines: usize,
 }
 
 impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
@@ -141,6 +152,8 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
             raw: Storage::with_capacity(lines, columns),
             max_scroll_limit,
             display_offset: 0,
+            evicted: None,
+            scrolled_lines: 0,
             saved_cursor: Cursor::default(),
             cursor: Cursor::default(),
             lines,
@@ -148,10 +161,56 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         }
     }
 
+    /// Total number of lines scrolled off the top of the screen since the grid was created.
+    ///
+    /// This keeps increasing once the history is full, which allows tracking lines across
+    /// scrolls.
+    #[inline]
+    pub fn scrolled_lines(&self) -> usize {
+        self.scrolled_lines
+    }
+
+    /// Time at which a line was first written to.
+    #[inline]
+    pub fn timestamp(&self, line: Line) -> Option<SystemTime> {
+        self.raw.line_data(line).time()
+    }
+
+    /// Shell integration marks of a line.
+    #[inline]
+    pub fn marks(&self, line: Line) -> LineMarks {
+        self.raw.line_data(line).marks
+    }
+
+    /// Mutable access to the shell integration marks of a line.
+    #[inline]
+    pub fn marks_mut(&mut self, line: Line) -> &mut LineMarks {
+        &mut self.raw.line_data_mut(line).marks
+    }
+
+    /// Record the current time for a line, unless it already has a timestamp.
+    #[inline]
+    pub(crate) fn touch(&mut self, line: Line) {
+        self.raw.touch(line);
+    }
+
+    /// Maximum number of lines in the scrollback history.
+    #[inline]
+    pub fn history_limit(&self) -> usize {
+        self.max_scroll_limit
+    }
+
+    /// Estimated memory used by the grid's rows, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        let row_size = mem::size_of::<Row<T>>() + self.columns * mem::size_of::<T>();
+        self.raw.allocated_lines() * row_size
+    }
+
     /// Update the size of the scrollback history.
     pub fn update_history(&mut self, history_size: usize) {
         let current_history_size = self.history_size();
         if current_history_size > history_size {
+            self.evict_history(current_history_size - history_size);
             self.raw.shrink_lines(current_history_size - history_size);
         }
         self.display_offset = min(self.display_offset, history_size);
@@ -170,8 +229,64 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         };
     }
 
+    /// Keep lines removed from the top of the history, instead of discarding them.
+    pub fn set_evict_history(&mut self, enabled: bool) {
+        if enabled != self.evicted.is_some() {
+            self.evicted = enabled.then(Vec::new);
+        }
+    }
+
+    /// Take all lines removed from the top of the history, from oldest to newest.
+    pub fn take_evicted(&mut self) -> Vec<(Row<T>, LineData)> {
+        self.evicted.as_mut().map(mem::take).unwrap_or_default()
+    }
+
+    /// Add lines above the top of the history, ordered from oldest to newest.
+    ///
+    /// This allows the history to temporarily exceed its maximum size, until it is trimmed using
+    /// [`Grid::trim_history`].
+    pub fn restore_history(&mut self, rows: Vec<(Row<T>, LineData)>) {
+        if rows.is_empty() {
+            return;
+        }
+
+        self.raw.initialize(rows.len(), self.columns);
+
+        let topmost_line = -(self.history_size() as i32);
+        for (i, (mut row, data)) in rows.into_iter().enumerate() {
+            row.grow(self.columns);
+            row.shrink(self.columns);
+            self.raw.replace(Line(topmost_line + i as i32), row, data);
+        }
+    }
+
+    /// Remove the oldest `count` lines from the history.
+    pub fn truncate_history(&mut self, count: usize) {
+        self.decrease_scroll_limit(count);
+    }
+
+    /// Remove all history exceeding the maximum history size.
+    pub fn trim_history(&mut self) {
+        let excess = self.history_size().saturating_sub(self.max_scroll_limit);
+        if excess != 0 {
+            self.evict_history(excess);
+            self.raw.shrink_lines(excess);
+            self.display_offset = min(self.display_offset, self.history_size());
+        }
+    }
+
+    /// Preserve the oldest `count` lines in the history before they are removed.
+    fn evict_history(&mut self, count: usize) {
+        let topmost_line = -(self.history_size() as i32);
+        if let Some(evicted) = &mut self.evicted {
+            for line in (topmost_line..topmost_line + count as i32).map(Line::from) {
+                evicted.push(self.raw.replace(line, Row::new(self.columns), LineData::default()));
+            }
+        }
+    }
+
     fn increase_scroll_limit(&mut self, count: usize) {
-        let count = min(count, self.max_scroll_limit - self.history_size());
+        let count = min(count, self.max_scroll_limit.saturating_sub(self.history_size()));
         if count != 0 {
             self.raw.initialize(count, self.columns);
         }
@@ -194,7 +309,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         // When rotating the entire region, just reset everything.
         if region.end - region.start <= positions {
             for i in (region.start.0..region.end.0).map(Line::from) {
-                self.raw[i].reset(&self.cursor.template);
+                self.raw.reset(i, &self.cursor.template);
             }
 
             return;
@@ -223,7 +338,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
             // Ensure all new lines are fully cleared.
             for i in (0..positions).map(Line::from) {
-                self.raw[i].reset(&self.cursor.template);
+                self.raw.reset(i, &self.cursor.template);
             }
 
             // Swap the fixed lines at the top back into position.
@@ -239,7 +354,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
             let range = region.start.0..(region.start + positions).0;
             for line in range.rev().map(Line::from) {
-                self.raw[line].reset(&self.cursor.template);
+                self.raw.reset(line, &self.cursor.template);
             }
         }
     }
@@ -255,19 +370,25 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         // When rotating the entire region with fixed lines at the top, just reset everything.
         if region.end - region.start <= positions && region.start != 0 {
             for i in (region.start.0..region.end.0).map(Line::from) {
-                self.raw[i].reset(&self.cursor.template);
+                self.raw.reset(i, &self.cursor.template);
             }
 
             return;
         }
 
-        // Update display offset when not pinned to active area.
-        if self.display_offset != 0 {
-            self.display_offset = min(self.display_offset + positions, self.max_scroll_limit);
-        }
+        // Preserve lines which will be rotated out of the history.
+        let free_lines = self.max_scroll_limit.saturating_sub(self.history_size());
+        let evicted_lines = min(positions.saturating_sub(free_lines), self.history_size());
+        self.evict_history(evicted_lines);
 
         // Create scrollback for the new lines.
         self.increase_scroll_limit(positions);
+        self.scrolled_lines = self.scrolled_lines.wrapping_add(positions);
+
+        // Update display offset when not pinned to active area.
+        if self.display_offset != 0 {
+            self.display_offset = min(self.display_offset + positions, self.history_size());
+        }
 
         // Swap the lines fixed at the top to their target positions after rotation.
         //
@@ -287,7 +408,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         // Ensure all new lines are fully cleared.
         let screen_lines = self.screen_lines();
         for i in ((screen_lines - positions)..screen_lines).map(Line::from) {
-            self.raw[i].reset(&self.cursor.template);
+            self.raw.reset(i, &self.cursor.template);
         }
 
         // Swap the fixed lines at the bottom back into position.
@@ -318,7 +439,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
         // Reset rotated lines.
         for line in (0..(self.lines - positions)).map(Line::from) {
-            self.raw[line].reset(&self.cursor.template);
+            self.raw.reset(line, &self.cursor.template);
         }
     }
 
@@ -337,7 +458,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         // Reset all visible lines.
         let range = self.topmost_line().0..(self.screen_lines() as i32);
         for line in range.map(Line::from) {
-            self.raw[line].reset(&self.cursor.template);
+            self.raw.reset(line, &self.cursor.template);
         }
     }
 }
@@ -365,7 +486,7 @@ impl<T> Grid<T> {
         debug_assert!(end <= self.screen_lines() as i32);
 
         for line in (start.0..end.0).map(Line::from) {
-            self.raw[line].reset(&self.cursor.template);
+            self.raw.reset(line, &self.cursor.template);
         }
     }
 
@@ -374,6 +495,10 @@ impl<T> Grid<T> {
         // Explicitly purge all lines from history.
         self.raw.shrink_lines(self.history_size());
 
+        if let Some(evicted) = &mut self.evicted {
+            evicted.clear();
+        }
+
         // Reset display offset.
         self.display_offset = 0;
     }
@@ -7,7 +7,7 @@ use crate::index::{Boundary, Column, Line};
 use crate::term::cell::{Flags, ResetDiscriminant};
 
 use crate::grid::row::Row;
-use crate::grid::{Dimensions, Grid, GridCell};
+use crate::grid::{Dimensions, Grid, GridCell, LineData};
 
 impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
     /// Resize the grid's width and/or height.
@@ -107,7 +107,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
         self.columns = columns;
 
-        let mut reversed: Vec<Row<T>> = Vec::with_capacity(self.raw.len());
+        let mut reversed: Vec<(Row<T>, LineData)> = Vec::with_capacity(self.raw.len());
         let mut cursor_line_delta = 0;
 
         // Remove the linewrap special case, by moving the cursor outside of the grid.
@@ -116,14 +116,14 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
             self.cursor.point.column += 1;
         }
 
-        let mut rows = self.raw.take_all();
+        let (mut rows, mut data) = self.raw.take_all();
 
-        for (i, mut row) in rows.drain(..).enumerate().rev() {
+        for (i, (mut row, line_data)) in rows.drain(..).zip(data.drain(..)).enumerate().rev() {
             // Check if reflowing should be performed.
-            let last_row = match reversed.last_mut() {
-                Some(last_row) if should_reflow(last_row) => last_row,
+            let (last_row, last_data) = match reversed.last_mut() {
+                Some((last_row, last_data)) if should_reflow(last_row) => (last_row, last_data),
                 _ => {
-                    reversed.push(row);
+                    reversed.push((row, line_data));
                     continue;
                 },
             };
@@ -182,6 +182,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                 let line_delta = self.cursor.point.line - target.line;
 
                 if line_delta != 0 && row.is_clear() {
+                    last_data.merge(line_data);
                     continue;
                 }
 
@@ -197,7 +198,8 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                     self.cursor.point.line += 1;
                 }
 
-                // Don't push line into the new buffer.
+                // Don't push line into the new buffer, but keep its marks.
+                last_data.merge(line_data);
                 continue;
             }
 
@@ -206,14 +208,14 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                 cell.flags_mut().insert(Flags::WRAPLINE);
             }
 
-            reversed.push(row);
+            reversed.push((row, line_data));
         }
 
         // Make sure we have at least the viewport filled.
         if reversed.len() < self.lines {
             let delta = (self.lines - reversed.len()) as i32;
             self.cursor.point.line = max(self.cursor.point.line - delta, Line(0));
-            reversed.resize_with(self.lines, || Row::new(columns));
+            reversed.resize_with(self.lines, || (Row::new(columns), LineData::default()));
         }
 
         // Pull content down to put cursor in correct position, or move cursor up if there's no
@@ -228,14 +230,16 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
         // Reverse iterator and fill all rows that are still too short.
         let mut new_raw = Vec::with_capacity(reversed.len());
-        for mut row in reversed.drain(..).rev() {
+        let mut new_data = Vec::with_capacity(reversed.len());
+        for (mut row, line_data) in reversed.drain(..).rev() {
             if row.len() < columns {
                 row.grow(columns);
             }
             new_raw.push(row);
+            new_data.push(line_data);
         }
 
-        self.raw.replace_inner(new_raw);
+        self.raw.replace_inner(new_raw, new_data);
 
         // Clamp display offset in case lines above it got merged.
         self.display_offset = min(self.display_offset, self.history_size());
@@ -254,8 +258,8 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         let mut new_raw = Vec::with_capacity(self.raw.len());
         let mut buffered: Option<Vec<T>> = None;
 
-        let mut rows = self.raw.take_all();
-        for (i, mut row) in rows.drain(..).enumerate().rev() {
+        let (mut rows, mut data) = self.raw.take_all();
+        for (i, (mut row, mut line_data)) in rows.drain(..).zip(data.drain(..)).enumerate().rev() {
             // Append lines left over from the previous row.
             if let Some(buffered) = buffered.take() {
                 // Add a column for every cell added before the cursor, if it goes beyond the new
@@ -280,7 +284,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                             Vec::new()
                         } else {
                             // Since it fits, just push the existing line without any reflow.
-                            new_raw.push(row);
+                            new_raw.push((row, line_data));
                             break;
                         }
                     },
@@ -302,7 +306,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                 if len > 0 && wrapped[len - 1].flags().contains(Flags::LEADING_WIDE_CHAR_SPACER) {
                     if len == 1 {
                         row[Column(columns - 1)].flags_mut().insert(Flags::WRAPLINE);
-                        new_raw.push(row);
+                        new_raw.push((row, line_data));
                         break;
                     } else {
                         // Remove the leading spacer from the end of the wrapped row.
@@ -311,10 +315,11 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                     }
                 }
 
-                new_raw.push(row);
+                // Only the first of the wrapped lines keeps the line's metadata.
+                new_raw.push((row, mem::take(&mut line_data)));
 
                 // Set line as wrapped if cells got removed.
-                if let Some(cell) = new_raw.last_mut().and_then(|r| r.last_mut()) {
+                if let Some(cell) = new_raw.last_mut().and_then(|(r, _)| r.last_mut()) {
                     cell.flags_mut().insert(Flags::WRAPLINE);
                 }
 
@@ -364,9 +369,10 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         }
 
         // Reverse iterator and use it as the new grid storage.
-        let mut reversed: Vec<Row<T>> = new_raw.drain(..).rev().collect();
+        let (mut reversed, mut data): (Vec<_>, Vec<_>) = new_raw.drain(..).rev().unzip();
         reversed.truncate(self.max_scroll_limit + self.lines);
-        self.raw.replace_inner(reversed);
+        data.truncate(reversed.len());
+        self.raw.replace_inner(reversed, data);
 
         // Clamp display offset in case some lines went off.
         self.display_offset = min(self.display_offset, self.history_size());
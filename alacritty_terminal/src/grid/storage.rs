@@ -1,12 +1,15 @@
 use std::cmp::{max, PartialEq};
+use std::collections::HashMap;
 use std::mem;
 use std::mem::MaybeUninit;
 use std::ops::{Index, IndexMut};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 
-use super::Row;
+use super::{GridCell, Row};
 use crate::index::Line;
+use crate::term::cell::ResetDiscriminant;
 
 /// Maximum number of buffered lines outside of the grid for performance optimization.
 const MAX_CACHE_SIZE: usize = 1_000;
@@ -48,6 +51,85 @@ pub struct Storage<T> {
     /// As long as `len` is bigger than `inner`, it is also possible to grow the scrollback buffer
     /// without any additional insertions.
     len: usize,
+
+    /// Metadata of the rows in `inner`, stored separately to keep [`Row`] small.
+    #[serde(skip)]
+    line_data: LineTable,
+}
+
+/// Metadata attached to a line, which is stored outside of its [`Row`].
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LineData {
+    /// Milliseconds since the UNIX epoch at which the line was first written to.
+    ///
+    /// This is `0` for lines which have not been written to since their last reset.
+    pub timestamp: u64,
+
+    /// Shell integration marks on this line.
+    pub marks: LineMarks,
+}
+
+impl LineData {
+    /// Check if no metadata is set.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Time at which the line was last written to.
+    pub fn time(&self) -> Option<SystemTime> {
+        (self.timestamp != 0).then(|| UNIX_EPOCH + Duration::from_millis(self.timestamp))
+    }
+
+    /// Combine the metadata of a line which was merged into this one.
+    pub fn merge(&mut self, other: LineData) {
+        if self.timestamp == 0 {
+            self.timestamp = other.timestamp;
+        }
+
+        self.marks.prompt |= other.marks.prompt;
+        self.marks.command |= other.marks.command;
+        self.marks.output |= other.marks.output;
+        self.marks.bookmark |= other.marks.bookmark;
+        if other.marks.command_end {
+            self.marks.command_end = true;
+            self.marks.exit_code = other.marks.exit_code;
+        }
+    }
+}
+
+/// Shell integration marks attached to a line.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LineMarks {
+    /// A prompt starts on this line.
+    pub prompt: bool,
+
+    /// The command input starts on this line.
+    pub command: bool,
+
+    /// The command output starts on this line.
+    pub output: bool,
+
+    /// A command finished on this line.
+    pub command_end: bool,
+
+    /// Exit code of the command finished on this line.
+    pub exit_code: Option<i32>,
+
+    /// The line was bookmarked by the user.
+    pub bookmark: bool,
+}
+
+/// Sparse [`LineData`] storage, indexed by the position of the rows in the raw buffer.
+///
+/// Rows without any metadata don't have an entry, so rows which are never written to and unused
+/// rows in the buffer don't require any additional memory.
+#[derive(Default, Clone, Debug)]
+struct LineTable {
+    entries: HashMap<usize, LineData>,
+
+    /// Raw index of the last row with a timestamp, to skip the lookup while writing to it.
+    touched: Option<usize>,
 }
 
 impl<T: PartialEq> PartialEq for Storage<T> {
@@ -70,7 +152,7 @@ impl<T> Storage<T> {
         let mut inner = Vec::with_capacity(visible_lines);
         inner.resize_with(visible_lines, || Row::new(columns));
 
-        Storage { inner, zero: 0, visible_lines, len: visible_lines }
+        Storage { inner, zero: 0, visible_lines, len: visible_lines, line_data: Default::default() }
     }
 
     /// Increase the number of lines in the buffer.
@@ -108,6 +190,11 @@ impl<T> Storage<T> {
         // Free memory.
         if self.inner.len() > self.len + MAX_CACHE_SIZE {
             self.truncate();
+        } else if !self.line_data.entries.is_empty() {
+            // Drop the metadata of all removed lines.
+            let (zero, len, total) = (self.zero, self.len, self.inner.len());
+            self.line_data.entries.retain(|index, _| (index + total - zero) % total < len);
+            self.line_data.touched = None;
         }
     }
 
@@ -117,6 +204,10 @@ impl<T> Storage<T> {
         self.rezero();
 
         self.inner.truncate(self.len);
+
+        let len = self.len;
+        self.line_data.entries.retain(|index, _| *index < len);
+        self.line_data.touched = None;
     }
 
     /// Dynamically grow the storage buffer at runtime.
@@ -135,6 +226,12 @@ impl<T> Storage<T> {
         self.len += additional_rows;
     }
 
+    /// Number of rows allocated in the raw buffer, including unused ones.
+    #[inline]
+    pub fn allocated_lines(&self) -> usize {
+        self.inner.len()
+    }
+
     #[inline]
     pub fn len(&self) -> usize {
         self.len
@@ -171,6 +268,79 @@ impl<T> Storage<T> {
                 *b_ptr.offset(i) = tmp;
             }
         }
+
+        // Move the metadata with its rows.
+        if !self.line_data.entries.is_empty() {
+            let a_data = self.line_data.entries.remove(&a);
+            let b_data = self.line_data.entries.remove(&b);
+            if let Some(data) = a_data {
+                self.line_data.entries.insert(b, data);
+            }
+            if let Some(data) = b_data {
+                self.line_data.entries.insert(a, data);
+            }
+            self.line_data.touched = None;
+        }
+    }
+
+    /// Reset a line to the `template` cell, removing all its metadata.
+    #[inline]
+    pub fn reset<D>(&mut self, line: Line, template: &T)
+    where
+        T: ResetDiscriminant<D> + GridCell + Clone + Default,
+        D: PartialEq,
+    {
+        let index = self.compute_index(line);
+        self.inner[index].reset(template);
+
+        if !self.line_data.entries.is_empty() && self.line_data.entries.remove(&index).is_some() {
+            self.line_data.touched = None;
+        }
+    }
+
+    /// Replace a line, returning the previous row and its metadata.
+    pub fn replace(&mut self, line: Line, row: Row<T>, data: LineData) -> (Row<T>, LineData) {
+        let index = self.compute_index(line);
+        let row = mem::replace(&mut self.inner[index], row);
+
+        let old_data = if data.is_empty() {
+            self.line_data.entries.remove(&index)
+        } else {
+            self.line_data.entries.insert(index, data)
+        };
+        self.line_data.touched = None;
+
+        (row, old_data.unwrap_or_default())
+    }
+
+    /// Metadata of a line.
+    #[inline]
+    pub fn line_data(&self, line: Line) -> LineData {
+        let index = self.compute_index(line);
+        self.line_data.entries.get(&index).copied().unwrap_or_default()
+    }
+
+    /// Mutable access to the metadata of a line.
+    #[inline]
+    pub fn line_data_mut(&mut self, line: Line) -> &mut LineData {
+        let index = self.compute_index(line);
+        self.line_data.entries.entry(index).or_default()
+    }
+
+    /// Record the current time as the line's timestamp, unless it already has one.
+    #[inline]
+    pub fn touch(&mut self, line: Line) {
+        let index = self.compute_index(line);
+        if self.line_data.touched == Some(index) {
+            return;
+        }
+
+        let data = self.line_data.entries.entry(index).or_default();
+        if data.timestamp == 0 {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            data.timestamp = (now.as_millis() as u64).max(1);
+        }
+        self.line_data.touched = Some(index);
     }
 
     /// Rotate the grid, moving all lines up/down in history.
@@ -193,16 +363,24 @@ impl<T> Storage<T> {
     }
 
     /// Update the raw storage buffer.
+    ///
+    /// The `data` must contain the metadata of every row in `vec`.
     #[inline]
-    pub fn replace_inner(&mut self, vec: Vec<Row<T>>) {
+    pub fn replace_inner(&mut self, vec: Vec<Row<T>>, data: Vec<LineData>) {
+        debug_assert_eq!(vec.len(), data.len());
+
         self.len = vec.len();
         self.inner = vec;
         self.zero = 0;
+
+        let entries = data.into_iter().enumerate().filter(|(_, data)| !data.is_empty());
+        self.line_data.entries = entries.collect();
+        self.line_data.touched = None;
     }
 
-    /// Remove all rows from storage.
+    /// Remove all rows from storage, together with the metadata of every row.
     #[inline]
-    pub fn take_all(&mut self) -> Vec<Row<T>> {
+    pub fn take_all(&mut self) -> (Vec<Row<T>>, Vec<LineData>) {
         self.truncate();
 
         let mut buffer = Vec::new();
@@ -210,7 +388,13 @@ impl<T> Storage<T> {
         mem::swap(&mut buffer, &mut self.inner);
         self.len = 0;
 
-        buffer
+        let mut data = vec![LineData::default(); buffer.len()];
+        for (index, line_data) in self.line_data.entries.drain() {
+            data[index] = line_data;
+        }
+        self.line_data.touched = None;
+
+        (buffer, data)
     }
 
     /// Compute actual index in underlying storage given the requested index.
@@ -243,6 +427,14 @@ impl<T> Storage<T> {
         }
 
         self.inner.rotate_left(self.zero);
+
+        // Move the metadata with its rows.
+        let (zero, total) = (self.zero, self.inner.len());
+        let entries = mem::take(&mut self.line_data.entries).into_iter();
+        self.line_data.entries =
+            entries.map(|(index, data)| ((index + total - zero) % total, data)).collect();
+        self.line_data.touched = None;
+
         self.zero = 0;
     }
 }
@@ -268,7 +460,7 @@ impl<T> IndexMut<Line> for Storage<T> {
 #[cfg(test)]
 mod tests {
     use crate::grid::row::Row;
-    use crate::grid::storage::{Storage, MAX_CACHE_SIZE};
+    use crate::grid::storage::{LineData, Storage, MAX_CACHE_SIZE};
     use crate::grid::GridCell;
     use crate::index::{Column, Line};
     use crate::term::cell::Flags;
@@ -356,6 +548,7 @@ mod tests {
             zero: 0,
             visible_lines: 3,
             len: 3,
+            line_data: Default::default(),
         };
 
         // Grow buffer.
@@ -367,6 +560,7 @@ mod tests {
             zero: 0,
             visible_lines: 4,
             len: 4,
+            line_data: Default::default(),
         };
         expected.inner.append(&mut vec![filled_row('\0'); MAX_CACHE_SIZE]);
 
@@ -397,6 +591,7 @@ mod tests {
             zero: 1,
             visible_lines: 3,
             len: 3,
+            line_data: Default::default(),
         };
 
         // Grow buffer.
@@ -408,6 +603,7 @@ mod tests {
             zero: 0,
             visible_lines: 4,
             len: 4,
+            line_data: Default::default(),
         };
         expected.inner.append(&mut vec![filled_row('\0'); MAX_CACHE_SIZE]);
 
@@ -435,6 +631,7 @@ mod tests {
             zero: 1,
             visible_lines: 3,
             len: 3,
+            line_data: Default::default(),
         };
 
         // Shrink buffer.
@@ -446,6 +643,7 @@ mod tests {
             zero: 1,
             visible_lines: 2,
             len: 2,
+            line_data: Default::default(),
         };
         assert_eq!(storage.visible_lines, expected.visible_lines);
         assert_eq!(storage.inner, expected.inner);
@@ -471,6 +669,7 @@ mod tests {
             zero: 0,
             visible_lines: 3,
             len: 3,
+            line_data: Default::default(),
         };
 
         // Shrink buffer.
@@ -482,6 +681,7 @@ mod tests {
             zero: 0,
             visible_lines: 2,
             len: 2,
+            line_data: Default::default(),
         };
         assert_eq!(storage.visible_lines, expected.visible_lines);
         assert_eq!(storage.inner, expected.inner);
@@ -520,6 +720,7 @@ mod tests {
             zero: 2,
             visible_lines: 6,
             len: 6,
+            line_data: Default::default(),
         };
 
         // Shrink buffer.
@@ -538,6 +739,7 @@ mod tests {
             zero: 2,
             visible_lines: 2,
             len: 2,
+            line_data: Default::default(),
         };
         assert_eq!(storage.visible_lines, expected.visible_lines);
         assert_eq!(storage.inner, expected.inner);
@@ -572,6 +774,7 @@ mod tests {
             zero: 2,
             visible_lines: 1,
             len: 2,
+            line_data: Default::default(),
         };
 
         // Truncate buffer.
@@ -583,6 +786,7 @@ mod tests {
             zero: 0,
             visible_lines: 1,
             len: 2,
+            line_data: Default::default(),
         };
         assert_eq!(storage.visible_lines, expected.visible_lines);
         assert_eq!(storage.inner, expected.inner);
@@ -607,6 +811,7 @@ mod tests {
             zero: 2,
             visible_lines: 1,
             len: 2,
+            line_data: Default::default(),
         };
 
         // Truncate buffer.
@@ -618,6 +823,7 @@ mod tests {
             zero: 0,
             visible_lines: 1,
             len: 2,
+            line_data: Default::default(),
         };
         assert_eq!(storage.visible_lines, expected.visible_lines);
         assert_eq!(storage.inner, expected.inner);
@@ -664,6 +870,7 @@ mod tests {
             zero: 2,
             visible_lines: 0,
             len: 6,
+            line_data: Default::default(),
         };
 
         // Shrink buffer.
@@ -682,6 +889,7 @@ mod tests {
             zero: 2,
             visible_lines: 0,
             len: 3,
+            line_data: Default::default(),
         };
         assert_eq!(storage.inner, shrinking_expected.inner);
         assert_eq!(storage.zero, shrinking_expected.zero);
@@ -703,6 +911,7 @@ mod tests {
             zero: 2,
             visible_lines: 0,
             len: 4,
+            line_data: Default::default(),
         };
 
         assert_eq!(storage.inner, growing_expected.inner);
@@ -725,6 +934,7 @@ mod tests {
             zero: 2,
             visible_lines: 0,
             len: 6,
+            line_data: Default::default(),
         };
 
         // Initialize additional lines.
@@ -742,7 +952,13 @@ mod tests {
         ];
         let expected_init_size = std::cmp::max(init_size, MAX_CACHE_SIZE);
         expected_inner.append(&mut vec![filled_row('\0'); expected_init_size]);
-        let expected_storage = Storage { inner: expected_inner, zero: 0, visible_lines: 0, len: 9 };
+        let expected_storage = Storage {
+            inner: expected_inner,
+            zero: 0,
+            visible_lines: 0,
+            len: 9,
+            line_data: Default::default(),
+        };
 
         assert_eq!(storage.len, expected_storage.len);
         assert_eq!(storage.zero, expected_storage.zero);
@@ -756,6 +972,7 @@ mod tests {
             zero: 2,
             visible_lines: 0,
             len: 3,
+            line_data: Default::default(),
         };
 
         storage.rotate(2);
@@ -763,6 +980,32 @@ mod tests {
         assert!(storage.zero < storage.inner.len());
     }
 
+    #[test]
+    fn line_data_follows_rows() {
+        let mut storage: Storage<char> = Storage {
+            inner: vec![filled_row('0'), filled_row('1'), filled_row('2')],
+            zero: 1,
+            visible_lines: 3,
+            len: 3,
+            line_data: Default::default(),
+        };
+        storage.touch(Line(0));
+        let data = storage.line_data(Line(0));
+        assert_ne!(data.timestamp, 0);
+
+        storage.swap(Line(0), Line(2));
+        assert_eq!(storage.line_data(Line(0)), LineData::default());
+        assert_eq!(storage.line_data(Line(2)), data);
+
+        storage.truncate();
+        assert_eq!(storage.zero, 0);
+        assert_eq!(storage[Line(2)], filled_row('0'));
+        assert_eq!(storage.line_data(Line(2)), data);
+
+        storage.reset(Line(2), &' ');
+        assert_eq!(storage.line_data(Line(2)), LineData::default());
+    }
+
     fn filled_row(content: char) -> Row<char> {
         let mut row = Row::new(1);
         row[Column(0)] = content;
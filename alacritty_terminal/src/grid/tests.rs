@@ -54,6 +54,20 @@ fn scroll_up() {
     assert_eq!(grid[Line(9)].occ, 0);
 }
 
+// Scrolled lines keep being counted once the history is full.
+#[test]
+fn scrolled_lines_beyond_history() {
+    let mut grid = Grid::<usize>::new(3, 1, 2);
+
+    grid.scroll_up::<usize>(&(Line(0)..Line(3)), 2);
+    assert_eq!(grid.history_size(), 2);
+    assert_eq!(grid.scrolled_lines(), 2);
+
+    grid.scroll_up::<usize>(&(Line(0)..Line(3)), 3);
+    assert_eq!(grid.history_size(), 2);
+    assert_eq!(grid.scrolled_lines(), 5);
+}
+
 // Scroll down moves lines downward.
 #[test]
 fn scroll_down() {
@@ -276,6 +290,24 @@ fn grow_reflow() {
     assert_eq!(grid[Line(1)][Column(2)], Cell::default());
 }
 
+#[test]
+fn grow_reflow_keeps_marks() {
+    let mut grid = Grid::<Cell>::new(2, 2, 0);
+    grid[Line(0)][Column(0)] = cell('1');
+    grid[Line(0)][Column(1)] = wrap_cell('2');
+    grid[Line(1)][Column(0)] = cell('3');
+    grid.marks_mut(Line(0)).prompt = true;
+    grid.marks_mut(Line(1)).command_end = true;
+
+    grid.resize(true, 2, 3);
+
+    // Marks of the wrapped line are moved to the line it was merged into.
+    let marks = grid.marks(Line(0));
+    assert!(marks.prompt);
+    assert!(marks.command_end);
+    assert_eq!(grid.marks(Line(1)), LineMarks::default());
+}
+
 #[test]
 fn grow_reflow_multiline() {
     let mut grid = Grid::<Cell>::new(3, 2, 0);
@@ -10,6 +10,8 @@ pub mod event;
 pub mod event_loop;
 pub mod grid;
 pub mod index;
+pub mod recording;
+pub mod replay;
 pub mod selection;
 pub mod sync;
 pub mod term;
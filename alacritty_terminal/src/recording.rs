@@ -0,0 +1,146 @@
+//! Recording of terminal sessions in the asciicast v2 format.
+//!
+//! See <https://docs.asciinema.org/manual/asciicast/v2/> for the format specification.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Recorder writing PTY output to an asciicast file.
+pub struct Recorder<W: Write = BufWriter<File>> {
+    writer: W,
+    start: Instant,
+
+    /// Incomplete UTF-8 sequence at the end of the last output.
+    pending: Vec<u8>,
+}
+
+impl Recorder {
+    /// Create a new recording file, replacing any existing file at `path`.
+    pub fn create(path: &Path, columns: usize, lines: usize) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Self::new(BufWriter::new(file), columns, lines)
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// Start a new recording by writing its header.
+    pub fn new(mut writer: W, columns: usize, lines: usize) -> io::Result<Self> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        writeln!(
+            writer,
+            "{{\"version\": 2, \"width\": {columns}, \"height\": {lines}, \"timestamp\": {}}}",
+            timestamp.as_secs()
+        )?;
+        writer.flush()?;
+
+        Ok(Self { writer, start: Instant::now(), pending: Vec::new() })
+    }
+
+    /// Record output read from the PTY.
+    pub fn output(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.pending.extend_from_slice(bytes);
+
+        // Hold back incomplete UTF-8 sequences until the rest of them is read.
+        let end = utf8_boundary(&self.pending);
+        if end == 0 {
+            return Ok(());
+        }
+
+        let data = String::from_utf8_lossy(&self.pending[..end]).into_owned();
+        self.pending.drain(..end);
+
+        self.event("o", &data)
+    }
+
+    /// Record a change of the terminal dimensions.
+    pub fn resize(&mut self, columns: usize, lines: usize) -> io::Result<()> {
+        self.event("r", &format!("{columns}x{lines}"))
+    }
+
+    /// Stop the recording, returning its writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> io::Result<()> {
+        let time = self.start.elapsed().as_secs_f64();
+        writeln!(self.writer, "[{time:.6}, \"{code}\", \"{}\"]", escape_json(data))?;
+        self.writer.flush()
+    }
+}
+
+/// Length of `bytes` without an incomplete UTF-8 sequence at its end.
+fn utf8_boundary(bytes: &[u8]) -> usize {
+    for (offset, byte) in bytes.iter().rev().take(4).enumerate() {
+        let width = match byte {
+            0x80..=0xbf => continue,
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => 1,
+        };
+
+        return if width > offset + 1 { bytes.len() - offset - 1 } else { bytes.len() };
+    }
+
+    bytes.len()
+}
+
+/// Escape text for use in a JSON string.
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            },
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape() {
+        assert_eq!(escape_json("a\"b\\c"), "a\\\"b\\\\c");
+        assert_eq!(escape_json("\x1b[0m\r\n"), "\\u001b[0m\\r\\n");
+        assert_eq!(escape_json("€"), "€");
+    }
+
+    #[test]
+    fn split_utf8() {
+        assert_eq!(utf8_boundary(b"abc"), 3);
+        assert_eq!(utf8_boundary(b"a\xe2\x82"), 1);
+        assert_eq!(utf8_boundary(b"a\xe2\x82\xac"), 4);
+        assert_eq!(utf8_boundary(b"\xf0"), 0);
+        assert_eq!(utf8_boundary(b"\xff"), 1);
+    }
+
+    #[test]
+    fn events() {
+        let mut recorder = Recorder::new(Vec::new(), 80, 24).unwrap();
+        recorder.output(b"a\xe2\x82").unwrap();
+        recorder.output(b"\xac").unwrap();
+        recorder.resize(100, 30).unwrap();
+
+        let text = String::from_utf8(recorder.writer).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("{\"version\": 2, \"width\": 80, \"height\": 24, "));
+        assert!(lines[1].ends_with(", \"o\", \"a\"]"));
+        assert!(lines[2].ends_with(", \"o\", \"€\"]"));
+        assert!(lines[3].ends_with(", \"r\", \"100x30\"]"));
+    }
+}
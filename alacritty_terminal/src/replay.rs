@@ -0,0 +1,431 @@
+//! Playback of terminal sessions recorded in the asciicast v2 format.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, ErrorKind};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use log::info;
+use mio::{self, Events, PollOpt, Ready};
+use mio_extras::channel::{self, Receiver, Sender};
+use serde::Deserialize;
+
+use crate::ansi::{self, Handler};
+use crate::event::{Event, EventListener};
+use crate::event_loop::Msg;
+use crate::sync::FairMutex;
+use crate::term::Term;
+use crate::thread;
+
+/// Slowest supported playback speed.
+const MIN_SPEED: f64 = 1. / 16.;
+
+/// Fastest supported playback speed.
+const MAX_SPEED: f64 = 16.;
+
+/// Playback controls of a replayed session.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ReplayControl {
+    /// Pause or resume the playback, restarting it once the end was reached.
+    TogglePause,
+
+    /// Move the playback position by the given number of seconds.
+    Seek(f64),
+
+    /// Multiply the playback speed by the given factor.
+    ScaleSpeed(f64),
+}
+
+/// Header of an asciicast file.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub version: u8,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Header {
+    /// Read the header of the asciicast file at `path`.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut line = String::new();
+        BufReader::new(File::open(path)?).read_line(&mut line)?;
+        Self::parse(&line)
+    }
+
+    fn parse(line: &str) -> io::Result<Self> {
+        let header: Self = serde_json::from_str(line).map_err(invalid_data)?;
+        if header.version != 2 {
+            let message = format!("unsupported asciicast version {}", header.version);
+            return Err(invalid_data(message));
+        }
+
+        Ok(header)
+    }
+}
+
+/// Output of a recorded session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cast {
+    pub header: Header,
+    events: Vec<Output>,
+}
+
+/// Output written at a time relative to the start of the recording.
+#[derive(Debug, Clone, PartialEq)]
+struct Output {
+    time: f64,
+    data: String,
+}
+
+impl Cast {
+    /// Load the asciicast file at `path`.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Recording without any output.
+    pub fn empty(width: usize, height: usize) -> Self {
+        Self { header: Header { version: 2, width, height }, events: Vec::new() }
+    }
+
+    fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut lines = reader.lines();
+        let header = match lines.next() {
+            Some(line) => Header::parse(&line?)?,
+            None => return Err(invalid_data("missing asciicast header")),
+        };
+
+        let mut events = Vec::new();
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // Only output is replayed, since the terminal dimensions follow the window.
+            let (time, code, data): (f64, String, String) =
+                serde_json::from_str(&line).map_err(invalid_data)?;
+            if code == "o" {
+                events.push(Output { time, data });
+            }
+        }
+
+        Ok(Self { header, events })
+    }
+
+    /// Time of the last output in seconds.
+    pub fn duration(&self) -> f64 {
+        self.events.last().map_or(0., |event| event.time)
+    }
+}
+
+/// Position of the playback in the recording.
+#[derive(Debug)]
+struct Clock {
+    /// Position at the time the playback was last resumed or moved.
+    position: f64,
+
+    /// Start of the current playback, `None` while paused.
+    resumed: Option<Instant>,
+
+    speed: f64,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self { position: 0., resumed: Some(Instant::now()), speed: 1. }
+    }
+}
+
+impl Clock {
+    /// Current playback position in seconds.
+    fn time(&self) -> f64 {
+        let elapsed = self.resumed.map_or(0., |resumed| resumed.elapsed().as_secs_f64());
+        self.position + elapsed * self.speed
+    }
+
+    fn paused(&self) -> bool {
+        self.resumed.is_none()
+    }
+
+    fn pause(&mut self) {
+        self.position = self.time();
+        self.resumed = None;
+    }
+
+    fn resume(&mut self) {
+        self.resumed.get_or_insert_with(Instant::now);
+    }
+
+    fn set_time(&mut self, time: f64) {
+        self.position = time;
+        if let Some(resumed) = &mut self.resumed {
+            *resumed = Instant::now();
+        }
+    }
+
+    fn set_speed(&mut self, speed: f64) {
+        self.set_time(self.time());
+        self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    }
+
+    /// Real time until the playback reaches `time`.
+    fn until(&self, time: f64) -> Option<Duration> {
+        self.resumed.map(|_| Duration::from_secs_f64(((time - self.time()) / self.speed).max(0.)))
+    }
+}
+
+/// Player feeding a recorded session into the terminal instead of a PTY.
+pub struct Player<U: EventListener> {
+    cast: Cast,
+    poll: mio::Poll,
+    rx: Receiver<Msg>,
+    tx: Sender<Msg>,
+    terminal: Arc<FairMutex<Term<U>>>,
+    event_proxy: U,
+    parser: ansi::Processor,
+    extended_parser: ansi::ExtendedProcessor,
+    clock: Clock,
+
+    /// Index of the next output which has not been replayed yet.
+    next: usize,
+}
+
+impl<U> Player<U>
+where
+    U: EventListener + Send + 'static,
+{
+    /// Create a new player, starting the playback immediately.
+    pub fn new(cast: Cast, terminal: Arc<FairMutex<Term<U>>>, event_proxy: U) -> Self {
+        let (tx, rx) = channel::channel();
+        Self {
+            cast,
+            poll: mio::Poll::new().expect("create mio Poll"),
+            rx,
+            tx,
+            terminal,
+            event_proxy,
+            parser: Default::default(),
+            extended_parser: Default::default(),
+            clock: Default::default(),
+            next: 0,
+        }
+    }
+
+    /// Channel for controlling the playback.
+    ///
+    /// Input and resize messages are ignored, since there is no PTY to write to.
+    pub fn channel(&self) -> Sender<Msg> {
+        self.tx.clone()
+    }
+
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        thread::spawn_named("replay", move || {
+            let token = mio::Token(0);
+            let poll_opts = PollOpt::edge() | PollOpt::oneshot();
+            self.poll.register(&self.rx, token, Ready::readable(), poll_opts).unwrap();
+
+            let mut events = Events::with_capacity(16);
+
+            loop {
+                self.advance();
+
+                if let Err(err) = self.poll.poll(&mut events, self.timeout()) {
+                    match err.kind() {
+                        ErrorKind::Interrupted => continue,
+                        _ => panic!("Replay polling error: {err:?}"),
+                    }
+                }
+
+                // Output is replayed at the start of the next iteration after a timeout.
+                if events.is_empty() {
+                    continue;
+                }
+
+                if !self.drain_recv_channel() {
+                    break;
+                }
+
+                self.poll.reregister(&self.rx, token, Ready::readable(), poll_opts).unwrap();
+            }
+
+            let _ = self.poll.deregister(&self.rx);
+        })
+    }
+
+    /// Drain the channel.
+    ///
+    /// Returns `false` when a shutdown message was received.
+    fn drain_recv_channel(&mut self) -> bool {
+        while let Ok(msg) = self.rx.try_recv() {
+            match msg {
+                Msg::Replay(control) => self.control(control),
+                Msg::Shutdown => return false,
+                Msg::Input(_) | Msg::Resize(_) | Msg::ToggleRecording(_) => (),
+            }
+        }
+
+        true
+    }
+
+    fn control(&mut self, control: ReplayControl) {
+        match control {
+            ReplayControl::TogglePause if self.clock.paused() => {
+                if self.next == self.cast.events.len() {
+                    self.rewind(0.);
+                }
+                self.clock.resume();
+            },
+            ReplayControl::TogglePause => self.clock.pause(),
+            ReplayControl::Seek(offset) => {
+                let time = (self.clock.time() + offset).clamp(0., self.cast.duration());
+                self.rewind(time);
+            },
+            ReplayControl::ScaleSpeed(factor) => self.clock.set_speed(self.clock.speed * factor),
+        }
+
+        info!(
+            "Replay at {:.1}s of {:.1}s, {}x speed{}",
+            self.clock.time(),
+            self.cast.duration(),
+            self.clock.speed,
+            if self.clock.paused() { ", paused" } else { "" }
+        );
+    }
+
+    /// Move the playback to `time`.
+    fn rewind(&mut self, time: f64) {
+        // Moving backwards requires replaying everything from the start.
+        if self.next > 0 && self.cast.events[self.next - 1].time > time {
+            self.terminal.lock().reset_state();
+            self.parser = Default::default();
+            self.extended_parser = Default::default();
+            self.next = 0;
+        }
+
+        self.clock.set_time(time);
+    }
+
+    /// Replay all output up to the current playback position.
+    fn advance(&mut self) {
+        let time = self.clock.time();
+        let pending = self.cast.events.get(self.next).map_or(false, |event| event.time <= time);
+        let sync_expired = self
+            .parser
+            .sync_timeout()
+            .sync_timeout()
+            .map_or(false, |timeout| timeout <= Instant::now());
+
+        if pending || sync_expired {
+            let mut terminal = self.terminal.lock();
+
+            while let Some(event) = self.cast.events.get(self.next).filter(|e| e.time <= time) {
+                for byte in event.data.as_bytes() {
+                    self.parser.advance(&mut *terminal, *byte);
+                    self.extended_parser.advance(&self.parser, &mut *terminal, *byte);
+                }
+                self.next += 1;
+            }
+
+            if sync_expired {
+                self.parser.stop_sync(&mut *terminal);
+                self.extended_parser.stop_sync(&mut *terminal);
+            }
+
+            drop(terminal);
+            self.event_proxy.send_event(Event::Wakeup);
+        }
+
+        // Stop at the end of the recording.
+        if self.next == self.cast.events.len() && !self.clock.paused() {
+            self.clock.pause();
+        }
+    }
+
+    /// Time until the next output or synchronized update timeout.
+    fn timeout(&self) -> Option<Duration> {
+        let output = self.cast.events.get(self.next).and_then(|event| self.clock.until(event.time));
+        let sync = self
+            .parser
+            .sync_timeout()
+            .sync_timeout()
+            .map(|timeout| timeout.saturating_duration_since(Instant::now()));
+
+        match (output, sync) {
+            (Some(output), Some(sync)) => Some(output.min(sync)),
+            (output, sync) => output.or(sync),
+        }
+    }
+}
+
+fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::recording::Recorder;
+
+    #[test]
+    fn parse_cast() {
+        let cast = concat!(
+            "{\"version\": 2, \"width\": 80, \"height\": 24}\n",
+            "[0.5, \"o\", \"a\\u001b[0m\"]\n",
+            "\n",
+            "[1.0, \"r\", \"100x30\"]\n",
+            "[1.25, \"o\", \"\\r\\n\"]\n",
+        );
+        let cast = Cast::from_reader(cast.as_bytes()).unwrap();
+
+        assert_eq!(cast.header, Header { version: 2, width: 80, height: 24 });
+        assert_eq!(cast.events.len(), 2);
+        assert_eq!(cast.events[0], Output { time: 0.5, data: "a\x1b[0m".into() });
+        assert_eq!(cast.events[1], Output { time: 1.25, data: "\r\n".into() });
+        assert_eq!(cast.duration(), 1.25);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let cast = "{\"version\": 1, \"width\": 80, \"height\": 24}\n";
+        assert!(Cast::from_reader(cast.as_bytes()).is_err());
+        assert!(Cast::from_reader("".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn recording_roundtrip() {
+        let mut recorder = Recorder::new(Vec::new(), 80, 24).unwrap();
+        recorder.output("\"€\"\x1b[0m".as_bytes()).unwrap();
+        recorder.resize(100, 30).unwrap();
+
+        let cast = Cast::from_reader(&recorder.into_inner()[..]).unwrap();
+
+        assert_eq!(cast.header, Header { version: 2, width: 80, height: 24 });
+        assert_eq!(cast.events.len(), 1);
+        assert_eq!(cast.events[0].data, "\"€\"\x1b[0m");
+    }
+
+    #[test]
+    fn clock() {
+        let mut clock = Clock { position: 2., resumed: None, speed: 1. };
+        assert_eq!(clock.time(), 2.);
+        assert_eq!(clock.until(3.), None);
+
+        clock.set_speed(64.);
+        assert_eq!(clock.speed, MAX_SPEED);
+
+        clock.set_time(1.);
+        clock.resume();
+        assert!(clock.time() >= 1.);
+        assert!(clock.until(17.).unwrap() <= Duration::from_secs(1));
+
+        clock.pause();
+        assert!(clock.paused());
+    }
+}
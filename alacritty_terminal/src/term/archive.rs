@@ -0,0 +1,785 @@
+//! Compressed storage for scrollback history exceeding the in-memory limit.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{mem, process};
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+use vte::ansi::Rgb as VteRgb;
+
+use crate::ansi::{Color, NamedColor};
+use crate::grid::{LineData, LineMarks, Row};
+use crate::index::Column;
+use crate::term::cell::{Cell, Flags, Hyperlink, Hyperlinks};
+
+/// Number of lines compressed into a single chunk.
+pub const CHUNK_LINES: usize = 256;
+
+/// Buffer size used when compacting the archive file.
+const COMPACT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Counter for unique archive file names within a process.
+static ARCHIVE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// All named colors, used to decode their discriminant.
+const NAMED_COLORS: [NamedColor; 29] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+    NamedColor::Foreground,
+    NamedColor::Background,
+    NamedColor::Cursor,
+    NamedColor::DimBlack,
+    NamedColor::DimRed,
+    NamedColor::DimGreen,
+    NamedColor::DimYellow,
+    NamedColor::DimBlue,
+    NamedColor::DimMagenta,
+    NamedColor::DimCyan,
+    NamedColor::DimWhite,
+    NamedColor::BrightForeground,
+    NamedColor::DimForeground,
+];
+
+/// Compressed scrollback history exceeding the uncompressed in-memory limit.
+///
+/// Lines are stored from oldest to newest in run-length compressed chunks. Lines are always
+/// removed from the newest end when paging history back into memory, so the chunks stay
+/// contiguous.
+#[derive(Debug)]
+pub struct Archive {
+    /// Storage for the compressed chunks.
+    backing: Backing,
+
+    /// Chunks in the backing storage, from oldest to newest.
+    chunks: VecDeque<Chunk>,
+
+    /// Encoded lines which have not been moved to the backing storage yet.
+    pending: Vec<u8>,
+
+    /// Number of lines in the pending buffer.
+    pending_lines: usize,
+
+    /// Number of lines stored in this archive.
+    lines: usize,
+
+    /// Maximum number of lines stored in this archive.
+    max_lines: usize,
+
+    /// End of the used portion of the backing storage.
+    len: u64,
+
+    /// Archive receiving the oldest chunks once the line limit is exceeded.
+    overflow: Option<Box<Archive>>,
+}
+
+/// Location of a chunk inside the backing storage.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Chunk {
+    offset: u64,
+    size: usize,
+    lines: usize,
+}
+
+/// Storage for compressed chunks.
+#[derive(Debug)]
+enum Backing {
+    /// Chunks kept in memory.
+    Memory(Vec<u8>),
+
+    /// Chunks stored in a temporary file, which is removed on drop.
+    File { file: File, path: PathBuf },
+}
+
+impl Backing {
+    fn read(&mut self, offset: u64, buffer: &mut [u8]) -> io::Result<()> {
+        match self {
+            Self::Memory(data) => {
+                let offset = offset as usize;
+                let data = data.get(offset..offset + buffer.len()).ok_or_else(invalid_data)?;
+                buffer.copy_from_slice(data);
+                Ok(())
+            },
+            Self::File { file, .. } => {
+                file.seek(SeekFrom::Start(offset))?;
+                file.read_exact(buffer)
+            },
+        }
+    }
+
+    /// Write data at the end of the used storage.
+    fn append(&mut self, offset: u64, data: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Memory(buffer) => {
+                buffer.truncate(offset as usize);
+                buffer.extend_from_slice(data);
+                Ok(())
+            },
+            Self::File { file, .. } => {
+                file.seek(SeekFrom::Start(offset))?;
+                file.write_all(data)
+            },
+        }
+    }
+
+    /// Move the data between `start` and `end` to the start of the storage.
+    fn compact(&mut self, start: u64, end: u64) -> io::Result<()> {
+        let file = match self {
+            Self::Memory(buffer) => {
+                buffer.truncate(end as usize);
+                buffer.drain(..start as usize);
+                buffer.shrink_to_fit();
+                return Ok(());
+            },
+            Self::File { file, .. } => file,
+        };
+
+        let mut buffer = vec![0; COMPACT_BUFFER_SIZE];
+        let mut read_offset = start;
+        let mut write_offset = 0;
+        while read_offset < end {
+            let size = ((end - read_offset) as usize).min(buffer.len());
+            file.seek(SeekFrom::Start(read_offset))?;
+            file.read_exact(&mut buffer[..size])?;
+            file.seek(SeekFrom::Start(write_offset))?;
+            file.write_all(&buffer[..size])?;
+
+            read_offset += size as u64;
+            write_offset += size as u64;
+        }
+
+        file.set_len(write_offset)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        match self {
+            Self::Memory(buffer) => {
+                *buffer = Vec::new();
+                Ok(())
+            },
+            Self::File { file, .. } => file.set_len(0),
+        }
+    }
+}
+
+impl Drop for Backing {
+    fn drop(&mut self) {
+        if let Self::File { path, .. } = self {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+impl Archive {
+    /// Create a new archive, keeping the compressed lines in memory.
+    pub fn in_memory(max_lines: usize) -> Self {
+        Self::with_backing(Backing::Memory(Vec::new()), max_lines)
+    }
+
+    /// Create a new archive in the system's temporary directory.
+    pub fn on_disk(max_lines: usize) -> io::Result<Self> {
+        let id = ARCHIVE_ID.fetch_add(1, Ordering::Relaxed);
+        let name = format!("alacritty-scrollback-{}-{id}", process::id());
+        let path = std::env::temp_dir().join(name);
+
+        let mut options = OpenOptions::new();
+        options.read(true).write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let file = options.open(&path)?;
+
+        Ok(Self::with_backing(Backing::File { file, path }, max_lines))
+    }
+
+    fn with_backing(backing: Backing, max_lines: usize) -> Self {
+        Self {
+            backing,
+            max_lines,
+            chunks: Default::default(),
+            pending: Default::default(),
+            pending_lines: Default::default(),
+            lines: Default::default(),
+            len: Default::default(),
+            overflow: Default::default(),
+        }
+    }
+
+    /// Move the oldest chunks to another archive, instead of dropping them.
+    #[must_use]
+    pub fn with_overflow(mut self, overflow: Option<Archive>) -> Self {
+        self.overflow = overflow.map(Box::new);
+        self
+    }
+
+    /// Archive receiving the oldest lines once the line limit is exceeded.
+    pub fn overflow_mut(&mut self) -> Option<&mut Archive> {
+        self.overflow.as_deref_mut()
+    }
+
+    /// Number of archived lines.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lines + self.overflow.as_ref().map_or(0, |overflow| overflow.len())
+    }
+
+    /// Check if there are no archived lines.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Memory used by the archive, excluding chunks stored on disk.
+    pub fn memory_usage(&self) -> usize {
+        let backing = match &self.backing {
+            Backing::Memory(data) => data.capacity(),
+            Backing::File { .. } => 0,
+        };
+        let chunks = self.chunks.capacity() * mem::size_of::<Chunk>();
+        let overflow = self.overflow.as_ref().map_or(0, |overflow| overflow.memory_usage());
+        backing + chunks + self.pending.capacity() + overflow
+    }
+
+    /// Update the maximum number of lines stored in this archive.
+    pub fn set_max_lines(&mut self, max_lines: usize) -> io::Result<()> {
+        self.max_lines = max_lines;
+        self.enforce_limit()
+    }
+
+    /// Add a line evicted from the in-memory history.
+    ///
+    /// Hyperlinks are stored by value, so they don't have to be kept in `hyperlinks`.
+    pub fn push(
+        &mut self,
+        row: &Row<Cell>,
+        data: LineData,
+        hyperlinks: &Hyperlinks,
+    ) -> io::Result<()> {
+        encode_row(&mut self.pending, row, data, hyperlinks);
+        self.pending_lines += 1;
+        self.lines += 1;
+
+        if self.pending_lines >= CHUNK_LINES {
+            self.flush_pending()?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove at least `count` of the newest lines, returning them from oldest to newest.
+    ///
+    /// Since lines are stored in chunks, this might return more lines than requested. The
+    /// hyperlinks of the returned lines are added to `hyperlinks`.
+    pub fn pop(
+        &mut self,
+        count: usize,
+        hyperlinks: &mut Hyperlinks,
+    ) -> io::Result<Vec<(Row<Cell>, LineData)>> {
+        let mut rows = Vec::new();
+
+        while rows.len() < count {
+            let buffer = if self.pending_lines > 0 {
+                self.pending_lines = 0;
+                mem::take(&mut self.pending)
+            } else if let Some(chunk) = self.chunks.pop_back() {
+                let mut buffer = vec![0; chunk.size];
+                self.backing.read(chunk.offset, &mut buffer)?;
+                self.len = chunk.offset;
+                buffer
+            } else {
+                break;
+            };
+
+            let mut older = decode_rows(&buffer, hyperlinks)?;
+            self.lines -= older.len();
+            older.append(&mut rows);
+            rows = older;
+        }
+
+        // Continue with the older lines of the overflow archive.
+        if let Some(overflow) = self.overflow.as_mut().filter(|_| rows.len() < count) {
+            let mut older = overflow.pop(count - rows.len(), hyperlinks)?;
+            older.append(&mut rows);
+            rows = older;
+        }
+
+        Ok(rows)
+    }
+
+    /// Visit all archived lines from oldest to newest, without removing them.
+    ///
+    /// Only a single chunk is decoded at a time.
+    pub fn for_each_line(&mut self, f: &mut dyn FnMut(&Row<Cell>, LineData)) -> io::Result<()> {
+        if let Some(overflow) = &mut self.overflow {
+            overflow.for_each_line(f)?;
+        }
+
+        // Hyperlinks are not part of the text, so they're resolved into a throwaway table.
+        let mut hyperlinks = Hyperlinks::default();
+
+        let mut buffer = Vec::new();
+        for chunk in &self.chunks {
+            buffer.resize(chunk.size, 0);
+            self.backing.read(chunk.offset, &mut buffer)?;
+            for (row, data) in decode_rows(&buffer, &mut hyperlinks)? {
+                f(&row, data);
+            }
+            hyperlinks = Hyperlinks::default();
+        }
+
+        for (row, data) in decode_rows(&self.pending, &mut hyperlinks)? {
+            f(&row, data);
+        }
+
+        Ok(())
+    }
+
+    /// Remove all archived lines.
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.chunks.clear();
+        self.pending = Vec::new();
+        self.pending_lines = 0;
+        self.lines = 0;
+        self.len = 0;
+        self.backing.clear()?;
+
+        match &mut self.overflow {
+            Some(overflow) => overflow.clear(),
+            None => Ok(()),
+        }
+    }
+
+    /// Add an encoded chunk as the newest lines.
+    fn push_chunk(&mut self, data: &[u8], lines: usize) -> io::Result<()> {
+        if self.pending_lines > 0 {
+            self.flush_pending()?;
+        }
+
+        self.backing.append(self.len, data)?;
+
+        self.chunks.push_back(Chunk { offset: self.len, size: data.len(), lines });
+        self.len += data.len() as u64;
+        self.lines += lines;
+
+        self.enforce_limit()
+    }
+
+    /// Move all pending lines to the backing storage as a new chunk.
+    fn flush_pending(&mut self) -> io::Result<()> {
+        let pending = mem::take(&mut self.pending);
+        let lines = mem::take(&mut self.pending_lines);
+        self.lines -= lines;
+
+        self.push_chunk(&pending, lines)
+    }
+
+    /// Remove the oldest chunks exceeding the line limit.
+    fn enforce_limit(&mut self) -> io::Result<()> {
+        let mut removed = false;
+        while self.chunks.front().map_or(false, |c| self.lines - c.lines >= self.max_lines) {
+            let chunk = self.chunks.pop_front().unwrap();
+            self.lines -= chunk.lines;
+            removed = true;
+
+            if let Some(overflow) = &mut self.overflow {
+                let mut buffer = vec![0; chunk.size];
+                self.backing.read(chunk.offset, &mut buffer)?;
+                overflow.push_chunk(&buffer, chunk.lines)?;
+            }
+        }
+
+        // Reclaim space once more than half of the storage is unused.
+        let start = self.chunks.front().map_or(self.len, |chunk| chunk.offset);
+        if removed && start > self.len - start {
+            self.backing.compact(start, self.len)?;
+
+            for chunk in &mut self.chunks {
+                chunk.offset -= start;
+            }
+            self.len -= start;
+        }
+
+        Ok(())
+    }
+}
+
+/// Encode a row, merging consecutive identical cells into a single run.
+fn encode_row(buffer: &mut Vec<u8>, row: &Row<Cell>, data: LineData, hyperlinks: &Hyperlinks) {
+    let cells = &row[..];
+    write_varint(buffer, cells.len() as u32);
+    encode_marks(buffer, data.marks);
+    buffer.extend_from_slice(&data.timestamp.to_le_bytes());
+
+    let mut index = 0;
+    while index < cells.len() {
+        let cell = &cells[index];
+        let run = cells[index..].iter().take_while(|other| *other == cell).count();
+        write_varint(buffer, run as u32);
+        encode_cell(buffer, cell, hyperlinks);
+        index += run;
+    }
+}
+
+/// Decode all rows in a buffer.
+fn decode_rows(
+    mut buffer: &[u8],
+    hyperlinks: &mut Hyperlinks,
+) -> io::Result<Vec<(Row<Cell>, LineData)>> {
+    let mut rows = Vec::new();
+    while !buffer.is_empty() {
+        rows.push(decode_row(&mut buffer, hyperlinks)?);
+    }
+    Ok(rows)
+}
+
+fn decode_row(
+    buffer: &mut &[u8],
+    hyperlinks: &mut Hyperlinks,
+) -> io::Result<(Row<Cell>, LineData)> {
+    let columns = read_varint(buffer)? as usize;
+    if columns == 0 {
+        return Err(invalid_data());
+    }
+
+    let mut row = Row::new(columns);
+    let marks = decode_marks(buffer)?;
+
+    let mut timestamp = [0; 8];
+    timestamp.copy_from_slice(read_bytes(buffer, 8)?);
+    let data = LineData { timestamp: u64::from_le_bytes(timestamp), marks };
+
+    let mut column = 0;
+    while column < columns {
+        let run = read_varint(buffer)? as usize;
+        let cell = decode_cell(buffer, hyperlinks)?;
+        if run == 0 || column + run > columns {
+            return Err(invalid_data());
+        }
+
+        for index in column..column + run {
+            row[Column(index)] = cell.clone();
+        }
+        column += run;
+    }
+    row.occ = columns;
+
+    Ok((row, data))
+}
+
+fn encode_marks(buffer: &mut Vec<u8>, marks: LineMarks) {
+    let bits = marks.prompt as u8
+        | (marks.command as u8) << 1
+        | (marks.output as u8) << 2
+        | (marks.command_end as u8) << 3
+        | (marks.exit_code.is_some() as u8) << 4
+        | (marks.bookmark as u8) << 5;
+    buffer.push(bits);
+
+    if let Some(exit_code) = marks.exit_code {
+        buffer.extend_from_slice(&exit_code.to_le_bytes());
+    }
+}
+
+fn decode_marks(buffer: &mut &[u8]) -> io::Result<LineMarks> {
+    let bits = read_u8(buffer)?;
+    let exit_code = if bits & 0b1_0000 != 0 {
+        let bytes = read_bytes(buffer, 4)?;
+        Some(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    } else {
+        None
+    };
+
+    Ok(LineMarks {
+        prompt: bits & 0b1 != 0,
+        command: bits & 0b10 != 0,
+        output: bits & 0b100 != 0,
+        command_end: bits & 0b1000 != 0,
+        bookmark: bits & 0b10_0000 != 0,
+        exit_code,
+    })
+}
+
+fn encode_cell(buffer: &mut Vec<u8>, cell: &Cell, hyperlinks: &Hyperlinks) {
+    write_varint(buffer, cell.c as u32);
+    encode_color(buffer, cell.fg);
+    encode_color(buffer, cell.bg);
+    buffer.extend_from_slice(&cell.flags.bits().to_le_bytes());
+
+    let zerowidth = cell.zerowidth().filter(|zerowidth| !zerowidth.is_empty());
+    let underline_color = cell.underline_color();
+    let hyperlink = cell.hyperlink().and_then(|id| hyperlinks.get(id));
+    let extra = zerowidth.is_some() as u8
+        | (underline_color.is_some() as u8) << 1
+        | (hyperlink.is_some() as u8) << 2;
+    buffer.push(extra);
+
+    if let Some(zerowidth) = zerowidth {
+        write_varint(buffer, zerowidth.len() as u32);
+        for c in zerowidth {
+            write_varint(buffer, *c as u32);
+        }
+    }
+
+    if let Some(color) = underline_color {
+        encode_color(buffer, color);
+    }
+
+    if let Some(hyperlink) = hyperlink {
+        write_str(buffer, hyperlink.id());
+        write_str(buffer, hyperlink.uri());
+    }
+}
+
+fn decode_cell(buffer: &mut &[u8], hyperlinks: &mut Hyperlinks) -> io::Result<Cell> {
+    let mut cell = Cell {
+        c: read_char(buffer)?,
+        fg: decode_color(buffer)?,
+        bg: decode_color(buffer)?,
+        ..Cell::default()
+    };
+
+    let bytes = read_bytes(buffer, 2)?;
+    cell.flags = Flags::from_bits_truncate(u16::from_le_bytes([bytes[0], bytes[1]]));
+
+    let extra = read_u8(buffer)?;
+
+    if extra & 0b1 != 0 {
+        for _ in 0..read_varint(buffer)? {
+            cell.push_zerowidth(read_char(buffer)?);
+        }
+    }
+
+    if extra & 0b10 != 0 {
+        cell.set_underline_color(Some(decode_color(buffer)?));
+    }
+
+    if extra & 0b100 != 0 {
+        let id = read_str(buffer)?;
+        let uri = read_str(buffer)?;
+        // Drop the hyperlink if the table is full, rather than failing to restore the line.
+        cell.set_hyperlink(hyperlinks.intern(Hyperlink::new(Some(id), uri)));
+    }
+
+    Ok(cell)
+}
+
+fn encode_color(buffer: &mut Vec<u8>, color: Color) {
+    match color {
+        Color::Named(named) => {
+            buffer.push(0);
+            write_varint(buffer, named as u32);
+        },
+        Color::Spec(rgb) => buffer.extend_from_slice(&[1, rgb.r, rgb.g, rgb.b]),
+        Color::Indexed(index) => buffer.extend_from_slice(&[2, index]),
+    }
+}
+
+fn decode_color(buffer: &mut &[u8]) -> io::Result<Color> {
+    match read_u8(buffer)? {
+        0 => {
+            let index = read_varint(buffer)?;
+            let named = NAMED_COLORS.iter().find(|named| **named as u32 == index);
+            named.map(|named| Color::Named(*named)).ok_or_else(invalid_data)
+        },
+        1 => {
+            let bytes = read_bytes(buffer, 3)?;
+            Ok(Color::Spec(VteRgb { r: bytes[0], g: bytes[1], b: bytes[2] }))
+        },
+        2 => Ok(Color::Indexed(read_u8(buffer)?)),
+        _ => Err(invalid_data()),
+    }
+}
+
+fn write_varint(buffer: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn read_varint(buffer: &mut &[u8]) -> io::Result<u32> {
+    let mut value = 0u32;
+    for shift in (0..32).step_by(7) {
+        let byte = read_u8(buffer)?;
+        value |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data())
+}
+
+fn write_str(buffer: &mut Vec<u8>, text: &str) {
+    write_varint(buffer, text.len() as u32);
+    buffer.extend_from_slice(text.as_bytes());
+}
+
+fn read_str(buffer: &mut &[u8]) -> io::Result<String> {
+    let len = read_varint(buffer)? as usize;
+    let bytes = read_bytes(buffer, len)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| invalid_data())
+}
+
+fn read_char(buffer: &mut &[u8]) -> io::Result<char> {
+    char::from_u32(read_varint(buffer)?).ok_or_else(invalid_data)
+}
+
+fn read_u8(buffer: &mut &[u8]) -> io::Result<u8> {
+    Ok(read_bytes(buffer, 1)?[0])
+}
+
+fn read_bytes<'a>(buffer: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if buffer.len() < len {
+        return Err(invalid_data());
+    }
+
+    let (bytes, remaining) = buffer.split_at(len);
+    *buffer = remaining;
+    Ok(bytes)
+}
+
+fn invalid_data() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "corrupted scrollback archive")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(text: &str) -> Row<Cell> {
+        let mut row: Row<Cell> = Row::new(10);
+        for (i, c) in text.chars().enumerate() {
+            row[Column(i)].c = c;
+        }
+        row
+    }
+
+    fn text(row: &Row<Cell>) -> String {
+        row[..].iter().map(|cell| cell.c).collect::<String>().trim_end().into()
+    }
+
+    #[test]
+    fn cell_round_trip() {
+        let mut cell = Cell {
+            c: '🦀',
+            fg: Color::Spec(VteRgb { r: 1, g: 2, b: 3 }),
+            bg: Color::Indexed(42),
+            flags: Flags::BOLD | Flags::WIDE_CHAR,
+            ..Cell::default()
+        };
+        cell.push_zerowidth('\u{301}');
+        cell.set_underline_color(Some(Color::Named(NamedColor::DimRed)));
+
+        let mut hyperlinks = Hyperlinks::default();
+        let hyperlink = Hyperlink::new(Some("id"), "https://example.org".into());
+        cell.set_hyperlink(hyperlinks.intern(hyperlink.clone()));
+
+        let mut buffer = Vec::new();
+        encode_cell(&mut buffer, &cell, &hyperlinks);
+
+        // Hyperlinks are restored by value.
+        let mut restored_hyperlinks = Hyperlinks::default();
+        restored_hyperlinks.intern(Hyperlink::new(Some("other"), "https://example.org".into()));
+        let restored = decode_cell(&mut buffer.as_slice(), &mut restored_hyperlinks).unwrap();
+        let restored_hyperlink = restored.hyperlink().and_then(|id| restored_hyperlinks.get(id));
+        assert_eq!(restored_hyperlink, Some(&hyperlink));
+        cell.set_hyperlink(restored.hyperlink());
+        assert_eq!(restored, cell);
+    }
+
+    #[test]
+    fn row_round_trip() {
+        let original = row("hello");
+        let marks =
+            LineMarks { prompt: true, exit_code: Some(-1), bookmark: true, ..Default::default() };
+        let data = LineData { timestamp: 1_700_000_000_000, marks };
+
+        let mut buffer = Vec::new();
+        encode_row(&mut buffer, &original, data, &Hyperlinks::default());
+        let rows = decode_rows(&buffer, &mut Hyperlinks::default()).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].0, original);
+        assert_eq!(rows[0].1, data);
+    }
+
+    #[test]
+    fn push_and_pop() {
+        let mut archive = Archive::on_disk(usize::MAX).unwrap();
+        for i in 0..CHUNK_LINES + 10 {
+            archive
+                .push(&row(&i.to_string()), LineData::default(), &Hyperlinks::default())
+                .unwrap();
+        }
+        assert_eq!(archive.len(), CHUNK_LINES + 10);
+
+        // Pending lines are returned first.
+        let rows = archive.pop(1, &mut Hyperlinks::default()).unwrap();
+        assert_eq!(rows.len(), 10);
+        assert_eq!(text(&rows[0].0), CHUNK_LINES.to_string());
+
+        // Chunks are read back from disk.
+        let rows = archive.pop(1, &mut Hyperlinks::default()).unwrap();
+        assert_eq!(rows.len(), CHUNK_LINES);
+        assert_eq!(text(&rows[0].0), "0");
+        assert_eq!(text(&rows[CHUNK_LINES - 1].0), (CHUNK_LINES - 1).to_string());
+
+        assert!(archive.is_empty());
+        assert!(archive.pop(1, &mut Hyperlinks::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn line_limit() {
+        let mut archive = Archive::on_disk(CHUNK_LINES).unwrap();
+        for i in 0..CHUNK_LINES * 4 {
+            archive
+                .push(&row(&i.to_string()), LineData::default(), &Hyperlinks::default())
+                .unwrap();
+        }
+
+        assert_eq!(archive.len(), CHUNK_LINES);
+        let rows = archive.pop(CHUNK_LINES, &mut Hyperlinks::default()).unwrap();
+        assert_eq!(text(&rows[0].0), (CHUNK_LINES * 3).to_string());
+        assert_eq!(archive.chunks.len(), 0);
+    }
+
+    #[test]
+    fn overflow() {
+        let overflow = Archive::in_memory(usize::MAX);
+        let mut archive = Archive::in_memory(CHUNK_LINES).with_overflow(Some(overflow));
+        for i in 0..CHUNK_LINES * 4 {
+            archive
+                .push(&row(&i.to_string()), LineData::default(), &Hyperlinks::default())
+                .unwrap();
+        }
+
+        assert_eq!(archive.len(), CHUNK_LINES * 4);
+        assert_eq!(archive.overflow_mut().unwrap().len(), CHUNK_LINES * 3);
+
+        // Lines are returned in order across both archives.
+        let rows = archive.pop(usize::MAX, &mut Hyperlinks::default()).unwrap();
+        assert_eq!(rows.len(), CHUNK_LINES * 4);
+        for (i, (row, _)) in rows.iter().enumerate() {
+            assert_eq!(text(row), i.to_string());
+        }
+        assert!(archive.is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+use std::num::NonZeroU16;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
@@ -94,6 +96,96 @@ impl HyperlinkInner {
     }
 }
 
+/// Identifier of a hyperlink in the terminal's [`Hyperlinks`].
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HyperlinkId(NonZeroU16);
+
+impl HyperlinkId {
+    /// Maximum number of hyperlinks which can be referenced at the same time.
+    const MAX: usize = u16::MAX as usize;
+
+    #[inline]
+    fn from_index(index: usize) -> Self {
+        Self(NonZeroU16::new(index as u16 + 1).unwrap())
+    }
+
+    #[inline]
+    fn index(self) -> usize {
+        self.0.get() as usize - 1
+    }
+}
+
+/// Side table for the hyperlinks of all cells in a terminal.
+///
+/// Cells only store a [`HyperlinkId`], so hyperlinks don't require any additional storage per
+/// cell. Identical hyperlinks always share the same ID.
+#[derive(Default, Clone, Debug)]
+pub struct Hyperlinks {
+    links: Vec<Option<Hyperlink>>,
+    ids: HashMap<Hyperlink, HyperlinkId>,
+
+    /// Indices of removed hyperlinks, which can be reused.
+    free: Vec<usize>,
+}
+
+impl Hyperlinks {
+    /// Hyperlink with the specified ID.
+    #[inline]
+    pub fn get(&self, id: HyperlinkId) -> Option<&Hyperlink> {
+        self.links.get(id.index())?.as_ref()
+    }
+
+    /// Get the ID of a hyperlink, adding it to the table if necessary.
+    ///
+    /// Returns `None` if the maximum number of hyperlinks is already in use.
+    pub fn intern(&mut self, hyperlink: Hyperlink) -> Option<HyperlinkId> {
+        if let Some(id) = self.ids.get(&hyperlink) {
+            return Some(*id);
+        }
+
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None if self.links.len() < HyperlinkId::MAX => {
+                self.links.push(None);
+                self.links.len() - 1
+            },
+            None => return None,
+        };
+
+        let id = HyperlinkId::from_index(index);
+        self.links[index] = Some(hyperlink.clone());
+        self.ids.insert(hyperlink, id);
+
+        Some(id)
+    }
+
+    /// Remove all hyperlinks for which `f` returns `false`, allowing reuse of their IDs.
+    pub fn retain<F: FnMut(HyperlinkId) -> bool>(&mut self, mut f: F) {
+        for (index, link) in self.links.iter_mut().enumerate() {
+            if link.is_none() || f(HyperlinkId::from_index(index)) {
+                continue;
+            }
+
+            if let Some(hyperlink) = link.take() {
+                self.ids.remove(&hyperlink);
+                self.free.push(index);
+            }
+        }
+    }
+
+    /// Number of hyperlinks in the table.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Check if the table contains no hyperlinks.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
 /// Trait for determining if a reset should be performed.
 pub trait ResetDiscriminant<T> {
     /// Value based on which equality for the reset will be determined.
@@ -117,13 +209,24 @@ impl ResetDiscriminant<Color> for Cell {
 /// This storage is reserved for cell attributes which are rarely set. This allows reducing the
 /// allocation required ahead of time for every cell, with some additional overhead when the extra
 /// storage is actually required.
+///
+/// New attributes should be added here instead of to [`Cell`], with [`CellExtra::is_empty`]
+/// updated to include them. The storage is shared between cells with identical attributes and
+/// only allocated while at least one attribute is set. Attributes which are shared by many cells,
+/// like hyperlinks, are better stored in a side table, with [`Cell`] only referencing them.
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
 pub struct CellExtra {
     zerowidth: Vec<char>,
 
     underline_color: Option<Color>,
+}
 
-    hyperlink: Option<Hyperlink>,
+impl CellExtra {
+    /// Check if none of the attributes are set.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.zerowidth.is_empty() && self.underline_color.is_none()
+    }
 }
 
 /// Content and attributes of a single cell in the terminal grid.
@@ -133,6 +236,11 @@ pub struct Cell {
     pub fg: Color,
     pub bg: Color,
     pub flags: Flags,
+
+    /// Hyperlink in the terminal's [`Hyperlinks`], which fits into the padding of the cell.
+    #[serde(default)]
+    pub(crate) hyperlink: Option<HyperlinkId>,
+
     pub extra: Option<Arc<CellExtra>>,
 }
 
@@ -144,6 +252,7 @@ impl Default for Cell {
             bg: Color::Named(NamedColor::Background),
             fg: Color::Named(NamedColor::Foreground),
             flags: Flags::empty(),
+            hyperlink: None,
             extra: None,
         }
     }
@@ -159,33 +268,23 @@ impl Cell {
     /// Write a new zerowidth character to this cell.
     #[inline]
     pub fn push_zerowidth(&mut self, character: char) {
-        let extra = self.extra.get_or_insert(Default::default());
-        Arc::make_mut(extra).zerowidth.push(character);
+        self.update_extra(|extra| extra.zerowidth.push(character));
     }
 
     /// Remove all wide char data from a cell.
     #[inline(never)]
     pub fn clear_wide(&mut self) {
         self.flags.remove(Flags::WIDE_CHAR);
-        if let Some(extra) = self.extra.as_mut() {
-            Arc::make_mut(extra).zerowidth = Vec::new();
+        if self.extra.is_some() {
+            self.update_extra(|extra| extra.zerowidth = Vec::new());
         }
         self.c = ' ';
     }
 
     /// Set underline color on the cell.
     pub fn set_underline_color(&mut self, color: Option<Color>) {
-        // If we reset color and we don't have zerowidth we should drop extra storage.
-        if color.is_none()
-            && self
-                .extra
-                .as_ref()
-                .map_or(true, |extra| extra.zerowidth.is_empty() && extra.hyperlink.is_none())
-        {
-            self.extra = None;
-        } else {
-            let extra = self.extra.get_or_insert(Default::default());
-            Arc::make_mut(extra).underline_color = color;
+        if color.is_some() || self.extra.is_some() {
+            self.update_extra(|extra| extra.underline_color = color);
         }
     }
 
@@ -196,24 +295,27 @@ impl Cell {
     }
 
     /// Set hyperlink.
-    pub fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>) {
-        let should_drop = hyperlink.is_none()
-            && self.extra.as_ref().map_or(true, |extra| {
-                extra.zerowidth.is_empty() && extra.underline_color.is_none()
-            });
-
-        if should_drop {
-            self.extra = None;
-        } else {
-            let extra = self.extra.get_or_insert(Default::default());
-            Arc::make_mut(extra).hyperlink = hyperlink;
-        }
+    #[inline]
+    pub fn set_hyperlink(&mut self, hyperlink: Option<HyperlinkId>) {
+        self.hyperlink = hyperlink;
     }
 
-    /// Hyperlink stored in this cell.
+    /// Hyperlink of this cell, which can be resolved using the terminal's [`Hyperlinks`].
     #[inline]
-    pub fn hyperlink(&self) -> Option<Hyperlink> {
-        self.extra.as_ref()?.hyperlink.clone()
+    pub fn hyperlink(&self) -> Option<HyperlinkId> {
+        self.hyperlink
+    }
+
+    /// Modify the extra storage, allocating it if necessary.
+    ///
+    /// The storage is dropped again once none of its attributes are set anymore.
+    fn update_extra<F: FnOnce(&mut CellExtra)>(&mut self, f: F) {
+        let extra = self.extra.get_or_insert_with(Default::default);
+        f(Arc::make_mut(extra));
+
+        if extra.is_empty() {
+            self.extra = None;
+        }
     }
 }
 
@@ -231,7 +333,7 @@ impl GridCell for Cell {
                     | Flags::WIDE_CHAR_SPACER
                     | Flags::LEADING_WIDE_CHAR_SPACER,
             )
-            && self.extra.as_ref().map(|extra| extra.zerowidth.is_empty()) != Some(false)
+            && self.zerowidth().map_or(true, <[char]>::is_empty)
     }
 
     #[inline]
@@ -272,9 +374,7 @@ impl LineLength for grid::Row<Cell> {
         }
 
         for (index, cell) in self[..].iter().rev().enumerate() {
-            if cell.c != ' '
-                || cell.extra.as_ref().map(|extra| extra.zerowidth.is_empty()) == Some(false)
-            {
+            if cell.c != ' ' || cell.zerowidth().map_or(false, |zerowidth| !zerowidth.is_empty()) {
                 length = Column(self.len() - index);
                 break;
             }
@@ -302,6 +402,47 @@ mod tests {
         assert!(mem::size_of::<Cell>() <= EXPECTED_CELL_SIZE);
     }
 
+    #[test]
+    fn extra_storage_is_dropped() {
+        let mut cell = Cell::default();
+        cell.push_zerowidth('\u{301}');
+        cell.set_underline_color(Some(Color::Named(NamedColor::Red)));
+        assert!(cell.extra.is_some());
+
+        cell.clear_wide();
+        assert!(cell.extra.is_some());
+
+        cell.set_underline_color(None);
+        assert_eq!(cell.extra, None);
+
+        // Clearing attributes of a cell without extra storage doesn't allocate it.
+        cell.clear_wide();
+        cell.set_underline_color(None);
+        assert_eq!(cell.extra, None);
+    }
+
+    #[test]
+    fn hyperlinks_are_shared() {
+        let mut hyperlinks = Hyperlinks::default();
+        let foo = Hyperlink::new(Some("foo"), String::from("https://example.org"));
+        let bar = Hyperlink::new(Some("bar"), String::from("https://example.org"));
+
+        let foo_id = hyperlinks.intern(foo.clone()).unwrap();
+        let bar_id = hyperlinks.intern(bar.clone()).unwrap();
+        assert_ne!(foo_id, bar_id);
+        assert_eq!(hyperlinks.intern(foo.clone()), Some(foo_id));
+        assert_eq!(hyperlinks.get(bar_id), Some(&bar));
+
+        // Removed IDs are reused.
+        hyperlinks.retain(|id| id == bar_id);
+        assert_eq!(hyperlinks.get(foo_id), None);
+        assert_eq!(hyperlinks.len(), 1);
+
+        let baz = Hyperlink::new(None::<String>, String::from("https://example.org"));
+        assert_eq!(hyperlinks.intern(baz.clone()), Some(foo_id));
+        assert_eq!(hyperlinks.get(foo_id), Some(&baz));
+    }
+
     #[test]
     fn line_length_works() {
         let mut row = Row::<Cell>::new(10);
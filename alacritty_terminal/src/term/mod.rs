@@ -1,28 +1,36 @@
 //! Exports the `Term` type which is a high-level API for the Grid.
 
-use std::ops::{Index, IndexMut, Range};
+use std::collections::HashSet;
+use std::ops::{Index, IndexMut, Range, RangeInclusive};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::{cmp, mem, ptr, slice, str};
 
 use bitflags::bitflags;
-use log::{debug, trace};
+use log::{debug, trace, warn};
 use unicode_width::UnicodeWidthChar;
 use vte::ansi::{Hyperlink as VteHyperlink, Rgb as VteRgb};
 
 use crate::ansi::{
-    self, Attr, CharsetIndex, Color, CursorShape, CursorStyle, Handler, NamedColor, StandardCharset,
+    self, Attr, CharsetIndex, Color, CursorShape, CursorStyle, ExtendedHandler, Handler,
+    NamedColor, Progress, PromptMark, StandardCharset,
 };
-use crate::config::{Config, Osc52, Terminal};
+use crate::config::{Config, CopyTarget, Osc52, Osc52Read, Scrolling, Terminal};
 use crate::event::{Event, EventListener};
-use crate::grid::{Dimensions, Grid, GridIterator, Scroll};
+use crate::grid::{Dimensions, Grid, GridIterator, Row, Scroll};
 use crate::index::{self, Boundary, Column, Direction, Line, Point, Side};
 use crate::selection::{Selection, SelectionRange, SelectionType};
-use crate::term::cell::{Cell, Flags, LineLength};
+use crate::term::archive::{Archive, CHUNK_LINES};
+use crate::term::cell::{Cell, Flags, Hyperlink, HyperlinkId, Hyperlinks, LineLength};
 use crate::term::color::Colors;
-use crate::vi_mode::{ViModeCursor, ViMotion};
+use crate::term::regis::Regis;
+use crate::vi_mode::{self, ViMarks, ViModeCursor, ViMotion};
 
+mod archive;
 pub mod cell;
 pub mod color;
+pub mod regis;
 pub mod search;
 
 /// Minimum number of columns.
@@ -62,6 +70,10 @@ bitflags! {
         const ALTERNATE_SCROLL    = 0b0000_1000_0000_0000_0000;
         const VI                  = 0b0001_0000_0000_0000_0000;
         const URGENCY_HINTS       = 0b0010_0000_0000_0000_0000;
+        const IN_BAND_RESIZE      = 0b0100_0000_0000_0000_0000;
+        const SGR_PIXELS_MOUSE    = 0b1000_0000_0000_0000_0000;
+        const WIN32_INPUT         = 0b1_0000_0000_0000_0000_0000;
+        const BIDI_EXPLICIT       = 0b10_0000_0000_0000_0000_0000;
         const ANY                 = u32::MAX;
     }
 }
@@ -89,6 +101,18 @@ pub fn viewport_to_point(display_offset: usize, point: Point<usize>) -> Point {
     Point::new(line, point.column)
 }
 
+/// Maximum number of uncompressed history lines, within the history's memory limit.
+fn history_limit(scrolling: Scrolling, columns: usize, screen_lines: usize) -> usize {
+    let history_size = scrolling.history() as usize - scrolling.compressed_history();
+    if scrolling.max_memory == 0 {
+        return history_size;
+    }
+
+    let line_size = columns * mem::size_of::<Cell>();
+    let max_lines = scrolling.max_memory * 1024 * 1024 / line_size.max(1);
+    history_size.min(max_lines.saturating_sub(screen_lines))
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct LineDamageBounds {
     /// Damaged line number.
@@ -250,6 +274,12 @@ pub struct Term<T> {
     /// Cursor for keyboard selection.
     pub vi_mode_cursor: ViModeCursor,
 
+    /// Marks and jump list of the vi mode cursor in the active grid.
+    pub vi_marks: ViMarks,
+
+    /// Vi mode marks of the inactive grid.
+    inactive_vi_marks: ViMarks,
+
     pub selection: Option<Selection>,
 
     /// Currently active grid.
@@ -302,9 +332,39 @@ pub struct Term<T> {
     /// term is set.
     title_stack: Vec<Option<String>>,
 
+    /// Icon name set using OSC 1.
+    icon_name: Option<String>,
+
+    /// Mouse pointer shape requested by the application.
+    mouse_pointer_shape: Option<&'static str>,
+
+    /// Working directory reported by the shell.
+    working_directory: Option<PathBuf>,
+
+    /// Progress of a long running operation.
+    progress: Option<Progress>,
+
+    /// ReGIS graphics drawn on top of the text.
+    regis: Regis,
+
+    /// Time at which the output of the running shell command started.
+    command_start: Option<Instant>,
+
+    /// Duration of the last shell command which finished.
+    last_command_duration: Option<Duration>,
+
     /// Information about damaged cells.
     damage: TermDamageState,
 
+    /// Hyperlinks referenced by the cells of both grids.
+    hyperlinks: Hyperlinks,
+
+    /// Scrollback history exceeding the uncompressed in-memory limit.
+    archive: Option<Archive>,
+
+    /// Scrollback history configuration.
+    scrolling: Scrolling,
+
     /// Config directly for the terminal.
     config: Terminal,
 }
@@ -316,9 +376,22 @@ impl<T> Term<T> {
         T: EventListener,
     {
         let old_display_offset = self.grid.display_offset();
+
+        // Page archived history back in when scrolling beyond the top of the history.
+        let target_offset = match scroll {
+            Scroll::Delta(count) => cmp::max(old_display_offset as i32 + count, 0) as usize,
+            Scroll::PageUp => old_display_offset + self.screen_lines(),
+            // Avoid reading the entire archive, the next jump to the top will page in more.
+            Scroll::Top => self.history_size() + self.screen_lines(),
+            Scroll::PageDown | Scroll::Bottom => 0,
+        };
+        self.restore_history(target_offset.saturating_sub(self.history_size()));
+
         self.grid.scroll_display(scroll);
         self.event_proxy.send_event(Event::MouseCursorDirty);
 
+        self.release_history();
+
         // Clamp vi mode cursor to the viewport.
         let viewport_start = -(self.grid.display_offset() as i32);
         let viewport_end = viewport_start + self.bottommost_line().0;
@@ -336,10 +409,14 @@ impl<T> Term<T> {
         let num_cols = dimensions.columns();
         let num_lines = dimensions.screen_lines();
 
-        let history_size = config.scrolling.history() as usize;
-        let grid = Grid::new(num_lines, num_cols, history_size);
+        let scrolling = config.scrolling;
+        let history_size = history_limit(scrolling, num_cols, num_lines);
+        let mut grid = Grid::new(num_lines, num_cols, history_size);
         let alt = Grid::new(num_lines, num_cols, 0);
 
+        let archive = Self::open_archive(scrolling);
+        grid.set_evict_history(archive.is_some());
+
         let tabs = TabStops::new(grid.columns());
 
         let scroll_region = Line(0)..Line(grid.screen_lines() as i32);
@@ -352,6 +429,8 @@ impl<T> Term<T> {
             inactive_grid: alt,
             active_charset: Default::default(),
             vi_mode_cursor: Default::default(),
+            vi_marks: Default::default(),
+            inactive_vi_marks: Default::default(),
             tabs,
             mode: Default::default(),
             scroll_region,
@@ -364,12 +443,171 @@ impl<T> Term<T> {
             is_focused: true,
             title: None,
             title_stack: Vec::new(),
+            icon_name: None,
+            mouse_pointer_shape: None,
+            working_directory: None,
+            progress: None,
+            regis: Default::default(),
+            command_start: None,
+            last_command_duration: None,
             selection: None,
             damage,
+            hyperlinks: Default::default(),
+            archive,
+            scrolling,
             config: config.terminal.clone(),
         }
     }
 
+    /// Open the scrollback archive, if history compression or storing it on disk is enabled.
+    fn open_archive(scrolling: Scrolling) -> Option<Archive> {
+        let disk_archive = match scrolling.disk_history {
+            0 => None,
+            max_lines => match Archive::on_disk(max_lines) {
+                Ok(archive) => Some(archive),
+                Err(err) => {
+                    warn!("Unable to create scrollback archive: {err}");
+                    None
+                },
+            },
+        };
+
+        match scrolling.compressed_history() {
+            0 => disk_archive,
+            max_lines => Some(Archive::in_memory(max_lines).with_overflow(disk_archive)),
+        }
+    }
+
+    /// Update the scrollback archive limits after a config change.
+    fn update_archive(&mut self, scrolling: Scrolling) {
+        let old_scrolling = mem::replace(&mut self.scrolling, scrolling);
+
+        // Recreate the archive if its layout has changed.
+        let layout = |scrolling: Scrolling| {
+            (scrolling.compressed_history() != 0, scrolling.disk_history != 0)
+        };
+        if layout(old_scrolling) != layout(scrolling) {
+            self.archive = Self::open_archive(scrolling);
+            return;
+        }
+
+        let archive = match &mut self.archive {
+            Some(archive) => archive,
+            None => return,
+        };
+
+        let result = match scrolling.compressed_history() {
+            0 => archive.set_max_lines(scrolling.disk_history),
+            max_lines => {
+                archive.set_max_lines(max_lines).and_then(|_| match archive.overflow_mut() {
+                    Some(disk_archive) => disk_archive.set_max_lines(scrolling.disk_history),
+                    None => Ok(()),
+                })
+            },
+        };
+
+        if let Err(err) = result {
+            warn!("Unable to resize scrollback archive: {err}");
+        }
+    }
+
+    /// Apply the scrollback limits to the history of the primary grid.
+    fn update_history_limit(&mut self) {
+        let history_size = history_limit(self.scrolling, self.columns(), self.screen_lines());
+        let primary_grid = if self.mode.contains(TermMode::ALT_SCREEN) {
+            &mut self.inactive_grid
+        } else {
+            &mut self.grid
+        };
+        primary_grid.set_evict_history(self.archive.is_some());
+        primary_grid.update_history(history_size);
+        self.trim_history();
+    }
+
+    /// Estimated memory used by the scrollback history, in bytes.
+    ///
+    /// This includes the compressed history, unless it's stored on disk.
+    pub fn scrollback_memory(&self) -> usize {
+        let archive = self.archive.as_ref().map_or(0, Archive::memory_usage);
+        self.grid.memory_usage() + self.inactive_grid.memory_usage() + archive
+    }
+
+    /// Number of history lines stored on disk.
+    #[inline]
+    pub fn archived_lines(&self) -> usize {
+        self.archive.as_ref().map_or(0, |archive| archive.len())
+    }
+
+    /// Page at least `count` lines of archived history back into memory.
+    ///
+    /// Returns the number of lines added to the top of the history.
+    pub fn restore_history(&mut self, count: usize) -> usize {
+        let archive = match &mut self.archive {
+            Some(archive)
+                if count > 0
+                    && !archive.is_empty()
+                    && !self.mode.contains(TermMode::ALT_SCREEN) =>
+            {
+                archive
+            },
+            _ => return 0,
+        };
+
+        match archive.pop(count, &mut self.hyperlinks) {
+            Ok(rows) => {
+                let count = rows.len();
+                self.grid.restore_history(rows);
+                count
+            },
+            Err(err) => {
+                warn!("Unable to read scrollback archive: {err}");
+                0
+            },
+        }
+    }
+
+    /// Move paged in history back to the archive, once it's sufficiently far out of view.
+    fn release_history(&mut self) {
+        let display_offset = self.grid.display_offset();
+        if display_offset == 0 || display_offset + CHUNK_LINES <= self.grid.history_limit() {
+            self.trim_history();
+        }
+    }
+
+    /// Move all history exceeding the in-memory limit back to the archive.
+    fn trim_history(&mut self) {
+        self.grid.trim_history();
+        self.inactive_grid.trim_history();
+        self.archive_evicted();
+
+        let topmost_line = self.topmost_line();
+        self.selection = self.selection.take().filter(|s| !s.intersects_range(..topmost_line));
+        self.vi_marks.retain_mut(|point| point.line >= topmost_line);
+    }
+
+    /// Remove all history stored on disk.
+    fn clear_archive(&mut self) {
+        if let Some(Err(err)) = self.archive.as_mut().map(|archive| archive.clear()) {
+            warn!("Unable to clear scrollback archive: {err}");
+        }
+    }
+
+    /// Store lines evicted from the in-memory history in the archive.
+    fn archive_evicted(&mut self) {
+        let rows = self.grid.take_evicted().into_iter().chain(self.inactive_grid.take_evicted());
+        let archive = match &mut self.archive {
+            Some(archive) => archive,
+            None => return,
+        };
+
+        for (row, data) in rows {
+            if let Err(err) = archive.push(&row, data, &self.hyperlinks) {
+                warn!("Unable to write scrollback archive: {err}");
+                break;
+            }
+        }
+    }
+
     #[must_use]
     pub fn damage(&mut self, selection: Option<SelectionRange>) -> TermDamage<'_> {
         // Ensure the entire terminal is damaged after entering insert mode.
@@ -459,11 +697,8 @@ impl<T> Term<T> {
 
         self.event_proxy.send_event(title_event);
 
-        if self.mode.contains(TermMode::ALT_SCREEN) {
-            self.inactive_grid.update_history(config.scrolling.history() as usize);
-        } else {
-            self.grid.update_history(config.scrolling.history() as usize);
-        }
+        self.update_archive(config.scrolling);
+        self.update_history_limit();
 
         self.config = config.terminal.clone();
 
@@ -500,6 +735,19 @@ impl<T> Term<T> {
         Some(res)
     }
 
+    /// Convert the active selection to a String with SGR escape sequences.
+    pub fn selection_to_ansi(&self) -> Option<String> {
+        let selection_range = self.selection.as_ref().and_then(|s| s.to_range(self))?;
+        let SelectionRange { start, end, is_block } = selection_range;
+
+        let mut text = self.bounds_to_ansi(start, end, is_block);
+        if let Some(Selection { ty: SelectionType::Lines, .. }) = self.selection {
+            text.push('\n');
+        }
+
+        Some(text)
+    }
+
     /// Convert range between two points to a String.
     pub fn bounds_to_string(&self, start: Point, end: Point) -> String {
         let mut res = String::new();
@@ -514,6 +762,81 @@ impl<T> Term<T> {
         res.strip_suffix('\n').map(str::to_owned).unwrap_or(res)
     }
 
+    /// Convert the entire scrollback history and screen to a String.
+    ///
+    /// When `ansi` is `true`, text attributes are preserved using SGR escape sequences. Every
+    /// line is prefixed with the time it was written at when a `timestamp` formatter is passed.
+    pub fn history_to_string(
+        &mut self,
+        ansi: bool,
+        timestamp: Option<fn(SystemTime) -> String>,
+    ) -> String {
+        let mut writer = HistoryWriter::new(&self.tabs, ansi, timestamp);
+
+        // Read archived history one chunk at a time, without paging it back into the grid.
+        let alt_screen = self.mode.contains(TermMode::ALT_SCREEN);
+        if let Some(archive) = self.archive.as_mut().filter(|_| !alt_screen) {
+            if let Err(err) = archive.for_each_line(&mut |row, data| writer.push(row, data.time()))
+            {
+                warn!("Unable to read scrollback archive: {err}");
+            }
+        }
+
+        // Omit empty lines at the bottom of the screen.
+        let (topmost_line, mut bottommost_line) = (self.topmost_line(), self.bottommost_line());
+        while bottommost_line > topmost_line && self.grid[bottommost_line].is_clear() {
+            bottommost_line -= 1;
+        }
+
+        for line in (topmost_line.0..=bottommost_line.0).map(Line) {
+            writer.push(&self.grid[line], self.grid.timestamp(line));
+        }
+
+        writer.finish()
+    }
+
+    /// Convert range between two points to a String with SGR escape sequences.
+    ///
+    /// With `is_block`, only the columns between `start` and `end` are included on every line.
+    fn bounds_to_ansi(&self, start: Point, end: Point, is_block: bool) -> String {
+        let mut text = String::new();
+        let mut sgr = String::from("0");
+
+        for line in (start.line.0..=end.line.0).map(Line) {
+            let grid_line = &self.grid[line];
+            let line_length = grid_line.line_length();
+
+            let mut start_col =
+                if is_block || line == start.line { start.column } else { Column(0) };
+            let end_col =
+                if is_block || line == end.line { end.column } else { self.last_column() };
+
+            // Include wide char when trailing spacer is selected.
+            if grid_line[start_col].flags.contains(Flags::WIDE_CHAR_SPACER) {
+                start_col -= 1;
+            }
+
+            let columns = start_col..cmp::min(line_length, end_col + 1);
+            push_ansi_cells(&mut text, &mut sgr, &self.tabs, grid_line, columns);
+
+            if is_block
+                || end_col != self.last_column()
+                || line_length.0 == 0
+                || !grid_line[line_length - 1].flags.contains(Flags::WRAPLINE)
+            {
+                text.push('\n');
+            }
+        }
+
+        // Reset attributes after the last character.
+        text.truncate(text.trim_end_matches('\n').len());
+        if sgr != "0" {
+            text.push_str("\x1b[0m");
+        }
+
+        text
+    }
+
     /// Convert a single line in the grid to a String.
     fn line_to_string(
         &self,
@@ -531,33 +854,7 @@ impl<T> Term<T> {
             cols.start -= 1;
         }
 
-        let mut tab_mode = false;
-        for column in (cols.start.0..line_length.0).map(Column::from) {
-            let cell = &grid_line[column];
-
-            // Skip over cells until next tab-stop once a tab was found.
-            if tab_mode {
-                if self.tabs[column] || cell.c != ' ' {
-                    tab_mode = false;
-                } else {
-                    continue;
-                }
-            }
-
-            if cell.c == '\t' {
-                tab_mode = true;
-            }
-
-            if !cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER) {
-                // Push cells primary character.
-                text.push(cell.c);
-
-                // Push zero-width characters.
-                for c in cell.zerowidth().into_iter().flatten() {
-                    text.push(*c);
-                }
-            }
-        }
+        push_cells(&mut text, &self.tabs, grid_line, cols.start..line_length);
 
         if cols.end >= self.columns() - 1
             && (line_length.0 == 0
@@ -598,13 +895,21 @@ impl<T> Term<T> {
     }
 
     /// Resize terminal to new dimensions.
-    pub fn resize<S: Dimensions>(&mut self, size: S) {
+    pub fn resize<S: Dimensions>(&mut self, size: S)
+    where
+        T: EventListener,
+    {
         let old_cols = self.columns();
         let old_lines = self.screen_lines();
 
         let num_cols = size.columns();
         let num_lines = size.screen_lines();
 
+        // Notify about every resize, since the cell size might have changed.
+        if self.mode.contains(TermMode::IN_BAND_RESIZE) {
+            self.report_in_band_resize();
+        }
+
         if old_cols == num_cols && old_lines == num_lines {
             debug!("Term::resize dimensions unchanged");
             return;
@@ -612,6 +917,9 @@ impl<T> Term<T> {
 
         debug!("New num_cols is {} and num_lines is {}", num_cols, num_lines);
 
+        // Archived lines are not reflowed, so move them out of the way first.
+        self.trim_history();
+
         // Move vi mode cursor with the content.
         let history_size = self.history_size();
         let mut delta = num_lines as i32 - old_lines as i32;
@@ -635,6 +943,21 @@ impl<T> Term<T> {
             self.selection = selection.rotate(self, &range, -delta);
         }
 
+        // Move vi mode marks with the content, dropping them once they're out of bounds.
+        let last_column = self.last_column();
+        let visible_lines = |grid: &Grid<Cell>| grid.topmost_line()..=grid.bottommost_line();
+        let lines = visible_lines(&self.grid);
+        self.vi_marks.retain_mut(|point| {
+            point.line += delta;
+            point.column = cmp::min(point.column, last_column);
+            lines.contains(&point.line)
+        });
+        let lines = visible_lines(&self.inactive_grid);
+        self.inactive_vi_marks.retain_mut(|point| {
+            point.column = cmp::min(point.column, last_column);
+            lines.contains(&point.line)
+        });
+
         // Clamp vi cursor to viewport.
         let vi_point = self.vi_mode_cursor.point;
         let viewport_top = Line(-(self.grid.display_offset() as i32));
@@ -648,6 +971,11 @@ impl<T> Term<T> {
 
         // Resize damage information.
         self.damage.resize(num_cols, num_lines);
+
+        // Keep the history within its memory limit, since the size of its lines changed.
+        if self.scrolling.max_memory != 0 {
+            self.update_history_limit();
+        }
     }
 
     /// Active terminal modes.
@@ -656,6 +984,19 @@ impl<T> Term<T> {
         &self.mode
     }
 
+    /// Send the text area size as in-band resize notification.
+    fn report_in_band_resize(&mut self)
+    where
+        T: EventListener,
+    {
+        self.event_proxy.send_event(Event::TextAreaSizeRequest(Arc::new(|window_size| {
+            let height = window_size.num_lines * window_size.cell_height;
+            let width = window_size.num_cols * window_size.cell_width;
+            let (lines, columns) = (window_size.num_lines, window_size.num_cols);
+            format!("\x1b[48;{lines};{columns};{height};{width}t")
+        })));
+    }
+
     /// Swap primary and alternate screen buffer.
     pub fn swap_alt(&mut self) {
         if !self.mode.contains(TermMode::ALT_SCREEN) {
@@ -667,9 +1008,16 @@ impl<T> Term<T> {
 
             // Reset alternate screen contents.
             self.inactive_grid.reset_region(..);
+            self.inactive_vi_marks = Default::default();
+        }
+
+        // Reset the application's pointer shape when leaving the alternate screen.
+        if self.mode.contains(TermMode::ALT_SCREEN) {
+            self.mouse_pointer_shape = None;
         }
 
         mem::swap(&mut self.grid, &mut self.inactive_grid);
+        mem::swap(&mut self.vi_marks, &mut self.inactive_vi_marks);
         self.mode ^= TermMode::ALT_SCREEN;
         self.selection = None;
         self.mark_fully_damaged();
@@ -698,6 +1046,10 @@ impl<T> Term<T> {
             *line = cmp::min(*line + lines, region.end - 1);
         }
 
+        // Scroll vi mode marks.
+        let topmost_line = self.topmost_line();
+        self.vi_marks.rotate(&region, -(lines as i32), topmost_line);
+
         // Scroll between origin and bottom
         self.grid.scroll_down(&region, lines);
         self.mark_fully_damaged();
@@ -719,6 +1071,7 @@ impl<T> Term<T> {
         self.selection = self.selection.take().and_then(|s| s.rotate(self, &region, lines as i32));
 
         self.grid.scroll_up(&region, lines);
+        self.archive_evicted();
 
         // Scroll vi mode cursor.
         let viewport_top = Line(-(self.grid.display_offset() as i32));
@@ -727,6 +1080,10 @@ impl<T> Term<T> {
         if (top <= *line) && region.end > *line {
             *line = cmp::max(*line - lines, top);
         }
+
+        // Scroll vi mode marks.
+        let topmost_line = self.topmost_line();
+        self.vi_marks.rotate(&region, lines as i32, topmost_line);
         self.mark_fully_damaged();
     }
 
@@ -787,8 +1144,16 @@ impl<T> Term<T> {
         }
 
         // Move cursor.
+        let point = self.vi_mode_cursor.point;
         self.vi_mode_cursor = self.vi_mode_cursor.motion(self, motion);
         self.vi_mode_recompute_selection();
+
+        // Remember the previous position for larger jumps, like in vi.
+        let is_jump =
+            matches!(motion, ViMotion::High | ViMotion::Middle | ViMotion::Low | ViMotion::Bracket);
+        if is_jump && point != self.vi_mode_cursor.point {
+            self.vi_marks.push_jump(point);
+        }
     }
 
     /// Move vi cursor to a point in the grid.
@@ -806,6 +1171,51 @@ impl<T> Term<T> {
         self.vi_mode_recompute_selection();
     }
 
+    /// Move vi cursor to a point in the grid, remembering the previous position in the jump list.
+    #[inline]
+    pub fn vi_jump_to_point(&mut self, point: Point)
+    where
+        T: EventListener,
+    {
+        self.vi_marks.push_jump(self.vi_mode_cursor.point);
+        self.vi_goto_point(point);
+    }
+
+    /// Move vi cursor to a mark.
+    ///
+    /// Jumps to the first occupied cell in the mark's line when `line` is `true`.
+    pub fn vi_goto_mark(&mut self, name: char, line: bool)
+    where
+        T: EventListener,
+    {
+        let mut point = match self.vi_marks.get(name) {
+            Some(point) => point,
+            None => return,
+        };
+
+        if line {
+            let occupied = vi_mode::first_occupied_in_line(self, point.line);
+            point.column = occupied.map_or(Column(0), |occupied| occupied.column);
+        }
+
+        self.vi_jump_to_point(point);
+    }
+
+    /// Move vi cursor to the previous or next position in the jump list.
+    pub fn vi_jump(&mut self, direction: Direction)
+    where
+        T: EventListener,
+    {
+        let point = match direction {
+            Direction::Left => self.vi_marks.jump_older(self.vi_mode_cursor.point),
+            Direction::Right => self.vi_marks.jump_newer(),
+        };
+
+        if let Some(point) = point {
+            self.vi_goto_point(point);
+        }
+    }
+
     /// Update the active selection to match the vi mode cursor position.
     #[inline]
     fn vi_mode_recompute_selection(&mut self) {
@@ -889,6 +1299,243 @@ impl<T> Term<T> {
         &self.colors
     }
 
+    /// Hyperlinks referenced by the terminal's cells.
+    #[inline]
+    pub fn hyperlinks(&self) -> &Hyperlinks {
+        &self.hyperlinks
+    }
+
+    /// Hyperlink of the cell at `point`.
+    #[inline]
+    pub fn hyperlink_at(&self, point: Point) -> Option<Hyperlink> {
+        let id = self.grid[point].hyperlink()?;
+        self.hyperlinks.get(id).cloned()
+    }
+
+    /// Get the ID of a hyperlink, removing unused hyperlinks once all IDs are taken.
+    fn intern_hyperlink(&mut self, hyperlink: Hyperlink) -> Option<HyperlinkId> {
+        if let Some(id) = self.hyperlinks.intern(hyperlink.clone()) {
+            return Some(id);
+        }
+
+        // Archive evicted lines first, since they could still reference removed hyperlinks.
+        self.archive_evicted();
+
+        let mut used = HashSet::new();
+        for grid in [&self.grid, &self.inactive_grid] {
+            let templates = [&grid.cursor.template, &grid.saved_cursor.template];
+            let lines = (grid.topmost_line().0..grid.screen_lines() as i32).map(Line);
+            let cells = lines.flat_map(|line| grid[line][..].iter());
+            used.extend(templates.into_iter().chain(cells).filter_map(Cell::hyperlink));
+        }
+        self.hyperlinks.retain(|id| used.contains(&id));
+
+        self.hyperlinks.intern(hyperlink)
+    }
+
+    /// Mouse pointer shape requested by the application.
+    ///
+    /// This is always a CSS cursor name, like `pointer` or `ew-resize`.
+    #[inline]
+    pub fn mouse_pointer_shape(&self) -> Option<&'static str> {
+        self.mouse_pointer_shape
+    }
+
+    /// Progress of a long running operation reported using OSC 9;4.
+    #[inline]
+    pub fn progress(&self) -> Option<Progress> {
+        self.progress
+    }
+
+    /// ReGIS graphics drawn on top of the text.
+    #[inline]
+    pub fn regis(&self) -> &Regis {
+        &self.regis
+    }
+
+    /// Working directory reported by the shell using OSC 7.
+    #[inline]
+    pub fn working_directory(&self) -> Option<&Path> {
+        self.working_directory.as_deref()
+    }
+
+    /// Output of the last `count` finished commands, starting with the most recent one.
+    ///
+    /// This requires the shell to report OSC 133 prompt marks. Trailing empty lines are not
+    /// included in the output and commands without any output are skipped.
+    pub fn command_outputs(&self, count: usize) -> Vec<RangeInclusive<Point>> {
+        let mut outputs = Vec::new();
+        let mut next_prompt = None;
+
+        let mut line = self.bottommost_line();
+        while line >= self.topmost_line() && outputs.len() < count {
+            let marks = self.grid.marks(line);
+
+            // The output ends right before the next prompt.
+            if marks.prompt || marks.command_end {
+                next_prompt = Some(line);
+            }
+
+            if let Some(prompt) = next_prompt.filter(|_| marks.output) {
+                let mut end: Line = prompt - 1;
+                while end >= line && self.grid[end].is_clear() {
+                    end -= 1;
+                }
+
+                if end >= line {
+                    let start = Point::new(line, Column(0));
+                    outputs.push(start..=Point::new(end, self.last_column()));
+                }
+
+                next_prompt = None;
+            }
+
+            line -= 1;
+        }
+
+        outputs
+    }
+
+    /// Time between the start of the last finished command's output and its end.
+    ///
+    /// This requires the shell to report the OSC 133 output start and command end marks.
+    pub fn last_command_duration(&self) -> Option<Duration> {
+        self.last_command_duration
+    }
+
+    /// Finished commands overlapping `lines`, starting with the most recent one.
+    ///
+    /// Each command spans from its prompt to the line before the one it finished on, along with
+    /// its exit code. This requires the shell to report OSC 133 prompt marks.
+    pub fn finished_commands(
+        &self,
+        lines: RangeInclusive<Line>,
+    ) -> Vec<(RangeInclusive<Line>, Option<i32>)> {
+        let mut commands = Vec::new();
+
+        // Start below the range, to include commands which finished after its last line.
+        let mut line = *lines.end();
+        while line < self.bottommost_line() && !self.grid.marks(line).command_end {
+            line += 1;
+        }
+
+        let mut command_end: Option<(Line, Option<i32>)> = None;
+        while line >= self.topmost_line() {
+            let marks = self.grid.marks(line);
+
+            if let Some((end, exit_code)) = command_end.filter(|_| marks.prompt) {
+                commands.push((line..=end - 1, exit_code));
+                command_end = None;
+            }
+
+            if marks.command_end {
+                // Commands finishing before the range's start cannot overlap with it.
+                if line <= *lines.start() {
+                    break;
+                }
+
+                command_end = Some((line, marks.exit_code));
+            }
+
+            if command_end.is_none() && line < *lines.start() {
+                break;
+            }
+
+            line -= 1;
+        }
+
+        commands
+    }
+
+    /// Line of the closest prompt above or below `line`, excluding `line` itself.
+    ///
+    /// This requires the shell to report OSC 133 prompt marks.
+    pub fn prompt_line(&self, mut line: Line, direction: Direction) -> Option<Line> {
+        loop {
+            line = match direction {
+                Direction::Left => line - 1,
+                Direction::Right => line + 1,
+            };
+
+            if line < self.topmost_line() || line > self.bottommost_line() {
+                return None;
+            }
+
+            if self.grid.marks(line).prompt {
+                return Some(line);
+            }
+        }
+    }
+
+    /// Remove all lines above `line`, including the entire scrollback history.
+    ///
+    /// When `line` is on the screen, it is moved to the top of the screen along with all lines
+    /// below it.
+    pub fn clear_above(&mut self, line: Line) {
+        // Applications in the alternate screen expect their content to stay in place.
+        if self.mode.contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+
+        let line = cmp::max(line, self.topmost_line());
+        let shift = cmp::max(line.0, 0);
+        if shift > 0 {
+            let region = Line(0)..Line(self.screen_lines() as i32);
+            self.grid.scroll_up(&region, shift as usize);
+            self.grid.clear_history();
+
+            let cursor = &mut self.grid.cursor.point;
+            cursor.line = cmp::max(cursor.line - shift, Line(0));
+            let saved_cursor = &mut self.grid.saved_cursor.point;
+            saved_cursor.line = cmp::max(saved_cursor.line - shift, Line(0));
+        } else {
+            self.grid.truncate_history((line - self.topmost_line()).0 as usize);
+        }
+        self.clear_archive();
+
+        let topmost_line = line - shift;
+        self.vi_marks.retain_mut(|point| {
+            point.line -= shift;
+            point.line >= topmost_line
+        });
+        self.vi_mode_cursor.point.line =
+            (self.vi_mode_cursor.point.line - shift).grid_clamp(self, Boundary::Grid);
+        self.selection = None;
+
+        self.mark_fully_damaged();
+    }
+
+    /// Add or remove the bookmark on `line`.
+    pub fn toggle_bookmark(&mut self, line: Line) {
+        let marks = self.grid.marks_mut(line);
+        marks.bookmark = !marks.bookmark;
+    }
+
+    /// Line of the closest bookmark above or below `line`, excluding `line` itself.
+    pub fn bookmark_line(&self, mut line: Line, direction: Direction) -> Option<Line> {
+        loop {
+            line = match direction {
+                Direction::Left => line - 1,
+                Direction::Right => line + 1,
+            };
+
+            if line < self.topmost_line() || line > self.bottommost_line() {
+                return None;
+            }
+
+            if self.grid.marks(line).bookmark {
+                return Some(line);
+            }
+        }
+    }
+
+    /// Bookmarked lines within `lines`.
+    pub fn bookmarks(&self, lines: RangeInclusive<Line>) -> Vec<Line> {
+        let start = cmp::max(*lines.start(), self.topmost_line());
+        let end = cmp::min(*lines.end(), self.bottommost_line());
+        (start.0..=end.0).map(Line).filter(|line| self.grid.marks(*line).bookmark).collect()
+    }
+
     /// Insert a linebreak at the current cursor position.
     #[inline]
     fn wrapline(&mut self)
@@ -922,8 +1569,13 @@ impl<T> Term<T> {
         let fg = self.grid.cursor.template.fg;
         let bg = self.grid.cursor.template.bg;
         let flags = self.grid.cursor.template.flags;
+        let hyperlink = self.grid.cursor.template.hyperlink;
         let extra = self.grid.cursor.template.extra.clone();
 
+        // Remember when the line was first written to.
+        let line = self.grid.cursor.point.line;
+        self.grid.touch(line);
+
         let mut cursor_cell = self.grid.cursor_cell();
 
         // Clear all related cells when overwriting a fullwidth cell.
@@ -950,6 +1602,7 @@ impl<T> Term<T> {
         cursor_cell.fg = fg;
         cursor_cell.bg = bg;
         cursor_cell.flags = flags;
+        cursor_cell.hyperlink = hyperlink;
         cursor_cell.extra = extra;
     }
 
@@ -1190,6 +1843,11 @@ impl<T: EventListener> Handler for Term<T> {
                 let text = format!("\x1b[>0;{version};1c");
                 self.event_proxy.send_event(Event::PtyWrite(text));
             },
+            Some('=') => {
+                trace!("Reporting tertiary device attributes");
+                let text = String::from("\x1bP!|00000000\x1b\\");
+                self.event_proxy.send_event(Event::PtyWrite(text));
+            },
             _ => debug!("Unsupported device attributes intermediate"),
         }
     }
@@ -1556,17 +2214,36 @@ impl<T: EventListener> Handler for Term<T> {
             _ => return,
         };
 
-        if let Ok(bytes) = base64::decode(base64) {
-            if let Ok(text) = String::from_utf8(bytes) {
-                self.event_proxy.send_event(Event::ClipboardStore(clipboard_type, text));
-            }
+        // Reject oversized payloads before decoding them.
+        if base64.len() / 4 * 3 > self.config.osc52_max_size.saturating_add(2) {
+            debug!("Denied osc52 store of {} base64 bytes", base64.len());
+            return;
+        }
+
+        let text = match base64::decode(base64).map(String::from_utf8) {
+            Ok(Ok(text)) if text.len() <= self.config.osc52_max_size => text,
+            _ => return,
+        };
+
+        // Store in the configured clipboards instead of the requested one.
+        let targets = match self.config.osc52_copy_target {
+            Some(CopyTarget::Selection) => vec![ClipboardType::Selection],
+            Some(CopyTarget::Clipboard) => vec![ClipboardType::Clipboard],
+            Some(CopyTarget::Both) => vec![ClipboardType::Clipboard, ClipboardType::Selection],
+            None => vec![clipboard_type],
+        };
+
+        for target in targets {
+            self.event_proxy.send_event(Event::ClipboardStore(target, text.clone()));
         }
     }
 
     /// Load data from clipboard.
     #[inline]
     fn clipboard_load(&mut self, clipboard: u8, terminator: &str) {
-        if !matches!(self.config.osc52, Osc52::OnlyPaste | Osc52::CopyPaste) {
+        if !matches!(self.config.osc52, Osc52::OnlyPaste | Osc52::CopyPaste)
+            || self.config.osc52_read == Osc52Read::Deny
+        {
             debug!("Denied osc52 load");
             return;
         }
@@ -1578,10 +2255,13 @@ impl<T: EventListener> Handler for Term<T> {
         };
 
         let terminator = terminator.to_owned();
+        let max_size = self.config.osc52_max_size;
 
         self.event_proxy.send_event(Event::ClipboardLoad(
             clipboard_type,
             Arc::new(move |text| {
+                // Reply with an empty clipboard when its content is too big.
+                let text = if text.len() <= max_size { text } else { "" };
                 let base64 = base64::encode(text);
                 format!("\x1b]52;{};{}{}", clipboard as char, base64, terminator)
             }),
@@ -1644,13 +2324,16 @@ impl<T: EventListener> Handler for Term<T> {
 
                 self.selection = None;
             },
-            ansi::ClearMode::Saved if self.history_size() > 0 => {
+            ansi::ClearMode::Saved if self.history_size() > 0 || self.archived_lines() > 0 => {
                 self.grid.clear_history();
+                self.clear_archive();
 
                 self.vi_mode_cursor.point.line =
                     self.vi_mode_cursor.point.line.grid_clamp(self, Boundary::Cursor);
 
                 self.selection = self.selection.take().filter(|s| !s.intersects_range(..Line(0)));
+
+                self.vi_marks.retain_mut(|point| point.line >= Line(0));
             },
             // We have no history to clear.
             ansi::ClearMode::Saved => (),
@@ -1682,18 +2365,26 @@ impl<T: EventListener> Handler for Term<T> {
         self.cursor_style = None;
         self.grid.reset();
         self.inactive_grid.reset();
+        self.clear_archive();
         self.scroll_region = Line(0)..Line(self.screen_lines() as i32);
         self.tabs = TabStops::new(self.columns());
         self.title_stack = Vec::new();
         self.title = None;
+        self.icon_name = None;
+        self.mouse_pointer_shape = None;
+        self.set_progress(None);
+        self.regis = Default::default();
         self.selection = None;
         self.vi_mode_cursor = Default::default();
+        self.vi_marks = Default::default();
+        self.inactive_vi_marks = Default::default();
 
         // Preserve vi mode across resets.
         self.mode &= TermMode::VI;
         self.mode.insert(TermMode::default());
 
         self.event_proxy.send_event(Event::CursorBlinkingChange);
+        self.event_proxy.send_event(Event::MouseCursorDirty);
         self.mark_fully_damaged();
     }
 
@@ -1713,7 +2404,8 @@ impl<T: EventListener> Handler for Term<T> {
     #[inline]
     fn set_hyperlink(&mut self, hyperlink: Option<VteHyperlink>) {
         trace!("Setting hyperlink: {:?}", hyperlink);
-        self.grid.cursor.template.set_hyperlink(hyperlink.map(|e| e.into()));
+        let id = hyperlink.and_then(|hyperlink| self.intern_hyperlink(hyperlink.into()));
+        self.grid.cursor.template.set_hyperlink(id);
     }
 
     /// Set a terminal attribute.
@@ -1802,11 +2494,11 @@ impl<T: EventListener> Handler for Term<T> {
             ansi::Mode::BracketedPaste => self.mode.insert(TermMode::BRACKETED_PASTE),
             // Mouse encodings are mutually exclusive.
             ansi::Mode::SgrMouse => {
-                self.mode.remove(TermMode::UTF8_MOUSE);
+                self.mode.remove(TermMode::UTF8_MOUSE | TermMode::SGR_PIXELS_MOUSE);
                 self.mode.insert(TermMode::SGR_MOUSE);
             },
             ansi::Mode::Utf8Mouse => {
-                self.mode.remove(TermMode::SGR_MOUSE);
+                self.mode.remove(TermMode::SGR_MOUSE | TermMode::SGR_PIXELS_MOUSE);
                 self.mode.insert(TermMode::UTF8_MOUSE);
             },
             ansi::Mode::AlternateScroll => self.mode.insert(TermMode::ALTERNATE_SCROLL),
@@ -1831,6 +2523,7 @@ impl<T: EventListener> Handler for Term<T> {
             ansi::Mode::SwapScreenAndSetRestoreCursor => {
                 if self.mode.contains(TermMode::ALT_SCREEN) {
                     self.swap_alt();
+                    self.event_proxy.send_event(Event::MouseCursorDirty);
                 }
             },
             ansi::Mode::ShowCursor => self.mode.remove(TermMode::SHOW_CURSOR),
@@ -1918,77 +2611,521 @@ impl<T: EventListener> Handler for Term<T> {
     }
 
     #[inline]
-    fn set_cursor_style(&mut self, style: Option<CursorStyle>) {
-        trace!("Setting cursor style {:?}", style);
-        self.cursor_style = style;
-
-        // Notify UI about blinking changes.
-        self.event_proxy.send_event(Event::CursorBlinkingChange);
+    fn set_cursor_style(&mut self, style: Option<CursorStyle>) {
+        trace!("Setting cursor style {:?}", style);
+        self.cursor_style = style;
+
+        // Notify UI about blinking changes.
+        self.event_proxy.send_event(Event::CursorBlinkingChange);
+    }
+
+    #[inline]
+    fn set_cursor_shape(&mut self, shape: CursorShape) {
+        trace!("Setting cursor shape {:?}", shape);
+
+        let style = self.cursor_style.get_or_insert(self.default_cursor_style);
+        style.shape = shape;
+    }
+
+    #[inline]
+    fn set_title(&mut self, title: Option<String>) {
+        trace!("Setting title to '{:?}'", title);
+
+        self.title = title.clone();
+
+        let title_event = match title {
+            Some(title) => Event::Title(title),
+            None => Event::ResetTitle,
+        };
+
+        self.event_proxy.send_event(title_event);
+    }
+
+    #[inline]
+    fn push_title(&mut self) {
+        trace!("Pushing '{:?}' onto title stack", self.title);
+
+        if self.title_stack.len() >= TITLE_STACK_MAX_DEPTH {
+            let removed = self.title_stack.remove(0);
+            trace!(
+                "Removing '{:?}' from bottom of title stack that exceeds its maximum depth",
+                removed
+            );
+        }
+
+        self.title_stack.push(self.title.clone());
+    }
+
+    #[inline]
+    fn pop_title(&mut self) {
+        trace!("Attempting to pop title from stack...");
+
+        if let Some(popped) = self.title_stack.pop() {
+            trace!("Title '{:?}' popped from stack", popped);
+            self.set_title(popped);
+        }
+    }
+
+    #[inline]
+    fn text_area_size_pixels(&mut self) {
+        self.event_proxy.send_event(Event::TextAreaSizeRequest(Arc::new(move |window_size| {
+            let height = window_size.num_lines * window_size.cell_height;
+            let width = window_size.num_cols * window_size.cell_width;
+            format!("\x1b[4;{height};{width}t")
+        })));
+    }
+
+    #[inline]
+    fn text_area_size_chars(&mut self) {
+        let text = format!("\x1b[8;{};{}t", self.screen_lines(), self.columns());
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+}
+
+impl<T: EventListener> ExtendedHandler for Term<T> {
+    #[inline]
+    fn set_mouse_pointer_shape(&mut self, shape: Option<&str>) {
+        trace!("Setting mouse pointer shape to {:?}", shape);
+
+        // Pick the first allowed shape from the comma-separated fallback list.
+        let shape = shape.and_then(|shapes| {
+            shapes.split(',').filter_map(pointer_shape_name).find(|name| {
+                self.config.pointer_shapes.iter().any(|allowed| allowed.eq_ignore_ascii_case(name))
+            })
+        });
+
+        if shape != self.mouse_pointer_shape {
+            self.mouse_pointer_shape = shape;
+            self.event_proxy.send_event(Event::MouseCursorDirty);
+        }
+    }
+
+    #[inline]
+    fn set_icon_name(&mut self, name: &str) {
+        trace!("Setting icon name to {:?}", name);
+        self.icon_name = Some(name.to_owned());
+        self.event_proxy.send_event(Event::Icon(name.to_owned()));
+    }
+
+    #[inline]
+    fn set_progress(&mut self, progress: Option<Progress>) {
+        trace!("Setting progress to {:?}", progress);
+
+        if progress != self.progress {
+            self.progress = progress;
+            self.event_proxy.send_event(Event::ProgressChange(progress));
+        }
+    }
+
+    #[inline]
+    fn request_capability(&mut self, name: &str) {
+        trace!("Reporting terminfo capability {:?}", name);
+
+        let hex = |text: &str| text.bytes().map(|byte| format!("{byte:02X}")).collect::<String>();
+        let text = match TERMINFO_CAPABILITIES.iter().find(|(capability, _)| *capability == name) {
+            Some((_, Some(value))) => format!("\x1bP1+r{}={}\x1b\\", hex(name), hex(value)),
+            Some((_, None)) => format!("\x1bP1+r{}\x1b\\", hex(name)),
+            None => format!("\x1bP0+r{}\x1b\\", hex(name)),
+        };
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
+    #[inline]
+    fn request_setting(&mut self, setting: &str) {
+        trace!("Reporting state of control function {:?}", setting);
+
+        let report = match setting {
+            "m" => Some(format!("{}m", sgr_report(&self.grid.cursor.template))),
+            "r" => Some(format!("{};{}r", self.scroll_region.start + 1, self.scroll_region.end)),
+            " q" => {
+                let style = self.cursor_style.unwrap_or(self.default_cursor_style);
+                let shape = match style.shape {
+                    CursorShape::Underline => 3,
+                    CursorShape::Beam => 5,
+                    _ => 1,
+                };
+                Some(format!("{} q", shape + u8::from(!style.blinking)))
+            },
+            _ => None,
+        };
+
+        let text = match report {
+            Some(report) => format!("\x1bP1$r{report}\x1b\\"),
+            None => String::from("\x1bP0$r\x1b\\"),
+        };
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
+    #[inline]
+    fn set_extended_mode(&mut self, mode: u16, enabled: bool) {
+        match mode {
+            IN_BAND_RESIZE_MODE => {
+                trace!("Setting in-band resize notifications to {}", enabled);
+                self.mode.set(TermMode::IN_BAND_RESIZE, enabled);
+
+                // Report the current size right away, so applications don't have to query it.
+                if enabled {
+                    self.report_in_band_resize();
+                }
+            },
+            SGR_PIXELS_MOUSE_MODE => {
+                trace!("Setting SGR pixel mouse reports to {}", enabled);
+
+                // Mouse encodings are mutually exclusive.
+                if enabled {
+                    self.mode.remove(TermMode::SGR_MOUSE | TermMode::UTF8_MOUSE);
+                }
+                self.mode.set(TermMode::SGR_PIXELS_MOUSE, enabled);
+            },
+            WIN32_INPUT_MODE => {
+                trace!("Setting win32-input-mode to {}", enabled);
+                self.mode.set(TermMode::WIN32_INPUT, enabled);
+            },
+            _ => (),
+        }
+    }
+
+    #[inline]
+    fn set_ansi_mode(&mut self, mode: u16, enabled: bool) {
+        if mode == BIDI_MODE {
+            trace!("Setting explicit bidirectional text mode to {}", enabled);
+            self.mode.set(TermMode::BIDI_EXPLICIT, enabled);
+            self.mark_fully_damaged();
+        }
+    }
+
+    #[inline]
+    fn report_extended_mode(&mut self, mode: u16) {
+        let flag = match mode {
+            IN_BAND_RESIZE_MODE => TermMode::IN_BAND_RESIZE,
+            SGR_PIXELS_MOUSE_MODE => TermMode::SGR_PIXELS_MOUSE,
+            WIN32_INPUT_MODE => TermMode::WIN32_INPUT,
+            _ => return,
+        };
+
+        let state = if self.mode.contains(flag) { 1 } else { 2 };
+        let text = format!("\x1b[?{mode};{state}$y");
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
+    #[inline]
+    fn answerback(&mut self) {
+        trace!("Sending answerback message");
+
+        if !self.config.answerback.is_empty() {
+            let text = self.config.answerback.clone();
+            self.event_proxy.send_event(Event::PtyWrite(text));
+        }
+    }
+
+    #[inline]
+    fn regis(&mut self, commands: &str) {
+        trace!("Executing ReGIS commands {:?}", commands);
+
+        self.regis.execute(commands);
+
+        // Lines are not tied to the grid, so all of them have to be redrawn.
+        self.mark_fully_damaged();
+    }
+
+    #[inline]
+    fn report_title(&mut self, icon: bool) {
+        if !self.config.report_title {
+            debug!("Denied title report");
+            return;
+        }
+
+        // OSC 0 sets the title and icon name at once, so use the title until OSC 1 was received.
+        let title = match &self.icon_name {
+            Some(icon_name) if icon => icon_name.as_str(),
+            _ => self.title.as_deref().unwrap_or_default(),
+        };
+
+        trace!("Reporting title {:?}", title);
+
+        // Strip control characters, to prevent injecting escapes into the reply.
+        let title: String = title.chars().filter(|c| !c.is_control()).collect();
+        let kind = if icon { 'L' } else { 'l' };
+        let text = format!("\x1b]{kind}{title}\x1b\\");
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
+    #[inline]
+    fn set_working_directory(&mut self, path: Option<PathBuf>) {
+        trace!("Setting working directory to {:?}", path);
+        self.working_directory = path;
     }
 
     #[inline]
-    fn set_cursor_shape(&mut self, shape: CursorShape) {
-        trace!("Setting cursor shape {:?}", shape);
+    fn prompt_mark(&mut self, mark: PromptMark) {
+        trace!("Setting prompt mark {:?}", mark);
+
+        let line = self.grid.cursor.point.line;
+        let marks = self.grid.marks_mut(line);
+        match mark {
+            PromptMark::PromptStart => {
+                marks.prompt = true;
+                self.command_start = None;
+                self.event_proxy.send_event(Event::PromptStart);
+            },
+            PromptMark::CommandStart => marks.command = true,
+            PromptMark::OutputStart => {
+                marks.output = true;
+                self.command_start = Some(Instant::now());
+            },
+            PromptMark::CommandEnd(exit_code) => {
+                marks.command_end = true;
+                marks.exit_code = exit_code;
+                self.last_command_duration = self.command_start.take().map(|start| start.elapsed());
+                self.event_proxy.send_event(Event::CommandFinished(exit_code));
+            },
+        }
+    }
+}
 
-        let style = self.cursor_style.get_or_insert(self.default_cursor_style);
-        style.shape = shape;
+/// ANSI mode for bidirectional text (BDSM), reordered by the terminal unless it's set.
+const BIDI_MODE: u16 = 8;
+
+/// Private mode for in-band resize notifications.
+const IN_BAND_RESIZE_MODE: u16 = 2048;
+
+/// Private mode for SGR mouse reports with pixel coordinates.
+const SGR_PIXELS_MOUSE_MODE: u16 = 1016;
+
+/// Private mode for reporting key events as Win32 `INPUT_RECORD`s, used by ConPTY.
+const WIN32_INPUT_MODE: u16 = 9001;
+
+/// Terminfo capabilities reported through XTGETTCAP, booleans have no value.
+const TERMINFO_CAPABILITIES: &[(&str, Option<&str>)] = &[
+    ("TN", Some("alacritty")),
+    ("name", Some("alacritty")),
+    ("Co", Some("256")),
+    ("colors", Some("256")),
+    ("RGB", Some("8/8/8")),
+    ("Tc", None),
+    ("Smulx", Some("\x1b[4:%p1%dm")),
+    ("Setulc", Some("\x1b[58:2::%p1%{65536}%/%d:%p1%{256}%/%{255}%&%d:%p1%{255}%&%d%;m")),
+    ("Ss", Some("\x1b[%p1%d q")),
+    ("Se", Some("\x1b[0 q")),
+    ("Ms", Some("\x1b]52;%p1%s;%p2%s\x07")),
+    ("Sync", Some("\x1b[?2026%?%p1%{1}%-%tl%eh%;")),
+];
+
+/// Conversion of full lines to text, for lines from the grid and the archive alike.
+struct HistoryWriter<'a> {
+    tabs: &'a TabStops,
+    ansi: bool,
+    timestamp: Option<fn(SystemTime) -> String>,
+
+    /// Prefix for logical lines without a timestamp.
+    padding: String,
+
+    text: String,
+    sgr: String,
+    wrapped: bool,
+}
+
+impl<'a> HistoryWriter<'a> {
+    fn new(tabs: &'a TabStops, ansi: bool, timestamp: Option<fn(SystemTime) -> String>) -> Self {
+        let padding = timestamp.map(|timestamp| " ".repeat(timestamp(UNIX_EPOCH).chars().count()));
+        Self {
+            padding: padding.unwrap_or_default(),
+            sgr: String::from("0"),
+            text: String::new(),
+            wrapped: false,
+            timestamp,
+            tabs,
+            ansi,
+        }
     }
 
-    #[inline]
-    fn set_title(&mut self, title: Option<String>) {
-        trace!("Setting title to '{:?}'", title);
+    /// Append the line following the previously written one.
+    fn push(&mut self, row: &Row<Cell>, time: Option<SystemTime>) {
+        // Prefix logical lines without any text attributes.
+        if let Some(timestamp) = self.timestamp.filter(|_| !self.wrapped) {
+            if self.sgr != "0" {
+                self.text.push_str("\x1b[0m");
+                self.sgr = String::from("0");
+            }
 
-        self.title = title.clone();
+            match time {
+                Some(time) => self.text.push_str(&timestamp(time)),
+                None => self.text.push_str(&self.padding),
+            }
+            self.text.push(' ');
+        }
 
-        let title_event = match title {
-            Some(title) => Event::Title(title),
-            None => Event::ResetTitle,
-        };
+        let line_length = row.line_length();
+        if self.ansi {
+            push_ansi_cells(&mut self.text, &mut self.sgr, self.tabs, row, Column(0)..line_length);
+        } else {
+            push_cells(&mut self.text, self.tabs, row, Column(0)..line_length);
+        }
 
-        self.event_proxy.send_event(title_event);
+        self.wrapped = line_length.0 != 0 && row[line_length - 1].flags.contains(Flags::WRAPLINE);
+        if !self.wrapped {
+            self.text.push('\n');
+        }
     }
 
-    #[inline]
-    fn push_title(&mut self) {
-        trace!("Pushing '{:?}' onto title stack", self.title);
+    /// Get the text of all lines, terminating the last line with exactly one newline.
+    fn finish(mut self) -> String {
+        self.text.truncate(self.text.trim_end_matches('\n').len());
+        if self.sgr != "0" {
+            self.text.push_str("\x1b[0m");
+        }
+        self.text.push('\n');
 
-        if self.title_stack.len() >= TITLE_STACK_MAX_DEPTH {
-            let removed = self.title_stack.remove(0);
-            trace!(
-                "Removing '{:?}' from bottom of title stack that exceeds its maximum depth",
-                removed
-            );
+        self.text
+    }
+}
+
+/// Append the text of the cells in `columns`.
+fn push_cells(text: &mut String, tabs: &TabStops, row: &Row<Cell>, columns: Range<Column>) {
+    let mut tab_mode = false;
+    for column in (columns.start.0..columns.end.0).map(Column::from) {
+        let cell = &row[column];
+
+        // Skip over cells until next tab-stop once a tab was found.
+        if tab_mode {
+            if tabs.is_stop(column) || cell.c != ' ' {
+                tab_mode = false;
+            } else {
+                continue;
+            }
         }
 
-        self.title_stack.push(self.title.clone());
+        if cell.c == '\t' {
+            tab_mode = true;
+        }
+
+        if !cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER) {
+            // Push cells primary character.
+            text.push(cell.c);
+
+            // Push zero-width characters.
+            for c in cell.zerowidth().into_iter().flatten() {
+                text.push(*c);
+            }
+        }
     }
+}
 
-    #[inline]
-    fn pop_title(&mut self) {
-        trace!("Attempting to pop title from stack...");
+/// Append the text of the cells in `columns`, with SGR escape sequences for their attributes.
+///
+/// The `sgr` parameters of the previous cell are updated to match the last appended cell.
+fn push_ansi_cells(
+    text: &mut String,
+    sgr: &mut String,
+    tabs: &TabStops,
+    row: &Row<Cell>,
+    columns: Range<Column>,
+) {
+    let mut tab_mode = false;
+    for column in (columns.start.0..columns.end.0).map(Column::from) {
+        let cell = &row[column];
+
+        // Skip over cells until next tab-stop once a tab was found.
+        if tab_mode && !tabs.is_stop(column) && cell.c == ' ' {
+            continue;
+        }
+        tab_mode = cell.c == '\t';
 
-        if let Some(popped) = self.title_stack.pop() {
-            trace!("Title '{:?}' popped from stack", popped);
-            self.set_title(popped);
+        let spacer = Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER;
+        if cell.flags.intersects(spacer) {
+            continue;
         }
+
+        let cell_sgr = sgr_report(cell);
+        if cell_sgr != *sgr {
+            text.push_str(&format!("\x1b[{cell_sgr}m"));
+            *sgr = cell_sgr;
+        }
+
+        text.push(cell.c);
+        text.extend(cell.zerowidth().into_iter().flatten());
     }
+}
 
-    #[inline]
-    fn text_area_size_pixels(&mut self) {
-        self.event_proxy.send_event(Event::TextAreaSizeRequest(Arc::new(move |window_size| {
-            let height = window_size.num_lines * window_size.cell_height;
-            let width = window_size.num_cols * window_size.cell_width;
-            format!("\x1b[4;{height};{width}t")
-        })));
+/// SGR parameters reproducing the attributes of a cell, for DECRQSS.
+fn sgr_report(cell: &Cell) -> String {
+    const FLAGS: [(Flags, &str); 11] = [
+        (Flags::BOLD, "1"),
+        (Flags::DIM, "2"),
+        (Flags::ITALIC, "3"),
+        (Flags::UNDERLINE, "4"),
+        (Flags::DOUBLE_UNDERLINE, "4:2"),
+        (Flags::UNDERCURL, "4:3"),
+        (Flags::DOTTED_UNDERLINE, "4:4"),
+        (Flags::DASHED_UNDERLINE, "4:5"),
+        (Flags::INVERSE, "7"),
+        (Flags::HIDDEN, "8"),
+        (Flags::STRIKEOUT, "9"),
+    ];
+
+    let mut params = vec![String::from("0")];
+    for (flag, param) in FLAGS {
+        if cell.flags.contains(flag) {
+            params.push(param.to_owned());
+        }
     }
 
-    #[inline]
-    fn text_area_size_chars(&mut self) {
-        let text = format!("\x1b[8;{};{}t", self.screen_lines(), self.columns());
-        self.event_proxy.send_event(Event::PtyWrite(text));
+    params.extend(sgr_color(cell.fg, 38));
+    params.extend(sgr_color(cell.bg, 48));
+    params.extend(cell.underline_color().and_then(|color| sgr_color(color, 58)));
+
+    params.join(";")
+}
+
+/// SGR parameter for a color, with `extended` being the parameter for indexed and RGB colors.
+///
+/// Default colors have no parameter, since they're reset by SGR 0.
+fn sgr_color(color: Color, extended: usize) -> Option<String> {
+    match color {
+        // Foreground and background have separate parameters for the 16 named colors.
+        Color::Named(named) if (named as usize) < 8 && extended != 58 => {
+            Some((extended - 8 + named as usize).to_string())
+        },
+        Color::Named(named) if (named as usize) < 16 && extended != 58 => {
+            Some((extended + 52 + named as usize - 8).to_string())
+        },
+        Color::Named(named) if (named as usize) < 16 => {
+            Some(format!("{extended};5;{}", named as usize))
+        },
+        Color::Named(_) => None,
+        Color::Indexed(index) => Some(format!("{extended};5;{index}")),
+        Color::Spec(rgb) => Some(format!("{extended};2;{};{};{}", rgb.r, rgb.g, rgb.b)),
     }
 }
 
+/// Convert an OSC 22 pointer name into its CSS cursor name.
+///
+/// Both CSS cursor names and the X11 cursor font names used by xterm are accepted.
+fn pointer_shape_name(name: &str) -> Option<&'static str> {
+    let name = match name.trim() {
+        "left_ptr" | "arrow" | "top_left_arrow" | "default" => "default",
+        "xterm" | "ibeam" | "text" => "text",
+        "hand" | "hand1" | "hand2" | "pointing_hand" | "pointer" => "pointer",
+        "cross" | "tcross" | "crosshair" => "crosshair",
+        "fleur" | "move" => "move",
+        "watch" | "wait" => "wait",
+        "left_ptr_watch" | "progress" => "progress",
+        "question_arrow" | "whats_this" | "help" => "help",
+        "not-allowed" | "crossed_circle" => "not-allowed",
+        "openhand" | "grab" => "grab",
+        "closedhand" | "grabbing" => "grabbing",
+        "col-resize" => "col-resize",
+        "row-resize" => "row-resize",
+        "sb_h_double_arrow" | "h_double_arrow" | "ew-resize" => "ew-resize",
+        "sb_v_double_arrow" | "v_double_arrow" | "ns-resize" => "ns-resize",
+        "fd_double_arrow" | "nesw-resize" => "nesw-resize",
+        "bd_double_arrow" | "nwse-resize" => "nwse-resize",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
 /// Terminal version for escape sequence reports.
 ///
 /// This returns the current terminal version as a unique number based on alacritty_terminal's
@@ -2044,6 +3181,14 @@ impl TabStops {
             is_tabstop
         });
     }
+
+    /// Check for a tabstop, using the initial tabstops for columns beyond the current width.
+    ///
+    /// Archived lines might be wider than the grid.
+    #[inline]
+    fn is_stop(&self, column: Column) -> bool {
+        self.tabs.get(column.0).copied().unwrap_or(column.0 % INITIAL_TABSTOPS == 0)
+    }
 }
 
 impl Index<Column> for TabStops {
@@ -2096,6 +3241,7 @@ pub struct RenderableContent<'a> {
     pub cursor: RenderableCursor,
     pub display_offset: usize,
     pub colors: &'a color::Colors,
+    pub hyperlinks: &'a Hyperlinks,
     pub mode: TermMode,
 }
 
@@ -2107,6 +3253,7 @@ impl<'a> RenderableContent<'a> {
             cursor: RenderableCursor::new(term),
             selection: term.selection.as_ref().and_then(|s| s.to_range(term)),
             colors: &term.colors,
+            hyperlinks: &term.hyperlinks,
             mode: *term.mode(),
         }
     }
@@ -2213,7 +3360,7 @@ mod tests {
     use std::mem;
 
     use crate::ansi::{self, CharsetIndex, Handler, StandardCharset};
-    use crate::config::Config;
+    use crate::config::{Config, MAX_SCROLLBACK_LINES};
     use crate::event::VoidListener;
     use crate::grid::{Grid, Scroll};
     use crate::index::{Column, Point, Side};
@@ -2277,6 +3424,110 @@ mod tests {
         assert_eq!(term.grid.display_offset(), 0);
     }
 
+    #[test]
+    fn scroll_display_disk_history() {
+        let mut config = Config::default();
+        config.scrolling.set_history(CHUNK_LINES as u32);
+        config.scrolling.disk_history = 10 * CHUNK_LINES;
+        let size = TermSize::new(5, 10);
+        let mut term = Term::new(&config, &size, VoidListener);
+
+        // Label every line with a unique character.
+        let label = |index: usize| char::from_u32(0x100 + index as u32).unwrap();
+        let total_lines = 4 * CHUNK_LINES;
+        for i in 0..total_lines {
+            let line = term.grid.cursor.point.line;
+            term.grid[line][Column(0)].c = label(i);
+            term.newline();
+        }
+
+        // Everything exceeding the in-memory history is moved to disk.
+        let history_size = total_lines - 9;
+        assert_eq!(term.history_size(), CHUNK_LINES);
+        assert_eq!(term.archived_lines(), history_size - CHUNK_LINES);
+
+        // Scrolling to the top only pages in the most recent archived lines.
+        term.scroll_display(Scroll::Top);
+        assert_eq!(term.grid.display_offset(), term.history_size());
+        assert!(term.history_size() > CHUNK_LINES);
+        assert!(term.archived_lines() > 0);
+
+        // Repeatedly scrolling to the top reaches the oldest line.
+        while term.archived_lines() > 0 {
+            term.scroll_display(Scroll::Top);
+        }
+        assert_eq!(term.grid.display_offset(), history_size);
+        assert_eq!(term.grid[Line(-(history_size as i32))][Column(0)].c, label(0));
+
+        // Scrolling back to the bottom moves it back to disk.
+        term.scroll_display(Scroll::Bottom);
+        assert_eq!(term.history_size(), CHUNK_LINES);
+        assert_eq!(term.archived_lines(), history_size - CHUNK_LINES);
+
+        // Scrolling beyond the in-memory history pages in the required lines.
+        term.scroll_display(Scroll::Delta(CHUNK_LINES as i32 + 1));
+        assert_eq!(term.grid.display_offset(), CHUNK_LINES + 1);
+        let top = Line(-(CHUNK_LINES as i32 + 1));
+        assert_eq!(term.grid[top][Column(0)].c, label(total_lines - 10 - CHUNK_LINES));
+    }
+
+    #[test]
+    fn history_memory_limit() {
+        let mut config = Config::default();
+        config.scrolling.set_history(MAX_SCROLLBACK_LINES);
+        config.scrolling.max_memory = 1;
+        let size = TermSize::new(100, 10);
+        let mut term = Term::new(&config, &size, VoidListener);
+
+        for _ in 0..MAX_SCROLLBACK_LINES {
+            term.newline();
+        }
+
+        let max_lines = |columns: usize| 1024 * 1024 / (columns * mem::size_of::<Cell>()) - 10;
+        assert_eq!(term.history_size(), max_lines(100));
+
+        // Wider lines evict history to stay within the limit.
+        term.resize(TermSize::new(200, 10));
+        assert_eq!(term.history_size(), max_lines(200));
+    }
+
+    #[test]
+    fn scroll_display_compressed_history() {
+        let mut config = Config::default();
+        config.scrolling.set_history(4 * CHUNK_LINES as u32);
+        config.scrolling.compress_history = true;
+        let size = TermSize::new(5, 10);
+        let mut term = Term::new(&config, &size, VoidListener);
+
+        // Label every line with a unique character.
+        let label = |index: usize| char::from_u32(0x100 + index as u32).unwrap();
+        let total_lines = 8 * CHUNK_LINES;
+        for i in 0..total_lines {
+            let line = term.grid.cursor.point.line;
+            term.grid[line][Column(0)].c = label(i);
+            term.newline();
+        }
+
+        // Only the most recent history is kept uncompressed.
+        let compressed_lines = config.scrolling.compressed_history();
+        assert_eq!(term.history_size(), 4 * CHUNK_LINES - compressed_lines);
+        assert!(term.archived_lines() >= compressed_lines);
+        assert!(term.archived_lines() < compressed_lines + CHUNK_LINES);
+
+        // Scrolling to the top repeatedly pages in all compressed lines.
+        let history_size = term.history_size() + term.archived_lines();
+        while term.archived_lines() > 0 {
+            term.scroll_display(Scroll::Top);
+        }
+        assert_eq!(term.grid.display_offset(), history_size);
+        let top = Line(-(history_size as i32));
+        assert_eq!(term.grid[top][Column(0)].c, label(total_lines - 9 - history_size));
+
+        // Scrolling back to the bottom compresses them again.
+        term.scroll_display(Scroll::Bottom);
+        assert_eq!(term.history_size(), 4 * CHUNK_LINES - compressed_lines);
+    }
+
     #[test]
     fn simple_selection_works() {
         let size = TermSize::new(5, 5);
@@ -3012,6 +4263,337 @@ mod tests {
         assert_eq!(term.title, None);
     }
 
+    #[test]
+    fn mouse_pointer_shape() {
+        let size = TermSize::new(21, 51);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        term.set_mouse_pointer_shape(Some("hand2"));
+        assert_eq!(term.mouse_pointer_shape(), Some("pointer"));
+
+        // Unknown shapes fall back to the next entry.
+        term.set_mouse_pointer_shape(Some("unknown,ew-resize"));
+        assert_eq!(term.mouse_pointer_shape(), Some("ew-resize"));
+
+        term.set_mouse_pointer_shape(None);
+        assert_eq!(term.mouse_pointer_shape(), None);
+
+        // Shape is reset when leaving the alternate screen.
+        term.set_mode(ansi::Mode::SwapScreenAndSetRestoreCursor);
+        term.set_mouse_pointer_shape(Some("crosshair"));
+        assert_eq!(term.mouse_pointer_shape(), Some("crosshair"));
+        term.unset_mode(ansi::Mode::SwapScreenAndSetRestoreCursor);
+        assert_eq!(term.mouse_pointer_shape(), None);
+
+        // Shapes outside of the allowlist are ignored.
+        let mut config = Config::default();
+        config.terminal.pointer_shapes = vec![String::from("text")];
+        let mut term = Term::new(&config, &size, VoidListener);
+        term.set_mouse_pointer_shape(Some("pointer"));
+        assert_eq!(term.mouse_pointer_shape(), None);
+    }
+
+    #[test]
+    fn command_outputs() {
+        fn run_command(term: &mut Term<VoidListener>, output: &[&str]) {
+            term.prompt_mark(PromptMark::PromptStart);
+            term.input('$');
+            term.prompt_mark(PromptMark::CommandStart);
+            term.carriage_return();
+            term.linefeed();
+            term.prompt_mark(PromptMark::OutputStart);
+            for line in output {
+                for c in line.chars() {
+                    term.input(c);
+                }
+                term.carriage_return();
+                term.linefeed();
+            }
+            term.prompt_mark(PromptMark::CommandEnd(Some(0)));
+        }
+
+        let size = TermSize::new(10, 10);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        run_command(&mut term, &["a", "b"]);
+        run_command(&mut term, &[]);
+        run_command(&mut term, &["c", ""]);
+        term.prompt_mark(PromptMark::PromptStart);
+
+        let outputs = term.command_outputs(usize::MAX);
+        assert_eq!(outputs, vec![
+            Point::new(Line(5), Column(0))..=Point::new(Line(5), Column(9)),
+            Point::new(Line(1), Column(0))..=Point::new(Line(2), Column(9)),
+        ]);
+
+        assert_eq!(term.command_outputs(1).len(), 1);
+    }
+
+    #[test]
+    fn finished_commands() {
+        let size = TermSize::new(10, 10);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        for exit_code in [1, 0] {
+            term.prompt_mark(PromptMark::PromptStart);
+            term.linefeed();
+            term.linefeed();
+            term.linefeed();
+            term.prompt_mark(PromptMark::CommandEnd(Some(exit_code)));
+        }
+        term.prompt_mark(PromptMark::PromptStart);
+
+        let commands = term.finished_commands(Line(0)..=Line(9));
+        assert_eq!(commands, vec![(Line(3)..=Line(5), Some(0)), (Line(0)..=Line(2), Some(1))]);
+
+        let commands = term.finished_commands(Line(4)..=Line(4));
+        assert_eq!(commands, vec![(Line(3)..=Line(5), Some(0))]);
+
+        let commands = term.finished_commands(Line(0)..=Line(0));
+        assert_eq!(commands, vec![(Line(0)..=Line(2), Some(1))]);
+
+        assert!(term.finished_commands(Line(6)..=Line(9)).is_empty());
+    }
+
+    #[test]
+    fn last_command_duration() {
+        let size = TermSize::new(10, 10);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        // Commands without output start mark have no known duration.
+        term.prompt_mark(PromptMark::PromptStart);
+        term.prompt_mark(PromptMark::CommandEnd(Some(0)));
+        assert_eq!(term.last_command_duration(), None);
+
+        term.prompt_mark(PromptMark::PromptStart);
+        term.prompt_mark(PromptMark::OutputStart);
+        assert_eq!(term.last_command_duration(), None);
+        term.prompt_mark(PromptMark::CommandEnd(Some(0)));
+        assert!(term.last_command_duration().is_some());
+
+        // A new prompt discards the start of commands which never finished.
+        term.prompt_mark(PromptMark::OutputStart);
+        term.prompt_mark(PromptMark::PromptStart);
+        term.prompt_mark(PromptMark::CommandEnd(Some(0)));
+        assert_eq!(term.last_command_duration(), None);
+    }
+
+    #[test]
+    fn prompt_lines() {
+        let size = TermSize::new(10, 10);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        for _ in 0..3 {
+            term.prompt_mark(PromptMark::PromptStart);
+            term.linefeed();
+            term.linefeed();
+        }
+
+        assert_eq!(term.prompt_line(Line(3), Direction::Left), Some(Line(2)));
+        assert_eq!(term.prompt_line(Line(2), Direction::Left), Some(Line(0)));
+        assert_eq!(term.prompt_line(Line(1), Direction::Right), Some(Line(2)));
+        assert_eq!(term.prompt_line(Line(2), Direction::Right), Some(Line(4)));
+        assert_eq!(term.prompt_line(Line(4), Direction::Right), None);
+        assert_eq!(term.prompt_line(Line(0), Direction::Left), None);
+    }
+
+    #[test]
+    fn clear_above() {
+        let size = TermSize::new(5, 3);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        for c in ['a', 'b', 'c', 'd', 'e'] {
+            term.input(c);
+            term.newline();
+            term.carriage_return();
+        }
+        term.input('f');
+
+        // Remove history above a line in the scrollback.
+        assert_eq!(term.history_size(), 3);
+        term.clear_above(Line(-2));
+        assert_eq!(term.history_size(), 2);
+        assert_eq!(term.grid()[Line(-2)][Column(0)].c, 'b');
+
+        // Move a line on the screen to the top.
+        term.clear_above(Line(1));
+        assert_eq!(term.history_size(), 0);
+        assert_eq!(term.grid()[Line(0)][Column(0)].c, 'e');
+        assert_eq!(term.grid()[Line(1)][Column(0)].c, 'f');
+        assert_eq!(term.grid()[Line(2)][Column(0)].c, ' ');
+        assert_eq!(term.grid().cursor.point, Point::new(Line(1), Column(1)));
+    }
+
+    #[test]
+    fn bookmarks() {
+        let size = TermSize::new(10, 3);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        term.toggle_bookmark(Line(0));
+        term.toggle_bookmark(Line(2));
+        assert_eq!(term.bookmarks(Line(0)..=Line(2)), vec![Line(0), Line(2)]);
+        assert_eq!(term.bookmark_line(Line(2), Direction::Left), Some(Line(0)));
+        assert_eq!(term.bookmark_line(Line(0), Direction::Right), Some(Line(2)));
+
+        // Bookmarks move into history with their lines.
+        term.linefeed();
+        term.linefeed();
+        term.linefeed();
+        assert_eq!(term.bookmarks(Line(-1)..=Line(2)), vec![Line(-1), Line(1)]);
+
+        term.toggle_bookmark(Line(1));
+        assert_eq!(term.bookmark_line(Line(2), Direction::Left), Some(Line(-1)));
+        assert_eq!(term.bookmark_line(Line(-1), Direction::Right), None);
+    }
+
+    #[test]
+    fn unused_hyperlinks_are_removed() {
+        let size = TermSize::new(10, 3);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        let foo = Hyperlink::new(Some("foo"), String::from("https://example.org"));
+        term.set_hyperlink(Some(foo.clone().into()));
+        term.input('a');
+
+        // Fill the table with hyperlinks which aren't referenced by any cell.
+        let mut index = 0;
+        while term.hyperlinks.intern(Hyperlink::new(Some(index), String::new())).is_some() {
+            index += 1;
+        }
+
+        let bar = Hyperlink::new(Some("bar"), String::from("https://example.org"));
+        term.set_hyperlink(Some(bar.clone().into()));
+        term.input('b');
+
+        assert_eq!(term.hyperlink_at(Point::new(Line(0), Column(0))), Some(foo));
+        assert_eq!(term.hyperlink_at(Point::new(Line(0), Column(1))), Some(bar));
+        assert_eq!(term.hyperlinks().len(), 2);
+    }
+
+    #[test]
+    fn history_to_string() {
+        let size = TermSize::new(5, 3);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        term.input('a');
+        term.terminal_attribute(Attr::Bold);
+        term.input('b');
+        term.newline();
+        term.carriage_return();
+        term.terminal_attribute(Attr::Foreground(Color::Indexed(42)));
+        term.input('c');
+        term.terminal_attribute(Attr::Reset);
+        term.newline();
+        term.newline();
+        term.newline();
+
+        assert_eq!(term.history_size(), 2);
+        assert_eq!(term.history_to_string(false, None), "ab\nc\n");
+        let ansi = "a\x1b[0;1mb\n\x1b[0;1;38;5;42mc\x1b[0m\n";
+        assert_eq!(term.history_to_string(true, None), ansi);
+    }
+
+    #[test]
+    fn history_to_string_timestamps() {
+        let size = TermSize::new(5, 3);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        term.terminal_attribute(Attr::Bold);
+        for c in "abcdefg".chars() {
+            term.input(c);
+        }
+        term.newline();
+        term.newline();
+        term.carriage_return();
+        term.input('h');
+
+        assert!(term.grid().timestamp(Line(-1)).is_some());
+        assert_eq!(term.grid().timestamp(Line(1)), None);
+
+        let timestamp = Some((|_| String::from("t")) as fn(SystemTime) -> String);
+        assert_eq!(term.history_to_string(false, timestamp), "t abcdefg\n  \nt h\n");
+        let ansi = "t \x1b[0;1mabcdefg\n\x1b[0m  \nt \x1b[0;1mh\x1b[0m\n";
+        assert_eq!(term.history_to_string(true, timestamp), ansi);
+    }
+
+    #[test]
+    fn history_to_string_compressed_history() {
+        let mut config = Config::default();
+        config.scrolling.set_history(4 * CHUNK_LINES as u32);
+        config.scrolling.compress_history = true;
+        let size = TermSize::new(5, 10);
+        let mut term = Term::new(&config, &size, VoidListener);
+
+        let total_lines = 8 * CHUNK_LINES;
+        for i in 0..total_lines {
+            for c in i.to_string().chars() {
+                term.input(c);
+            }
+            term.newline();
+            term.carriage_return();
+        }
+
+        let history_size = term.history_size();
+        let archived_lines = term.archived_lines();
+        assert!(archived_lines > 0);
+
+        // Archived lines are included without paging them back into the grid.
+        let text = term.history_to_string(false, None);
+        assert_eq!(term.history_size(), history_size);
+        assert_eq!(term.archived_lines(), archived_lines);
+
+        let lines = history_size + archived_lines + 9;
+        let expected: String =
+            (total_lines - lines..total_lines).map(|i| format!("{i}\n")).collect();
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn selection_to_ansi() {
+        let size = TermSize::new(5, 3);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        term.input('a');
+        term.terminal_attribute(Attr::Italic);
+        for c in "bcdef".chars() {
+            term.input(c);
+        }
+        term.terminal_attribute(Attr::Reset);
+        term.input('g');
+
+        // Selection spanning a wrapped line.
+        let mut selection =
+            Selection::new(SelectionType::Simple, Point::new(Line(0), Column(3)), Side::Left);
+        selection.update(Point::new(Line(1), Column(0)), Side::Right);
+        term.selection = Some(selection);
+        assert_eq!(term.selection_to_ansi(), Some(String::from("\x1b[0;3mdef\x1b[0m")));
+
+        // Block selection only includes the selected columns.
+        let mut selection =
+            Selection::new(SelectionType::Block, Point::new(Line(0), Column(0)), Side::Left);
+        selection.update(Point::new(Line(1), Column(1)), Side::Right);
+        term.selection = Some(selection);
+        assert_eq!(term.selection_to_ansi(), Some(String::from("a\x1b[0;3mb\nf\x1b[0mg")));
+    }
+
+    #[test]
+    fn sgr_reports() {
+        let mut cell = Cell::default();
+        assert_eq!(sgr_report(&cell), "0");
+
+        cell.flags = Flags::BOLD | Flags::UNDERCURL;
+        cell.fg = Color::Named(NamedColor::Red);
+        cell.bg = Color::Named(NamedColor::BrightBlue);
+        cell.set_underline_color(Some(Color::Indexed(42)));
+        assert_eq!(sgr_report(&cell), "0;1;4:3;31;104;58;5;42");
+
+        cell.flags = Flags::empty();
+        cell.fg = Color::Spec(VteRgb { r: 1, g: 2, b: 3 });
+        cell.bg = Color::Named(NamedColor::Background);
+        cell.set_underline_color(None);
+        assert_eq!(sgr_report(&cell), "0;38;2;1;2;3");
+    }
+
     #[test]
     fn parse_cargo_version() {
         assert!(version_number(env!("CARGO_PKG_VERSION")) >= 10_01);
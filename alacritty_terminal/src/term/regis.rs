@@ -0,0 +1,250 @@
+//! ReGIS vector graphics, drawn on top of the terminal's text area.
+//!
+//! Only the drawing subset of ReGIS is supported: positioning (`P`), vectors (`V`), circles (`C`),
+//! screen erasure (`S(E)`) and the writing color (`W(I…)`). All other commands are ignored.
+
+use std::f32::consts::PI;
+
+use log::debug;
+
+use crate::ansi::NamedColor;
+
+/// Width of the ReGIS screen in its own coordinates.
+pub const SCREEN_WIDTH: f32 = 800.;
+
+/// Height of the ReGIS screen in its own coordinates.
+pub const SCREEN_HEIGHT: f32 = 480.;
+
+/// Maximum number of lines kept, the oldest ones are dropped beyond it.
+const MAX_LINES: usize = 10_000;
+
+/// Number of segments approximating a circle.
+const CIRCLE_SEGMENTS: usize = 64;
+
+/// Default colors of the VT340 color map, for colors selected by index.
+const INDEXED_COLORS: [NamedColor; 8] = [
+    NamedColor::Black,
+    NamedColor::Blue,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::Yellow,
+    NamedColor::White,
+];
+
+/// Position on the ReGIS screen.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct RegisPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl RegisPoint {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Straight line drawn on the ReGIS screen.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RegisLine {
+    pub start: RegisPoint,
+    pub end: RegisPoint,
+    pub color: NamedColor,
+}
+
+/// State of the ReGIS screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regis {
+    lines: Vec<RegisLine>,
+
+    /// Current drawing position.
+    position: RegisPoint,
+
+    /// Color of new lines.
+    color: NamedColor,
+}
+
+impl Default for Regis {
+    fn default() -> Self {
+        Self { lines: Vec::new(), position: RegisPoint::default(), color: NamedColor::Foreground }
+    }
+}
+
+impl Regis {
+    /// All lines drawn since the screen was last erased.
+    pub fn lines(&self) -> &[RegisLine] {
+        &self.lines
+    }
+
+    /// Execute a string of ReGIS commands.
+    pub fn execute(&mut self, commands: &str) {
+        let mut command = None;
+        let mut chars = commands.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '[' => {
+                    let argument: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                    let point = self.parse_point(&argument);
+                    match command {
+                        Some('P') => self.position = point,
+                        Some('V') => self.draw_line(self.position, point),
+                        Some('C') => self.draw_circle(self.position, point),
+                        _ => (),
+                    }
+                },
+                '(' => {
+                    let mut depth = 1;
+                    let options: String = chars
+                        .by_ref()
+                        .take_while(|&c| {
+                            depth += match c {
+                                '(' => 1,
+                                ')' => -1,
+                                _ => 0,
+                            };
+                            depth > 0
+                        })
+                        .collect();
+                    self.options(command, &options);
+                },
+                // Text is not supported, but its quotes have to be skipped.
+                '\'' | '"' => for _ in chars.by_ref().take_while(|&quoted| quoted != c) {},
+                ';' => command = None,
+                c if c.is_ascii_alphabetic() => {
+                    let c = c.to_ascii_uppercase();
+                    if !matches!(c, 'P' | 'V' | 'C' | 'S' | 'W') {
+                        debug!("Ignoring unsupported ReGIS command {:?}", c);
+                    }
+                    command = Some(c);
+                },
+                _ => (),
+            }
+        }
+    }
+
+    /// Parse a coordinate argument, relative to the current position for signed values.
+    fn parse_point(&self, argument: &str) -> RegisPoint {
+        let mut coordinates = argument.split(',').map(str::trim);
+        let mut parse = |current: f32, max: f32| {
+            let coordinate = coordinates.next().unwrap_or_default();
+            let value = match coordinate.parse::<f32>() {
+                Ok(value) if coordinate.starts_with(['+', '-']) => current + value,
+                Ok(value) => value,
+                Err(_) => current,
+            };
+            value.clamp(0., max)
+        };
+
+        let x = parse(self.position.x, SCREEN_WIDTH);
+        let y = parse(self.position.y, SCREEN_HEIGHT);
+        RegisPoint::new(x, y)
+    }
+
+    /// Apply the options of a command.
+    fn options(&mut self, command: Option<char>, options: &str) {
+        let options = options.trim().to_ascii_uppercase();
+        match command {
+            Some('S') if options.starts_with('E') => self.lines.clear(),
+            Some('W') => {
+                if let Some(color) = options.strip_prefix('I').and_then(parse_color) {
+                    self.color = color;
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn draw_line(&mut self, start: RegisPoint, end: RegisPoint) {
+        if self.lines.len() >= MAX_LINES {
+            self.lines.remove(0);
+        }
+
+        self.lines.push(RegisLine { start, end, color: self.color });
+        self.position = end;
+    }
+
+    /// Draw a circle around `center`, passing through `point`.
+    fn draw_circle(&mut self, center: RegisPoint, point: RegisPoint) {
+        let radius = (point.x - center.x).hypot(point.y - center.y);
+        let circle_point = |i: usize| {
+            let angle = i as f32 / CIRCLE_SEGMENTS as f32 * 2. * PI;
+            RegisPoint::new(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+        };
+
+        for i in 0..CIRCLE_SEGMENTS {
+            self.draw_line(circle_point(i), circle_point(i + 1));
+        }
+
+        // Circles don't move the current position.
+        self.position = center;
+    }
+}
+
+/// Parse a color index or color letter.
+fn parse_color(color: &str) -> Option<NamedColor> {
+    let color = color.trim_start_matches('(').trim_end_matches(')').trim();
+    match color {
+        "D" => Some(NamedColor::Black),
+        "B" => Some(NamedColor::Blue),
+        "R" => Some(NamedColor::Red),
+        "G" => Some(NamedColor::Green),
+        "M" => Some(NamedColor::Magenta),
+        "C" => Some(NamedColor::Cyan),
+        "Y" => Some(NamedColor::Yellow),
+        "W" => Some(NamedColor::White),
+        _ => INDEXED_COLORS.get(color.parse::<usize>().ok()?).copied(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vectors() {
+        let mut regis = Regis::default();
+        regis.execute("P[100,100] V[200,100][+0,+50]; P[10,10]");
+
+        assert_eq!(regis.lines(), [
+            RegisLine {
+                start: RegisPoint::new(100., 100.),
+                end: RegisPoint::new(200., 100.),
+                color: NamedColor::Foreground,
+            },
+            RegisLine {
+                start: RegisPoint::new(200., 100.),
+                end: RegisPoint::new(200., 150.),
+                color: NamedColor::Foreground,
+            },
+        ]);
+        assert_eq!(regis.position, RegisPoint::new(10., 10.));
+
+        // Coordinates are limited to the screen.
+        regis.execute("V[,900]");
+        assert_eq!(regis.lines()[2].end, RegisPoint::new(10., SCREEN_HEIGHT));
+    }
+
+    #[test]
+    fn colors_and_erase() {
+        let mut regis = Regis::default();
+        regis.execute("W(I(R)) P[0,0] V[10,10] W(I2) T'V[5,5]' V[20,20]");
+        assert_eq!(regis.lines().len(), 2);
+        assert_eq!(regis.lines()[0].color, NamedColor::Red);
+        assert_eq!(regis.lines()[1].color, NamedColor::Red);
+
+        regis.execute("S(E)");
+        assert!(regis.lines().is_empty());
+    }
+
+    #[test]
+    fn circles() {
+        let mut regis = Regis::default();
+        regis.execute("P[400,240] C[+100]");
+
+        assert_eq!(regis.lines().len(), CIRCLE_SEGMENTS);
+        assert_eq!(regis.lines()[0].start, RegisPoint::new(500., 240.));
+        assert_eq!(regis.position, RegisPoint::new(400., 240.));
+    }
+}
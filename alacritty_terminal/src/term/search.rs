@@ -5,6 +5,7 @@ use std::ops::RangeInclusive;
 pub use regex_automata::dfa::dense::BuildError;
 use regex_automata::dfa::dense::{Builder, Config, DFA};
 use regex_automata::dfa::Automaton;
+use regex_automata::meta::{self, Regex};
 use regex_automata::nfa::thompson::Config as ThompsonConfig;
 use regex_automata::util::syntax::Config as SyntaxConfig;
 use regex_automata::{Anchored, Input};
@@ -20,25 +21,93 @@ const BRACKET_PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('
 /// Maximum DFA size to prevent pathological regexes taking down the entire system.
 const MAX_DFA_SIZE: usize = 100_000_000;
 
+/// Fuzzy match score for every character of the query.
+const FUZZY_MATCH_SCORE: i32 = 16;
+
+/// Fuzzy match bonus for characters directly following the previous match.
+const FUZZY_CONSECUTIVE_BONUS: i32 = 8;
+
+/// Fuzzy match bonus for characters at the start of a word.
+const FUZZY_WORD_START_BONUS: i32 = 8;
+
+/// Fuzzy match penalty for every skipped character.
+const FUZZY_GAP_PENALTY: i32 = 1;
+
 pub type Match = RangeInclusive<Point>;
 
+/// Case sensitivity of a regex search.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum CaseSensitivity {
+    /// Case insensitive, unless the search contains uppercase characters.
+    #[default]
+    Smart,
+    /// Always case sensitive.
+    Sensitive,
+    /// Always case insensitive.
+    Insensitive,
+}
+
+impl CaseSensitivity {
+    /// Check if a search for `search` should ignore case.
+    fn is_insensitive(self, search: &str) -> bool {
+        match self {
+            Self::Smart => !search.chars().any(|c| c.is_uppercase()),
+            Self::Sensitive => false,
+            Self::Insensitive => true,
+        }
+    }
+}
+
+/// Options for building a regex search.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Case sensitivity of the search.
+    pub case_sensitivity: CaseSensitivity,
+
+    /// Only match whole words.
+    pub whole_word: bool,
+
+    /// Match the characters of the search in order, with anything in between them.
+    pub fuzzy: bool,
+}
+
 /// Terminal regex search state.
 #[derive(Clone, Debug)]
 pub struct RegexSearch {
     dfa: DFA<Vec<u32>>,
     rdfa: DFA<Vec<u32>>,
+    pattern: String,
+    syntax_config: SyntaxConfig,
+    regex: Option<Regex>,
+    case_insensitive: bool,
+    fuzzy_query: Option<String>,
 }
 
 impl RegexSearch {
     /// Build the forward and backward search DFAs.
     pub fn new(search: &str) -> Result<RegexSearch, Box<BuildError>> {
+        Self::with_options(search, SearchOptions::default())
+    }
+
+    /// Build the forward and backward search DFAs with custom search options.
+    pub fn with_options(
+        search: &str,
+        options: SearchOptions,
+    ) -> Result<RegexSearch, Box<BuildError>> {
+        let mut pattern = if options.fuzzy { fuzzy_pattern(search) } else { search.to_owned() };
+
+        // Surround the search with ASCII word boundaries, since DFAs don't support Unicode ones.
+        if options.whole_word {
+            pattern = format!(r"(?-u:\b)(?:{pattern})(?-u:\b)");
+        }
+
         // Setup configs for both DFA directions.
-        let has_uppercase = search.chars().any(|c| c.is_uppercase());
-        let syntax_config = SyntaxConfig::new().case_insensitive(!has_uppercase);
+        let case_insensitive = options.case_sensitivity.is_insensitive(search);
+        let syntax_config = SyntaxConfig::new().case_insensitive(case_insensitive);
         let config = Config::new().dfa_size_limit(Some(MAX_DFA_SIZE));
 
         // Create Regex DFA for left-to-right search.
-        let dfa = Builder::new().configure(config.clone()).syntax(syntax_config).build(search)?;
+        let dfa = Builder::new().configure(config.clone()).syntax(syntax_config).build(&pattern)?;
 
         // Create Regex DFA for right-to-left search.
         let thompson_config = ThompsonConfig::new().reverse(true);
@@ -46,10 +115,106 @@ impl RegexSearch {
             .configure(config)
             .syntax(syntax_config)
             .thompson(thompson_config)
-            .build(search)?;
+            .build(&pattern)?;
+
+        let fuzzy_query = options.fuzzy.then(|| search.to_owned());
+
+        Ok(RegexSearch {
+            dfa,
+            rdfa,
+            pattern,
+            syntax_config,
+            regex: None,
+            case_insensitive,
+            fuzzy_query,
+        })
+    }
+
+    /// Rate how closely a match of a fuzzy search resembles its query.
+    ///
+    /// Higher scores are better, with consecutive characters and characters at the start of words
+    /// being preferred. This returns `None` for regular regex searches.
+    pub fn fuzzy_score(&self, text: &str) -> Option<i32> {
+        let query = self.fuzzy_query.as_ref()?;
+        let eq = |a: char, b: char| {
+            a == b || (self.case_insensitive && a.to_lowercase().eq(b.to_lowercase()))
+        };
+
+        // Pair every character with its predecessor, to detect the start of words.
+        let mut chars = text.chars().scan(None, |previous, c| Some((previous.replace(c), c)));
+
+        let mut score = 0;
+        let mut first = true;
+        for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+            let mut skipped = 0;
+            let previous = loop {
+                match chars.next()? {
+                    (previous, c) if eq(query_char, c) => break previous,
+                    _ => skipped += 1,
+                }
+            };
+
+            score += FUZZY_MATCH_SCORE;
+            if previous.map_or(true, |previous| !previous.is_alphanumeric()) {
+                score += FUZZY_WORD_START_BONUS;
+            }
 
-        Ok(RegexSearch { dfa, rdfa })
+            // Gaps before the first character are not penalized.
+            if first {
+                first = false;
+            } else if skipped == 0 {
+                score += FUZZY_CONSECUTIVE_BONUS;
+            } else {
+                score -= skipped * FUZZY_GAP_PENALTY;
+            }
+        }
+
+        Some(score)
     }
+
+    /// Extract the capture groups from text matching the regex.
+    ///
+    /// The first element is the entire match, followed by all capture groups in order. Groups
+    /// which did not participate in the match are `None`.
+    ///
+    /// The regex required for this is only built on the first call, since the DFAs cannot
+    /// extract capture groups.
+    pub fn captures(&mut self, text: &str) -> Option<Vec<Option<String>>> {
+        if self.regex.is_none() {
+            let config = meta::Config::new().nfa_size_limit(Some(MAX_DFA_SIZE));
+            let mut builder = Regex::builder();
+            builder.configure(config).syntax(self.syntax_config);
+            self.regex = Some(builder.build(&self.pattern).ok()?);
+        }
+        let regex = self.regex.as_ref()?;
+
+        let mut captures = regex.create_captures();
+        regex.search_captures(&Input::new(text).anchored(Anchored::Yes), &mut captures);
+        if !captures.is_match() {
+            return None;
+        }
+
+        let groups = (0..captures.group_len())
+            .map(|index| captures.get_group(index).map(|span| text[span.range()].to_owned()))
+            .collect();
+
+        Some(groups)
+    }
+}
+
+/// Convert a fuzzy query to a regex matching its characters in order.
+fn fuzzy_pattern(query: &str) -> String {
+    let mut pattern = String::new();
+    for c in query.chars().filter(|c| !c.is_whitespace()) {
+        if !pattern.is_empty() {
+            pattern.push_str(".*?");
+        }
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern
 }
 
 impl<T> Term<T> {
@@ -549,6 +714,95 @@ mod tests {
     use crate::index::{Column, Line};
     use crate::term::test::{mock_term, TermSize};
 
+    #[test]
+    fn regex_captures() {
+        let mut regex = RegexSearch::new(r"([a-z./]+):(\d+)(?::(\d+))?").unwrap();
+
+        let captures = regex.captures("src/main.rs:42").unwrap();
+        assert_eq!(captures, vec![
+            Some(String::from("src/main.rs:42")),
+            Some(String::from("src/main.rs")),
+            Some(String::from("42")),
+            None,
+        ]);
+
+        assert_eq!(regex.captures("42"), None);
+    }
+
+    #[test]
+    fn case_sensitivity() {
+        let term = mock_term("alacritty Alacritty");
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(18));
+        let lowercase = start..=Point::new(Line(0), Column(8));
+        let uppercase = Point::new(Line(0), Column(10))..=end;
+
+        // Smart case is insensitive unless there's an uppercase character in the search.
+        let regex = RegexSearch::new("alacritty").unwrap();
+        assert_eq!(term.regex_search_right(&regex, start, end), Some(lowercase.clone()));
+        let regex = RegexSearch::new("Alacritty").unwrap();
+        assert_eq!(term.regex_search_right(&regex, start, end), Some(uppercase.clone()));
+
+        let options =
+            SearchOptions { case_sensitivity: CaseSensitivity::Insensitive, ..Default::default() };
+        let regex = RegexSearch::with_options("Alacritty", options).unwrap();
+        assert_eq!(term.regex_search_right(&regex, start, end), Some(lowercase));
+
+        let options =
+            SearchOptions { case_sensitivity: CaseSensitivity::Sensitive, ..Default::default() };
+        let regex = RegexSearch::with_options("alacritty", options).unwrap();
+        let start = Point::new(Line(0), Column(1));
+        assert_eq!(term.regex_search_right(&regex, start, end), None);
+    }
+
+    #[test]
+    fn whole_word() {
+        let term = mock_term("critty alacritty critty");
+        let start = Point::new(Line(0), Column(3));
+        let end = Point::new(Line(0), Column(22));
+
+        let options = SearchOptions { whole_word: true, ..Default::default() };
+        let regex = RegexSearch::with_options("critty", options).unwrap();
+        let match_start = Point::new(Line(0), Column(17));
+        assert_eq!(term.regex_search_right(&regex, start, end), Some(match_start..=end));
+
+        let regex = RegexSearch::new("critty").unwrap();
+        let match_start = Point::new(Line(0), Column(10));
+        let match_end = Point::new(Line(0), Column(15));
+        assert_eq!(term.regex_search_right(&regex, start, end), Some(match_start..=match_end));
+    }
+
+    #[test]
+    fn fuzzy_search() {
+        let term = mock_term("alacritty: a fast terminal");
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(25));
+
+        let options = SearchOptions { fuzzy: true, ..Default::default() };
+        let regex = RegexSearch::with_options("aft", options).unwrap();
+        let match_start = Point::new(Line(0), Column(11));
+        let match_end = Point::new(Line(0), Column(16));
+        assert_eq!(term.regex_search_right(&regex, start, end), Some(match_start..=match_end));
+
+        // Regular searches are never scored.
+        assert_eq!(RegexSearch::new("aft").unwrap().fuzzy_score("a fast"), None);
+    }
+
+    #[test]
+    fn fuzzy_score() {
+        let options = SearchOptions { fuzzy: true, ..Default::default() };
+        let regex = RegexSearch::with_options("term", options).unwrap();
+
+        assert_eq!(regex.fuzzy_score("tram"), None);
+
+        // Consecutive characters and word starts are preferred over gaps.
+        let consecutive = regex.fuzzy_score("terminal").unwrap();
+        let word_start = regex.fuzzy_score("the_early_rust_mascot").unwrap();
+        let gaps = regex.fuzzy_score("theatre room").unwrap();
+        assert!(consecutive > word_start);
+        assert!(word_start > gaps);
+    }
+
     #[test]
     fn regex_right() {
         #[rustfmt::skip]
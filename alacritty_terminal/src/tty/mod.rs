@@ -41,8 +41,8 @@ pub trait EventedReadWrite {
 /// Events concerning TTY child processes.
 #[derive(Debug, PartialEq, Eq)]
 pub enum ChildEvent {
-    /// Indicates the child has exited.
-    Exited,
+    /// Indicates the child has exited, with its exit code if it terminated normally.
+    Exited(Option<i32>),
 }
 
 /// A pseudoterminal (or PTY).
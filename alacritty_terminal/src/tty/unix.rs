@@ -223,6 +223,8 @@ pub fn new(config: &PtyConfig, window_size: WindowSize, window_id: u64) -> Resul
     // Set Window ID for clients relying on X11 hacks.
     builder.env("WINDOWID", window_id);
 
+    builder.envs(&config.env);
+
     unsafe {
         builder.pre_exec(move || {
             // Create a new process group.
@@ -377,7 +379,7 @@ impl EventedPty for Pty {
                     None
                 },
                 Ok(None) => None,
-                Ok(_) => Some(ChildEvent::Exited),
+                Ok(Some(status)) => Some(ChildEvent::Exited(status.code())),
             }
         })
     }
@@ -6,20 +6,31 @@ use mio_extras::channel::{channel, Receiver, Sender};
 
 use windows_sys::Win32::Foundation::{BOOLEAN, HANDLE};
 use windows_sys::Win32::System::Threading::{
-    RegisterWaitForSingleObject, UnregisterWait, INFINITE, WT_EXECUTEINWAITTHREAD,
-    WT_EXECUTEONLYONCE,
+    GetExitCodeProcess, RegisterWaitForSingleObject, UnregisterWait, INFINITE,
+    WT_EXECUTEINWAITTHREAD, WT_EXECUTEONLYONCE,
 };
 
 use crate::tty::ChildEvent;
 
+/// Context passed to the child exit callback.
+struct ChildExitContext {
+    event_tx: Sender<ChildEvent>,
+    child_handle: HANDLE,
+}
+
 /// WinAPI callback to run when child process exits.
 extern "system" fn child_exit_callback(ctx: *mut c_void, timed_out: BOOLEAN) {
     if timed_out != 0 {
         return;
     }
 
-    let event_tx: Box<_> = unsafe { Box::from_raw(ctx as *mut Sender<ChildEvent>) };
-    let _ = event_tx.send(ChildEvent::Exited);
+    let ctx: Box<_> = unsafe { Box::from_raw(ctx as *mut ChildExitContext) };
+
+    let mut exit_code = 0;
+    let success = unsafe { GetExitCodeProcess(ctx.child_handle, &mut exit_code) };
+    let exit_code = (success != 0).then_some(exit_code as i32);
+
+    let _ = ctx.event_tx.send(ChildEvent::Exited(exit_code));
 }
 
 pub struct ChildExitWatcher {
@@ -32,14 +43,14 @@ impl ChildExitWatcher {
         let (event_tx, event_rx) = channel::<ChildEvent>();
 
         let mut wait_handle: HANDLE = 0;
-        let sender_ref = Box::new(event_tx);
+        let ctx = Box::new(ChildExitContext { event_tx, child_handle });
 
         let success = unsafe {
             RegisterWaitForSingleObject(
                 &mut wait_handle,
                 child_handle,
                 Some(child_exit_callback),
-                Box::into_raw(sender_ref).cast(),
+                Box::into_raw(ctx).cast(),
                 INFINITE,
                 WT_EXECUTEINWAITTHREAD | WT_EXECUTEONLYONCE,
             )
@@ -102,7 +113,8 @@ mod tests {
         // Poll for the event or fail with timeout if nothing has been sent.
         poll.poll(&mut events, Some(WAIT_TIMEOUT)).unwrap();
         assert_eq!(events.iter().next().unwrap().token(), child_events_token);
-        // Verify that at least one `ChildEvent::Exited` was received.
-        assert_eq!(child_exit_watcher.event_rx().try_recv(), Ok(ChildEvent::Exited));
+        // Verify that at least one `ChildEvent::Exited` was received, with the exit code used by
+        // `Child::kill`.
+        assert_eq!(child_exit_watcher.event_rx().try_recv(), Ok(ChildEvent::Exited(Some(1))));
     }
 }
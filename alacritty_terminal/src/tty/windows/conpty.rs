@@ -1,5 +1,7 @@
 use log::info;
+use std::collections::HashMap;
 use std::io::Error;
+use std::os::windows::ffi::OsStrExt;
 use std::os::windows::io::IntoRawHandle;
 use std::{mem, ptr};
 
@@ -15,8 +17,8 @@ use windows_sys::{s, w};
 
 use windows_sys::Win32::System::Threading::{
     CreateProcessW, InitializeProcThreadAttributeList, UpdateProcThreadAttribute,
-    EXTENDED_STARTUPINFO_PRESENT, PROCESS_INFORMATION, PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE,
-    STARTF_USESTDHANDLES, STARTUPINFOEXW, STARTUPINFOW,
+    CREATE_UNICODE_ENVIRONMENT, EXTENDED_STARTUPINFO_PRESENT, PROCESS_INFORMATION,
+    PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE, STARTF_USESTDHANDLES, STARTUPINFOEXW, STARTUPINFOW,
 };
 
 use crate::config::PtyConfig;
@@ -88,6 +90,9 @@ impl ConptyApi {
 pub struct Conpty {
     pub handle: HPCON,
     api: ConptyApi,
+
+    /// Last number of columns and lines passed to the pseudoconsole.
+    size: (u16, u16),
 }
 
 impl Drop for Conpty {
@@ -199,6 +204,7 @@ pub fn new(config: &PtyConfig, window_size: WindowSize) -> Option<Pty> {
 
     let cmdline = win32_string(&cmdline(config));
     let cwd = config.working_directory.as_ref().map(win32_string);
+    let env = (!config.env.is_empty()).then(|| env_block(&config.env));
 
     let mut proc_info: PROCESS_INFORMATION = unsafe { mem::zeroed() };
     unsafe {
@@ -208,8 +214,8 @@ pub fn new(config: &PtyConfig, window_size: WindowSize) -> Option<Pty> {
             ptr::null_mut(),
             ptr::null_mut(),
             false as i32,
-            EXTENDED_STARTUPINFO_PRESENT,
-            ptr::null_mut(),
+            EXTENDED_STARTUPINFO_PRESENT | CREATE_UNICODE_ENVIRONMENT,
+            env.as_ref().map_or_else(ptr::null, |env| env.as_ptr()) as _,
             cwd.as_ref().map_or_else(ptr::null, |s| s.as_ptr()),
             &mut startup_info_ex.StartupInfo as *mut STARTUPINFOW,
             &mut proc_info as *mut PROCESS_INFORMATION,
@@ -224,11 +230,38 @@ pub fn new(config: &PtyConfig, window_size: WindowSize) -> Option<Pty> {
     let conout = EventedAnonRead::new(conout);
 
     let child_watcher = ChildExitWatcher::new(proc_info.hProcess).unwrap();
-    let conpty = Conpty { handle: pty_handle as HPCON, api };
+    let size = (window_size.num_cols, window_size.num_lines);
+    let conpty = Conpty { handle: pty_handle as HPCON, api, size };
 
     Some(Pty::new(conpty, conout, conin, child_watcher))
 }
 
+/// Create an environment block with additional variables for `CreateProcessW`.
+fn env_block(env: &HashMap<String, String>) -> Vec<u16> {
+    let mut block = Vec::new();
+    for (key, value) in std::env::vars_os() {
+        // Variable names are case-insensitive on Windows.
+        if env.keys().any(|name| key.eq_ignore_ascii_case(name)) {
+            continue;
+        }
+
+        block.extend(key.encode_wide());
+        block.push(u16::from(b'='));
+        block.extend(value.encode_wide());
+        block.push(0);
+    }
+
+    for (key, value) in env {
+        block.extend(key.encode_utf16());
+        block.push(u16::from(b'='));
+        block.extend(value.encode_utf16());
+        block.push(0);
+    }
+    block.push(0);
+
+    block
+}
+
 // Panic with the last os error as message.
 fn panic_shell_spawn() {
     panic!("Unable to spawn shell: {}", Error::last_os_error());
@@ -236,6 +269,13 @@ fn panic_shell_spawn() {
 
 impl OnResize for Conpty {
     fn on_resize(&mut self, window_size: WindowSize) {
+        // Pixel-only changes don't affect the pseudoconsole, but would still reflow its buffer.
+        let size = (window_size.num_cols, window_size.num_lines);
+        if size == self.size {
+            return;
+        }
+        self.size = size;
+
         let result = unsafe { (self.api.resize)(self.handle, window_size.into()) };
         assert_eq!(result, S_OK);
     }
@@ -1,9 +1,11 @@
 use std::cmp::min;
+use std::collections::VecDeque;
+use std::ops::{Range, RangeInclusive};
 
 use alacritty_config_derive::ConfigDeserialize;
 
 use crate::event::EventListener;
-use crate::grid::{Dimensions, GridCell};
+use crate::grid::{BidirectionalIterator, Dimensions, GridCell};
 use crate::index::{Boundary, Column, Direction, Line, Point, Side};
 use crate::term::cell::Flags;
 use crate::term::Term;
@@ -52,15 +54,216 @@ pub enum ViMotion {
     Bracket,
 }
 
+/// Text objects which can be selected around a vi mode cursor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViTextObject {
+    /// Semantically separated word.
+    Word,
+    /// Whitespace separated word.
+    BigWord,
+    /// Lines separated by empty lines.
+    Paragraph,
+    /// Text between two identical quote characters.
+    Quote(char),
+    /// Text between an opening and closing bracket.
+    Bracket(char, char),
+}
+
+impl ViTextObject {
+    /// Text object for a character typed after `i` or `a`, like in vi.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'w' => Some(Self::Word),
+            'W' => Some(Self::BigWord),
+            'p' => Some(Self::Paragraph),
+            '"' | '\'' | '`' => Some(Self::Quote(c)),
+            '(' | ')' | 'b' => Some(Self::Bracket('(', ')')),
+            '[' | ']' => Some(Self::Bracket('[', ']')),
+            '{' | '}' | 'B' => Some(Self::Bracket('{', '}')),
+            '<' | '>' => Some(Self::Bracket('<', '>')),
+            _ => None,
+        }
+    }
+
+    /// Find the text object surrounding a point.
+    ///
+    /// Inner text objects exclude surrounding whitespace, quotes and brackets.
+    pub fn range<T>(
+        self,
+        term: &Term<T>,
+        point: Point,
+        inner: bool,
+    ) -> Option<RangeInclusive<Point>> {
+        let point = term.expand_wide(point, Direction::Left);
+
+        match self {
+            Self::Word | Self::BigWord => Some(word_object(term, point, self, inner)),
+            Self::Paragraph => Some(paragraph_object(term, point.line, inner)),
+            Self::Quote(quote) => quote_object(term, point, quote, inner),
+            Self::Bracket(open, close) => bracket_object(term, point, open, close, inner),
+        }
+    }
+}
+
+/// Operators waiting for a text object in vi mode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViOperator {
+    /// Copy the text object to the clipboard, or a named register.
+    Yank { register: Option<char> },
+    /// Replace the active selection with the text object.
+    Select,
+}
+
+/// Vi mode command waiting for further input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViPending {
+    /// Operator waiting for an inner (`i`) or outer (`a`) text object.
+    TextObject { operator: ViOperator, inner: Option<bool> },
+    /// Waiting for the name of the mark to set.
+    SetMark,
+    /// Waiting for the name of the mark to jump to.
+    ///
+    /// Jumps to the first occupied cell in the mark's line when `line` is `true`.
+    GotoMark { line: bool },
+    /// Waiting for a register name, followed by `y` or `p` to yank to or paste from it.
+    Register { name: Option<char> },
+}
+
+/// Maximum number of positions remembered in the jump list.
+const MAX_JUMPS: usize = 100;
+
+/// Named marks and jump list of the vi mode cursor.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct ViMarks {
+    /// Positions of the marks `a` to `z`.
+    marks: [Option<Point>; 26],
+
+    /// Positions the vi mode cursor jumped away from.
+    jumps: VecDeque<Point>,
+
+    /// Current position in the jump list.
+    jump_index: usize,
+}
+
+impl ViMarks {
+    /// Set a mark, returning `false` if the name is not a valid mark.
+    pub fn set(&mut self, name: char, point: Point) -> bool {
+        match Self::index(name) {
+            Some(index) => {
+                self.marks[index] = Some(point);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Position of a mark.
+    pub fn get(&self, name: char) -> Option<Point> {
+        self.marks[Self::index(name)?]
+    }
+
+    /// Remember the position before a jump.
+    ///
+    /// This discards all newer positions when jumping after moving back in the jump list.
+    pub fn push_jump(&mut self, point: Point) {
+        self.jumps.truncate(self.jump_index);
+
+        if self.jumps.back() != Some(&point) {
+            self.jumps.push_back(point);
+        }
+
+        if self.jumps.len() > MAX_JUMPS {
+            self.jumps.pop_front();
+        }
+
+        self.jump_index = self.jumps.len();
+    }
+
+    /// Move to an older position in the jump list.
+    ///
+    /// The current position is remembered when leaving the end of the list, so it's possible to
+    /// jump back to it.
+    pub fn jump_older(&mut self, current: Point) -> Option<Point> {
+        if self.jump_index == self.jumps.len() {
+            self.push_jump(current);
+            self.jump_index = self.jumps.len() - 1;
+        }
+
+        self.jump_index = self.jump_index.checked_sub(1)?;
+
+        Some(self.jumps[self.jump_index])
+    }
+
+    /// Move to a newer position in the jump list.
+    pub fn jump_newer(&mut self) -> Option<Point> {
+        if self.jump_index + 1 >= self.jumps.len() {
+            return None;
+        }
+
+        self.jump_index += 1;
+
+        Some(self.jumps[self.jump_index])
+    }
+
+    /// Move all positions with content scrolled by `lines` inside of `region`.
+    ///
+    /// Positions which are scrolled out of the region or history are removed.
+    pub(crate) fn rotate(&mut self, region: &Range<Line>, lines: i32, topmost_line: Line) {
+        // Scrolling the full screen up moves all lines into the history.
+        let scroll_history = region.start == 0 && lines > 0;
+        let top = if region.start == 0 { topmost_line } else { region.start };
+
+        self.retain_mut(|point| {
+            if point.line >= region.end || (point.line < region.start && !scroll_history) {
+                return true;
+            }
+
+            point.line -= lines;
+
+            point.line >= top && point.line < region.end
+        });
+    }
+
+    /// Update all positions, removing those for which the closure returns `false`.
+    pub(crate) fn retain_mut(&mut self, mut f: impl FnMut(&mut Point) -> bool) {
+        for mark in &mut self.marks {
+            if mark.as_mut().map_or(false, |point| !f(point)) {
+                *mark = None;
+            }
+        }
+
+        let mut index = 0;
+        let jump_index = self.jump_index;
+        let mut removed = 0;
+        self.jumps.retain_mut(|point| {
+            let retain = f(point);
+            if !retain && index < jump_index {
+                removed += 1;
+            }
+            index += 1;
+            retain
+        });
+        self.jump_index = min(self.jump_index - removed, self.jumps.len());
+    }
+
+    /// Index of a mark by its name.
+    fn index(name: char) -> Option<usize> {
+        name.is_ascii_lowercase().then(|| (name as u8 - b'a') as usize)
+    }
+}
+
 /// Cursor tracking vi mode position.
 #[derive(Default, Copy, Clone, PartialEq, Eq)]
 pub struct ViModeCursor {
     pub point: Point,
+
+    /// Operator waiting for its text object.
+    pub pending: Option<ViPending>,
 }
 
 impl ViModeCursor {
     pub fn new(point: Point) -> Self {
-        Self { point }
+        Self { point, pending: None }
     }
 
     /// Move vi mode cursor.
@@ -174,6 +377,177 @@ impl ViModeCursor {
     }
 }
 
+/// Find the bounds of a word text object.
+fn word_object<T>(
+    term: &Term<T>,
+    point: Point,
+    object: ViTextObject,
+    inner: bool,
+) -> RangeInclusive<Point> {
+    let space = |point| is_space(term, point);
+
+    // Find the word itself, without any surrounding whitespace.
+    let word_bounds = |point: Point| match object {
+        ViTextObject::Word if term.semantic_escape_chars().contains(term.grid()[point].c) => {
+            (point, point)
+        },
+        ViTextObject::Word => (term.semantic_search_left(point), term.semantic_search_right(point)),
+        _ => {
+            let word = |point| !is_space(term, point);
+            let start = expand_in_line(term, point, Direction::Left, word);
+            (start, expand_in_line(term, point, Direction::Right, word))
+        },
+    };
+
+    if space(point) {
+        let start = expand_in_line(term, point, Direction::Left, space);
+        let end = expand_in_line(term, point, Direction::Right, space);
+
+        // Outer objects on whitespace include the following word.
+        match next_in_line(term, end, Direction::Right) {
+            Some(next) if !inner => start..=word_bounds(next).1,
+            _ => start..=end,
+        }
+    } else {
+        let (mut start, mut end) = word_bounds(point);
+
+        // Outer objects include trailing whitespace, or leading whitespace without it.
+        //
+        // Whitespace extending to the end of the line is ignored, since it is usually just empty
+        // cells rather than actual whitespace.
+        if !inner {
+            let trailing = expand_in_line(term, end, Direction::Right, space);
+            if trailing != end && next_in_line(term, trailing, Direction::Right).is_some() {
+                end = trailing;
+            } else if next_in_line(term, start, Direction::Left).map_or(false, space) {
+                start = expand_in_line(term, start, Direction::Left, space);
+            }
+        }
+
+        start..=end
+    }
+}
+
+/// Find the bounds of a paragraph text object.
+fn paragraph_object<T>(term: &Term<T>, line: Line, inner: bool) -> RangeInclusive<Point> {
+    let empty = |line: Line| term.grid()[line].is_clear();
+    let is_empty = empty(line);
+
+    // Expand across all lines which match the current line's emptiness.
+    let mut start = line;
+    while start > term.topmost_line() && empty(start - 1) == is_empty {
+        start -= 1;
+    }
+    let mut end = line;
+    while end < term.bottommost_line() && empty(end + 1) == is_empty {
+        end += 1;
+    }
+
+    // Outer objects include the following block of lines.
+    if !inner && end < term.bottommost_line() {
+        end += 1;
+        while end < term.bottommost_line() && empty(end + 1) != is_empty {
+            end += 1;
+        }
+    }
+
+    Point::new(start, Column(0))..=Point::new(end, term.last_column())
+}
+
+/// Find the bounds of a quote text object within the current line.
+fn quote_object<T>(
+    term: &Term<T>,
+    point: Point,
+    quote: char,
+    inner: bool,
+) -> Option<RangeInclusive<Point>> {
+    // Find all quotes in the line, across linewraps.
+    let mut quotes = Vec::new();
+    let mut next = Some(term.line_search_left(point));
+    while let Some(point) = next {
+        if term.grid()[point].c == quote {
+            quotes.push(point);
+        }
+        next = next_in_line(term, point, Direction::Right);
+    }
+
+    // Use the quote pair around the point, or the next pair after it.
+    let (open, close) =
+        quotes.chunks_exact(2).map(|pair| (pair[0], pair[1])).find(|&(_, close)| point <= close)?;
+
+    if inner {
+        let start = next_in_line(term, open, Direction::Right)?;
+        let end = next_in_line(term, close, Direction::Left)?;
+        (start < close).then_some(start..=end)
+    } else {
+        Some(open..=close)
+    }
+}
+
+/// Find the bounds of a bracket text object.
+fn bracket_object<T>(
+    term: &Term<T>,
+    point: Point,
+    open: char,
+    close: char,
+    inner: bool,
+) -> Option<RangeInclusive<Point>> {
+    // Find the unmatched opening bracket before the point.
+    let start = if term.grid()[point].c == open {
+        point
+    } else {
+        let mut iter = term.grid().iter_from(point);
+        let mut depth = 0;
+        loop {
+            let cell = iter.prev()?;
+            if cell.c == close {
+                depth += 1;
+            } else if cell.c == open && depth == 0 {
+                break cell.point;
+            } else if cell.c == open {
+                depth -= 1;
+            }
+        }
+    };
+
+    let end = term.bracket_search(start)?;
+
+    if inner {
+        let inner_start = start.add(term, Boundary::Grid, 1);
+        (inner_start < end).then(|| inner_start..=end.sub(term, Boundary::Grid, 1))
+    } else {
+        Some(start..=end)
+    }
+}
+
+/// Expand a point within its line, while the predicate matches the adjacent cell.
+fn expand_in_line<T>(
+    term: &Term<T>,
+    mut point: Point,
+    direction: Direction,
+    predicate: impl Fn(Point) -> bool,
+) -> Point {
+    while let Some(next) = next_in_line(term, point, direction).filter(|&next| predicate(next)) {
+        point = next;
+    }
+    point
+}
+
+/// Get the adjacent cell without leaving the current line.
+fn next_in_line<T>(term: &Term<T>, point: Point, direction: Direction) -> Option<Point> {
+    match direction {
+        Direction::Left if point.column > 0 => Some(Point::new(point.line, point.column - 1)),
+        Direction::Left => {
+            let wrap_point = Point::new(point.line - 1, term.last_column());
+            (point.line > term.topmost_line() && is_wrap(term, wrap_point)).then_some(wrap_point)
+        },
+        Direction::Right if point.column < term.last_column() => {
+            Some(Point::new(point.line, point.column + 1))
+        },
+        Direction::Right => is_wrap(term, point).then(|| Point::new(point.line + 1, Column(0))),
+    }
+}
+
 /// Find next end of line to move to.
 fn last<T>(term: &Term<T>, mut point: Point) -> Point {
     // Expand across wide cells.
@@ -333,7 +707,7 @@ fn word<T: EventListener>(
 }
 
 /// Find first non-empty cell in line.
-fn first_occupied_in_line<T>(term: &Term<T>, line: Line) -> Option<Point> {
+pub(crate) fn first_occupied_in_line<T>(term: &Term<T>, line: Line) -> Option<Point> {
     (0..term.columns())
         .map(|col| Point::new(line, Column(col)))
         .find(|&point| !is_space(term, point))
@@ -819,4 +1193,124 @@ mod tests {
         cursor = cursor.scroll(&term, -20);
         assert_eq!(cursor.point, Point::new(Line(19), Column(0)));
     }
+
+    fn text_object_term(lines: &[&str]) -> Term<VoidListener> {
+        let mut term = term();
+        for (line, text) in lines.iter().enumerate() {
+            for (column, c) in text.chars().enumerate() {
+                term.grid_mut()[Line(line as i32)][Column(column)].c = c;
+            }
+        }
+        term
+    }
+
+    fn object_range(
+        term: &Term<VoidListener>,
+        column: usize,
+        object: ViTextObject,
+        inner: bool,
+    ) -> Option<(usize, usize)> {
+        let point = Point::new(Line(0), Column(column));
+        let range = object.range(term, point, inner)?;
+        Some((range.start().column.0, range.end().column.0))
+    }
+
+    #[test]
+    fn text_object_word() {
+        let term = text_object_term(&["ab cd  ef", "a:b c"]);
+
+        assert_eq!(object_range(&term, 3, ViTextObject::Word, true), Some((3, 4)));
+        assert_eq!(object_range(&term, 3, ViTextObject::Word, false), Some((3, 6)));
+        assert_eq!(object_range(&term, 5, ViTextObject::Word, true), Some((5, 6)));
+        assert_eq!(object_range(&term, 5, ViTextObject::Word, false), Some((5, 8)));
+        assert_eq!(object_range(&term, 8, ViTextObject::Word, false), Some((5, 8)));
+
+        let point = Point::new(Line(1), Column(0));
+        let range = ViTextObject::Word.range(&term, point, true);
+        assert_eq!(range, Some(point..=point));
+        let range = ViTextObject::BigWord.range(&term, point, true);
+        assert_eq!(range, Some(point..=Point::new(Line(1), Column(2))));
+    }
+
+    #[test]
+    fn text_object_paragraph() {
+        let term = text_object_term(&["a", "b", "", "c"]);
+        let point = Point::new(Line(0), Column(0));
+
+        let range = ViTextObject::Paragraph.range(&term, point, true);
+        assert_eq!(range, Some(point..=Point::new(Line(1), Column(19))));
+
+        let range = ViTextObject::Paragraph.range(&term, point, false);
+        assert_eq!(range, Some(point..=Point::new(Line(2), Column(19))));
+    }
+
+    #[test]
+    fn text_object_quote() {
+        let term = text_object_term(&["x \"ab\" y \"\""]);
+        let quote = ViTextObject::Quote('"');
+
+        assert_eq!(object_range(&term, 3, quote, true), Some((3, 4)));
+        assert_eq!(object_range(&term, 3, quote, false), Some((2, 5)));
+        assert_eq!(object_range(&term, 0, quote, true), Some((3, 4)));
+        assert_eq!(object_range(&term, 9, quote, true), None);
+        assert_eq!(object_range(&term, 9, quote, false), Some((9, 10)));
+        assert_eq!(object_range(&term, 11, quote, false), None);
+    }
+
+    #[test]
+    fn text_object_bracket() {
+        let term = text_object_term(&["(a [b] c) ()"]);
+        let parens = ViTextObject::Bracket('(', ')');
+        let brackets = ViTextObject::Bracket('[', ']');
+
+        assert_eq!(object_range(&term, 4, parens, true), Some((1, 7)));
+        assert_eq!(object_range(&term, 4, parens, false), Some((0, 8)));
+        assert_eq!(object_range(&term, 8, parens, false), Some((0, 8)));
+        assert_eq!(object_range(&term, 4, brackets, true), Some((4, 4)));
+        assert_eq!(object_range(&term, 10, parens, true), None);
+        assert_eq!(object_range(&term, 10, parens, false), Some((10, 11)));
+    }
+
+    #[test]
+    fn marks_jump_list() {
+        let mut marks = ViMarks::default();
+        let point = |line| Point::new(Line(line), Column(0));
+
+        marks.push_jump(point(0));
+        marks.push_jump(point(1));
+
+        assert_eq!(marks.jump_older(point(2)), Some(point(1)));
+        assert_eq!(marks.jump_older(point(1)), Some(point(0)));
+        assert_eq!(marks.jump_older(point(0)), None);
+        assert_eq!(marks.jump_newer(), Some(point(1)));
+        assert_eq!(marks.jump_newer(), Some(point(2)));
+        assert_eq!(marks.jump_newer(), None);
+
+        assert!(marks.set('a', point(3)));
+        assert!(!marks.set('A', point(3)));
+        assert_eq!(marks.get('a'), Some(point(3)));
+        assert_eq!(marks.get('b'), None);
+    }
+
+    #[test]
+    fn marks_follow_content() {
+        let mut term = term();
+        term.vi_marks.set('a', Point::new(Line(5), Column(3)));
+        term.vi_marks.push_jump(Point::new(Line(0), Column(0)));
+
+        // Scroll content up by three lines.
+        for _ in 0..22 {
+            term.newline();
+        }
+
+        assert_eq!(term.vi_marks.get('a'), Some(Point::new(Line(2), Column(3))));
+        let older = term.vi_marks.jump_older(Point::default());
+        assert_eq!(older, Some(Point::new(Line(-3), Column(0))));
+
+        // Marks are tracked separately for the alternate screen.
+        term.swap_alt();
+        assert_eq!(term.vi_marks.get('a'), None);
+        term.swap_alt();
+        assert_eq!(term.vi_marks.get('a'), Some(Point::new(Line(2), Column(3))));
+    }
 }
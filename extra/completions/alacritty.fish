@@ -1,46 +1,153 @@
 complete -c alacritty -n "__fish_use_subcommand" -l embed -d 'X11 window ID to embed Alacritty within (decimal or hexadecimal with "0x" prefix)' -r
 complete -c alacritty -n "__fish_use_subcommand" -l config-file -d 'Specify alternative configuration file [default: $XDG_CONFIG_HOME/alacritty/alacritty.toml]' -r -F
 complete -c alacritty -n "__fish_use_subcommand" -l socket -d 'Path for IPC socket creation' -r -F
+complete -c alacritty -n "__fish_use_subcommand" -l layout -d 'Open the windows, tabs and panes described by a layout file' -r -F
 complete -c alacritty -n "__fish_use_subcommand" -s o -l option -d 'Override configuration file options [example: cursor.style=Beam]' -r
 complete -c alacritty -n "__fish_use_subcommand" -l working-directory -d 'Start the shell in the specified working directory' -r -F
+complete -c alacritty -n "__fish_use_subcommand" -l env -d 'Environment variable for the shell, overriding the env config section' -r
+complete -c alacritty -n "__fish_use_subcommand" -l record -d 'Record the terminal output to an asciicast file' -r -F
 complete -c alacritty -n "__fish_use_subcommand" -s e -l command -d 'Command and args to execute (must be last argument)' -r
 complete -c alacritty -n "__fish_use_subcommand" -s T -l title -d 'Defines the window title [default: Alacritty]' -r
 complete -c alacritty -n "__fish_use_subcommand" -l class -d 'Defines window class/app_id on X11/Wayland [default: Alacritty]' -r
+complete -c alacritty -n "__fish_use_subcommand" -l name -d 'Name used to target the window through IPC' -r
+complete -c alacritty -n "__fish_use_subcommand" -l icon -d 'Defines the window icon on X11 and Windows' -r -F
 complete -c alacritty -n "__fish_use_subcommand" -l print-events -d 'Print all events to stdout'
 complete -c alacritty -n "__fish_use_subcommand" -l ref-test -d 'Generates ref test'
+complete -c alacritty -n "__fish_use_subcommand" -l daemon -d 'Run without an initial window, hosting windows created through IPC until terminated'
 complete -c alacritty -n "__fish_use_subcommand" -s q -d 'Reduces the level of verbosity (the min level is -qq)'
 complete -c alacritty -n "__fish_use_subcommand" -s v -d 'Increases the level of verbosity (the max level is -vvv)'
 complete -c alacritty -n "__fish_use_subcommand" -l hold -d 'Remain open after child process exit'
+complete -c alacritty -n "__fish_use_subcommand" -l hold-on-error -d 'Remain open after child process exit with a non-zero exit code'
+complete -c alacritty -n "__fish_use_subcommand" -l dropdown -d 'Dock the window to the top of the screen, showing and hiding it with `alacritty msg toggle`'
 complete -c alacritty -n "__fish_use_subcommand" -s h -l help -d 'Print help'
 complete -c alacritty -n "__fish_use_subcommand" -s V -l version -d 'Print version'
 complete -c alacritty -n "__fish_use_subcommand" -f -a "msg" -d 'Send a message to the Alacritty socket'
 complete -c alacritty -n "__fish_use_subcommand" -f -a "migrate" -d 'Migrate the configuration file'
+complete -c alacritty -n "__fish_use_subcommand" -f -a "replay" -d 'Replay a session recorded in the asciicast format'
+complete -c alacritty -n "__fish_use_subcommand" -f -a "bench" -d 'Measure rendering performance using bundled stress workloads'
 complete -c alacritty -n "__fish_use_subcommand" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -s s -l socket -d 'IPC socket connection path override' -r -F
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -s h -l help -d 'Print help'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -f -a "create-window" -d 'Create a new window in the same Alacritty process'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -f -a "config" -d 'Update the Alacritty configuration'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -s s -l socket -d 'IPC socket connection path override' -r -F
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "create-window" -d 'Create a new window in the same Alacritty process'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "config" -d 'Update the Alacritty configuration'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "search" -d 'Search for a regex in the scrollback'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "dump-history" -d 'Write the scrollback history and screen content to a file'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "screenshot" -d 'Write the next rendered frame to a PNG file'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "get-text" -d 'Print the terminal content'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "get-state" -d 'Print the terminal state as JSON'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "list-windows" -d 'Print all windows as JSON'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "send-text" -d 'Write text to the terminal as if it was typed'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "broadcast" -d 'Mirror input to all panes of a window or a group of windows'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "layout" -d 'Open the windows, tabs and panes described by a layout file'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "move-pane" -d 'Move the focused pane of a window into a new or existing window'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "stats" -d 'Print performance metrics as JSON'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "toggle" -d 'Show or hide dropdown windows, opening one if none exists'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -l working-directory -d 'Start the shell in the specified working directory' -r -F
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -l env -d 'Environment variable for the shell, overriding the env config section' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -l record -d 'Record the terminal output to an asciicast file' -r -F
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -s e -l command -d 'Command and args to execute (must be last argument)' -r
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -s T -l title -d 'Defines the window title [default: Alacritty]' -r
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -l class -d 'Defines window class/app_id on X11/Wayland [default: Alacritty]' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -l name -d 'Name used to target the window through IPC' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -l icon -d 'Defines the window icon on X11 and Windows' -r -F
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -l hold -d 'Remain open after child process exit'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -l hold-on-error -d 'Remain open after child process exit with a non-zero exit code'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -l dropdown -d 'Dock the window to the top of the screen, showing and hiding it with `alacritty msg toggle`'
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -s h -l help -d 'Print help'
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from config" -s w -l window-id -d 'Window ID for the new config' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from config" -l window -d 'Window name or ID for the new config, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from config" -l get -d 'Print the value of an option set through configuration files, the CLI or IPC' -r
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from config" -s r -l reset -d 'Clear all runtime configuration changes'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from config" -s p -l pane -d 'Only change the configuration of the window\'s focused pane'
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from config" -s h -l help -d 'Print help (see more with \'--help\')'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -f -a "create-window" -d 'Create a new window in the same Alacritty process'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -f -a "config" -d 'Update the Alacritty configuration'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from search" -s w -l window-id -d 'Window ID for the search, defaults to the focused window' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from search" -l window -d 'Window name or ID for the search, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from search" -s b -l backwards -d 'Search upwards from the bottom of the screen'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from search" -s s -l select -d 'Select the first match'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from search" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from dump-history" -s w -l window-id -d 'Window ID for the history, defaults to the focused window' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from dump-history" -l window -d 'Window name or ID for the history, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from dump-history" -s a -l ansi -d 'Preserve text attributes using SGR escape sequences'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from dump-history" -s t -l timestamps -d 'Prefix every line with the time it was written at'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from dump-history" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from screenshot" -s w -l window-id -d 'Window ID for the screenshot, defaults to the focused window' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from screenshot" -l window -d 'Window name or ID for the screenshot, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from screenshot" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-text" -l history -d 'Print the screen content along with up to LINES lines of scrollback history above it' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-text" -s w -l window-id -d 'Window ID for the text, defaults to the focused window' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-text" -l window -d 'Window name or ID for the text, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-text" -l selection -d 'Print the active selection'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-text" -l screen -d 'Print the visible screen content [default]'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-text" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-state" -s w -l window-id -d 'Window ID for the state, defaults to the focused window' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-state" -l window -d 'Window name or ID for the state, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-state" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from list-windows" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from send-text" -s w -l window-id -d 'Window ID for the text, defaults to the focused window' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from send-text" -l window -d 'Window name or ID for the text, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from send-text" -s b -l bracketed -d 'Send the text as a bracketed paste, if enabled by the application'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from send-text" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from broadcast" -s g -l group -d 'Share input with all windows in the same group' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from broadcast" -s w -l window-id -d 'Window ID for the broadcast, defaults to the focused window' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from broadcast" -l window -d 'Window name or ID for the broadcast, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from broadcast" -s p -l panes -d 'Write input to all panes of the window'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from broadcast" -l off -d 'Stop mirroring input from and to the window'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from broadcast" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from layout" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from move-pane" -s t -l target -d 'Window name or ID receiving the pane, defaults to a new window' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from move-pane" -s d -l direction -d 'Side of the target window\'s focused pane the pane is opened on' -r -f -a "{left	'',right	'',up	'',down	''}"
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from move-pane" -s w -l window-id -d 'Window ID of the moved pane, defaults to the focused window' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from move-pane" -l window -d 'Window name or ID of the moved pane, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from move-pane" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from stats" -s w -l window-id -d 'Window ID for the metrics, defaults to the focused window' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from stats" -l window -d 'Window name or ID for the metrics, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from stats" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from toggle" -s w -l window-id -d 'Window ID of the dropdown, defaults to all dropdown windows' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from toggle" -l window -d 'Window name or ID of the dropdown, overriding --window-id' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from toggle" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "create-window" -d 'Create a new window in the same Alacritty process'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "config" -d 'Update the Alacritty configuration'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "search" -d 'Search for a regex in the scrollback'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "dump-history" -d 'Write the scrollback history and screen content to a file'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "screenshot" -d 'Write the next rendered frame to a PNG file'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "get-text" -d 'Print the terminal content'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "get-state" -d 'Print the terminal state as JSON'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "list-windows" -d 'Print all windows as JSON'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "send-text" -d 'Write text to the terminal as if it was typed'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "broadcast" -d 'Mirror input to all panes of a window or a group of windows'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "layout" -d 'Open the windows, tabs and panes described by a layout file'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "move-pane" -d 'Move the focused pane of a window into a new or existing window'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "stats" -d 'Print performance metrics as JSON'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "toggle" -d 'Show or hide dropdown windows, opening one if none exists'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from help; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c alacritty -n "__fish_seen_subcommand_from migrate" -s c -l config-file -d 'Path to the configuration file' -r -F
 complete -c alacritty -n "__fish_seen_subcommand_from migrate" -s d -l dry-run -d 'Only output TOML config to stdout'
 complete -c alacritty -n "__fish_seen_subcommand_from migrate" -s i -l skip-imports -d 'Do not recurse over imports'
 complete -c alacritty -n "__fish_seen_subcommand_from migrate" -l skip-renames -d 'Do not move renamed fields to their new location'
 complete -c alacritty -n "__fish_seen_subcommand_from migrate" -s s -l silent -d 'Do not output to STDOUT'
 complete -c alacritty -n "__fish_seen_subcommand_from migrate" -s h -l help -d 'Print help'
-complete -c alacritty -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from migrate; and not __fish_seen_subcommand_from help" -f -a "msg" -d 'Send a message to the Alacritty socket'
-complete -c alacritty -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from migrate; and not __fish_seen_subcommand_from help" -f -a "migrate" -d 'Migrate the configuration file'
-complete -c alacritty -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from migrate; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
-complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config" -f -a "create-window" -d 'Create a new window in the same Alacritty process'
-complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config" -f -a "config" -d 'Update the Alacritty configuration'
+complete -c alacritty -n "__fish_seen_subcommand_from replay" -s h -l help -d 'Print help'
+complete -c alacritty -n "__fish_seen_subcommand_from bench" -s w -l workload -d 'Workloads which should be run, defaults to all of them' -r -f -a "{scroll	'Plain text scrolling the screen',colors	'Full screen redraws with random foreground and background colors',wide-chars	'Full screen redraws with wide characters',underlines	'Full screen redraws with every underline style',cursor	'Cursor jumping across the screen while changing its shape'}"
+complete -c alacritty -n "__fish_seen_subcommand_from bench" -l frames -d 'Number of frames drawn for every workload' -r
+complete -c alacritty -n "__fish_seen_subcommand_from bench" -l headless -d 'Only measure the terminal\'s processing of the output, without opening a window'
+complete -c alacritty -n "__fish_seen_subcommand_from bench" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from migrate; and not __fish_seen_subcommand_from replay; and not __fish_seen_subcommand_from bench; and not __fish_seen_subcommand_from help" -f -a "msg" -d 'Send a message to the Alacritty socket'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from migrate; and not __fish_seen_subcommand_from replay; and not __fish_seen_subcommand_from bench; and not __fish_seen_subcommand_from help" -f -a "migrate" -d 'Migrate the configuration file'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from migrate; and not __fish_seen_subcommand_from replay; and not __fish_seen_subcommand_from bench; and not __fish_seen_subcommand_from help" -f -a "replay" -d 'Replay a session recorded in the asciicast format'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from migrate; and not __fish_seen_subcommand_from replay; and not __fish_seen_subcommand_from bench; and not __fish_seen_subcommand_from help" -f -a "bench" -d 'Measure rendering performance using bundled stress workloads'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from migrate; and not __fish_seen_subcommand_from replay; and not __fish_seen_subcommand_from bench; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "create-window" -d 'Create a new window in the same Alacritty process'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "config" -d 'Update the Alacritty configuration'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "search" -d 'Search for a regex in the scrollback'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "dump-history" -d 'Write the scrollback history and screen content to a file'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "screenshot" -d 'Write the next rendered frame to a PNG file'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "get-text" -d 'Print the terminal content'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "get-state" -d 'Print the terminal state as JSON'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "list-windows" -d 'Print all windows as JSON'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "send-text" -d 'Write text to the terminal as if it was typed'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "broadcast" -d 'Mirror input to all panes of a window or a group of windows'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "layout" -d 'Open the windows, tabs and panes described by a layout file'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "move-pane" -d 'Move the focused pane of a window into a new or existing window'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "stats" -d 'Print performance metrics as JSON'
+complete -c alacritty -n "__fish_seen_subcommand_from help; and __fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from search; and not __fish_seen_subcommand_from dump-history; and not __fish_seen_subcommand_from screenshot; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from get-state; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from broadcast; and not __fish_seen_subcommand_from layout; and not __fish_seen_subcommand_from move-pane; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from toggle" -f -a "toggle" -d 'Show or hide dropdown windows, opening one if none exists'
@@ -0,0 +1,42 @@
+# Alacritty shell integration for bash.
+#
+# Marks prompts, commands and their output using OSC 133, which allows
+# jumping between prompts and selecting the output of the last command.
+#
+# Source this file at the end of your `~/.bashrc`, requires bash 4.4 or newer.
+
+# Only run once in interactive shells.
+[[ $- == *i* && -z "$__alacritty_integration" ]] || return 0
+__alacritty_integration=1
+
+__alacritty_prompt_command() {
+    local exit_code=$?
+
+    # Report the exit code of the last command, if one was run.
+    if [[ -n "$__alacritty_running" ]]; then
+        printf '\e]133;D;%s\a' "$exit_code"
+        __alacritty_running=
+    fi
+
+    # Report the working directory, used when opening new windows.
+    printf '\e]7;file://%s%s\a' "$HOSTNAME" "$PWD"
+
+    # Mark the prompt again, in case it was replaced by another prompt command.
+    if [[ "$PS1" != *'133;A'* ]]; then
+        PS1='\[\e]133;A\a\]'"$PS1"'\[\e]133;B\a\]'
+    fi
+
+    return $exit_code
+}
+
+# Command output starts right after the command line was submitted.
+#
+# The arithmetic array subscript sets the running flag without printing
+# anything, since parameter expansion in PS0 is done in the current shell.
+PS0='\e]133;C\a${__alacritty_noop[__alacritty_running=1]}'"$PS0"
+
+if [[ "$(declare -p PROMPT_COMMAND 2>/dev/null)" == "declare -a"* ]]; then
+    PROMPT_COMMAND+=(__alacritty_prompt_command)
+else
+    PROMPT_COMMAND="__alacritty_prompt_command${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
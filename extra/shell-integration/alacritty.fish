@@ -0,0 +1,31 @@
+# Alacritty shell integration for fish.
+#
+# Marks prompts, commands and their output using OSC 133, which allows
+# jumping between prompts and selecting the output of the last command.
+#
+# Source this file at the end of your `~/.config/fish/config.fish`.
+
+# Only run once in interactive shells.
+if not status is-interactive; or set -q __alacritty_integration
+    exit
+end
+set -g __alacritty_integration 1
+
+# Wrap the prompt, so its start and end are marked.
+functions --copy fish_prompt __alacritty_original_prompt
+function fish_prompt
+    # Report the working directory, used when opening new windows.
+    printf '\e]7;file://%s%s\a' $hostname $PWD
+
+    printf '\e]133;A\a'
+    __alacritty_original_prompt
+    printf '\e]133;B\a'
+end
+
+function __alacritty_preexec --on-event fish_preexec
+    printf '\e]133;C\a'
+end
+
+function __alacritty_postexec --on-event fish_postexec
+    printf '\e]133;D;%s\a' $status
+end